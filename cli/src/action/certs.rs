@@ -33,10 +33,19 @@ use openssl::pkey::{PKey, PKeyRef, Private};
 use openssl::rsa::Rsa;
 use openssl::x509::extension::{BasicConstraints, ExtendedKeyUsage, KeyUsage};
 use openssl::x509::{X509NameBuilder, X509Ref, X509};
+#[cfg(feature = "tls-reload")]
+use reqwest::StatusCode;
+#[cfg(feature = "tls-reload")]
+use serde_json::Value;
 
 use crate::error::CliError;
 
 use super::{chown, Action};
+#[cfg(feature = "tls-reload")]
+use super::{DEFAULT_SPLINTER_REST_API_URL, SPLINTER_REST_API_URL_ENV};
+
+#[cfg(feature = "tls-reload")]
+const SPLINTER_REST_API_TOKEN_ENV: &str = "SPLINTER_REST_API_TOKEN";
 
 pub struct CertGenAction;
 
@@ -60,29 +69,7 @@ impl Action for CertGenAction {
             .unwrap_or("localhost")
             .to_string();
 
-        let cert_dir_string = args
-            .value_of("cert_dir")
-            .map(ToOwned::to_owned)
-            .or_else(|| env::var(CERT_DIR_ENV).ok())
-            .or_else(|| {
-                if let Ok(splinter_home) = env::var(SPLINTER_HOME_ENV) {
-                    let cert_path = Path::new(&splinter_home).join("certs");
-                    if !cert_path.is_dir() {
-                        fs::create_dir_all(&cert_path)
-                            .map_err(|err| {
-                                CliError::ActionError(format!(
-                                    "Unable to create cert directory: {}",
-                                    err
-                                ))
-                            })
-                            .ok()?
-                    }
-                    cert_path.to_str().map(ToOwned::to_owned)
-                } else {
-                    Some(DEFAULT_CERT_DIR.to_string())
-                }
-            })
-            .unwrap();
+        let cert_dir_string = get_cert_dir(args);
 
         let cert_dir = Path::new(&cert_dir_string);
 
@@ -221,6 +208,169 @@ impl Action for CertGenAction {
     }
 }
 
+/// Reissues the client and server certificates and keys using the existing CA, so that they can
+/// be rotated on a running splinterd without changing the CA trusted by its peers.
+#[cfg(feature = "tls-reload")]
+pub struct CertRotateAction;
+
+#[cfg(feature = "tls-reload")]
+impl Action for CertRotateAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or_else(|| CliError::RequiresArgs)?;
+
+        let common_name = args
+            .value_of("common_name")
+            .unwrap_or("localhost")
+            .to_string();
+
+        let cert_dir_string = get_cert_dir(args);
+        let cert_path = Path::new(&cert_dir_string).to_path_buf();
+        let private_cert_path = cert_path.join("private/");
+
+        let ca_cert_path = cert_path.join(CA_CERT);
+        let ca_key_path = private_cert_path.join(CA_KEY);
+        if !ca_cert_path.is_file() || !ca_key_path.is_file() {
+            return Err(CliError::ActionError(format!(
+                "Unable to rotate certificates without an existing CA certificate and key \
+                 ({} and {}); use `splinter cert generate` to create them",
+                ca_cert_path.display(),
+                ca_key_path.display()
+            )));
+        }
+
+        let ca_cert = get_ca_cert(&ca_cert_path)?;
+        let ca_key = get_ca_key(&ca_key_path)?;
+
+        info!(
+            "Overwriting file: {}/{}",
+            absolute_path(&cert_path)?,
+            CLIENT_CERT
+        );
+        info!(
+            "Overwriting file: {}/{}",
+            absolute_path(&private_cert_path)?,
+            CLIENT_KEY
+        );
+        write_client(
+            cert_path.clone(),
+            private_cert_path.clone(),
+            &ca_key,
+            &ca_cert,
+            &common_name,
+        )?;
+
+        info!(
+            "Overwriting file: {}/{}",
+            absolute_path(&cert_path)?,
+            SERVER_CERT
+        );
+        info!(
+            "Overwriting file: {}/{}",
+            absolute_path(&private_cert_path)?,
+            SERVER_KEY
+        );
+        write_server(
+            cert_path,
+            private_cert_path,
+            &ca_key,
+            &ca_cert,
+            &common_name,
+        )?;
+
+        if args.is_present("reload") {
+            let url = args
+                .value_of("url")
+                .map(ToOwned::to_owned)
+                .or_else(|| env::var(SPLINTER_REST_API_URL_ENV).ok())
+                .unwrap_or_else(|| DEFAULT_SPLINTER_REST_API_URL.to_string());
+
+            let token = args
+                .value_of("token")
+                .map(ToOwned::to_owned)
+                .or_else(|| env::var(SPLINTER_REST_API_TOKEN_ENV).ok())
+                .ok_or_else(|| {
+                    CliError::ActionError(
+                        "An access token is required to reload the TLS certificates; provide \
+                         one with --token or the SPLINTER_REST_API_TOKEN environment variable"
+                            .into(),
+                    )
+                })?;
+
+            request_tls_reload(&url, &token)?;
+            info!("Reloaded TLS certificates on {}", url);
+        }
+
+        Ok(())
+    }
+}
+
+// Request that the splinterd at the given URL reload its TLS certificates and keys
+#[cfg(feature = "tls-reload")]
+fn request_tls_reload(url: &str, token: &str) -> Result<(), CliError> {
+    let response = reqwest::blocking::Client::new()
+        .post(&format!("{}/admin/tls/reload", url))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .map_err(|err| {
+            CliError::ActionError(format!("Unable to contact the server at {}: {}", url, err))
+        })?;
+
+    match response.status() {
+        StatusCode::OK => Ok(()),
+        StatusCode::NOT_FOUND => Err(CliError::ActionError(
+            "The TLS reload endpoint was not found. The specified splinter daemon has not \
+             enabled this feature."
+                .into(),
+        )),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(CliError::ActionError(
+            "The access token was rejected; the user must have been granted the node:manage \
+             permission"
+                .into(),
+        )),
+        status_code => {
+            let message = response
+                .json::<Value>()
+                .ok()
+                .and_then(|body| {
+                    body.get("message")
+                        .and_then(Value::as_str)
+                        .map(String::from)
+                })
+                .unwrap_or_else(|| format!("status {}", status_code.as_u16()));
+            Err(CliError::ActionError(format!(
+                "The server was unable to reload its TLS certificates: {}",
+                message
+            )))
+        }
+    }
+}
+
+// determine the cert directory from the arguments, the environment, or the default location
+fn get_cert_dir(args: &ArgMatches) -> String {
+    args.value_of("cert_dir")
+        .map(ToOwned::to_owned)
+        .or_else(|| env::var(CERT_DIR_ENV).ok())
+        .or_else(|| {
+            if let Ok(splinter_home) = env::var(SPLINTER_HOME_ENV) {
+                let cert_path = Path::new(&splinter_home).join("certs");
+                if !cert_path.is_dir() {
+                    fs::create_dir_all(&cert_path)
+                        .map_err(|err| {
+                            CliError::ActionError(format!(
+                                "Unable to create cert directory: {}",
+                                err
+                            ))
+                        })
+                        .ok()?
+                }
+                cert_path.to_str().map(ToOwned::to_owned)
+            } else {
+                Some(DEFAULT_CERT_DIR.to_string())
+            }
+        })
+        .unwrap()
+}
+
 // if skip, check each pair of certificate/key to see if it exists. If not generate the
 // the missing files. If only one of the two files exists, this is an error.
 fn handle_skip(
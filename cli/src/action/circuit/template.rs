@@ -26,8 +26,11 @@ impl Action for ListCircuitTemplates {
         let templates = CircuitTemplate::list_available_templates()?;
 
         println!("Available templates:");
-        for name in templates {
-            println!("{}", name);
+        for template in templates {
+            match template.deprecated() {
+                Some(notice) => println!("{} (deprecated: {})", template.template_ref(), notice),
+                None => println!("{}", template.template_ref()),
+            }
         }
         Ok(())
     }
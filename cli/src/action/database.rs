@@ -17,8 +17,12 @@ use clap::ArgMatches;
 use super::Action;
 use crate::error::CliError;
 use diesel::{connection::Connection as _, pg::PgConnection};
+#[cfg(feature = "database-migrate-admin-service")]
+use splinter::admin::store::diesel::migrations::run_postgres_migrations as run_admin_service_postgres_migrations;
 #[cfg(feature = "database-migrate-biome")]
 use splinter::biome::migrations::run_postgres_migrations;
+#[cfg(feature = "database-migrate-registry")]
+use splinter::registry::run_postgres_migrations as run_registry_postgres_migrations;
 
 pub struct MigrateAction;
 
@@ -43,6 +47,16 @@ impl Action for MigrateAction {
             CliError::ActionError(format!("Unable to run Biome migrations: {}", err))
         })?;
 
+        #[cfg(feature = "database-migrate-admin-service")]
+        run_admin_service_postgres_migrations(&connection).map_err(|err| {
+            CliError::ActionError(format!("Unable to run admin service migrations: {}", err))
+        })?;
+
+        #[cfg(feature = "database-migrate-registry")]
+        run_registry_postgres_migrations(&connection).map_err(|err| {
+            CliError::ActionError(format!("Unable to run registry migrations: {}", err))
+        })?;
+
         Ok(())
     }
 }
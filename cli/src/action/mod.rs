@@ -23,6 +23,8 @@ pub mod database;
 pub mod health;
 pub mod keygen;
 pub mod registry;
+#[cfg(any(feature = "biome-authorization", feature = "biome-lockout"))]
+pub mod user;
 
 use std::collections::HashMap;
 use std::ffi::CString;
@@ -95,63 +95,61 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
         )
     );
 
-    app = app
+    let cert_command = SubCommand::with_name("cert")
+        .about("Generates certificates that can be used for development")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
         .subcommand(
-            SubCommand::with_name("cert")
-                .about("Generates certificates that can be used for development")
-                .setting(AppSettings::SubcommandRequiredElseHelp)
-                .subcommand(
-                    SubCommand::with_name("generate")
-                        .long_about(
-                            "Generates test certificates and keys for running splinterd with \
+            SubCommand::with_name("generate")
+                .long_about(
+                    "Generates test certificates and keys for running splinterd with \
                          TLS (in insecure mode)",
-                        )
-                        .arg(
-                            Arg::with_name("common_name")
-                                .long("common-name")
-                                .takes_value(true)
-                                .long_help(
-                                    "String that specifies a common name for the generated \
+                )
+                .arg(
+                    Arg::with_name("common_name")
+                        .long("common-name")
+                        .takes_value(true)
+                        .long_help(
+                            "String that specifies a common name for the generated \
                              certificate (defaults to localhost). Use this option if the \
                              splinterd URL uses a DNS address instead of a numerical IP \
                              address.",
-                                ),
-                        )
-                        .arg(
-                            Arg::with_name("cert_dir")
-                                .long("cert-dir")
-                                .short("d")
-                                .takes_value(true)
-                                .long_help(
-                                    "Path to the directory certificates are created in. \
+                        ),
+                )
+                .arg(
+                    Arg::with_name("cert_dir")
+                        .long("cert-dir")
+                        .short("d")
+                        .takes_value(true)
+                        .long_help(
+                            "Path to the directory certificates are created in. \
                              Defaults to /etc/splinter/certs/. This location can also be \
                              changed with the SPLINTER_CERT_DIR environment variable. \
                              This directory must exist.
                         ",
-                                ),
-                        )
-                        .arg(
-                            Arg::with_name("force")
-                                .long("force")
-                                .conflicts_with("skip")
-                                .long_help(
-                                    "Overwrites files if they exist. If this flag is not \
+                        ),
+                )
+                .arg(
+                    Arg::with_name("force")
+                        .long("force")
+                        .conflicts_with("skip")
+                        .long_help(
+                            "Overwrites files if they exist. If this flag is not \
                             provided and the file exists, an error is returned.
                         ",
-                                ),
-                        )
-                        .arg(
-                            Arg::with_name("skip")
-                                .long("skip")
-                                .conflicts_with("force")
-                                .long_help(
-                                    "Checks if the files exists and generates the files that \
+                        ),
+                )
+                .arg(
+                    Arg::with_name("skip")
+                        .long("skip")
+                        .conflicts_with("force")
+                        .long_help(
+                            "Checks if the files exists and generates the files that \
                              are missing. If this flag is not \
                              provided and the file exists, an error is returned.",
-                                ),
-                        )
-                        .after_help(
-                            "DETAILS: \n\n\
+                        ),
+                )
+                .after_help(
+                    "DETAILS: \n\n\
                     The files are generated in the location specified by --cert-dir, the \
                     SPLINTER_CERT_DIR environment variable, or in the default location \
                      /etc/splinter/certs/. \n\n\
@@ -163,40 +161,103 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
                         - generated_ca.pem \n    \
                         - generated_ca.key
                                     ",
-                        ),
-                ),
-        )
-        .subcommand(
-            SubCommand::with_name("keygen")
-                .about("Generates secp256k1 keys")
-                .arg(
-                    Arg::with_name("key-name")
-                        .takes_value(true)
-                        .help("Name of keys generated; defaults to user name"),
-                )
-                .arg(
-                    Arg::with_name("key_dir")
-                        .long("key-dir")
-                        .takes_value(true)
-                        .conflicts_with("system")
-                        .help(
-                            "Name of the directory in which to create the keys; defaults to \
-                             $HOME/splinter/keys",
-                        ),
-                )
-                .arg(
-                    Arg::with_name("force")
-                        .short("f")
-                        .long("force")
-                        .help("Overwrite files if they exist"),
-                )
-                .arg(
-                    Arg::with_name("system")
-                        .long("system")
-                        .help("Generate system keys in /etc/splinter/keys"),
                 ),
         );
 
+    #[cfg(feature = "tls-reload")]
+    let cert_command = cert_command.subcommand(
+        SubCommand::with_name("rotate")
+            .long_about(
+                "Reissues the client and server certificates and keys using the existing CA, \
+                 optionally asking a running splinterd to reload them",
+            )
+            .arg(
+                Arg::with_name("common_name")
+                    .long("common-name")
+                    .takes_value(true)
+                    .long_help(
+                        "String that specifies a common name for the reissued certificates \
+                         (defaults to localhost)",
+                    ),
+            )
+            .arg(
+                Arg::with_name("cert_dir")
+                    .long("cert-dir")
+                    .short("d")
+                    .takes_value(true)
+                    .long_help(
+                        "Path to the directory containing the certificates. Defaults to \
+                         /etc/splinter/certs/. This location can also be changed with the \
+                         SPLINTER_CERT_DIR environment variable.",
+                    ),
+            )
+            .arg(
+                Arg::with_name("reload")
+                    .long("reload")
+                    .help("Requests that splinterd reload its TLS certificates after rotation"),
+            )
+            .arg(
+                Arg::with_name("url")
+                    .short("U")
+                    .long("url")
+                    .takes_value(true)
+                    .requires("reload")
+                    .help("URL of the Splinter daemon REST API"),
+            )
+            .arg(
+                Arg::with_name("token")
+                    .long("token")
+                    .takes_value(true)
+                    .requires("reload")
+                    .long_help(
+                        "Biome access token of a user who has been granted the node:manage \
+                         permission, which is required to reload the TLS certificates. This \
+                         can also be set with the SPLINTER_REST_API_TOKEN environment variable.",
+                    ),
+            )
+            .after_help(
+                "DETAILS: \n\n\
+                 The CA certificate and key created by `splinter cert generate` \
+                 (generated_ca.pem and private/generated_ca.key) must exist. The following \
+                 files are replaced: \n    \
+                     - client.crt \n    \
+                     - client.key \n    \
+                     - server.crt \n    \
+                     - server.key",
+            ),
+    );
+
+    app = app.subcommand(cert_command).subcommand(
+        SubCommand::with_name("keygen")
+            .about("Generates secp256k1 keys")
+            .arg(
+                Arg::with_name("key-name")
+                    .takes_value(true)
+                    .help("Name of keys generated; defaults to user name"),
+            )
+            .arg(
+                Arg::with_name("key_dir")
+                    .long("key-dir")
+                    .takes_value(true)
+                    .conflicts_with("system")
+                    .help(
+                        "Name of the directory in which to create the keys; defaults to \
+                             $HOME/splinter/keys",
+                    ),
+            )
+            .arg(
+                Arg::with_name("force")
+                    .short("f")
+                    .long("force")
+                    .help("Overwrite files if they exist"),
+            )
+            .arg(
+                Arg::with_name("system")
+                    .long("system")
+                    .help("Generate system keys in /etc/splinter/keys"),
+            ),
+    );
+
     let propose_circuit = SubCommand::with_name("propose")
         .about("Propose that a new circuit is created")
         .arg(
@@ -327,7 +388,10 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
                 .long("template")
                 .takes_value(true)
                 .required_unless("service")
-                .help("Template name to be applied to circuit"),
+                .help(
+                    "Template name to be applied to circuit, optionally followed by \
+                     @<version>",
+                ),
         )
         .arg(
             Arg::with_name("template_arg")
@@ -489,7 +553,7 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
                         .required(true)
                         .takes_value(true)
                         .value_name("name")
-                        .help("Name of template"),
+                        .help("Name of template, optionally followed by @<version>"),
                 ),
             )
             .subcommand(
@@ -500,7 +564,7 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
                             .required(true)
                             .takes_value(true)
                             .value_name("name")
-                            .help("Name of template"),
+                            .help("Name of template, optionally followed by @<version>"),
                     ),
             ),
     );
@@ -588,6 +652,54 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
         )
     }
 
+    #[cfg(any(feature = "biome-authorization", feature = "biome-lockout"))]
+    {
+        let user_command = SubCommand::with_name("user")
+            .about("Biome user commands")
+            .setting(AppSettings::SubcommandRequiredElseHelp);
+
+        #[cfg(feature = "biome-authorization")]
+        let user_command = user_command.subcommand(
+            SubCommand::with_name("make-admin")
+                .about(
+                    "Assigns the admin role to a user, who can then manage the node and assign \
+                     roles to other users",
+                )
+                .arg(
+                    Arg::with_name("connect")
+                        .short("C")
+                        .takes_value(true)
+                        .help("Database connection URI"),
+                )
+                .arg(
+                    Arg::with_name("username")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Username of the user to make an administrator"),
+                ),
+        );
+
+        #[cfg(feature = "biome-lockout")]
+        let user_command = user_command.subcommand(
+            SubCommand::with_name("unlock")
+                .about("Unlocks a user that has been locked out by too many failed login attempts")
+                .arg(
+                    Arg::with_name("connect")
+                        .short("C")
+                        .takes_value(true)
+                        .help("Database connection URI"),
+                )
+                .arg(
+                    Arg::with_name("username")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Username of the user to unlock"),
+                ),
+        );
+
+        app = app.subcommand(user_command)
+    }
+
     let matches = app.get_matches_from_safe(args)?;
 
     // set default to info
@@ -621,15 +733,16 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
         Err(err) => panic!("Failed to start logger: {}", err),
     }
 
+    let cert_command = SubcommandActions::new().with_command("generate", certs::CertGenAction);
+    #[cfg(feature = "tls-reload")]
+    let cert_command = cert_command.with_command("rotate", certs::CertRotateAction);
+
     let mut subcommands = SubcommandActions::new()
         .with_command(
             "admin",
             SubcommandActions::new().with_command("keygen", admin::AdminKeyGenAction),
         )
-        .with_command(
-            "cert",
-            SubcommandActions::new().with_command("generate", certs::CertGenAction),
-        )
+        .with_command("cert", cert_command)
         .with_command("keygen", keygen::KeyGenAction);
 
     let circuit_command = SubcommandActions::new()
@@ -673,6 +786,17 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
         )
     }
 
+    #[cfg(any(feature = "biome-authorization", feature = "biome-lockout"))]
+    {
+        use action::user;
+        let user_command = SubcommandActions::new();
+        #[cfg(feature = "biome-authorization")]
+        let user_command = user_command.with_command("make-admin", user::MakeAdminAction);
+        #[cfg(feature = "biome-lockout")]
+        let user_command = user_command.with_command("unlock", user::UnlockUserAction);
+        subcommands = subcommands.with_command("user", user_command)
+    }
+
     subcommands.run(Some(&matches))
 }
 
@@ -15,9 +15,14 @@
 //! Data structure and implementation of the circuit template representation for the CLI.
 
 use std::collections::HashMap;
+use std::time::Duration;
 
 use splinter::circuit::template::{
-    CircuitCreateTemplate, CircuitTemplateError, CircuitTemplateManager, RuleArgument,
+    catalog::{
+        LocalTemplateCatalog, RemoteTemplateCatalog, TemplateCatalog, TemplateEntry,
+        UnifiedTemplateCatalog,
+    },
+    CircuitCreateTemplate, CircuitTemplateError, RuleArgument, DEFAULT_TEMPLATE_DIR,
 };
 
 use crate::action::circuit::CreateCircuitMessageBuilder;
@@ -25,6 +30,9 @@ use crate::error::CliError;
 
 const NODES_ARG: &str = "nodes";
 
+const TEMPLATE_CATALOGS_ENV: &str = "SPLINTER_CIRCUIT_TEMPLATE_CATALOGS";
+const REMOTE_CATALOG_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
 /// Representation of a circuit template used in CLI actions.
 pub struct CircuitTemplate {
     template: CircuitCreateTemplate,
@@ -32,40 +40,54 @@ pub struct CircuitTemplate {
 }
 
 impl CircuitTemplate {
-    /// Lists all available circuit templates found in the default template directory.
-    pub fn list_available_templates() -> Result<Vec<String>, CliError> {
-        let manager = CircuitTemplateManager::default();
-        let templates = manager.list_available_templates()?;
+    /// Lists all available versions of the circuit templates in the template catalogs.
+    pub fn list_available_templates() -> Result<Vec<TemplateEntry>, CliError> {
+        let mut templates = template_catalog()?.list_templates()?;
+        templates.sort_by(|a, b| a.name().cmp(b.name()).then(a.version().cmp(&b.version())));
         Ok(templates)
     }
 
-    /// Loads a YAML circuit template file into a YAML string.
+    /// Loads the YAML definition of a circuit template into a YAML string.
     ///
     /// # Arguments
     ///
-    /// * `name` - File name of the circuit template YAML file.
-    pub fn load_raw(name: &str) -> Result<String, CliError> {
-        let manager = CircuitTemplateManager::default();
-        let template_yaml = manager.load_raw_yaml(name)?;
+    /// * `template_ref` - Name of the circuit template, optionally followed by `@` and a version,
+    ///   such as `scabbard@1.2`.
+    pub fn load_raw(template_ref: &str) -> Result<String, CliError> {
+        let template_yaml = template_catalog()?.load_raw_yaml(template_ref)?;
         Ok(template_yaml)
     }
 
-    /// Loads a YAML circuit template file and returns a `CircuitTemplate` that can be used to
-    /// build `CreateCircuit` messages.
+    /// Loads a circuit template and returns a `CircuitTemplate` that can be used to build
+    /// `CreateCircuit` messages.
     ///
     /// # Arguments
     ///
-    /// * `name` - File name of the circuit template YAML file.
-    pub fn load(name: &str) -> Result<Self, CliError> {
-        let manager = CircuitTemplateManager::default();
-        let possible_values = manager.list_available_templates()?;
-        if !possible_values.iter().any(|val| val == name) {
-            return Err(CliError::ActionError(format!(
-                "Template with name {} was not found. Available templates: {:?}",
-                name, possible_values
-            )));
+    /// * `template_ref` - Name of the circuit template, optionally followed by `@` and a version,
+    ///   such as `scabbard@1.2`.
+    pub fn load(template_ref: &str) -> Result<Self, CliError> {
+        let catalog = template_catalog()?;
+        let entry = catalog.find_template(template_ref).map_err(|err| {
+            let available = catalog
+                .list_templates()
+                .map(|templates| {
+                    templates
+                        .iter()
+                        .map(TemplateEntry::template_ref)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            CliError::ActionError(format!("{}. Available templates: {:?}", err, available))
+        })?;
+        if let Some(notice) = entry.deprecated() {
+            warn!(
+                "Template {} is deprecated: {}",
+                entry.template_ref(),
+                notice
+            );
         }
-        let template = manager.load(name)?;
+
+        let template = catalog.load_template(&entry.template_ref())?;
         Ok(CircuitTemplate {
             template,
             arguments: HashMap::new(),
@@ -149,6 +171,45 @@ impl CircuitTemplate {
     }
 }
 
+/// Builds the template catalog from the comma-separated list of local directories and HTTP(S)
+/// catalog URLs in the `SPLINTER_CIRCUIT_TEMPLATE_CATALOGS` environment variable. If the variable
+/// is not set, the default template directory is used.
+fn template_catalog() -> Result<Box<dyn TemplateCatalog>, CliError> {
+    let catalogs = match std::env::var(TEMPLATE_CATALOGS_ENV) {
+        Ok(catalogs) => catalogs
+            .split(',')
+            .map(str::trim)
+            .filter(|catalog| !catalog.is_empty())
+            .map(String::from)
+            .collect(),
+        Err(_) => vec![DEFAULT_TEMPLATE_DIR.to_string()],
+    };
+
+    let mut unified = UnifiedTemplateCatalog::new();
+    for catalog in catalogs {
+        if catalog.starts_with("http://") || catalog.starts_with("https://") {
+            let cache_dir = dirs::cache_dir()
+                .ok_or_else(|| {
+                    CliError::EnvironmentError("Unable to determine cache directory".into())
+                })?
+                .join("splinter")
+                .join("circuit-templates");
+            let cache_dir = cache_dir.to_str().ok_or_else(|| {
+                CliError::EnvironmentError("Cache directory is not valid UTF-8".into())
+            })?;
+            unified.add_catalog(Box::new(RemoteTemplateCatalog::new(
+                &catalog,
+                cache_dir,
+                Some(REMOTE_CATALOG_CACHE_TTL),
+            )?));
+        } else {
+            unified.add_catalog(Box::new(LocalTemplateCatalog::new(&[&catalog])?));
+        }
+    }
+
+    Ok(Box::new(unified))
+}
+
 impl From<CircuitTemplateError> for CliError {
     fn from(err: CircuitTemplateError) -> CliError {
         CliError::ActionError(format!("Failed to process template: {}", err))
@@ -17,11 +17,10 @@ use std::convert::TryInto;
 use std::sync::mpsc::{channel, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread::{Builder, JoinHandle};
-use std::time::Duration;
 
 use protobuf::{Message, RepeatedField};
 
-use crate::consensus::two_phase::TwoPhaseEngine;
+use crate::consensus::two_phase::{CoordinatorTimeoutHandle, TwoPhaseEngine};
 use crate::consensus::{
     error::{ConsensusSendError, ProposalManagerError},
     ConsensusMessage, ConsensusNetworkSender, PeerId, Proposal, ProposalId, ProposalManager,
@@ -51,7 +50,7 @@ impl AdminConsensusManager {
         service_id: String,
         shared: Arc<Mutex<AdminServiceShared>>,
         // The coordinator timeout for the two-phase commit consensus engine
-        coordinator_timeout: Duration,
+        coordinator_timeout: CoordinatorTimeoutHandle,
     ) -> Result<Self, AdminConsensusManagerError> {
         let (consensus_msg_tx, consensus_msg_rx) = channel();
         let (proposal_update_tx, proposal_update_rx) = channel();
@@ -68,7 +67,8 @@ impl AdminConsensusManager {
         let thread_handle = Builder::new()
             .name(format!("consensus-{}", service_id))
             .spawn(move || {
-                let mut two_phase_engine = TwoPhaseEngine::new(coordinator_timeout);
+                let mut two_phase_engine =
+                    TwoPhaseEngine::with_coordinator_timeout_handle(coordinator_timeout);
                 if let Err(err) = two_phase_engine.run(
                     consensus_msg_rx,
                     proposal_update_rx,
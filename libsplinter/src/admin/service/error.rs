@@ -352,6 +352,7 @@ impl From<Sha256Error> for AdminSharedError {
 #[derive(Debug)]
 pub enum MarshallingError {
     UnsetField(String),
+    UnsupportedRouteType(String),
     ProtobufError(error::ProtobufError),
 }
 
@@ -359,6 +360,7 @@ impl std::error::Error for MarshallingError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             MarshallingError::UnsetField(_) => None,
+            MarshallingError::UnsupportedRouteType(_) => None,
             MarshallingError::ProtobufError(err) => Some(err),
         }
     }
@@ -368,6 +370,9 @@ impl std::fmt::Display for MarshallingError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             MarshallingError::UnsetField(_) => write!(f, "Invalid enumerated type"),
+            MarshallingError::UnsupportedRouteType(route_type) => {
+                write!(f, "Unsupported route type: {}", route_type)
+            }
             MarshallingError::ProtobufError(err) => write!(f, "Protobuf Error: {}", err),
         }
     }
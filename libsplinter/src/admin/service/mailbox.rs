@@ -30,6 +30,23 @@ pub struct EventEntry {
     event: AdminServiceEvent,
 }
 
+impl EventEntry {
+    /// Constructs a new entry for an event that occurred at the given time.
+    pub fn new(timestamp: SystemTime, event: AdminServiceEvent) -> Self {
+        Self { timestamp, event }
+    }
+
+    /// Returns the time at which the event occurred.
+    pub fn timestamp(&self) -> &SystemTime {
+        &self.timestamp
+    }
+
+    /// Returns the event.
+    pub fn event(&self) -> &AdminServiceEvent {
+        &self.event
+    }
+}
+
 impl cmp::Ord for EventEntry {
     fn cmp(&self, other: &Self) -> cmp::Ordering {
         self.timestamp.cmp(&other.timestamp)
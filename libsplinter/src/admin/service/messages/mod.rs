@@ -72,6 +72,16 @@ impl CreateCircuit {
 
         let routes = match proto.get_routes() {
             admin::Circuit_RouteType::ANY_ROUTE => RouteType::Any,
+            #[cfg(feature = "circuit-relay")]
+            admin::Circuit_RouteType::RELAYED_ROUTE => RouteType::Relayed {
+                relay_node: SplinterNode::from_proto(proto.take_relay_node())?,
+            },
+            #[cfg(not(feature = "circuit-relay"))]
+            admin::Circuit_RouteType::RELAYED_ROUTE => {
+                return Err(MarshallingError::UnsupportedRouteType(
+                    "RELAYED_ROUTE".to_string(),
+                ));
+            }
             admin::Circuit_RouteType::UNSET_ROUTE_TYPE => {
                 return Err(MarshallingError::UnsetField("Unset route type".to_string()));
             }
@@ -140,6 +150,11 @@ impl CreateCircuit {
 
         match self.routes {
             RouteType::Any => circuit.set_routes(admin::Circuit_RouteType::ANY_ROUTE),
+            #[cfg(feature = "circuit-relay")]
+            RouteType::Relayed { relay_node } => {
+                circuit.set_routes(admin::Circuit_RouteType::RELAYED_ROUTE);
+                circuit.set_relay_node(relay_node.into_proto());
+            }
         };
 
         let mut create_request = CircuitCreateRequest::new();
@@ -186,6 +201,12 @@ pub enum DurabilityType {
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub enum RouteType {
     Any,
+    /// Messages between members are forwarded through the relay node, which must not be a member
+    /// of the circuit.
+    #[cfg(feature = "circuit-relay")]
+    Relayed {
+        relay_node: SplinterNode,
+    },
 }
 
 impl Default for RouteType {
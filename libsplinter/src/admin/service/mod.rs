@@ -30,7 +30,10 @@ use std::time::{Duration, SystemTime};
 use openssl::hash::{hash, MessageDigest};
 use protobuf::{self, Message};
 
+#[cfg(feature = "circuit-relay")]
+use crate::circuit::RouteType;
 use crate::circuit::SplinterState;
+use crate::consensus::two_phase::CoordinatorTimeoutHandle;
 use crate::consensus::Proposal;
 use crate::hex::to_hex;
 use crate::keys::KeyPermissionManager;
@@ -49,19 +52,27 @@ use crate::service::{
     Service, ServiceMessageContext, ServiceNetworkRegistry,
 };
 use crate::signing::SignatureVerifier;
+use crate::storage::sets::DurableOrderedSet;
 
 use self::consensus::AdminConsensusManager;
 use self::error::{AdminError, Sha256Error};
+use self::mailbox::Mailbox;
 use self::proposal_store::{AdminServiceProposals, ProposalStore};
 use self::shared::AdminServiceShared;
 
 pub use self::error::AdminKeyVerifierError;
 pub use self::error::AdminServiceError;
 pub use self::error::AdminSubscriberError;
+pub use self::mailbox::EventEntry;
 pub use self::shared::AdminServiceStatus;
+#[cfg(all(feature = "admin-service-store", feature = "store-factory"))]
+pub(crate) use self::shared::DEFAULT_IN_MEMORY_EVENT_LIMIT;
 
 const DEFAULT_COORDINATOR_TIMEOUT: u64 = 30; // 30 seconds
 
+/// The event type under which a subscriber receives the events of every circuit management type.
+pub const ALL_EVENT_TYPES: &str = "*";
+
 pub trait AdminServiceEventSubscriber: Send {
     fn handle_event(
         &self,
@@ -76,6 +87,8 @@ pub trait AdminCommands: Send + Sync {
         circuit_change: CircuitManagementPayload,
     ) -> Result<(), AdminServiceError>;
 
+    /// Adds a subscriber for the events of circuits with the given circuit management type. A
+    /// subscriber added with the type `ALL_EVENT_TYPES` receives the events of every circuit.
     fn add_event_subscriber(
         &self,
         event_type: &str,
@@ -149,7 +162,7 @@ pub struct AdminService {
     admin_service_shared: Arc<Mutex<AdminServiceShared>>,
     orchestrator: Arc<Mutex<ServiceOrchestrator>>,
     /// The coordinator timeout for the two-phase commit consensus engine
-    coordinator_timeout: Duration,
+    coordinator_timeout: CoordinatorTimeoutHandle,
     consensus: Option<AdminConsensusManager>,
     peer_connector: PeerManagerConnector,
 }
@@ -174,8 +187,9 @@ impl AdminService {
         // default value will be used (30 seconds).
         coordinator_timeout: Option<Duration>,
     ) -> Result<(Self, thread::JoinHandle<()>), ServiceError> {
-        let coordinator_timeout =
-            coordinator_timeout.unwrap_or_else(|| Duration::from_secs(DEFAULT_COORDINATOR_TIMEOUT));
+        let coordinator_timeout = CoordinatorTimeoutHandle::new(
+            coordinator_timeout.unwrap_or_else(|| Duration::from_secs(DEFAULT_COORDINATOR_TIMEOUT)),
+        );
         let orchestrator = Arc::new(Mutex::new(orchestrator));
         let (sender, receiver) = channel();
         peer_connector
@@ -243,6 +257,26 @@ impl AdminService {
         AdminServiceProposals::new(&self.admin_service_shared)
     }
 
+    /// Replaces the set that backs the service's event mailbox, which is kept in memory by
+    /// default. This should be called before the service is started, as the events recorded in
+    /// the previous set are not carried over.
+    pub fn set_event_store(
+        &self,
+        event_store: Box<dyn DurableOrderedSet<EventEntry, SystemTime>>,
+    ) -> Result<(), AdminServiceError> {
+        self.admin_service_shared
+            .lock()
+            .map_err(|_| AdminServiceError::general_error("Admin shared lock was lock poisoned"))?
+            .set_event_mailbox(Mailbox::new(event_store));
+        Ok(())
+    }
+
+    /// Returns a handle that can change the two-phase commit coordinator timeout while the service
+    /// is running.
+    pub fn coordinator_timeout_handle(&self) -> CoordinatorTimeoutHandle {
+        self.coordinator_timeout.clone()
+    }
+
     /// On restart of a splinter node, all services that this node should run on the existing
     /// circuits should be initialized using the service orchestrator. This may not include all
     /// services if they are not supported locally. It is expected that some services will be
@@ -274,9 +308,30 @@ impl AdminService {
         let mut peer_refs = vec![];
         // start all services of the supported types
         for (circuit_name, circuit) in circuits.iter() {
+            // the members of a circuit with relayed routes are reached through its relay node
+            #[cfg(feature = "circuit-relay")]
+            let relayed = if let RouteType::Relayed { relay_node } = circuit.routes() {
+                if let Some(node) = nodes.get(relay_node) {
+                    match self
+                        .peer_connector
+                        .add_peer_ref(relay_node.to_string(), node.endpoints().to_vec())
+                    {
+                        Ok(peer_ref) => peer_refs.push(peer_ref),
+                        Err(_) => info!("Unable to peer with relay {} at this time", relay_node),
+                    }
+                } else {
+                    error!("Missing node information for relay {}", relay_node);
+                }
+                true
+            } else {
+                false
+            };
+            #[cfg(not(feature = "circuit-relay"))]
+            let relayed = false;
+
             // restart all peer in the circuit
             for member in circuit.members() {
-                if member != &self.node_id {
+                if member != &self.node_id && !relayed {
                     if let Some(node) = nodes.get(member) {
                         let peer_ref = self
                             .peer_connector
@@ -341,9 +396,40 @@ impl AdminService {
             .get_proposals();
 
         for (_, proposal) in proposals.iter() {
+            #[cfg(feature = "circuit-relay")]
+            self.admin_service_shared
+                .lock()
+                .map_err(|_| {
+                    ServiceStartError::PoisonedLock("the admin shared lock was poisoned".into())
+                })?
+                .add_proposal_route(proposal)
+                .map_err(|err| ServiceStartError::Internal(Box::new(err)))?;
+
+            // the members of a proposed circuit with relayed routes are reached through its relay
+            // node
+            #[cfg(feature = "circuit-relay")]
+            let relayed =
+                if let messages::RouteType::Relayed { relay_node } = &proposal.circuit.routes {
+                    match self.peer_connector.add_peer_ref(
+                        relay_node.node_id.to_string(),
+                        relay_node.endpoints.to_vec(),
+                    ) {
+                        Ok(peer_ref) => peer_refs.push(peer_ref),
+                        Err(_) => info!(
+                            "Unable to peer with relay {} at this time",
+                            relay_node.node_id
+                        ),
+                    }
+                    true
+                } else {
+                    false
+                };
+            #[cfg(not(feature = "circuit-relay"))]
+            let relayed = false;
+
             // restart all peer in the circuit
             for member in proposal.circuit.members.iter() {
-                if member.node_id != self.node_id {
+                if member.node_id != self.node_id && !relayed {
                     let peer_ref = self
                         .peer_connector
                         .add_peer_ref(member.node_id.to_string(), member.endpoints.to_vec());
@@ -398,7 +484,7 @@ impl Service for AdminService {
         let consensus = AdminConsensusManager::new(
             self.service_id().into(),
             self.admin_service_shared.clone(),
-            self.coordinator_timeout,
+            self.coordinator_timeout.clone(),
         )
         .map_err(|err| ServiceStartError::Internal(Box::new(err)))?;
         let proposal_sender = consensus.proposal_update_sender();
@@ -547,6 +633,39 @@ impl Service for AdminService {
                     .add_ready_member(circuit_id, member_node_id.into())
                     .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))
             }
+            #[cfg(feature = "circuit-relay")]
+            AdminMessage_Type::RELAY_REGISTRATION_REQUEST => self
+                .admin_service_shared
+                .lock()
+                .map_err(|_| {
+                    ServiceError::PoisonedLock("the admin shared lock was poisoned".into())
+                })?
+                .handle_relay_registration(
+                    &message_context.sender,
+                    admin_message.get_relay_registration_request(),
+                )
+                .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err))),
+            #[cfg(feature = "circuit-relay")]
+            AdminMessage_Type::RELAY_REGISTRATION_RESPONSE => self
+                .admin_service_shared
+                .lock()
+                .map_err(|_| {
+                    ServiceError::PoisonedLock("the admin shared lock was poisoned".into())
+                })?
+                .on_relay_registered(
+                    &message_context.sender,
+                    admin_message.get_relay_registration_response(),
+                )
+                .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err))),
+            #[cfg(not(feature = "circuit-relay"))]
+            AdminMessage_Type::RELAY_REGISTRATION_REQUEST
+            | AdminMessage_Type::RELAY_REGISTRATION_RESPONSE => {
+                warn!(
+                    "Ignoring relay registration message from {}; relays are not supported",
+                    message_context.sender
+                );
+                Ok(())
+            }
             AdminMessage_Type::SERVICE_PROTOCOL_VERSION_REQUEST => {
                 let request = admin_message.get_protocol_request();
                 let protocol =
@@ -45,6 +45,8 @@ use crate::protos::admin::{
     Circuit_PersistenceType, Circuit_RouteType, MemberReady, ServiceProtocolVersionRequest,
     SplinterNode,
 };
+#[cfg(feature = "circuit-relay")]
+use crate::protos::admin::{RelayRegistrationRequest, RelayRegistrationResponse};
 use crate::service::error::ServiceError;
 #[cfg(feature = "service-arg-validation")]
 use crate::service::validation::ServiceArgValidator;
@@ -59,13 +61,13 @@ use super::messages;
 use super::open_proposals::OpenProposals;
 use super::{
     admin_service_id, sha256, AdminKeyVerifier, AdminServiceEventSubscriber, AdminSubscriberError,
-    Events,
+    Events, ALL_EVENT_TYPES,
 };
 
 static VOTER_ROLE: &str = "voter";
 static PROPOSER_ROLE: &str = "proposer";
 
-const DEFAULT_IN_MEMORY_EVENT_LIMIT: usize = 100;
+pub(crate) const DEFAULT_IN_MEMORY_EVENT_LIMIT: usize = 100;
 
 pub enum PayloadType {
     Circuit(CircuitManagementPayload),
@@ -88,6 +90,13 @@ pub struct PendingPayload {
     pub members: Vec<String>,
 }
 
+/// The peers and admin services a payload for a circuit waits on before it can proceed
+struct MemberPeering {
+    unpeered_ids: Vec<String>,
+    missing_protocol_ids: Vec<String>,
+    members: Vec<String>,
+}
+
 enum CircuitProposalStatus {
     Accepted,
     Rejected,
@@ -123,17 +132,25 @@ impl SubscriberMap {
         timestamp: &SystemTime,
     ) {
         let mut subscribers_by_type = self.subscribers_by_type.borrow_mut();
-        if let Some(subscribers) = subscribers_by_type.get_mut(event_type) {
-            subscribers.retain(|subscriber| {
-                match subscriber.handle_event(admin_service_event, timestamp) {
-                    Ok(()) => true,
-                    Err(AdminSubscriberError::Unsubscribe) => false,
-                    Err(AdminSubscriberError::UnableToHandleEvent(msg)) => {
-                        error!("Unable to send event: {}", msg);
-                        true
+        // Subscribers registered under the wildcard type receive every event, regardless of the
+        // circuit management type.
+        let mut types = vec![event_type];
+        if event_type != ALL_EVENT_TYPES {
+            types.push(ALL_EVENT_TYPES);
+        }
+        for subscriber_type in types {
+            if let Some(subscribers) = subscribers_by_type.get_mut(subscriber_type) {
+                subscribers.retain(|subscriber| {
+                    match subscriber.handle_event(admin_service_event, timestamp) {
+                        Ok(()) => true,
+                        Err(AdminSubscriberError::Unsubscribe) => false,
+                        Err(AdminSubscriberError::UnableToHandleEvent(msg)) => {
+                            error!("Unable to send event: {}", msg);
+                            true
+                        }
                     }
-                }
-            });
+                });
+            }
         }
     }
 
@@ -278,6 +295,10 @@ impl AdminServiceShared {
         self.network_sender = network_sender;
     }
 
+    pub fn set_event_mailbox(&mut self, event_mailbox: Mailbox) {
+        self.event_mailbox = event_mailbox;
+    }
+
     pub fn set_proposal_sender(&mut self, proposal_sender: Option<Sender<ProposalUpdate>>) {
         self.proposal_sender = proposal_sender;
     }
@@ -475,10 +496,12 @@ impl AdminServiceShared {
 
     pub fn rollback(&mut self) -> Result<(), AdminSharedError> {
         match self.pending_changes.take() {
-            Some(circuit_proposal_context) => info!(
-                "discarded change for {}",
-                circuit_proposal_context.circuit_proposal.get_circuit_id()
-            ),
+            Some(circuit_proposal_context) => {
+                let circuit_id = circuit_proposal_context.circuit_proposal.get_circuit_id();
+                #[cfg(feature = "circuit-relay")]
+                self.remove_circuit_route(circuit_id);
+                info!("discarded change for {}", circuit_id)
+            }
             None => debug!("no changes to rollback"),
         }
 
@@ -516,9 +539,7 @@ impl AdminServiceShared {
                 )
                 .map_err(|err| {
                     // remove peer_ref because we will not accept this proposal
-                    for member in proposed_circuit.get_members() {
-                        self.remove_peer_ref(member.get_node_id())
-                    }
+                    self.remove_member_peer_refs(&proposed_circuit);
                     err
                 })?;
                 debug!("proposing {}", proposed_circuit.get_circuit_id());
@@ -577,9 +598,7 @@ impl AdminServiceShared {
                     if circuit_proposal.get_proposal_type() == CircuitProposal_ProposalType::CREATE
                     {
                         // remove peer_ref because we will not accept this proposal
-                        for member in circuit_proposal.get_circuit_proposal().get_members() {
-                            self.remove_peer_ref(member.get_node_id())
-                        }
+                        self.remove_member_peer_refs(circuit_proposal.get_circuit_proposal());
                     }
                     err
                 })?;
@@ -633,13 +652,7 @@ impl AdminServiceShared {
                 .get_circuit_id()
         );
 
-        // get members as vec to payload can be sent to helper function as well
-        let members = payload
-            .get_circuit_create_request()
-            .get_circuit()
-            .get_members()
-            .to_vec();
-        self.check_connected_peers_payload_create(&members, payload, message_sender)
+        self.check_connected_peers_payload_create(payload, message_sender)
     }
 
     pub fn propose_vote(
@@ -669,7 +682,7 @@ impl AdminServiceShared {
             })?;
 
         self.check_connected_peers_payload_vote(
-            proposal.get_circuit_proposal().get_members(),
+            proposal.get_circuit_proposal(),
             payload,
             message_sender,
         )
@@ -708,24 +721,29 @@ impl AdminServiceShared {
 
     fn check_connected_peers_payload_vote(
         &mut self,
-        members: &[SplinterNode],
+        circuit: &Circuit,
         payload: CircuitManagementPayload,
         message_sender: String,
     ) -> Result<(), ServiceError> {
         let mut missing_protocol_ids = vec![];
-        let mut pending_members = vec![];
-        for node in members {
-            if self.node_id() != node.get_node_id()
-                && self
-                    .service_protocols
-                    .get(&admin_service_id(node.get_node_id()))
-                    .is_none()
+        let member_routes = self
+            .route_members(circuit)
+            .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+        for (member, _) in member_routes {
+            if self
+                .service_protocols
+                .get(&admin_service_id(member.get_node_id()))
+                .is_none()
             {
-                self.send_protocol_request(node.get_node_id())?;
-                missing_protocol_ids.push(admin_service_id(node.get_node_id()))
+                self.send_protocol_request(member.get_node_id())?;
+                missing_protocol_ids.push(admin_service_id(member.get_node_id()))
             }
-            pending_members.push(node.get_node_id().to_string());
         }
+        let pending_members = circuit
+            .get_members()
+            .iter()
+            .map(|member| member.get_node_id().to_string())
+            .collect();
 
         if missing_protocol_ids.is_empty() {
             self.pending_circuit_payloads.push_back(payload);
@@ -748,22 +766,59 @@ impl AdminServiceShared {
 
     fn check_connected_peers_payload_create(
         &mut self,
-        members: &[SplinterNode],
         payload: CircuitManagementPayload,
         message_sender: String,
     ) -> Result<(), ServiceError> {
+        let MemberPeering {
+            unpeered_ids: pending_peers,
+            missing_protocol_ids,
+            members: pending_members,
+        } = self.peer_with_members(payload.get_circuit_create_request().get_circuit())?;
+
+        if missing_protocol_ids.is_empty() {
+            self.pending_circuit_payloads.push_back(payload);
+        } else {
+            debug!(
+                "Members {:?} added; awaiting peering and service protocol agreement before \
+                proceeding",
+                &missing_protocol_ids
+            );
+            self.unpeered_payloads.push(PendingPayload {
+                unpeered_ids: pending_peers,
+                missing_protocol_ids,
+                payload_type: PayloadType::Circuit(payload),
+                members: pending_members,
+                message_sender,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Adds peer refs for the nodes this node connects to in order to reach the other members of
+    /// the circuit.
+    ///
+    /// Returns the peers that must be connected and the admin services that must agree on a
+    /// protocol before a payload for the circuit can proceed, and the members and peers whose
+    /// disconnection holds the payload back again.
+    fn peer_with_members(&mut self, circuit: &Circuit) -> Result<MemberPeering, ServiceError> {
         let mut missing_protocol_ids = vec![];
         let mut pending_peers = vec![];
-        let mut pending_members = vec![];
         let mut added_peers: Vec<String> = vec![];
-        for node in members {
-            if self.node_id() != node.get_node_id() {
-                debug!("Referencing node {:?}", node);
+        let member_routes = self
+            .route_members(circuit)
+            .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+        for (member, peer) in member_routes {
+            if !added_peers
+                .iter()
+                .any(|node_id| node_id == peer.get_node_id())
+            {
+                debug!("Referencing node {:?}", peer);
                 let peer_ref = self
                     .peer_connector
                     .add_peer_ref(
-                        node.get_node_id().to_string(),
-                        node.get_endpoints().to_vec(),
+                        peer.get_node_id().to_string(),
+                        peer.get_endpoints().to_vec(),
                     )
                     .map_err(|err| {
                         // remove all peer refs added for this proposal
@@ -775,41 +830,293 @@ impl AdminServiceShared {
                     })?;
 
                 self.add_peer_ref(peer_ref);
-                added_peers.push(node.get_node_id().to_string());
+                added_peers.push(peer.get_node_id().to_string());
+            }
 
-                // if we have a protocol the connection exists for the peer already
-                if self
-                    .service_protocols
-                    .get(&admin_service_id(node.get_node_id()))
-                    .is_none()
+            // if we have a protocol the connection exists for the peer already
+            if self
+                .service_protocols
+                .get(&admin_service_id(member.get_node_id()))
+                .is_none()
+            {
+                if !pending_peers
+                    .iter()
+                    .any(|node_id| node_id == peer.get_node_id())
                 {
-                    pending_peers.push(node.get_node_id().to_string());
-                    missing_protocol_ids.push(admin_service_id(node.get_node_id()))
+                    pending_peers.push(peer.get_node_id().to_string());
                 }
+                missing_protocol_ids.push(admin_service_id(member.get_node_id()))
             }
-            pending_members.push(node.get_node_id().to_string())
         }
 
-        if missing_protocol_ids.is_empty() {
-            self.pending_circuit_payloads.push_back(payload);
+        let mut pending_members: Vec<String> = circuit
+            .get_members()
+            .iter()
+            .map(|member| member.get_node_id().to_string())
+            .collect();
+        for node_id in added_peers {
+            if !pending_members.contains(&node_id) {
+                pending_members.push(node_id);
+            }
+        }
+
+        Ok(MemberPeering {
+            unpeered_ids: pending_peers,
+            missing_protocol_ids,
+            members: pending_members,
+        })
+    }
+
+    /// Returns each of the other members of the circuit, paired with the node this node peers with
+    /// to reach it.
+    ///
+    /// The members of a circuit with relayed routes are reached through its relay node. The
+    /// circuit's route is recorded so that admin messages to its members are sent through the
+    /// relay, and so that admin messages from them are only accepted through it.
+    fn route_members<'a>(
+        &self,
+        circuit: &'a Circuit,
+    ) -> Result<Vec<(&'a SplinterNode, &'a SplinterNode)>, AdminSharedError> {
+        let members = circuit
+            .get_members()
+            .iter()
+            .filter(|member| member.get_node_id() != self.node_id());
+
+        #[cfg(feature = "circuit-relay")]
+        {
+            let relayed = circuit.get_routes() == Circuit_RouteType::RELAYED_ROUTE;
+            self.splinter_state.add_circuit_route(
+                circuit.get_circuit_id().to_string(),
+                circuit
+                    .get_members()
+                    .iter()
+                    .map(|member| member.get_node_id().to_string())
+                    .collect(),
+                if relayed {
+                    Some(circuit.get_relay_node().get_node_id().to_string())
+                } else {
+                    None
+                },
+            )?;
+
+            if relayed {
+                let relay_node = circuit.get_relay_node();
+                // If the relay is not connected yet, the circuit is registered once it is
+                if let Err(err) = self.send_relay_registration(
+                    relay_node.get_node_id(),
+                    circuit.get_circuit_id(),
+                    circuit
+                        .get_members()
+                        .iter()
+                        .map(|member| member.get_node_id().to_string())
+                        .collect(),
+                    false,
+                ) {
+                    debug!(
+                        "Unable to register circuit {} with relay {}: {}",
+                        circuit.get_circuit_id(),
+                        relay_node.get_node_id(),
+                        err
+                    );
+                }
+                return Ok(members.map(|member| (member, relay_node)).collect());
+            }
+        }
+
+        Ok(members.map(|member| (member, member)).collect())
+    }
+
+    /// Removes the route recorded for a circuit proposal, unless the proposal is stored or the
+    /// circuit has been added, and unregisters it from its relay node.
+    #[cfg(feature = "circuit-relay")]
+    fn remove_circuit_route(&self, circuit_id: &str) {
+        match self.open_proposals.get_proposal(circuit_id) {
+            Ok(Some(_)) => (),
+            Ok(None) => {
+                if let Err(err) = self.unroute_circuit(circuit_id) {
+                    error!("Unable to remove route for circuit {}: {}", circuit_id, err);
+                }
+            }
+            Err(err) => error!("Unable to get proposal for circuit {}: {}", circuit_id, err),
+        }
+    }
+
+    /// Removes the route recorded for a circuit or proposal, and unregisters it from its relay
+    /// node if the route was removed.
+    #[cfg(feature = "circuit-relay")]
+    fn unroute_circuit(&self, circuit_id: &str) -> Result<(), AdminSharedError> {
+        let relay_node = self.splinter_state.circuit_relay_node(circuit_id)?;
+        self.splinter_state.remove_circuit_route(circuit_id)?;
+
+        if let Some(relay_node) = relay_node {
+            // the route is kept if the circuit has been added
+            if self
+                .splinter_state
+                .circuit_relay_node(circuit_id)?
+                .is_none()
+            {
+                self.send_relay_registration(&relay_node, circuit_id, vec![], true)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends a request to the admin service of the relay node to register or unregister a
+    /// circuit or proposal with relayed routes, so that the relay forwards messages between its
+    /// members.
+    #[cfg(feature = "circuit-relay")]
+    fn send_relay_registration(
+        &self,
+        relay_node: &str,
+        circuit_id: &str,
+        members: Vec<String>,
+        unregister: bool,
+    ) -> Result<(), AdminSharedError> {
+        let network_sender = self.network_sender.as_ref().ok_or_else(|| {
+            AdminSharedError::ServiceProtocolError(format!(
+                "AdminService is not started, can't register circuit with relay {}",
+                relay_node
+            ))
+        })?;
+
+        let mut request = RelayRegistrationRequest::new();
+        request.set_circuit_id(circuit_id.to_string());
+        request.set_members(RepeatedField::from_vec(members));
+        request.set_unregister(unregister);
+        let mut msg = AdminMessage::new();
+        msg.set_message_type(AdminMessage_Type::RELAY_REGISTRATION_REQUEST);
+        msg.set_relay_registration_request(request);
+
+        let envelope_bytes = msg.write_to_bytes().map_err(MarshallingError::from)?;
+        network_sender.send(&admin_service_id(relay_node), &envelope_bytes)?;
+        Ok(())
+    }
+
+    /// Handles a request from a member of a circuit or proposal with relayed routes through this
+    /// node to register or unregister it, and replies with whether it was accepted.
+    #[cfg(feature = "circuit-relay")]
+    pub fn handle_relay_registration(
+        &self,
+        message_sender: &str,
+        request: &RelayRegistrationRequest,
+    ) -> Result<(), AdminSharedError> {
+        let node_id = message_sender.strip_prefix("admin::").ok_or_else(|| {
+            AdminSharedError::ValidationFailed(format!(
+                "Relay registration sender is not an admin service: {}",
+                message_sender
+            ))
+        })?;
+        let circuit_id = request.get_circuit_id();
+
+        if request.get_unregister() {
+            self.splinter_state
+                .unregister_relayed_circuit(circuit_id, node_id)?;
+            return Ok(());
+        }
+
+        let accepted = if !request.get_members().iter().any(|member| member == node_id) {
+            warn!(
+                "Node {} registered circuit {} without being a member",
+                node_id, circuit_id
+            );
+            false
         } else {
-            debug!(
-                "Members {:?} added; awaiting peering and service protocol agreement before \
-                proceeding",
-                &missing_protocol_ids
+            let registered = self.splinter_state.register_relayed_circuit(
+                circuit_id.to_string(),
+                node_id.to_string(),
+                request.get_members().to_vec(),
+            )?;
+            if !registered {
+                warn!(
+                    "Node {} has registered too many circuits to register {}",
+                    node_id, circuit_id
+                );
+            }
+            registered
+        };
+
+        let network_sender = self.network_sender.as_ref().ok_or_else(|| {
+            AdminSharedError::ServiceProtocolError(format!(
+                "AdminService is not started, can't reply to {}",
+                message_sender
+            ))
+        })?;
+        let mut response = RelayRegistrationResponse::new();
+        response.set_circuit_id(circuit_id.to_string());
+        response.set_accepted(accepted);
+        let mut msg = AdminMessage::new();
+        msg.set_message_type(AdminMessage_Type::RELAY_REGISTRATION_RESPONSE);
+        msg.set_relay_registration_response(response);
+
+        let envelope_bytes = msg.write_to_bytes().map_err(MarshallingError::from)?;
+        network_sender.send(message_sender, &envelope_bytes)?;
+        Ok(())
+    }
+
+    /// Called when the relay node has registered a circuit or proposal with relayed routes
+    /// through it. The members reached through the relay can now agree on a protocol.
+    #[cfg(feature = "circuit-relay")]
+    pub fn on_relay_registered(
+        &mut self,
+        message_sender: &str,
+        response: &RelayRegistrationResponse,
+    ) -> Result<(), AdminSharedError> {
+        let relay_node = message_sender
+            .strip_prefix("admin::")
+            .unwrap_or(message_sender);
+        let circuit_id = response.get_circuit_id();
+        if !response.get_accepted() {
+            warn!(
+                "Relay {} did not accept the registration of circuit {}",
+                relay_node, circuit_id
             );
-            self.unpeered_payloads.push(PendingPayload {
-                unpeered_ids: pending_peers,
-                missing_protocol_ids,
-                payload_type: PayloadType::Circuit(payload),
-                members: pending_members,
-                message_sender,
-            });
+            return Ok(());
         }
 
+        let members = match self
+            .splinter_state
+            .relayed_circuits(relay_node)?
+            .into_iter()
+            .find(|(relayed_circuit_id, _)| relayed_circuit_id == circuit_id)
+        {
+            Some((_, members)) => members,
+            None => return Ok(()),
+        };
+
+        // the relay may have been connected before the circuit was proposed
+        self.on_peer_reachable(relay_node);
+
+        for node_id in members {
+            if node_id != self.node_id
+                && self.splinter_state.relay_route(&node_id)?.as_deref() == Some(relay_node)
+            {
+                self.send_protocol_request(&node_id).map_err(|err| {
+                    AdminSharedError::ServiceProtocolError(format!(
+                        "Unable to send service protocol request: {}",
+                        err
+                    ))
+                })?;
+            }
+        }
         Ok(())
     }
 
+    /// Removes the peer refs that were added to reach the other members of the circuit.
+    fn remove_member_peer_refs(&mut self, circuit: &Circuit) {
+        #[cfg(feature = "circuit-relay")]
+        {
+            self.remove_circuit_route(circuit.get_circuit_id());
+            if circuit.get_routes() == Circuit_RouteType::RELAYED_ROUTE {
+                self.remove_peer_ref(circuit.get_relay_node().get_node_id());
+                return;
+            }
+        }
+
+        for member in circuit.get_members() {
+            self.remove_peer_ref(member.get_node_id())
+        }
+    }
+
     pub fn submit(&mut self, payload: CircuitManagementPayload) -> Result<(), ServiceError> {
         debug!("Payload submitted: {:?}", payload);
 
@@ -891,47 +1198,11 @@ impl AdminServiceShared {
         payload: CircuitManagementPayload,
         message_sender: String,
     ) -> Result<(), ServiceError> {
-        let mut missing_protocol_ids = vec![];
-        let mut pending_peers = vec![];
-        let mut added_peers: Vec<String> = vec![];
-        let mut pending_members = vec![];
-        for node in payload
-            .get_circuit_create_request()
-            .get_circuit()
-            .get_members()
-        {
-            if self.node_id() != node.get_node_id() {
-                debug!("Referencing node {:?}", node);
-                let peer_ref = self
-                    .peer_connector
-                    .add_peer_ref(
-                        node.get_node_id().to_string(),
-                        node.get_endpoints().to_vec(),
-                    )
-                    .map_err(|err| {
-                        // remove all peer refs added for this proposal
-                        for node_id in added_peers.iter() {
-                            self.remove_peer_ref(node_id);
-                        }
-
-                        ServiceError::UnableToHandleMessage(Box::new(err))
-                    })?;
-
-                self.add_peer_ref(peer_ref);
-                added_peers.push(node.get_node_id().to_string());
-
-                // if we have a protocol the connection exists for the peer already
-                if self
-                    .service_protocols
-                    .get(&admin_service_id(node.get_node_id()))
-                    .is_none()
-                {
-                    pending_peers.push(node.get_node_id().to_string());
-                    missing_protocol_ids.push(admin_service_id(node.get_node_id()))
-                }
-            }
-            pending_members.push(node.get_node_id().to_string())
-        }
+        let MemberPeering {
+            unpeered_ids: pending_peers,
+            missing_protocol_ids,
+            members: pending_members,
+        } = self.peer_with_members(payload.get_circuit_create_request().get_circuit())?;
 
         if missing_protocol_ids.is_empty() {
             self.add_pending_consensus_proposal(proposal.id.clone(), (proposal.clone(), payload));
@@ -1012,6 +1283,33 @@ impl AdminServiceShared {
 
     pub fn on_peer_disconnected(&mut self, peer_id: String) {
         self.service_protocols.remove(&admin_service_id(&peer_id));
+
+        // the admin services reached through the peer as a relay must agree on a protocol again
+        #[cfg(feature = "circuit-relay")]
+        {
+            match self.splinter_state.relayed_nodes(&peer_id) {
+                Ok(relayed_nodes) => {
+                    for node_id in relayed_nodes {
+                        self.service_protocols.remove(&admin_service_id(&node_id));
+                    }
+                }
+                Err(err) => error!("Unable to get nodes relayed through {}: {}", peer_id, err),
+            }
+            if let Err(err) = self.splinter_state.remove_learned_relay_routes(&peer_id) {
+                error!(
+                    "Unable to remove routes learned through {}: {}",
+                    peer_id, err
+                );
+            }
+            // the peer registers its circuits again when it reconnects
+            if let Err(err) = self.splinter_state.remove_relay_registrations(&peer_id) {
+                error!(
+                    "Unable to remove relay registrations of {}: {}",
+                    peer_id, err
+                );
+            }
+        }
+
         let mut pending_protocol_payloads =
             std::mem::replace(&mut self.pending_protocol_payloads, vec![]);
 
@@ -1044,7 +1342,8 @@ impl AdminServiceShared {
         self.unpeered_payloads = unpeered_payloads;
     }
 
-    pub fn on_peer_connected(&mut self, peer_id: &str) -> Result<(), AdminSharedError> {
+    /// Removes the peer from the peers that pending payloads are waiting to be connected to.
+    fn on_peer_reachable(&mut self, peer_id: &str) {
         let mut unpeered_payloads = std::mem::replace(&mut self.unpeered_payloads, vec![]);
         for unpeered_payload in unpeered_payloads.iter_mut() {
             unpeered_payload
@@ -1061,6 +1360,19 @@ impl AdminServiceShared {
         for peered_payload in fully_peered {
             self.pending_protocol_payloads.push(peered_payload);
         }
+    }
+
+    pub fn on_peer_connected(&mut self, peer_id: &str) -> Result<(), AdminSharedError> {
+        self.on_peer_reachable(peer_id);
+
+        // the circuits with relayed routes through the peer are registered with it; the members
+        // reached through it agree on a protocol once the relay has registered them
+        #[cfg(feature = "circuit-relay")]
+        {
+            for (circuit_id, members) in self.splinter_state.relayed_circuits(peer_id)? {
+                self.send_relay_registration(peer_id, &circuit_id, members, false)?;
+            }
+        }
 
         // Ignore own admin service
         if peer_id == admin_service_id(self.node_id()) {
@@ -1159,6 +1471,17 @@ impl AdminServiceShared {
         if protocol == 0 {
             // if no agreed protocol, remove all peer refs for proposals
             for pending_payload in ready {
+                #[cfg(feature = "circuit-relay")]
+                {
+                    if let PayloadType::Circuit(payload) = &pending_payload.payload_type {
+                        self.remove_circuit_route(
+                            payload
+                                .get_circuit_create_request()
+                                .get_circuit()
+                                .get_circuit_id(),
+                        );
+                    }
+                }
                 for peer in pending_payload.members {
                     self.remove_peer_ref(&peer);
                 }
@@ -1208,7 +1531,10 @@ impl AdminServiceShared {
         &mut self,
         circuit_id: &str,
     ) -> Result<Option<CircuitProposal>, AdminSharedError> {
-        Ok(self.open_proposals.remove_proposal(circuit_id)?)
+        let proposal = self.open_proposals.remove_proposal(circuit_id)?;
+        #[cfg(feature = "circuit-relay")]
+        self.remove_circuit_route(circuit_id);
+        Ok(proposal)
     }
 
     pub fn add_proposal(
@@ -1484,6 +1810,15 @@ impl AdminServiceShared {
             )));
         }
 
+        if circuit.get_routes() == Circuit_RouteType::RELAYED_ROUTE {
+            #[cfg(feature = "circuit-relay")]
+            self.validate_relay_node(circuit.get_relay_node(), &members, &all_endpoints)?;
+            #[cfg(not(feature = "circuit-relay"))]
+            return Err(AdminSharedError::ValidationFailed(
+                "Relayed routes are not supported".to_string(),
+            ));
+        }
+
         if circuit.get_roster().is_empty() {
             return Err(AdminSharedError::ValidationFailed(
                 "The circuit must have services".to_string(),
@@ -1547,6 +1882,51 @@ impl AdminServiceShared {
         Ok(())
     }
 
+    #[cfg(feature = "circuit-relay")]
+    fn validate_relay_node(
+        &self,
+        relay_node: &SplinterNode,
+        members: &[String],
+        member_endpoints: &[String],
+    ) -> Result<(), AdminSharedError> {
+        if relay_node.get_node_id().is_empty() {
+            return Err(AdminSharedError::ValidationFailed(
+                "A circuit with relayed routes must have a relay node".to_string(),
+            ));
+        }
+
+        // The relay only forwards messages, so it must not be able to participate in the circuit
+        if members
+            .iter()
+            .any(|member| member == relay_node.get_node_id())
+        {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Relay node cannot be a member of the circuit: {}",
+                relay_node.get_node_id()
+            )));
+        }
+
+        let endpoints = relay_node.get_endpoints();
+        if endpoints.is_empty() {
+            return Err(AdminSharedError::ValidationFailed(
+                "Relay node endpoints cannot be empty".to_string(),
+            ));
+        } else if endpoints.iter().any(|endpoint| endpoint.is_empty()) {
+            return Err(AdminSharedError::ValidationFailed(
+                "Relay node cannot have an empty endpoint".to_string(),
+            ));
+        } else if endpoints
+            .iter()
+            .any(|endpoint| member_endpoints.contains(endpoint))
+        {
+            return Err(AdminSharedError::ValidationFailed(
+                "Relay node endpoints cannot be member endpoints".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
     #[cfg(feature = "service-arg-validation")]
     fn validate_service_args(&self, service: &SplinterService) -> Result<(), AdminSharedError> {
         if let Some(validator) = self.service_arg_validators.get(service.get_service_type()) {
@@ -1752,6 +2132,30 @@ impl AdminServiceShared {
         self.splinter_state.nodes().map_err(AdminSharedError::from)
     }
 
+    /// Records the route to the members of a stored circuit proposal, so that admin messages to
+    /// them are sent through its relay node, if any.
+    #[cfg(feature = "circuit-relay")]
+    pub fn add_proposal_route(
+        &self,
+        proposal: &messages::CircuitProposal,
+    ) -> Result<(), AdminSharedError> {
+        let relay_node = match &proposal.circuit.routes {
+            messages::RouteType::Any => None,
+            messages::RouteType::Relayed { relay_node } => Some(relay_node.node_id.to_string()),
+        };
+        self.splinter_state.add_circuit_route(
+            proposal.circuit_id.to_string(),
+            proposal
+                .circuit
+                .members
+                .iter()
+                .map(|member| member.node_id.to_string())
+                .collect(),
+            relay_node,
+        )?;
+        Ok(())
+    }
+
     fn update_splinter_state(&mut self, circuit: &Circuit) -> Result<(), AdminSharedError> {
         let members: Vec<StateNode> = circuit
             .get_members()
@@ -1817,6 +2221,17 @@ impl AdminServiceShared {
 
         let routes = match circuit.get_routes() {
             Circuit_RouteType::ANY_ROUTE => RouteType::Any,
+            #[cfg(feature = "circuit-relay")]
+            Circuit_RouteType::RELAYED_ROUTE => RouteType::Relayed {
+                relay_node: circuit.get_relay_node().get_node_id().to_string(),
+            },
+            // This should never happen, as relayed routes fail validation
+            #[cfg(not(feature = "circuit-relay"))]
+            Circuit_RouteType::RELAYED_ROUTE => {
+                return Err(AdminSharedError::CommitError(
+                    "Relayed routes are not supported".to_string(),
+                ))
+            }
             // This should never happen
             Circuit_RouteType::UNSET_ROUTE_TYPE => {
                 return Err(AdminSharedError::CommitError(
@@ -1849,6 +2264,33 @@ impl AdminServiceShared {
                 .add_node(member.id().to_string(), member)?;
         }
 
+        #[cfg(feature = "circuit-relay")]
+        {
+            if circuit.get_routes() == Circuit_RouteType::RELAYED_ROUTE {
+                let relay_node = circuit.get_relay_node();
+                self.splinter_state.add_node(
+                    relay_node.get_node_id().to_string(),
+                    StateNode::new(
+                        relay_node.get_node_id().to_string(),
+                        relay_node.get_endpoints().to_vec(),
+                    ),
+                )?;
+
+                // Circuit messages to other members are sent through the relay
+                match self.peer_connector.add_peer_ref(
+                    relay_node.get_node_id().to_string(),
+                    relay_node.get_endpoints().to_vec(),
+                ) {
+                    Ok(peer_ref) => self.add_peer_ref(peer_ref),
+                    Err(err) => warn!(
+                        "Unable to peer with relay node {}: {}",
+                        relay_node.get_node_id(),
+                        err
+                    ),
+                }
+            }
+        }
+
         self.splinter_state
             .add_circuit(new_circuit.id().to_string(), new_circuit)?;
 
@@ -2089,6 +2531,247 @@ mod tests {
         shutdown(mesh, cm, pm);
     }
 
+    /// Test that a proposed circuit with relayed routes only waits on the relay node to be peered,
+    /// and that the members are sent protocol requests through the relay once it is connected.
+    #[cfg(feature = "circuit-relay")]
+    #[test]
+    fn test_relayed_protocol_agreement() {
+        let mut transport = InprocTransport::default();
+        let mut orchestrator_transport = transport.clone();
+
+        let mut relay_listener = transport
+            .listen("inproc://otherplace:8000")
+            .expect("Unable to get listener");
+        let _orchestator_listener = transport
+            .listen("inproc://orchestator")
+            .expect("Unable to get listener");
+
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(Some(transport));
+        let orchestrator_connection = orchestrator_transport
+            .connect("inproc://orchestator")
+            .expect("failed to create connection");
+        let (orchestrator, _) = ServiceOrchestrator::new(vec![], orchestrator_connection, 1, 1, 1)
+            .expect("failed to create orchestrator");
+        let state = setup_splinter_state();
+        let mut shared = AdminServiceShared::new(
+            "my_peer_id".into(),
+            Arc::new(Mutex::new(orchestrator)),
+            #[cfg(feature = "service-arg-validation")]
+            HashMap::new(),
+            peer_connector,
+            state.clone(),
+            Box::new(HashVerifier),
+            Box::new(MockAdminKeyVerifier::default()),
+            Box::new(AllowAllKeyPermissionManager),
+            "memory",
+            STATE_DIR,
+        )
+        .unwrap();
+
+        let service_sender = MockServiceNetworkSender::new();
+        shared.set_network_sender(Some(Box::new(service_sender.clone())));
+
+        let mut circuit = admin::Circuit::new();
+        circuit.set_circuit_id("01234-ABCDE".into());
+        circuit.set_authorization_type(admin::Circuit_AuthorizationType::TRUST_AUTHORIZATION);
+        circuit.set_persistence(admin::Circuit_PersistenceType::ANY_PERSISTENCE);
+        circuit.set_routes(admin::Circuit_RouteType::RELAYED_ROUTE);
+        circuit.set_durability(admin::Circuit_DurabilityType::NO_DURABILITY);
+        circuit.set_circuit_management_type("test app auth handler".into());
+        circuit.set_comments("test circuit".into());
+
+        // the remote node is not reachable; it is only connected to the relay
+        circuit.set_members(protobuf::RepeatedField::from_vec(vec![
+            splinter_node("my_peer_id", &["inproc://myplace:8000".to_string()]),
+            splinter_node("remote-node", &["inproc://remoteplace:8000".to_string()]),
+        ]));
+        circuit.set_relay_node(splinter_node(
+            "other-node",
+            &["inproc://otherplace:8000".to_string()],
+        ));
+        circuit.set_roster(protobuf::RepeatedField::from_vec(vec![
+            splinter_service("0123", "sabre"),
+            splinter_service("ABCD", "sabre"),
+        ]));
+
+        let mut request = admin::CircuitCreateRequest::new();
+        request.set_circuit(circuit);
+
+        let mut header = admin::CircuitManagementPayload_Header::new();
+        header.set_action(admin::CircuitManagementPayload_Action::CIRCUIT_CREATE_REQUEST);
+
+        let mut payload = admin::CircuitManagementPayload::new();
+
+        payload.set_signature(Vec::new());
+        payload.set_header(protobuf::Message::write_to_bytes(&header).unwrap());
+        payload.set_circuit_create_request(request);
+
+        // start up thread for the relay node
+        std::thread::spawn(move || {
+            let mesh = Mesh::new(2, 2);
+            let conn = relay_listener.accept().unwrap();
+            mesh.add(conn, "my_peer_id".to_string()).unwrap();
+
+            handle_auth(&mesh, "my_peer_id", "other-node");
+
+            mesh.shutdown_signaler().shutdown();
+        });
+
+        shared
+            .propose_circuit(payload, "test".to_string())
+            .expect("Proposal not accepted");
+
+        // Only the relay is peered with, and admin messages to the member are sent through it
+        assert!(shared.peer_refs.contains_key("other-node"));
+        assert!(!shared.peer_refs.contains_key("remote-node"));
+        assert_eq!(
+            state
+                .relay_route("remote-node")
+                .expect("Unable to get relay route"),
+            Some("other-node".to_string())
+        );
+        assert_eq!(1, shared.unpeered_payloads.len());
+        assert_eq!(
+            vec!["other-node".to_string()],
+            shared.unpeered_payloads[0].unpeered_ids
+        );
+
+        // Set the relay to peered
+        shared
+            .on_peer_connected("other-node")
+            .expect("Unable to set peer to peered");
+
+        // We're fully peered, and the circuit was registered with the relay, but the member is
+        // not sent a protocol request until the relay has registered it
+        assert_eq!(1, shared.pending_protocol_payloads.len());
+        assert_eq!(0, shared.pending_circuit_payloads.len());
+        assert!(service_sender
+            .sent
+            .lock()
+            .expect("sent lock poisoned")
+            .iter()
+            .any(|(recipient, message)| {
+                let msg: admin::AdminMessage = protobuf::parse_from_bytes(message).unwrap();
+                recipient == "admin::other-node"
+                    && msg.get_message_type()
+                        == admin::AdminMessage_Type::RELAY_REGISTRATION_REQUEST
+                    && msg.get_relay_registration_request().get_circuit_id() == "01234-ABCDE"
+                    && msg.get_relay_registration_request().get_members().len() == 2
+            }));
+        assert!(!service_sender
+            .sent
+            .lock()
+            .expect("sent lock poisoned")
+            .iter()
+            .any(|(recipient, _)| recipient == "admin::remote-node"));
+
+        let mut response = admin::RelayRegistrationResponse::new();
+        response.set_circuit_id("01234-ABCDE".into());
+        response.set_accepted(true);
+        shared
+            .on_relay_registered("admin::other-node", &response)
+            .expect("Unable to handle relay registration");
+
+        // the member was sent a protocol request through the relay
+        assert!(service_sender
+            .sent
+            .lock()
+            .expect("sent lock poisoned")
+            .iter()
+            .any(|(recipient, _)| recipient == "admin::remote-node"));
+
+        shared
+            .on_protocol_agreement("admin::remote-node", 1)
+            .expect("received unexpected error");
+
+        // We're agreed on protocol with the member, so the pending payload is now available
+        assert_eq!(0, shared.pending_protocol_payloads.len());
+        assert_eq!(1, shared.pending_circuit_payloads.len());
+        shutdown(mesh, cm, pm);
+    }
+
+    /// Test that a relay node registers a circuit with relayed routes from one of its members,
+    /// and replies that the registration was accepted, and that it does not accept a
+    /// registration from a node that is not a member of the circuit.
+    #[cfg(feature = "circuit-relay")]
+    #[test]
+    fn test_handle_relay_registration() {
+        let mut transport = InprocTransport::default();
+        let mut orchestrator_transport = transport.clone();
+
+        let _orchestator_listener = transport
+            .listen("inproc://orchestator")
+            .expect("Unable to get listener");
+
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(Some(transport));
+        let orchestrator_connection = orchestrator_transport
+            .connect("inproc://orchestator")
+            .expect("failed to create connection");
+        let (orchestrator, _) = ServiceOrchestrator::new(vec![], orchestrator_connection, 1, 1, 1)
+            .expect("failed to create orchestrator");
+        let state = setup_splinter_state();
+        let mut shared = AdminServiceShared::new(
+            "other-node".into(),
+            Arc::new(Mutex::new(orchestrator)),
+            #[cfg(feature = "service-arg-validation")]
+            HashMap::new(),
+            peer_connector,
+            state.clone(),
+            Box::new(HashVerifier),
+            Box::new(MockAdminKeyVerifier::default()),
+            Box::new(AllowAllKeyPermissionManager),
+            "memory",
+            STATE_DIR,
+        )
+        .unwrap();
+
+        let service_sender = MockServiceNetworkSender::new();
+        shared.set_network_sender(Some(Box::new(service_sender.clone())));
+
+        let mut request = admin::RelayRegistrationRequest::new();
+        request.set_circuit_id("01234-ABCDE".into());
+        request.set_members(protobuf::RepeatedField::from_vec(vec![
+            "my_peer_id".into(),
+            "remote-node".into(),
+        ]));
+
+        shared
+            .handle_relay_registration("admin::my_peer_id", &request)
+            .expect("Unable to handle relay registration");
+        shared
+            .handle_relay_registration("admin::unknown-node", &request)
+            .expect("Unable to handle relay registration");
+
+        assert!(state
+            .relays_admin_messages("my_peer_id", "remote-node")
+            .expect("Unable to check relay registrations"));
+        assert!(!state
+            .relays_admin_messages("unknown-node", "remote-node")
+            .expect("Unable to check relay registrations"));
+
+        let responses = service_sender
+            .sent
+            .lock()
+            .expect("sent lock poisoned")
+            .iter()
+            .map(|(recipient, message)| {
+                let msg: admin::AdminMessage = protobuf::parse_from_bytes(message).unwrap();
+                (
+                    recipient.to_string(),
+                    msg.get_relay_registration_response().get_accepted(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("admin::my_peer_id".to_string(), true),
+                ("admin::unknown-node".to_string(), false)
+            ],
+            responses
+        );
+        shutdown(mesh, cm, pm);
+    }
+
     /// Test that the CircuitManagementPayload message is dropped, if a node fails to match
     /// protocol versions
     #[test]
@@ -2965,6 +3648,54 @@ mod tests {
         shutdown(mesh, cm, pm);
     }
 
+    #[cfg(feature = "circuit-relay")]
+    #[test]
+    // test that a circuit with relayed routes is valid only if its relay node is set and is not a
+    // member of the circuit
+    fn test_validate_circuit_relay_node() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
+
+        let admin_shared = AdminServiceShared::new(
+            "node_a".into(),
+            Arc::new(Mutex::new(orchestrator)),
+            #[cfg(feature = "service-arg-validation")]
+            HashMap::new(),
+            peer_connector,
+            state,
+            Box::new(HashVerifier),
+            Box::new(MockAdminKeyVerifier::default()),
+            Box::new(AllowAllKeyPermissionManager),
+            "memory",
+            STATE_DIR,
+        )
+        .unwrap();
+        let mut circuit = setup_test_circuit();
+        circuit.set_routes(Circuit_RouteType::RELAYED_ROUTE);
+
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid because the relay node is unset");
+        }
+
+        let mut relay_node = SplinterNode::new();
+        relay_node.set_node_id("node_b".to_string());
+        relay_node.set_endpoints(vec!["test://endpoint_relay:0".to_string()].into());
+        circuit.set_relay_node(relay_node.clone());
+
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid because the relay node is a member");
+        }
+
+        relay_node.set_node_id("node_relay".to_string());
+        circuit.set_relay_node(relay_node);
+
+        if let Err(err) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been valid: {}", err);
+        }
+        shutdown(mesh, cm, pm);
+    }
+
     #[test]
     // test that if a circuit does not have circuit_management_type set an error is returned
     fn test_validate_circuit_no_management_type() {
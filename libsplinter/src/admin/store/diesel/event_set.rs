@@ -0,0 +1,442 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A database-backed set of admin service events, used as the admin service's event mailbox.
+
+use std::ops::Bound;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
+
+use crate::admin::service::messages::AdminServiceEvent;
+use crate::admin::service::EventEntry;
+use crate::admin::store::error::AdminServiceStoreError;
+use crate::storage::sets::{DurableOrderedSet, DurableRange, DurableSet, DurableSetError};
+
+use super::models::AdminServiceEventModel;
+use super::operations::add_event::AdminServiceStoreAddEventOperation as _;
+use super::operations::count_events::AdminServiceStoreCountEventsOperation as _;
+use super::operations::fetch_event::AdminServiceStoreFetchEventOperation as _;
+use super::operations::list_events::AdminServiceStoreListEventsOperation as _;
+use super::operations::remove_event::AdminServiceStoreRemoveEventOperation as _;
+use super::operations::AdminServiceStoreOperations;
+
+/// A database-backed `DurableOrderedSet` of admin service events, indexed by the time at which
+/// each event occurred.
+///
+/// Event times are stored with nanosecond precision, so no two events in the set may occur at the
+/// same nanosecond.
+pub struct DieselAdminServiceEventSet<C: diesel::Connection + 'static> {
+    connection_pool: Pool<ConnectionManager<C>>,
+}
+
+impl<C: diesel::Connection> DieselAdminServiceEventSet<C> {
+    /// Creates a new `DieselAdminServiceEventSet`.
+    ///
+    /// # Arguments
+    ///
+    ///  * `connection_pool`: connection pool for the database
+    pub fn new(connection_pool: Pool<ConnectionManager<C>>) -> Self {
+        DieselAdminServiceEventSet { connection_pool }
+    }
+
+    fn connection(&self) -> Result<PooledConnection<ConnectionManager<C>>, DurableSetError> {
+        self.connection_pool.get().map_err(|err| {
+            DurableSetError::with_source("Failed to get database connection", Box::new(err))
+        })
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl Clone for DieselAdminServiceEventSet<diesel::pg::PgConnection> {
+    fn clone(&self) -> Self {
+        Self {
+            connection_pool: self.connection_pool.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl Clone for DieselAdminServiceEventSet<diesel::sqlite::SqliteConnection> {
+    fn clone(&self) -> Self {
+        Self {
+            connection_pool: self.connection_pool.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl DurableSet for DieselAdminServiceEventSet<diesel::pg::PgConnection> {
+    type Item = EventEntry;
+
+    fn add(&mut self, item: Self::Item) -> Result<(), DurableSetError> {
+        let event = to_event_model(&item)?;
+        AdminServiceStoreOperations::new(&*self.connection()?)
+            .add_event(event)
+            .map_err(set_error)
+    }
+
+    fn remove(&mut self, item: &Self::Item) -> Result<Option<Self::Item>, DurableSetError> {
+        let event_time = to_event_time(item.timestamp())?;
+        AdminServiceStoreOperations::new(&*self.connection()?)
+            .remove_event(event_time)
+            .map_err(set_error)?
+            .map(to_event_entry)
+            .transpose()
+    }
+
+    fn contains(&self, item: &Self::Item) -> Result<bool, DurableSetError> {
+        self.contains_by_index(item.timestamp())
+    }
+
+    fn iter<'a>(&'a self) -> Result<Box<dyn Iterator<Item = Self::Item> + 'a>, DurableSetError> {
+        self.range_iter((..).into())
+    }
+
+    fn len(&self) -> Result<u64, DurableSetError> {
+        AdminServiceStoreOperations::new(&*self.connection()?)
+            .count_events()
+            .map_err(set_error)
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl DurableOrderedSet<EventEntry, SystemTime>
+    for DieselAdminServiceEventSet<diesel::pg::PgConnection>
+{
+    fn get_by_index(
+        &self,
+        index_value: &SystemTime,
+    ) -> Result<Option<EventEntry>, DurableSetError> {
+        let event_time = to_event_time(index_value)?;
+        AdminServiceStoreOperations::new(&*self.connection()?)
+            .fetch_event(event_time)
+            .map_err(set_error)?
+            .map(to_event_entry)
+            .transpose()
+    }
+
+    fn contains_by_index(&self, index_value: &SystemTime) -> Result<bool, DurableSetError> {
+        Ok(self.get_by_index(index_value)?.is_some())
+    }
+
+    fn range_iter<'a>(
+        &'a self,
+        range: DurableRange<&SystemTime>,
+    ) -> Result<Box<dyn Iterator<Item = EventEntry> + 'a>, DurableSetError> {
+        let start = match range.start {
+            Bound::Included(start) => to_event_time(start)?,
+            Bound::Excluded(start) => to_event_time(start)?.saturating_add(1),
+            Bound::Unbounded => i64::MIN,
+        };
+        let end = match range.end {
+            Bound::Included(end) => to_event_time(end)?,
+            Bound::Excluded(end) => to_event_time(end)?.saturating_sub(1),
+            Bound::Unbounded => i64::MAX,
+        };
+
+        let events = AdminServiceStoreOperations::new(&*self.connection()?)
+            .list_events(start, end)
+            .map_err(set_error)?
+            .into_iter()
+            .map(to_event_entry)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Box::new(events.into_iter()))
+    }
+
+    fn first(&self) -> Result<Option<EventEntry>, DurableSetError> {
+        AdminServiceStoreOperations::new(&*self.connection()?)
+            .fetch_first_event()
+            .map_err(set_error)?
+            .map(to_event_entry)
+            .transpose()
+    }
+
+    fn last(&self) -> Result<Option<EventEntry>, DurableSetError> {
+        AdminServiceStoreOperations::new(&*self.connection()?)
+            .fetch_last_event()
+            .map_err(set_error)?
+            .map(to_event_entry)
+            .transpose()
+    }
+
+    fn clone_boxed_ordered_set(&self) -> Box<dyn DurableOrderedSet<EventEntry, SystemTime>> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl DurableSet for DieselAdminServiceEventSet<diesel::sqlite::SqliteConnection> {
+    type Item = EventEntry;
+
+    fn add(&mut self, item: Self::Item) -> Result<(), DurableSetError> {
+        let event = to_event_model(&item)?;
+        AdminServiceStoreOperations::new(&*self.connection()?)
+            .add_event(event)
+            .map_err(set_error)
+    }
+
+    fn remove(&mut self, item: &Self::Item) -> Result<Option<Self::Item>, DurableSetError> {
+        let event_time = to_event_time(item.timestamp())?;
+        AdminServiceStoreOperations::new(&*self.connection()?)
+            .remove_event(event_time)
+            .map_err(set_error)?
+            .map(to_event_entry)
+            .transpose()
+    }
+
+    fn contains(&self, item: &Self::Item) -> Result<bool, DurableSetError> {
+        self.contains_by_index(item.timestamp())
+    }
+
+    fn iter<'a>(&'a self) -> Result<Box<dyn Iterator<Item = Self::Item> + 'a>, DurableSetError> {
+        self.range_iter((..).into())
+    }
+
+    fn len(&self) -> Result<u64, DurableSetError> {
+        AdminServiceStoreOperations::new(&*self.connection()?)
+            .count_events()
+            .map_err(set_error)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl DurableOrderedSet<EventEntry, SystemTime>
+    for DieselAdminServiceEventSet<diesel::sqlite::SqliteConnection>
+{
+    fn get_by_index(
+        &self,
+        index_value: &SystemTime,
+    ) -> Result<Option<EventEntry>, DurableSetError> {
+        let event_time = to_event_time(index_value)?;
+        AdminServiceStoreOperations::new(&*self.connection()?)
+            .fetch_event(event_time)
+            .map_err(set_error)?
+            .map(to_event_entry)
+            .transpose()
+    }
+
+    fn contains_by_index(&self, index_value: &SystemTime) -> Result<bool, DurableSetError> {
+        Ok(self.get_by_index(index_value)?.is_some())
+    }
+
+    fn range_iter<'a>(
+        &'a self,
+        range: DurableRange<&SystemTime>,
+    ) -> Result<Box<dyn Iterator<Item = EventEntry> + 'a>, DurableSetError> {
+        let start = match range.start {
+            Bound::Included(start) => to_event_time(start)?,
+            Bound::Excluded(start) => to_event_time(start)?.saturating_add(1),
+            Bound::Unbounded => i64::MIN,
+        };
+        let end = match range.end {
+            Bound::Included(end) => to_event_time(end)?,
+            Bound::Excluded(end) => to_event_time(end)?.saturating_sub(1),
+            Bound::Unbounded => i64::MAX,
+        };
+
+        let events = AdminServiceStoreOperations::new(&*self.connection()?)
+            .list_events(start, end)
+            .map_err(set_error)?
+            .into_iter()
+            .map(to_event_entry)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Box::new(events.into_iter()))
+    }
+
+    fn first(&self) -> Result<Option<EventEntry>, DurableSetError> {
+        AdminServiceStoreOperations::new(&*self.connection()?)
+            .fetch_first_event()
+            .map_err(set_error)?
+            .map(to_event_entry)
+            .transpose()
+    }
+
+    fn last(&self) -> Result<Option<EventEntry>, DurableSetError> {
+        AdminServiceStoreOperations::new(&*self.connection()?)
+            .fetch_last_event()
+            .map_err(set_error)?
+            .map(to_event_entry)
+            .transpose()
+    }
+
+    fn clone_boxed_ordered_set(&self) -> Box<dyn DurableOrderedSet<EventEntry, SystemTime>> {
+        Box::new(self.clone())
+    }
+}
+
+/// Converts an event time to the number of nanoseconds since the Unix epoch.
+fn to_event_time(time: &SystemTime) -> Result<i64, DurableSetError> {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as i64)
+        .map_err(|_| DurableSetError::new("Event time is earlier than the Unix epoch"))
+}
+
+fn to_event_model(entry: &EventEntry) -> Result<AdminServiceEventModel, DurableSetError> {
+    Ok(AdminServiceEventModel {
+        event_time: to_event_time(entry.timestamp())?,
+        event: serde_json::to_string(entry.event()).map_err(|err| {
+            DurableSetError::with_source("Failed to serialize admin service event", Box::new(err))
+        })?,
+    })
+}
+
+fn to_event_entry(model: AdminServiceEventModel) -> Result<EventEntry, DurableSetError> {
+    let event = serde_json::from_str::<AdminServiceEvent>(&model.event).map_err(|err| {
+        DurableSetError::with_source("Failed to deserialize admin service event", Box::new(err))
+    })?;
+    Ok(EventEntry::new(
+        UNIX_EPOCH + std::time::Duration::from_nanos(model.event_time as u64),
+        event,
+    ))
+}
+
+/// Converts a store error into a set error; the store error is not `Send`, so it is kept as
+/// context only.
+fn set_error(err: AdminServiceStoreError) -> DurableSetError {
+    DurableSetError::new(&err.to_string())
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use crate::admin::messages::{self, CircuitProposal, ProposalType};
+
+    use super::super::tests::create_connection_pool_and_migrate;
+
+    /// Verify that a SQLite-backed `DieselAdminServiceEventSet` correctly supports adding,
+    /// fetching and removing events.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Add some events, including one twice, and verify the length of the set.
+    /// 3. Verify that the events can be fetched by their timestamps.
+    /// 4. Verify that the first and last events are returned correctly.
+    /// 5. Remove an event and verify that it is no longer in the set.
+    #[test]
+    fn sqlite_add_fetch_remove() {
+        let mut set = DieselAdminServiceEventSet::new(create_connection_pool_and_migrate());
+        assert_eq!(set.first().expect("Failed to get first event"), None);
+
+        let event_1 = make_entry(1, "circuit_one");
+        let event_2 = make_entry(2, "circuit_two");
+        let event_3 = make_entry(3, "circuit_three");
+
+        set.add(event_2.clone()).expect("Failed to add event 2");
+        set.add(event_1.clone()).expect("Failed to add event 1");
+        set.add(event_3.clone()).expect("Failed to add event 3");
+        set.add(event_3.clone())
+            .expect("Failed to add event 3 again");
+        assert_eq!(set.len().expect("Failed to get length"), 3);
+
+        assert_eq!(
+            set.get_by_index(event_2.timestamp())
+                .expect("Failed to get event 2"),
+            Some(event_2.clone())
+        );
+        assert!(set.contains(&event_1).expect("Failed to check event 1"));
+        assert_eq!(
+            set.first().expect("Failed to get first event"),
+            Some(event_1.clone())
+        );
+        assert_eq!(
+            set.last().expect("Failed to get last event"),
+            Some(event_3.clone())
+        );
+
+        assert_eq!(
+            set.remove(&event_1).expect("Failed to remove event 1"),
+            Some(event_1.clone())
+        );
+        assert!(!set.contains(&event_1).expect("Failed to check event 1"));
+        assert_eq!(
+            set.remove(&event_1).expect("Failed to remove event 1"),
+            None
+        );
+        assert_eq!(set.len().expect("Failed to get length"), 2);
+    }
+
+    /// Verify that a SQLite-backed `DieselAdminServiceEventSet` returns events in timestamp
+    /// order, respecting the bounds of the requested range.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Add some events out of order.
+    /// 3. Verify that iterating the set returns all events in order.
+    /// 4. Verify that inclusive, exclusive and unbounded ranges return the expected events.
+    #[test]
+    fn sqlite_range_iter() {
+        let mut set = DieselAdminServiceEventSet::new(create_connection_pool_and_migrate());
+
+        let event_1 = make_entry(1, "circuit_one");
+        let event_2 = make_entry(2, "circuit_two");
+        let event_3 = make_entry(3, "circuit_three");
+
+        set.add(event_3.clone()).expect("Failed to add event 3");
+        set.add(event_1.clone()).expect("Failed to add event 1");
+        set.add(event_2.clone()).expect("Failed to add event 2");
+
+        assert_eq!(
+            set.iter().expect("Failed to iterate").collect::<Vec<_>>(),
+            vec![event_1.clone(), event_2.clone(), event_3.clone()]
+        );
+        assert_eq!(
+            set.range_iter((event_1.timestamp()..event_3.timestamp()).into())
+                .expect("Failed to iterate")
+                .collect::<Vec<_>>(),
+            vec![event_1.clone(), event_2.clone()]
+        );
+        assert_eq!(
+            set.range_iter((event_2.timestamp()..).into())
+                .expect("Failed to iterate")
+                .collect::<Vec<_>>(),
+            vec![event_2.clone(), event_3.clone()]
+        );
+        assert_eq!(
+            set.range_iter((..=event_2.timestamp()).into())
+                .expect("Failed to iterate")
+                .collect::<Vec<_>>(),
+            vec![event_1, event_2]
+        );
+    }
+
+    fn make_entry(seconds: u64, circuit_id: &str) -> EventEntry {
+        EventEntry::new(
+            UNIX_EPOCH + Duration::from_secs(seconds),
+            AdminServiceEvent::ProposalSubmitted(CircuitProposal {
+                proposal_type: ProposalType::Create,
+                circuit_id: circuit_id.into(),
+                circuit_hash: "not real hash for tests".into(),
+                circuit: messages::CreateCircuit {
+                    circuit_id: circuit_id.into(),
+                    roster: vec![],
+                    members: vec![],
+                    authorization_type: messages::AuthorizationType::Trust,
+                    persistence: messages::PersistenceType::Any,
+                    durability: messages::DurabilityType::NoDurability,
+                    routes: messages::RouteType::Any,
+                    circuit_management_type: "default".into(),
+                    application_metadata: vec![],
+                    comments: "mock circuit".into(),
+                },
+                votes: vec![],
+                requester: vec![],
+                requester_node_id: "another-node".into(),
+            }),
+        )
+    }
+}
@@ -0,0 +1,94 @@
+---- Copyright 2018-2020 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- -----------------------------------------------------------------------------
+
+-- Key the endpoints, allowed nodes and arguments of proposed and active circuits by circuit, so
+-- that the same node or service ID may be used by more than one circuit. Each table is rebuilt,
+-- keeping its rows; the endpoints of a proposed node are copied to each proposal that includes
+-- the node. Node endpoints are shared by all of the circuits a node is a member of.
+
+CREATE TABLE proposed_node_endpoint_new (
+    circuit_id                TEXT NOT NULL,
+    node_id                   TEXT NOT NULL,
+    endpoint                  TEXT NOT NULL,
+    PRIMARY KEY (circuit_id, node_id, endpoint),
+    FOREIGN KEY (circuit_id, node_id) REFERENCES proposed_node(circuit_id, node_id) ON DELETE CASCADE
+);
+INSERT INTO proposed_node_endpoint_new
+    SELECT DISTINCT proposed_node.circuit_id, proposed_node_endpoint.node_id,
+        proposed_node_endpoint.endpoint
+    FROM proposed_node_endpoint
+    JOIN proposed_node ON proposed_node.node_id = proposed_node_endpoint.node_id;
+DROP TABLE proposed_node_endpoint;
+ALTER TABLE proposed_node_endpoint_new RENAME TO proposed_node_endpoint;
+
+CREATE TABLE proposed_service_allowed_node_new (
+    circuit_id                TEXT NOT NULL,
+    service_id                TEXT NOT NULL,
+    allowed_node              TEXT NOT NULL,
+    PRIMARY KEY (circuit_id, service_id, allowed_node),
+    FOREIGN KEY (circuit_id, service_id) REFERENCES proposed_service(circuit_id, service_id) ON DELETE CASCADE
+);
+INSERT INTO proposed_service_allowed_node_new
+    SELECT circuit_id, service_id, allowed_node FROM proposed_service_allowed_node;
+DROP TABLE proposed_service_allowed_node;
+ALTER TABLE proposed_service_allowed_node_new RENAME TO proposed_service_allowed_node;
+
+CREATE TABLE proposed_service_argument_new (
+    circuit_id                TEXT NOT NULL,
+    service_id                TEXT NOT NULL,
+    key                       TEXT NOT NULL,
+    value                     TEXT NOT NULL,
+    PRIMARY KEY (circuit_id, service_id, key),
+    FOREIGN KEY (circuit_id, service_id) REFERENCES proposed_service(circuit_id, service_id) ON DELETE CASCADE
+);
+INSERT INTO proposed_service_argument_new
+    SELECT circuit_id, service_id, key, value FROM proposed_service_argument;
+DROP TABLE proposed_service_argument;
+ALTER TABLE proposed_service_argument_new RENAME TO proposed_service_argument;
+
+CREATE TABLE service_allowed_node_new (
+    circuit_id                TEXT NOT NULL,
+    service_id                TEXT NOT NULL,
+    allowed_node              TEXT NOT NULL,
+    PRIMARY KEY (circuit_id, service_id, allowed_node),
+    FOREIGN KEY (circuit_id, service_id) REFERENCES service(circuit_id, service_id) ON DELETE CASCADE
+);
+INSERT INTO service_allowed_node_new
+    SELECT circuit_id, service_id, allowed_node FROM service_allowed_node;
+DROP TABLE service_allowed_node;
+ALTER TABLE service_allowed_node_new RENAME TO service_allowed_node;
+
+CREATE TABLE service_argument_new (
+    circuit_id                TEXT NOT NULL,
+    service_id                TEXT NOT NULL,
+    key                       TEXT NOT NULL,
+    value                     TEXT NOT NULL,
+    PRIMARY KEY (circuit_id, service_id, key),
+    FOREIGN KEY (circuit_id, service_id) REFERENCES service(circuit_id, service_id) ON DELETE CASCADE
+);
+INSERT INTO service_argument_new
+    SELECT circuit_id, service_id, key, value FROM service_argument;
+DROP TABLE service_argument;
+ALTER TABLE service_argument_new RENAME TO service_argument;
+
+CREATE TABLE node_endpoint_new (
+    node_id                   TEXT NOT NULL,
+    endpoint                  TEXT NOT NULL,
+    PRIMARY KEY (node_id, endpoint)
+);
+INSERT INTO node_endpoint_new
+    SELECT node_id, endpoint FROM node_endpoint;
+DROP TABLE node_endpoint;
+ALTER TABLE node_endpoint_new RENAME TO node_endpoint;
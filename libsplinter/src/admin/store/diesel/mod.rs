@@ -16,11 +16,14 @@
 //! [`Diesel`](https://crates.io/crates/diesel).
 //!
 //! This module contains the [`DieselAdminServiceStore`], which provides an implementation of the
-//! [`AdminServiceStore`] trait.
+//! [`AdminServiceStore`] trait, and the [`DieselAdminServiceEventSet`], which stores the admin
+//! service's event mailbox.
 //!
 //! [`DieselAdminServiceStore`]: struct.DieselAdminServiceStore.html
 //! [`AdminServiceStore`]: ../trait.AdminServiceStore.html
+//! [`DieselAdminServiceEventSet`]: struct.DieselAdminServiceEventSet.html
 
+mod event_set;
 pub mod migrations;
 mod models;
 pub mod operations;
@@ -28,6 +31,30 @@ mod schema;
 
 use diesel::r2d2::{ConnectionManager, Pool};
 
+use super::{
+    error::AdminServiceStoreError, AdminServiceStore, Circuit, CircuitNode, CircuitPredicate,
+    CircuitProposal, Service, ServiceId,
+};
+
+use operations::add_circuit::AdminServiceStoreAddCircuitOperation as _;
+use operations::add_proposal::AdminServiceStoreAddProposalOperation as _;
+use operations::fetch_circuit::AdminServiceStoreFetchCircuitOperation as _;
+use operations::fetch_node::AdminServiceStoreFetchNodeOperation as _;
+use operations::fetch_proposal::AdminServiceStoreFetchProposalOperation as _;
+use operations::fetch_service::AdminServiceStoreFetchServiceOperation as _;
+use operations::list_circuits::AdminServiceStoreListCircuitsOperation as _;
+use operations::list_nodes::AdminServiceStoreListNodesOperation as _;
+use operations::list_proposals::AdminServiceStoreListProposalsOperation as _;
+use operations::list_services::AdminServiceStoreListServicesOperation as _;
+use operations::remove_circuit::AdminServiceStoreRemoveCircuitOperation as _;
+use operations::remove_proposal::AdminServiceStoreRemoveProposalOperation as _;
+use operations::update_circuit::AdminServiceStoreUpdateCircuitOperation as _;
+use operations::update_proposal::AdminServiceStoreUpdateProposalOperation as _;
+use operations::upgrade_proposal_to_circuit::AdminServiceStoreUpgradeProposalToCircuitOperation as _;
+use operations::AdminServiceStoreOperations;
+
+pub use event_set::DieselAdminServiceEventSet;
+
 /// A database-backed AdminServiceStore, powered by [`Diesel`](https://crates.io/crates/diesel).
 pub struct DieselAdminServiceStore<C: diesel::Connection + 'static> {
     connection_pool: Pool<ConnectionManager<C>>,
@@ -61,3 +88,505 @@ impl Clone for DieselAdminServiceStore<diesel::pg::PgConnection> {
         }
     }
 }
+
+#[cfg(feature = "postgres")]
+impl AdminServiceStore for DieselAdminServiceStore<diesel::pg::PgConnection> {
+    fn add_proposal(&self, proposal: CircuitProposal) -> Result<(), AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).add_proposal(proposal)
+    }
+
+    fn update_proposal(&self, proposal: CircuitProposal) -> Result<(), AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).update_proposal(proposal)
+    }
+
+    fn remove_proposal(&self, proposal_id: &str) -> Result<(), AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).remove_proposal(proposal_id)
+    }
+
+    fn fetch_proposal(
+        &self,
+        proposal_id: &str,
+    ) -> Result<Option<CircuitProposal>, AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).fetch_proposal(proposal_id)
+    }
+
+    fn list_proposals(
+        &self,
+        predicates: &[CircuitPredicate],
+    ) -> Result<Box<dyn ExactSizeIterator<Item = CircuitProposal>>, AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).list_proposals(predicates)
+    }
+
+    fn add_circuit(
+        &self,
+        circuit: Circuit,
+        nodes: Vec<CircuitNode>,
+    ) -> Result<(), AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).add_circuit(circuit, nodes)
+    }
+
+    fn update_circuit(&self, circuit: Circuit) -> Result<(), AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).update_circuit(circuit)
+    }
+
+    fn remove_circuit(&self, circuit_id: &str) -> Result<(), AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).remove_circuit(circuit_id)
+    }
+
+    fn fetch_circuit(&self, circuit_id: &str) -> Result<Option<Circuit>, AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).fetch_circuit(circuit_id)
+    }
+
+    fn list_circuits(
+        &self,
+        predicates: &[CircuitPredicate],
+    ) -> Result<Box<dyn ExactSizeIterator<Item = Circuit>>, AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).list_circuits(predicates)
+    }
+
+    fn upgrade_proposal_to_circuit(&self, circuit_id: &str) -> Result<(), AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?)
+            .upgrade_proposal_to_circuit(circuit_id)
+    }
+
+    fn fetch_node(&self, node_id: &str) -> Result<Option<CircuitNode>, AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).fetch_node(node_id)
+    }
+
+    fn list_nodes(
+        &self,
+    ) -> Result<Box<dyn ExactSizeIterator<Item = CircuitNode>>, AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).list_nodes()
+    }
+
+    fn fetch_service(
+        &self,
+        service_id: &ServiceId,
+    ) -> Result<Option<Service>, AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).fetch_service(service_id)
+    }
+
+    fn list_services(
+        &self,
+        circuit_id: &str,
+    ) -> Result<Box<dyn ExactSizeIterator<Item = Service>>, AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).list_services(circuit_id)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl AdminServiceStore for DieselAdminServiceStore<diesel::sqlite::SqliteConnection> {
+    fn add_proposal(&self, proposal: CircuitProposal) -> Result<(), AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).add_proposal(proposal)
+    }
+
+    fn update_proposal(&self, proposal: CircuitProposal) -> Result<(), AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).update_proposal(proposal)
+    }
+
+    fn remove_proposal(&self, proposal_id: &str) -> Result<(), AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).remove_proposal(proposal_id)
+    }
+
+    fn fetch_proposal(
+        &self,
+        proposal_id: &str,
+    ) -> Result<Option<CircuitProposal>, AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).fetch_proposal(proposal_id)
+    }
+
+    fn list_proposals(
+        &self,
+        predicates: &[CircuitPredicate],
+    ) -> Result<Box<dyn ExactSizeIterator<Item = CircuitProposal>>, AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).list_proposals(predicates)
+    }
+
+    fn add_circuit(
+        &self,
+        circuit: Circuit,
+        nodes: Vec<CircuitNode>,
+    ) -> Result<(), AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).add_circuit(circuit, nodes)
+    }
+
+    fn update_circuit(&self, circuit: Circuit) -> Result<(), AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).update_circuit(circuit)
+    }
+
+    fn remove_circuit(&self, circuit_id: &str) -> Result<(), AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).remove_circuit(circuit_id)
+    }
+
+    fn fetch_circuit(&self, circuit_id: &str) -> Result<Option<Circuit>, AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).fetch_circuit(circuit_id)
+    }
+
+    fn list_circuits(
+        &self,
+        predicates: &[CircuitPredicate],
+    ) -> Result<Box<dyn ExactSizeIterator<Item = Circuit>>, AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).list_circuits(predicates)
+    }
+
+    fn upgrade_proposal_to_circuit(&self, circuit_id: &str) -> Result<(), AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?)
+            .upgrade_proposal_to_circuit(circuit_id)
+    }
+
+    fn fetch_node(&self, node_id: &str) -> Result<Option<CircuitNode>, AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).fetch_node(node_id)
+    }
+
+    fn list_nodes(
+        &self,
+    ) -> Result<Box<dyn ExactSizeIterator<Item = CircuitNode>>, AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).list_nodes()
+    }
+
+    fn fetch_service(
+        &self,
+        service_id: &ServiceId,
+    ) -> Result<Option<Service>, AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).fetch_service(service_id)
+    }
+
+    fn list_services(
+        &self,
+        circuit_id: &str,
+    ) -> Result<Box<dyn ExactSizeIterator<Item = Service>>, AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).list_services(circuit_id)
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+pub mod tests {
+    use super::*;
+
+    use diesel::{
+        r2d2::{ConnectionManager, Pool},
+        sqlite::SqliteConnection,
+    };
+
+    use crate::admin::store::builders::{
+        CircuitBuilder, CircuitNodeBuilder, CircuitProposalBuilder, ProposedCircuitBuilder,
+        ProposedNodeBuilder, ProposedServiceBuilder, ServiceBuilder,
+    };
+    #[cfg(feature = "circuit-relay")]
+    use crate::admin::store::RouteType;
+    use crate::admin::store::{ProposalType, Vote, VoteRecord};
+
+    use super::migrations::run_sqlite_migrations;
+
+    /// Verify that a SQLite-backed `DieselAdminServiceStore` correctly supports adding, fetching,
+    /// updating, listing and removing proposals.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Add a proposal and verify that adding it again fails.
+    /// 3. Verify that the proposal can be fetched and listed, with and without predicates.
+    /// 4. Update the proposal with a vote and verify that the vote is returned.
+    /// 5. Remove the proposal and verify that it is no longer returned, and that removing or
+    ///    updating it again fails.
+    #[test]
+    fn sqlite_proposals() {
+        let store = DieselAdminServiceStore::new(create_connection_pool_and_migrate());
+        let proposal = create_proposal();
+
+        store
+            .add_proposal(proposal.clone())
+            .expect("Failed to add proposal");
+        assert!(store.add_proposal(proposal.clone()).is_err());
+
+        assert_eq!(
+            store
+                .fetch_proposal("WBKLF-BBBBB")
+                .expect("Failed to fetch proposal"),
+            Some(proposal.clone())
+        );
+        assert_eq!(
+            store
+                .list_proposals(&[CircuitPredicate::MembersInclude(vec![
+                    "acme-node-000".into()
+                ])])
+                .expect("Failed to list proposals")
+                .collect::<Vec<_>>(),
+            vec![proposal.clone()]
+        );
+        assert_eq!(
+            store
+                .list_proposals(&[CircuitPredicate::ManagmentTypeEq("other".into())])
+                .expect("Failed to list proposals")
+                .len(),
+            0
+        );
+
+        let mut voted_proposal = proposal.clone();
+        voted_proposal.add_vote(VoteRecord {
+            public_key: vec![4, 5, 6],
+            vote: Vote::Accept,
+            voter_node_id: "bubba-node-000".into(),
+        });
+        store
+            .update_proposal(voted_proposal.clone())
+            .expect("Failed to update proposal");
+        assert_eq!(
+            store
+                .fetch_proposal("WBKLF-BBBBB")
+                .expect("Failed to fetch proposal"),
+            Some(voted_proposal)
+        );
+
+        store
+            .remove_proposal("WBKLF-BBBBB")
+            .expect("Failed to remove proposal");
+        assert_eq!(
+            store
+                .fetch_proposal("WBKLF-BBBBB")
+                .expect("Failed to fetch proposal"),
+            None
+        );
+        assert!(store.remove_proposal("WBKLF-BBBBB").is_err());
+        assert!(store.update_proposal(proposal).is_err());
+    }
+
+    /// Verify that a SQLite-backed `DieselAdminServiceStore` correctly supports adding, fetching,
+    /// listing and removing circuits, along with their nodes and services.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Add a circuit with a member node and verify that adding it again fails.
+    /// 3. Verify that the circuit, its node and its services can be fetched and listed.
+    /// 4. Remove the circuit and verify that it and its services are no longer returned, while
+    ///    the node is kept.
+    #[test]
+    fn sqlite_circuits() {
+        let store = DieselAdminServiceStore::new(create_connection_pool_and_migrate());
+        let (circuit, node) = create_circuit();
+
+        store
+            .add_circuit(circuit.clone(), vec![node.clone()])
+            .expect("Failed to add circuit");
+        assert!(store.add_circuit(circuit.clone(), vec![]).is_err());
+
+        assert_eq!(
+            store
+                .fetch_circuit("WBKLF-DDDDD")
+                .expect("Failed to fetch circuit"),
+            Some(circuit.clone())
+        );
+        assert_eq!(
+            store
+                .list_circuits(&[CircuitPredicate::ManagmentTypeEq("test".into())])
+                .expect("Failed to list circuits")
+                .collect::<Vec<_>>(),
+            vec![circuit.clone()]
+        );
+        assert_eq!(
+            store
+                .fetch_node("new-node-000")
+                .expect("Failed to fetch node"),
+            Some(node.clone())
+        );
+        assert_eq!(
+            store
+                .list_nodes()
+                .expect("Failed to list nodes")
+                .collect::<Vec<_>>(),
+            vec![node]
+        );
+
+        let service_id = ServiceId::new("WBKLF-DDDDD".into(), "a001".into());
+        assert_eq!(
+            store
+                .fetch_service(&service_id)
+                .expect("Failed to fetch service"),
+            Some(circuit.roster[1].clone())
+        );
+        assert_eq!(
+            store
+                .list_services("WBKLF-DDDDD")
+                .expect("Failed to list services")
+                .collect::<Vec<_>>(),
+            circuit.roster
+        );
+
+        store
+            .remove_circuit("WBKLF-DDDDD")
+            .expect("Failed to remove circuit");
+        assert_eq!(
+            store
+                .fetch_circuit("WBKLF-DDDDD")
+                .expect("Failed to fetch circuit"),
+            None
+        );
+        assert_eq!(
+            store
+                .fetch_service(&service_id)
+                .expect("Failed to fetch service"),
+            None
+        );
+        assert!(store
+            .fetch_node("new-node-000")
+            .expect("Failed to fetch node")
+            .is_some());
+        assert!(store.remove_circuit("WBKLF-DDDDD").is_err());
+    }
+
+    /// Verify that a circuit with relayed routes keeps its relay node when stored.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Add a circuit with relayed routes.
+    /// 3. Verify that the fetched circuit has the same relay node.
+    #[cfg(feature = "circuit-relay")]
+    #[test]
+    fn sqlite_relayed_circuit() {
+        let store = DieselAdminServiceStore::new(create_connection_pool_and_migrate());
+        let (mut circuit, node) = create_circuit();
+        circuit.routes = RouteType::Relayed {
+            relay_node: "relay-node-000".into(),
+        };
+
+        store
+            .add_circuit(circuit.clone(), vec![node])
+            .expect("Failed to add circuit");
+
+        let fetched = store
+            .fetch_circuit("WBKLF-DDDDD")
+            .expect("Failed to fetch circuit")
+            .expect("Circuit was not stored");
+        assert_eq!(
+            fetched.routes,
+            RouteType::Relayed {
+                relay_node: "relay-node-000".into()
+            }
+        );
+        assert_eq!(fetched, circuit);
+    }
+
+    /// Verify that a SQLite-backed `DieselAdminServiceStore` correctly upgrades a proposal to a
+    /// circuit.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Add a proposal and upgrade it to a circuit.
+    /// 3. Verify that the proposal was removed and that the circuit, its nodes and its services
+    ///    were added.
+    /// 4. Verify that upgrading the proposal again fails.
+    #[test]
+    fn sqlite_upgrade_proposal_to_circuit() {
+        let store = DieselAdminServiceStore::new(create_connection_pool_and_migrate());
+
+        store
+            .add_proposal(create_proposal())
+            .expect("Failed to add proposal");
+        store
+            .upgrade_proposal_to_circuit("WBKLF-BBBBB")
+            .expect("Failed to upgrade proposal");
+
+        assert_eq!(
+            store
+                .list_proposals(&[])
+                .expect("Failed to list proposals")
+                .len(),
+            0
+        );
+        let circuit = store
+            .fetch_circuit("WBKLF-BBBBB")
+            .expect("Failed to fetch circuit")
+            .expect("Circuit was not added");
+        assert_eq!(circuit.members, vec!["acme-node-000", "bubba-node-000"]);
+        assert_eq!(store.list_nodes().expect("Failed to list nodes").len(), 2);
+        assert!(store
+            .fetch_service(&ServiceId::new("WBKLF-BBBBB".into(), "a000".into()))
+            .expect("Failed to fetch service")
+            .is_some());
+
+        assert!(store.upgrade_proposal_to_circuit("WBKLF-BBBBB").is_err());
+    }
+
+    fn create_proposal() -> CircuitProposal {
+        CircuitProposalBuilder::default()
+            .with_proposal_type(&ProposalType::Create)
+            .with_circuit_id("WBKLF-BBBBB")
+            .with_circuit_hash("7ddc426972710adc0b2ecd49e89a9dd805fb9206bf516079724c887bedbcdf1d")
+            .with_circuit(
+                &ProposedCircuitBuilder::default()
+                    .with_circuit_id("WBKLF-BBBBB")
+                    .with_roster(&[ProposedServiceBuilder::default()
+                        .with_service_id("a000")
+                        .with_service_type("scabbard")
+                        .with_allowed_nodes(&["acme-node-000".into()])
+                        .with_arguments(&[("peer_services".into(), "[]".into())])
+                        .build()
+                        .expect("Unable to build service")])
+                    .with_members(&[
+                        ProposedNodeBuilder::default()
+                            .with_node_id("acme-node-000")
+                            .with_endpoints(&["tcps://splinterd-node-acme:8044".into()])
+                            .build()
+                            .expect("Unable to build node"),
+                        ProposedNodeBuilder::default()
+                            .with_node_id("bubba-node-000")
+                            .with_endpoints(&["tcps://splinterd-node-bubba:8044".into()])
+                            .build()
+                            .expect("Unable to build node"),
+                    ])
+                    .with_circuit_management_type("test")
+                    .with_application_metadata(b"test")
+                    .with_comments("test comments")
+                    .build()
+                    .expect("Unable to build circuit"),
+            )
+            .with_requester(&[1, 2, 3])
+            .with_requester_node_id("acme-node-000")
+            .build()
+            .expect("Unable to build proposal")
+    }
+
+    fn create_circuit() -> (Circuit, CircuitNode) {
+        (
+            CircuitBuilder::default()
+                .with_circuit_id("WBKLF-DDDDD")
+                .with_roster(&[
+                    ServiceBuilder::default()
+                        .with_service_id("a000")
+                        .with_service_type("scabbard")
+                        .with_allowed_nodes(&["acme-node-000".into()])
+                        .with_arguments(&[("peer_services".into(), "[\"a001\"]".into())])
+                        .build()
+                        .expect("Unable to build service"),
+                    ServiceBuilder::default()
+                        .with_service_id("a001")
+                        .with_service_type("scabbard")
+                        .with_allowed_nodes(&["new-node-000".into()])
+                        .with_arguments(&[("peer_services".into(), "[\"a000\"]".into())])
+                        .build()
+                        .expect("Unable to build service"),
+                ])
+                .with_members(&["acme-node-000".into(), "new-node-000".into()])
+                .with_circuit_management_type("test")
+                .build()
+                .expect("Unable to build circuit"),
+            CircuitNodeBuilder::default()
+                .with_node_id("new-node-000")
+                .with_endpoints(&["tcps://splinterd-node-new:8044".into()])
+                .build()
+                .expect("Unable to build node"),
+        )
+    }
+
+    /// Creates a connection pool for an in-memory SQLite database with only a single connection
+    /// available. Each connection is backed by a different in-memory SQLite database, so limiting
+    /// the pool to a single connection insures that the same DB is used for all operations.
+    pub(super) fn create_connection_pool_and_migrate() -> Pool<ConnectionManager<SqliteConnection>>
+    {
+        let connection_manager = ConnectionManager::<SqliteConnection>::new(":memory:");
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(connection_manager)
+            .expect("Failed to build connection pool");
+
+        run_sqlite_migrations(&*pool.get().expect("Failed to get connection for migrations"))
+            .expect("Failed to run migrations");
+
+        pool
+    }
+}
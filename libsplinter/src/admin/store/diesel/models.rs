@@ -19,15 +19,19 @@
 use std::convert::TryFrom;
 
 use crate::admin::store::diesel::schema::{
-    circuit, circuit_member, circuit_proposal, node_endpoint, proposed_circuit, proposed_node,
-    proposed_node_endpoint, proposed_service, proposed_service_allowed_node,
-    proposed_service_argument, service, service_allowed_node, service_argument, vote_record,
+    admin_service_event, circuit, circuit_member, circuit_proposal, node_endpoint,
+    proposed_circuit, proposed_node, proposed_node_endpoint, proposed_service,
+    proposed_service_allowed_node, proposed_service_argument, service, service_allowed_node,
+    service_argument, vote_record,
 };
 use crate::admin::store::error::AdminServiceStoreError;
 use crate::admin::store::{
     AuthorizationType, DurabilityType, PersistenceType, ProposalType, RouteType, Vote, VoteRecord,
 };
-use crate::admin::store::{Circuit, CircuitProposal, ProposedCircuit};
+use crate::admin::store::{Circuit, CircuitNode, CircuitProposal, ProposedCircuit};
+
+#[cfg(feature = "circuit-relay")]
+const RELAYED_ROUTE_PREFIX: &str = "Relayed:";
 
 /// Database model representation of a `CircuitProposal`
 #[derive(Debug, PartialEq, Associations, Identifiable, Insertable, Queryable, QueryableByName)]
@@ -149,8 +153,9 @@ impl From<&ProposedCircuit> for Vec<ProposedNodeModel> {
 #[derive(Debug, PartialEq, Associations, Identifiable, Insertable, Queryable, QueryableByName)]
 #[table_name = "proposed_node_endpoint"]
 #[belongs_to(ProposedNodeModel, foreign_key = "node_id")]
-#[primary_key(node_id, endpoint)]
+#[primary_key(circuit_id, node_id, endpoint)]
 pub struct ProposedNodeEndpointModel {
+    pub circuit_id: String,
     pub node_id: String,
     pub endpoint: String,
 }
@@ -163,6 +168,7 @@ impl From<&ProposedCircuit> for Vec<ProposedNodeEndpointModel> {
                 node.endpoints
                     .iter()
                     .map(|endpoint| ProposedNodeEndpointModel {
+                        circuit_id: proposed_circuit.circuit_id.clone(),
                         node_id: node.node_id.clone(),
                         endpoint: endpoint.clone(),
                     })
@@ -410,6 +416,27 @@ pub struct NodeEndpointModel {
     pub endpoint: String,
 }
 
+impl From<&CircuitNode> for Vec<NodeEndpointModel> {
+    fn from(node: &CircuitNode) -> Self {
+        node.endpoints
+            .iter()
+            .map(|endpoint| NodeEndpointModel {
+                node_id: node.id.clone(),
+                endpoint: endpoint.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Database model representation of an admin service event stored in the event mailbox
+#[derive(Debug, PartialEq, Identifiable, Insertable, Queryable, QueryableByName)]
+#[table_name = "admin_service_event"]
+#[primary_key(event_time)]
+pub struct AdminServiceEventModel {
+    pub event_time: i64,
+    pub event: String,
+}
+
 // All enums associated with the above structs have TryFrom and From implemented in order to
 // translate the enums to a `Text` representation to be stored in the database.
 
@@ -533,6 +560,11 @@ impl TryFrom<String> for RouteType {
     fn try_from(variant: String) -> Result<Self, Self::Error> {
         match variant.as_ref() {
             "Any" => Ok(RouteType::Any),
+            // The relay node's ID is stored with the route type, as "Relayed:<relay node ID>"
+            #[cfg(feature = "circuit-relay")]
+            relayed if relayed.starts_with(RELAYED_ROUTE_PREFIX) => Ok(RouteType::Relayed {
+                relay_node: relayed[RELAYED_ROUTE_PREFIX.len()..].to_string(),
+            }),
             _ => Err(AdminServiceStoreError::StorageError {
                 context: "Unable to convert string to RouteType".into(),
                 source: None,
@@ -545,6 +577,8 @@ impl From<&RouteType> for String {
     fn from(variant: &RouteType) -> Self {
         match variant {
             RouteType::Any => String::from("Any"),
+            #[cfg(feature = "circuit-relay")]
+            RouteType::Relayed { relay_node } => format!("{}{}", RELAYED_ROUTE_PREFIX, relay_node),
         }
     }
 }
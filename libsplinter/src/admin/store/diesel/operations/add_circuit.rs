@@ -0,0 +1,183 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use diesel::{dsl::insert_into, prelude::*};
+
+use crate::admin::store::{
+    diesel::{
+        models::{
+            CircuitMemberModel, CircuitModel, NodeEndpointModel, ServiceAllowedNodeModel,
+            ServiceArgumentModel, ServiceModel,
+        },
+        schema::{
+            circuit, circuit_member, node_endpoint, service, service_allowed_node, service_argument,
+        },
+    },
+    error::AdminServiceStoreError,
+    Circuit, CircuitNode,
+};
+
+use super::{query_error, AdminServiceStoreOperations};
+
+pub(in crate::admin::store::diesel) trait AdminServiceStoreAddCircuitOperation {
+    fn add_circuit(
+        &self,
+        circuit: Circuit,
+        nodes: Vec<CircuitNode>,
+    ) -> Result<(), AdminServiceStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> AdminServiceStoreAddCircuitOperation
+    for AdminServiceStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn add_circuit(
+        &self,
+        circuit: Circuit,
+        nodes: Vec<CircuitNode>,
+    ) -> Result<(), AdminServiceStoreError> {
+        self.conn.transaction::<_, AdminServiceStoreError, _>(|| {
+            let existing = circuit::table
+                .find(&circuit.id)
+                .select(circuit::circuit_id)
+                .first::<String>(self.conn)
+                .optional()
+                .map_err(|err| query_error("Failed to check if circuit exists", err))?;
+            if existing.is_some() {
+                return Err(AdminServiceStoreError::OperationError {
+                    context: format!("A circuit with ID {} already exists", circuit.id),
+                    source: None,
+                });
+            }
+
+            insert_into(circuit::table)
+                .values(CircuitModel::from(&circuit))
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert circuit", err))?;
+
+            let members: Vec<CircuitMemberModel> = Vec::from(&circuit);
+            insert_into(circuit_member::table)
+                .values(&members)
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert circuit members", err))?;
+
+            let services: Vec<ServiceModel> = Vec::from(&circuit);
+            insert_into(service::table)
+                .values(&services)
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert services", err))?;
+            let allowed_nodes: Vec<ServiceAllowedNodeModel> = Vec::from(&circuit);
+            insert_into(service_allowed_node::table)
+                .values(&allowed_nodes)
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert service nodes", err))?;
+            let arguments: Vec<ServiceArgumentModel> = Vec::from(&circuit);
+            insert_into(service_argument::table)
+                .values(&arguments)
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert service arguments", err))?;
+
+            // Nodes are shared between circuits, so only the endpoints of unknown nodes are added
+            for node in nodes.iter() {
+                let known = node_endpoint::table
+                    .filter(node_endpoint::node_id.eq(&node.id))
+                    .select(node_endpoint::node_id)
+                    .first::<String>(self.conn)
+                    .optional()
+                    .map_err(|err| query_error("Failed to check if node exists", err))?;
+                if known.is_none() {
+                    let endpoints: Vec<NodeEndpointModel> = Vec::from(node);
+                    insert_into(node_endpoint::table)
+                        .values(&endpoints)
+                        .execute(self.conn)
+                        .map_err(|err| query_error("Failed to insert node endpoints", err))?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> AdminServiceStoreAddCircuitOperation
+    for AdminServiceStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn add_circuit(
+        &self,
+        circuit: Circuit,
+        nodes: Vec<CircuitNode>,
+    ) -> Result<(), AdminServiceStoreError> {
+        self.conn.transaction::<_, AdminServiceStoreError, _>(|| {
+            let existing = circuit::table
+                .find(&circuit.id)
+                .select(circuit::circuit_id)
+                .first::<String>(self.conn)
+                .optional()
+                .map_err(|err| query_error("Failed to check if circuit exists", err))?;
+            if existing.is_some() {
+                return Err(AdminServiceStoreError::OperationError {
+                    context: format!("A circuit with ID {} already exists", circuit.id),
+                    source: None,
+                });
+            }
+
+            insert_into(circuit::table)
+                .values(CircuitModel::from(&circuit))
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert circuit", err))?;
+
+            let members: Vec<CircuitMemberModel> = Vec::from(&circuit);
+            insert_into(circuit_member::table)
+                .values(&members)
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert circuit members", err))?;
+
+            let services: Vec<ServiceModel> = Vec::from(&circuit);
+            insert_into(service::table)
+                .values(&services)
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert services", err))?;
+            let allowed_nodes: Vec<ServiceAllowedNodeModel> = Vec::from(&circuit);
+            insert_into(service_allowed_node::table)
+                .values(&allowed_nodes)
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert service nodes", err))?;
+            let arguments: Vec<ServiceArgumentModel> = Vec::from(&circuit);
+            insert_into(service_argument::table)
+                .values(&arguments)
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert service arguments", err))?;
+
+            // Nodes are shared between circuits, so only the endpoints of unknown nodes are added
+            for node in nodes.iter() {
+                let known = node_endpoint::table
+                    .filter(node_endpoint::node_id.eq(&node.id))
+                    .select(node_endpoint::node_id)
+                    .first::<String>(self.conn)
+                    .optional()
+                    .map_err(|err| query_error("Failed to check if node exists", err))?;
+                if known.is_none() {
+                    let endpoints: Vec<NodeEndpointModel> = Vec::from(node);
+                    insert_into(node_endpoint::table)
+                        .values(&endpoints)
+                        .execute(self.conn)
+                        .map_err(|err| query_error("Failed to insert node endpoints", err))?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
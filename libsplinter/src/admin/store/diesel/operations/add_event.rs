@@ -0,0 +1,62 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use diesel::{
+    dsl::insert_into,
+    prelude::*,
+    result::{DatabaseErrorKind, Error as QueryError},
+};
+
+use crate::admin::store::{
+    diesel::{models::AdminServiceEventModel, schema::admin_service_event},
+    error::AdminServiceStoreError,
+};
+
+use super::{query_error, AdminServiceStoreOperations};
+
+pub(in crate::admin::store::diesel) trait AdminServiceStoreAddEventOperation {
+    fn add_event(&self, event: AdminServiceEventModel) -> Result<(), AdminServiceStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> AdminServiceStoreAddEventOperation
+    for AdminServiceStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn add_event(&self, event: AdminServiceEventModel) -> Result<(), AdminServiceStoreError> {
+        match insert_into(admin_service_event::table)
+            .values(&event)
+            .execute(self.conn)
+        {
+            // The events form a set, so adding an event that was already recorded is a no-op
+            Ok(_) | Err(QueryError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => Ok(()),
+            Err(err) => Err(query_error("Failed to add admin service event", err)),
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> AdminServiceStoreAddEventOperation
+    for AdminServiceStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn add_event(&self, event: AdminServiceEventModel) -> Result<(), AdminServiceStoreError> {
+        match insert_into(admin_service_event::table)
+            .values(&event)
+            .execute(self.conn)
+        {
+            // The events form a set, so adding an event that was already recorded is a no-op
+            Ok(_) | Err(QueryError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => Ok(()),
+            Err(err) => Err(query_error("Failed to add admin service event", err)),
+        }
+    }
+}
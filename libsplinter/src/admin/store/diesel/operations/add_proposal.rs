@@ -0,0 +1,190 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use diesel::{dsl::insert_into, prelude::*};
+
+use crate::admin::store::{
+    diesel::{
+        models::{
+            CircuitProposalModel, ProposedCircuitModel, ProposedNodeEndpointModel,
+            ProposedNodeModel, ProposedServiceAllowedNodeModel, ProposedServiceArgumentModel,
+            ProposedServiceModel, VoteRecordModel,
+        },
+        schema::{
+            circuit_proposal, proposed_circuit, proposed_node, proposed_node_endpoint,
+            proposed_service, proposed_service_allowed_node, proposed_service_argument,
+            vote_record,
+        },
+    },
+    error::AdminServiceStoreError,
+    CircuitProposal,
+};
+
+use super::{query_error, AdminServiceStoreOperations};
+
+pub(in crate::admin::store::diesel) trait AdminServiceStoreAddProposalOperation {
+    fn add_proposal(&self, proposal: CircuitProposal) -> Result<(), AdminServiceStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> AdminServiceStoreAddProposalOperation
+    for AdminServiceStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn add_proposal(&self, proposal: CircuitProposal) -> Result<(), AdminServiceStoreError> {
+        if proposal.circuit_id != proposal.circuit.circuit_id {
+            return Err(AdminServiceStoreError::OperationError {
+                context: format!(
+                    "Proposal ID {} does not match the ID of its circuit {}",
+                    proposal.circuit_id, proposal.circuit.circuit_id
+                ),
+                source: None,
+            });
+        }
+
+        self.conn.transaction::<_, AdminServiceStoreError, _>(|| {
+            let existing = circuit_proposal::table
+                .find(&proposal.circuit_id)
+                .select(circuit_proposal::circuit_id)
+                .first::<String>(self.conn)
+                .optional()
+                .map_err(|err| query_error("Failed to check if proposal exists", err))?;
+            if existing.is_some() {
+                return Err(AdminServiceStoreError::OperationError {
+                    context: format!("A proposal with ID {} already exists", proposal.circuit_id),
+                    source: None,
+                });
+            }
+
+            insert_into(circuit_proposal::table)
+                .values(CircuitProposalModel::from(&proposal))
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert proposal", err))?;
+            insert_into(proposed_circuit::table)
+                .values(ProposedCircuitModel::from(&proposal.circuit))
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert proposed circuit", err))?;
+
+            let votes: Vec<VoteRecordModel> = Vec::from(&proposal);
+            insert_into(vote_record::table)
+                .values(&votes)
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert proposal votes", err))?;
+
+            let nodes: Vec<ProposedNodeModel> = Vec::from(&proposal.circuit);
+            insert_into(proposed_node::table)
+                .values(&nodes)
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert proposed nodes", err))?;
+            let endpoints: Vec<ProposedNodeEndpointModel> = Vec::from(&proposal.circuit);
+            insert_into(proposed_node_endpoint::table)
+                .values(&endpoints)
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert proposed node endpoints", err))?;
+
+            let services: Vec<ProposedServiceModel> = Vec::from(&proposal.circuit);
+            insert_into(proposed_service::table)
+                .values(&services)
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert proposed services", err))?;
+            let allowed_nodes: Vec<ProposedServiceAllowedNodeModel> = Vec::from(&proposal.circuit);
+            insert_into(proposed_service_allowed_node::table)
+                .values(&allowed_nodes)
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert proposed service nodes", err))?;
+            let arguments: Vec<ProposedServiceArgumentModel> = Vec::from(&proposal.circuit);
+            insert_into(proposed_service_argument::table)
+                .values(&arguments)
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert proposed service arguments", err))?;
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> AdminServiceStoreAddProposalOperation
+    for AdminServiceStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn add_proposal(&self, proposal: CircuitProposal) -> Result<(), AdminServiceStoreError> {
+        if proposal.circuit_id != proposal.circuit.circuit_id {
+            return Err(AdminServiceStoreError::OperationError {
+                context: format!(
+                    "Proposal ID {} does not match the ID of its circuit {}",
+                    proposal.circuit_id, proposal.circuit.circuit_id
+                ),
+                source: None,
+            });
+        }
+
+        self.conn.transaction::<_, AdminServiceStoreError, _>(|| {
+            let existing = circuit_proposal::table
+                .find(&proposal.circuit_id)
+                .select(circuit_proposal::circuit_id)
+                .first::<String>(self.conn)
+                .optional()
+                .map_err(|err| query_error("Failed to check if proposal exists", err))?;
+            if existing.is_some() {
+                return Err(AdminServiceStoreError::OperationError {
+                    context: format!("A proposal with ID {} already exists", proposal.circuit_id),
+                    source: None,
+                });
+            }
+
+            insert_into(circuit_proposal::table)
+                .values(CircuitProposalModel::from(&proposal))
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert proposal", err))?;
+            insert_into(proposed_circuit::table)
+                .values(ProposedCircuitModel::from(&proposal.circuit))
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert proposed circuit", err))?;
+
+            let votes: Vec<VoteRecordModel> = Vec::from(&proposal);
+            insert_into(vote_record::table)
+                .values(&votes)
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert proposal votes", err))?;
+
+            let nodes: Vec<ProposedNodeModel> = Vec::from(&proposal.circuit);
+            insert_into(proposed_node::table)
+                .values(&nodes)
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert proposed nodes", err))?;
+            let endpoints: Vec<ProposedNodeEndpointModel> = Vec::from(&proposal.circuit);
+            insert_into(proposed_node_endpoint::table)
+                .values(&endpoints)
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert proposed node endpoints", err))?;
+
+            let services: Vec<ProposedServiceModel> = Vec::from(&proposal.circuit);
+            insert_into(proposed_service::table)
+                .values(&services)
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert proposed services", err))?;
+            let allowed_nodes: Vec<ProposedServiceAllowedNodeModel> = Vec::from(&proposal.circuit);
+            insert_into(proposed_service_allowed_node::table)
+                .values(&allowed_nodes)
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert proposed service nodes", err))?;
+            let arguments: Vec<ProposedServiceArgumentModel> = Vec::from(&proposal.circuit);
+            insert_into(proposed_service_argument::table)
+                .values(&arguments)
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to insert proposed service arguments", err))?;
+
+            Ok(())
+        })
+    }
+}
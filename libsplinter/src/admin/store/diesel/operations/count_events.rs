@@ -0,0 +1,37 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use diesel::prelude::*;
+
+use crate::admin::store::{diesel::schema::admin_service_event, error::AdminServiceStoreError};
+
+use super::{query_error, AdminServiceStoreOperations};
+
+pub(in crate::admin::store::diesel) trait AdminServiceStoreCountEventsOperation {
+    fn count_events(&self) -> Result<u64, AdminServiceStoreError>;
+}
+
+impl<'a, C> AdminServiceStoreCountEventsOperation for AdminServiceStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+{
+    fn count_events(&self) -> Result<u64, AdminServiceStoreError> {
+        admin_service_event::table
+            .count()
+            .get_result::<i64>(self.conn)
+            .map(|count| count as u64)
+            .map_err(|err| query_error("Failed to count admin service events", err))
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryFrom;
+
+use diesel::prelude::*;
+
+use crate::admin::store::{
+    diesel::{
+        models::{CircuitMemberModel, CircuitModel},
+        schema::{circuit, circuit_member},
+    },
+    error::AdminServiceStoreError,
+    AuthorizationType, Circuit, DurabilityType, PersistenceType, RouteType,
+};
+
+use super::{
+    list_services::AdminServiceStoreListServicesOperation, query_error, AdminServiceStoreOperations,
+};
+
+pub(in crate::admin::store::diesel) trait AdminServiceStoreFetchCircuitOperation {
+    fn fetch_circuit(&self, circuit_id: &str) -> Result<Option<Circuit>, AdminServiceStoreError>;
+}
+
+impl<'a, C> AdminServiceStoreFetchCircuitOperation for AdminServiceStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn fetch_circuit(&self, circuit_id: &str) -> Result<Option<Circuit>, AdminServiceStoreError> {
+        let circuit = match circuit::table
+            .find(circuit_id)
+            .first::<CircuitModel>(self.conn)
+            .optional()
+            .map_err(|err| query_error("Failed to fetch circuit", err))?
+        {
+            Some(circuit) => circuit,
+            None => return Ok(None),
+        };
+
+        let members = circuit_member::table
+            .filter(circuit_member::circuit_id.eq(circuit_id))
+            .order(circuit_member::node_id)
+            .load::<CircuitMemberModel>(self.conn)
+            .map_err(|err| query_error("Failed to fetch circuit members", err))?
+            .into_iter()
+            .map(|member| member.node_id)
+            .collect();
+
+        Ok(Some(Circuit {
+            roster: self.list_services(circuit_id)?.collect(),
+            members,
+            auth: AuthorizationType::try_from(circuit.auth)?,
+            persistence: PersistenceType::try_from(circuit.persistence)?,
+            durability: DurabilityType::try_from(circuit.durability)?,
+            routes: RouteType::try_from(circuit.routes)?,
+            circuit_management_type: circuit.circuit_management_type,
+            id: circuit.circuit_id,
+        }))
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use diesel::prelude::*;
+
+use crate::admin::store::{
+    diesel::{models::AdminServiceEventModel, schema::admin_service_event},
+    error::AdminServiceStoreError,
+};
+
+use super::{query_error, AdminServiceStoreOperations};
+
+pub(in crate::admin::store::diesel) trait AdminServiceStoreFetchEventOperation {
+    fn fetch_event(
+        &self,
+        event_time: i64,
+    ) -> Result<Option<AdminServiceEventModel>, AdminServiceStoreError>;
+
+    fn fetch_first_event(&self) -> Result<Option<AdminServiceEventModel>, AdminServiceStoreError>;
+
+    fn fetch_last_event(&self) -> Result<Option<AdminServiceEventModel>, AdminServiceStoreError>;
+}
+
+impl<'a, C> AdminServiceStoreFetchEventOperation for AdminServiceStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn fetch_event(
+        &self,
+        event_time: i64,
+    ) -> Result<Option<AdminServiceEventModel>, AdminServiceStoreError> {
+        admin_service_event::table
+            .find(event_time)
+            .first::<AdminServiceEventModel>(self.conn)
+            .optional()
+            .map_err(|err| query_error("Failed to fetch admin service event", err))
+    }
+
+    fn fetch_first_event(&self) -> Result<Option<AdminServiceEventModel>, AdminServiceStoreError> {
+        admin_service_event::table
+            .order(admin_service_event::event_time.asc())
+            .first::<AdminServiceEventModel>(self.conn)
+            .optional()
+            .map_err(|err| query_error("Failed to fetch first admin service event", err))
+    }
+
+    fn fetch_last_event(&self) -> Result<Option<AdminServiceEventModel>, AdminServiceStoreError> {
+        admin_service_event::table
+            .order(admin_service_event::event_time.desc())
+            .first::<AdminServiceEventModel>(self.conn)
+            .optional()
+            .map_err(|err| query_error("Failed to fetch last admin service event", err))
+    }
+}
@@ -0,0 +1,53 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use diesel::prelude::*;
+
+use crate::admin::store::{
+    diesel::{models::NodeEndpointModel, schema::node_endpoint},
+    error::AdminServiceStoreError,
+    CircuitNode,
+};
+
+use super::{query_error, AdminServiceStoreOperations};
+
+pub(in crate::admin::store::diesel) trait AdminServiceStoreFetchNodeOperation {
+    fn fetch_node(&self, node_id: &str) -> Result<Option<CircuitNode>, AdminServiceStoreError>;
+}
+
+impl<'a, C> AdminServiceStoreFetchNodeOperation for AdminServiceStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn fetch_node(&self, node_id: &str) -> Result<Option<CircuitNode>, AdminServiceStoreError> {
+        let endpoints = node_endpoint::table
+            .filter(node_endpoint::node_id.eq(node_id))
+            .order(node_endpoint::endpoint)
+            .load::<NodeEndpointModel>(self.conn)
+            .map_err(|err| query_error("Failed to fetch node endpoints", err))?
+            .into_iter()
+            .map(|endpoint| endpoint.endpoint)
+            .collect::<Vec<_>>();
+
+        if endpoints.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(CircuitNode {
+            id: node_id.to_string(),
+            endpoints,
+        }))
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryFrom;
+
+use diesel::prelude::*;
+
+use crate::admin::store::{
+    diesel::{
+        models::{
+            CircuitProposalModel, ProposedCircuitModel, ProposedNodeEndpointModel,
+            ProposedNodeModel, ProposedServiceAllowedNodeModel, ProposedServiceArgumentModel,
+            ProposedServiceModel, VoteRecordModel,
+        },
+        schema::{
+            circuit_proposal, proposed_circuit, proposed_node, proposed_node_endpoint,
+            proposed_service, proposed_service_allowed_node, proposed_service_argument,
+            vote_record,
+        },
+    },
+    error::AdminServiceStoreError,
+    AuthorizationType, CircuitProposal, DurabilityType, PersistenceType, ProposalType,
+    ProposedCircuit, ProposedNode, ProposedService, RouteType, VoteRecord,
+};
+
+use super::{query_error, AdminServiceStoreOperations};
+
+pub(in crate::admin::store::diesel) trait AdminServiceStoreFetchProposalOperation {
+    fn fetch_proposal(
+        &self,
+        proposal_id: &str,
+    ) -> Result<Option<CircuitProposal>, AdminServiceStoreError>;
+}
+
+impl<'a, C> AdminServiceStoreFetchProposalOperation for AdminServiceStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+    Vec<u8>: diesel::deserialize::FromSql<diesel::sql_types::Binary, C::Backend>,
+{
+    fn fetch_proposal(
+        &self,
+        proposal_id: &str,
+    ) -> Result<Option<CircuitProposal>, AdminServiceStoreError> {
+        let proposal = match circuit_proposal::table
+            .find(proposal_id)
+            .first::<CircuitProposalModel>(self.conn)
+            .optional()
+            .map_err(|err| query_error("Failed to fetch proposal", err))?
+        {
+            Some(proposal) => proposal,
+            None => return Ok(None),
+        };
+
+        let circuit = proposed_circuit::table
+            .find(proposal_id)
+            .first::<ProposedCircuitModel>(self.conn)
+            .map_err(|err| query_error("Failed to fetch proposed circuit", err))?;
+
+        let votes = vote_record::table
+            .filter(vote_record::circuit_id.eq(proposal_id))
+            .order(vote_record::voter_node_id)
+            .load::<VoteRecordModel>(self.conn)
+            .map_err(|err| query_error("Failed to fetch proposal votes", err))?
+            .iter()
+            .map(VoteRecord::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let endpoints = proposed_node_endpoint::table
+            .filter(proposed_node_endpoint::circuit_id.eq(proposal_id))
+            .order((
+                proposed_node_endpoint::node_id,
+                proposed_node_endpoint::endpoint,
+            ))
+            .load::<ProposedNodeEndpointModel>(self.conn)
+            .map_err(|err| query_error("Failed to fetch proposed node endpoints", err))?;
+        let members = proposed_node::table
+            .filter(proposed_node::circuit_id.eq(proposal_id))
+            .order(proposed_node::node_id)
+            .load::<ProposedNodeModel>(self.conn)
+            .map_err(|err| query_error("Failed to fetch proposed nodes", err))?
+            .into_iter()
+            .map(|node| ProposedNode {
+                endpoints: endpoints
+                    .iter()
+                    .filter(|endpoint| endpoint.node_id == node.node_id)
+                    .map(|endpoint| endpoint.endpoint.clone())
+                    .collect(),
+                node_id: node.node_id,
+            })
+            .collect();
+
+        let allowed_nodes = proposed_service_allowed_node::table
+            .filter(proposed_service_allowed_node::circuit_id.eq(proposal_id))
+            .order((
+                proposed_service_allowed_node::service_id,
+                proposed_service_allowed_node::allowed_node,
+            ))
+            .load::<ProposedServiceAllowedNodeModel>(self.conn)
+            .map_err(|err| query_error("Failed to fetch proposed service nodes", err))?;
+        let arguments = proposed_service_argument::table
+            .filter(proposed_service_argument::circuit_id.eq(proposal_id))
+            .order((
+                proposed_service_argument::service_id,
+                proposed_service_argument::key,
+            ))
+            .load::<ProposedServiceArgumentModel>(self.conn)
+            .map_err(|err| query_error("Failed to fetch proposed service arguments", err))?;
+        let roster = proposed_service::table
+            .filter(proposed_service::circuit_id.eq(proposal_id))
+            .order(proposed_service::service_id)
+            .load::<ProposedServiceModel>(self.conn)
+            .map_err(|err| query_error("Failed to fetch proposed services", err))?
+            .into_iter()
+            .map(|service| ProposedService {
+                allowed_nodes: allowed_nodes
+                    .iter()
+                    .filter(|node| node.service_id == service.service_id)
+                    .map(|node| node.allowed_node.clone())
+                    .collect(),
+                arguments: arguments
+                    .iter()
+                    .filter(|argument| argument.service_id == service.service_id)
+                    .map(|argument| (argument.key.clone(), argument.value.clone()))
+                    .collect(),
+                service_id: service.service_id,
+                service_type: service.service_type,
+            })
+            .collect();
+
+        Ok(Some(CircuitProposal {
+            proposal_type: ProposalType::try_from(proposal.proposal_type)?,
+            circuit_id: proposal.circuit_id,
+            circuit_hash: proposal.circuit_hash,
+            circuit: ProposedCircuit {
+                circuit_id: circuit.circuit_id,
+                roster,
+                members,
+                authorization_type: AuthorizationType::try_from(circuit.authorization_type)?,
+                persistence: PersistenceType::try_from(circuit.persistence)?,
+                durability: DurabilityType::try_from(circuit.durability)?,
+                routes: RouteType::try_from(circuit.routes)?,
+                circuit_management_type: circuit.circuit_management_type,
+                application_metadata: circuit.application_metadata,
+                comments: circuit.comments,
+            },
+            votes,
+            requester: proposal.requester,
+            requester_node_id: proposal.requester_node_id,
+        }))
+    }
+}
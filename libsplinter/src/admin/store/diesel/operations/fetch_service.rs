@@ -0,0 +1,80 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use diesel::prelude::*;
+
+use crate::admin::store::{
+    diesel::{
+        models::{ServiceAllowedNodeModel, ServiceArgumentModel, ServiceModel},
+        schema::{service, service_allowed_node, service_argument},
+    },
+    error::AdminServiceStoreError,
+    Service, ServiceId,
+};
+
+use super::{query_error, AdminServiceStoreOperations};
+
+pub(in crate::admin::store::diesel) trait AdminServiceStoreFetchServiceOperation {
+    fn fetch_service(
+        &self,
+        service_id: &ServiceId,
+    ) -> Result<Option<Service>, AdminServiceStoreError>;
+}
+
+impl<'a, C> AdminServiceStoreFetchServiceOperation for AdminServiceStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn fetch_service(
+        &self,
+        service_id: &ServiceId,
+    ) -> Result<Option<Service>, AdminServiceStoreError> {
+        let service = match service::table
+            .find((service_id.circuit(), service_id.service_id()))
+            .first::<ServiceModel>(self.conn)
+            .optional()
+            .map_err(|err| query_error("Failed to fetch service", err))?
+        {
+            Some(service) => service,
+            None => return Ok(None),
+        };
+
+        let allowed_nodes = service_allowed_node::table
+            .filter(service_allowed_node::circuit_id.eq(service_id.circuit()))
+            .filter(service_allowed_node::service_id.eq(service_id.service_id()))
+            .order(service_allowed_node::allowed_node)
+            .load::<ServiceAllowedNodeModel>(self.conn)
+            .map_err(|err| query_error("Failed to fetch service nodes", err))?
+            .into_iter()
+            .map(|node| node.allowed_node)
+            .collect();
+        let arguments = service_argument::table
+            .filter(service_argument::circuit_id.eq(service_id.circuit()))
+            .filter(service_argument::service_id.eq(service_id.service_id()))
+            .order(service_argument::key)
+            .load::<ServiceArgumentModel>(self.conn)
+            .map_err(|err| query_error("Failed to fetch service arguments", err))?
+            .into_iter()
+            .map(|argument| (argument.key, argument.value))
+            .collect();
+
+        Ok(Some(Service {
+            service_id: service.service_id,
+            service_type: service.service_type,
+            allowed_nodes,
+            arguments,
+        }))
+    }
+}
@@ -0,0 +1,62 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use diesel::prelude::*;
+
+use crate::admin::store::{
+    diesel::schema::circuit, error::AdminServiceStoreError, Circuit, CircuitPredicate,
+};
+
+use super::{
+    fetch_circuit::AdminServiceStoreFetchCircuitOperation, query_error, AdminServiceStoreOperations,
+};
+
+pub(in crate::admin::store::diesel) trait AdminServiceStoreListCircuitsOperation {
+    fn list_circuits(
+        &self,
+        predicates: &[CircuitPredicate],
+    ) -> Result<Box<dyn ExactSizeIterator<Item = Circuit>>, AdminServiceStoreError>;
+}
+
+impl<'a, C> AdminServiceStoreListCircuitsOperation for AdminServiceStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn list_circuits(
+        &self,
+        predicates: &[CircuitPredicate],
+    ) -> Result<Box<dyn ExactSizeIterator<Item = Circuit>>, AdminServiceStoreError> {
+        let circuit_ids = circuit::table
+            .select(circuit::circuit_id)
+            .order(circuit::circuit_id)
+            .load::<String>(self.conn)
+            .map_err(|err| query_error("Failed to list circuits", err))?;
+
+        let mut circuits = Vec::with_capacity(circuit_ids.len());
+        for circuit_id in circuit_ids {
+            if let Some(circuit) = self.fetch_circuit(&circuit_id)? {
+                circuits.push(circuit);
+            }
+        }
+
+        circuits.retain(|circuit| {
+            predicates
+                .iter()
+                .all(|predicate| predicate.apply_to_circuit(circuit))
+        });
+
+        Ok(Box::new(circuits.into_iter()))
+    }
+}
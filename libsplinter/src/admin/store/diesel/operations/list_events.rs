@@ -0,0 +1,51 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use diesel::prelude::*;
+
+use crate::admin::store::{
+    diesel::{models::AdminServiceEventModel, schema::admin_service_event},
+    error::AdminServiceStoreError,
+};
+
+use super::{query_error, AdminServiceStoreOperations};
+
+pub(in crate::admin::store::diesel) trait AdminServiceStoreListEventsOperation {
+    /// Lists the events recorded between the given times, inclusively, in order of their time.
+    fn list_events(
+        &self,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<AdminServiceEventModel>, AdminServiceStoreError>;
+}
+
+impl<'a, C> AdminServiceStoreListEventsOperation for AdminServiceStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn list_events(
+        &self,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<AdminServiceEventModel>, AdminServiceStoreError> {
+        admin_service_event::table
+            .filter(admin_service_event::event_time.ge(start))
+            .filter(admin_service_event::event_time.le(end))
+            .order(admin_service_event::event_time.asc())
+            .load::<AdminServiceEventModel>(self.conn)
+            .map_err(|err| query_error("Failed to list admin service events", err))
+    }
+}
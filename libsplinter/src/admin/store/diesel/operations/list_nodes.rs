@@ -0,0 +1,58 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use diesel::prelude::*;
+
+use crate::admin::store::{
+    diesel::{models::NodeEndpointModel, schema::node_endpoint},
+    error::AdminServiceStoreError,
+    CircuitNode,
+};
+
+use super::{query_error, AdminServiceStoreOperations};
+
+pub(in crate::admin::store::diesel) trait AdminServiceStoreListNodesOperation {
+    fn list_nodes(
+        &self,
+    ) -> Result<Box<dyn ExactSizeIterator<Item = CircuitNode>>, AdminServiceStoreError>;
+}
+
+impl<'a, C> AdminServiceStoreListNodesOperation for AdminServiceStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn list_nodes(
+        &self,
+    ) -> Result<Box<dyn ExactSizeIterator<Item = CircuitNode>>, AdminServiceStoreError> {
+        let endpoints = node_endpoint::table
+            .order((node_endpoint::node_id, node_endpoint::endpoint))
+            .load::<NodeEndpointModel>(self.conn)
+            .map_err(|err| query_error("Failed to list node endpoints", err))?;
+
+        // The endpoints are ordered by node, so each node's endpoints are adjacent
+        let mut nodes: Vec<CircuitNode> = Vec::new();
+        for endpoint in endpoints {
+            match nodes.last_mut() {
+                Some(node) if node.id == endpoint.node_id => node.endpoints.push(endpoint.endpoint),
+                _ => nodes.push(CircuitNode {
+                    id: endpoint.node_id,
+                    endpoints: vec![endpoint.endpoint],
+                }),
+            }
+        }
+
+        Ok(Box::new(nodes.into_iter()))
+    }
+}
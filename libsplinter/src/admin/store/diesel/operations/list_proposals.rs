@@ -0,0 +1,65 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use diesel::prelude::*;
+
+use crate::admin::store::{
+    diesel::schema::circuit_proposal, error::AdminServiceStoreError, CircuitPredicate,
+    CircuitProposal,
+};
+
+use super::{
+    fetch_proposal::AdminServiceStoreFetchProposalOperation, query_error,
+    AdminServiceStoreOperations,
+};
+
+pub(in crate::admin::store::diesel) trait AdminServiceStoreListProposalsOperation {
+    fn list_proposals(
+        &self,
+        predicates: &[CircuitPredicate],
+    ) -> Result<Box<dyn ExactSizeIterator<Item = CircuitProposal>>, AdminServiceStoreError>;
+}
+
+impl<'a, C> AdminServiceStoreListProposalsOperation for AdminServiceStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+    Vec<u8>: diesel::deserialize::FromSql<diesel::sql_types::Binary, C::Backend>,
+{
+    fn list_proposals(
+        &self,
+        predicates: &[CircuitPredicate],
+    ) -> Result<Box<dyn ExactSizeIterator<Item = CircuitProposal>>, AdminServiceStoreError> {
+        let proposal_ids = circuit_proposal::table
+            .select(circuit_proposal::circuit_id)
+            .order(circuit_proposal::circuit_id)
+            .load::<String>(self.conn)
+            .map_err(|err| query_error("Failed to list proposals", err))?;
+
+        let mut proposals = Vec::with_capacity(proposal_ids.len());
+        for proposal_id in proposal_ids {
+            if let Some(proposal) = self.fetch_proposal(&proposal_id)? {
+                proposals.push(proposal);
+            }
+        }
+
+        proposals.retain(|proposal| {
+            predicates
+                .iter()
+                .all(|predicate| predicate.apply_to_proposals(proposal))
+        });
+
+        Ok(Box::new(proposals.into_iter()))
+    }
+}
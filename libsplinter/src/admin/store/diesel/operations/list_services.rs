@@ -0,0 +1,92 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use diesel::prelude::*;
+
+use crate::admin::store::{
+    diesel::{
+        models::{ServiceAllowedNodeModel, ServiceArgumentModel, ServiceModel},
+        schema::{circuit, service, service_allowed_node, service_argument},
+    },
+    error::AdminServiceStoreError,
+    Service,
+};
+
+use super::{query_error, AdminServiceStoreOperations};
+
+pub(in crate::admin::store::diesel) trait AdminServiceStoreListServicesOperation {
+    fn list_services(
+        &self,
+        circuit_id: &str,
+    ) -> Result<Box<dyn ExactSizeIterator<Item = Service>>, AdminServiceStoreError>;
+}
+
+impl<'a, C> AdminServiceStoreListServicesOperation for AdminServiceStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn list_services(
+        &self,
+        circuit_id: &str,
+    ) -> Result<Box<dyn ExactSizeIterator<Item = Service>>, AdminServiceStoreError> {
+        circuit::table
+            .find(circuit_id)
+            .select(circuit::circuit_id)
+            .first::<String>(self.conn)
+            .optional()
+            .map_err(|err| query_error("Failed to check if circuit exists", err))?
+            .ok_or_else(|| AdminServiceStoreError::OperationError {
+                context: format!("Circuit {} does not exist", circuit_id),
+                source: None,
+            })?;
+
+        let allowed_nodes = service_allowed_node::table
+            .filter(service_allowed_node::circuit_id.eq(circuit_id))
+            .order((
+                service_allowed_node::service_id,
+                service_allowed_node::allowed_node,
+            ))
+            .load::<ServiceAllowedNodeModel>(self.conn)
+            .map_err(|err| query_error("Failed to fetch service nodes", err))?;
+        let arguments = service_argument::table
+            .filter(service_argument::circuit_id.eq(circuit_id))
+            .order((service_argument::service_id, service_argument::key))
+            .load::<ServiceArgumentModel>(self.conn)
+            .map_err(|err| query_error("Failed to fetch service arguments", err))?;
+        let services = service::table
+            .filter(service::circuit_id.eq(circuit_id))
+            .order(service::service_id)
+            .load::<ServiceModel>(self.conn)
+            .map_err(|err| query_error("Failed to fetch services", err))?
+            .into_iter()
+            .map(|service| Service {
+                allowed_nodes: allowed_nodes
+                    .iter()
+                    .filter(|node| node.service_id == service.service_id)
+                    .map(|node| node.allowed_node.clone())
+                    .collect(),
+                arguments: arguments
+                    .iter()
+                    .filter(|argument| argument.service_id == service.service_id)
+                    .map(|argument| (argument.key.clone(), argument.value.clone()))
+                    .collect(),
+                service_id: service.service_id,
+                service_type: service.service_type,
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Box::new(services.into_iter()))
+    }
+}
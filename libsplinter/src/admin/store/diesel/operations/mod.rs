@@ -14,8 +14,30 @@
 
 //! Provides database operations for the `DieselAdminServiceStore`.
 
+pub(super) mod add_circuit;
+pub(super) mod add_event;
+pub(super) mod add_proposal;
+pub(super) mod count_events;
+pub(super) mod fetch_circuit;
+pub(super) mod fetch_event;
+pub(super) mod fetch_node;
+pub(super) mod fetch_proposal;
+pub(super) mod fetch_service;
+pub(super) mod list_circuits;
+pub(super) mod list_events;
+pub(super) mod list_nodes;
+pub(super) mod list_proposals;
+pub(super) mod list_services;
+pub(super) mod remove_circuit;
+pub(super) mod remove_event;
+pub(super) mod remove_proposal;
+pub(super) mod update_circuit;
+pub(super) mod update_proposal;
+pub(super) mod upgrade_proposal_to_circuit;
+
+use crate::admin::store::error::AdminServiceStoreError;
+
 pub struct AdminServiceStoreOperations<'a, C> {
-    #[allow(dead_code)]
     conn: &'a C,
 }
 
@@ -24,3 +46,11 @@ impl<'a, C: diesel::Connection> AdminServiceStoreOperations<'a, C> {
         AdminServiceStoreOperations { conn }
     }
 }
+
+/// Wraps a database error with the context of the query that caused it.
+fn query_error(context: &str, err: diesel::result::Error) -> AdminServiceStoreError {
+    AdminServiceStoreError::QueryError {
+        context: context.to_string(),
+        source: Box::new(err),
+    }
+}
@@ -0,0 +1,61 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use diesel::{dsl::delete, prelude::*};
+
+use crate::admin::store::{
+    diesel::schema::{circuit, circuit_member, service, service_allowed_node, service_argument},
+    error::AdminServiceStoreError,
+};
+
+use super::{query_error, AdminServiceStoreOperations};
+
+pub(in crate::admin::store::diesel) trait AdminServiceStoreRemoveCircuitOperation {
+    fn remove_circuit(&self, circuit_id: &str) -> Result<(), AdminServiceStoreError>;
+}
+
+impl<'a, C> AdminServiceStoreRemoveCircuitOperation for AdminServiceStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+{
+    fn remove_circuit(&self, circuit_id: &str) -> Result<(), AdminServiceStoreError> {
+        let removed = self
+            .conn
+            .transaction::<_, diesel::result::Error, _>(|| {
+                // The child rows are removed explicitly, as foreign keys may not be enforced. The
+                // endpoints of the circuit's members are kept, since nodes are shared by circuits.
+                delete(service_argument::table.filter(service_argument::circuit_id.eq(circuit_id)))
+                    .execute(self.conn)?;
+                delete(
+                    service_allowed_node::table
+                        .filter(service_allowed_node::circuit_id.eq(circuit_id)),
+                )
+                .execute(self.conn)?;
+                delete(service::table.filter(service::circuit_id.eq(circuit_id)))
+                    .execute(self.conn)?;
+                delete(circuit_member::table.filter(circuit_member::circuit_id.eq(circuit_id)))
+                    .execute(self.conn)?;
+                delete(circuit::table.find(circuit_id)).execute(self.conn)
+            })
+            .map_err(|err| query_error("Failed to remove circuit", err))?;
+
+        if removed == 0 {
+            return Err(AdminServiceStoreError::OperationError {
+                context: format!("A circuit with ID {} does not exist", circuit_id),
+                source: None,
+            });
+        }
+        Ok(())
+    }
+}
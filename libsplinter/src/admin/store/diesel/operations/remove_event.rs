@@ -0,0 +1,50 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use diesel::{dsl::delete, prelude::*};
+
+use crate::admin::store::{
+    diesel::{models::AdminServiceEventModel, schema::admin_service_event},
+    error::AdminServiceStoreError,
+};
+
+use super::{
+    fetch_event::AdminServiceStoreFetchEventOperation, query_error, AdminServiceStoreOperations,
+};
+
+pub(in crate::admin::store::diesel) trait AdminServiceStoreRemoveEventOperation {
+    fn remove_event(
+        &self,
+        event_time: i64,
+    ) -> Result<Option<AdminServiceEventModel>, AdminServiceStoreError>;
+}
+
+impl<'a, C> AdminServiceStoreRemoveEventOperation for AdminServiceStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn remove_event(
+        &self,
+        event_time: i64,
+    ) -> Result<Option<AdminServiceEventModel>, AdminServiceStoreError> {
+        self.fetch_event(event_time).and_then(|event| {
+            delete(admin_service_event::table.find(event_time))
+                .execute(self.conn)
+                .map_err(|err| query_error("Failed to remove admin service event", err))?;
+            Ok(event)
+        })
+    }
+}
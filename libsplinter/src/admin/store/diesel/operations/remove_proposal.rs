@@ -0,0 +1,76 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use diesel::{dsl::delete, prelude::*};
+
+use crate::admin::store::{
+    diesel::schema::{
+        circuit_proposal, proposed_circuit, proposed_node, proposed_node_endpoint,
+        proposed_service, proposed_service_allowed_node, proposed_service_argument, vote_record,
+    },
+    error::AdminServiceStoreError,
+};
+
+use super::{query_error, AdminServiceStoreOperations};
+
+pub(in crate::admin::store::diesel) trait AdminServiceStoreRemoveProposalOperation {
+    fn remove_proposal(&self, proposal_id: &str) -> Result<(), AdminServiceStoreError>;
+}
+
+impl<'a, C> AdminServiceStoreRemoveProposalOperation for AdminServiceStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+{
+    fn remove_proposal(&self, proposal_id: &str) -> Result<(), AdminServiceStoreError> {
+        let removed = self
+            .conn
+            .transaction::<_, diesel::result::Error, _>(|| {
+                // The child rows are removed explicitly, as foreign keys may not be enforced
+                delete(vote_record::table.filter(vote_record::circuit_id.eq(proposal_id)))
+                    .execute(self.conn)?;
+                delete(
+                    proposed_service_argument::table
+                        .filter(proposed_service_argument::circuit_id.eq(proposal_id)),
+                )
+                .execute(self.conn)?;
+                delete(
+                    proposed_service_allowed_node::table
+                        .filter(proposed_service_allowed_node::circuit_id.eq(proposal_id)),
+                )
+                .execute(self.conn)?;
+                delete(
+                    proposed_service::table.filter(proposed_service::circuit_id.eq(proposal_id)),
+                )
+                .execute(self.conn)?;
+                delete(
+                    proposed_node_endpoint::table
+                        .filter(proposed_node_endpoint::circuit_id.eq(proposal_id)),
+                )
+                .execute(self.conn)?;
+                delete(proposed_node::table.filter(proposed_node::circuit_id.eq(proposal_id)))
+                    .execute(self.conn)?;
+                delete(proposed_circuit::table.find(proposal_id)).execute(self.conn)?;
+                delete(circuit_proposal::table.find(proposal_id)).execute(self.conn)
+            })
+            .map_err(|err| query_error("Failed to remove proposal", err))?;
+
+        if removed == 0 {
+            return Err(AdminServiceStoreError::OperationError {
+                context: format!("A proposal with ID {} does not exist", proposal_id),
+                source: None,
+            });
+        }
+        Ok(())
+    }
+}
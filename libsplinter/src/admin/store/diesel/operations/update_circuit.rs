@@ -0,0 +1,40 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use diesel::Connection;
+
+use crate::admin::store::{error::AdminServiceStoreError, Circuit};
+
+use super::{
+    add_circuit::AdminServiceStoreAddCircuitOperation,
+    remove_circuit::AdminServiceStoreRemoveCircuitOperation, AdminServiceStoreOperations,
+};
+
+pub(in crate::admin::store::diesel) trait AdminServiceStoreUpdateCircuitOperation {
+    fn update_circuit(&self, circuit: Circuit) -> Result<(), AdminServiceStoreError>;
+}
+
+impl<'a, C> AdminServiceStoreUpdateCircuitOperation for AdminServiceStoreOperations<'a, C>
+where
+    C: Connection,
+    Self: AdminServiceStoreAddCircuitOperation + AdminServiceStoreRemoveCircuitOperation,
+{
+    fn update_circuit(&self, circuit: Circuit) -> Result<(), AdminServiceStoreError> {
+        // The circuit is replaced as a whole; the endpoints of its members are left unchanged
+        self.conn.transaction::<_, AdminServiceStoreError, _>(|| {
+            self.remove_circuit(&circuit.id)?;
+            self.add_circuit(circuit, vec![])
+        })
+    }
+}
@@ -0,0 +1,40 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use diesel::Connection;
+
+use crate::admin::store::{error::AdminServiceStoreError, CircuitProposal};
+
+use super::{
+    add_proposal::AdminServiceStoreAddProposalOperation,
+    remove_proposal::AdminServiceStoreRemoveProposalOperation, AdminServiceStoreOperations,
+};
+
+pub(in crate::admin::store::diesel) trait AdminServiceStoreUpdateProposalOperation {
+    fn update_proposal(&self, proposal: CircuitProposal) -> Result<(), AdminServiceStoreError>;
+}
+
+impl<'a, C> AdminServiceStoreUpdateProposalOperation for AdminServiceStoreOperations<'a, C>
+where
+    C: Connection,
+    Self: AdminServiceStoreAddProposalOperation + AdminServiceStoreRemoveProposalOperation,
+{
+    fn update_proposal(&self, proposal: CircuitProposal) -> Result<(), AdminServiceStoreError> {
+        // The proposal is replaced as a whole, since any of its child rows may have changed
+        self.conn.transaction::<_, AdminServiceStoreError, _>(|| {
+            self.remove_proposal(&proposal.circuit_id)?;
+            self.add_proposal(proposal)
+        })
+    }
+}
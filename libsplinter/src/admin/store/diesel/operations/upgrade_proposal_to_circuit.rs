@@ -0,0 +1,57 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use diesel::Connection;
+
+use crate::admin::store::{error::AdminServiceStoreError, Circuit, CircuitNode};
+
+use super::{
+    add_circuit::AdminServiceStoreAddCircuitOperation,
+    fetch_proposal::AdminServiceStoreFetchProposalOperation,
+    remove_proposal::AdminServiceStoreRemoveProposalOperation, AdminServiceStoreOperations,
+};
+
+pub(in crate::admin::store::diesel) trait AdminServiceStoreUpgradeProposalToCircuitOperation {
+    fn upgrade_proposal_to_circuit(&self, circuit_id: &str) -> Result<(), AdminServiceStoreError>;
+}
+
+impl<'a, C> AdminServiceStoreUpgradeProposalToCircuitOperation
+    for AdminServiceStoreOperations<'a, C>
+where
+    C: Connection,
+    Self: AdminServiceStoreAddCircuitOperation
+        + AdminServiceStoreFetchProposalOperation
+        + AdminServiceStoreRemoveProposalOperation,
+{
+    fn upgrade_proposal_to_circuit(&self, circuit_id: &str) -> Result<(), AdminServiceStoreError> {
+        self.conn.transaction::<_, AdminServiceStoreError, _>(|| {
+            let proposal = self.fetch_proposal(circuit_id)?.ok_or_else(|| {
+                AdminServiceStoreError::OperationError {
+                    context: format!("A proposal with ID {} does not exist", circuit_id),
+                    source: None,
+                }
+            })?;
+            self.remove_proposal(circuit_id)?;
+
+            let nodes = proposal
+                .circuit
+                .members
+                .iter()
+                .cloned()
+                .map(CircuitNode::from)
+                .collect();
+            self.add_circuit(Circuit::from(proposal.circuit), nodes)
+        })
+    }
+}
@@ -95,6 +95,23 @@ impl fmt::Display for AdminServiceStoreError {
     }
 }
 
+#[cfg(feature = "diesel")]
+impl From<diesel::r2d2::PoolError> for AdminServiceStoreError {
+    fn from(err: diesel::r2d2::PoolError) -> AdminServiceStoreError {
+        AdminServiceStoreError::ConnectionError(Box::new(err))
+    }
+}
+
+#[cfg(feature = "diesel")]
+impl From<diesel::result::Error> for AdminServiceStoreError {
+    fn from(err: diesel::result::Error) -> AdminServiceStoreError {
+        AdminServiceStoreError::QueryError {
+            context: "A database error occurred".to_string(),
+            source: Box::new(err),
+        }
+    }
+}
+
 /// Represents errors raised while building
 #[derive(Debug)]
 pub enum BuilderError {
@@ -0,0 +1,471 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines an in-memory implementation of the `AdminServiceStore`. State held by this store is
+//! lost when the store is dropped, so it is intended for testing and for nodes that do not need
+//! to persist circuit state.
+//!
+//! The public interface includes the struct [`MemoryAdminServiceStore`].
+//!
+//! [`MemoryAdminServiceStore`]: struct.MemoryAdminServiceStore.html
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use super::{
+    AdminServiceStore, AdminServiceStoreError, Circuit, CircuitNode, CircuitPredicate,
+    CircuitProposal, Service, ServiceId,
+};
+
+/// An in-memory implementation of the `AdminServiceStore`
+#[derive(Clone, Default)]
+pub struct MemoryAdminServiceStore {
+    state: Arc<Mutex<MemoryState>>,
+}
+
+impl MemoryAdminServiceStore {
+    /// Creates a new, empty `MemoryAdminServiceStore`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock_state(&self) -> Result<MutexGuard<MemoryState>, AdminServiceStoreError> {
+        self.state
+            .lock()
+            .map_err(|_| AdminServiceStoreError::StorageError {
+                context: "Memory admin service store's internal lock was poisoned".to_string(),
+                source: None,
+            })
+    }
+}
+
+impl AdminServiceStore for MemoryAdminServiceStore {
+    fn add_proposal(&self, proposal: CircuitProposal) -> Result<(), AdminServiceStoreError> {
+        let mut state = self.lock_state()?;
+
+        if state.proposals.contains_key(&proposal.circuit_id) {
+            return Err(AdminServiceStoreError::OperationError {
+                context: format!("A proposal with ID {} already exists", proposal.circuit_id),
+                source: None,
+            });
+        }
+
+        state
+            .proposals
+            .insert(proposal.circuit_id.to_string(), proposal);
+
+        Ok(())
+    }
+
+    fn update_proposal(&self, proposal: CircuitProposal) -> Result<(), AdminServiceStoreError> {
+        let mut state = self.lock_state()?;
+
+        if !state.proposals.contains_key(&proposal.circuit_id) {
+            return Err(AdminServiceStoreError::OperationError {
+                context: format!("A proposal with ID {} does not exist", proposal.circuit_id),
+                source: None,
+            });
+        }
+
+        state
+            .proposals
+            .insert(proposal.circuit_id.to_string(), proposal);
+
+        Ok(())
+    }
+
+    fn remove_proposal(&self, proposal_id: &str) -> Result<(), AdminServiceStoreError> {
+        self.lock_state()?
+            .proposals
+            .remove(proposal_id)
+            .map(|_| ())
+            .ok_or_else(|| AdminServiceStoreError::OperationError {
+                context: format!("A proposal with ID {} does not exist", proposal_id),
+                source: None,
+            })
+    }
+
+    fn fetch_proposal(
+        &self,
+        proposal_id: &str,
+    ) -> Result<Option<CircuitProposal>, AdminServiceStoreError> {
+        Ok(self.lock_state()?.proposals.get(proposal_id).cloned())
+    }
+
+    fn list_proposals(
+        &self,
+        predicates: &[CircuitPredicate],
+    ) -> Result<Box<dyn ExactSizeIterator<Item = CircuitProposal>>, AdminServiceStoreError> {
+        let proposals = self
+            .lock_state()?
+            .proposals
+            .values()
+            .filter(|proposal| {
+                predicates
+                    .iter()
+                    .all(|predicate| predicate.apply_to_proposals(proposal))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        Ok(Box::new(proposals.into_iter()))
+    }
+
+    fn add_circuit(
+        &self,
+        circuit: Circuit,
+        nodes: Vec<CircuitNode>,
+    ) -> Result<(), AdminServiceStoreError> {
+        let mut state = self.lock_state()?;
+
+        if state.circuits.contains_key(&circuit.id) {
+            return Err(AdminServiceStoreError::OperationError {
+                context: format!("A circuit with ID {} already exists", circuit.id),
+                source: None,
+            });
+        }
+
+        for node in nodes.into_iter() {
+            if !state.nodes.contains_key(&node.id) {
+                state.nodes.insert(node.id.to_string(), node);
+            }
+        }
+
+        state.circuits.insert(circuit.id.to_string(), circuit);
+
+        Ok(())
+    }
+
+    fn update_circuit(&self, circuit: Circuit) -> Result<(), AdminServiceStoreError> {
+        let mut state = self.lock_state()?;
+
+        if !state.circuits.contains_key(&circuit.id) {
+            return Err(AdminServiceStoreError::OperationError {
+                context: format!("A circuit with ID {} does not exist", circuit.id),
+                source: None,
+            });
+        }
+
+        state.circuits.insert(circuit.id.to_string(), circuit);
+
+        Ok(())
+    }
+
+    fn remove_circuit(&self, circuit_id: &str) -> Result<(), AdminServiceStoreError> {
+        self.lock_state()?
+            .circuits
+            .remove(circuit_id)
+            .map(|_| ())
+            .ok_or_else(|| AdminServiceStoreError::OperationError {
+                context: format!("A circuit with ID {} does not exist", circuit_id),
+                source: None,
+            })
+    }
+
+    fn fetch_circuit(&self, circuit_id: &str) -> Result<Option<Circuit>, AdminServiceStoreError> {
+        Ok(self.lock_state()?.circuits.get(circuit_id).cloned())
+    }
+
+    fn list_circuits(
+        &self,
+        predicates: &[CircuitPredicate],
+    ) -> Result<Box<dyn ExactSizeIterator<Item = Circuit>>, AdminServiceStoreError> {
+        let circuits = self
+            .lock_state()?
+            .circuits
+            .values()
+            .filter(|circuit| {
+                predicates
+                    .iter()
+                    .all(|predicate| predicate.apply_to_circuit(circuit))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        Ok(Box::new(circuits.into_iter()))
+    }
+
+    fn upgrade_proposal_to_circuit(&self, circuit_id: &str) -> Result<(), AdminServiceStoreError> {
+        let mut state = self.lock_state()?;
+
+        if state.circuits.contains_key(circuit_id) {
+            return Err(AdminServiceStoreError::OperationError {
+                context: format!("A circuit with ID {} already exists", circuit_id),
+                source: None,
+            });
+        }
+
+        let proposal = state.proposals.remove(circuit_id).ok_or_else(|| {
+            AdminServiceStoreError::OperationError {
+                context: format!("A proposal with ID {} does not exist", circuit_id),
+                source: None,
+            }
+        })?;
+
+        for node in proposal.circuit.members.iter() {
+            if !state.nodes.contains_key(&node.node_id) {
+                state
+                    .nodes
+                    .insert(node.node_id.to_string(), CircuitNode::from(node.clone()));
+            }
+        }
+
+        let circuit = Circuit::from(proposal.circuit);
+        state.circuits.insert(circuit.id.to_string(), circuit);
+
+        Ok(())
+    }
+
+    fn fetch_node(&self, node_id: &str) -> Result<Option<CircuitNode>, AdminServiceStoreError> {
+        Ok(self.lock_state()?.nodes.get(node_id).cloned())
+    }
+
+    fn list_nodes(
+        &self,
+    ) -> Result<Box<dyn ExactSizeIterator<Item = CircuitNode>>, AdminServiceStoreError> {
+        let nodes = self
+            .lock_state()?
+            .nodes
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        Ok(Box::new(nodes.into_iter()))
+    }
+
+    fn fetch_service(
+        &self,
+        service_id: &ServiceId,
+    ) -> Result<Option<Service>, AdminServiceStoreError> {
+        Ok(self
+            .lock_state()?
+            .circuits
+            .get(service_id.circuit())
+            .and_then(|circuit| {
+                circuit
+                    .roster
+                    .iter()
+                    .find(|service| service.service_id == service_id.service_id())
+                    .cloned()
+            }))
+    }
+
+    fn list_services(
+        &self,
+        circuit_id: &str,
+    ) -> Result<Box<dyn ExactSizeIterator<Item = Service>>, AdminServiceStoreError> {
+        let services = self
+            .lock_state()?
+            .circuits
+            .get(circuit_id)
+            .ok_or_else(|| AdminServiceStoreError::OperationError {
+                context: format!("Circuit {} does not exist", circuit_id),
+                source: None,
+            })?
+            .roster
+            .clone();
+
+        Ok(Box::new(services.into_iter()))
+    }
+}
+
+/// The circuits, circuit proposals and nodes held by the memory admin service store
+#[derive(Default)]
+struct MemoryState {
+    proposals: BTreeMap<String, CircuitProposal>,
+    circuits: BTreeMap<String, Circuit>,
+    nodes: BTreeMap<String, CircuitNode>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::admin::store::builders::{
+        CircuitBuilder, CircuitNodeBuilder, CircuitProposalBuilder, ProposedCircuitBuilder,
+        ProposedNodeBuilder, ProposedServiceBuilder, ServiceBuilder,
+    };
+    use crate::admin::store::ProposalType;
+
+    /// Verify that proposals can be added, updated, listed and removed, and that duplicate or
+    /// missing proposals are rejected.
+    #[test]
+    fn test_proposals() {
+        let store = MemoryAdminServiceStore::new();
+        let proposal = new_proposal();
+
+        store
+            .add_proposal(proposal.clone())
+            .expect("Unable to add proposal");
+        assert!(store.add_proposal(proposal.clone()).is_err());
+
+        assert_eq!(
+            store.fetch_proposal("WBKLF-BBBBB").unwrap(),
+            Some(proposal.clone())
+        );
+        assert_eq!(
+            store
+                .list_proposals(&[CircuitPredicate::ManagmentTypeEq("test".into())])
+                .unwrap()
+                .collect::<Vec<_>>(),
+            vec![proposal.clone()]
+        );
+        assert_eq!(
+            store
+                .list_proposals(&[CircuitPredicate::ManagmentTypeEq("other".into())])
+                .unwrap()
+                .len(),
+            0
+        );
+
+        store
+            .update_proposal(proposal)
+            .expect("Unable to update proposal");
+
+        store
+            .remove_proposal("WBKLF-BBBBB")
+            .expect("Unable to remove proposal");
+        assert_eq!(store.fetch_proposal("WBKLF-BBBBB").unwrap(), None);
+        assert!(store.remove_proposal("WBKLF-BBBBB").is_err());
+        assert!(store.update_proposal(new_proposal()).is_err());
+    }
+
+    /// Verify that circuits can be added, listed and removed, and that their nodes and services
+    /// can be fetched.
+    #[test]
+    fn test_circuits() {
+        let store = MemoryAdminServiceStore::new();
+        let (circuit, node) = new_circuit();
+
+        store
+            .add_circuit(circuit.clone(), vec![node.clone()])
+            .expect("Unable to add circuit");
+        assert!(store.add_circuit(circuit.clone(), vec![]).is_err());
+
+        assert_eq!(
+            store.fetch_circuit("WBKLF-DDDDD").unwrap(),
+            Some(circuit.clone())
+        );
+        assert_eq!(
+            store
+                .list_circuits(&[CircuitPredicate::MembersInclude(
+                    vec!["new-node-000".into()]
+                )])
+                .unwrap()
+                .collect::<Vec<_>>(),
+            vec![circuit.clone()]
+        );
+        assert_eq!(store.fetch_node("new-node-000").unwrap(), Some(node));
+        assert_eq!(store.list_nodes().unwrap().len(), 1);
+
+        let service_id = ServiceId::new("WBKLF-DDDDD".into(), "a001".into());
+        assert_eq!(
+            store.fetch_service(&service_id).unwrap(),
+            Some(circuit.roster[1].clone())
+        );
+        assert_eq!(store.list_services("WBKLF-DDDDD").unwrap().len(), 2);
+        assert!(store.list_services("WBKLF-EEEEE").is_err());
+
+        store
+            .remove_circuit("WBKLF-DDDDD")
+            .expect("Unable to remove circuit");
+        assert_eq!(store.fetch_circuit("WBKLF-DDDDD").unwrap(), None);
+        assert_eq!(store.fetch_service(&service_id).unwrap(), None);
+        assert!(store.remove_circuit("WBKLF-DDDDD").is_err());
+    }
+
+    /// Verify that upgrading a proposal removes the proposal and adds the circuit, its services
+    /// and its members.
+    #[test]
+    fn test_upgrading_proposals_to_circuit() {
+        let store = MemoryAdminServiceStore::new();
+        store
+            .add_proposal(new_proposal())
+            .expect("Unable to add proposal");
+
+        store
+            .upgrade_proposal_to_circuit("WBKLF-BBBBB")
+            .expect("Unable to upgrade proposal to circuit");
+
+        assert_eq!(store.list_proposals(&[]).unwrap().len(), 0);
+        assert!(store.fetch_circuit("WBKLF-BBBBB").unwrap().is_some());
+        assert!(store.fetch_node("acme-node-000").unwrap().is_some());
+        assert!(store
+            .fetch_service(&ServiceId::new("WBKLF-BBBBB".into(), "a000".into()))
+            .unwrap()
+            .is_some());
+
+        assert!(store.upgrade_proposal_to_circuit("WBKLF-BBBBB").is_err());
+    }
+
+    fn new_proposal() -> CircuitProposal {
+        CircuitProposalBuilder::default()
+            .with_proposal_type(&ProposalType::Create)
+            .with_circuit_id("WBKLF-BBBBB")
+            .with_circuit_hash("7ddc426972710adc0b2ecd49e89a9dd805fb9206bf516079724c887bedbcdf1d")
+            .with_circuit(
+                &ProposedCircuitBuilder::default()
+                    .with_circuit_id("WBKLF-BBBBB")
+                    .with_roster(&[ProposedServiceBuilder::default()
+                        .with_service_id("a000")
+                        .with_service_type("scabbard")
+                        .with_allowed_nodes(&["acme-node-000".into()])
+                        .with_arguments(&[("peer_services".into(), "[]".into())])
+                        .build()
+                        .expect("Unable to build service")])
+                    .with_members(&[ProposedNodeBuilder::default()
+                        .with_node_id("acme-node-000")
+                        .with_endpoints(&["tcps://splinterd-node-acme:8044".into()])
+                        .build()
+                        .expect("Unable to build node")])
+                    .with_circuit_management_type("test")
+                    .build()
+                    .expect("Unable to build circuit"),
+            )
+            .with_requester(&[1, 2, 3])
+            .with_requester_node_id("acme-node-000")
+            .build()
+            .expect("Unable to build proposal")
+    }
+
+    fn new_circuit() -> (Circuit, CircuitNode) {
+        (
+            CircuitBuilder::default()
+                .with_circuit_id("WBKLF-DDDDD")
+                .with_roster(&[
+                    ServiceBuilder::default()
+                        .with_service_id("a000")
+                        .with_service_type("scabbard")
+                        .with_allowed_nodes(&["acme-node-000".into()])
+                        .build()
+                        .expect("Unable to build service"),
+                    ServiceBuilder::default()
+                        .with_service_id("a001")
+                        .with_service_type("scabbard")
+                        .with_allowed_nodes(&["new-node-000".into()])
+                        .build()
+                        .expect("Unable to build service"),
+                ])
+                .with_members(&["acme-node-000".into(), "new-node-000".into()])
+                .with_circuit_management_type("test")
+                .build()
+                .expect("Unable to build circuit"),
+            CircuitNodeBuilder::default()
+                .with_node_id("new-node-000")
+                .with_endpoints(&["tcps://splinterd-node-new:8044".into()])
+                .build()
+                .expect("Unable to build node"),
+        )
+    }
+}
@@ -18,8 +18,8 @@
 //!
 //! The public interface includes the trait [`AdminServiceStore`] and structs for
 //! [`Circuit`], [`ProposedCircuit`], [`CircuitNode`], [`ProposedNode`], [`Service`],
-//! [`ProposedService`], and [`CircuitProposal`]. A YAML backed [`YamlAdminServiceStore`] is
-//! also available.
+//! [`ProposedService`], and [`CircuitProposal`]. A YAML backed [`YamlAdminServiceStore`] and an
+//! in-memory [`MemoryAdminServiceStore`] are also available.
 //!
 //! Builders are also provided. The structs are [`CircuitBuilder`], [`ProposedCircuitBuilder`],
 //! [`CircuitNodeBuilder`], [`ProposedNodeBuilder`], [`ServiceBuilder`],
@@ -34,6 +34,7 @@
 //! [`ProposedService`]: struct.ProposedService.html
 //! [`CircuitProposal`]: struct.CircuitProposal.html
 //! [`YamlAdminServiceStore`]: yaml/struct.YamlAdminServiceStore.html
+//! [`MemoryAdminServiceStore`]: memory/struct.MemoryAdminServiceStore.html
 //!
 //! [`CircuitBuilder`]: struct.CircuitBuilder.html
 //! [`ProposedCircuitBuilder`]: struct.ProposedCircuitBuilder.html
@@ -47,6 +48,7 @@ mod builders;
 #[cfg(feature = "diesel")]
 pub mod diesel;
 pub mod error;
+pub mod memory;
 pub mod yaml;
 
 use std::cmp::Ordering;
@@ -165,6 +167,12 @@ pub enum DurabilityType {
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub enum RouteType {
     Any,
+    /// Messages between members are forwarded through the relay node, which is not a member of
+    /// the circuit.
+    #[cfg(feature = "circuit-relay")]
+    Relayed {
+        relay_node: String,
+    },
 }
 
 impl Default for RouteType {
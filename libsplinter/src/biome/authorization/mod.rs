@@ -0,0 +1,284 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides role and group based authorization of Biome users.
+//!
+//! A role grants a set of permissions. Roles are assigned to users, either directly or through
+//! the groups the users are members of. The `admin` role grants every permission; it is assigned
+//! to the first administrator by the node's operator, with `splinter user make-admin`, after
+//! which administrators can assign roles to others.
+
+pub mod store;
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "biome-api-keys")]
+use super::credentials::api_keys::ApiKeyScope;
+use store::{AuthorizationStoreError, GroupStore, RoleStore};
+
+/// The name of the built-in role that grants every permission
+pub const ADMIN_ROLE: &str = "admin";
+
+/// An action that may be granted to users through roles
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Permission {
+    /// List users and fetch users other than oneself
+    ReadUsers,
+    /// Remove, disable and enable users other than oneself
+    ManageUsers,
+    /// Manage roles, groups, and role assignments
+    ManageRoles,
+    /// Reload the node's configuration and TLS certificates
+    ManageNode,
+    /// Read circuits and proposals through the admin service
+    ReadAdmin,
+    /// Submit circuit management payloads to the admin service
+    SubmitAdmin,
+    /// Submit batches to scabbard services
+    SubmitScabbard,
+    /// Modify the node registry
+    WriteRegistry,
+}
+
+impl Permission {
+    /// Every permission, as granted by the admin role
+    pub const ALL: [Permission; 8] = [
+        Permission::ReadUsers,
+        Permission::ManageUsers,
+        Permission::ManageRoles,
+        Permission::ManageNode,
+        Permission::ReadAdmin,
+        Permission::SubmitAdmin,
+        Permission::SubmitScabbard,
+        Permission::WriteRegistry,
+    ];
+
+    /// Returns the name of the permission, as it is presented and stored
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::ReadUsers => "users:read",
+            Permission::ManageUsers => "users:manage",
+            Permission::ManageRoles => "roles:manage",
+            Permission::ManageNode => "node:manage",
+            Permission::ReadAdmin => "admin:read",
+            Permission::SubmitAdmin => "admin:submit",
+            Permission::SubmitScabbard => "scabbard:submit",
+            Permission::WriteRegistry => "registry:write",
+        }
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Permission {
+    type Err = InvalidPermissionError;
+
+    fn from_str(permission: &str) -> Result<Self, Self::Err> {
+        match permission {
+            "users:read" => Ok(Permission::ReadUsers),
+            "users:manage" => Ok(Permission::ManageUsers),
+            "roles:manage" => Ok(Permission::ManageRoles),
+            "node:manage" => Ok(Permission::ManageNode),
+            "admin:read" => Ok(Permission::ReadAdmin),
+            "admin:submit" => Ok(Permission::SubmitAdmin),
+            "scabbard:submit" => Ok(Permission::SubmitScabbard),
+            "registry:write" => Ok(Permission::WriteRegistry),
+            _ => Err(InvalidPermissionError(permission.to_string())),
+        }
+    }
+}
+
+/// The permission that a user must have been granted for their access tokens and API keys to be
+/// accepted where an API key scope is required
+#[cfg(feature = "biome-api-keys")]
+impl From<ApiKeyScope> for Permission {
+    fn from(scope: ApiKeyScope) -> Self {
+        match scope {
+            ApiKeyScope::AdminRead => Permission::ReadAdmin,
+            ApiKeyScope::AdminSubmit => Permission::SubmitAdmin,
+            ApiKeyScope::ScabbardSubmit => Permission::SubmitScabbard,
+            ApiKeyScope::RegistryWrite => Permission::WriteRegistry,
+        }
+    }
+}
+
+/// Returned when a string is not the name of a permission
+#[derive(Debug)]
+pub struct InvalidPermissionError(pub String);
+
+impl Error for InvalidPermissionError {}
+
+impl fmt::Display for InvalidPermissionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid permission: {}", self.0)
+    }
+}
+
+/// A named set of permissions
+#[derive(Clone, Debug, PartialEq)]
+pub struct Role {
+    pub name: String,
+    pub permissions: Vec<Permission>,
+}
+
+impl Role {
+    pub fn new(name: &str, permissions: Vec<Permission>) -> Self {
+        Role {
+            name: name.to_string(),
+            permissions,
+        }
+    }
+}
+
+/// A named set of users, which may be assigned roles as a whole
+#[derive(Clone, Debug, PartialEq)]
+pub struct Group {
+    pub name: String,
+    /// The IDs of the users in the group
+    pub members: Vec<String>,
+}
+
+/// Who a role is assigned to
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Assignee {
+    /// A user, by ID
+    User(String),
+    /// A group, by name
+    Group(String),
+}
+
+/// Returns the permissions granted to a user by the roles assigned to them, directly or through
+/// their groups
+///
+/// # Arguments
+///
+/// * `role_store`: The store the roles and role assignments are fetched from.
+/// * `group_store`: The store the user's groups are fetched from.
+/// * `user_id`: The ID of the user.
+///
+pub fn user_permissions(
+    role_store: &dyn RoleStore,
+    group_store: &dyn GroupStore,
+    user_id: &str,
+) -> Result<Vec<Permission>, AuthorizationStoreError> {
+    let groups = group_store.list_user_groups(user_id)?;
+    let mut permissions = role_store
+        .list_assigned_roles(user_id, &groups)?
+        .into_iter()
+        .flat_map(|role| role.permissions)
+        .collect::<Vec<_>>();
+    permissions.sort_by_key(|permission| permission.as_str());
+    permissions.dedup();
+    Ok(permissions)
+}
+
+/// Assigns the admin role to a user, creating the role if it does not exist. Assigning the role
+/// to a user who already has it has no effect.
+///
+/// This is intended for the node's operator to create the first administrator; it does not check
+/// who is making the assignment.
+///
+/// # Arguments
+///
+/// * `role_store`: The store the admin role is assigned in.
+/// * `user_id`: The ID of the user to make an administrator.
+///
+pub fn assign_admin(
+    role_store: &dyn RoleStore,
+    user_id: &str,
+) -> Result<(), AuthorizationStoreError> {
+    match role_store.add_role(Role::new(ADMIN_ROLE, Permission::ALL.to_vec())) {
+        Ok(()) | Err(AuthorizationStoreError::DuplicateError(_)) => (),
+        Err(err) => return Err(err),
+    }
+
+    role_store.assign_role(ADMIN_ROLE, &Assignee::User(user_id.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use store::memory::{MemoryGroupStore, MemoryRoleStore};
+
+    /// Verify that a user is granted the permissions of the roles assigned to them directly and
+    /// through their groups, and no others.
+    #[test]
+    fn permissions_through_roles_and_groups() {
+        let role_store = MemoryRoleStore::new();
+        let group_store = MemoryGroupStore::new();
+
+        role_store
+            .add_role(Role::new("auditor", vec![Permission::ReadUsers]))
+            .expect("Failed to add auditor role");
+        role_store
+            .add_role(Role::new("operator", vec![Permission::ManageUsers]))
+            .expect("Failed to add operator role");
+        group_store
+            .add_group("ops")
+            .expect("Failed to add ops group");
+        group_store
+            .add_group_member("ops", "user1")
+            .expect("Failed to add group member");
+
+        role_store
+            .assign_role("auditor", &Assignee::User("user1".into()))
+            .expect("Failed to assign auditor role");
+        role_store
+            .assign_role("operator", &Assignee::Group("ops".into()))
+            .expect("Failed to assign operator role");
+
+        assert_eq!(
+            user_permissions(&role_store, &group_store, "user1")
+                .expect("Failed to get permissions"),
+            vec![Permission::ManageUsers, Permission::ReadUsers]
+        );
+        assert!(user_permissions(&role_store, &group_store, "user2")
+            .expect("Failed to get permissions")
+            .is_empty());
+    }
+
+    /// Verify that assigning the admin role creates it with every permission, and that assigning
+    /// it again has no effect.
+    #[test]
+    fn assign_admin_role() {
+        let role_store = MemoryRoleStore::new();
+
+        assign_admin(&role_store, "user1").expect("Failed to assign admin to user1");
+        assign_admin(&role_store, "user1").expect("Failed to reassign admin to user1");
+        assign_admin(&role_store, "user2").expect("Failed to assign admin to user2");
+
+        assert_eq!(
+            role_store.list_roles().expect("Failed to list roles"),
+            vec![Role::new(ADMIN_ROLE, Permission::ALL.to_vec())]
+        );
+        let mut assignments = role_store
+            .list_role_assignments(ADMIN_ROLE)
+            .expect("Failed to list assignments");
+        assignments.sort_by_key(|assignee| format!("{:?}", assignee));
+        assert_eq!(
+            assignments,
+            vec![
+                Assignee::User("user1".into()),
+                Assignee::User("user2".into())
+            ]
+        );
+    }
+}
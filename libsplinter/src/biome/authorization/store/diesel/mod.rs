@@ -0,0 +1,429 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub(in crate::biome) mod models;
+mod operations;
+mod schema;
+
+use diesel::r2d2::{ConnectionManager, Pool};
+
+use crate::biome::authorization::store::{AuthorizationStoreError, GroupStore, RoleStore};
+use crate::biome::authorization::{Assignee, Group, Role};
+
+use operations::{
+    add_group::AuthorizationStoreAddGroupOperation as _,
+    add_group_member::AuthorizationStoreAddGroupMemberOperation as _,
+    add_role::AuthorizationStoreAddRoleOperation as _,
+    assign_role::AuthorizationStoreAssignRoleOperation as _,
+    list_assigned_roles::AuthorizationStoreListAssignedRolesOperation as _,
+    list_groups::AuthorizationStoreListGroupsOperation as _,
+    list_role_assignments::AuthorizationStoreListRoleAssignmentsOperation as _,
+    list_roles::AuthorizationStoreListRolesOperation as _,
+    list_user_groups::AuthorizationStoreListUserGroupsOperation as _,
+    remove_group::AuthorizationStoreRemoveGroupOperation as _,
+    remove_group_member::AuthorizationStoreRemoveGroupMemberOperation as _,
+    remove_role::AuthorizationStoreRemoveRoleOperation as _,
+    unassign_role::AuthorizationStoreUnassignRoleOperation as _, AuthorizationStoreOperations,
+};
+
+/// Manages roles and their assignments in a database.
+pub struct DieselRoleStore<C: diesel::Connection + 'static> {
+    connection_pool: Pool<ConnectionManager<C>>,
+}
+
+impl<C: diesel::Connection> DieselRoleStore<C> {
+    /// Creates a new DieselRoleStore
+    ///
+    /// # Arguments
+    ///
+    ///  * `connection_pool`: connection pool to the database
+    ///
+    pub fn new(connection_pool: Pool<ConnectionManager<C>>) -> Self {
+        DieselRoleStore { connection_pool }
+    }
+}
+
+/// Manages groups and their members in a database.
+pub struct DieselGroupStore<C: diesel::Connection + 'static> {
+    connection_pool: Pool<ConnectionManager<C>>,
+}
+
+impl<C: diesel::Connection> DieselGroupStore<C> {
+    /// Creates a new DieselGroupStore
+    ///
+    /// # Arguments
+    ///
+    ///  * `connection_pool`: connection pool to the database
+    ///
+    pub fn new(connection_pool: Pool<ConnectionManager<C>>) -> Self {
+        DieselGroupStore { connection_pool }
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl RoleStore for DieselRoleStore<diesel::pg::PgConnection> {
+    fn add_role(&self, role: Role) -> Result<(), AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?).add_role(role)
+    }
+
+    fn list_roles(&self) -> Result<Vec<Role>, AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?).list_roles()
+    }
+
+    fn remove_role(&self, name: &str) -> Result<(), AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?).remove_role(name)
+    }
+
+    fn assign_role(&self, name: &str, assignee: &Assignee) -> Result<(), AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?).assign_role(name, assignee)
+    }
+
+    fn unassign_role(
+        &self,
+        name: &str,
+        assignee: &Assignee,
+    ) -> Result<(), AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?)
+            .unassign_role(name, assignee)
+    }
+
+    fn list_role_assignments(&self, name: &str) -> Result<Vec<Assignee>, AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?).list_role_assignments(name)
+    }
+
+    fn list_assigned_roles(
+        &self,
+        user_id: &str,
+        groups: &[String],
+    ) -> Result<Vec<Role>, AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?)
+            .list_assigned_roles(user_id, groups)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl RoleStore for DieselRoleStore<diesel::sqlite::SqliteConnection> {
+    fn add_role(&self, role: Role) -> Result<(), AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?).add_role(role)
+    }
+
+    fn list_roles(&self) -> Result<Vec<Role>, AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?).list_roles()
+    }
+
+    fn remove_role(&self, name: &str) -> Result<(), AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?).remove_role(name)
+    }
+
+    fn assign_role(&self, name: &str, assignee: &Assignee) -> Result<(), AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?).assign_role(name, assignee)
+    }
+
+    fn unassign_role(
+        &self,
+        name: &str,
+        assignee: &Assignee,
+    ) -> Result<(), AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?)
+            .unassign_role(name, assignee)
+    }
+
+    fn list_role_assignments(&self, name: &str) -> Result<Vec<Assignee>, AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?).list_role_assignments(name)
+    }
+
+    fn list_assigned_roles(
+        &self,
+        user_id: &str,
+        groups: &[String],
+    ) -> Result<Vec<Role>, AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?)
+            .list_assigned_roles(user_id, groups)
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl GroupStore for DieselGroupStore<diesel::pg::PgConnection> {
+    fn add_group(&self, name: &str) -> Result<(), AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?).add_group(name)
+    }
+
+    fn list_groups(&self) -> Result<Vec<Group>, AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?).list_groups()
+    }
+
+    fn remove_group(&self, name: &str) -> Result<(), AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?).remove_group(name)
+    }
+
+    fn add_group_member(&self, name: &str, user_id: &str) -> Result<(), AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?)
+            .add_group_member(name, user_id)
+    }
+
+    fn remove_group_member(
+        &self,
+        name: &str,
+        user_id: &str,
+    ) -> Result<(), AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?)
+            .remove_group_member(name, user_id)
+    }
+
+    fn list_user_groups(&self, user_id: &str) -> Result<Vec<String>, AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?).list_user_groups(user_id)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl GroupStore for DieselGroupStore<diesel::sqlite::SqliteConnection> {
+    fn add_group(&self, name: &str) -> Result<(), AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?).add_group(name)
+    }
+
+    fn list_groups(&self) -> Result<Vec<Group>, AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?).list_groups()
+    }
+
+    fn remove_group(&self, name: &str) -> Result<(), AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?).remove_group(name)
+    }
+
+    fn add_group_member(&self, name: &str, user_id: &str) -> Result<(), AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?)
+            .add_group_member(name, user_id)
+    }
+
+    fn remove_group_member(
+        &self,
+        name: &str,
+        user_id: &str,
+    ) -> Result<(), AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?)
+            .remove_group_member(name, user_id)
+    }
+
+    fn list_user_groups(&self, user_id: &str) -> Result<Vec<String>, AuthorizationStoreError> {
+        AuthorizationStoreOperations::new(&*self.connection_pool.get()?).list_user_groups(user_id)
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+pub mod tests {
+    use super::*;
+
+    use crate::biome::authorization::Permission;
+    use crate::biome::migrations::run_sqlite_migrations;
+
+    use diesel::{
+        r2d2::{ConnectionManager, Pool},
+        sqlite::SqliteConnection,
+    };
+
+    /// Verify that a SQLite-backed `DieselRoleStore` correctly supports adding, assigning and
+    /// removing roles.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Add two roles and verify that a duplicate role is rejected.
+    /// 3. Assign the roles to a user and a group, and verify that assigning a role twice has no
+    ///    effect and that assigning a missing role fails.
+    /// 4. Verify the roles assigned to the user, with and without the group.
+    /// 5. Unassign and remove roles, and verify that their assignments are removed with them.
+    #[test]
+    fn sqlite_roles() {
+        let pool = create_connection_pool_and_migrate();
+        let store = DieselRoleStore::new(pool);
+
+        let auditor = Role::new("auditor", vec![Permission::ReadUsers]);
+        let operator = Role::new(
+            "operator",
+            vec![Permission::ReadUsers, Permission::ManageUsers],
+        );
+        store
+            .add_role(operator.clone())
+            .expect("Failed to add operator");
+        store
+            .add_role(auditor.clone())
+            .expect("Failed to add auditor");
+        match store.add_role(auditor.clone()) {
+            Err(AuthorizationStoreError::DuplicateError(_)) => {}
+            res => panic!(
+                "Expected Err(AuthorizationStoreError::DuplicateError), got {:?} instead",
+                res
+            ),
+        }
+        assert_eq!(
+            store.list_roles().expect("Failed to list roles"),
+            vec![auditor.clone(), operator.clone()]
+        );
+
+        let user = Assignee::User("user1".into());
+        let group = Assignee::Group("ops".into());
+        store
+            .assign_role("auditor", &user)
+            .expect("Failed to assign auditor");
+        store
+            .assign_role("auditor", &user)
+            .expect("Failed to assign auditor twice");
+        store
+            .assign_role("operator", &group)
+            .expect("Failed to assign operator");
+        match store.assign_role("missing", &user) {
+            Err(AuthorizationStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(AuthorizationStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+        assert_eq!(
+            store
+                .list_role_assignments("auditor")
+                .expect("Failed to list assignments"),
+            vec![user.clone()]
+        );
+
+        assert_eq!(
+            store
+                .list_assigned_roles("user1", &[])
+                .expect("Failed to list assigned roles"),
+            vec![auditor.clone()]
+        );
+        assert_eq!(
+            store
+                .list_assigned_roles("user1", &["ops".to_string()])
+                .expect("Failed to list assigned roles"),
+            vec![auditor, operator]
+        );
+
+        store
+            .unassign_role("auditor", &user)
+            .expect("Failed to unassign auditor");
+        match store.unassign_role("auditor", &user) {
+            Err(AuthorizationStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(AuthorizationStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+
+        store
+            .remove_role("operator")
+            .expect("Failed to remove operator");
+        assert!(store
+            .list_role_assignments("operator")
+            .expect("Failed to list assignments")
+            .is_empty());
+        match store.remove_role("operator") {
+            Err(AuthorizationStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(AuthorizationStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+    }
+
+    /// Verify that a SQLite-backed `DieselGroupStore` correctly supports adding groups, managing
+    /// their members and removing them.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Add two groups and verify that a duplicate group is rejected.
+    /// 3. Add members, and verify that adding a member twice has no effect and that adding a
+    ///    member to a missing group fails.
+    /// 4. Verify the groups are listed with their members, and the groups of a user.
+    /// 5. Remove a member and a group.
+    #[test]
+    fn sqlite_groups() {
+        let pool = create_connection_pool_and_migrate();
+        let store = DieselGroupStore::new(pool);
+
+        store.add_group("ops").expect("Failed to add ops");
+        store.add_group("dev").expect("Failed to add dev");
+        match store.add_group("ops") {
+            Err(AuthorizationStoreError::DuplicateError(_)) => {}
+            res => panic!(
+                "Expected Err(AuthorizationStoreError::DuplicateError), got {:?} instead",
+                res
+            ),
+        }
+
+        store
+            .add_group_member("ops", "user2")
+            .expect("Failed to add member");
+        store
+            .add_group_member("ops", "user1")
+            .expect("Failed to add member");
+        store
+            .add_group_member("ops", "user1")
+            .expect("Failed to add member twice");
+        store
+            .add_group_member("dev", "user1")
+            .expect("Failed to add member");
+        match store.add_group_member("missing", "user1") {
+            Err(AuthorizationStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(AuthorizationStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+
+        assert_eq!(
+            store.list_groups().expect("Failed to list groups"),
+            vec![
+                Group {
+                    name: "dev".into(),
+                    members: vec!["user1".into()],
+                },
+                Group {
+                    name: "ops".into(),
+                    members: vec!["user1".into(), "user2".into()],
+                },
+            ]
+        );
+        assert_eq!(
+            store
+                .list_user_groups("user1")
+                .expect("Failed to list user groups"),
+            vec!["dev".to_string(), "ops".to_string()]
+        );
+
+        store
+            .remove_group_member("ops", "user1")
+            .expect("Failed to remove member");
+        match store.remove_group_member("ops", "user1") {
+            Err(AuthorizationStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(AuthorizationStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+        store.remove_group("dev").expect("Failed to remove dev");
+        assert!(store
+            .list_user_groups("user1")
+            .expect("Failed to list user groups")
+            .is_empty());
+    }
+
+    fn create_connection_pool_and_migrate() -> Pool<ConnectionManager<SqliteConnection>> {
+        let connection_manager = ConnectionManager::<SqliteConnection>::new(":memory:");
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(connection_manager)
+            .expect("Failed to build connection pool");
+
+        run_sqlite_migrations(&*pool.get().expect("Failed to get connection for migrations"))
+            .expect("Failed to run migrations");
+
+        pool
+    }
+}
@@ -0,0 +1,45 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::schema::{role_assignments, roles, user_group_members, user_groups};
+
+/// A role, with its permissions stored as a space-separated list
+#[derive(Insertable, Queryable, PartialEq, Debug)]
+#[table_name = "roles"]
+pub struct RoleModel {
+    pub name: String,
+    pub permissions: String,
+}
+
+/// The assignment of a role to either a user or a group, as given by `assignee_type`
+#[derive(Insertable, Queryable, PartialEq, Debug)]
+#[table_name = "role_assignments"]
+pub struct RoleAssignmentModel {
+    pub role_name: String,
+    pub assignee_type: String,
+    pub assignee_id: String,
+}
+
+#[derive(Insertable, Queryable, PartialEq, Debug)]
+#[table_name = "user_groups"]
+pub struct GroupModel {
+    pub name: String,
+}
+
+#[derive(Insertable, Queryable, PartialEq, Debug)]
+#[table_name = "user_group_members"]
+pub struct GroupMemberModel {
+    pub group_name: String,
+    pub user_id: String,
+}
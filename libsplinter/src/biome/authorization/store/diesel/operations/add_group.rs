@@ -0,0 +1,74 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::AuthorizationStoreOperations;
+use crate::biome::authorization::store::diesel::models::GroupModel;
+use crate::biome::authorization::store::diesel::schema::user_groups;
+use crate::biome::authorization::store::AuthorizationStoreError;
+
+use diesel::{
+    dsl::insert_into,
+    prelude::*,
+    result::{DatabaseErrorKind, Error as QueryError},
+};
+
+pub(in crate::biome::authorization) trait AuthorizationStoreAddGroupOperation {
+    fn add_group(&self, name: &str) -> Result<(), AuthorizationStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> AuthorizationStoreAddGroupOperation
+    for AuthorizationStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn add_group(&self, name: &str) -> Result<(), AuthorizationStoreError> {
+        insert_into(user_groups::table)
+            .values(GroupModel {
+                name: name.to_string(),
+            })
+            .execute(self.conn)
+            .map(|_| ())
+            .map_err(|err| match err {
+                QueryError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                    AuthorizationStoreError::DuplicateError(format!("Group {}", name))
+                }
+                _ => AuthorizationStoreError::OperationError {
+                    context: "Failed to add group".to_string(),
+                    source: Box::new(err),
+                },
+            })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> AuthorizationStoreAddGroupOperation
+    for AuthorizationStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn add_group(&self, name: &str) -> Result<(), AuthorizationStoreError> {
+        insert_into(user_groups::table)
+            .values(GroupModel {
+                name: name.to_string(),
+            })
+            .execute(self.conn)
+            .map(|_| ())
+            .map_err(|err| match err {
+                QueryError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                    AuthorizationStoreError::DuplicateError(format!("Group {}", name))
+                }
+                _ => AuthorizationStoreError::OperationError {
+                    context: "Failed to add group".to_string(),
+                    source: Box::new(err),
+                },
+            })
+    }
+}
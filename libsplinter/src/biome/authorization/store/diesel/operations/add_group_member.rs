@@ -0,0 +1,104 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::AuthorizationStoreOperations;
+use crate::biome::authorization::store::diesel::models::GroupMemberModel;
+use crate::biome::authorization::store::diesel::schema::{user_group_members, user_groups};
+use crate::biome::authorization::store::AuthorizationStoreError;
+
+use diesel::{
+    dsl::insert_into,
+    prelude::*,
+    result::{DatabaseErrorKind, Error as QueryError},
+};
+
+pub(in crate::biome::authorization) trait AuthorizationStoreAddGroupMemberOperation {
+    fn add_group_member(&self, name: &str, user_id: &str) -> Result<(), AuthorizationStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> AuthorizationStoreAddGroupMemberOperation
+    for AuthorizationStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn add_group_member(&self, name: &str, user_id: &str) -> Result<(), AuthorizationStoreError> {
+        let group_exists = user_groups::table
+            .find(name)
+            .count()
+            .get_result::<i64>(self.conn)
+            .map_err(|err| AuthorizationStoreError::QueryError {
+                context: "Failed check for existing group".to_string(),
+                source: Box::new(err),
+            })?
+            > 0;
+        if !group_exists {
+            return Err(AuthorizationStoreError::NotFoundError(format!(
+                "Group {}",
+                name
+            )));
+        }
+
+        match insert_into(user_group_members::table)
+            .values(GroupMemberModel {
+                group_name: name.to_string(),
+                user_id: user_id.to_string(),
+            })
+            .execute(self.conn)
+        {
+            // The user is already a member
+            Ok(_) | Err(QueryError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => Ok(()),
+            Err(err) => Err(AuthorizationStoreError::OperationError {
+                context: "Failed to add group member".to_string(),
+                source: Box::new(err),
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> AuthorizationStoreAddGroupMemberOperation
+    for AuthorizationStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn add_group_member(&self, name: &str, user_id: &str) -> Result<(), AuthorizationStoreError> {
+        let group_exists = user_groups::table
+            .find(name)
+            .count()
+            .get_result::<i64>(self.conn)
+            .map_err(|err| AuthorizationStoreError::QueryError {
+                context: "Failed check for existing group".to_string(),
+                source: Box::new(err),
+            })?
+            > 0;
+        if !group_exists {
+            return Err(AuthorizationStoreError::NotFoundError(format!(
+                "Group {}",
+                name
+            )));
+        }
+
+        match insert_into(user_group_members::table)
+            .values(GroupMemberModel {
+                group_name: name.to_string(),
+                user_id: user_id.to_string(),
+            })
+            .execute(self.conn)
+        {
+            // The user is already a member
+            Ok(_) | Err(QueryError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => Ok(()),
+            Err(err) => Err(AuthorizationStoreError::OperationError {
+                context: "Failed to add group member".to_string(),
+                source: Box::new(err),
+            }),
+        }
+    }
+}
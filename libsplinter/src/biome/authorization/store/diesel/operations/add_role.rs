@@ -0,0 +1,72 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{to_role_model, AuthorizationStoreOperations};
+use crate::biome::authorization::store::diesel::schema::roles;
+use crate::biome::authorization::store::AuthorizationStoreError;
+use crate::biome::authorization::Role;
+
+use diesel::{
+    dsl::insert_into,
+    prelude::*,
+    result::{DatabaseErrorKind, Error as QueryError},
+};
+
+pub(in crate::biome::authorization) trait AuthorizationStoreAddRoleOperation {
+    fn add_role(&self, role: Role) -> Result<(), AuthorizationStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> AuthorizationStoreAddRoleOperation
+    for AuthorizationStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn add_role(&self, role: Role) -> Result<(), AuthorizationStoreError> {
+        let name = role.name.clone();
+        insert_into(roles::table)
+            .values(to_role_model(role))
+            .execute(self.conn)
+            .map(|_| ())
+            .map_err(|err| match err {
+                QueryError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                    AuthorizationStoreError::DuplicateError(format!("Role {}", name))
+                }
+                _ => AuthorizationStoreError::OperationError {
+                    context: "Failed to add role".to_string(),
+                    source: Box::new(err),
+                },
+            })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> AuthorizationStoreAddRoleOperation
+    for AuthorizationStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn add_role(&self, role: Role) -> Result<(), AuthorizationStoreError> {
+        let name = role.name.clone();
+        insert_into(roles::table)
+            .values(to_role_model(role))
+            .execute(self.conn)
+            .map(|_| ())
+            .map_err(|err| match err {
+                QueryError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                    AuthorizationStoreError::DuplicateError(format!("Role {}", name))
+                }
+                _ => AuthorizationStoreError::OperationError {
+                    context: "Failed to add role".to_string(),
+                    source: Box::new(err),
+                },
+            })
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{assignee_columns, AuthorizationStoreOperations};
+use crate::biome::authorization::store::diesel::models::RoleAssignmentModel;
+use crate::biome::authorization::store::diesel::schema::{role_assignments, roles};
+use crate::biome::authorization::store::AuthorizationStoreError;
+use crate::biome::authorization::Assignee;
+
+use diesel::{
+    dsl::insert_into,
+    prelude::*,
+    result::{DatabaseErrorKind, Error as QueryError},
+};
+
+pub(in crate::biome::authorization) trait AuthorizationStoreAssignRoleOperation {
+    fn assign_role(&self, name: &str, assignee: &Assignee) -> Result<(), AuthorizationStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> AuthorizationStoreAssignRoleOperation
+    for AuthorizationStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn assign_role(&self, name: &str, assignee: &Assignee) -> Result<(), AuthorizationStoreError> {
+        let role_exists = roles::table
+            .find(name)
+            .count()
+            .get_result::<i64>(self.conn)
+            .map_err(|err| AuthorizationStoreError::QueryError {
+                context: "Failed check for existing role".to_string(),
+                source: Box::new(err),
+            })?
+            > 0;
+        if !role_exists {
+            return Err(AuthorizationStoreError::NotFoundError(format!(
+                "Role {}",
+                name
+            )));
+        }
+
+        let (assignee_type, assignee_id) = assignee_columns(assignee);
+        match insert_into(role_assignments::table)
+            .values(RoleAssignmentModel {
+                role_name: name.to_string(),
+                assignee_type: assignee_type.to_string(),
+                assignee_id: assignee_id.to_string(),
+            })
+            .execute(self.conn)
+        {
+            // The role is already assigned
+            Ok(_) | Err(QueryError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => Ok(()),
+            Err(err) => Err(AuthorizationStoreError::OperationError {
+                context: "Failed to assign role".to_string(),
+                source: Box::new(err),
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> AuthorizationStoreAssignRoleOperation
+    for AuthorizationStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn assign_role(&self, name: &str, assignee: &Assignee) -> Result<(), AuthorizationStoreError> {
+        let role_exists = roles::table
+            .find(name)
+            .count()
+            .get_result::<i64>(self.conn)
+            .map_err(|err| AuthorizationStoreError::QueryError {
+                context: "Failed check for existing role".to_string(),
+                source: Box::new(err),
+            })?
+            > 0;
+        if !role_exists {
+            return Err(AuthorizationStoreError::NotFoundError(format!(
+                "Role {}",
+                name
+            )));
+        }
+
+        let (assignee_type, assignee_id) = assignee_columns(assignee);
+        match insert_into(role_assignments::table)
+            .values(RoleAssignmentModel {
+                role_name: name.to_string(),
+                assignee_type: assignee_type.to_string(),
+                assignee_id: assignee_id.to_string(),
+            })
+            .execute(self.conn)
+        {
+            // The role is already assigned
+            Ok(_) | Err(QueryError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => Ok(()),
+            Err(err) => Err(AuthorizationStoreError::OperationError {
+                context: "Failed to assign role".to_string(),
+                source: Box::new(err),
+            }),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{to_role, AuthorizationStoreOperations, GROUP_ASSIGNEE, USER_ASSIGNEE};
+use crate::biome::authorization::store::diesel::models::RoleModel;
+use crate::biome::authorization::store::diesel::schema::{role_assignments, roles};
+use crate::biome::authorization::store::AuthorizationStoreError;
+use crate::biome::authorization::Role;
+
+use diesel::prelude::*;
+
+pub(in crate::biome::authorization) trait AuthorizationStoreListAssignedRolesOperation {
+    fn list_assigned_roles(
+        &self,
+        user_id: &str,
+        groups: &[String],
+    ) -> Result<Vec<Role>, AuthorizationStoreError>;
+}
+
+impl<'a, C> AuthorizationStoreListAssignedRolesOperation for AuthorizationStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn list_assigned_roles(
+        &self,
+        user_id: &str,
+        groups: &[String],
+    ) -> Result<Vec<Role>, AuthorizationStoreError> {
+        let assigned_roles = role_assignments::table
+            .filter(
+                role_assignments::assignee_type
+                    .eq(USER_ASSIGNEE)
+                    .and(role_assignments::assignee_id.eq(user_id))
+                    .or(role_assignments::assignee_type
+                        .eq(GROUP_ASSIGNEE)
+                        .and(role_assignments::assignee_id.eq_any(groups))),
+            )
+            .select(role_assignments::role_name);
+
+        let roles = roles::table
+            .filter(roles::name.eq_any(assigned_roles))
+            .order(roles::name.asc())
+            .load::<RoleModel>(self.conn)
+            .map_err(|err| AuthorizationStoreError::QueryError {
+                context: "Failed to list assigned roles".to_string(),
+                source: Box::new(err),
+            })?;
+
+        Ok(roles.into_iter().map(to_role).collect())
+    }
+}
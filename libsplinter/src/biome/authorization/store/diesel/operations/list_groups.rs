@@ -0,0 +1,64 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use super::AuthorizationStoreOperations;
+use crate::biome::authorization::store::diesel::models::{GroupMemberModel, GroupModel};
+use crate::biome::authorization::store::diesel::schema::{user_group_members, user_groups};
+use crate::biome::authorization::store::AuthorizationStoreError;
+use crate::biome::authorization::Group;
+
+use diesel::prelude::*;
+
+pub(in crate::biome::authorization) trait AuthorizationStoreListGroupsOperation {
+    fn list_groups(&self) -> Result<Vec<Group>, AuthorizationStoreError>;
+}
+
+impl<'a, C> AuthorizationStoreListGroupsOperation for AuthorizationStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn list_groups(&self) -> Result<Vec<Group>, AuthorizationStoreError> {
+        let groups = user_groups::table
+            .load::<GroupModel>(self.conn)
+            .map_err(|err| AuthorizationStoreError::QueryError {
+                context: "Failed to list groups".to_string(),
+                source: Box::new(err),
+            })?;
+        let members = user_group_members::table
+            .order(user_group_members::user_id.asc())
+            .load::<GroupMemberModel>(self.conn)
+            .map_err(|err| AuthorizationStoreError::QueryError {
+                context: "Failed to list group members".to_string(),
+                source: Box::new(err),
+            })?;
+
+        let mut members_by_group: BTreeMap<String, Vec<String>> = groups
+            .into_iter()
+            .map(|group| (group.name, vec![]))
+            .collect();
+        for member in members {
+            if let Some(group_members) = members_by_group.get_mut(&member.group_name) {
+                group_members.push(member.user_id);
+            }
+        }
+
+        Ok(members_by_group
+            .into_iter()
+            .map(|(name, members)| Group { name, members })
+            .collect())
+    }
+}
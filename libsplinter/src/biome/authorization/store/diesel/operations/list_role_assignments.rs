@@ -0,0 +1,47 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{to_assignee, AuthorizationStoreOperations};
+use crate::biome::authorization::store::diesel::models::RoleAssignmentModel;
+use crate::biome::authorization::store::diesel::schema::role_assignments;
+use crate::biome::authorization::store::AuthorizationStoreError;
+use crate::biome::authorization::Assignee;
+
+use diesel::prelude::*;
+
+pub(in crate::biome::authorization) trait AuthorizationStoreListRoleAssignmentsOperation {
+    fn list_role_assignments(&self, name: &str) -> Result<Vec<Assignee>, AuthorizationStoreError>;
+}
+
+impl<'a, C> AuthorizationStoreListRoleAssignmentsOperation for AuthorizationStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn list_role_assignments(&self, name: &str) -> Result<Vec<Assignee>, AuthorizationStoreError> {
+        let assignments = role_assignments::table
+            .filter(role_assignments::role_name.eq(name))
+            .order((
+                role_assignments::assignee_type.asc(),
+                role_assignments::assignee_id.asc(),
+            ))
+            .load::<RoleAssignmentModel>(self.conn)
+            .map_err(|err| AuthorizationStoreError::QueryError {
+                context: "Failed to list role assignments".to_string(),
+                source: Box::new(err),
+            })?;
+
+        Ok(assignments.into_iter().filter_map(to_assignee).collect())
+    }
+}
@@ -0,0 +1,43 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{to_role, AuthorizationStoreOperations};
+use crate::biome::authorization::store::diesel::models::RoleModel;
+use crate::biome::authorization::store::diesel::schema::roles;
+use crate::biome::authorization::store::AuthorizationStoreError;
+use crate::biome::authorization::Role;
+
+use diesel::prelude::*;
+
+pub(in crate::biome::authorization) trait AuthorizationStoreListRolesOperation {
+    fn list_roles(&self) -> Result<Vec<Role>, AuthorizationStoreError>;
+}
+
+impl<'a, C> AuthorizationStoreListRolesOperation for AuthorizationStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn list_roles(&self) -> Result<Vec<Role>, AuthorizationStoreError> {
+        let roles = roles::table
+            .order(roles::name.asc())
+            .load::<RoleModel>(self.conn)
+            .map_err(|err| AuthorizationStoreError::QueryError {
+                context: "Failed to list roles".to_string(),
+                source: Box::new(err),
+            })?;
+
+        Ok(roles.into_iter().map(to_role).collect())
+    }
+}
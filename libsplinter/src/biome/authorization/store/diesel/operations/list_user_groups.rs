@@ -0,0 +1,41 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::AuthorizationStoreOperations;
+use crate::biome::authorization::store::diesel::schema::user_group_members;
+use crate::biome::authorization::store::AuthorizationStoreError;
+
+use diesel::prelude::*;
+
+pub(in crate::biome::authorization) trait AuthorizationStoreListUserGroupsOperation {
+    fn list_user_groups(&self, user_id: &str) -> Result<Vec<String>, AuthorizationStoreError>;
+}
+
+impl<'a, C> AuthorizationStoreListUserGroupsOperation for AuthorizationStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn list_user_groups(&self, user_id: &str) -> Result<Vec<String>, AuthorizationStoreError> {
+        user_group_members::table
+            .filter(user_group_members::user_id.eq(user_id))
+            .select(user_group_members::group_name)
+            .order(user_group_members::group_name.asc())
+            .load::<String>(self.conn)
+            .map_err(|err| AuthorizationStoreError::QueryError {
+                context: "Failed to list user groups".to_string(),
+                source: Box::new(err),
+            })
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub(super) mod add_group;
+pub(super) mod add_group_member;
+pub(super) mod add_role;
+pub(super) mod assign_role;
+pub(super) mod list_assigned_roles;
+pub(super) mod list_groups;
+pub(super) mod list_role_assignments;
+pub(super) mod list_roles;
+pub(super) mod list_user_groups;
+pub(super) mod remove_group;
+pub(super) mod remove_group_member;
+pub(super) mod remove_role;
+pub(super) mod unassign_role;
+
+use crate::biome::authorization::store::diesel::models::{RoleAssignmentModel, RoleModel};
+use crate::biome::authorization::{Assignee, Role};
+
+const USER_ASSIGNEE: &str = "user";
+const GROUP_ASSIGNEE: &str = "group";
+
+pub(super) struct AuthorizationStoreOperations<'a, C> {
+    conn: &'a C,
+}
+
+impl<'a, C> AuthorizationStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+{
+    pub fn new(conn: &'a C) -> Self {
+        AuthorizationStoreOperations { conn }
+    }
+}
+
+/// Returns the assignee type and ID an assignee is stored as
+fn assignee_columns(assignee: &Assignee) -> (&'static str, &str) {
+    match assignee {
+        Assignee::User(user_id) => (USER_ASSIGNEE, user_id),
+        Assignee::Group(name) => (GROUP_ASSIGNEE, name),
+    }
+}
+
+/// Converts a stored role assignment to its assignee, or returns `None` if its type is unknown
+fn to_assignee(assignment: RoleAssignmentModel) -> Option<Assignee> {
+    match assignment.assignee_type.as_str() {
+        USER_ASSIGNEE => Some(Assignee::User(assignment.assignee_id)),
+        GROUP_ASSIGNEE => Some(Assignee::Group(assignment.assignee_id)),
+        _ => None,
+    }
+}
+
+/// Converts a stored role to a role; permissions that are no longer recognized are dropped, so
+/// that they grant nothing
+fn to_role(role: RoleModel) -> Role {
+    Role {
+        name: role.name,
+        permissions: role
+            .permissions
+            .split_whitespace()
+            .filter_map(|permission| permission.parse().ok())
+            .collect(),
+    }
+}
+
+fn to_role_model(role: Role) -> RoleModel {
+    RoleModel {
+        name: role.name,
+        permissions: role
+            .permissions
+            .iter()
+            .map(|permission| permission.as_str())
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
@@ -0,0 +1,50 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::AuthorizationStoreOperations;
+use crate::biome::authorization::store::diesel::schema::user_group_members;
+use crate::biome::authorization::store::AuthorizationStoreError;
+
+use diesel::{dsl::delete, prelude::*};
+
+pub(in crate::biome::authorization) trait AuthorizationStoreRemoveGroupMemberOperation {
+    fn remove_group_member(&self, name: &str, user_id: &str)
+        -> Result<(), AuthorizationStoreError>;
+}
+
+impl<'a, C> AuthorizationStoreRemoveGroupMemberOperation for AuthorizationStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+{
+    fn remove_group_member(
+        &self,
+        name: &str,
+        user_id: &str,
+    ) -> Result<(), AuthorizationStoreError> {
+        let removed = delete(user_group_members::table.find((name, user_id)))
+            .execute(self.conn)
+            .map_err(|err| AuthorizationStoreError::OperationError {
+                context: "Failed to remove group member".to_string(),
+                source: Box::new(err),
+            })?;
+
+        if removed == 0 {
+            return Err(AuthorizationStoreError::NotFoundError(format!(
+                "User {} is not a member of group {}",
+                user_id, name
+            )));
+        }
+        Ok(())
+    }
+}
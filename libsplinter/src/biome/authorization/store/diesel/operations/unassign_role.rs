@@ -0,0 +1,52 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{assignee_columns, AuthorizationStoreOperations};
+use crate::biome::authorization::store::diesel::schema::role_assignments;
+use crate::biome::authorization::store::AuthorizationStoreError;
+use crate::biome::authorization::Assignee;
+
+use diesel::{dsl::delete, prelude::*};
+
+pub(in crate::biome::authorization) trait AuthorizationStoreUnassignRoleOperation {
+    fn unassign_role(&self, name: &str, assignee: &Assignee)
+        -> Result<(), AuthorizationStoreError>;
+}
+
+impl<'a, C> AuthorizationStoreUnassignRoleOperation for AuthorizationStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+{
+    fn unassign_role(
+        &self,
+        name: &str,
+        assignee: &Assignee,
+    ) -> Result<(), AuthorizationStoreError> {
+        let (assignee_type, assignee_id) = assignee_columns(assignee);
+        let removed = delete(role_assignments::table.find((name, assignee_type, assignee_id)))
+            .execute(self.conn)
+            .map_err(|err| AuthorizationStoreError::OperationError {
+                context: "Failed to unassign role".to_string(),
+                source: Box::new(err),
+            })?;
+
+        if removed == 0 {
+            return Err(AuthorizationStoreError::NotFoundError(format!(
+                "Role {} is not assigned to {:?}",
+                name, assignee
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+table! {
+    roles (name) {
+        name -> Text,
+        permissions -> Text,
+    }
+}
+
+table! {
+    role_assignments (role_name, assignee_type, assignee_id) {
+        role_name -> Text,
+        assignee_type -> Text,
+        assignee_id -> Text,
+    }
+}
+
+table! {
+    user_groups (name) {
+        name -> Text,
+    }
+}
+
+table! {
+    user_group_members (group_name, user_id) {
+        group_name -> Text,
+        user_id -> Text,
+    }
+}
+
+joinable!(role_assignments -> roles (role_name));
+joinable!(user_group_members -> user_groups (group_name));
+
+allow_tables_to_appear_in_same_query!(roles, role_assignments, user_groups, user_group_members);
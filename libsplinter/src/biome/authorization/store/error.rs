@@ -0,0 +1,100 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::fmt;
+
+/// Represents RoleStore and GroupStore errors
+#[derive(Debug)]
+pub enum AuthorizationStoreError {
+    /// Represents CRUD operations failures
+    OperationError {
+        context: String,
+        source: Box<dyn Error>,
+    },
+    /// Represents database query failures
+    QueryError {
+        context: String,
+        source: Box<dyn Error>,
+    },
+    /// Represents general failures in the database
+    StorageError {
+        context: String,
+        source: Option<Box<dyn Error>>,
+    },
+    /// Represents an issue connecting to the database
+    ConnectionError(Box<dyn Error>),
+    /// Represents the specific case where a query returns no records
+    NotFoundError(String),
+    /// Represents an attempt to add a role or group whose name is already in use
+    DuplicateError(String),
+}
+
+impl Error for AuthorizationStoreError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AuthorizationStoreError::OperationError { source, .. } => Some(&**source),
+            AuthorizationStoreError::QueryError { source, .. } => Some(&**source),
+            AuthorizationStoreError::StorageError {
+                source: Some(source),
+                ..
+            } => Some(&**source),
+            AuthorizationStoreError::StorageError { source: None, .. } => None,
+            AuthorizationStoreError::ConnectionError(err) => Some(&**err),
+            AuthorizationStoreError::NotFoundError(_) => None,
+            AuthorizationStoreError::DuplicateError(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for AuthorizationStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AuthorizationStoreError::OperationError { context, source } => {
+                write!(f, "failed to perform operation: {}: {}", context, source)
+            }
+            AuthorizationStoreError::QueryError { context, source } => {
+                write!(f, "failed query: {}: {}", context, source)
+            }
+            AuthorizationStoreError::StorageError {
+                context,
+                source: Some(source),
+            } => write!(
+                f,
+                "the underlying storage returned an error: {}: {}",
+                context, source
+            ),
+            AuthorizationStoreError::StorageError {
+                context,
+                source: None,
+            } => write!(f, "the underlying storage returned an error: {}", context),
+            AuthorizationStoreError::ConnectionError(ref s) => {
+                write!(f, "failed to connect to underlying storage: {}", s)
+            }
+            AuthorizationStoreError::NotFoundError(ref s) => {
+                write!(f, "not found: {}", s)
+            }
+            AuthorizationStoreError::DuplicateError(ref s) => {
+                write!(f, "already exists: {}", s)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "diesel")]
+impl From<diesel::r2d2::PoolError> for AuthorizationStoreError {
+    fn from(err: diesel::r2d2::PoolError) -> AuthorizationStoreError {
+        AuthorizationStoreError::ConnectionError(Box::new(err))
+    }
+}
@@ -0,0 +1,233 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::biome::authorization::store::{AuthorizationStoreError, GroupStore, RoleStore};
+use crate::biome::authorization::{Assignee, Group, Role};
+
+/// A role, along with who it is assigned to
+struct StoredRole {
+    role: Role,
+    assignees: Vec<Assignee>,
+}
+
+#[derive(Default, Clone)]
+pub struct MemoryRoleStore {
+    inner: Arc<Mutex<BTreeMap<String, StoredRole>>>,
+}
+
+impl MemoryRoleStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> Result<MutexGuard<BTreeMap<String, StoredRole>>, AuthorizationStoreError> {
+        self.inner
+            .lock()
+            .map_err(|_| AuthorizationStoreError::StorageError {
+                context: "Cannot access role store: mutex lock poisoned".to_string(),
+                source: None,
+            })
+    }
+}
+
+impl RoleStore for MemoryRoleStore {
+    fn add_role(&self, role: Role) -> Result<(), AuthorizationStoreError> {
+        let mut inner = self.lock()?;
+
+        if inner.contains_key(&role.name) {
+            return Err(AuthorizationStoreError::DuplicateError(format!(
+                "Role {}",
+                role.name
+            )));
+        }
+
+        inner.insert(
+            role.name.clone(),
+            StoredRole {
+                role,
+                assignees: vec![],
+            },
+        );
+        Ok(())
+    }
+
+    fn list_roles(&self) -> Result<Vec<Role>, AuthorizationStoreError> {
+        Ok(self
+            .lock()?
+            .values()
+            .map(|stored| stored.role.clone())
+            .collect())
+    }
+
+    fn remove_role(&self, name: &str) -> Result<(), AuthorizationStoreError> {
+        self.lock()?
+            .remove(name)
+            .map(|_| ())
+            .ok_or_else(|| AuthorizationStoreError::NotFoundError(format!("Role {}", name)))
+    }
+
+    fn assign_role(&self, name: &str, assignee: &Assignee) -> Result<(), AuthorizationStoreError> {
+        let mut inner = self.lock()?;
+        let stored = inner
+            .get_mut(name)
+            .ok_or_else(|| AuthorizationStoreError::NotFoundError(format!("Role {}", name)))?;
+
+        if !stored.assignees.contains(assignee) {
+            stored.assignees.push(assignee.clone());
+        }
+        Ok(())
+    }
+
+    fn unassign_role(
+        &self,
+        name: &str,
+        assignee: &Assignee,
+    ) -> Result<(), AuthorizationStoreError> {
+        let mut inner = self.lock()?;
+        let stored = inner
+            .get_mut(name)
+            .ok_or_else(|| AuthorizationStoreError::NotFoundError(format!("Role {}", name)))?;
+
+        let assignees = stored.assignees.len();
+        stored.assignees.retain(|existing| existing != assignee);
+        if stored.assignees.len() == assignees {
+            return Err(AuthorizationStoreError::NotFoundError(format!(
+                "Role {} is not assigned to {:?}",
+                name, assignee
+            )));
+        }
+        Ok(())
+    }
+
+    fn list_role_assignments(&self, name: &str) -> Result<Vec<Assignee>, AuthorizationStoreError> {
+        Ok(self
+            .lock()?
+            .get(name)
+            .map(|stored| stored.assignees.clone())
+            .unwrap_or_default())
+    }
+
+    fn list_assigned_roles(
+        &self,
+        user_id: &str,
+        groups: &[String],
+    ) -> Result<Vec<Role>, AuthorizationStoreError> {
+        Ok(self
+            .lock()?
+            .values()
+            .filter(|stored| {
+                stored.assignees.iter().any(|assignee| match assignee {
+                    Assignee::User(id) => id == user_id,
+                    Assignee::Group(name) => groups.contains(name),
+                })
+            })
+            .map(|stored| stored.role.clone())
+            .collect())
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct MemoryGroupStore {
+    inner: Arc<Mutex<BTreeMap<String, BTreeSet<String>>>>,
+}
+
+impl MemoryGroupStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(
+        &self,
+    ) -> Result<MutexGuard<BTreeMap<String, BTreeSet<String>>>, AuthorizationStoreError> {
+        self.inner
+            .lock()
+            .map_err(|_| AuthorizationStoreError::StorageError {
+                context: "Cannot access group store: mutex lock poisoned".to_string(),
+                source: None,
+            })
+    }
+}
+
+impl GroupStore for MemoryGroupStore {
+    fn add_group(&self, name: &str) -> Result<(), AuthorizationStoreError> {
+        let mut inner = self.lock()?;
+
+        if inner.contains_key(name) {
+            return Err(AuthorizationStoreError::DuplicateError(format!(
+                "Group {}",
+                name
+            )));
+        }
+
+        inner.insert(name.to_string(), BTreeSet::new());
+        Ok(())
+    }
+
+    fn list_groups(&self) -> Result<Vec<Group>, AuthorizationStoreError> {
+        Ok(self
+            .lock()?
+            .iter()
+            .map(|(name, members)| Group {
+                name: name.to_string(),
+                members: members.iter().cloned().collect(),
+            })
+            .collect())
+    }
+
+    fn remove_group(&self, name: &str) -> Result<(), AuthorizationStoreError> {
+        self.lock()?
+            .remove(name)
+            .map(|_| ())
+            .ok_or_else(|| AuthorizationStoreError::NotFoundError(format!("Group {}", name)))
+    }
+
+    fn add_group_member(&self, name: &str, user_id: &str) -> Result<(), AuthorizationStoreError> {
+        self.lock()?
+            .get_mut(name)
+            .ok_or_else(|| AuthorizationStoreError::NotFoundError(format!("Group {}", name)))?
+            .insert(user_id.to_string());
+        Ok(())
+    }
+
+    fn remove_group_member(
+        &self,
+        name: &str,
+        user_id: &str,
+    ) -> Result<(), AuthorizationStoreError> {
+        let removed = self
+            .lock()?
+            .get_mut(name)
+            .ok_or_else(|| AuthorizationStoreError::NotFoundError(format!("Group {}", name)))?
+            .remove(user_id);
+        if !removed {
+            return Err(AuthorizationStoreError::NotFoundError(format!(
+                "User {} is not a member of group {}",
+                user_id, name
+            )));
+        }
+        Ok(())
+    }
+
+    fn list_user_groups(&self, user_id: &str) -> Result<Vec<String>, AuthorizationStoreError> {
+        Ok(self
+            .lock()?
+            .iter()
+            .filter(|(_, members)| members.contains(user_id))
+            .map(|(name, _)| name.to_string())
+            .collect())
+    }
+}
@@ -0,0 +1,239 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines the stores for roles, role assignments and groups.
+
+#[cfg(feature = "diesel")]
+pub(in crate::biome) mod diesel;
+mod error;
+pub(in crate::biome) mod memory;
+
+use super::{Assignee, Group, Role};
+
+pub use error::AuthorizationStoreError;
+
+/// Defines methods for managing roles and assigning them to users and groups, without defining a
+/// storage strategy
+pub trait RoleStore: Send + Sync {
+    /// Adds a role
+    ///
+    /// # Arguments
+    ///
+    ///  * `role` - The role to add
+    ///
+    /// # Errors
+    ///
+    /// Returns an AuthorizationStoreError if the implementation cannot add the role or if a role
+    /// with the same name already exists
+    fn add_role(&self, role: Role) -> Result<(), AuthorizationStoreError>;
+
+    /// Lists all roles, ordered by name
+    fn list_roles(&self) -> Result<Vec<Role>, AuthorizationStoreError>;
+
+    /// Removes a role, along with all of its assignments
+    ///
+    /// # Arguments
+    ///
+    ///  * `name` - The name of the role
+    ///
+    /// # Errors
+    ///
+    /// Returns an AuthorizationStoreError if the implementation cannot remove the role or if the
+    /// role does not exist
+    fn remove_role(&self, name: &str) -> Result<(), AuthorizationStoreError>;
+
+    /// Assigns a role to a user or group; assigning a role that is already assigned has no effect
+    ///
+    /// # Arguments
+    ///
+    ///  * `name` - The name of the role
+    ///  * `assignee` - The user or group the role is assigned to
+    ///
+    /// # Errors
+    ///
+    /// Returns an AuthorizationStoreError if the implementation cannot assign the role or if the
+    /// role does not exist
+    fn assign_role(&self, name: &str, assignee: &Assignee) -> Result<(), AuthorizationStoreError>;
+
+    /// Removes the assignment of a role to a user or group
+    ///
+    /// # Arguments
+    ///
+    ///  * `name` - The name of the role
+    ///  * `assignee` - The user or group the role is assigned to
+    ///
+    /// # Errors
+    ///
+    /// Returns an AuthorizationStoreError if the implementation cannot remove the assignment or
+    /// if the role is not assigned to the assignee
+    fn unassign_role(&self, name: &str, assignee: &Assignee)
+        -> Result<(), AuthorizationStoreError>;
+
+    /// Lists the users and groups a role is assigned to
+    ///
+    /// # Arguments
+    ///
+    ///  * `name` - The name of the role
+    fn list_role_assignments(&self, name: &str) -> Result<Vec<Assignee>, AuthorizationStoreError>;
+
+    /// Lists the roles assigned to a user directly or to any of the given groups, ordered by name
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The ID of the user
+    ///  * `groups` - The names of the groups the user is a member of
+    fn list_assigned_roles(
+        &self,
+        user_id: &str,
+        groups: &[String],
+    ) -> Result<Vec<Role>, AuthorizationStoreError>;
+}
+
+impl<RS> RoleStore for Box<RS>
+where
+    RS: RoleStore + ?Sized,
+{
+    fn add_role(&self, role: Role) -> Result<(), AuthorizationStoreError> {
+        (**self).add_role(role)
+    }
+
+    fn list_roles(&self) -> Result<Vec<Role>, AuthorizationStoreError> {
+        (**self).list_roles()
+    }
+
+    fn remove_role(&self, name: &str) -> Result<(), AuthorizationStoreError> {
+        (**self).remove_role(name)
+    }
+
+    fn assign_role(&self, name: &str, assignee: &Assignee) -> Result<(), AuthorizationStoreError> {
+        (**self).assign_role(name, assignee)
+    }
+
+    fn unassign_role(
+        &self,
+        name: &str,
+        assignee: &Assignee,
+    ) -> Result<(), AuthorizationStoreError> {
+        (**self).unassign_role(name, assignee)
+    }
+
+    fn list_role_assignments(&self, name: &str) -> Result<Vec<Assignee>, AuthorizationStoreError> {
+        (**self).list_role_assignments(name)
+    }
+
+    fn list_assigned_roles(
+        &self,
+        user_id: &str,
+        groups: &[String],
+    ) -> Result<Vec<Role>, AuthorizationStoreError> {
+        (**self).list_assigned_roles(user_id, groups)
+    }
+}
+
+/// Defines methods for managing groups of users, without defining a storage strategy
+pub trait GroupStore: Send + Sync {
+    /// Adds an empty group
+    ///
+    /// # Arguments
+    ///
+    ///  * `name` - The name of the group
+    ///
+    /// # Errors
+    ///
+    /// Returns an AuthorizationStoreError if the implementation cannot add the group or if a
+    /// group with the same name already exists
+    fn add_group(&self, name: &str) -> Result<(), AuthorizationStoreError>;
+
+    /// Lists all groups along with their members, ordered by name
+    fn list_groups(&self) -> Result<Vec<Group>, AuthorizationStoreError>;
+
+    /// Removes a group
+    ///
+    /// # Arguments
+    ///
+    ///  * `name` - The name of the group
+    ///
+    /// # Errors
+    ///
+    /// Returns an AuthorizationStoreError if the implementation cannot remove the group or if the
+    /// group does not exist
+    fn remove_group(&self, name: &str) -> Result<(), AuthorizationStoreError>;
+
+    /// Adds a user to a group; adding a user that is already a member has no effect
+    ///
+    /// # Arguments
+    ///
+    ///  * `name` - The name of the group
+    ///  * `user_id` - The ID of the user
+    ///
+    /// # Errors
+    ///
+    /// Returns an AuthorizationStoreError if the implementation cannot add the member or if the
+    /// group does not exist
+    fn add_group_member(&self, name: &str, user_id: &str) -> Result<(), AuthorizationStoreError>;
+
+    /// Removes a user from a group
+    ///
+    /// # Arguments
+    ///
+    ///  * `name` - The name of the group
+    ///  * `user_id` - The ID of the user
+    ///
+    /// # Errors
+    ///
+    /// Returns an AuthorizationStoreError if the implementation cannot remove the member or if
+    /// the user is not a member of the group
+    fn remove_group_member(&self, name: &str, user_id: &str)
+        -> Result<(), AuthorizationStoreError>;
+
+    /// Lists the names of the groups a user is a member of
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The ID of the user
+    fn list_user_groups(&self, user_id: &str) -> Result<Vec<String>, AuthorizationStoreError>;
+}
+
+impl<GS> GroupStore for Box<GS>
+where
+    GS: GroupStore + ?Sized,
+{
+    fn add_group(&self, name: &str) -> Result<(), AuthorizationStoreError> {
+        (**self).add_group(name)
+    }
+
+    fn list_groups(&self) -> Result<Vec<Group>, AuthorizationStoreError> {
+        (**self).list_groups()
+    }
+
+    fn remove_group(&self, name: &str) -> Result<(), AuthorizationStoreError> {
+        (**self).remove_group(name)
+    }
+
+    fn add_group_member(&self, name: &str, user_id: &str) -> Result<(), AuthorizationStoreError> {
+        (**self).add_group_member(name, user_id)
+    }
+
+    fn remove_group_member(
+        &self,
+        name: &str,
+        user_id: &str,
+    ) -> Result<(), AuthorizationStoreError> {
+        (**self).remove_group_member(name, user_id)
+    }
+
+    fn list_user_groups(&self, user_id: &str) -> Result<Vec<String>, AuthorizationStoreError> {
+        (**self).list_user_groups(user_id)
+    }
+}
@@ -0,0 +1,238 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides long-lived API keys, which authenticate machine clients as a user without the user's
+//! password.
+//!
+//! An API key is presented as `<id>.<secret>`. Only the hash of the secret is stored, so the key
+//! itself is only available when it is created.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use openssl::sha::sha256;
+
+use crate::base62::generate_random_base62_string;
+use crate::hex::to_hex;
+
+const ID_LENGTH: usize = 16;
+const SECRET_LENGTH: usize = 43; // ~256 bits of entropy
+
+/// A permission that may be granted to an API key
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ApiKeyScope {
+    /// Read circuits and proposals through the admin service
+    AdminRead,
+    /// Submit circuit management payloads to the admin service
+    AdminSubmit,
+    /// Submit batches to scabbard services
+    ScabbardSubmit,
+    /// Modify the node registry
+    RegistryWrite,
+}
+
+impl ApiKeyScope {
+    /// Returns the name of the scope, as it is presented and stored
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyScope::AdminRead => "admin:read",
+            ApiKeyScope::AdminSubmit => "admin:submit",
+            ApiKeyScope::ScabbardSubmit => "scabbard:submit",
+            ApiKeyScope::RegistryWrite => "registry:write",
+        }
+    }
+}
+
+impl fmt::Display for ApiKeyScope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ApiKeyScope {
+    type Err = InvalidScopeError;
+
+    fn from_str(scope: &str) -> Result<Self, Self::Err> {
+        match scope {
+            "admin:read" => Ok(ApiKeyScope::AdminRead),
+            "admin:submit" => Ok(ApiKeyScope::AdminSubmit),
+            "scabbard:submit" => Ok(ApiKeyScope::ScabbardSubmit),
+            "registry:write" => Ok(ApiKeyScope::RegistryWrite),
+            _ => Err(InvalidScopeError(scope.to_string())),
+        }
+    }
+}
+
+/// Returned when a string is not the name of an API key scope
+#[derive(Debug)]
+pub struct InvalidScopeError(pub String);
+
+impl Error for InvalidScopeError {}
+
+impl fmt::Display for InvalidScopeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid API key scope: {}", self.0)
+    }
+}
+
+/// A stored API key. Only the hash of the key's secret is stored; the key itself is only returned
+/// to the user when it is created.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiKey {
+    /// The public identifier of the key, which is also the first part of the key itself
+    pub id: String,
+    /// The ID of the user the key authenticates as
+    pub user_id: String,
+    /// A name for the key, chosen by the user
+    pub display_name: String,
+    /// The hex-encoded SHA-256 hash of the key's secret
+    pub secret_hash: String,
+    /// The permissions granted to the key
+    pub scopes: Vec<ApiKeyScope>,
+    /// The time the key was created
+    pub created: SystemTime,
+    /// The time after which the key may no longer be used, if it expires
+    pub expires: Option<SystemTime>,
+}
+
+impl ApiKey {
+    /// Generates a new random API key for the given user. Returns the key, which is to be
+    /// returned to the user, along with the `ApiKey` that is to be stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id`: The ID of the user the key authenticates as.
+    /// * `display_name`: A name for the key.
+    /// * `scopes`: The permissions granted to the key.
+    /// * `duration`: How long the key may be used for, or `None` if it does not expire.
+    ///
+    pub fn generate(
+        user_id: &str,
+        display_name: &str,
+        scopes: Vec<ApiKeyScope>,
+        duration: Option<Duration>,
+    ) -> (String, Self) {
+        let id = generate_random_base62_string(ID_LENGTH);
+        let secret = generate_random_base62_string(SECRET_LENGTH);
+
+        // Times are stored with millisecond precision
+        let since_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let created = UNIX_EPOCH + Duration::from_millis(since_epoch.as_millis() as u64);
+
+        let api_key = ApiKey {
+            id: id.clone(),
+            user_id: user_id.to_string(),
+            display_name: display_name.to_string(),
+            secret_hash: hash_secret(&secret),
+            scopes,
+            created,
+            expires: duration.map(|duration| created + duration),
+        };
+
+        (format!("{}.{}", id, secret), api_key)
+    }
+
+    /// Returns `true` if the given secret is the secret of this key
+    pub fn verify_secret(&self, secret: &str) -> bool {
+        openssl::memcmp::eq(hash_secret(secret).as_bytes(), self.secret_hash.as_bytes())
+    }
+
+    /// Returns `true` if the key may no longer be used
+    pub fn is_expired(&self) -> bool {
+        self.expires
+            .map(|expires| SystemTime::now() > expires)
+            .unwrap_or(false)
+    }
+
+    /// Returns `true` if the key has been granted the given scope
+    pub fn has_scope(&self, scope: ApiKeyScope) -> bool {
+        self.scopes.contains(&scope)
+    }
+}
+
+/// Splits an API key into its ID and its secret, or returns `None` if it is not well-formed
+pub fn parse_api_key(key: &str) -> Option<(&str, &str)> {
+    let mut parts = key.splitn(2, '.');
+    match (parts.next(), parts.next()) {
+        (Some(id), Some(secret)) if !id.is_empty() && !secret.is_empty() => Some((id, secret)),
+        _ => None,
+    }
+}
+
+fn hash_secret(secret: &str) -> String {
+    to_hex(&sha256(secret.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that a generated key can be parsed and verified against the stored `ApiKey`, and
+    /// that a different secret is rejected.
+    #[test]
+    fn generate_and_verify() {
+        let (key, api_key) = ApiKey::generate(
+            "user",
+            "ci",
+            vec![ApiKeyScope::AdminRead, ApiKeyScope::ScabbardSubmit],
+            None,
+        );
+
+        let (id, secret) = parse_api_key(&key).expect("Failed to parse key");
+        assert_eq!(id, api_key.id);
+        assert!(api_key.verify_secret(secret));
+        assert!(!api_key.verify_secret("not-the-secret"));
+        assert!(!api_key.is_expired());
+        assert!(api_key.has_scope(ApiKeyScope::ScabbardSubmit));
+        assert!(!api_key.has_scope(ApiKeyScope::RegistryWrite));
+
+        assert_eq!(parse_api_key("no-separator"), None);
+        assert_eq!(parse_api_key(".secret"), None);
+    }
+
+    /// Verify that a key whose duration has passed is expired.
+    #[test]
+    fn expiry() {
+        let (_, mut api_key) =
+            ApiKey::generate("user", "ci", vec![], Some(Duration::from_secs(60)));
+        assert!(!api_key.is_expired());
+
+        api_key.expires = Some(SystemTime::now() - Duration::from_secs(1));
+        assert!(api_key.is_expired());
+    }
+
+    /// Verify that scopes round-trip through their names, and that unknown names are rejected.
+    #[test]
+    fn scope_names() {
+        for scope in &[
+            ApiKeyScope::AdminRead,
+            ApiKeyScope::AdminSubmit,
+            ApiKeyScope::ScabbardSubmit,
+            ApiKeyScope::RegistryWrite,
+        ] {
+            assert_eq!(
+                scope
+                    .as_str()
+                    .parse::<ApiKeyScope>()
+                    .expect("Failed to parse"),
+                *scope
+            );
+        }
+        assert!("admin:write".parse::<ApiKeyScope>().is_err());
+    }
+}
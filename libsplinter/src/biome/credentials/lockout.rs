@@ -0,0 +1,404 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides throttling of failed login attempts, with an increasing delay between attempts and a
+//! temporary lockout once too many attempts have failed.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime};
+
+use super::store::{CredentialsStore, CredentialsStoreError};
+
+const BASE_DELAY: Duration = Duration::from_secs(1);
+const MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// The consecutive failed login attempts for a user or a client.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoginAttempts {
+    /// The number of consecutive failed attempts
+    pub failed_attempts: u32,
+    /// The time of the most recent failed attempt
+    pub last_failure: SystemTime,
+    /// The time the lockout ends, if the failed attempts have reached the lockout threshold
+    pub locked_until: Option<SystemTime>,
+}
+
+/// Determines how long a user or client must wait between failed login attempts.
+///
+/// After each failed attempt, the next attempt is delayed for twice as long as the one before,
+/// starting at one second and up to one minute. Once the number of failed attempts reaches the
+/// threshold, attempts are refused until the lockout duration has passed. Failed attempts are
+/// forgotten once the lockout duration has passed without another failure.
+#[derive(Clone, Debug)]
+pub struct LockoutPolicy {
+    threshold: u32,
+    lockout_duration: Duration,
+}
+
+impl LockoutPolicy {
+    /// Creates a new policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold`: The number of consecutive failed attempts that cause a lockout; `0`
+    ///   disables the lockout, leaving only the delay between attempts.
+    /// * `lockout_duration`: How long a lockout lasts.
+    ///
+    pub fn new(threshold: u32, lockout_duration: Duration) -> Self {
+        LockoutPolicy {
+            threshold,
+            lockout_duration,
+        }
+    }
+
+    /// Returns how long to wait before another attempt is allowed, or `None` if an attempt is
+    /// allowed now.
+    pub fn retry_after(&self, attempts: &LoginAttempts, now: SystemTime) -> Option<Duration> {
+        if self.is_expired(attempts, now) {
+            return None;
+        }
+        let allowed_at = attempts
+            .locked_until
+            .unwrap_or_else(|| attempts.last_failure + delay(attempts.failed_attempts));
+        allowed_at
+            .duration_since(now)
+            .ok()
+            .filter(|wait| *wait > Duration::from_secs(0))
+    }
+
+    /// Returns the attempts after recording a failed attempt at the given time.
+    pub fn record_failure(
+        &self,
+        attempts: Option<LoginAttempts>,
+        now: SystemTime,
+    ) -> LoginAttempts {
+        let failed_attempts = match attempts {
+            Some(ref attempts) if !self.is_expired(attempts, now) => attempts.failed_attempts + 1,
+            _ => 1,
+        };
+        let locked_until = if self.threshold > 0 && failed_attempts >= self.threshold {
+            Some(now + self.lockout_duration)
+        } else {
+            None
+        };
+
+        LoginAttempts {
+            failed_attempts,
+            last_failure: now,
+            locked_until,
+        }
+    }
+
+    /// Returns whether the attempts no longer count against the user or client, either because
+    /// their lockout has ended or because they are older than the lockout duration.
+    fn is_expired(&self, attempts: &LoginAttempts, now: SystemTime) -> bool {
+        match attempts.locked_until {
+            Some(locked_until) => locked_until <= now,
+            None => attempts.last_failure + self.lockout_duration <= now,
+        }
+    }
+}
+
+/// Returns the delay required after the given number of consecutive failed attempts.
+fn delay(failed_attempts: u32) -> Duration {
+    if failed_attempts == 0 {
+        return Duration::from_secs(0);
+    }
+    // Past 2^6 seconds the delay is capped anyway, so larger shifts are not needed
+    let factor = 1 << (failed_attempts - 1).min(6);
+    (BASE_DELAY * factor).min(MAX_DELAY)
+}
+
+/// Tracks failed login attempts per user and per client IP address.
+///
+/// The attempts for each user are persisted in the credentials store, so that a lockout survives
+/// a restart and is shared by every instance using the same store. The attempts for each client
+/// are kept in memory.
+pub struct LoginThrottle {
+    credentials_store: Arc<dyn CredentialsStore>,
+    user_policy: LockoutPolicy,
+    client_policy: LockoutPolicy,
+    client_attempts: Mutex<HashMap<IpAddr, LoginAttempts>>,
+}
+
+impl LoginThrottle {
+    /// Creates a new throttle.
+    ///
+    /// # Arguments
+    ///
+    /// * `credentials_store`: The store used to persist the attempts for each user.
+    /// * `user_policy`: The policy applied to the attempts for each user.
+    /// * `client_policy`: The policy applied to the attempts from each client IP address.
+    ///
+    pub fn new(
+        credentials_store: Arc<dyn CredentialsStore>,
+        user_policy: LockoutPolicy,
+        client_policy: LockoutPolicy,
+    ) -> Self {
+        LoginThrottle {
+            credentials_store,
+            user_policy,
+            client_policy,
+            client_attempts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns how long to wait before a login attempt for the given user from the given client
+    /// is allowed, or `None` if the attempt is allowed now.
+    pub fn check(
+        &self,
+        user_id: Option<&str>,
+        client: Option<IpAddr>,
+    ) -> Result<Option<Duration>, CredentialsStoreError> {
+        let now = SystemTime::now();
+        let mut retry_after = None;
+
+        if let Some(client) = client {
+            let client_attempts = self.lock_client_attempts()?;
+            if let Some(attempts) = client_attempts.get(&client) {
+                retry_after = retry_after.max(self.client_policy.retry_after(attempts, now));
+            }
+        }
+
+        if let Some(user_id) = user_id {
+            match self.credentials_store.fetch_login_attempts(user_id) {
+                Ok(attempts) => {
+                    retry_after = retry_after.max(self.user_policy.retry_after(&attempts, now))
+                }
+                Err(CredentialsStoreError::NotFoundError(_)) => (),
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(retry_after)
+    }
+
+    /// Records a failed login attempt for the given user from the given client, returning the
+    /// user's updated attempts.
+    pub fn record_failure(
+        &self,
+        user_id: Option<&str>,
+        client: Option<IpAddr>,
+    ) -> Result<Option<LoginAttempts>, CredentialsStoreError> {
+        let now = SystemTime::now();
+
+        if let Some(client) = client {
+            let mut client_attempts = self.lock_client_attempts()?;
+            // Forget clients whose attempts no longer count, so the map does not grow without
+            // bound
+            let client_policy = &self.client_policy;
+            client_attempts.retain(|_, attempts| !client_policy.is_expired(attempts, now));
+            let attempts = client_attempts.remove(&client);
+            client_attempts.insert(client, client_policy.record_failure(attempts, now));
+        }
+
+        match user_id {
+            Some(user_id) => {
+                let attempts = match self.credentials_store.fetch_login_attempts(user_id) {
+                    Ok(attempts) => Some(attempts),
+                    Err(CredentialsStoreError::NotFoundError(_)) => None,
+                    Err(err) => return Err(err),
+                };
+                let attempts = self.user_policy.record_failure(attempts, now);
+                self.credentials_store
+                    .set_login_attempts(user_id, attempts.clone())?;
+                Ok(Some(attempts))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Records a successful login for the given user, clearing their failed attempts.
+    ///
+    /// The client's failed attempts are kept, so that a client cannot reset its count by logging
+    /// in to an account it controls.
+    pub fn record_success(&self, user_id: &str) -> Result<(), CredentialsStoreError> {
+        match self.credentials_store.remove_login_attempts(user_id) {
+            Ok(()) | Err(CredentialsStoreError::NotFoundError(_)) => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn lock_client_attempts(
+        &self,
+    ) -> Result<MutexGuard<'_, HashMap<IpAddr, LoginAttempts>>, CredentialsStoreError> {
+        self.client_attempts
+            .lock()
+            .map_err(|_| CredentialsStoreError::StorageError {
+                context: "Cannot access login attempts: mutex lock poisoned".to_string(),
+                source: None,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::biome::credentials::store::{
+        memory::MemoryCredentialsStore, CredentialsBuilder, PasswordEncryptionCost,
+    };
+
+    /// Verify that the delay between attempts doubles after each failure, up to the maximum, and
+    /// that the failures are forgotten once the lockout duration has passed.
+    #[test]
+    fn test_policy_delay() {
+        let policy = LockoutPolicy::new(0, Duration::from_secs(900));
+        let start = SystemTime::now();
+
+        let attempts = policy.record_failure(None, start);
+        assert_eq!(attempts.failed_attempts, 1);
+        assert_eq!(attempts.locked_until, None);
+        assert_eq!(
+            policy.retry_after(&attempts, start),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(
+            policy.retry_after(&attempts, start + Duration::from_secs(1)),
+            None
+        );
+
+        let attempts = policy.record_failure(Some(attempts), start);
+        assert_eq!(
+            policy.retry_after(&attempts, start),
+            Some(Duration::from_secs(2))
+        );
+
+        let mut attempts = attempts;
+        for _ in 0..10 {
+            attempts = policy.record_failure(Some(attempts), start);
+        }
+        assert_eq!(attempts.failed_attempts, 12);
+        assert_eq!(attempts.locked_until, None);
+        assert_eq!(policy.retry_after(&attempts, start), Some(MAX_DELAY));
+
+        let later = start + Duration::from_secs(900);
+        assert_eq!(policy.retry_after(&attempts, later), None);
+        assert_eq!(
+            policy.record_failure(Some(attempts), later).failed_attempts,
+            1
+        );
+    }
+
+    /// Verify that reaching the threshold locks out further attempts for the lockout duration,
+    /// after which the failures are forgotten.
+    #[test]
+    fn test_policy_lockout() {
+        let policy = LockoutPolicy::new(3, Duration::from_secs(900));
+        let start = SystemTime::now();
+
+        let mut attempts = policy.record_failure(None, start);
+        attempts = policy.record_failure(Some(attempts), start);
+        assert_eq!(attempts.locked_until, None);
+        attempts = policy.record_failure(Some(attempts), start);
+        assert_eq!(
+            attempts.locked_until,
+            Some(start + Duration::from_secs(900))
+        );
+        assert_eq!(
+            policy.retry_after(&attempts, start + Duration::from_secs(100)),
+            Some(Duration::from_secs(800))
+        );
+
+        let later = start + Duration::from_secs(900);
+        assert_eq!(policy.retry_after(&attempts, later), None);
+        let attempts = policy.record_failure(Some(attempts), later);
+        assert_eq!(attempts.failed_attempts, 1);
+        assert_eq!(attempts.locked_until, None);
+    }
+
+    /// Verify that the throttle persists the attempts for a user, tracks the attempts from a
+    /// client separately, and clears only the user's attempts on success.
+    #[test]
+    fn test_throttle() {
+        let store = MemoryCredentialsStore::new();
+        store
+            .add_credentials(
+                CredentialsBuilder::default()
+                    .with_user_id("id1")
+                    .with_username("user1")
+                    .with_password("pwd1")
+                    .with_password_encryption_cost(PasswordEncryptionCost::Low)
+                    .build()
+                    .expect("Failed to build credentials"),
+            )
+            .expect("Failed to add credentials");
+        let store: Arc<dyn CredentialsStore> = Arc::new(store);
+
+        let throttle = LoginThrottle::new(
+            store.clone(),
+            LockoutPolicy::new(2, Duration::from_secs(900)),
+            LockoutPolicy::new(10, Duration::from_secs(900)),
+        );
+        let client: IpAddr = "127.0.0.1".parse().expect("Failed to parse address");
+        let other_client: IpAddr = "127.0.0.2".parse().expect("Failed to parse address");
+
+        assert_eq!(
+            throttle
+                .check(Some("id1"), Some(client))
+                .expect("Failed to check"),
+            None
+        );
+
+        throttle
+            .record_failure(Some("id1"), Some(client))
+            .expect("Failed to record failure");
+        let attempts = throttle
+            .record_failure(Some("id1"), Some(client))
+            .expect("Failed to record failure")
+            .expect("No attempts returned");
+        assert_eq!(attempts.failed_attempts, 2);
+        assert!(attempts.locked_until.is_some());
+        assert_eq!(
+            store
+                .fetch_login_attempts("id1")
+                .expect("Failed to fetch attempts"),
+            attempts
+        );
+
+        // The user is locked out from every client
+        assert!(throttle
+            .check(Some("id1"), Some(other_client))
+            .expect("Failed to check")
+            .is_some());
+        // The client is delayed for any user
+        assert!(throttle
+            .check(None, Some(client))
+            .expect("Failed to check")
+            .is_some());
+        assert_eq!(
+            throttle
+                .check(None, Some(other_client))
+                .expect("Failed to check"),
+            None
+        );
+
+        throttle
+            .record_success("id1")
+            .expect("Failed to record success");
+        match store.fetch_login_attempts("id1") {
+            Err(CredentialsStoreError::NotFoundError(_)) => (),
+            res => panic!(
+                "Expected Err(CredentialsStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+        assert!(throttle
+            .check(None, Some(client))
+            .expect("Failed to check")
+            .is_some());
+    }
+}
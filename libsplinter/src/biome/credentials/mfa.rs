@@ -0,0 +1,345 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides RFC 6238 time-based one-time passwords (TOTP) and recovery codes, used as a second
+//! factor when logging in.
+
+use std::error::Error;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use openssl::hash::MessageDigest;
+use openssl::pkey::PKey;
+use openssl::rand::rand_bytes;
+use openssl::sha::sha256;
+use openssl::sign::Signer;
+
+use crate::hex::to_hex;
+
+const SECRET_LENGTH: usize = 20; // 160 bits, as recommended by RFC 4226
+const TIME_STEP: u64 = 30;
+const CODE_DIGITS: u32 = 6;
+// Codes from one time step before or after the current time step are accepted, to allow for
+// clock drift and for codes entered near the end of a time step
+const ALLOWED_DRIFT: u64 = 1;
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_LENGTH: usize = 5; // in bytes; 10 hex characters
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// A user's TOTP secret.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MfaSecret {
+    /// The ID of the user the secret belongs to
+    pub user_id: String,
+    /// The base32-encoded shared secret
+    pub secret: String,
+    /// Whether the user has confirmed enrollment by verifying a code. Login only requires a code
+    /// once the secret is enabled.
+    pub enabled: bool,
+    /// The last time step a code was accepted for; codes for this or earlier time steps are
+    /// rejected so that a code may not be replayed
+    pub last_used_step: Option<u64>,
+}
+
+impl MfaSecret {
+    /// Generates a new random secret for the given user. The secret is not enabled.
+    pub fn generate(user_id: &str) -> Result<Self, MfaError> {
+        let mut secret = [0; SECRET_LENGTH];
+        rand_bytes(&mut secret)
+            .map_err(|err| MfaError::new_with_source("Unable to generate secret", Box::new(err)))?;
+
+        Ok(MfaSecret {
+            user_id: user_id.to_string(),
+            secret: base32_encode(&secret),
+            enabled: false,
+            last_used_step: None,
+        })
+    }
+
+    /// Returns the `otpauth://` URI used to provision the secret in an authenticator app,
+    /// typically by rendering it as a QR code.
+    ///
+    /// # Arguments
+    ///
+    /// * `issuer`: The name of the service the secret is used with.
+    /// * `username`: The username of the user the secret belongs to.
+    ///
+    pub fn provisioning_uri(&self, issuer: &str, username: &str) -> String {
+        format!(
+            "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+            uri_encode(issuer),
+            uri_encode(username),
+            self.secret,
+            uri_encode(issuer),
+            CODE_DIGITS,
+            TIME_STEP
+        )
+    }
+
+    /// Returns the code for the secret at the given time, as an authenticator app would display
+    /// it.
+    pub fn generate_code(&self, time: SystemTime) -> Result<String, MfaError> {
+        let key = base32_decode(&self.secret)?;
+        let code = totp_code(&key, time_step(time)?)?;
+        Ok(format!("{:0width$}", code, width = CODE_DIGITS as usize))
+    }
+
+    /// Checks a code against the secret at the given time. Returns the time step the code was
+    /// valid for, which should be stored as the `last_used_step`, or `None` if the code is not
+    /// valid or has already been used.
+    pub fn verify_code(&self, code: &str, time: SystemTime) -> Result<Option<u64>, MfaError> {
+        let code = code.trim();
+        let code = match code.parse::<u32>() {
+            Ok(value) if code.len() == CODE_DIGITS as usize => value,
+            _ => return Ok(None),
+        };
+
+        let key = base32_decode(&self.secret)?;
+        let current_step = time_step(time)?;
+
+        for step in current_step.saturating_sub(ALLOWED_DRIFT)..=current_step + ALLOWED_DRIFT {
+            if let Some(last_used_step) = self.last_used_step {
+                if step <= last_used_step {
+                    continue;
+                }
+            }
+            if totp_code(&key, step)? == code {
+                return Ok(Some(step));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Generates a set of single-use recovery codes, which may be used in place of a TOTP code.
+/// Returns the codes, which are to be given to the user, along with their hashes, which are to be
+/// stored.
+pub fn generate_recovery_codes() -> Result<(Vec<String>, Vec<String>), MfaError> {
+    let mut codes = Vec::with_capacity(RECOVERY_CODE_COUNT);
+    for _ in 0..RECOVERY_CODE_COUNT {
+        let mut bytes = [0; RECOVERY_CODE_LENGTH];
+        rand_bytes(&mut bytes).map_err(|err| {
+            MfaError::new_with_source("Unable to generate recovery code", Box::new(err))
+        })?;
+        codes.push(to_hex(&bytes));
+    }
+    let hashes = codes.iter().map(|code| hash_recovery_code(code)).collect();
+
+    Ok((codes, hashes))
+}
+
+/// Returns the hex-encoded SHA-256 hash of a recovery code, as it is stored
+pub fn hash_recovery_code(code: &str) -> String {
+    to_hex(&sha256(code.trim().to_lowercase().as_bytes()))
+}
+
+/// Returns the time step that the given time falls in
+fn time_step(time: SystemTime) -> Result<u64, MfaError> {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() / TIME_STEP)
+        .map_err(|err| MfaError::new_with_source("Invalid time", Box::new(err)))
+}
+
+/// Computes the TOTP code for the given time step, as defined by RFC 4226 and RFC 6238
+fn totp_code(key: &[u8], step: u64) -> Result<u32, MfaError> {
+    let hmac = PKey::hmac(key)
+        .and_then(|pkey| {
+            let mut signer = Signer::new(MessageDigest::sha1(), &pkey)?;
+            signer.update(&step.to_be_bytes())?;
+            signer.sign_to_vec()
+        })
+        .map_err(|err| MfaError::new_with_source("Unable to compute code", Box::new(err)))?;
+
+    // Dynamic truncation
+    let offset = (hmac[hmac.len() - 1] & 0x0f) as usize;
+    let binary = (u32::from(hmac[offset]) & 0x7f) << 24
+        | u32::from(hmac[offset + 1]) << 16
+        | u32::from(hmac[offset + 2]) << 8
+        | u32::from(hmac[offset + 3]);
+
+    Ok(binary % 10u32.pow(CODE_DIGITS))
+}
+
+/// Encodes bytes as unpadded RFC 4648 base32, as expected by authenticator apps
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity((bytes.len() * 8 + 4) / 5);
+    let mut buffer: u16 = 0;
+    let mut bits = 0;
+    for byte in bytes {
+        buffer = (buffer << 8) | u16::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        encoded.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    encoded
+}
+
+/// Decodes RFC 4648 base32, ignoring padding
+fn base32_decode(encoded: &str) -> Result<Vec<u8>, MfaError> {
+    let mut decoded = Vec::with_capacity(encoded.len() * 5 / 8);
+    let mut buffer: u16 = 0;
+    let mut bits = 0;
+    for c in encoded.trim_end_matches('=').bytes() {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|a| *a == c.to_ascii_uppercase())
+            .ok_or_else(|| MfaError::new("Secret is not valid base32"))?;
+        buffer = (buffer << 5) | value as u16;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(decoded)
+}
+
+/// Percent-encodes all but the unreserved characters of a URI component
+fn uri_encode(component: &str) -> String {
+    component
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Returned when a TOTP secret or recovery code could not be generated or checked
+#[derive(Debug)]
+pub struct MfaError {
+    context: String,
+    source: Option<Box<dyn Error>>,
+}
+
+impl MfaError {
+    /// Builds an `MfaError` with a `context`. This sets the `source` field to `None`.
+    pub fn new(context: &str) -> Self {
+        Self {
+            context: context.into(),
+            source: None,
+        }
+    }
+
+    /// Builds an `MfaError` with a `context`, and a `source` error.
+    pub fn new_with_source(context: &str, err: Box<dyn Error>) -> Self {
+        Self {
+            context: context.into(),
+            source: Some(err),
+        }
+    }
+}
+
+impl Error for MfaError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_ref().map(|err| &**err)
+    }
+}
+
+impl fmt::Display for MfaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ref err) = self.source {
+            write!(f, "{}: {}", self.context, err)
+        } else {
+            f.write_str(&self.context)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    /// Verify that codes match the SHA-1 test vectors from RFC 6238, Appendix B, truncated to
+    /// six digits.
+    #[test]
+    fn test_totp_rfc_vectors() {
+        let key = b"12345678901234567890";
+        for (time, expected) in &[
+            (59, 287_082),
+            (1_111_111_109, 81_804),
+            (1_111_111_111, 50_471),
+            (1_234_567_890, 5_924),
+            (2_000_000_000, 279_037),
+        ] {
+            assert_eq!(totp_code(key, time / TIME_STEP).unwrap(), *expected);
+        }
+    }
+
+    /// Verify that base32 encoding round-trips and matches the RFC 4648 test vectors.
+    #[test]
+    fn test_base32() {
+        assert_eq!(base32_encode(b"foobar"), "MZXW6YTBOI");
+        assert_eq!(base32_decode("MZXW6YTBOI======").unwrap(), b"foobar");
+
+        let secret = MfaSecret::generate("user").unwrap();
+        assert_eq!(base32_decode(&secret.secret).unwrap().len(), SECRET_LENGTH);
+    }
+
+    /// Verify that a code is accepted within the allowed drift, rejected outside it, and rejected
+    /// once its time step has been used.
+    #[test]
+    fn test_verify_code() {
+        let mut secret = MfaSecret {
+            user_id: "user".into(),
+            secret: base32_encode(b"12345678901234567890"),
+            enabled: true,
+            last_used_step: None,
+        };
+        let time = UNIX_EPOCH + Duration::from_secs(59);
+
+        assert_eq!(secret.generate_code(time).unwrap(), "287082");
+        assert_eq!(secret.verify_code("287082", time).unwrap(), Some(1));
+        assert_eq!(
+            secret
+                .verify_code("287082", time + Duration::from_secs(TIME_STEP))
+                .unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            secret
+                .verify_code("287082", time + Duration::from_secs(3 * TIME_STEP))
+                .unwrap(),
+            None
+        );
+        assert_eq!(secret.verify_code("not a code", time).unwrap(), None);
+
+        secret.last_used_step = Some(1);
+        assert_eq!(secret.verify_code("287082", time).unwrap(), None);
+    }
+
+    /// Verify that recovery codes are unique and hashed consistently.
+    #[test]
+    fn test_recovery_codes() {
+        let (codes, hashes) = generate_recovery_codes().unwrap();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+        assert_eq!(hashes[0], hash_recovery_code(&codes[0].to_uppercase()));
+
+        let mut unique = codes.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), codes.len());
+    }
+}
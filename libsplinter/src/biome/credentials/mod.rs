@@ -15,4 +15,12 @@
 //! Defines a basic API to register and authenticate a User using a username and a password.
 //! Not recommended for use in production.
 
+#[cfg(feature = "biome-api-keys")]
+pub mod api_keys;
+#[cfg(feature = "biome-lockout")]
+pub mod lockout;
+#[cfg(feature = "biome-mfa")]
+pub mod mfa;
+#[cfg(feature = "biome-password-reset")]
+pub mod password_reset;
 pub mod store;
@@ -0,0 +1,272 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides single-use tokens that allow users to reset a forgotten password, and the notifiers
+//! used to deliver them.
+
+use std::error::Error;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use openssl::sha::sha256;
+
+use crate::base62::generate_random_base62_string;
+use crate::hex::to_hex;
+
+const TOKEN_LENGTH: usize = 43; // ~256 bits of entropy
+
+/// A stored password reset token. Only the hash of the token is stored; the token itself is only
+/// delivered to the user.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PasswordResetToken {
+    /// The ID of the user whose password may be reset with the token
+    pub user_id: String,
+    /// The hex-encoded SHA-256 hash of the token
+    pub token_hash: String,
+    /// The time the token was issued
+    pub created: SystemTime,
+    /// The time after which the token may no longer be used
+    pub expires: SystemTime,
+}
+
+impl PasswordResetToken {
+    /// Generates a new random token for the given user. Returns the token, which is to be
+    /// delivered to the user, along with the `PasswordResetToken` that is to be stored.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id`: The ID of the user whose password may be reset with the token.
+    /// * `duration`: How long the token may be used for.
+    ///
+    pub fn generate(user_id: &str, duration: Duration) -> (String, Self) {
+        let token = generate_random_base62_string(TOKEN_LENGTH);
+
+        // Times are stored with millisecond precision
+        let since_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let created = UNIX_EPOCH + Duration::from_millis(since_epoch.as_millis() as u64);
+
+        let reset_token = PasswordResetToken {
+            user_id: user_id.to_string(),
+            token_hash: hash_token(&token),
+            created,
+            expires: created + duration,
+        };
+
+        (token, reset_token)
+    }
+
+    /// Returns `true` if the token may no longer be used
+    pub fn is_expired(&self) -> bool {
+        SystemTime::now() > self.expires
+    }
+}
+
+/// Returns the hex-encoded SHA-256 hash of a token, as it is stored
+pub fn hash_token(token: &str) -> String {
+    to_hex(&sha256(token.as_bytes()))
+}
+
+/// Delivers password reset tokens to users, for example by email
+pub trait PasswordResetNotifier: Send + Sync {
+    /// Sends a password reset token to a user
+    ///
+    /// # Arguments
+    ///
+    /// * `username`: The username of the user, which may be used to determine where the token is
+    ///   sent.
+    /// * `token`: The password reset token.
+    fn send_reset_token(
+        &self,
+        username: &str,
+        token: &str,
+    ) -> Result<(), PasswordResetNotifierError>;
+}
+
+impl<N> PasswordResetNotifier for Box<N>
+where
+    N: PasswordResetNotifier + ?Sized,
+{
+    fn send_reset_token(
+        &self,
+        username: &str,
+        token: &str,
+    ) -> Result<(), PasswordResetNotifierError> {
+        (**self).send_reset_token(username, token)
+    }
+}
+
+/// A `PasswordResetNotifier` that writes password reset tokens to the log. Not recommended for use
+/// in production, since anyone with access to the log may reset any user's password.
+#[derive(Default)]
+pub struct LogPasswordResetNotifier;
+
+impl PasswordResetNotifier for LogPasswordResetNotifier {
+    fn send_reset_token(
+        &self,
+        username: &str,
+        token: &str,
+    ) -> Result<(), PasswordResetNotifierError> {
+        info!("Password reset token for {}: {}", username, token);
+        Ok(())
+    }
+}
+
+/// A `PasswordResetNotifier` that appends password reset tokens to a file, one
+/// `<username> <token>` line per token. Intended for testing and development.
+pub struct FilePasswordResetNotifier {
+    path: PathBuf,
+    // Serializes writes so that lines from concurrent requests are not interleaved
+    lock: Mutex<()>,
+}
+
+impl FilePasswordResetNotifier {
+    /// Creates a new `FilePasswordResetNotifier` that writes to the file at the given path. The
+    /// file is created if it does not exist.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        FilePasswordResetNotifier {
+            path: path.as_ref().to_path_buf(),
+            lock: Mutex::new(()),
+        }
+    }
+}
+
+impl PasswordResetNotifier for FilePasswordResetNotifier {
+    fn send_reset_token(
+        &self,
+        username: &str,
+        token: &str,
+    ) -> Result<(), PasswordResetNotifierError> {
+        let _guard = self
+            .lock
+            .lock()
+            .map_err(|_| PasswordResetNotifierError::new("Notifier lock was poisoned"))?;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|err| {
+                PasswordResetNotifierError::new_with_source(
+                    &format!("Unable to open {}", self.path.display()),
+                    Box::new(err),
+                )
+            })?;
+
+        writeln!(file, "{} {}", username, token).map_err(|err| {
+            PasswordResetNotifierError::new_with_source(
+                &format!("Unable to write to {}", self.path.display()),
+                Box::new(err),
+            )
+        })
+    }
+}
+
+/// Returned when a password reset token could not be delivered
+#[derive(Debug)]
+pub struct PasswordResetNotifierError {
+    context: String,
+    source: Option<Box<dyn Error>>,
+}
+
+impl PasswordResetNotifierError {
+    /// Builds a `PasswordResetNotifierError` with a `context`. This sets the `source` field to
+    /// `None`.
+    pub fn new(context: &str) -> Self {
+        Self {
+            context: context.into(),
+            source: None,
+        }
+    }
+
+    /// Builds a `PasswordResetNotifierError` with a `context`, and a `source` error.
+    pub fn new_with_source(context: &str, err: Box<dyn Error>) -> Self {
+        Self {
+            context: context.into(),
+            source: Some(err),
+        }
+    }
+}
+
+impl Error for PasswordResetNotifierError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_ref().map(|err| &**err)
+    }
+}
+
+impl fmt::Display for PasswordResetNotifierError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(ref err) = self.source {
+            write!(f, "{}: {}", self.context, err)
+        } else {
+            f.write_str(&self.context)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    use tempdir::TempDir;
+
+    /// Verify that a generated token is stored only as its hash and expires after the given
+    /// duration.
+    #[test]
+    fn test_generate_token() {
+        let (token, reset_token) = PasswordResetToken::generate("user1", Duration::from_secs(60));
+
+        assert_eq!(token.len(), TOKEN_LENGTH);
+        assert_eq!(reset_token.user_id, "user1");
+        assert_ne!(reset_token.token_hash, token);
+        assert_eq!(reset_token.token_hash, hash_token(&token));
+        assert_eq!(
+            reset_token.expires,
+            reset_token.created + Duration::from_secs(60)
+        );
+        assert!(!reset_token.is_expired());
+
+        let (_, expired) = PasswordResetToken::generate("user1", Duration::from_secs(0));
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(expired.is_expired());
+    }
+
+    /// Verify that the `FilePasswordResetNotifier` appends a line for each token it sends.
+    #[test]
+    fn test_file_notifier() {
+        let temp_dir = TempDir::new("test_file_notifier").expect("Failed to create temp dir");
+        let path = temp_dir.path().join("reset_tokens");
+
+        let notifier = FilePasswordResetNotifier::new(&path);
+        notifier
+            .send_reset_token("user1@example.com", "token1")
+            .expect("Failed to send first token");
+        notifier
+            .send_reset_token("user2@example.com", "token2")
+            .expect("Failed to send second token");
+
+        let contents = fs::read_to_string(&path).expect("Failed to read tokens file");
+        assert_eq!(
+            contents,
+            "user1@example.com token1\nuser2@example.com token2\n"
+        );
+    }
+}
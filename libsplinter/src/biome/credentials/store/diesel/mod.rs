@@ -16,19 +16,74 @@ pub(in crate::biome) mod models;
 mod operations;
 pub(in crate::biome) mod schema;
 
+#[cfg(any(
+    feature = "biome-api-keys",
+    feature = "biome-lockout",
+    feature = "biome-password-reset"
+))]
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use diesel::r2d2::{ConnectionManager, Pool};
 
+#[cfg(feature = "biome-api-keys")]
+use super::super::api_keys::{ApiKey, ApiKeyScope};
+#[cfg(feature = "biome-lockout")]
+use super::super::lockout::LoginAttempts;
+#[cfg(feature = "biome-mfa")]
+use super::super::mfa::MfaSecret;
+#[cfg(feature = "biome-password-reset")]
+use super::super::password_reset::PasswordResetToken;
 use super::{
     Credentials, CredentialsStore, CredentialsStoreError, PasswordEncryptionCost, UsernameId,
 };
 
+#[cfg(feature = "biome-api-keys")]
+use models::ApiKeyModel;
 use models::CredentialsModel;
+#[cfg(feature = "biome-lockout")]
+use models::LoginAttemptsModel;
+#[cfg(feature = "biome-mfa")]
+use models::MfaSecretModel;
+#[cfg(feature = "biome-password-reset")]
+use models::PasswordResetTokenModel;
+#[cfg(feature = "biome-api-keys")]
+use operations::add_api_key::CredentialsStoreAddApiKeyOperation as _;
 use operations::add_credentials::CredentialsStoreAddCredentialsOperation as _;
+#[cfg(feature = "biome-password-reset")]
+use operations::add_password_reset_token::CredentialsStoreAddPasswordResetTokenOperation as _;
+#[cfg(feature = "biome-password-reset")]
+use operations::count_password_reset_tokens::CredentialsStoreCountPasswordResetTokensOperation as _;
+#[cfg(feature = "biome-api-keys")]
+use operations::fetch_api_key::CredentialsStoreFetchApiKeyOperation as _;
 use operations::fetch_credential_by_id::CredentialsStoreFetchCredentialByIdOperation as _;
 use operations::fetch_credential_by_username::CredentialsStoreFetchCredentialByUsernameOperation as _;
+#[cfg(feature = "biome-lockout")]
+use operations::fetch_login_attempts::CredentialsStoreFetchLoginAttemptsOperation as _;
+#[cfg(feature = "biome-mfa")]
+use operations::fetch_mfa_secret::CredentialsStoreFetchMfaSecretOperation as _;
 use operations::fetch_username::CredentialsStoreFetchUsernameOperation as _;
+#[cfg(feature = "biome-api-keys")]
+use operations::list_api_keys::CredentialsStoreListApiKeysOperation as _;
 use operations::list_usernames::CredentialsStoreListUsernamesOperation as _;
+#[cfg(feature = "biome-api-keys")]
+use operations::remove_api_key::CredentialsStoreRemoveApiKeyOperation as _;
 use operations::remove_credentials::CredentialsStoreRemoveCredentialsOperation as _;
+#[cfg(feature = "biome-lockout")]
+use operations::remove_login_attempts::CredentialsStoreRemoveLoginAttemptsOperation as _;
+#[cfg(feature = "biome-mfa")]
+use operations::remove_mfa_secret::CredentialsStoreRemoveMfaSecretOperation as _;
+#[cfg(feature = "biome-password-reset")]
+use operations::remove_password_reset_tokens::CredentialsStoreRemovePasswordResetTokensOperation as _;
+#[cfg(feature = "biome-lockout")]
+use operations::set_login_attempts::CredentialsStoreSetLoginAttemptsOperation as _;
+#[cfg(feature = "biome-mfa")]
+use operations::set_mfa_recovery_codes::CredentialsStoreSetMfaRecoveryCodesOperation as _;
+#[cfg(feature = "biome-mfa")]
+use operations::set_mfa_secret::CredentialsStoreSetMfaSecretOperation as _;
+#[cfg(feature = "biome-mfa")]
+use operations::take_mfa_recovery_code::CredentialsStoreTakeMfaRecoveryCodeOperation as _;
+#[cfg(feature = "biome-password-reset")]
+use operations::take_password_reset_token::CredentialsStoreTakePasswordResetTokenOperation as _;
 use operations::update_credentials::CredentialsStoreUpdateCredentialsOperation as _;
 use operations::CredentialsStoreOperations;
 
@@ -96,6 +151,116 @@ impl CredentialsStore for DieselCredentialsStore<diesel::pg::PgConnection> {
     fn list_usernames(&self) -> Result<Vec<UsernameId>, CredentialsStoreError> {
         CredentialsStoreOperations::new(&*self.connection_pool.get()?).list_usernames()
     }
+
+    #[cfg(feature = "biome-password-reset")]
+    fn add_password_reset_token(
+        &self,
+        token: PasswordResetToken,
+    ) -> Result<(), CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?)
+            .add_password_reset_token(token)
+    }
+
+    #[cfg(feature = "biome-password-reset")]
+    fn take_password_reset_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<PasswordResetToken, CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?)
+            .take_password_reset_token(token_hash)
+    }
+
+    #[cfg(feature = "biome-password-reset")]
+    fn count_password_reset_tokens(
+        &self,
+        user_id: &str,
+        since: SystemTime,
+    ) -> Result<u64, CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?)
+            .count_password_reset_tokens(user_id, since)
+    }
+
+    #[cfg(feature = "biome-password-reset")]
+    fn remove_password_reset_tokens(&self, user_id: &str) -> Result<(), CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?)
+            .remove_password_reset_tokens(user_id)
+    }
+
+    #[cfg(feature = "biome-mfa")]
+    fn set_mfa_secret(&self, secret: MfaSecret) -> Result<(), CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?).set_mfa_secret(secret)
+    }
+
+    #[cfg(feature = "biome-mfa")]
+    fn fetch_mfa_secret(&self, user_id: &str) -> Result<MfaSecret, CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?).fetch_mfa_secret(user_id)
+    }
+
+    #[cfg(feature = "biome-mfa")]
+    fn remove_mfa_secret(&self, user_id: &str) -> Result<(), CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?).remove_mfa_secret(user_id)
+    }
+
+    #[cfg(feature = "biome-mfa")]
+    fn set_mfa_recovery_codes(
+        &self,
+        user_id: &str,
+        code_hashes: Vec<String>,
+    ) -> Result<(), CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?)
+            .set_mfa_recovery_codes(user_id, code_hashes)
+    }
+
+    #[cfg(feature = "biome-mfa")]
+    fn take_mfa_recovery_code(
+        &self,
+        user_id: &str,
+        code_hash: &str,
+    ) -> Result<(), CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?)
+            .take_mfa_recovery_code(user_id, code_hash)
+    }
+
+    #[cfg(feature = "biome-lockout")]
+    fn set_login_attempts(
+        &self,
+        user_id: &str,
+        attempts: LoginAttempts,
+    ) -> Result<(), CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?)
+            .set_login_attempts(user_id, attempts)
+    }
+
+    #[cfg(feature = "biome-lockout")]
+    fn fetch_login_attempts(&self, user_id: &str) -> Result<LoginAttempts, CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?).fetch_login_attempts(user_id)
+    }
+
+    #[cfg(feature = "biome-lockout")]
+    fn remove_login_attempts(&self, user_id: &str) -> Result<(), CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?)
+            .remove_login_attempts(user_id)
+    }
+
+    #[cfg(feature = "biome-api-keys")]
+    fn add_api_key(&self, api_key: ApiKey) -> Result<(), CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?).add_api_key(api_key)
+    }
+
+    #[cfg(feature = "biome-api-keys")]
+    fn fetch_api_key(&self, id: &str) -> Result<ApiKey, CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?).fetch_api_key(id)
+    }
+
+    #[cfg(feature = "biome-api-keys")]
+    fn list_api_keys(&self, user_id: &str) -> Result<Vec<ApiKey>, CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?).list_api_keys(user_id)
+    }
+
+    #[cfg(feature = "biome-api-keys")]
+    fn remove_api_key(&self, user_id: &str, id: &str) -> Result<(), CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?).remove_api_key(user_id, id)
+    }
 }
 
 #[cfg(feature = "sqlite")]
@@ -146,6 +311,116 @@ impl CredentialsStore for DieselCredentialsStore<diesel::sqlite::SqliteConnectio
     fn list_usernames(&self) -> Result<Vec<UsernameId>, CredentialsStoreError> {
         CredentialsStoreOperations::new(&*self.connection_pool.get()?).list_usernames()
     }
+
+    #[cfg(feature = "biome-password-reset")]
+    fn add_password_reset_token(
+        &self,
+        token: PasswordResetToken,
+    ) -> Result<(), CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?)
+            .add_password_reset_token(token)
+    }
+
+    #[cfg(feature = "biome-password-reset")]
+    fn take_password_reset_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<PasswordResetToken, CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?)
+            .take_password_reset_token(token_hash)
+    }
+
+    #[cfg(feature = "biome-password-reset")]
+    fn count_password_reset_tokens(
+        &self,
+        user_id: &str,
+        since: SystemTime,
+    ) -> Result<u64, CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?)
+            .count_password_reset_tokens(user_id, since)
+    }
+
+    #[cfg(feature = "biome-password-reset")]
+    fn remove_password_reset_tokens(&self, user_id: &str) -> Result<(), CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?)
+            .remove_password_reset_tokens(user_id)
+    }
+
+    #[cfg(feature = "biome-mfa")]
+    fn set_mfa_secret(&self, secret: MfaSecret) -> Result<(), CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?).set_mfa_secret(secret)
+    }
+
+    #[cfg(feature = "biome-mfa")]
+    fn fetch_mfa_secret(&self, user_id: &str) -> Result<MfaSecret, CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?).fetch_mfa_secret(user_id)
+    }
+
+    #[cfg(feature = "biome-mfa")]
+    fn remove_mfa_secret(&self, user_id: &str) -> Result<(), CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?).remove_mfa_secret(user_id)
+    }
+
+    #[cfg(feature = "biome-mfa")]
+    fn set_mfa_recovery_codes(
+        &self,
+        user_id: &str,
+        code_hashes: Vec<String>,
+    ) -> Result<(), CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?)
+            .set_mfa_recovery_codes(user_id, code_hashes)
+    }
+
+    #[cfg(feature = "biome-mfa")]
+    fn take_mfa_recovery_code(
+        &self,
+        user_id: &str,
+        code_hash: &str,
+    ) -> Result<(), CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?)
+            .take_mfa_recovery_code(user_id, code_hash)
+    }
+
+    #[cfg(feature = "biome-lockout")]
+    fn set_login_attempts(
+        &self,
+        user_id: &str,
+        attempts: LoginAttempts,
+    ) -> Result<(), CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?)
+            .set_login_attempts(user_id, attempts)
+    }
+
+    #[cfg(feature = "biome-lockout")]
+    fn fetch_login_attempts(&self, user_id: &str) -> Result<LoginAttempts, CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?).fetch_login_attempts(user_id)
+    }
+
+    #[cfg(feature = "biome-lockout")]
+    fn remove_login_attempts(&self, user_id: &str) -> Result<(), CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?)
+            .remove_login_attempts(user_id)
+    }
+
+    #[cfg(feature = "biome-api-keys")]
+    fn add_api_key(&self, api_key: ApiKey) -> Result<(), CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?).add_api_key(api_key)
+    }
+
+    #[cfg(feature = "biome-api-keys")]
+    fn fetch_api_key(&self, id: &str) -> Result<ApiKey, CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?).fetch_api_key(id)
+    }
+
+    #[cfg(feature = "biome-api-keys")]
+    fn list_api_keys(&self, user_id: &str) -> Result<Vec<ApiKey>, CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?).list_api_keys(user_id)
+    }
+
+    #[cfg(feature = "biome-api-keys")]
+    fn remove_api_key(&self, user_id: &str, id: &str) -> Result<(), CredentialsStoreError> {
+        CredentialsStoreOperations::new(&*self.connection_pool.get()?).remove_api_key(user_id, id)
+    }
 }
 
 impl From<CredentialsModel> for UsernameId {
@@ -167,6 +442,133 @@ impl From<CredentialsModel> for Credentials {
     }
 }
 
+#[cfg(feature = "biome-password-reset")]
+impl From<PasswordResetToken> for PasswordResetTokenModel {
+    fn from(token: PasswordResetToken) -> Self {
+        Self {
+            token_hash: token.token_hash,
+            user_id: token.user_id,
+            created: to_millis(token.created),
+            expires: to_millis(token.expires),
+        }
+    }
+}
+
+#[cfg(feature = "biome-password-reset")]
+impl From<PasswordResetTokenModel> for PasswordResetToken {
+    fn from(token: PasswordResetTokenModel) -> Self {
+        Self {
+            token_hash: token.token_hash,
+            user_id: token.user_id,
+            created: UNIX_EPOCH + Duration::from_millis(token.created as u64),
+            expires: UNIX_EPOCH + Duration::from_millis(token.expires as u64),
+        }
+    }
+}
+
+#[cfg(feature = "biome-mfa")]
+impl From<MfaSecret> for MfaSecretModel {
+    fn from(secret: MfaSecret) -> Self {
+        Self {
+            user_id: secret.user_id,
+            secret: secret.secret,
+            enabled: secret.enabled,
+            last_used_step: secret.last_used_step.map(|step| step as i64),
+        }
+    }
+}
+
+#[cfg(feature = "biome-mfa")]
+impl From<MfaSecretModel> for MfaSecret {
+    fn from(secret: MfaSecretModel) -> Self {
+        Self {
+            user_id: secret.user_id,
+            secret: secret.secret,
+            enabled: secret.enabled,
+            last_used_step: secret.last_used_step.map(|step| step as u64),
+        }
+    }
+}
+
+#[cfg(feature = "biome-lockout")]
+impl From<(&str, LoginAttempts)> for LoginAttemptsModel {
+    fn from((user_id, attempts): (&str, LoginAttempts)) -> Self {
+        Self {
+            user_id: user_id.to_string(),
+            failed_attempts: attempts.failed_attempts as i32,
+            last_failure: to_millis(attempts.last_failure),
+            locked_until: attempts.locked_until.map(to_millis),
+        }
+    }
+}
+
+#[cfg(feature = "biome-lockout")]
+impl From<LoginAttemptsModel> for LoginAttempts {
+    fn from(attempts: LoginAttemptsModel) -> Self {
+        Self {
+            failed_attempts: attempts.failed_attempts as u32,
+            last_failure: UNIX_EPOCH + Duration::from_millis(attempts.last_failure as u64),
+            locked_until: attempts
+                .locked_until
+                .map(|locked_until| UNIX_EPOCH + Duration::from_millis(locked_until as u64)),
+        }
+    }
+}
+
+#[cfg(feature = "biome-api-keys")]
+impl From<ApiKey> for ApiKeyModel {
+    fn from(api_key: ApiKey) -> Self {
+        Self {
+            id: api_key.id,
+            user_id: api_key.user_id,
+            display_name: api_key.display_name,
+            secret_hash: api_key.secret_hash,
+            scopes: api_key
+                .scopes
+                .iter()
+                .map(ApiKeyScope::as_str)
+                .collect::<Vec<_>>()
+                .join(" "),
+            created: to_millis(api_key.created),
+            expires: api_key.expires.map(to_millis),
+        }
+    }
+}
+
+/// Scopes that are no longer recognized are dropped, so that they grant no permissions.
+#[cfg(feature = "biome-api-keys")]
+impl From<ApiKeyModel> for ApiKey {
+    fn from(api_key: ApiKeyModel) -> Self {
+        Self {
+            id: api_key.id,
+            user_id: api_key.user_id,
+            display_name: api_key.display_name,
+            secret_hash: api_key.secret_hash,
+            scopes: api_key
+                .scopes
+                .split_whitespace()
+                .filter_map(|scope| scope.parse().ok())
+                .collect(),
+            created: UNIX_EPOCH + Duration::from_millis(api_key.created as u64),
+            expires: api_key
+                .expires
+                .map(|expires| UNIX_EPOCH + Duration::from_millis(expires as u64)),
+        }
+    }
+}
+
+/// Converts a time to the milliseconds since the Unix epoch, as it is stored
+#[cfg(any(
+    feature = "biome-api-keys",
+    feature = "biome-lockout",
+    feature = "biome-password-reset"
+))]
+fn to_millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or_default()
+}
+
 #[cfg(all(test, feature = "sqlite"))]
 pub mod tests {
     use super::*;
@@ -573,9 +975,358 @@ pub mod tests {
         }));
     }
 
+    /// Verify that a SQLite-backed `DieselCredentialsStore` correctly supports password reset
+    /// tokens.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Create the `DieselCredentialsStore`.
+    /// 3. Verify that a token cannot be added for a user without credentials.
+    /// 4. Add credentials and two tokens, and verify that both are counted.
+    /// 5. Take a token and verify that it is returned once and is no longer counted.
+    /// 6. Remove the user's tokens and verify that the remaining token can no longer be taken.
+    #[cfg(feature = "biome-password-reset")]
+    #[test]
+    fn sqlite_password_reset_tokens() {
+        let pool = create_connection_pool_and_migrate();
+
+        let store = DieselCredentialsStore::new(pool);
+
+        let (_, token) = PasswordResetToken::generate("id1", Duration::from_secs(60));
+        match store.add_password_reset_token(token) {
+            Err(CredentialsStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(CredentialsStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+
+        let cred = CredentialsBuilder::default()
+            .with_user_id("id1")
+            .with_username("user1")
+            .with_password("pwd1")
+            .with_password_encryption_cost(PasswordEncryptionCost::Low)
+            .build()
+            .expect("Failed to build cred");
+        store.add_credentials(cred).expect("Failed to add cred");
+
+        let since = SystemTime::now() - Duration::from_secs(1);
+        let (_, token1) = PasswordResetToken::generate("id1", Duration::from_secs(60));
+        let (_, token2) = PasswordResetToken::generate("id1", Duration::from_secs(60));
+        store
+            .add_password_reset_token(token1.clone())
+            .expect("Failed to add token1");
+        store
+            .add_password_reset_token(token2.clone())
+            .expect("Failed to add token2");
+        assert_eq!(
+            store
+                .count_password_reset_tokens("id1", since)
+                .expect("Failed to count tokens"),
+            2
+        );
+
+        assert_eq!(
+            store
+                .take_password_reset_token(&token1.token_hash)
+                .expect("Failed to take token1"),
+            token1
+        );
+        match store.take_password_reset_token(&token1.token_hash) {
+            Err(CredentialsStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(CredentialsStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+        assert_eq!(
+            store
+                .count_password_reset_tokens("id1", since)
+                .expect("Failed to count tokens"),
+            1
+        );
+
+        store
+            .remove_password_reset_tokens("id1")
+            .expect("Failed to remove tokens");
+        match store.take_password_reset_token(&token2.token_hash) {
+            Err(CredentialsStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(CredentialsStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+    }
+
+    /// Verify that a SQLite-backed `DieselCredentialsStore` correctly supports setting, fetching
+    /// and removing MFA secrets and recovery codes.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Create the `DieselCredentialsStore`.
+    /// 3. Verify that setting a secret for a user without credentials fails.
+    /// 4. Add credentials and set a secret and recovery codes for the user.
+    /// 5. Update the secret and verify that the updated secret is returned and the recovery codes
+    ///    are kept.
+    /// 6. Verify that a recovery code may only be taken once.
+    /// 7. Remove the secret and verify that the secret and recovery codes are gone.
+    #[cfg(feature = "biome-mfa")]
+    #[test]
+    fn sqlite_mfa_secrets() {
+        let pool = create_connection_pool_and_migrate();
+
+        let store = DieselCredentialsStore::new(pool);
+
+        let mut secret = MfaSecret::generate("id1").expect("Failed to generate secret");
+        match store.set_mfa_secret(secret.clone()) {
+            Err(CredentialsStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(CredentialsStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+
+        let cred = CredentialsBuilder::default()
+            .with_user_id("id1")
+            .with_username("user1")
+            .with_password("pwd1")
+            .with_password_encryption_cost(PasswordEncryptionCost::Low)
+            .build()
+            .expect("Failed to build cred");
+        store.add_credentials(cred).expect("Failed to add cred");
+
+        store
+            .set_mfa_secret(secret.clone())
+            .expect("Failed to set secret");
+        store
+            .set_mfa_recovery_codes("id1", vec!["hash1".into(), "hash2".into()])
+            .expect("Failed to set recovery codes");
+        assert_eq!(
+            store
+                .fetch_mfa_secret("id1")
+                .expect("Failed to fetch secret"),
+            secret
+        );
+
+        secret.enabled = true;
+        secret.last_used_step = Some(1);
+        store
+            .set_mfa_secret(secret.clone())
+            .expect("Failed to update secret");
+        assert_eq!(
+            store
+                .fetch_mfa_secret("id1")
+                .expect("Failed to fetch secret"),
+            secret
+        );
+
+        store
+            .take_mfa_recovery_code("id1", "hash1")
+            .expect("Failed to take recovery code");
+        match store.take_mfa_recovery_code("id1", "hash1") {
+            Err(CredentialsStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(CredentialsStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+
+        store
+            .remove_mfa_secret("id1")
+            .expect("Failed to remove secret");
+        match store.fetch_mfa_secret("id1") {
+            Err(CredentialsStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(CredentialsStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+        match store.take_mfa_recovery_code("id1", "hash2") {
+            Err(CredentialsStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(CredentialsStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+    }
+
     /// Creates a conneciton pool for an in-memory SQLite database with only a single connection
     /// available. Each connection is backed by a different in-memory SQLite database, so limiting
     /// the pool to a single connection insures that the same DB is used for all operations.
+    /// Verify that a SQLite-backed `DieselCredentialsStore` correctly supports setting, fetching
+    /// and removing a user's failed login attempts.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Create the `DieselCredentialsStore`.
+    /// 3. Verify that attempts cannot be set for a user that does not exist.
+    /// 4. Add credentials, then set and update the user's attempts, verifying that they are
+    ///    fetched correctly.
+    /// 5. Remove the attempts and verify that fetching or removing them again returns a
+    ///    `CredentialsStoreError::NotFoundError`.
+    #[cfg(feature = "biome-lockout")]
+    #[test]
+    fn sqlite_login_attempts() {
+        let pool = create_connection_pool_and_migrate();
+
+        let store = DieselCredentialsStore::new(pool);
+
+        // Times are stored with millisecond precision
+        let now = UNIX_EPOCH + Duration::from_millis(1_593_000_000_000);
+        let mut attempts = LoginAttempts {
+            failed_attempts: 1,
+            last_failure: now,
+            locked_until: None,
+        };
+        match store.set_login_attempts("id1", attempts.clone()) {
+            Err(CredentialsStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(CredentialsStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+
+        let cred = CredentialsBuilder::default()
+            .with_user_id("id1")
+            .with_username("user1")
+            .with_password("pwd1")
+            .with_password_encryption_cost(PasswordEncryptionCost::Low)
+            .build()
+            .expect("Failed to build cred");
+        store.add_credentials(cred).expect("Failed to add cred");
+
+        store
+            .set_login_attempts("id1", attempts.clone())
+            .expect("Failed to set attempts");
+        assert_eq!(
+            store
+                .fetch_login_attempts("id1")
+                .expect("Failed to fetch attempts"),
+            attempts
+        );
+
+        attempts.failed_attempts = 5;
+        attempts.locked_until = Some(now + Duration::from_secs(900));
+        store
+            .set_login_attempts("id1", attempts.clone())
+            .expect("Failed to update attempts");
+        assert_eq!(
+            store
+                .fetch_login_attempts("id1")
+                .expect("Failed to fetch attempts"),
+            attempts
+        );
+
+        store
+            .remove_login_attempts("id1")
+            .expect("Failed to remove attempts");
+        match store.fetch_login_attempts("id1") {
+            Err(CredentialsStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(CredentialsStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+        match store.remove_login_attempts("id1") {
+            Err(CredentialsStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(CredentialsStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+    }
+
+    /// Verify that a SQLite-backed `DieselCredentialsStore` correctly supports adding, fetching,
+    /// listing and removing API keys.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Create the `DieselCredentialsStore`.
+    /// 3. Verify that an API key cannot be added for a user without credentials.
+    /// 4. Add credentials and two API keys, and verify that they are fetched and listed in the
+    ///    order they were created, and that a duplicate key is rejected.
+    /// 5. Verify that a key can only be removed by the user it belongs to.
+    #[cfg(feature = "biome-api-keys")]
+    #[test]
+    fn sqlite_api_keys() {
+        let pool = create_connection_pool_and_migrate();
+
+        let store = DieselCredentialsStore::new(pool);
+
+        let now = UNIX_EPOCH + Duration::from_millis(1_593_000_000_000);
+        let (_, mut api_key1) = ApiKey::generate(
+            "id1",
+            "ci",
+            vec![ApiKeyScope::AdminRead, ApiKeyScope::ScabbardSubmit],
+            Some(Duration::from_secs(3600)),
+        );
+        api_key1.created = now;
+        api_key1.expires = Some(now + Duration::from_secs(3600));
+        let (_, mut api_key2) =
+            ApiKey::generate("id1", "registry", vec![ApiKeyScope::RegistryWrite], None);
+        api_key2.created = now + Duration::from_secs(1);
+
+        match store.add_api_key(api_key1.clone()) {
+            Err(CredentialsStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(CredentialsStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+
+        let cred = CredentialsBuilder::default()
+            .with_user_id("id1")
+            .with_username("user1")
+            .with_password("pwd1")
+            .with_password_encryption_cost(PasswordEncryptionCost::Low)
+            .build()
+            .expect("Failed to build cred");
+        store.add_credentials(cred).expect("Failed to add cred");
+
+        store
+            .add_api_key(api_key2.clone())
+            .expect("Failed to add api key 2");
+        store
+            .add_api_key(api_key1.clone())
+            .expect("Failed to add api key 1");
+        match store.add_api_key(api_key1.clone()) {
+            Err(CredentialsStoreError::DuplicateError(_)) => {}
+            res => panic!(
+                "Expected Err(CredentialsStoreError::DuplicateError), got {:?} instead",
+                res
+            ),
+        }
+
+        assert_eq!(
+            store
+                .fetch_api_key(&api_key1.id)
+                .expect("Failed to fetch api key"),
+            api_key1
+        );
+        assert_eq!(
+            store.list_api_keys("id1").expect("Failed to list api keys"),
+            vec![api_key1.clone(), api_key2.clone()]
+        );
+
+        match store.remove_api_key("id2", &api_key1.id) {
+            Err(CredentialsStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(CredentialsStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+        store
+            .remove_api_key("id1", &api_key1.id)
+            .expect("Failed to remove api key");
+        match store.fetch_api_key(&api_key1.id) {
+            Err(CredentialsStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(CredentialsStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+        assert_eq!(
+            store.list_api_keys("id1").expect("Failed to list api keys"),
+            vec![api_key2]
+        );
+    }
+
     fn create_connection_pool_and_migrate() -> Pool<ConnectionManager<SqliteConnection>> {
         let connection_manager = ConnectionManager::<SqliteConnection>::new(":memory:");
         let pool = Pool::builder()
@@ -12,7 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "biome-password-reset")]
+use super::schema::password_reset_tokens;
+#[cfg(feature = "biome-api-keys")]
+use super::schema::user_api_keys;
 use super::schema::user_credentials;
+#[cfg(feature = "biome-lockout")]
+use super::schema::user_login_attempts;
+#[cfg(feature = "biome-mfa")]
+use super::schema::{user_mfa_recovery_codes, user_mfa_secrets};
 use crate::biome::user::store::diesel::models::UserModel;
 
 #[derive(Queryable, Identifiable, Associations, PartialEq, Debug)]
@@ -32,3 +40,60 @@ pub struct NewCredentialsModel {
     pub username: String,
     pub password: String,
 }
+
+/// A password reset token, with its times stored as milliseconds since the Unix epoch
+#[cfg(feature = "biome-password-reset")]
+#[derive(Queryable, Insertable, PartialEq, Debug)]
+#[table_name = "password_reset_tokens"]
+pub struct PasswordResetTokenModel {
+    pub token_hash: String,
+    pub user_id: String,
+    pub created: i64,
+    pub expires: i64,
+}
+
+/// A user's TOTP secret
+#[cfg(feature = "biome-mfa")]
+#[derive(Queryable, Insertable, PartialEq, Debug)]
+#[table_name = "user_mfa_secrets"]
+pub struct MfaSecretModel {
+    pub user_id: String,
+    pub secret: String,
+    pub enabled: bool,
+    pub last_used_step: Option<i64>,
+}
+
+/// The hash of one of a user's recovery codes
+#[cfg(feature = "biome-mfa")]
+#[derive(Queryable, Insertable, PartialEq, Debug)]
+#[table_name = "user_mfa_recovery_codes"]
+pub struct MfaRecoveryCodeModel {
+    pub user_id: String,
+    pub code_hash: String,
+}
+
+/// A user's failed login attempts, with their times stored as milliseconds since the Unix epoch
+#[cfg(feature = "biome-lockout")]
+#[derive(Queryable, Insertable, PartialEq, Debug)]
+#[table_name = "user_login_attempts"]
+pub struct LoginAttemptsModel {
+    pub user_id: String,
+    pub failed_attempts: i32,
+    pub last_failure: i64,
+    pub locked_until: Option<i64>,
+}
+
+/// A user's API key, with its scopes stored as a space-separated list and its times stored as
+/// milliseconds since the Unix epoch
+#[cfg(feature = "biome-api-keys")]
+#[derive(Queryable, Insertable, PartialEq, Debug)]
+#[table_name = "user_api_keys"]
+pub struct ApiKeyModel {
+    pub id: String,
+    pub user_id: String,
+    pub display_name: String,
+    pub secret_hash: String,
+    pub scopes: String,
+    pub created: i64,
+    pub expires: Option<i64>,
+}
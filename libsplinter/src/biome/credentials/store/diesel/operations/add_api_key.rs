@@ -0,0 +1,120 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CredentialsStoreOperations;
+use crate::biome::credentials::api_keys::ApiKey;
+use crate::biome::credentials::store::diesel::models::ApiKeyModel;
+use crate::biome::credentials::store::diesel::schema::{user_api_keys, user_credentials};
+use crate::biome::credentials::store::error::CredentialsStoreError;
+use diesel::{dsl::insert_into, prelude::*};
+
+pub(in crate::biome::credentials) trait CredentialsStoreAddApiKeyOperation {
+    fn add_api_key(&self, api_key: ApiKey) -> Result<(), CredentialsStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> CredentialsStoreAddApiKeyOperation
+    for CredentialsStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn add_api_key(&self, api_key: ApiKey) -> Result<(), CredentialsStoreError> {
+        let user_exists = user_credentials::table
+            .filter(user_credentials::user_id.eq(&api_key.user_id))
+            .count()
+            .get_result::<i64>(self.conn)
+            .map_err(|err| CredentialsStoreError::QueryError {
+                context: "Failed check for existing user id".to_string(),
+                source: Box::new(err),
+            })?
+            > 0;
+        if !user_exists {
+            return Err(CredentialsStoreError::NotFoundError(format!(
+                "Credentials not found for user id: {}",
+                &api_key.user_id
+            )));
+        }
+
+        let key_exists = user_api_keys::table
+            .filter(user_api_keys::id.eq(&api_key.id))
+            .count()
+            .get_result::<i64>(self.conn)
+            .map_err(|err| CredentialsStoreError::QueryError {
+                context: "Failed check for existing API key id".to_string(),
+                source: Box::new(err),
+            })?
+            > 0;
+        if key_exists {
+            return Err(CredentialsStoreError::DuplicateError(format!(
+                "API key {} already exists",
+                &api_key.id
+            )));
+        }
+
+        insert_into(user_api_keys::table)
+            .values(ApiKeyModel::from(api_key))
+            .execute(self.conn)
+            .map(|_| ())
+            .map_err(|err| CredentialsStoreError::OperationError {
+                context: "Failed to add API key".to_string(),
+                source: Box::new(err),
+            })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> CredentialsStoreAddApiKeyOperation
+    for CredentialsStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn add_api_key(&self, api_key: ApiKey) -> Result<(), CredentialsStoreError> {
+        let user_exists = user_credentials::table
+            .filter(user_credentials::user_id.eq(&api_key.user_id))
+            .count()
+            .get_result::<i64>(self.conn)
+            .map_err(|err| CredentialsStoreError::QueryError {
+                context: "Failed check for existing user id".to_string(),
+                source: Box::new(err),
+            })?
+            > 0;
+        if !user_exists {
+            return Err(CredentialsStoreError::NotFoundError(format!(
+                "Credentials not found for user id: {}",
+                &api_key.user_id
+            )));
+        }
+
+        let key_exists = user_api_keys::table
+            .filter(user_api_keys::id.eq(&api_key.id))
+            .count()
+            .get_result::<i64>(self.conn)
+            .map_err(|err| CredentialsStoreError::QueryError {
+                context: "Failed check for existing API key id".to_string(),
+                source: Box::new(err),
+            })?
+            > 0;
+        if key_exists {
+            return Err(CredentialsStoreError::DuplicateError(format!(
+                "API key {} already exists",
+                &api_key.id
+            )));
+        }
+
+        insert_into(user_api_keys::table)
+            .values(ApiKeyModel::from(api_key))
+            .execute(self.conn)
+            .map(|_| ())
+            .map_err(|err| CredentialsStoreError::OperationError {
+                context: "Failed to add API key".to_string(),
+                source: Box::new(err),
+            })
+    }
+}
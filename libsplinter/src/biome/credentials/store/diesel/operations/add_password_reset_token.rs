@@ -0,0 +1,97 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CredentialsStoreOperations;
+use crate::biome::credentials::password_reset::PasswordResetToken;
+use crate::biome::credentials::store::diesel::models::PasswordResetTokenModel;
+use crate::biome::credentials::store::diesel::schema::{password_reset_tokens, user_credentials};
+use crate::biome::credentials::store::error::CredentialsStoreError;
+use diesel::{dsl::insert_into, prelude::*};
+
+pub(in crate::biome::credentials) trait CredentialsStoreAddPasswordResetTokenOperation {
+    fn add_password_reset_token(
+        &self,
+        token: PasswordResetToken,
+    ) -> Result<(), CredentialsStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> CredentialsStoreAddPasswordResetTokenOperation
+    for CredentialsStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn add_password_reset_token(
+        &self,
+        token: PasswordResetToken,
+    ) -> Result<(), CredentialsStoreError> {
+        let user_exists = user_credentials::table
+            .filter(user_credentials::user_id.eq(&token.user_id))
+            .count()
+            .get_result::<i64>(self.conn)
+            .map_err(|err| CredentialsStoreError::QueryError {
+                context: "Failed check for existing user id".to_string(),
+                source: Box::new(err),
+            })?
+            > 0;
+        if !user_exists {
+            return Err(CredentialsStoreError::NotFoundError(format!(
+                "Credentials not found for user id: {}",
+                &token.user_id
+            )));
+        }
+
+        insert_into(password_reset_tokens::table)
+            .values(PasswordResetTokenModel::from(token))
+            .execute(self.conn)
+            .map(|_| ())
+            .map_err(|err| CredentialsStoreError::OperationError {
+                context: "Failed to add password reset token".to_string(),
+                source: Box::new(err),
+            })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> CredentialsStoreAddPasswordResetTokenOperation
+    for CredentialsStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn add_password_reset_token(
+        &self,
+        token: PasswordResetToken,
+    ) -> Result<(), CredentialsStoreError> {
+        let user_exists = user_credentials::table
+            .filter(user_credentials::user_id.eq(&token.user_id))
+            .count()
+            .get_result::<i64>(self.conn)
+            .map_err(|err| CredentialsStoreError::QueryError {
+                context: "Failed check for existing user id".to_string(),
+                source: Box::new(err),
+            })?
+            > 0;
+        if !user_exists {
+            return Err(CredentialsStoreError::NotFoundError(format!(
+                "Credentials not found for user id: {}",
+                &token.user_id
+            )));
+        }
+
+        insert_into(password_reset_tokens::table)
+            .values(PasswordResetTokenModel::from(token))
+            .execute(self.conn)
+            .map(|_| ())
+            .map_err(|err| CredentialsStoreError::OperationError {
+                context: "Failed to add password reset token".to_string(),
+                source: Box::new(err),
+            })
+    }
+}
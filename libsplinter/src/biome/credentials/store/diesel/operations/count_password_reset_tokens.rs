@@ -0,0 +1,52 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::SystemTime;
+
+use super::CredentialsStoreOperations;
+use crate::biome::credentials::store::diesel::schema::password_reset_tokens;
+use crate::biome::credentials::store::diesel::to_millis;
+use crate::biome::credentials::store::error::CredentialsStoreError;
+use diesel::prelude::*;
+
+pub(in crate::biome::credentials) trait CredentialsStoreCountPasswordResetTokensOperation {
+    fn count_password_reset_tokens(
+        &self,
+        user_id: &str,
+        since: SystemTime,
+    ) -> Result<u64, CredentialsStoreError>;
+}
+
+impl<'a, C> CredentialsStoreCountPasswordResetTokensOperation for CredentialsStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+{
+    fn count_password_reset_tokens(
+        &self,
+        user_id: &str,
+        since: SystemTime,
+    ) -> Result<u64, CredentialsStoreError> {
+        password_reset_tokens::table
+            .filter(password_reset_tokens::user_id.eq(user_id))
+            .filter(password_reset_tokens::created.ge(to_millis(since)))
+            .count()
+            .get_result::<i64>(self.conn)
+            .map(|count| count as u64)
+            .map_err(|err| CredentialsStoreError::QueryError {
+                context: "Failed to count password reset tokens".to_string(),
+                source: Box::new(err),
+            })
+    }
+}
@@ -0,0 +1,48 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CredentialsStoreOperations;
+use crate::biome::credentials::api_keys::ApiKey;
+use crate::biome::credentials::store::diesel::models::ApiKeyModel;
+use crate::biome::credentials::store::diesel::schema::user_api_keys;
+use crate::biome::credentials::store::error::CredentialsStoreError;
+use diesel::{prelude::*, result::Error::NotFound};
+
+pub(in crate::biome::credentials) trait CredentialsStoreFetchApiKeyOperation {
+    fn fetch_api_key(&self, id: &str) -> Result<ApiKey, CredentialsStoreError>;
+}
+
+impl<'a, C> CredentialsStoreFetchApiKeyOperation for CredentialsStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+{
+    fn fetch_api_key(&self, id: &str) -> Result<ApiKey, CredentialsStoreError> {
+        let api_key = user_api_keys::table
+            .find(id)
+            .first::<ApiKeyModel>(self.conn)
+            .map(Some)
+            .or_else(|err| if err == NotFound { Ok(None) } else { Err(err) })
+            .map_err(|err| CredentialsStoreError::QueryError {
+                context: "Failed to fetch API key".to_string(),
+                source: Box::new(err),
+            })?
+            .ok_or_else(|| {
+                CredentialsStoreError::NotFoundError(format!("API key {} not found", id))
+            })?;
+
+        Ok(ApiKey::from(api_key))
+    }
+}
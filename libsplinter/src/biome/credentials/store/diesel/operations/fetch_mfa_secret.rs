@@ -0,0 +1,53 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CredentialsStoreOperations;
+use crate::biome::credentials::mfa::MfaSecret;
+use crate::biome::credentials::store::diesel::models::MfaSecretModel;
+use crate::biome::credentials::store::diesel::schema::user_mfa_secrets;
+use crate::biome::credentials::store::error::CredentialsStoreError;
+use diesel::{prelude::*, result::Error::NotFound};
+
+pub(in crate::biome::credentials) trait CredentialsStoreFetchMfaSecretOperation {
+    fn fetch_mfa_secret(&self, user_id: &str) -> Result<MfaSecret, CredentialsStoreError>;
+}
+
+impl<'a, C> CredentialsStoreFetchMfaSecretOperation for CredentialsStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+    bool: diesel::deserialize::FromSql<diesel::sql_types::Bool, C::Backend>,
+    C::Backend: diesel::sql_types::HasSqlType<diesel::sql_types::Bool>,
+{
+    fn fetch_mfa_secret(&self, user_id: &str) -> Result<MfaSecret, CredentialsStoreError> {
+        let secret = user_mfa_secrets::table
+            .find(user_id)
+            .first::<MfaSecretModel>(self.conn)
+            .map(Some)
+            .or_else(|err| if err == NotFound { Ok(None) } else { Err(err) })
+            .map_err(|err| CredentialsStoreError::QueryError {
+                context: "Failed to fetch MFA secret".to_string(),
+                source: Box::new(err),
+            })?
+            .ok_or_else(|| {
+                CredentialsStoreError::NotFoundError(format!(
+                    "MFA secret not found for user id: {}",
+                    user_id
+                ))
+            })?;
+
+        Ok(MfaSecret::from(secret))
+    }
+}
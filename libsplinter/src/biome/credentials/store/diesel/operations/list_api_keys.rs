@@ -0,0 +1,44 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CredentialsStoreOperations;
+use crate::biome::credentials::api_keys::ApiKey;
+use crate::biome::credentials::store::diesel::models::ApiKeyModel;
+use crate::biome::credentials::store::diesel::schema::user_api_keys;
+use crate::biome::credentials::store::error::CredentialsStoreError;
+use diesel::prelude::*;
+
+pub(in crate::biome::credentials) trait CredentialsStoreListApiKeysOperation {
+    fn list_api_keys(&self, user_id: &str) -> Result<Vec<ApiKey>, CredentialsStoreError>;
+}
+
+impl<'a, C> CredentialsStoreListApiKeysOperation for CredentialsStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+{
+    fn list_api_keys(&self, user_id: &str) -> Result<Vec<ApiKey>, CredentialsStoreError> {
+        let api_keys = user_api_keys::table
+            .filter(user_api_keys::user_id.eq(user_id))
+            .order(user_api_keys::created.asc())
+            .load::<ApiKeyModel>(self.conn)
+            .map_err(|err| CredentialsStoreError::QueryError {
+                context: "Failed to list API keys".to_string(),
+                source: Box::new(err),
+            })?;
+
+        Ok(api_keys.into_iter().map(ApiKey::from).collect())
+    }
+}
@@ -14,12 +14,44 @@
 
 //! Provides CredentialsStoreOperations implemented for a diesel backend
 
+#[cfg(feature = "biome-api-keys")]
+pub(super) mod add_api_key;
 pub(super) mod add_credentials;
+#[cfg(feature = "biome-password-reset")]
+pub(super) mod add_password_reset_token;
+#[cfg(feature = "biome-password-reset")]
+pub(super) mod count_password_reset_tokens;
+#[cfg(feature = "biome-api-keys")]
+pub(super) mod fetch_api_key;
 pub(super) mod fetch_credential_by_id;
 pub(super) mod fetch_credential_by_username;
+#[cfg(feature = "biome-lockout")]
+pub(super) mod fetch_login_attempts;
+#[cfg(feature = "biome-mfa")]
+pub(super) mod fetch_mfa_secret;
 pub(super) mod fetch_username;
+#[cfg(feature = "biome-api-keys")]
+pub(super) mod list_api_keys;
 pub(super) mod list_usernames;
+#[cfg(feature = "biome-api-keys")]
+pub(super) mod remove_api_key;
 pub(super) mod remove_credentials;
+#[cfg(feature = "biome-lockout")]
+pub(super) mod remove_login_attempts;
+#[cfg(feature = "biome-mfa")]
+pub(super) mod remove_mfa_secret;
+#[cfg(feature = "biome-password-reset")]
+pub(super) mod remove_password_reset_tokens;
+#[cfg(feature = "biome-lockout")]
+pub(super) mod set_login_attempts;
+#[cfg(feature = "biome-mfa")]
+pub(super) mod set_mfa_recovery_codes;
+#[cfg(feature = "biome-mfa")]
+pub(super) mod set_mfa_secret;
+#[cfg(feature = "biome-mfa")]
+pub(super) mod take_mfa_recovery_code;
+#[cfg(feature = "biome-password-reset")]
+pub(super) mod take_password_reset_token;
 pub(super) mod update_credentials;
 
 pub(super) struct CredentialsStoreOperations<'a, C> {
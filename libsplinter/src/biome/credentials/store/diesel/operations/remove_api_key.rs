@@ -0,0 +1,48 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CredentialsStoreOperations;
+use crate::biome::credentials::store::diesel::schema::user_api_keys;
+use crate::biome::credentials::store::error::CredentialsStoreError;
+use diesel::{dsl::delete, prelude::*};
+
+pub(in crate::biome::credentials) trait CredentialsStoreRemoveApiKeyOperation {
+    fn remove_api_key(&self, user_id: &str, id: &str) -> Result<(), CredentialsStoreError>;
+}
+
+impl<'a, C> CredentialsStoreRemoveApiKeyOperation for CredentialsStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+{
+    fn remove_api_key(&self, user_id: &str, id: &str) -> Result<(), CredentialsStoreError> {
+        let removed = delete(
+            user_api_keys::table
+                .filter(user_api_keys::id.eq(id))
+                .filter(user_api_keys::user_id.eq(user_id)),
+        )
+        .execute(self.conn)
+        .map_err(|err| CredentialsStoreError::OperationError {
+            context: "Failed to remove API key".to_string(),
+            source: Box::new(err),
+        })?;
+
+        if removed == 0 {
+            return Err(CredentialsStoreError::NotFoundError(format!(
+                "API key {} not found for user id: {}",
+                id, user_id
+            )));
+        }
+        Ok(())
+    }
+}
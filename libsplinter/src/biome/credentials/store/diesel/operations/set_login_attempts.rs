@@ -0,0 +1,129 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CredentialsStoreOperations;
+use crate::biome::credentials::lockout::LoginAttempts;
+use crate::biome::credentials::store::diesel::models::LoginAttemptsModel;
+use crate::biome::credentials::store::diesel::schema::{user_credentials, user_login_attempts};
+use crate::biome::credentials::store::error::CredentialsStoreError;
+use diesel::{
+    dsl::{insert_into, update},
+    prelude::*,
+};
+
+pub(in crate::biome::credentials) trait CredentialsStoreSetLoginAttemptsOperation {
+    fn set_login_attempts(
+        &self,
+        user_id: &str,
+        attempts: LoginAttempts,
+    ) -> Result<(), CredentialsStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> CredentialsStoreSetLoginAttemptsOperation
+    for CredentialsStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn set_login_attempts(
+        &self,
+        user_id: &str,
+        attempts: LoginAttempts,
+    ) -> Result<(), CredentialsStoreError> {
+        let user_exists = user_credentials::table
+            .filter(user_credentials::user_id.eq(user_id))
+            .count()
+            .get_result::<i64>(self.conn)
+            .map_err(|err| CredentialsStoreError::QueryError {
+                context: "Failed check for existing user id".to_string(),
+                source: Box::new(err),
+            })?
+            > 0;
+        if !user_exists {
+            return Err(CredentialsStoreError::NotFoundError(format!(
+                "Credentials not found for user id: {}",
+                user_id
+            )));
+        }
+
+        let model = LoginAttemptsModel::from((user_id, attempts));
+        self.conn
+            .transaction::<_, diesel::result::Error, _>(|| {
+                let updated = update(user_login_attempts::table.find(&model.user_id))
+                    .set((
+                        user_login_attempts::failed_attempts.eq(model.failed_attempts),
+                        user_login_attempts::last_failure.eq(model.last_failure),
+                        user_login_attempts::locked_until.eq(model.locked_until),
+                    ))
+                    .execute(self.conn)?;
+                if updated == 0 {
+                    insert_into(user_login_attempts::table)
+                        .values(&model)
+                        .execute(self.conn)?;
+                }
+                Ok(())
+            })
+            .map_err(|err| CredentialsStoreError::OperationError {
+                context: "Failed to set login attempts".to_string(),
+                source: Box::new(err),
+            })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> CredentialsStoreSetLoginAttemptsOperation
+    for CredentialsStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn set_login_attempts(
+        &self,
+        user_id: &str,
+        attempts: LoginAttempts,
+    ) -> Result<(), CredentialsStoreError> {
+        let user_exists = user_credentials::table
+            .filter(user_credentials::user_id.eq(user_id))
+            .count()
+            .get_result::<i64>(self.conn)
+            .map_err(|err| CredentialsStoreError::QueryError {
+                context: "Failed check for existing user id".to_string(),
+                source: Box::new(err),
+            })?
+            > 0;
+        if !user_exists {
+            return Err(CredentialsStoreError::NotFoundError(format!(
+                "Credentials not found for user id: {}",
+                user_id
+            )));
+        }
+
+        let model = LoginAttemptsModel::from((user_id, attempts));
+        self.conn
+            .transaction::<_, diesel::result::Error, _>(|| {
+                let updated = update(user_login_attempts::table.find(&model.user_id))
+                    .set((
+                        user_login_attempts::failed_attempts.eq(model.failed_attempts),
+                        user_login_attempts::last_failure.eq(model.last_failure),
+                        user_login_attempts::locked_until.eq(model.locked_until),
+                    ))
+                    .execute(self.conn)?;
+                if updated == 0 {
+                    insert_into(user_login_attempts::table)
+                        .values(&model)
+                        .execute(self.conn)?;
+                }
+                Ok(())
+            })
+            .map_err(|err| CredentialsStoreError::OperationError {
+                context: "Failed to set login attempts".to_string(),
+                source: Box::new(err),
+            })
+    }
+}
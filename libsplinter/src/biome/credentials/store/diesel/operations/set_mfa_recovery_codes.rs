@@ -0,0 +1,132 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CredentialsStoreOperations;
+use crate::biome::credentials::store::diesel::models::MfaRecoveryCodeModel;
+use crate::biome::credentials::store::diesel::schema::{user_mfa_recovery_codes, user_mfa_secrets};
+use crate::biome::credentials::store::error::CredentialsStoreError;
+use diesel::{
+    dsl::{delete, insert_into},
+    prelude::*,
+};
+
+pub(in crate::biome::credentials) trait CredentialsStoreSetMfaRecoveryCodesOperation {
+    fn set_mfa_recovery_codes(
+        &self,
+        user_id: &str,
+        code_hashes: Vec<String>,
+    ) -> Result<(), CredentialsStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> CredentialsStoreSetMfaRecoveryCodesOperation
+    for CredentialsStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn set_mfa_recovery_codes(
+        &self,
+        user_id: &str,
+        code_hashes: Vec<String>,
+    ) -> Result<(), CredentialsStoreError> {
+        let secret_exists = user_mfa_secrets::table
+            .filter(user_mfa_secrets::user_id.eq(user_id))
+            .count()
+            .get_result::<i64>(self.conn)
+            .map_err(|err| CredentialsStoreError::QueryError {
+                context: "Failed check for existing MFA secret".to_string(),
+                source: Box::new(err),
+            })?
+            > 0;
+        if !secret_exists {
+            return Err(CredentialsStoreError::NotFoundError(format!(
+                "MFA secret not found for user id: {}",
+                user_id
+            )));
+        }
+
+        let codes = code_hashes
+            .into_iter()
+            .map(|code_hash| MfaRecoveryCodeModel {
+                user_id: user_id.to_string(),
+                code_hash,
+            })
+            .collect::<Vec<_>>();
+        self.conn
+            .transaction::<_, diesel::result::Error, _>(|| {
+                delete(
+                    user_mfa_recovery_codes::table
+                        .filter(user_mfa_recovery_codes::user_id.eq(user_id)),
+                )
+                .execute(self.conn)?;
+                insert_into(user_mfa_recovery_codes::table)
+                    .values(&codes)
+                    .execute(self.conn)?;
+                Ok(())
+            })
+            .map_err(|err| CredentialsStoreError::OperationError {
+                context: "Failed to set MFA recovery codes".to_string(),
+                source: Box::new(err),
+            })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> CredentialsStoreSetMfaRecoveryCodesOperation
+    for CredentialsStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn set_mfa_recovery_codes(
+        &self,
+        user_id: &str,
+        code_hashes: Vec<String>,
+    ) -> Result<(), CredentialsStoreError> {
+        let secret_exists = user_mfa_secrets::table
+            .filter(user_mfa_secrets::user_id.eq(user_id))
+            .count()
+            .get_result::<i64>(self.conn)
+            .map_err(|err| CredentialsStoreError::QueryError {
+                context: "Failed check for existing MFA secret".to_string(),
+                source: Box::new(err),
+            })?
+            > 0;
+        if !secret_exists {
+            return Err(CredentialsStoreError::NotFoundError(format!(
+                "MFA secret not found for user id: {}",
+                user_id
+            )));
+        }
+
+        let codes = code_hashes
+            .into_iter()
+            .map(|code_hash| MfaRecoveryCodeModel {
+                user_id: user_id.to_string(),
+                code_hash,
+            })
+            .collect::<Vec<_>>();
+        self.conn
+            .transaction::<_, diesel::result::Error, _>(|| {
+                delete(
+                    user_mfa_recovery_codes::table
+                        .filter(user_mfa_recovery_codes::user_id.eq(user_id)),
+                )
+                .execute(self.conn)?;
+                insert_into(user_mfa_recovery_codes::table)
+                    .values(&codes)
+                    .execute(self.conn)?;
+                Ok(())
+            })
+            .map_err(|err| CredentialsStoreError::OperationError {
+                context: "Failed to set MFA recovery codes".to_string(),
+                source: Box::new(err),
+            })
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CredentialsStoreOperations;
+use crate::biome::credentials::mfa::MfaSecret;
+use crate::biome::credentials::store::diesel::models::MfaSecretModel;
+use crate::biome::credentials::store::diesel::schema::{user_credentials, user_mfa_secrets};
+use crate::biome::credentials::store::error::CredentialsStoreError;
+use diesel::{
+    dsl::{insert_into, update},
+    prelude::*,
+};
+
+pub(in crate::biome::credentials) trait CredentialsStoreSetMfaSecretOperation {
+    fn set_mfa_secret(&self, secret: MfaSecret) -> Result<(), CredentialsStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> CredentialsStoreSetMfaSecretOperation
+    for CredentialsStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn set_mfa_secret(&self, secret: MfaSecret) -> Result<(), CredentialsStoreError> {
+        let user_exists = user_credentials::table
+            .filter(user_credentials::user_id.eq(&secret.user_id))
+            .count()
+            .get_result::<i64>(self.conn)
+            .map_err(|err| CredentialsStoreError::QueryError {
+                context: "Failed check for existing user id".to_string(),
+                source: Box::new(err),
+            })?
+            > 0;
+        if !user_exists {
+            return Err(CredentialsStoreError::NotFoundError(format!(
+                "Credentials not found for user id: {}",
+                &secret.user_id
+            )));
+        }
+
+        let model = MfaSecretModel::from(secret);
+        // The existing row is updated rather than replaced, so that the user's recovery codes are
+        // kept
+        self.conn
+            .transaction::<_, diesel::result::Error, _>(|| {
+                let updated = update(user_mfa_secrets::table.find(&model.user_id))
+                    .set((
+                        user_mfa_secrets::secret.eq(&model.secret),
+                        user_mfa_secrets::enabled.eq(model.enabled),
+                        user_mfa_secrets::last_used_step.eq(model.last_used_step),
+                    ))
+                    .execute(self.conn)?;
+                if updated == 0 {
+                    insert_into(user_mfa_secrets::table)
+                        .values(&model)
+                        .execute(self.conn)?;
+                }
+                Ok(())
+            })
+            .map_err(|err| CredentialsStoreError::OperationError {
+                context: "Failed to set MFA secret".to_string(),
+                source: Box::new(err),
+            })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> CredentialsStoreSetMfaSecretOperation
+    for CredentialsStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn set_mfa_secret(&self, secret: MfaSecret) -> Result<(), CredentialsStoreError> {
+        let user_exists = user_credentials::table
+            .filter(user_credentials::user_id.eq(&secret.user_id))
+            .count()
+            .get_result::<i64>(self.conn)
+            .map_err(|err| CredentialsStoreError::QueryError {
+                context: "Failed check for existing user id".to_string(),
+                source: Box::new(err),
+            })?
+            > 0;
+        if !user_exists {
+            return Err(CredentialsStoreError::NotFoundError(format!(
+                "Credentials not found for user id: {}",
+                &secret.user_id
+            )));
+        }
+
+        let model = MfaSecretModel::from(secret);
+        // The existing row is updated rather than replaced, so that the user's recovery codes are
+        // kept
+        self.conn
+            .transaction::<_, diesel::result::Error, _>(|| {
+                let updated = update(user_mfa_secrets::table.find(&model.user_id))
+                    .set((
+                        user_mfa_secrets::secret.eq(&model.secret),
+                        user_mfa_secrets::enabled.eq(model.enabled),
+                        user_mfa_secrets::last_used_step.eq(model.last_used_step),
+                    ))
+                    .execute(self.conn)?;
+                if updated == 0 {
+                    insert_into(user_mfa_secrets::table)
+                        .values(&model)
+                        .execute(self.conn)?;
+                }
+                Ok(())
+            })
+            .map_err(|err| CredentialsStoreError::OperationError {
+                context: "Failed to set MFA secret".to_string(),
+                source: Box::new(err),
+            })
+    }
+}
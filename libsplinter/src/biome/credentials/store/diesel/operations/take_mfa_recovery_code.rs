@@ -0,0 +1,52 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CredentialsStoreOperations;
+use crate::biome::credentials::store::diesel::schema::user_mfa_recovery_codes;
+use crate::biome::credentials::store::error::CredentialsStoreError;
+use diesel::{dsl::delete, prelude::*};
+
+pub(in crate::biome::credentials) trait CredentialsStoreTakeMfaRecoveryCodeOperation {
+    fn take_mfa_recovery_code(
+        &self,
+        user_id: &str,
+        code_hash: &str,
+    ) -> Result<(), CredentialsStoreError>;
+}
+
+impl<'a, C> CredentialsStoreTakeMfaRecoveryCodeOperation for CredentialsStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+{
+    fn take_mfa_recovery_code(
+        &self,
+        user_id: &str,
+        code_hash: &str,
+    ) -> Result<(), CredentialsStoreError> {
+        // Removing the code is a single statement, so concurrent requests cannot both use it
+        let removed = delete(user_mfa_recovery_codes::table.find((user_id, code_hash)))
+            .execute(self.conn)
+            .map_err(|err| CredentialsStoreError::OperationError {
+                context: "Failed to take MFA recovery code".to_string(),
+                source: Box::new(err),
+            })?;
+
+        if removed == 0 {
+            return Err(CredentialsStoreError::NotFoundError(
+                "Recovery code not found".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
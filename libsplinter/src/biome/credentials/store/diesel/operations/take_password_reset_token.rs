@@ -0,0 +1,64 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CredentialsStoreOperations;
+use crate::biome::credentials::password_reset::PasswordResetToken;
+use crate::biome::credentials::store::diesel::models::PasswordResetTokenModel;
+use crate::biome::credentials::store::diesel::schema::password_reset_tokens;
+use crate::biome::credentials::store::error::CredentialsStoreError;
+use diesel::{dsl::delete, prelude::*, result::Error::NotFound};
+
+pub(in crate::biome::credentials) trait CredentialsStoreTakePasswordResetTokenOperation {
+    fn take_password_reset_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<PasswordResetToken, CredentialsStoreError>;
+}
+
+impl<'a, C> CredentialsStoreTakePasswordResetTokenOperation for CredentialsStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn take_password_reset_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<PasswordResetToken, CredentialsStoreError> {
+        // The token is removed in the same transaction it is fetched in, so that concurrent
+        // requests cannot both use it
+        let token = self
+            .conn
+            .transaction::<_, diesel::result::Error, _>(|| {
+                let token = password_reset_tokens::table
+                    .find(token_hash)
+                    .first::<PasswordResetTokenModel>(self.conn)
+                    .map(Some)
+                    .or_else(|err| if err == NotFound { Ok(None) } else { Err(err) })?;
+                if token.is_some() {
+                    delete(password_reset_tokens::table.find(token_hash)).execute(self.conn)?;
+                }
+                Ok(token)
+            })
+            .map_err(|err| CredentialsStoreError::OperationError {
+                context: "Failed to take password reset token".to_string(),
+                source: Box::new(err),
+            })?
+            .ok_or_else(|| {
+                CredentialsStoreError::NotFoundError("Password reset token not found".to_string())
+            })?;
+
+        Ok(PasswordResetToken::from(token))
+    }
+}
@@ -20,3 +20,49 @@ table! {
         password -> Text,
     }
 }
+
+table! {
+    password_reset_tokens (token_hash) {
+        token_hash -> Text,
+        user_id -> Text,
+        created -> Int8,
+        expires -> Int8,
+    }
+}
+
+table! {
+    user_mfa_secrets (user_id) {
+        user_id -> Text,
+        secret -> Text,
+        enabled -> Bool,
+        last_used_step -> Nullable<Int8>,
+    }
+}
+
+table! {
+    user_mfa_recovery_codes (user_id, code_hash) {
+        user_id -> Text,
+        code_hash -> Text,
+    }
+}
+
+table! {
+    user_login_attempts (user_id) {
+        user_id -> Text,
+        failed_attempts -> Int4,
+        last_failure -> Int8,
+        locked_until -> Nullable<Int8>,
+    }
+}
+
+table! {
+    user_api_keys (id) {
+        id -> Text,
+        user_id -> Text,
+        display_name -> Text,
+        secret_hash -> Text,
+        scopes -> Text,
+        created -> Int8,
+        expires -> Nullable<Int8>,
+    }
+}
@@ -14,7 +14,17 @@
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+#[cfg(feature = "biome-password-reset")]
+use std::time::SystemTime;
 
+#[cfg(feature = "biome-api-keys")]
+use crate::biome::credentials::api_keys::ApiKey;
+#[cfg(feature = "biome-lockout")]
+use crate::biome::credentials::lockout::LoginAttempts;
+#[cfg(feature = "biome-mfa")]
+use crate::biome::credentials::mfa::MfaSecret;
+#[cfg(feature = "biome-password-reset")]
+use crate::biome::credentials::password_reset::PasswordResetToken;
 use crate::biome::credentials::store::{
     error::CredentialsStoreError, Credentials, CredentialsBuilder, CredentialsStore,
     PasswordEncryptionCost, UsernameId,
@@ -23,12 +33,31 @@ use crate::biome::credentials::store::{
 #[derive(Default, Clone)]
 pub struct MemoryCredentialsStore {
     inner: Arc<Mutex<HashMap<String, Credentials>>>,
+    #[cfg(feature = "biome-password-reset")]
+    reset_tokens: Arc<Mutex<HashMap<String, PasswordResetToken>>>,
+    // TOTP secrets and recovery code hashes, keyed by user ID
+    #[cfg(feature = "biome-mfa")]
+    mfa: Arc<Mutex<HashMap<String, (MfaSecret, Vec<String>)>>>,
+    // Failed login attempts, keyed by user ID
+    #[cfg(feature = "biome-lockout")]
+    login_attempts: Arc<Mutex<HashMap<String, LoginAttempts>>>,
+    // API keys, keyed by key ID
+    #[cfg(feature = "biome-api-keys")]
+    api_keys: Arc<Mutex<HashMap<String, ApiKey>>>,
 }
 
 impl MemoryCredentialsStore {
     pub fn new() -> Self {
         MemoryCredentialsStore {
             inner: Arc::new(Mutex::new(HashMap::new())),
+            #[cfg(feature = "biome-password-reset")]
+            reset_tokens: Arc::new(Mutex::new(HashMap::new())),
+            #[cfg(feature = "biome-mfa")]
+            mfa: Arc::new(Mutex::new(HashMap::new())),
+            #[cfg(feature = "biome-lockout")]
+            login_attempts: Arc::new(Mutex::new(HashMap::new())),
+            #[cfg(feature = "biome-api-keys")]
+            api_keys: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 }
@@ -90,6 +119,15 @@ impl CredentialsStore for MemoryCredentialsStore {
                 source: None,
             })?;
         if inner.remove(user_id).is_some() {
+            // The user's API keys must not outlive their credentials
+            #[cfg(feature = "biome-api-keys")]
+            self.api_keys
+                .lock()
+                .map_err(|_| CredentialsStoreError::StorageError {
+                    context: "Cannot access API keys: mutex lock poisoned".to_string(),
+                    source: None,
+                })?
+                .retain(|_, api_key| api_key.user_id != user_id);
             Ok(())
         } else {
             Err(CredentialsStoreError::NotFoundError(format!(
@@ -180,4 +218,365 @@ impl CredentialsStore for MemoryCredentialsStore {
             })
             .collect())
     }
+
+    #[cfg(feature = "biome-password-reset")]
+    fn add_password_reset_token(
+        &self,
+        token: PasswordResetToken,
+    ) -> Result<(), CredentialsStoreError> {
+        let inner = self
+            .inner
+            .lock()
+            .map_err(|_| CredentialsStoreError::StorageError {
+                context: "Cannot access credentials: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+        if !inner.contains_key(&token.user_id) {
+            return Err(CredentialsStoreError::NotFoundError(format!(
+                "User with user id {} not found",
+                token.user_id
+            )));
+        }
+
+        let mut reset_tokens =
+            self.reset_tokens
+                .lock()
+                .map_err(|_| CredentialsStoreError::StorageError {
+                    context: "Cannot access reset tokens: mutex lock poisoned".to_string(),
+                    source: None,
+                })?;
+        if reset_tokens.contains_key(&token.token_hash) {
+            return Err(CredentialsStoreError::DuplicateError(
+                "Password reset token already exists".to_string(),
+            ));
+        }
+        reset_tokens.insert(token.token_hash.clone(), token);
+        Ok(())
+    }
+
+    #[cfg(feature = "biome-password-reset")]
+    fn take_password_reset_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<PasswordResetToken, CredentialsStoreError> {
+        let mut reset_tokens =
+            self.reset_tokens
+                .lock()
+                .map_err(|_| CredentialsStoreError::StorageError {
+                    context: "Cannot access reset tokens: mutex lock poisoned".to_string(),
+                    source: None,
+                })?;
+        reset_tokens.remove(token_hash).ok_or_else(|| {
+            CredentialsStoreError::NotFoundError("Password reset token not found".to_string())
+        })
+    }
+
+    #[cfg(feature = "biome-password-reset")]
+    fn count_password_reset_tokens(
+        &self,
+        user_id: &str,
+        since: SystemTime,
+    ) -> Result<u64, CredentialsStoreError> {
+        let reset_tokens =
+            self.reset_tokens
+                .lock()
+                .map_err(|_| CredentialsStoreError::StorageError {
+                    context: "Cannot access reset tokens: mutex lock poisoned".to_string(),
+                    source: None,
+                })?;
+        Ok(reset_tokens
+            .values()
+            .filter(|token| token.user_id == user_id && token.created >= since)
+            .count() as u64)
+    }
+
+    #[cfg(feature = "biome-password-reset")]
+    fn remove_password_reset_tokens(&self, user_id: &str) -> Result<(), CredentialsStoreError> {
+        let mut reset_tokens =
+            self.reset_tokens
+                .lock()
+                .map_err(|_| CredentialsStoreError::StorageError {
+                    context: "Cannot access reset tokens: mutex lock poisoned".to_string(),
+                    source: None,
+                })?;
+        reset_tokens.retain(|_, token| token.user_id != user_id);
+        Ok(())
+    }
+    #[cfg(feature = "biome-mfa")]
+    fn set_mfa_secret(&self, secret: MfaSecret) -> Result<(), CredentialsStoreError> {
+        let inner = self
+            .inner
+            .lock()
+            .map_err(|_| CredentialsStoreError::StorageError {
+                context: "Cannot access credentials: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+        if !inner.contains_key(&secret.user_id) {
+            return Err(CredentialsStoreError::NotFoundError(format!(
+                "User with user id {} not found",
+                secret.user_id
+            )));
+        }
+
+        let mut mfa = self
+            .mfa
+            .lock()
+            .map_err(|_| CredentialsStoreError::StorageError {
+                context: "Cannot access MFA secrets: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+        match mfa.get_mut(&secret.user_id) {
+            Some((existing, _)) => *existing = secret,
+            None => {
+                mfa.insert(secret.user_id.clone(), (secret, vec![]));
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "biome-mfa")]
+    fn fetch_mfa_secret(&self, user_id: &str) -> Result<MfaSecret, CredentialsStoreError> {
+        let mfa = self
+            .mfa
+            .lock()
+            .map_err(|_| CredentialsStoreError::StorageError {
+                context: "Cannot access MFA secrets: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+        mfa.get(user_id)
+            .map(|(secret, _)| secret.clone())
+            .ok_or_else(|| {
+                CredentialsStoreError::NotFoundError(format!(
+                    "MFA secret for user id {} not found",
+                    user_id
+                ))
+            })
+    }
+
+    #[cfg(feature = "biome-mfa")]
+    fn remove_mfa_secret(&self, user_id: &str) -> Result<(), CredentialsStoreError> {
+        let mut mfa = self
+            .mfa
+            .lock()
+            .map_err(|_| CredentialsStoreError::StorageError {
+                context: "Cannot access MFA secrets: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+        mfa.remove(user_id).map(|_| ()).ok_or_else(|| {
+            CredentialsStoreError::NotFoundError(format!(
+                "MFA secret for user id {} not found",
+                user_id
+            ))
+        })
+    }
+
+    #[cfg(feature = "biome-mfa")]
+    fn set_mfa_recovery_codes(
+        &self,
+        user_id: &str,
+        code_hashes: Vec<String>,
+    ) -> Result<(), CredentialsStoreError> {
+        let mut mfa = self
+            .mfa
+            .lock()
+            .map_err(|_| CredentialsStoreError::StorageError {
+                context: "Cannot access MFA secrets: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+        match mfa.get_mut(user_id) {
+            Some((_, recovery_codes)) => {
+                *recovery_codes = code_hashes;
+                Ok(())
+            }
+            None => Err(CredentialsStoreError::NotFoundError(format!(
+                "MFA secret for user id {} not found",
+                user_id
+            ))),
+        }
+    }
+
+    #[cfg(feature = "biome-mfa")]
+    fn take_mfa_recovery_code(
+        &self,
+        user_id: &str,
+        code_hash: &str,
+    ) -> Result<(), CredentialsStoreError> {
+        let mut mfa = self
+            .mfa
+            .lock()
+            .map_err(|_| CredentialsStoreError::StorageError {
+                context: "Cannot access MFA secrets: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+        let recovery_codes = mfa
+            .get_mut(user_id)
+            .map(|(_, recovery_codes)| recovery_codes)
+            .ok_or_else(|| {
+                CredentialsStoreError::NotFoundError(format!(
+                    "MFA secret for user id {} not found",
+                    user_id
+                ))
+            })?;
+        match recovery_codes.iter().position(|hash| hash == code_hash) {
+            Some(index) => {
+                recovery_codes.remove(index);
+                Ok(())
+            }
+            None => Err(CredentialsStoreError::NotFoundError(
+                "Recovery code not found".to_string(),
+            )),
+        }
+    }
+
+    #[cfg(feature = "biome-lockout")]
+    fn set_login_attempts(
+        &self,
+        user_id: &str,
+        attempts: LoginAttempts,
+    ) -> Result<(), CredentialsStoreError> {
+        let user_exists = self
+            .inner
+            .lock()
+            .map_err(|_| CredentialsStoreError::StorageError {
+                context: "Cannot access credentials: mutex lock poisoned".to_string(),
+                source: None,
+            })?
+            .contains_key(user_id);
+        if !user_exists {
+            return Err(CredentialsStoreError::NotFoundError(format!(
+                "Credentials not found for user id: {}",
+                user_id
+            )));
+        }
+
+        self.login_attempts
+            .lock()
+            .map_err(|_| CredentialsStoreError::StorageError {
+                context: "Cannot access login attempts: mutex lock poisoned".to_string(),
+                source: None,
+            })?
+            .insert(user_id.to_string(), attempts);
+        Ok(())
+    }
+
+    #[cfg(feature = "biome-lockout")]
+    fn fetch_login_attempts(&self, user_id: &str) -> Result<LoginAttempts, CredentialsStoreError> {
+        let login_attempts =
+            self.login_attempts
+                .lock()
+                .map_err(|_| CredentialsStoreError::StorageError {
+                    context: "Cannot access login attempts: mutex lock poisoned".to_string(),
+                    source: None,
+                })?;
+        login_attempts.get(user_id).cloned().ok_or_else(|| {
+            CredentialsStoreError::NotFoundError(format!(
+                "Login attempts for user id {} not found",
+                user_id
+            ))
+        })
+    }
+
+    #[cfg(feature = "biome-lockout")]
+    fn remove_login_attempts(&self, user_id: &str) -> Result<(), CredentialsStoreError> {
+        let mut login_attempts =
+            self.login_attempts
+                .lock()
+                .map_err(|_| CredentialsStoreError::StorageError {
+                    context: "Cannot access login attempts: mutex lock poisoned".to_string(),
+                    source: None,
+                })?;
+        login_attempts.remove(user_id).map(|_| ()).ok_or_else(|| {
+            CredentialsStoreError::NotFoundError(format!(
+                "Login attempts for user id {} not found",
+                user_id
+            ))
+        })
+    }
+
+    #[cfg(feature = "biome-api-keys")]
+    fn add_api_key(&self, api_key: ApiKey) -> Result<(), CredentialsStoreError> {
+        let user_exists = self
+            .inner
+            .lock()
+            .map_err(|_| CredentialsStoreError::StorageError {
+                context: "Cannot access credentials: mutex lock poisoned".to_string(),
+                source: None,
+            })?
+            .contains_key(&api_key.user_id);
+        if !user_exists {
+            return Err(CredentialsStoreError::NotFoundError(format!(
+                "Credentials not found for user id: {}",
+                api_key.user_id
+            )));
+        }
+
+        let mut api_keys =
+            self.api_keys
+                .lock()
+                .map_err(|_| CredentialsStoreError::StorageError {
+                    context: "Cannot access API keys: mutex lock poisoned".to_string(),
+                    source: None,
+                })?;
+        if api_keys.contains_key(&api_key.id) {
+            return Err(CredentialsStoreError::DuplicateError(format!(
+                "API key {} already exists",
+                api_key.id
+            )));
+        }
+        api_keys.insert(api_key.id.clone(), api_key);
+        Ok(())
+    }
+
+    #[cfg(feature = "biome-api-keys")]
+    fn fetch_api_key(&self, id: &str) -> Result<ApiKey, CredentialsStoreError> {
+        let api_keys = self
+            .api_keys
+            .lock()
+            .map_err(|_| CredentialsStoreError::StorageError {
+                context: "Cannot access API keys: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+        api_keys.get(id).cloned().ok_or_else(|| {
+            CredentialsStoreError::NotFoundError(format!("API key {} not found", id))
+        })
+    }
+
+    #[cfg(feature = "biome-api-keys")]
+    fn list_api_keys(&self, user_id: &str) -> Result<Vec<ApiKey>, CredentialsStoreError> {
+        let api_keys = self
+            .api_keys
+            .lock()
+            .map_err(|_| CredentialsStoreError::StorageError {
+                context: "Cannot access API keys: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+        let mut user_api_keys = api_keys
+            .values()
+            .filter(|api_key| api_key.user_id == user_id)
+            .cloned()
+            .collect::<Vec<_>>();
+        user_api_keys.sort_by_key(|api_key| api_key.created);
+        Ok(user_api_keys)
+    }
+
+    #[cfg(feature = "biome-api-keys")]
+    fn remove_api_key(&self, user_id: &str, id: &str) -> Result<(), CredentialsStoreError> {
+        let mut api_keys =
+            self.api_keys
+                .lock()
+                .map_err(|_| CredentialsStoreError::StorageError {
+                    context: "Cannot access API keys: mutex lock poisoned".to_string(),
+                    source: None,
+                })?;
+        match api_keys.get(id) {
+            Some(api_key) if api_key.user_id == user_id => {
+                api_keys.remove(id);
+                Ok(())
+            }
+            _ => Err(CredentialsStoreError::NotFoundError(format!(
+                "API key {} not found for user id: {}",
+                id, user_id
+            ))),
+        }
+    }
 }
@@ -18,6 +18,8 @@
 pub(in crate::biome) mod diesel;
 pub(in crate::biome) mod memory;
 use std::str::FromStr;
+#[cfg(feature = "biome-password-reset")]
+use std::time::SystemTime;
 mod error;
 
 pub use error::CredentialsStoreError;
@@ -26,6 +28,14 @@ use bcrypt::{hash, verify, DEFAULT_COST};
 
 #[cfg(feature = "diesel")]
 use self::diesel::models::{CredentialsModel, NewCredentialsModel};
+#[cfg(feature = "biome-api-keys")]
+use super::api_keys::ApiKey;
+#[cfg(feature = "biome-lockout")]
+use super::lockout::LoginAttempts;
+#[cfg(feature = "biome-mfa")]
+use super::mfa::MfaSecret;
+#[cfg(feature = "biome-password-reset")]
+use super::password_reset::PasswordResetToken;
 use error::{CredentialsBuilderError, CredentialsError};
 
 const MEDIUM_COST: u32 = 8;
@@ -236,6 +246,242 @@ pub trait CredentialsStore: Send + Sync {
     ///
     /// Returns a CredentialsStoreError if implementation cannot fetch the user IDs
     fn list_usernames(&self) -> Result<Vec<UsernameId>, CredentialsStoreError>;
+
+    /// Adds a password reset token to the underlying storage
+    ///
+    /// # Arguments
+    ///
+    ///  * `token` - The password reset token to be added
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if the implementation cannot add the token or if the
+    /// user does not exist
+    #[cfg(feature = "biome-password-reset")]
+    fn add_password_reset_token(
+        &self,
+        token: PasswordResetToken,
+    ) -> Result<(), CredentialsStoreError>;
+
+    /// Removes a password reset token from the underlying storage and returns it, so that each
+    /// token may only be used once. Expired tokens are returned as well; checking the expiration
+    /// is left to the caller.
+    ///
+    /// # Arguments
+    ///
+    ///  * `token_hash` - The hash of the token
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if implementation cannot remove the token or if the
+    /// token cannot be found
+    #[cfg(feature = "biome-password-reset")]
+    fn take_password_reset_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<PasswordResetToken, CredentialsStoreError>;
+
+    /// Counts the password reset tokens issued for a user since the given time that have not
+    /// been used
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The unique identifier of the user the tokens were issued for
+    ///  * `since` - The earliest time a counted token may have been issued
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if implementation cannot count the tokens
+    #[cfg(feature = "biome-password-reset")]
+    fn count_password_reset_tokens(
+        &self,
+        user_id: &str,
+        since: SystemTime,
+    ) -> Result<u64, CredentialsStoreError>;
+
+    /// Removes all password reset tokens issued for a user
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The unique identifier of the user the tokens were issued for
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if implementation cannot remove the tokens
+    #[cfg(feature = "biome-password-reset")]
+    fn remove_password_reset_tokens(&self, user_id: &str) -> Result<(), CredentialsStoreError>;
+
+    /// Adds a TOTP secret for a user to the underlying storage, replacing the user's existing
+    /// secret if there is one
+    ///
+    /// # Arguments
+    ///
+    ///  * `secret` - The secret to be set
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if the implementation cannot set the secret or if the
+    /// user does not exist
+    #[cfg(feature = "biome-mfa")]
+    fn set_mfa_secret(&self, secret: MfaSecret) -> Result<(), CredentialsStoreError>;
+
+    /// Fetches the TOTP secret for a user
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The unique identifier of the user the secret belongs to
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if implementation cannot fetch the secret or if the user
+    /// does not have a secret
+    #[cfg(feature = "biome-mfa")]
+    fn fetch_mfa_secret(&self, user_id: &str) -> Result<MfaSecret, CredentialsStoreError>;
+
+    /// Removes the TOTP secret and recovery codes for a user
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The unique identifier of the user the secret belongs to
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if implementation cannot remove the secret or if the user
+    /// does not have a secret
+    #[cfg(feature = "biome-mfa")]
+    fn remove_mfa_secret(&self, user_id: &str) -> Result<(), CredentialsStoreError>;
+
+    /// Replaces the recovery codes for a user
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The unique identifier of the user the recovery codes belong to
+    ///  * `code_hashes` - The hashes of the new recovery codes
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if implementation cannot set the recovery codes or if the
+    /// user does not have a secret
+    #[cfg(feature = "biome-mfa")]
+    fn set_mfa_recovery_codes(
+        &self,
+        user_id: &str,
+        code_hashes: Vec<String>,
+    ) -> Result<(), CredentialsStoreError>;
+
+    /// Removes a recovery code for a user, so that each recovery code may only be used once
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The unique identifier of the user the recovery code belongs to
+    ///  * `code_hash` - The hash of the recovery code
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if implementation cannot remove the recovery code or if
+    /// the recovery code cannot be found
+    #[cfg(feature = "biome-mfa")]
+    fn take_mfa_recovery_code(
+        &self,
+        user_id: &str,
+        code_hash: &str,
+    ) -> Result<(), CredentialsStoreError>;
+    /// Sets the failed login attempts for a user, replacing the user's existing attempts if there
+    /// are any
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The unique identifier of the user the attempts were made for
+    ///  * `attempts` - The failed login attempts
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if the implementation cannot set the attempts or if the
+    /// user does not exist
+    #[cfg(feature = "biome-lockout")]
+    fn set_login_attempts(
+        &self,
+        user_id: &str,
+        attempts: LoginAttempts,
+    ) -> Result<(), CredentialsStoreError>;
+
+    /// Fetches the failed login attempts for a user
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The unique identifier of the user the attempts were made for
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if implementation cannot fetch the attempts or if the user
+    /// does not have any failed attempts
+    #[cfg(feature = "biome-lockout")]
+    fn fetch_login_attempts(&self, user_id: &str) -> Result<LoginAttempts, CredentialsStoreError>;
+
+    /// Removes the failed login attempts for a user, which also ends any lockout
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The unique identifier of the user the attempts were made for
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if implementation cannot remove the attempts or if the user
+    /// does not have any failed attempts
+    #[cfg(feature = "biome-lockout")]
+    fn remove_login_attempts(&self, user_id: &str) -> Result<(), CredentialsStoreError>;
+
+    /// Adds an API key for a user
+    ///
+    /// # Arguments
+    ///
+    ///  * `api_key` - The API key to add
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if the implementation cannot add the key, if the user does
+    /// not exist or if a key with the same ID already exists
+    #[cfg(feature = "biome-api-keys")]
+    fn add_api_key(&self, api_key: ApiKey) -> Result<(), CredentialsStoreError>;
+
+    /// Fetches an API key by its ID
+    ///
+    /// # Arguments
+    ///
+    ///  * `id` - The ID of the API key
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if the implementation cannot fetch the key or if the key
+    /// does not exist
+    #[cfg(feature = "biome-api-keys")]
+    fn fetch_api_key(&self, id: &str) -> Result<ApiKey, CredentialsStoreError>;
+
+    /// Lists the API keys of a user, ordered by creation time
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The unique identifier of the user the keys belong to
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if the implementation cannot list the keys
+    #[cfg(feature = "biome-api-keys")]
+    fn list_api_keys(&self, user_id: &str) -> Result<Vec<ApiKey>, CredentialsStoreError>;
+
+    /// Removes an API key of a user
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The unique identifier of the user the key belongs to
+    ///  * `id` - The ID of the API key
+    ///
+    /// # Errors
+    ///
+    /// Returns a CredentialsStoreError if the implementation cannot remove the key or if the user
+    /// does not have a key with the given ID
+    #[cfg(feature = "biome-api-keys")]
+    fn remove_api_key(&self, user_id: &str, id: &str) -> Result<(), CredentialsStoreError>;
 }
 
 impl<CS> CredentialsStore for Box<CS>
@@ -286,6 +532,108 @@ where
     fn list_usernames(&self) -> Result<Vec<UsernameId>, CredentialsStoreError> {
         (**self).list_usernames()
     }
+
+    #[cfg(feature = "biome-password-reset")]
+    fn add_password_reset_token(
+        &self,
+        token: PasswordResetToken,
+    ) -> Result<(), CredentialsStoreError> {
+        (**self).add_password_reset_token(token)
+    }
+
+    #[cfg(feature = "biome-password-reset")]
+    fn take_password_reset_token(
+        &self,
+        token_hash: &str,
+    ) -> Result<PasswordResetToken, CredentialsStoreError> {
+        (**self).take_password_reset_token(token_hash)
+    }
+
+    #[cfg(feature = "biome-password-reset")]
+    fn count_password_reset_tokens(
+        &self,
+        user_id: &str,
+        since: SystemTime,
+    ) -> Result<u64, CredentialsStoreError> {
+        (**self).count_password_reset_tokens(user_id, since)
+    }
+
+    #[cfg(feature = "biome-password-reset")]
+    fn remove_password_reset_tokens(&self, user_id: &str) -> Result<(), CredentialsStoreError> {
+        (**self).remove_password_reset_tokens(user_id)
+    }
+
+    #[cfg(feature = "biome-mfa")]
+    fn set_mfa_secret(&self, secret: MfaSecret) -> Result<(), CredentialsStoreError> {
+        (**self).set_mfa_secret(secret)
+    }
+
+    #[cfg(feature = "biome-mfa")]
+    fn fetch_mfa_secret(&self, user_id: &str) -> Result<MfaSecret, CredentialsStoreError> {
+        (**self).fetch_mfa_secret(user_id)
+    }
+
+    #[cfg(feature = "biome-mfa")]
+    fn remove_mfa_secret(&self, user_id: &str) -> Result<(), CredentialsStoreError> {
+        (**self).remove_mfa_secret(user_id)
+    }
+
+    #[cfg(feature = "biome-mfa")]
+    fn set_mfa_recovery_codes(
+        &self,
+        user_id: &str,
+        code_hashes: Vec<String>,
+    ) -> Result<(), CredentialsStoreError> {
+        (**self).set_mfa_recovery_codes(user_id, code_hashes)
+    }
+
+    #[cfg(feature = "biome-mfa")]
+    fn take_mfa_recovery_code(
+        &self,
+        user_id: &str,
+        code_hash: &str,
+    ) -> Result<(), CredentialsStoreError> {
+        (**self).take_mfa_recovery_code(user_id, code_hash)
+    }
+
+    #[cfg(feature = "biome-lockout")]
+    fn set_login_attempts(
+        &self,
+        user_id: &str,
+        attempts: LoginAttempts,
+    ) -> Result<(), CredentialsStoreError> {
+        (**self).set_login_attempts(user_id, attempts)
+    }
+
+    #[cfg(feature = "biome-lockout")]
+    fn fetch_login_attempts(&self, user_id: &str) -> Result<LoginAttempts, CredentialsStoreError> {
+        (**self).fetch_login_attempts(user_id)
+    }
+
+    #[cfg(feature = "biome-lockout")]
+    fn remove_login_attempts(&self, user_id: &str) -> Result<(), CredentialsStoreError> {
+        (**self).remove_login_attempts(user_id)
+    }
+
+    #[cfg(feature = "biome-api-keys")]
+    fn add_api_key(&self, api_key: ApiKey) -> Result<(), CredentialsStoreError> {
+        (**self).add_api_key(api_key)
+    }
+
+    #[cfg(feature = "biome-api-keys")]
+    fn fetch_api_key(&self, id: &str) -> Result<ApiKey, CredentialsStoreError> {
+        (**self).fetch_api_key(id)
+    }
+
+    #[cfg(feature = "biome-api-keys")]
+    fn list_api_keys(&self, user_id: &str) -> Result<Vec<ApiKey>, CredentialsStoreError> {
+        (**self).list_api_keys(user_id)
+    }
+
+    #[cfg(feature = "biome-api-keys")]
+    fn remove_api_key(&self, user_id: &str, id: &str) -> Result<(), CredentialsStoreError> {
+        (**self).remove_api_key(user_id, id)
+    }
 }
 
 #[cfg(feature = "diesel")]
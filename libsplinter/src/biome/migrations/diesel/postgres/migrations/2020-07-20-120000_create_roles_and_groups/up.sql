@@ -0,0 +1,39 @@
+-- Copyright 2018-2020 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- -----------------------------------------------------------------------------
+
+CREATE TABLE IF NOT EXISTS roles (
+    name                  TEXT          PRIMARY KEY,
+    permissions           TEXT          NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS role_assignments (
+    role_name             TEXT          NOT NULL,
+    assignee_type         TEXT          NOT NULL,
+    assignee_id           TEXT          NOT NULL,
+    PRIMARY KEY (role_name, assignee_type, assignee_id),
+    FOREIGN KEY (role_name) REFERENCES roles(name) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS user_groups (
+    name                  TEXT          PRIMARY KEY
+);
+
+CREATE TABLE IF NOT EXISTS user_group_members (
+    group_name            TEXT          NOT NULL,
+    user_id               TEXT          NOT NULL,
+    PRIMARY KEY (group_name, user_id),
+    FOREIGN KEY (group_name) REFERENCES user_groups(name) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES splinter_user(id) ON DELETE CASCADE
+);
@@ -0,0 +1,21 @@
+-- Copyright 2018-2020 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- -----------------------------------------------------------------------------
+
+ALTER TABLE splinter_user ADD COLUMN created BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE splinter_user ADD COLUMN last_login BIGINT;
+ALTER TABLE splinter_user ADD COLUMN enabled BOOLEAN NOT NULL DEFAULT TRUE;
+
+-- The creation time of existing users is unknown, so the time of the migration is used
+UPDATE splinter_user SET created = CAST(strftime('%s', 'now') AS BIGINT) * 1000;
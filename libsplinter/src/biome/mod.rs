@@ -24,6 +24,14 @@
 //! Private Key Management: API to store and retrieve encrypted private keys.
 //!
 //! User Notifications: API to create and manage user notifications.
+//!
+//! OpenID Connect: API to log in users through an external OpenID Connect
+//! identity provider.
+//!
+//! Authorization: API to grant users permissions through roles and groups.
+
+#[cfg(feature = "biome-authorization")]
+pub mod authorization;
 
 #[cfg(feature = "biome-credentials")]
 pub mod credentials;
@@ -36,6 +44,9 @@ pub mod migrations;
 #[cfg(feature = "biome-notifications")]
 pub mod notifications;
 
+#[cfg(feature = "biome-oidc")]
+pub mod oidc;
+
 #[cfg(feature = "biome-credentials")]
 pub mod refresh_tokens;
 
@@ -43,6 +54,13 @@ pub mod refresh_tokens;
 pub mod rest_api;
 mod user;
 
+#[cfg(all(feature = "biome-authorization", feature = "diesel"))]
+pub use authorization::store::diesel::{DieselGroupStore, DieselRoleStore};
+#[cfg(feature = "biome-authorization")]
+pub use authorization::store::memory::{MemoryGroupStore, MemoryRoleStore};
+#[cfg(feature = "biome-authorization")]
+pub use authorization::store::{GroupStore, RoleStore};
+
 #[cfg(all(feature = "biome-credentials", feature = "diesel"))]
 pub use credentials::store::diesel::DieselCredentialsStore;
 #[cfg(feature = "biome-credentials")]
@@ -57,6 +75,13 @@ pub use key_management::store::memory::MemoryKeyStore;
 #[cfg(feature = "biome-key-management")]
 pub use key_management::store::KeyStore;
 
+#[cfg(all(feature = "biome-notifications", feature = "diesel"))]
+pub use notifications::store::diesel::DieselNotificationStore;
+#[cfg(feature = "biome-notifications")]
+pub use notifications::store::memory::MemoryNotificationStore;
+#[cfg(feature = "biome-notifications")]
+pub use notifications::store::NotificationStore;
+
 #[cfg(all(feature = "biome-credentials", feature = "diesel"))]
 pub use refresh_tokens::store::diesel::DieselRefreshTokenStore;
 #[cfg(feature = "biome-credentials")]
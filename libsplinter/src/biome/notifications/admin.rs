@@ -0,0 +1,437 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Creates Biome notifications from admin service events.
+
+use std::collections::BTreeSet;
+use std::time::SystemTime;
+
+use crate::admin::service::messages::{AdminServiceEvent, CircuitProposal, Vote};
+use crate::admin::service::{AdminServiceEventSubscriber, AdminSubscriberError};
+use crate::biome::key_management::store::KeyStore;
+use crate::hex::to_hex;
+use crate::registry::RegistryReader;
+
+use super::store::NotificationStore;
+use super::Notification;
+
+/// An admin service event subscriber that notifies the Biome users who own the keys involved in a
+/// circuit proposal.
+///
+/// When a proposal is submitted by another node, the owners of the local node's keys are invited
+/// to vote on it. The owner of the requesting key is notified of each vote, and both are notified
+/// when the proposal is accepted or rejected and when the circuit is ready.
+pub struct AdminNotificationSubscriber {
+    node_id: String,
+    registry: Box<dyn RegistryReader>,
+    key_store: Box<dyn KeyStore>,
+    notification_store: Box<dyn NotificationStore>,
+}
+
+impl AdminNotificationSubscriber {
+    /// Creates a new AdminNotificationSubscriber
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id`: The ID of the local node.
+    /// * `registry`: The registry used to look up the local node's keys.
+    /// * `key_store`: The key store used to find the Biome users who own a key.
+    /// * `notification_store`: The store that the notifications are added to.
+    ///
+    pub fn new(
+        node_id: &str,
+        registry: Box<dyn RegistryReader>,
+        key_store: Box<dyn KeyStore>,
+        notification_store: Box<dyn NotificationStore>,
+    ) -> Self {
+        AdminNotificationSubscriber {
+            node_id: node_id.to_string(),
+            registry,
+            key_store,
+            notification_store,
+        }
+    }
+
+    /// Returns the IDs of the Biome users who own any of the given public keys
+    fn key_owners(&self, public_keys: &[String]) -> Result<BTreeSet<String>, AdminSubscriberError> {
+        let keys = self.key_store.list_keys(None).map_err(|err| {
+            AdminSubscriberError::UnableToHandleEvent(format!("Unable to list keys: {}", err))
+        })?;
+
+        Ok(keys
+            .into_iter()
+            .filter(|key| {
+                public_keys
+                    .iter()
+                    .any(|public_key| public_key.eq_ignore_ascii_case(&key.public_key))
+            })
+            .map(|key| key.user_id)
+            .collect())
+    }
+
+    /// Returns the IDs of the Biome users who own the key that requested the proposal
+    fn requester_owners(
+        &self,
+        proposal: &CircuitProposal,
+    ) -> Result<BTreeSet<String>, AdminSubscriberError> {
+        self.key_owners(&[to_hex(&proposal.requester)])
+    }
+
+    /// Returns the IDs of the Biome users who own one of the local node's keys
+    fn local_node_owners(&self) -> Result<BTreeSet<String>, AdminSubscriberError> {
+        let node_keys = self
+            .registry
+            .fetch_node(&self.node_id)
+            .map_err(|err| {
+                AdminSubscriberError::UnableToHandleEvent(format!(
+                    "Unable to fetch local node from registry: {}",
+                    err
+                ))
+            })?
+            .map(|node| node.keys)
+            .unwrap_or_default();
+
+        self.key_owners(&node_keys)
+    }
+
+    /// Returns the title, body and recipients of the notification for the given event
+    fn notification_content(
+        &self,
+        event: &AdminServiceEvent,
+    ) -> Result<(String, String, BTreeSet<String>), AdminSubscriberError> {
+        let content = match event {
+            AdminServiceEvent::ProposalSubmitted(proposal) => {
+                // The requesting node's vote is implied, so only other nodes are asked to vote
+                let recipients = if proposal.requester_node_id == self.node_id {
+                    BTreeSet::new()
+                } else {
+                    self.local_node_owners()?
+                };
+                (
+                    "Circuit proposal submitted".to_string(),
+                    format!(
+                        "Node {} proposed circuit {}; your vote is requested",
+                        proposal.requester_node_id, proposal.circuit_id
+                    ),
+                    recipients,
+                )
+            }
+            AdminServiceEvent::ProposalVote((proposal, signer)) => {
+                let (voter, vote) = proposal
+                    .votes
+                    .iter()
+                    .find(|record| &record.public_key == signer)
+                    .map(|record| {
+                        let vote = match record.vote {
+                            Vote::Accept => "accept",
+                            Vote::Reject => "reject",
+                        };
+                        (record.voter_node_id.as_str(), vote)
+                    })
+                    .unwrap_or(("unknown", "vote on"));
+                (
+                    "Circuit proposal vote".to_string(),
+                    format!(
+                        "Node {} voted to {} your proposal for circuit {}",
+                        voter, vote, proposal.circuit_id
+                    ),
+                    self.requester_owners(proposal)?,
+                )
+            }
+            AdminServiceEvent::ProposalAccepted((proposal, _)) => (
+                "Circuit proposal accepted".to_string(),
+                format!(
+                    "The proposal for circuit {} was accepted by all members",
+                    proposal.circuit_id
+                ),
+                self.participant_owners(proposal)?,
+            ),
+            AdminServiceEvent::ProposalRejected((proposal, signer)) => {
+                let voter = proposal
+                    .votes
+                    .iter()
+                    .find(|record| &record.public_key == signer)
+                    .map(|record| record.voter_node_id.as_str())
+                    .unwrap_or("unknown");
+                (
+                    "Circuit proposal rejected".to_string(),
+                    format!(
+                        "The proposal for circuit {} was rejected by node {}",
+                        proposal.circuit_id, voter
+                    ),
+                    self.participant_owners(proposal)?,
+                )
+            }
+            AdminServiceEvent::CircuitReady(proposal) => (
+                "Circuit ready".to_string(),
+                format!("Circuit {} is ready", proposal.circuit_id),
+                self.participant_owners(proposal)?,
+            ),
+        };
+
+        Ok(content)
+    }
+
+    /// Returns the IDs of the Biome users who requested the proposal or who may vote on it for
+    /// the local node
+    fn participant_owners(
+        &self,
+        proposal: &CircuitProposal,
+    ) -> Result<BTreeSet<String>, AdminSubscriberError> {
+        let mut owners = self.requester_owners(proposal)?;
+        owners.extend(self.local_node_owners()?);
+        Ok(owners)
+    }
+}
+
+impl AdminServiceEventSubscriber for AdminNotificationSubscriber {
+    fn handle_event(
+        &self,
+        admin_service_event: &AdminServiceEvent,
+        _timestamp: &SystemTime,
+    ) -> Result<(), AdminSubscriberError> {
+        let (title, body, recipients) = self.notification_content(admin_service_event)?;
+        if recipients.is_empty() {
+            return Ok(());
+        }
+
+        let event_type = match admin_service_event {
+            AdminServiceEvent::ProposalSubmitted(_) => "ProposalSubmitted",
+            AdminServiceEvent::ProposalVote(_) => "ProposalVote",
+            AdminServiceEvent::ProposalAccepted(_) => "ProposalAccepted",
+            AdminServiceEvent::ProposalRejected(_) => "ProposalRejected",
+            AdminServiceEvent::CircuitReady(_) => "CircuitReady",
+        };
+
+        let notification = Notification::new(&title, &body, recipients.into_iter().collect())
+            .with_property("circuit_id", &admin_service_event.proposal().circuit_id)
+            .with_property("event_type", event_type);
+
+        self.notification_store
+            .add_notification(notification)
+            .map_err(|err| {
+                AdminSubscriberError::UnableToHandleEvent(format!(
+                    "Unable to add notification: {}",
+                    err
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use crate::admin::service::messages::{
+        AuthorizationType, CreateCircuit, DurabilityType, PersistenceType, ProposalType, RouteType,
+        SplinterNode, VoteRecord,
+    };
+    use crate::biome::key_management::Key;
+    #[cfg(feature = "biome-credentials")]
+    use crate::biome::MemoryCredentialsStore;
+    use crate::biome::{MemoryKeyStore, MemoryNotificationStore};
+    use crate::registry::{MetadataPredicate, Node, NodeIter, RegistryError};
+
+    const LOCAL_KEY: &[u8] = &[1, 2, 3];
+    const REMOTE_KEY: &[u8] = &[4, 5, 6];
+
+    /// Verifies that a proposal submitted by another node notifies the owners of the local node's
+    /// keys, and that the resulting votes notify the owner of the requesting key.
+    ///
+    /// 1. Submit a proposal from the remote node and verify that only the owner of the local
+    ///    node's key is notified
+    /// 2. Vote on a proposal requested by the local node and verify that only the owner of the
+    ///    requesting key is notified of the vote
+    /// 3. Mark the circuit as ready and verify that the owner is notified
+    #[test]
+    fn test_proposal_notifications() {
+        let notification_store = MemoryNotificationStore::default();
+        let key_store = new_key_store();
+        key_store
+            .add_key(Key::new(&to_hex(LOCAL_KEY), "", "local-user", "local"))
+            .expect("Unable to add key");
+        key_store
+            .add_key(Key::new("abcdef", "", "other-user", "other"))
+            .expect("Unable to add key");
+
+        let mut nodes = HashMap::new();
+        nodes.insert(
+            "local-node".to_string(),
+            Node::builder("local-node")
+                .with_endpoint("tcps://localhost:8044")
+                .with_display_name("local")
+                .with_key(to_hex(LOCAL_KEY))
+                .build()
+                .expect("Unable to build node"),
+        );
+        let registry = MemRegistry {
+            nodes: Arc::new(Mutex::new(nodes)),
+        };
+
+        let subscriber = AdminNotificationSubscriber::new(
+            "local-node",
+            Box::new(registry),
+            Box::new(key_store),
+            Box::new(notification_store.clone()),
+        );
+
+        let remote_proposal = new_proposal(REMOTE_KEY, "remote-node");
+        subscriber
+            .handle_event(
+                &AdminServiceEvent::ProposalSubmitted(remote_proposal),
+                &SystemTime::now(),
+            )
+            .expect("Unable to handle submitted event");
+
+        let notifications = notification_store
+            .list_notifications("local-user")
+            .expect("Unable to list notifications");
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(
+            notifications[0].properties.get("circuit_id"),
+            Some(&"01234-ABCDE".to_string())
+        );
+        assert_eq!(
+            notifications[0].properties.get("event_type"),
+            Some(&"ProposalSubmitted".to_string())
+        );
+        assert!(notification_store
+            .list_notifications("other-user")
+            .expect("Unable to list notifications")
+            .is_empty());
+
+        let mut local_proposal = new_proposal(LOCAL_KEY, "local-node");
+        subscriber
+            .handle_event(
+                &AdminServiceEvent::ProposalSubmitted(local_proposal.clone()),
+                &SystemTime::now(),
+            )
+            .expect("Unable to handle submitted event");
+        local_proposal.votes.push(VoteRecord {
+            public_key: REMOTE_KEY.to_vec(),
+            vote: Vote::Accept,
+            voter_node_id: "remote-node".to_string(),
+        });
+        subscriber
+            .handle_event(
+                &AdminServiceEvent::ProposalVote((local_proposal.clone(), REMOTE_KEY.to_vec())),
+                &SystemTime::now(),
+            )
+            .expect("Unable to handle vote event");
+
+        let notifications = notification_store
+            .list_notifications("local-user")
+            .expect("Unable to list notifications");
+        assert_eq!(notifications.len(), 2);
+        assert!(notifications
+            .iter()
+            .any(|notification| notification.payload_body
+                == "Node remote-node voted to accept your proposal for circuit 01234-ABCDE"));
+
+        subscriber
+            .handle_event(
+                &AdminServiceEvent::CircuitReady(local_proposal),
+                &SystemTime::now(),
+            )
+            .expect("Unable to handle ready event");
+
+        let notifications = notification_store
+            .list_notifications("local-user")
+            .expect("Unable to list notifications");
+        assert_eq!(notifications.len(), 3);
+        assert!(notifications.iter().all(|notification| notification.unread));
+        assert!(notification_store
+            .list_notifications("other-user")
+            .expect("Unable to list notifications")
+            .is_empty());
+    }
+
+    #[cfg(feature = "biome-credentials")]
+    fn new_key_store() -> MemoryKeyStore {
+        MemoryKeyStore::new(MemoryCredentialsStore::default())
+    }
+
+    #[cfg(not(feature = "biome-credentials"))]
+    fn new_key_store() -> MemoryKeyStore {
+        MemoryKeyStore::new()
+    }
+
+    fn new_proposal(requester: &[u8], requester_node_id: &str) -> CircuitProposal {
+        CircuitProposal {
+            proposal_type: ProposalType::Create,
+            circuit_id: "01234-ABCDE".to_string(),
+            circuit_hash: "abcd".to_string(),
+            circuit: CreateCircuit {
+                circuit_id: "01234-ABCDE".to_string(),
+                roster: vec![],
+                members: vec![
+                    SplinterNode {
+                        node_id: "local-node".to_string(),
+                        endpoints: vec!["tcps://localhost:8044".to_string()],
+                    },
+                    SplinterNode {
+                        node_id: "remote-node".to_string(),
+                        endpoints: vec!["tcps://localhost:8045".to_string()],
+                    },
+                ],
+                authorization_type: AuthorizationType::Trust,
+                persistence: PersistenceType::Any,
+                durability: DurabilityType::NoDurability,
+                routes: RouteType::Any,
+                circuit_management_type: "test".to_string(),
+                application_metadata: vec![],
+                comments: String::new(),
+            },
+            votes: vec![],
+            requester: requester.to_vec(),
+            requester_node_id: requester_node_id.to_string(),
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct MemRegistry {
+        nodes: Arc<Mutex<HashMap<String, Node>>>,
+    }
+
+    impl RegistryReader for MemRegistry {
+        fn list_nodes<'a, 'b: 'a>(
+            &'b self,
+            predicates: &'a [MetadataPredicate],
+        ) -> Result<NodeIter<'a>, RegistryError> {
+            let mut nodes = self
+                .nodes
+                .lock()
+                .expect("mem registry lock was poisoned")
+                .clone();
+            nodes.retain(|_, node| predicates.iter().all(|predicate| predicate.apply(node)));
+            Ok(Box::new(nodes.into_iter().map(|(_, node)| node)))
+        }
+
+        fn count_nodes(&self, predicates: &[MetadataPredicate]) -> Result<u32, RegistryError> {
+            self.list_nodes(predicates).map(|iter| iter.count() as u32)
+        }
+
+        fn fetch_node(&self, identity: &str) -> Result<Option<Node>, RegistryError> {
+            Ok(self
+                .nodes
+                .lock()
+                .expect("mem registry lock was poisoned")
+                .get(identity)
+                .cloned())
+        }
+    }
+}
@@ -15,6 +15,85 @@
  * -----------------------------------------------------------------------------
  */
 
-//! Provides an API for notifications.
+//! Provides an API for sending notifications to Biome users and managing them.
 
+#[cfg(all(feature = "biome-key-management", feature = "registry"))]
+pub mod admin;
 pub mod store;
+
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use uuid::Uuid;
+
+/// Represents a notification sent to one or more Biome users
+#[derive(Clone, Debug, PartialEq)]
+pub struct Notification {
+    pub id: String,
+    pub payload_title: String,
+    pub payload_body: String,
+    pub created: SystemTime,
+    pub recipients: Vec<String>,
+    pub properties: HashMap<String, String>,
+}
+
+impl Notification {
+    /// Creates a new Notification with a unique ID, created at the current time
+    ///
+    /// # Arguments
+    ///
+    /// * `payload_title`: A short summary of the notification.
+    /// * `payload_body`: The full text of the notification.
+    /// * `recipients`: The IDs of the Biome users who receive the notification.
+    ///
+    pub fn new(payload_title: &str, payload_body: &str, recipients: Vec<String>) -> Self {
+        // Notifications are stored with millisecond precision
+        let since_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let created = UNIX_EPOCH + Duration::from_millis(since_epoch.as_millis() as u64);
+
+        Notification {
+            id: Uuid::new_v4().to_string(),
+            payload_title: payload_title.to_string(),
+            payload_body: payload_body.to_string(),
+            created,
+            recipients,
+            properties: HashMap::new(),
+        }
+    }
+
+    /// Adds a property to the notification, such as the ID of the circuit it refers to
+    pub fn with_property(mut self, property: &str, value: &str) -> Self {
+        self.properties
+            .insert(property.to_string(), value.to_string());
+        self
+    }
+}
+
+/// Represents a notification as received by a single Biome user
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserNotification {
+    pub id: String,
+    pub user_id: String,
+    pub payload_title: String,
+    pub payload_body: String,
+    pub created: SystemTime,
+    pub properties: HashMap<String, String>,
+    pub unread: bool,
+}
+
+impl UserNotification {
+    /// Creates the copy of the given notification received by the given user
+    pub(in crate::biome) fn new(notification: &Notification, user_id: &str, unread: bool) -> Self {
+        UserNotification {
+            id: notification.id.clone(),
+            user_id: user_id.to_string(),
+            payload_title: notification.payload_title.clone(),
+            payload_body: notification.payload_body.clone(),
+            created: notification.created,
+            properties: notification.properties.clone(),
+            unread,
+        }
+    }
+}
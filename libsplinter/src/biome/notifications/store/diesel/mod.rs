@@ -16,4 +16,315 @@
  */
 
 pub(in crate::biome) mod models;
+mod operations;
 mod schema;
+
+use diesel::r2d2::{ConnectionManager, Pool};
+
+use crate::biome::notifications::store::{NotificationStore, NotificationStoreError};
+use crate::biome::notifications::{Notification, UserNotification};
+
+use operations::{
+    add_notification::NotificationStoreAddNotificationOperation as _,
+    fetch_notification::NotificationStoreFetchNotificationOperation as _,
+    list_notifications::NotificationStoreListNotificationsOperation as _,
+    mark_read::NotificationStoreMarkReadOperation as _,
+    remove_notification::NotificationStoreRemoveNotificationOperation as _,
+    NotificationStoreOperations,
+};
+
+/// Manages adding, fetching and removing notifications in a database.
+pub struct DieselNotificationStore<C: diesel::Connection + 'static> {
+    connection_pool: Pool<ConnectionManager<C>>,
+}
+
+impl<C: diesel::Connection> DieselNotificationStore<C> {
+    /// Creates a new DieselNotificationStore
+    ///
+    /// # Arguments
+    ///
+    ///  * `connection_pool`: connection pool to the database
+    ///
+    pub fn new(connection_pool: Pool<ConnectionManager<C>>) -> Self {
+        DieselNotificationStore { connection_pool }
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl NotificationStore for DieselNotificationStore<diesel::pg::PgConnection> {
+    fn add_notification(&self, notification: Notification) -> Result<(), NotificationStoreError> {
+        NotificationStoreOperations::new(&*self.connection_pool.get()?)
+            .add_notification(notification)
+    }
+
+    fn list_notifications(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<UserNotification>, NotificationStoreError> {
+        NotificationStoreOperations::new(&*self.connection_pool.get()?).list_notifications(user_id)
+    }
+
+    fn fetch_notification(
+        &self,
+        notification_id: &str,
+        user_id: &str,
+    ) -> Result<UserNotification, NotificationStoreError> {
+        NotificationStoreOperations::new(&*self.connection_pool.get()?)
+            .fetch_notification(notification_id, user_id)
+    }
+
+    fn mark_read(
+        &self,
+        notification_id: &str,
+        user_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        NotificationStoreOperations::new(&*self.connection_pool.get()?)
+            .mark_read(notification_id, user_id)
+    }
+
+    fn remove_notification(
+        &self,
+        notification_id: &str,
+        user_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        NotificationStoreOperations::new(&*self.connection_pool.get()?)
+            .remove_notification(notification_id, user_id)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl NotificationStore for DieselNotificationStore<diesel::sqlite::SqliteConnection> {
+    fn add_notification(&self, notification: Notification) -> Result<(), NotificationStoreError> {
+        NotificationStoreOperations::new(&*self.connection_pool.get()?)
+            .add_notification(notification)
+    }
+
+    fn list_notifications(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<UserNotification>, NotificationStoreError> {
+        NotificationStoreOperations::new(&*self.connection_pool.get()?).list_notifications(user_id)
+    }
+
+    fn fetch_notification(
+        &self,
+        notification_id: &str,
+        user_id: &str,
+    ) -> Result<UserNotification, NotificationStoreError> {
+        NotificationStoreOperations::new(&*self.connection_pool.get()?)
+            .fetch_notification(notification_id, user_id)
+    }
+
+    fn mark_read(
+        &self,
+        notification_id: &str,
+        user_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        NotificationStoreOperations::new(&*self.connection_pool.get()?)
+            .mark_read(notification_id, user_id)
+    }
+
+    fn remove_notification(
+        &self,
+        notification_id: &str,
+        user_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        NotificationStoreOperations::new(&*self.connection_pool.get()?)
+            .remove_notification(notification_id, user_id)
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+pub mod tests {
+    use super::*;
+
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::biome::migrations::run_sqlite_migrations;
+
+    use diesel::{
+        r2d2::{ConnectionManager, Pool},
+        sqlite::SqliteConnection,
+    };
+
+    /// Verify that a SQLite-backed `DieselNotificationStore` correctly supports adding and
+    /// listing notifications.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Create the `DieselNotificationStore`.
+    /// 3. Add a notification for two users, with a property, and a newer notification for only
+    ///    one of them.
+    /// 4. Verify that each user's list contains only their notifications, newest first, unread
+    ///    and with their properties.
+    /// 5. Verify that adding a notification with an existing ID returns a
+    ///    `NotificationStoreError::DuplicateNotificationError`.
+    #[test]
+    fn sqlite_add_and_list() {
+        let pool = create_connection_pool_and_migrate();
+
+        let store = DieselNotificationStore::new(pool);
+
+        let notification1 = create_notification("notification1", 1000, &["user1", "user2"])
+            .with_property("circuit_id", "01234-ABCDE");
+        store
+            .add_notification(notification1.clone())
+            .expect("Failed to add notification1");
+        let notification2 = create_notification("notification2", 2000, &["user1"]);
+        store
+            .add_notification(notification2.clone())
+            .expect("Failed to add notification2");
+
+        assert_eq!(
+            store
+                .list_notifications("user1")
+                .expect("Failed to list notifications for user1"),
+            vec![
+                UserNotification::new(&notification2, "user1", true),
+                UserNotification::new(&notification1, "user1", true),
+            ]
+        );
+        assert_eq!(
+            store
+                .list_notifications("user2")
+                .expect("Failed to list notifications for user2"),
+            vec![UserNotification::new(&notification1, "user2", true)]
+        );
+        assert!(store
+            .list_notifications("user3")
+            .expect("Failed to list notifications for user3")
+            .is_empty());
+
+        match store.add_notification(notification2) {
+            Err(NotificationStoreError::DuplicateNotificationError(_)) => {}
+            res => panic!(
+                "Expected Err(NotificationStoreError::DuplicateNotificationError), got {:?} \
+                 instead",
+                res
+            ),
+        }
+    }
+
+    /// Verify that a SQLite-backed `DieselNotificationStore` correctly supports marking
+    /// notifications as read.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Create the `DieselNotificationStore` and add a notification for two users.
+    /// 3. Mark the notification as read for one user.
+    /// 4. Verify that the notification is read for that user only.
+    /// 5. Verify that marking a notification that the user did not receive returns a
+    ///    `NotificationStoreError::NotFoundError`.
+    #[test]
+    fn sqlite_mark_read() {
+        let pool = create_connection_pool_and_migrate();
+
+        let store = DieselNotificationStore::new(pool);
+
+        let notification = create_notification("notification1", 1000, &["user1", "user2"]);
+        store
+            .add_notification(notification.clone())
+            .expect("Failed to add notification");
+
+        store
+            .mark_read("notification1", "user1")
+            .expect("Failed to mark notification as read");
+
+        assert_eq!(
+            store
+                .fetch_notification("notification1", "user1")
+                .expect("Failed to fetch notification for user1"),
+            UserNotification::new(&notification, "user1", false)
+        );
+        assert_eq!(
+            store
+                .fetch_notification("notification1", "user2")
+                .expect("Failed to fetch notification for user2"),
+            UserNotification::new(&notification, "user2", true)
+        );
+
+        match store.mark_read("notification1", "user3") {
+            Err(NotificationStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(NotificationStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+    }
+
+    /// Verify that a SQLite-backed `DieselNotificationStore` correctly supports removing
+    /// notifications.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Create the `DieselNotificationStore` and add a notification for two users.
+    /// 3. Remove the notification for the first user and verify that only the second user can
+    ///    still fetch it.
+    /// 4. Remove the notification for the second user and verify that removing it again returns
+    ///    a `NotificationStoreError::NotFoundError`.
+    #[test]
+    fn sqlite_remove() {
+        let pool = create_connection_pool_and_migrate();
+
+        let store = DieselNotificationStore::new(pool);
+
+        let notification = create_notification("notification1", 1000, &["user1", "user2"])
+            .with_property("circuit_id", "01234-ABCDE");
+        store
+            .add_notification(notification)
+            .expect("Failed to add notification");
+
+        store
+            .remove_notification("notification1", "user1")
+            .expect("Failed to remove notification for user1");
+        match store.fetch_notification("notification1", "user1") {
+            Err(NotificationStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(NotificationStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+        store
+            .fetch_notification("notification1", "user2")
+            .expect("Failed to fetch notification for user2");
+
+        store
+            .remove_notification("notification1", "user2")
+            .expect("Failed to remove notification for user2");
+        match store.remove_notification("notification1", "user2") {
+            Err(NotificationStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(NotificationStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+    }
+
+    fn create_notification(id: &str, created_millis: u64, recipients: &[&str]) -> Notification {
+        Notification {
+            id: id.to_string(),
+            created: UNIX_EPOCH + Duration::from_millis(created_millis),
+            ..Notification::new(
+                "Circuit proposal",
+                "You were invited to vote on a circuit proposal",
+                recipients
+                    .iter()
+                    .map(|user_id| user_id.to_string())
+                    .collect(),
+            )
+        }
+    }
+
+    /// Creates a connection pool for an in-memory SQLite database with only a single connection
+    /// available. Each connection is backed by a different in-memory SQLite database, so limiting
+    /// the pool to a single connection ensures that the same DB is used for all operations.
+    fn create_connection_pool_and_migrate() -> Pool<ConnectionManager<SqliteConnection>> {
+        let connection_manager = ConnectionManager::<SqliteConnection>::new(":memory:");
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(connection_manager)
+            .expect("Failed to build connection pool");
+
+        run_sqlite_migrations(&*pool.get().expect("Failed to get connection for migrations"))
+            .expect("Failed to run migrations");
+
+        pool
+    }
+}
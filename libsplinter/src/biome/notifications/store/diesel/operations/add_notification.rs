@@ -0,0 +1,152 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::UNIX_EPOCH;
+
+use super::NotificationStoreOperations;
+use crate::biome::notifications::store::diesel::models::{
+    NewNotificationPropertyModel, NotificationModel, UserNotificationModel,
+};
+use crate::biome::notifications::store::diesel::schema::{
+    notification_properties, notifications, user_notifications,
+};
+use crate::biome::notifications::store::NotificationStoreError;
+use crate::biome::notifications::Notification;
+
+use diesel::{
+    dsl::insert_into,
+    prelude::*,
+    result::{DatabaseErrorKind, Error as QueryError},
+};
+
+pub(in crate::biome::notifications) trait NotificationStoreAddNotificationOperation {
+    fn add_notification(&self, notification: Notification) -> Result<(), NotificationStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> NotificationStoreAddNotificationOperation
+    for NotificationStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn add_notification(&self, notification: Notification) -> Result<(), NotificationStoreError> {
+        let notification_id = notification.id.clone();
+        let (notification, user_notifications, properties) = into_models(notification);
+
+        self.conn
+            .transaction::<(), _, _>(|| {
+                insert_into(notifications::table)
+                    .values(vec![notification])
+                    .execute(self.conn)?;
+                insert_into(user_notifications::table)
+                    .values(user_notifications)
+                    .execute(self.conn)?;
+                insert_into(notification_properties::table)
+                    .values(properties)
+                    .execute(self.conn)?;
+                Ok(())
+            })
+            .map_err(|err| match err {
+                QueryError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                    NotificationStoreError::DuplicateNotificationError(format!(
+                        "Notification {} already exists",
+                        notification_id
+                    ))
+                }
+                _ => NotificationStoreError::OperationError {
+                    context: "Failed to add notification".to_string(),
+                    source: Box::new(err),
+                },
+            })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> NotificationStoreAddNotificationOperation
+    for NotificationStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn add_notification(&self, notification: Notification) -> Result<(), NotificationStoreError> {
+        let notification_id = notification.id.clone();
+        let (notification, user_notifications, properties) = into_models(notification);
+
+        self.conn
+            .transaction::<(), _, _>(|| {
+                insert_into(notifications::table)
+                    .values(vec![notification])
+                    .execute(self.conn)?;
+                insert_into(user_notifications::table)
+                    .values(user_notifications)
+                    .execute(self.conn)?;
+                insert_into(notification_properties::table)
+                    .values(properties)
+                    .execute(self.conn)?;
+                Ok(())
+            })
+            .map_err(|err| match err {
+                QueryError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+                    NotificationStoreError::DuplicateNotificationError(format!(
+                        "Notification {} already exists",
+                        notification_id
+                    ))
+                }
+                _ => NotificationStoreError::OperationError {
+                    context: "Failed to add notification".to_string(),
+                    source: Box::new(err),
+                },
+            })
+    }
+}
+
+/// Splits a notification into the rows of the notifications, user_notifications and
+/// notification_properties tables; each recipient's copy of the notification starts out unread.
+fn into_models(
+    notification: Notification,
+) -> (
+    NotificationModel,
+    Vec<UserNotificationModel>,
+    Vec<NewNotificationPropertyModel>,
+) {
+    let user_notifications = notification
+        .recipients
+        .iter()
+        .map(|user_id| UserNotificationModel {
+            notification_id: notification.id.clone(),
+            user_id: user_id.to_string(),
+            unread: true,
+        })
+        .collect();
+    let properties = notification
+        .properties
+        .iter()
+        .map(|(property, value)| NewNotificationPropertyModel {
+            notification_id: notification.id.clone(),
+            property: property.to_string(),
+            property_value: value.to_string(),
+        })
+        .collect();
+    let created = notification
+        .created
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64;
+
+    (
+        NotificationModel {
+            id: notification.id,
+            payload_title: notification.payload_title,
+            payload_body: notification.payload_body,
+            created,
+        },
+        user_notifications,
+        properties,
+    )
+}
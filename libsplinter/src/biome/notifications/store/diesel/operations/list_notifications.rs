@@ -0,0 +1,70 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{to_user_notifications, NotificationStoreOperations};
+use crate::biome::notifications::store::diesel::models::{
+    NotificationModel, NotificationPropertyModel, UserNotificationModel,
+};
+use crate::biome::notifications::store::diesel::schema::{
+    notification_properties, notifications, user_notifications,
+};
+use crate::biome::notifications::store::NotificationStoreError;
+use crate::biome::notifications::UserNotification;
+
+use diesel::prelude::*;
+
+pub(in crate::biome::notifications) trait NotificationStoreListNotificationsOperation {
+    fn list_notifications(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<UserNotification>, NotificationStoreError>;
+}
+
+impl<'a, C> NotificationStoreListNotificationsOperation for NotificationStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+    bool: diesel::deserialize::FromSql<diesel::sql_types::Bool, C::Backend>,
+    C::Backend: diesel::sql_types::HasSqlType<diesel::sql_types::Bool>,
+{
+    fn list_notifications(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<UserNotification>, NotificationStoreError> {
+        let rows = notifications::table
+            .inner_join(user_notifications::table)
+            .filter(user_notifications::user_id.eq(user_id))
+            .order((notifications::created.desc(), notifications::id.asc()))
+            .load::<(NotificationModel, UserNotificationModel)>(self.conn)
+            .map_err(|err| NotificationStoreError::QueryError {
+                context: "Failed to list notifications for user".to_string(),
+                source: Box::new(err),
+            })?;
+
+        let notification_ids = rows
+            .iter()
+            .map(|(notification, _)| notification.id.as_str())
+            .collect::<Vec<_>>();
+        let properties = notification_properties::table
+            .filter(notification_properties::notification_id.eq_any(notification_ids))
+            .load::<NotificationPropertyModel>(self.conn)
+            .map_err(|err| NotificationStoreError::QueryError {
+                context: "Failed to list notification properties".to_string(),
+                source: Box::new(err),
+            })?;
+
+        Ok(to_user_notifications(rows, properties))
+    }
+}
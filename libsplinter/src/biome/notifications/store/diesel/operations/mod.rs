@@ -0,0 +1,70 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub(super) mod add_notification;
+pub(super) mod fetch_notification;
+pub(super) mod list_notifications;
+pub(super) mod mark_read;
+pub(super) mod remove_notification;
+
+use std::collections::HashMap;
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::biome::notifications::store::diesel::models::{
+    NotificationModel, NotificationPropertyModel, UserNotificationModel,
+};
+use crate::biome::notifications::UserNotification;
+
+pub(super) struct NotificationStoreOperations<'a, C> {
+    conn: &'a C,
+}
+
+impl<'a, C> NotificationStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+{
+    pub fn new(conn: &'a C) -> Self {
+        NotificationStoreOperations { conn }
+    }
+}
+
+/// Combines the rows of a notification query with the properties of the notifications that were
+/// returned, keeping the order of the rows.
+fn to_user_notifications(
+    rows: Vec<(NotificationModel, UserNotificationModel)>,
+    properties: Vec<NotificationPropertyModel>,
+) -> Vec<UserNotification> {
+    let mut properties_by_id: HashMap<String, HashMap<String, String>> = HashMap::new();
+    for property in properties {
+        properties_by_id
+            .entry(property.notification_id)
+            .or_insert_with(HashMap::new)
+            .insert(property.property, property.property_value);
+    }
+
+    rows.into_iter()
+        .map(|(notification, user_notification)| UserNotification {
+            properties: properties_by_id
+                .get(&notification.id)
+                .cloned()
+                .unwrap_or_default(),
+            id: notification.id,
+            user_id: user_notification.user_id,
+            payload_title: notification.payload_title,
+            payload_body: notification.payload_body,
+            created: UNIX_EPOCH + Duration::from_millis(notification.created as u64),
+            unread: user_notification.unread,
+        })
+        .collect()
+}
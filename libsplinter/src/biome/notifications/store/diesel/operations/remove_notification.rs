@@ -0,0 +1,81 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::NotificationStoreOperations;
+use crate::biome::notifications::store::diesel::schema::{
+    notification_properties, notifications, user_notifications,
+};
+use crate::biome::notifications::store::NotificationStoreError;
+
+use diesel::{dsl::delete, prelude::*};
+
+pub(in crate::biome::notifications) trait NotificationStoreRemoveNotificationOperation {
+    fn remove_notification(
+        &self,
+        notification_id: &str,
+        user_id: &str,
+    ) -> Result<(), NotificationStoreError>;
+}
+
+impl<'a, C> NotificationStoreRemoveNotificationOperation for NotificationStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+{
+    fn remove_notification(
+        &self,
+        notification_id: &str,
+        user_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        let removed = self
+            .conn
+            .transaction::<_, diesel::result::Error, _>(|| {
+                let removed = delete(user_notifications::table.find((notification_id, user_id)))
+                    .execute(self.conn)?;
+                if removed == 0 {
+                    return Ok(removed);
+                }
+
+                // Once no recipients have the notification, it is removed along with its
+                // properties
+                let remaining = user_notifications::table
+                    .filter(user_notifications::notification_id.eq(notification_id))
+                    .count()
+                    .get_result::<i64>(self.conn)?;
+                if remaining == 0 {
+                    delete(
+                        notification_properties::table
+                            .filter(notification_properties::notification_id.eq(notification_id)),
+                    )
+                    .execute(self.conn)?;
+                    delete(notifications::table.find(notification_id)).execute(self.conn)?;
+                }
+
+                Ok(removed)
+            })
+            .map_err(|err| NotificationStoreError::OperationError {
+                context: "Failed to remove notification".to_string(),
+                source: Box::new(err),
+            })?;
+
+        if removed == 0 {
+            Err(NotificationStoreError::NotFoundError(format!(
+                "Notification {} not found for user {}",
+                notification_id, user_id
+            )))
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -19,13 +19,12 @@ table! {
         id -> Text,
         payload_title -> Text,
         payload_body -> Text,
-        created -> Timestamp,
-        recipients -> Array<Text>,
+        created -> Int8,
     }
 }
 
 table! {
-    user_notifications (notification_id) {
+    user_notifications (notification_id, user_id) {
         notification_id -> Text,
         user_id -> Text,
         unread -> Bool,
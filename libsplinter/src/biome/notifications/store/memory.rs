@@ -0,0 +1,152 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::biome::notifications::store::{NotificationStore, NotificationStoreError};
+use crate::biome::notifications::{Notification, UserNotification};
+
+/// A notification, along with whether it is unread, by recipient
+struct StoredNotification {
+    notification: Notification,
+    unread: HashMap<String, bool>,
+}
+
+#[derive(Default, Clone)]
+pub struct MemoryNotificationStore {
+    inner: Arc<Mutex<HashMap<String, StoredNotification>>>,
+}
+
+impl MemoryNotificationStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(
+        &self,
+    ) -> Result<MutexGuard<HashMap<String, StoredNotification>>, NotificationStoreError> {
+        self.inner
+            .lock()
+            .map_err(|_| NotificationStoreError::StorageError {
+                context: "Cannot access notification store: mutex lock poisoned".to_string(),
+                source: None,
+            })
+    }
+}
+
+impl NotificationStore for MemoryNotificationStore {
+    fn add_notification(&self, notification: Notification) -> Result<(), NotificationStoreError> {
+        let mut inner = self.lock()?;
+
+        if inner.contains_key(&notification.id) {
+            return Err(NotificationStoreError::DuplicateNotificationError(format!(
+                "Notification {} already exists",
+                notification.id
+            )));
+        }
+
+        let unread = notification
+            .recipients
+            .iter()
+            .map(|user_id| (user_id.to_string(), true))
+            .collect();
+        inner.insert(
+            notification.id.clone(),
+            StoredNotification {
+                notification,
+                unread,
+            },
+        );
+        Ok(())
+    }
+
+    fn list_notifications(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<UserNotification>, NotificationStoreError> {
+        let inner = self.lock()?;
+
+        let mut notifications = inner
+            .values()
+            .filter_map(|stored| {
+                stored
+                    .unread
+                    .get(user_id)
+                    .map(|unread| UserNotification::new(&stored.notification, user_id, *unread))
+            })
+            .collect::<Vec<_>>();
+        notifications.sort_by(|a, b| b.created.cmp(&a.created).then_with(|| a.id.cmp(&b.id)));
+        Ok(notifications)
+    }
+
+    fn fetch_notification(
+        &self,
+        notification_id: &str,
+        user_id: &str,
+    ) -> Result<UserNotification, NotificationStoreError> {
+        let inner = self.lock()?;
+
+        inner
+            .get(notification_id)
+            .and_then(|stored| {
+                stored
+                    .unread
+                    .get(user_id)
+                    .map(|unread| UserNotification::new(&stored.notification, user_id, *unread))
+            })
+            .ok_or_else(|| not_found(notification_id, user_id))
+    }
+
+    fn mark_read(
+        &self,
+        notification_id: &str,
+        user_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        let mut inner = self.lock()?;
+
+        let unread = inner
+            .get_mut(notification_id)
+            .and_then(|stored| stored.unread.get_mut(user_id))
+            .ok_or_else(|| not_found(notification_id, user_id))?;
+        *unread = false;
+        Ok(())
+    }
+
+    fn remove_notification(
+        &self,
+        notification_id: &str,
+        user_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        let mut inner = self.lock()?;
+
+        let stored = inner
+            .get_mut(notification_id)
+            .ok_or_else(|| not_found(notification_id, user_id))?;
+        if stored.unread.remove(user_id).is_none() {
+            return Err(not_found(notification_id, user_id));
+        }
+        if stored.unread.is_empty() {
+            inner.remove(notification_id);
+        }
+        Ok(())
+    }
+}
+
+fn not_found(notification_id: &str, user_id: &str) -> NotificationStoreError {
+    NotificationStoreError::NotFoundError(format!(
+        "Notification {} not found for user {}",
+        notification_id, user_id
+    ))
+}
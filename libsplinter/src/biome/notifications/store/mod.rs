@@ -16,3 +16,105 @@
 
 #[cfg(feature = "diesel")]
 pub(in crate::biome) mod diesel;
+mod error;
+pub(in crate::biome) mod memory;
+
+use super::{Notification, UserNotification};
+
+pub use error::NotificationStoreError;
+
+/// Defines methods for sending notifications to users and managing the notifications a user has
+/// received, without defining a storage strategy
+pub trait NotificationStore: Send + Sync {
+    /// Adds a notification to the underlying storage; each of the notification's recipients
+    /// receives an unread copy of it
+    ///
+    /// # Arguments
+    ///
+    ///  * `notification` - The notification to be added
+    fn add_notification(&self, notification: Notification) -> Result<(), NotificationStoreError>;
+
+    /// Lists the notifications received by a user, newest first
+    ///
+    /// # Arguments
+    ///
+    ///  * `user_id` - The ID of the user whose notifications are listed
+    fn list_notifications(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<UserNotification>, NotificationStoreError>;
+
+    /// Fetches a notification received by a user
+    ///
+    /// # Arguments
+    ///
+    ///  * `notification_id` - The ID of the notification
+    ///  * `user_id` - The ID of the user who received the notification
+    fn fetch_notification(
+        &self,
+        notification_id: &str,
+        user_id: &str,
+    ) -> Result<UserNotification, NotificationStoreError>;
+
+    /// Marks a notification received by a user as read
+    ///
+    /// # Arguments
+    ///
+    ///  * `notification_id` - The ID of the notification
+    ///  * `user_id` - The ID of the user who received the notification
+    fn mark_read(&self, notification_id: &str, user_id: &str)
+        -> Result<(), NotificationStoreError>;
+
+    /// Removes a notification from those received by a user; the notification itself is removed
+    /// once none of its recipients have it anymore
+    ///
+    /// # Arguments
+    ///
+    ///  * `notification_id` - The ID of the notification
+    ///  * `user_id` - The ID of the user who received the notification
+    fn remove_notification(
+        &self,
+        notification_id: &str,
+        user_id: &str,
+    ) -> Result<(), NotificationStoreError>;
+}
+
+impl<NS> NotificationStore for Box<NS>
+where
+    NS: NotificationStore + ?Sized,
+{
+    fn add_notification(&self, notification: Notification) -> Result<(), NotificationStoreError> {
+        (**self).add_notification(notification)
+    }
+
+    fn list_notifications(
+        &self,
+        user_id: &str,
+    ) -> Result<Vec<UserNotification>, NotificationStoreError> {
+        (**self).list_notifications(user_id)
+    }
+
+    fn fetch_notification(
+        &self,
+        notification_id: &str,
+        user_id: &str,
+    ) -> Result<UserNotification, NotificationStoreError> {
+        (**self).fetch_notification(notification_id, user_id)
+    }
+
+    fn mark_read(
+        &self,
+        notification_id: &str,
+        user_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        (**self).mark_read(notification_id, user_id)
+    }
+
+    fn remove_notification(
+        &self,
+        notification_id: &str,
+        user_id: &str,
+    ) -> Result<(), NotificationStoreError> {
+        (**self).remove_notification(notification_id, user_id)
+    }
+}
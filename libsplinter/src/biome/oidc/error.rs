@@ -0,0 +1,84 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::fmt;
+
+/// Represents errors raised while logging a user in through an OpenID Connect provider
+#[derive(Debug)]
+pub enum OidcError {
+    /// Returned when an `OidcClient` is built with a missing or invalid argument
+    InvalidArgument(String),
+    /// Returned when the `state` of an authorization response does not match a pending login,
+    /// either because it is unknown, was already used, or has expired
+    InvalidState(String),
+    /// Returned when the ID token issued by the provider fails validation
+    InvalidToken(String),
+    /// Returned when the provider cannot be reached or returns an unexpected response
+    ProviderError {
+        context: String,
+        source: Option<Box<dyn Error + Send>>,
+    },
+    /// Returned when an internal error occurs
+    InternalError {
+        context: String,
+        source: Option<Box<dyn Error + Send>>,
+    },
+}
+
+impl Error for OidcError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            OidcError::InvalidArgument(_) => None,
+            OidcError::InvalidState(_) => None,
+            OidcError::InvalidToken(_) => None,
+            OidcError::ProviderError {
+                source: Some(source),
+                ..
+            } => Some(&**source),
+            OidcError::ProviderError { source: None, .. } => None,
+            OidcError::InternalError {
+                source: Some(source),
+                ..
+            } => Some(&**source),
+            OidcError::InternalError { source: None, .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for OidcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OidcError::InvalidArgument(msg) => write!(f, "invalid argument: {}", msg),
+            OidcError::InvalidState(msg) => write!(f, "invalid state: {}", msg),
+            OidcError::InvalidToken(msg) => write!(f, "invalid ID token: {}", msg),
+            OidcError::ProviderError {
+                context,
+                source: Some(source),
+            } => write!(f, "{}: {}", context, source),
+            OidcError::ProviderError {
+                context,
+                source: None,
+            } => f.write_str(context),
+            OidcError::InternalError {
+                context,
+                source: Some(source),
+            } => write!(f, "{}: {}", context, source),
+            OidcError::InternalError {
+                context,
+                source: None,
+            } => f.write_str(context),
+        }
+    }
+}
@@ -0,0 +1,345 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Validation of OpenID Connect ID tokens, as described in section 3.1.3.7 of the OpenID Connect
+//! Core specification. Only RS256-signed tokens are supported.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use openssl::base64::decode_block;
+use openssl::bn::BigNum;
+use openssl::hash::MessageDigest;
+use openssl::pkey::PKey;
+use openssl::rsa::Rsa;
+use openssl::sign::Verifier;
+
+use super::OidcError;
+
+// Allowance for clock skew between the provider and this node when checking expiration
+const CLOCK_SKEW_SECS: u64 = 60;
+
+/// A JSON Web Key Set, as served from a provider's `jwks_uri`
+#[derive(Deserialize)]
+pub(super) struct JsonWebKeySet {
+    keys: Vec<JsonWebKey>,
+}
+
+#[derive(Deserialize)]
+struct JsonWebKey {
+    kty: String,
+    kid: Option<String>,
+    #[serde(rename = "use")]
+    key_use: Option<String>,
+    n: Option<String>,
+    e: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Header {
+    alg: String,
+    kid: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Audience {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl Audience {
+    fn contains(&self, client_id: &str) -> bool {
+        match self {
+            Audience::Single(aud) => aud == client_id,
+            Audience::Multiple(auds) => auds.iter().any(|aud| aud == client_id),
+        }
+    }
+
+    fn is_multiple(&self) -> bool {
+        match self {
+            Audience::Single(_) => false,
+            Audience::Multiple(auds) => auds.len() > 1,
+        }
+    }
+}
+
+/// The claims of an ID token that are used by Biome
+#[derive(Deserialize)]
+pub(super) struct IdTokenClaims {
+    pub iss: String,
+    pub sub: String,
+    aud: Audience,
+    azp: Option<String>,
+    exp: u64,
+    nonce: Option<String>,
+    pub email: Option<String>,
+    pub name: Option<String>,
+}
+
+/// Verifies the signature of an ID token against the provider's keys and validates its claims,
+/// returning the claims if the token is valid.
+pub(super) fn validate_id_token(
+    id_token: &str,
+    jwks: &JsonWebKeySet,
+    issuer: &str,
+    client_id: &str,
+    nonce: &str,
+    now: SystemTime,
+) -> Result<IdTokenClaims, OidcError> {
+    let parts = id_token.split('.').collect::<Vec<_>>();
+    if parts.len() != 3 {
+        return Err(OidcError::InvalidToken("token is not a signed JWT".into()));
+    }
+
+    let header: Header = serde_json::from_slice(&base64url_decode(parts[0])?)
+        .map_err(|err| OidcError::InvalidToken(format!("malformed header: {}", err)))?;
+    if header.alg != "RS256" {
+        return Err(OidcError::InvalidToken(format!(
+            "unsupported signing algorithm: {}",
+            header.alg
+        )));
+    }
+
+    let key = find_key(jwks, header.kid.as_deref())?;
+    let signature = base64url_decode(parts[2])?;
+    let mut verifier = Verifier::new(MessageDigest::sha256(), &key).map_err(internal_error)?;
+    verifier
+        .update(parts[0].as_bytes())
+        .and_then(|_| verifier.update(b"."))
+        .and_then(|_| verifier.update(parts[1].as_bytes()))
+        .map_err(internal_error)?;
+    // A malformed signature is reported as an error rather than a failed verification
+    if !verifier.verify(&signature).unwrap_or(false) {
+        return Err(OidcError::InvalidToken("signature is not valid".into()));
+    }
+
+    let claims: IdTokenClaims = serde_json::from_slice(&base64url_decode(parts[1])?)
+        .map_err(|err| OidcError::InvalidToken(format!("malformed claims: {}", err)))?;
+
+    if claims.iss.trim_end_matches('/') != issuer.trim_end_matches('/') {
+        return Err(OidcError::InvalidToken(format!(
+            "issued by {} instead of {}",
+            claims.iss, issuer
+        )));
+    }
+    if !claims.aud.contains(client_id) {
+        return Err(OidcError::InvalidToken(
+            "token was not issued for this client".into(),
+        ));
+    }
+    if claims.aud.is_multiple() && claims.azp.as_deref() != Some(client_id) {
+        return Err(OidcError::InvalidToken(
+            "token has multiple audiences but was not authorized for this client".into(),
+        ));
+    }
+
+    let now = now
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| OidcError::InternalError {
+            context: "System time is before the unix epoch".into(),
+            source: Some(Box::new(err)),
+        })?
+        .as_secs();
+    if claims.exp + CLOCK_SKEW_SECS < now {
+        return Err(OidcError::InvalidToken("token has expired".into()));
+    }
+
+    if claims.nonce.as_deref() != Some(nonce) {
+        return Err(OidcError::InvalidToken(
+            "nonce does not match the login request".into(),
+        ));
+    }
+
+    Ok(claims)
+}
+
+/// Finds the RSA signing key with the given key ID. If the token does not specify a key ID, the
+/// key set must contain exactly one RSA signing key.
+fn find_key(
+    jwks: &JsonWebKeySet,
+    kid: Option<&str>,
+) -> Result<PKey<openssl::pkey::Public>, OidcError> {
+    let mut candidates = jwks.keys.iter().filter(|key| {
+        key.kty == "RSA"
+            && key.key_use.as_deref().unwrap_or("sig") == "sig"
+            && (kid.is_none() || key.kid.as_deref() == kid)
+    });
+
+    let key = match (candidates.next(), candidates.next()) {
+        (Some(key), None) => key,
+        (None, _) => {
+            return Err(OidcError::InvalidToken(format!(
+                "no signing key found for key ID {}",
+                kid.unwrap_or("<none>")
+            )))
+        }
+        (Some(_), Some(_)) => {
+            return Err(OidcError::InvalidToken(
+                "token does not specify which of the provider's keys signed it".into(),
+            ))
+        }
+    };
+
+    let (n, e) = match (&key.n, &key.e) {
+        (Some(n), Some(e)) => (n, e),
+        _ => {
+            return Err(OidcError::ProviderError {
+                context: "Provider returned an RSA key without a modulus or exponent".into(),
+                source: None,
+            })
+        }
+    };
+
+    let n = BigNum::from_slice(&base64url_decode(n)?).map_err(internal_error)?;
+    let e = BigNum::from_slice(&base64url_decode(e)?).map_err(internal_error)?;
+    Rsa::from_public_components(n, e)
+        .and_then(PKey::from_rsa)
+        .map_err(|err| OidcError::ProviderError {
+            context: "Provider returned an invalid RSA key".into(),
+            source: Some(Box::new(err)),
+        })
+}
+
+/// Decodes unpadded base64url, as used by JSON Web Tokens and JSON Web Keys
+pub(super) fn base64url_decode(value: &str) -> Result<Vec<u8>, OidcError> {
+    let mut encoded = value.replace('-', "+").replace('_', "/");
+    let padding = (4 - encoded.len() % 4) % 4;
+    encoded.extend(std::iter::repeat('=').take(padding));
+    decode_block(&encoded)
+        .map_err(|_| OidcError::InvalidToken("value is not valid base64url".into()))
+}
+
+/// Encodes bytes as unpadded base64url
+#[cfg(test)]
+pub(super) fn base64url_encode(value: &[u8]) -> String {
+    openssl::base64::encode_block(value)
+        .trim_end_matches('=')
+        .replace('+', "-")
+        .replace('/', "_")
+}
+
+fn internal_error(err: openssl::error::ErrorStack) -> OidcError {
+    OidcError::InternalError {
+        context: "Failed to verify ID token signature".into(),
+        source: Some(Box::new(err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use serde_json::{json, Value};
+
+    use crate::biome::oidc::mock_issuer::{id_token_claims, jwks, sign_id_token, signing_key};
+
+    const ISSUER: &str = "https://issuer.example.com";
+    const CLIENT_ID: &str = "splinter";
+    const NONCE: &str = "nonce";
+
+    fn key_set(key: &PKey<openssl::pkey::Private>) -> JsonWebKeySet {
+        serde_json::from_value(jwks(key)).expect("Failed to parse key set")
+    }
+
+    fn validate(token: &str, jwks: &JsonWebKeySet) -> Result<IdTokenClaims, OidcError> {
+        validate_id_token(token, jwks, ISSUER, CLIENT_ID, NONCE, SystemTime::now())
+    }
+
+    fn expect_invalid(result: Result<IdTokenClaims, OidcError>) {
+        match result {
+            Err(OidcError::InvalidToken(_)) => (),
+            Err(err) => panic!("Expected InvalidToken, got {}", err),
+            Ok(_) => panic!("Expected InvalidToken, but token was accepted"),
+        }
+    }
+
+    /// Test that a correctly signed ID token with valid claims is accepted, including when the
+    /// issuer has a trailing slash or the audience is a list.
+    #[test]
+    fn test_valid_token() {
+        let key = signing_key();
+        let jwks = key_set(&key);
+
+        let claims = id_token_claims(ISSUER, CLIENT_ID, "alice", NONCE);
+        let validated =
+            validate(&sign_id_token(&key, &claims), &jwks).expect("Failed to validate token");
+        assert_eq!(validated.sub, "alice");
+        assert_eq!(validated.email, Some("alice@example.com".to_string()));
+
+        let mut claims = id_token_claims(&format!("{}/", ISSUER), CLIENT_ID, "alice", NONCE);
+        claims["aud"] = json!([CLIENT_ID]);
+        validate(&sign_id_token(&key, &claims), &jwks).expect("Failed to validate token");
+
+        claims["aud"] = json!([CLIENT_ID, "another-client"]);
+        claims["azp"] = json!(CLIENT_ID);
+        validate(&sign_id_token(&key, &claims), &jwks).expect("Failed to validate token");
+    }
+
+    /// Test that tokens signed by another key or with a tampered payload are rejected.
+    #[test]
+    fn test_invalid_signature() {
+        let key = signing_key();
+        let jwks = key_set(&key);
+        let claims = id_token_claims(ISSUER, CLIENT_ID, "alice", NONCE);
+
+        expect_invalid(validate(&sign_id_token(&signing_key(), &claims), &jwks));
+
+        let token = sign_id_token(&key, &claims);
+        let parts = token.split('.').collect::<Vec<_>>();
+        let forged_claims = id_token_claims(ISSUER, CLIENT_ID, "mallory", NONCE);
+        let forged = format!(
+            "{}.{}.{}",
+            parts[0],
+            base64url_encode(forged_claims.to_string().as_bytes()),
+            parts[2]
+        );
+        expect_invalid(validate(&forged, &jwks));
+
+        expect_invalid(validate("not-a-token", &jwks));
+    }
+
+    /// Test that tokens with an unexpected issuer, audience or nonce, or that have expired, are
+    /// rejected.
+    #[test]
+    fn test_invalid_claims() {
+        let key = signing_key();
+        let jwks = key_set(&key);
+        let sign = |claims: &Value| sign_id_token(&key, claims);
+
+        let claims = id_token_claims("https://other.example.com", CLIENT_ID, "alice", NONCE);
+        expect_invalid(validate(&sign(&claims), &jwks));
+
+        let claims = id_token_claims(ISSUER, "another-client", "alice", NONCE);
+        expect_invalid(validate(&sign(&claims), &jwks));
+
+        let mut claims = id_token_claims(ISSUER, CLIENT_ID, "alice", NONCE);
+        claims["aud"] = json!([CLIENT_ID, "another-client"]);
+        expect_invalid(validate(&sign(&claims), &jwks));
+
+        let claims = id_token_claims(ISSUER, CLIENT_ID, "alice", "another-nonce");
+        expect_invalid(validate(&sign(&claims), &jwks));
+
+        let claims = id_token_claims(ISSUER, CLIENT_ID, "alice", NONCE);
+        expect_invalid(validate_id_token(
+            &sign(&claims),
+            &jwks,
+            ISSUER,
+            CLIENT_ID,
+            NONCE,
+            SystemTime::now() + Duration::from_secs(3600),
+        ));
+    }
+}
@@ -0,0 +1,239 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A minimal OpenID Connect provider for tests, which serves discovery, key set and token
+//! endpoints on a local port and issues RS256-signed ID tokens.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use openssl::hash::MessageDigest;
+use openssl::pkey::{PKey, Private};
+use openssl::rsa::Rsa;
+use openssl::sign::Signer;
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::hex::to_hex;
+
+use super::id_token::base64url_encode;
+
+const KEY_ID: &str = "mock-key";
+
+struct Inner {
+    url: String,
+    client_id: String,
+    key: PKey<Private>,
+    // Authorization codes that have been issued, mapped to the nonce and subject they are for
+    codes: Mutex<HashMap<String, (String, String)>>,
+}
+
+pub(crate) struct MockIssuer {
+    inner: Arc<Inner>,
+}
+
+impl MockIssuer {
+    /// Starts an issuer that issues ID tokens for the given client ID
+    pub fn start(client_id: &str) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind mock issuer");
+        let port = listener
+            .local_addr()
+            .expect("Failed to get mock issuer address")
+            .port();
+
+        let inner = Arc::new(Inner {
+            url: format!("http://127.0.0.1:{}", port),
+            client_id: client_id.to_string(),
+            key: signing_key(),
+            codes: Mutex::new(HashMap::new()),
+        });
+
+        let thread_inner = inner.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                handle_request(&thread_inner, stream);
+            }
+        });
+
+        MockIssuer { inner }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.inner.url
+    }
+
+    /// Simulates the user logging in at the provider as `subject`, returning the authorization
+    /// code and state that the provider would send to the redirect URL.
+    pub fn authorize(&self, authorization_url: &str, subject: &str) -> (String, String) {
+        let url = Url::parse(authorization_url).expect("Invalid authorization URL");
+        let params = url.query_pairs().into_owned().collect::<HashMap<_, _>>();
+
+        let mut code = [0; 8];
+        openssl::rand::rand_bytes(&mut code).expect("Failed to generate code");
+        let code = to_hex(&code);
+
+        self.inner
+            .codes
+            .lock()
+            .expect("codes lock poisoned")
+            .insert(code.clone(), (params["nonce"].clone(), subject.to_string()));
+
+        (code, params["state"].clone())
+    }
+}
+
+/// Generates an RSA key for signing ID tokens
+pub(crate) fn signing_key() -> PKey<Private> {
+    Rsa::generate(2048)
+        .and_then(PKey::from_rsa)
+        .expect("Failed to generate key")
+}
+
+/// Returns the JSON Web Key Set containing the public part of the given key
+pub(crate) fn jwks(key: &PKey<Private>) -> Value {
+    let rsa = key.rsa().expect("Key is not an RSA key");
+    json!({
+        "keys": [{
+            "kty": "RSA",
+            "kid": KEY_ID,
+            "use": "sig",
+            "alg": "RS256",
+            "n": base64url_encode(&rsa.n().to_vec()),
+            "e": base64url_encode(&rsa.e().to_vec()),
+        }]
+    })
+}
+
+/// Signs the given claims as an RS256 JSON Web Token
+pub(crate) fn sign_id_token(key: &PKey<Private>, claims: &Value) -> String {
+    let header = json!({ "alg": "RS256", "typ": "JWT", "kid": KEY_ID });
+    let signing_input = format!(
+        "{}.{}",
+        base64url_encode(header.to_string().as_bytes()),
+        base64url_encode(claims.to_string().as_bytes())
+    );
+
+    let mut signer = Signer::new(MessageDigest::sha256(), key).expect("Failed to create signer");
+    signer
+        .update(signing_input.as_bytes())
+        .expect("Failed to sign token");
+    let signature = signer.sign_to_vec().expect("Failed to sign token");
+
+    format!("{}.{}", signing_input, base64url_encode(&signature))
+}
+
+/// Returns valid ID token claims for the given issuer, client ID, subject and nonce
+pub(crate) fn id_token_claims(issuer: &str, client_id: &str, subject: &str, nonce: &str) -> Value {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is before the unix epoch")
+        .as_secs();
+    json!({
+        "iss": issuer,
+        "sub": subject,
+        "aud": client_id,
+        "iat": now,
+        "exp": now + 300,
+        "nonce": nonce,
+        "email": format!("{}@example.com", subject),
+        "name": subject,
+    })
+}
+
+fn handle_request(inner: &Inner, mut stream: TcpStream) {
+    let mut reader = BufReader::new(&stream);
+
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).is_err() {
+            return;
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(index) = header.find(':') {
+            if header[..index].eq_ignore_ascii_case("content-length") {
+                content_length = header[index + 1..].trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    if reader.read_exact(&mut body).is_err() {
+        return;
+    }
+
+    let (status, response) = match (method.as_str(), path.as_str()) {
+        ("GET", "/.well-known/openid-configuration") => (
+            "200 OK",
+            json!({
+                "issuer": inner.url,
+                "authorization_endpoint": format!("{}/authorize", inner.url),
+                "token_endpoint": format!("{}/token", inner.url),
+                "jwks_uri": format!("{}/jwks", inner.url),
+            }),
+        ),
+        ("GET", "/jwks") => ("200 OK", jwks(&inner.key)),
+        ("POST", "/token") => {
+            let params = url::form_urlencoded::parse(&body)
+                .into_owned()
+                .collect::<HashMap<_, _>>();
+            let issued = params.get("code").and_then(|code| {
+                inner
+                    .codes
+                    .lock()
+                    .expect("codes lock poisoned")
+                    .remove(code)
+            });
+            match issued {
+                Some((nonce, subject)) => {
+                    let claims = id_token_claims(&inner.url, &inner.client_id, &subject, &nonce);
+                    (
+                        "200 OK",
+                        json!({
+                            "access_token": "mock-access-token",
+                            "token_type": "Bearer",
+                            "id_token": sign_id_token(&inner.key, &claims),
+                        }),
+                    )
+                }
+                None => ("400 Bad Request", json!({ "error": "invalid_grant" })),
+            }
+        }
+        _ => ("404 Not Found", json!({})),
+    };
+
+    let response = response.to_string();
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        response.len(),
+        response
+    );
+}
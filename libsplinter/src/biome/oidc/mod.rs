@@ -0,0 +1,531 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides login through an external OpenID Connect identity provider, using the authorization
+//! code flow.
+//!
+//! The provider's endpoints are discovered from its issuer URL. A login starts by redirecting the
+//! user to the URL returned by `OidcClient::start_login`; once the user has authenticated, the
+//! provider redirects back to the configured redirect URL with an authorization code, which is
+//! exchanged for an ID token by `OidcClient::authenticate`.
+//!
+//! Each login is bound to the user agent that started it by a secret that is kept out of the
+//! authorization URL (the REST API stores it in a cookie), so a login cannot be completed by a
+//! different user agent that obtained the state.
+//!
+//! Biome users are identified by the provider's `sub` claim: the user ID is derived from the
+//! issuer and subject, so the same provider account always maps to the same Biome user.
+
+mod error;
+mod id_token;
+#[cfg(test)]
+pub(crate) mod mock_issuer;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use openssl::rand::rand_bytes;
+use openssl::sha::sha256;
+use reqwest::blocking::Client;
+use url::Url;
+
+use crate::hex::to_hex;
+
+use id_token::{validate_id_token, JsonWebKeySet};
+
+pub use error::OidcError;
+
+const DISCOVERY_PATH: &str = "/.well-known/openid-configuration";
+const DEFAULT_SCOPES: &[&str] = &["openid", "profile", "email"];
+// How long a user has to complete a login at the provider
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(600);
+// The most logins that may be pending at once; the oldest is dropped to make room for a new one
+const MAX_PENDING_LOGINS: usize = 1024;
+
+/// The identity of a user that has logged in through an OpenID Connect provider
+#[derive(Clone, Debug, PartialEq)]
+pub struct OidcIdentity {
+    /// The Biome user ID for the identity, derived from the issuer and subject
+    pub user_id: String,
+    /// The provider's identifier for the user (the `sub` claim)
+    pub subject: String,
+    /// The user's email address, if the provider included it
+    pub email: Option<String>,
+    /// The user's name, if the provider included it
+    pub name: Option<String>,
+}
+
+#[derive(Clone, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+/// A login that has been started by `OidcClient::start_login`
+#[derive(Clone, Debug)]
+pub struct OidcLogin {
+    /// The provider's URL that the user should be redirected to
+    pub authorization_url: String,
+    /// The secret that binds the login to the user agent that started it. It must be kept by the
+    /// user agent, but not sent to the provider, and presented when the login is completed.
+    pub binding: String,
+}
+
+struct PendingLogin {
+    nonce: String,
+    binding: String,
+    started: Instant,
+}
+
+/// A client for a single OpenID Connect provider.
+///
+/// The client keeps track of the logins it has started, so the callback must be handled by the
+/// same client that produced the authorization URL.
+pub struct OidcClient {
+    issuer_url: String,
+    client_id: String,
+    client_secret: String,
+    redirect_url: String,
+    scopes: Vec<String>,
+    http_client: Client,
+    provider_metadata: Mutex<Option<ProviderMetadata>>,
+    pending_logins: Mutex<HashMap<String, PendingLogin>>,
+}
+
+impl OidcClient {
+    /// Returns the URL of the provider
+    pub fn issuer_url(&self) -> &str {
+        &self.issuer_url
+    }
+
+    /// Returns the URL the provider redirects to after the user has authenticated
+    pub fn redirect_url(&self) -> &str {
+        &self.redirect_url
+    }
+
+    /// Starts a login, returning the provider's URL that the user should be redirected to and the
+    /// secret that binds the login to the user agent.
+    ///
+    /// The provider's metadata is fetched the first time this is called.
+    pub fn start_login(&self) -> Result<OidcLogin, OidcError> {
+        let provider = self.provider_metadata()?;
+
+        let state = random_token()?;
+        let nonce = random_token()?;
+        let binding = random_token()?;
+
+        let mut url = Url::parse(&provider.authorization_endpoint).map_err(|err| {
+            OidcError::ProviderError {
+                context: "Provider returned an invalid authorization endpoint".into(),
+                source: Some(Box::new(err)),
+            }
+        })?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.client_id)
+            .append_pair("redirect_uri", &self.redirect_url)
+            .append_pair("scope", &self.scopes.join(" "))
+            .append_pair("state", &state)
+            .append_pair("nonce", &nonce);
+
+        let mut pending_logins = self.pending_logins.lock().map_err(|_| lock_error())?;
+        pending_logins.retain(|_, pending| pending.started.elapsed() < LOGIN_TIMEOUT);
+        if pending_logins.len() >= MAX_PENDING_LOGINS {
+            let oldest = pending_logins
+                .iter()
+                .min_by_key(|(_, pending)| pending.started)
+                .map(|(state, _)| state.clone());
+            if let Some(oldest) = oldest {
+                pending_logins.remove(&oldest);
+            }
+        }
+        pending_logins.insert(
+            state,
+            PendingLogin {
+                nonce,
+                binding: binding.clone(),
+                started: Instant::now(),
+            },
+        );
+
+        Ok(OidcLogin {
+            authorization_url: url.to_string(),
+            binding,
+        })
+    }
+
+    /// Completes a login using the authorization code and state that the provider returned to
+    /// the redirect URL, and the binding that was returned when the login was started. The code
+    /// is exchanged for an ID token, which is validated and used to determine the user's identity.
+    ///
+    /// Each state may only be used once.
+    pub fn authenticate(
+        &self,
+        code: &str,
+        state: &str,
+        binding: &str,
+    ) -> Result<OidcIdentity, OidcError> {
+        let pending = self
+            .pending_logins
+            .lock()
+            .map_err(|_| lock_error())?
+            .remove(state)
+            .filter(|pending| pending.started.elapsed() < LOGIN_TIMEOUT)
+            .ok_or_else(|| {
+                OidcError::InvalidState("no pending login for the given state".into())
+            })?;
+        if pending.binding.len() != binding.len()
+            || !openssl::memcmp::eq(pending.binding.as_bytes(), binding.as_bytes())
+        {
+            return Err(OidcError::InvalidState(
+                "login was started by a different user agent".into(),
+            ));
+        }
+
+        let provider = self.provider_metadata()?;
+
+        let response = self
+            .http_client
+            .post(&provider.token_endpoint)
+            .basic_auth(&self.client_id, Some(&self.client_secret))
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", self.redirect_url.as_str()),
+            ])
+            .send()
+            .map_err(|err| OidcError::ProviderError {
+                context: "Failed to request token from provider".into(),
+                source: Some(Box::new(err)),
+            })?;
+        if !response.status().is_success() {
+            return Err(OidcError::ProviderError {
+                context: format!(
+                    "Provider rejected the authorization code with status {}",
+                    response.status()
+                ),
+                source: None,
+            });
+        }
+        let token_response: TokenResponse =
+            response.json().map_err(|err| OidcError::ProviderError {
+                context: "Provider returned an invalid token response".into(),
+                source: Some(Box::new(err)),
+            })?;
+
+        let jwks: JsonWebKeySet = self
+            .http_client
+            .get(&provider.jwks_uri)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.json())
+            .map_err(|err| OidcError::ProviderError {
+                context: "Failed to fetch provider's signing keys".into(),
+                source: Some(Box::new(err)),
+            })?;
+
+        let claims = validate_id_token(
+            &token_response.id_token,
+            &jwks,
+            &provider.issuer,
+            &self.client_id,
+            &pending.nonce,
+            SystemTime::now(),
+        )?;
+
+        Ok(OidcIdentity {
+            user_id: user_id(&claims.iss, &claims.sub),
+            subject: claims.sub,
+            email: claims.email,
+            name: claims.name,
+        })
+    }
+
+    /// Returns the provider's metadata, fetching it from the discovery endpoint if it has not
+    /// been fetched yet
+    fn provider_metadata(&self) -> Result<ProviderMetadata, OidcError> {
+        let mut cached = self.provider_metadata.lock().map_err(|_| lock_error())?;
+        if let Some(metadata) = &*cached {
+            return Ok(metadata.clone());
+        }
+
+        let metadata: ProviderMetadata = self
+            .http_client
+            .get(&format!("{}{}", self.issuer_url, DISCOVERY_PATH))
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.json())
+            .map_err(|err| OidcError::ProviderError {
+                context: "Failed to fetch provider metadata".into(),
+                source: Some(Box::new(err)),
+            })?;
+
+        if metadata.issuer.trim_end_matches('/') != self.issuer_url {
+            return Err(OidcError::ProviderError {
+                context: format!(
+                    "Provider metadata is for issuer {} instead of {}",
+                    metadata.issuer, self.issuer_url
+                ),
+                source: None,
+            });
+        }
+
+        *cached = Some(metadata.clone());
+        Ok(metadata)
+    }
+}
+
+/// Builds an `OidcClient`.
+///
+/// The issuer URL, client ID, client secret and redirect URL are required.
+#[derive(Default)]
+pub struct OidcClientBuilder {
+    issuer_url: Option<String>,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+    redirect_url: Option<String>,
+    scopes: Option<Vec<String>>,
+}
+
+impl OidcClientBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the URL of the provider, which must match the `iss` claim of its ID tokens
+    pub fn with_issuer_url(mut self, issuer_url: &str) -> Self {
+        self.issuer_url = Some(issuer_url.to_string());
+        self
+    }
+
+    /// Sets the client ID that Splinter is registered with at the provider
+    pub fn with_client_id(mut self, client_id: &str) -> Self {
+        self.client_id = Some(client_id.to_string());
+        self
+    }
+
+    /// Sets the client secret that Splinter is registered with at the provider
+    pub fn with_client_secret(mut self, client_secret: &str) -> Self {
+        self.client_secret = Some(client_secret.to_string());
+        self
+    }
+
+    /// Sets the URL the provider redirects to after the user has authenticated. This must be
+    /// registered with the provider, and should route to `/biome/oidc/callback`.
+    pub fn with_redirect_url(mut self, redirect_url: &str) -> Self {
+        self.redirect_url = Some(redirect_url.to_string());
+        self
+    }
+
+    /// Sets the scopes to request; `openid` is always requested. Defaults to `openid`, `profile`
+    /// and `email`.
+    pub fn with_scopes(mut self, scopes: &[&str]) -> Self {
+        self.scopes = Some(scopes.iter().map(|scope| scope.to_string()).collect());
+        self
+    }
+
+    pub fn build(self) -> Result<OidcClient, OidcError> {
+        let issuer_url = self
+            .issuer_url
+            .ok_or_else(|| OidcError::InvalidArgument("missing issuer URL".into()))?;
+        Url::parse(&issuer_url).map_err(|err| {
+            OidcError::InvalidArgument(format!("invalid issuer URL {}: {}", issuer_url, err))
+        })?;
+        let redirect_url = self
+            .redirect_url
+            .ok_or_else(|| OidcError::InvalidArgument("missing redirect URL".into()))?;
+        Url::parse(&redirect_url).map_err(|err| {
+            OidcError::InvalidArgument(format!("invalid redirect URL {}: {}", redirect_url, err))
+        })?;
+
+        let mut scopes = self
+            .scopes
+            .unwrap_or_else(|| DEFAULT_SCOPES.iter().map(|s| s.to_string()).collect());
+        if !scopes.iter().any(|scope| scope == "openid") {
+            scopes.insert(0, "openid".into());
+        }
+
+        Ok(OidcClient {
+            issuer_url: issuer_url.trim_end_matches('/').to_string(),
+            client_id: self
+                .client_id
+                .ok_or_else(|| OidcError::InvalidArgument("missing client ID".into()))?,
+            client_secret: self
+                .client_secret
+                .ok_or_else(|| OidcError::InvalidArgument("missing client secret".into()))?,
+            redirect_url,
+            scopes,
+            http_client: Client::new(),
+            provider_metadata: Mutex::new(None),
+            pending_logins: Mutex::new(HashMap::new()),
+        })
+    }
+}
+
+/// Derives the Biome user ID for a provider's subject
+fn user_id(issuer: &str, subject: &str) -> String {
+    to_hex(&sha256(
+        format!("{}|{}", issuer.trim_end_matches('/'), subject).as_bytes(),
+    ))
+}
+
+fn random_token() -> Result<String, OidcError> {
+    let mut bytes = [0; 16];
+    rand_bytes(&mut bytes).map_err(|err| OidcError::InternalError {
+        context: "Failed to generate random login state".into(),
+        source: Some(Box::new(err)),
+    })?;
+    Ok(to_hex(&bytes))
+}
+
+fn lock_error() -> OidcError {
+    OidcError::InternalError {
+        context: "OIDC client lock was poisoned".into(),
+        source: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mock_issuer::MockIssuer;
+
+    const CLIENT_ID: &str = "splinter";
+
+    fn client(issuer: &MockIssuer) -> OidcClient {
+        OidcClientBuilder::new()
+            .with_issuer_url(issuer.url())
+            .with_client_id(CLIENT_ID)
+            .with_client_secret("secret")
+            .with_redirect_url("http://localhost:8080/biome/oidc/callback")
+            .build()
+            .expect("Failed to build client")
+    }
+
+    /// Test that a full authorization code login against a mock issuer returns the user's
+    /// identity, that the user ID is stable across logins, and that a state may only be used once.
+    #[test]
+    fn test_authorization_code_login() {
+        let issuer = MockIssuer::start(CLIENT_ID);
+        let client = client(&issuer);
+
+        let login = client.start_login().expect("Failed to start login");
+        assert!(login
+            .authorization_url
+            .starts_with(&format!("{}/authorize", issuer.url())));
+        assert!(!login.authorization_url.contains(&login.binding));
+        let (code, state) = issuer.authorize(&login.authorization_url, "alice");
+
+        let identity = client
+            .authenticate(&code, &state, &login.binding)
+            .expect("Failed to authenticate");
+        assert_eq!(identity.subject, "alice");
+        assert_eq!(identity.email, Some("alice@example.com".to_string()));
+        assert_eq!(identity.user_id, user_id(issuer.url(), "alice"));
+
+        match client.authenticate(&code, &state, &login.binding) {
+            Err(OidcError::InvalidState(_)) => (),
+            res => panic!("Expected InvalidState, got {:?}", res),
+        }
+
+        let login = client.start_login().expect("Failed to start login");
+        let (code, state) = issuer.authorize(&login.authorization_url, "alice");
+        let second_identity = client
+            .authenticate(&code, &state, &login.binding)
+            .expect("Failed to authenticate");
+        assert_eq!(identity.user_id, second_identity.user_id);
+    }
+
+    /// Test that a login fails if the state was not produced by the client.
+    #[test]
+    fn test_unknown_state() {
+        let issuer = MockIssuer::start(CLIENT_ID);
+        let client = client(&issuer);
+
+        let login = client.start_login().expect("Failed to start login");
+        let (code, _) = issuer.authorize(&login.authorization_url, "alice");
+
+        match client.authenticate(&code, "forged-state", &login.binding) {
+            Err(OidcError::InvalidState(_)) => (),
+            res => panic!("Expected InvalidState, got {:?}", res),
+        }
+    }
+
+    /// Test that a login fails if it is completed without the binding returned when it was
+    /// started, such as by a different user agent, and that the state cannot be retried.
+    #[test]
+    fn test_wrong_binding() {
+        let issuer = MockIssuer::start(CLIENT_ID);
+        let client = client(&issuer);
+
+        let login = client.start_login().expect("Failed to start login");
+        let other_login = client.start_login().expect("Failed to start login");
+        let (code, state) = issuer.authorize(&login.authorization_url, "alice");
+
+        match client.authenticate(&code, &state, &other_login.binding) {
+            Err(OidcError::InvalidState(_)) => (),
+            res => panic!("Expected InvalidState, got {:?}", res),
+        }
+        match client.authenticate(&code, &state, &login.binding) {
+            Err(OidcError::InvalidState(_)) => (),
+            res => panic!("Expected InvalidState, got {:?}", res),
+        }
+    }
+
+    /// Test that the number of pending logins is capped, dropping the oldest login.
+    #[test]
+    fn test_pending_logins_capped() {
+        let issuer = MockIssuer::start(CLIENT_ID);
+        let client = client(&issuer);
+
+        let first_login = client.start_login().expect("Failed to start login");
+        let (code, state) = issuer.authorize(&first_login.authorization_url, "alice");
+        for _ in 0..MAX_PENDING_LOGINS {
+            client.start_login().expect("Failed to start login");
+        }
+        assert_eq!(
+            client.pending_logins.lock().unwrap().len(),
+            MAX_PENDING_LOGINS
+        );
+
+        match client.authenticate(&code, &state, &first_login.binding) {
+            Err(OidcError::InvalidState(_)) => (),
+            res => panic!("Expected InvalidState, got {:?}", res),
+        }
+    }
+
+    /// Test that a client for a different client ID rejects ID tokens issued for another client.
+    #[test]
+    fn test_wrong_audience() {
+        let issuer = MockIssuer::start("another-client");
+        let client = client(&issuer);
+
+        let login = client.start_login().expect("Failed to start login");
+        let (code, state) = issuer.authorize(&login.authorization_url, "alice");
+
+        match client.authenticate(&code, &state, &login.binding) {
+            Err(OidcError::InvalidToken(_)) => (),
+            res => panic!("Expected InvalidToken, got {:?}", res),
+        }
+    }
+}
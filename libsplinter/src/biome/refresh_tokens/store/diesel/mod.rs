@@ -16,10 +16,26 @@ mod models;
 mod operations;
 mod schema;
 
+#[cfg(feature = "biome-sessions")]
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use diesel::r2d2::{ConnectionManager, Pool};
 
+#[cfg(feature = "biome-sessions")]
+use crate::biome::refresh_tokens::store::Session;
 use crate::biome::refresh_tokens::store::{RefreshTokenError, RefreshTokenStore};
 
+#[cfg(feature = "biome-sessions")]
+use models::SessionModel;
+#[cfg(feature = "biome-sessions")]
+use operations::{
+    add_session::RefreshTokenStoreAddSessionOperation,
+    fetch_session::RefreshTokenStoreFetchSessionOperation,
+    is_session_revoked::RefreshTokenStoreIsSessionRevokedOperation,
+    list_sessions::RefreshTokenStoreListSessionsOperation,
+    revoke_session::RefreshTokenStoreRevokeSessionOperation,
+    update_session_last_used::RefreshTokenStoreUpdateSessionLastUsedOperation,
+};
 use operations::{
     add_token::RefreshTokenStoreAddTokenOperation,
     fetch_token::RefreshTokenStoreFetchTokenOperation,
@@ -51,6 +67,41 @@ impl RefreshTokenStore for DieselRefreshTokenStore<diesel::pg::PgConnection> {
     fn fetch_token(&self, user_id: &str) -> Result<String, RefreshTokenError> {
         RefreshTokenStoreOperations::new(&*self.connection_pool.get()?).fetch_token(user_id)
     }
+    #[cfg(feature = "biome-sessions")]
+    fn add_session(&self, session: Session, token: &str) -> Result<(), RefreshTokenError> {
+        RefreshTokenStoreOperations::new(&*self.connection_pool.get()?).add_session(session, token)
+    }
+    #[cfg(feature = "biome-sessions")]
+    fn fetch_session(&self, session_id: &str) -> Result<(Session, String), RefreshTokenError> {
+        RefreshTokenStoreOperations::new(&*self.connection_pool.get()?).fetch_session(session_id)
+    }
+    #[cfg(feature = "biome-sessions")]
+    fn update_session_last_used(
+        &self,
+        session_id: &str,
+        last_used: SystemTime,
+    ) -> Result<(), RefreshTokenError> {
+        RefreshTokenStoreOperations::new(&*self.connection_pool.get()?)
+            .update_session_last_used(session_id, last_used)
+    }
+    #[cfg(feature = "biome-sessions")]
+    fn list_sessions(&self, user_id: &str) -> Result<Vec<Session>, RefreshTokenError> {
+        RefreshTokenStoreOperations::new(&*self.connection_pool.get()?).list_sessions(user_id)
+    }
+    #[cfg(feature = "biome-sessions")]
+    fn revoke_session(
+        &self,
+        session_id: &str,
+        revoked_until: SystemTime,
+    ) -> Result<(), RefreshTokenError> {
+        RefreshTokenStoreOperations::new(&*self.connection_pool.get()?)
+            .revoke_session(session_id, revoked_until)
+    }
+    #[cfg(feature = "biome-sessions")]
+    fn is_session_revoked(&self, session_id: &str) -> Result<bool, RefreshTokenError> {
+        RefreshTokenStoreOperations::new(&*self.connection_pool.get()?)
+            .is_session_revoked(session_id)
+    }
 }
 
 #[cfg(feature = "sqlite")]
@@ -67,6 +118,78 @@ impl RefreshTokenStore for DieselRefreshTokenStore<diesel::sqlite::SqliteConnect
     fn fetch_token(&self, user_id: &str) -> Result<String, RefreshTokenError> {
         RefreshTokenStoreOperations::new(&*self.connection_pool.get()?).fetch_token(user_id)
     }
+    #[cfg(feature = "biome-sessions")]
+    fn add_session(&self, session: Session, token: &str) -> Result<(), RefreshTokenError> {
+        RefreshTokenStoreOperations::new(&*self.connection_pool.get()?).add_session(session, token)
+    }
+    #[cfg(feature = "biome-sessions")]
+    fn fetch_session(&self, session_id: &str) -> Result<(Session, String), RefreshTokenError> {
+        RefreshTokenStoreOperations::new(&*self.connection_pool.get()?).fetch_session(session_id)
+    }
+    #[cfg(feature = "biome-sessions")]
+    fn update_session_last_used(
+        &self,
+        session_id: &str,
+        last_used: SystemTime,
+    ) -> Result<(), RefreshTokenError> {
+        RefreshTokenStoreOperations::new(&*self.connection_pool.get()?)
+            .update_session_last_used(session_id, last_used)
+    }
+    #[cfg(feature = "biome-sessions")]
+    fn list_sessions(&self, user_id: &str) -> Result<Vec<Session>, RefreshTokenError> {
+        RefreshTokenStoreOperations::new(&*self.connection_pool.get()?).list_sessions(user_id)
+    }
+    #[cfg(feature = "biome-sessions")]
+    fn revoke_session(
+        &self,
+        session_id: &str,
+        revoked_until: SystemTime,
+    ) -> Result<(), RefreshTokenError> {
+        RefreshTokenStoreOperations::new(&*self.connection_pool.get()?)
+            .revoke_session(session_id, revoked_until)
+    }
+    #[cfg(feature = "biome-sessions")]
+    fn is_session_revoked(&self, session_id: &str) -> Result<bool, RefreshTokenError> {
+        RefreshTokenStoreOperations::new(&*self.connection_pool.get()?)
+            .is_session_revoked(session_id)
+    }
+}
+
+#[cfg(feature = "biome-sessions")]
+impl From<(Session, &str)> for SessionModel {
+    fn from((session, token): (Session, &str)) -> Self {
+        Self {
+            session_id: session.session_id,
+            user_id: session.user_id,
+            token: token.to_string(),
+            created: to_millis(session.created),
+            last_used: to_millis(session.last_used),
+            user_agent: session.user_agent,
+            ip_address: session.ip_address,
+        }
+    }
+}
+
+#[cfg(feature = "biome-sessions")]
+impl From<SessionModel> for Session {
+    fn from(session: SessionModel) -> Self {
+        Self {
+            session_id: session.session_id,
+            user_id: session.user_id,
+            created: UNIX_EPOCH + Duration::from_millis(session.created as u64),
+            last_used: UNIX_EPOCH + Duration::from_millis(session.last_used as u64),
+            user_agent: session.user_agent,
+            ip_address: session.ip_address,
+        }
+    }
+}
+
+/// Converts a time to the milliseconds since the Unix epoch, as it is stored
+#[cfg(feature = "biome-sessions")]
+fn to_millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or_default()
 }
 
 #[cfg(all(test, feature = "sqlite"))]
@@ -221,6 +344,100 @@ pub mod tests {
         }
     }
 
+    /// Verify that a SQLite-backed `DieselRefreshTokenStore` correctly supports listing and
+    /// revoking sessions.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Create a `DieselUserStore` and add the necessary users.
+    /// 3. Create the `DieselRefreshTokenStore`.
+    /// 4. Add two sessions for one user and one for another, and verify that `list_sessions`
+    ///    returns the user's sessions in creation order.
+    /// 5. Update a session's last use and verify that `fetch_session` returns the new time along
+    ///    with the session's token.
+    /// 6. Revoke a session and verify that it is no longer listed and is on the revocation list,
+    ///    while the remaining session is not.
+    /// 7. Verify that revoking the session again returns a `RefreshTokenError::NotFoundError`.
+    #[cfg(feature = "biome-sessions")]
+    #[test]
+    fn sqlite_sessions() {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        let pool = create_connection_pool_and_migrate();
+
+        let user_store = DieselUserStore::new(pool.clone());
+        user_store
+            .add_user(User::new("user1"))
+            .expect("Failed to add user1");
+        user_store
+            .add_user(User::new("user2"))
+            .expect("Failed to add user2");
+
+        let store = DieselRefreshTokenStore::new(pool);
+
+        let session = |session_id: &str, user_id: &str, created: u64| Session {
+            session_id: session_id.into(),
+            user_id: user_id.into(),
+            created: UNIX_EPOCH + Duration::from_secs(created),
+            last_used: UNIX_EPOCH + Duration::from_secs(created),
+            user_agent: Some("test-agent".into()),
+            ip_address: Some("127.0.0.1".into()),
+        };
+
+        store
+            .add_session(session("session2", "user1", 2000), "token2")
+            .expect("Failed to add session2");
+        store
+            .add_session(session("session1", "user1", 1000), "token1")
+            .expect("Failed to add session1");
+        store
+            .add_session(session("session3", "user2", 3000), "token3")
+            .expect("Failed to add session3");
+
+        assert_eq!(
+            store
+                .list_sessions("user1")
+                .expect("Failed to list sessions"),
+            vec![
+                session("session1", "user1", 1000),
+                session("session2", "user1", 2000)
+            ],
+        );
+
+        let last_used = UNIX_EPOCH + Duration::from_secs(5000);
+        store
+            .update_session_last_used("session1", last_used)
+            .expect("Failed to update session1");
+        let (fetched, token) = store
+            .fetch_session("session1")
+            .expect("Failed to fetch session1");
+        assert_eq!(fetched.last_used, last_used);
+        assert_eq!(token, "token1");
+
+        store
+            .revoke_session("session1", SystemTime::now() + Duration::from_secs(60))
+            .expect("Failed to revoke session1");
+        assert_eq!(
+            store
+                .list_sessions("user1")
+                .expect("Failed to list sessions"),
+            vec![session("session2", "user1", 2000)],
+        );
+        assert!(store
+            .is_session_revoked("session1")
+            .expect("Failed to check session1"));
+        assert!(!store
+            .is_session_revoked("session2")
+            .expect("Failed to check session2"));
+
+        match store.revoke_session("session1", SystemTime::now()) {
+            Err(RefreshTokenError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(RefreshTokenError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+    }
+
     /// Creates a conneciton pool for an in-memory SQLite database with only a single connection
     /// available. Each connection is backed by a different in-memory SQLite database, so limiting
     /// the pool to a single connection insures that the same DB is used for all operations.
@@ -13,6 +13,8 @@
 // limitations under the License.
 
 use super::schema::refresh_tokens;
+#[cfg(feature = "biome-sessions")]
+use super::schema::{revoked_sessions, user_sessions};
 
 #[derive(Queryable, Identifiable, PartialEq, Debug)]
 #[table_name = "refresh_tokens"]
@@ -29,3 +31,26 @@ pub struct NewRefreshToken<'a> {
     pub user_id: &'a str,
     pub token: &'a str,
 }
+
+/// A session and its refresh token, with its times stored as milliseconds since the Unix epoch
+#[cfg(feature = "biome-sessions")]
+#[derive(Queryable, Insertable, PartialEq, Debug)]
+#[table_name = "user_sessions"]
+pub struct SessionModel {
+    pub session_id: String,
+    pub user_id: String,
+    pub token: String,
+    pub created: i64,
+    pub last_used: i64,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+/// An entry in the session revocation list
+#[cfg(feature = "biome-sessions")]
+#[derive(Queryable, Insertable, PartialEq, Debug)]
+#[table_name = "revoked_sessions"]
+pub struct RevokedSessionModel {
+    pub session_id: String,
+    pub expires: i64,
+}
@@ -0,0 +1,89 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::RefreshTokenStoreOperations;
+use crate::biome::refresh_tokens::store::{
+    diesel::{models::SessionModel, schema::user_sessions},
+    RefreshTokenError, Session,
+};
+use crate::biome::user::store::diesel::schema::splinter_user;
+use diesel::{dsl::insert_into, prelude::*};
+
+pub(in crate::biome) trait RefreshTokenStoreAddSessionOperation {
+    fn add_session(&self, session: Session, token: &str) -> Result<(), RefreshTokenError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> RefreshTokenStoreAddSessionOperation
+    for RefreshTokenStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn add_session(&self, session: Session, token: &str) -> Result<(), RefreshTokenError> {
+        let user_exists = splinter_user::table
+            .filter(splinter_user::id.eq(&session.user_id))
+            .count()
+            .get_result::<i64>(self.conn)
+            .map_err(|err| RefreshTokenError::QueryError {
+                context: "Failed to check if user exists".into(),
+                source: Box::new(err),
+            })?
+            > 0;
+        if !user_exists {
+            return Err(RefreshTokenError::NotFoundError(format!(
+                "User {} not found",
+                session.user_id
+            )));
+        }
+
+        insert_into(user_sessions::table)
+            .values(SessionModel::from((session, token)))
+            .execute(self.conn)
+            .map_err(|err| RefreshTokenError::OperationError {
+                context: "Failed to create session".to_string(),
+                source: Box::new(err),
+            })?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> RefreshTokenStoreAddSessionOperation
+    for RefreshTokenStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn add_session(&self, session: Session, token: &str) -> Result<(), RefreshTokenError> {
+        let user_exists = splinter_user::table
+            .filter(splinter_user::id.eq(&session.user_id))
+            .count()
+            .get_result::<i64>(self.conn)
+            .map_err(|err| RefreshTokenError::QueryError {
+                context: "Failed to check if user exists".into(),
+                source: Box::new(err),
+            })?
+            > 0;
+        if !user_exists {
+            return Err(RefreshTokenError::NotFoundError(format!(
+                "User {} not found",
+                session.user_id
+            )));
+        }
+
+        insert_into(user_sessions::table)
+            .values(SessionModel::from((session, token)))
+            .execute(self.conn)
+            .map_err(|err| RefreshTokenError::OperationError {
+                context: "Failed to create session".to_string(),
+                source: Box::new(err),
+            })?;
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::SystemTime;
+
+use super::RefreshTokenStoreOperations;
+use crate::biome::refresh_tokens::store::{
+    diesel::{schema::revoked_sessions, to_millis},
+    RefreshTokenError,
+};
+use diesel::prelude::*;
+
+pub(in crate::biome) trait RefreshTokenStoreIsSessionRevokedOperation {
+    fn is_session_revoked(&self, session_id: &str) -> Result<bool, RefreshTokenError>;
+}
+
+impl<'a, C> RefreshTokenStoreIsSessionRevokedOperation for RefreshTokenStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+{
+    fn is_session_revoked(&self, session_id: &str) -> Result<bool, RefreshTokenError> {
+        revoked_sessions::table
+            .filter(revoked_sessions::session_id.eq(session_id))
+            .filter(revoked_sessions::expires.gt(to_millis(SystemTime::now())))
+            .count()
+            .get_result::<i64>(self.conn)
+            .map(|count| count > 0)
+            .map_err(|err| RefreshTokenError::QueryError {
+                context: format!("Failed to check revocation of session {}", session_id),
+                source: Box::new(err),
+            })
+    }
+}
@@ -12,9 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "biome-sessions")]
+pub(super) mod add_session;
 pub(super) mod add_token;
+#[cfg(feature = "biome-sessions")]
+pub(super) mod fetch_session;
 pub(super) mod fetch_token;
+#[cfg(feature = "biome-sessions")]
+pub(super) mod is_session_revoked;
+#[cfg(feature = "biome-sessions")]
+pub(super) mod list_sessions;
 pub(super) mod remove_token;
+#[cfg(feature = "biome-sessions")]
+pub(super) mod revoke_session;
+#[cfg(feature = "biome-sessions")]
+pub(super) mod update_session_last_used;
 pub(super) mod update_token;
 
 pub(super) struct RefreshTokenStoreOperations<'a, C> {
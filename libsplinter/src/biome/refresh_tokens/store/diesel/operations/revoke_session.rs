@@ -0,0 +1,119 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::SystemTime;
+
+use super::RefreshTokenStoreOperations;
+use crate::biome::refresh_tokens::store::{
+    diesel::{
+        models::RevokedSessionModel,
+        schema::{revoked_sessions, user_sessions},
+        to_millis,
+    },
+    RefreshTokenError,
+};
+use diesel::{
+    dsl::{delete, insert_into},
+    prelude::*,
+};
+
+pub(in crate::biome) trait RefreshTokenStoreRevokeSessionOperation {
+    fn revoke_session(
+        &self,
+        session_id: &str,
+        revoked_until: SystemTime,
+    ) -> Result<(), RefreshTokenError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> RefreshTokenStoreRevokeSessionOperation
+    for RefreshTokenStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn revoke_session(
+        &self,
+        session_id: &str,
+        revoked_until: SystemTime,
+    ) -> Result<(), RefreshTokenError> {
+        let now = to_millis(SystemTime::now());
+        let removed = self
+            .conn
+            .transaction::<_, diesel::result::Error, _>(|| {
+                let removed = delete(user_sessions::table.find(session_id)).execute(self.conn)?;
+                if removed > 0 {
+                    delete(revoked_sessions::table.filter(revoked_sessions::expires.le(now)))
+                        .execute(self.conn)?;
+                    insert_into(revoked_sessions::table)
+                        .values(RevokedSessionModel {
+                            session_id: session_id.to_string(),
+                            expires: to_millis(revoked_until),
+                        })
+                        .execute(self.conn)?;
+                }
+                Ok(removed)
+            })
+            .map_err(|err| RefreshTokenError::OperationError {
+                context: format!("Failed to revoke session {}", session_id),
+                source: Box::new(err),
+            })?;
+
+        if removed == 0 {
+            return Err(RefreshTokenError::NotFoundError(format!(
+                "Session {} not found",
+                session_id
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> RefreshTokenStoreRevokeSessionOperation
+    for RefreshTokenStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn revoke_session(
+        &self,
+        session_id: &str,
+        revoked_until: SystemTime,
+    ) -> Result<(), RefreshTokenError> {
+        let now = to_millis(SystemTime::now());
+        let removed = self
+            .conn
+            .transaction::<_, diesel::result::Error, _>(|| {
+                let removed = delete(user_sessions::table.find(session_id)).execute(self.conn)?;
+                if removed > 0 {
+                    delete(revoked_sessions::table.filter(revoked_sessions::expires.le(now)))
+                        .execute(self.conn)?;
+                    insert_into(revoked_sessions::table)
+                        .values(RevokedSessionModel {
+                            session_id: session_id.to_string(),
+                            expires: to_millis(revoked_until),
+                        })
+                        .execute(self.conn)?;
+                }
+                Ok(removed)
+            })
+            .map_err(|err| RefreshTokenError::OperationError {
+                context: format!("Failed to revoke session {}", session_id),
+                source: Box::new(err),
+            })?;
+
+        if removed == 0 {
+            return Err(RefreshTokenError::NotFoundError(format!(
+                "Session {} not found",
+                session_id
+            )));
+        }
+        Ok(())
+    }
+}
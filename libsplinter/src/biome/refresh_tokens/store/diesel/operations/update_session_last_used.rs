@@ -0,0 +1,57 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::SystemTime;
+
+use super::RefreshTokenStoreOperations;
+use crate::biome::refresh_tokens::store::{
+    diesel::{schema::user_sessions, to_millis},
+    RefreshTokenError,
+};
+use diesel::{dsl::update, prelude::*};
+
+pub(in crate::biome) trait RefreshTokenStoreUpdateSessionLastUsedOperation {
+    fn update_session_last_used(
+        &self,
+        session_id: &str,
+        last_used: SystemTime,
+    ) -> Result<(), RefreshTokenError>;
+}
+
+impl<'a, C> RefreshTokenStoreUpdateSessionLastUsedOperation for RefreshTokenStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+{
+    fn update_session_last_used(
+        &self,
+        session_id: &str,
+        last_used: SystemTime,
+    ) -> Result<(), RefreshTokenError> {
+        let updated = update(user_sessions::table.find(session_id))
+            .set(user_sessions::last_used.eq(to_millis(last_used)))
+            .execute(self.conn)
+            .map_err(|err| RefreshTokenError::OperationError {
+                context: format!("Failed to update session {}", session_id),
+                source: Box::new(err),
+            })?;
+
+        if updated == 0 {
+            return Err(RefreshTokenError::NotFoundError(format!(
+                "Session {} not found",
+                session_id
+            )));
+        }
+        Ok(())
+    }
+}
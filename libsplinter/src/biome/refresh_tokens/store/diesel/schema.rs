@@ -19,3 +19,24 @@ table! {
         token -> Text,
     }
 }
+
+#[cfg(feature = "biome-sessions")]
+table! {
+    user_sessions (session_id) {
+        session_id -> Text,
+        user_id -> Text,
+        token -> Text,
+        created -> Int8,
+        last_used -> Int8,
+        user_agent -> Nullable<Text>,
+        ip_address -> Nullable<Text>,
+    }
+}
+
+#[cfg(feature = "biome-sessions")]
+table! {
+    revoked_sessions (session_id) {
+        session_id -> Text,
+        expires -> Int8,
+    }
+}
@@ -14,18 +14,30 @@
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+#[cfg(feature = "biome-sessions")]
+use std::time::SystemTime;
 
+#[cfg(feature = "biome-sessions")]
+use crate::biome::refresh_tokens::store::Session;
 use crate::biome::refresh_tokens::store::{error::RefreshTokenError, RefreshTokenStore};
 
 #[derive(Default, Clone)]
 pub struct MemoryRefreshTokenStore {
     inner: Arc<Mutex<HashMap<String, String>>>,
+    #[cfg(feature = "biome-sessions")]
+    sessions: Arc<Mutex<HashMap<String, (Session, String)>>>,
+    #[cfg(feature = "biome-sessions")]
+    revoked_sessions: Arc<Mutex<HashMap<String, SystemTime>>>,
 }
 
 impl MemoryRefreshTokenStore {
     pub fn new() -> Self {
         Self {
             inner: Arc::new(Mutex::new(HashMap::new())),
+            #[cfg(feature = "biome-sessions")]
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+            #[cfg(feature = "biome-sessions")]
+            revoked_sessions: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 }
@@ -100,4 +112,126 @@ impl RefreshTokenStore for MemoryRefreshTokenStore {
             )))
         }
     }
+
+    #[cfg(feature = "biome-sessions")]
+    fn add_session(&self, session: Session, token: &str) -> Result<(), RefreshTokenError> {
+        let mut sessions = self
+            .sessions
+            .lock()
+            .map_err(|_| RefreshTokenError::StorageError {
+                context: "Cannot access refresh token store: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+        sessions.insert(session.session_id.clone(), (session, token.to_string()));
+        Ok(())
+    }
+
+    #[cfg(feature = "biome-sessions")]
+    fn fetch_session(&self, session_id: &str) -> Result<(Session, String), RefreshTokenError> {
+        let sessions = self
+            .sessions
+            .lock()
+            .map_err(|_| RefreshTokenError::StorageError {
+                context: "Cannot access refresh token store: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+
+        sessions.get(session_id).cloned().ok_or_else(|| {
+            RefreshTokenError::NotFoundError(format!("Session {} not found.", session_id))
+        })
+    }
+
+    #[cfg(feature = "biome-sessions")]
+    fn update_session_last_used(
+        &self,
+        session_id: &str,
+        last_used: SystemTime,
+    ) -> Result<(), RefreshTokenError> {
+        let mut sessions = self
+            .sessions
+            .lock()
+            .map_err(|_| RefreshTokenError::StorageError {
+                context: "Cannot access refresh token store: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+
+        if let Some((session, _)) = sessions.get_mut(session_id) {
+            session.last_used = last_used;
+            Ok(())
+        } else {
+            Err(RefreshTokenError::NotFoundError(format!(
+                "Session {} not found.",
+                session_id
+            )))
+        }
+    }
+
+    #[cfg(feature = "biome-sessions")]
+    fn list_sessions(&self, user_id: &str) -> Result<Vec<Session>, RefreshTokenError> {
+        let sessions = self
+            .sessions
+            .lock()
+            .map_err(|_| RefreshTokenError::StorageError {
+                context: "Cannot access refresh token store: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+
+        let mut user_sessions = sessions
+            .values()
+            .filter(|(session, _)| session.user_id == user_id)
+            .map(|(session, _)| session.clone())
+            .collect::<Vec<_>>();
+        user_sessions.sort_by_key(|session| session.created);
+        Ok(user_sessions)
+    }
+
+    #[cfg(feature = "biome-sessions")]
+    fn revoke_session(
+        &self,
+        session_id: &str,
+        revoked_until: SystemTime,
+    ) -> Result<(), RefreshTokenError> {
+        let mut sessions = self
+            .sessions
+            .lock()
+            .map_err(|_| RefreshTokenError::StorageError {
+                context: "Cannot access refresh token store: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+        let mut revoked_sessions =
+            self.revoked_sessions
+                .lock()
+                .map_err(|_| RefreshTokenError::StorageError {
+                    context: "Cannot access refresh token store: mutex lock poisoned".to_string(),
+                    source: None,
+                })?;
+
+        if sessions.remove(session_id).is_none() {
+            return Err(RefreshTokenError::NotFoundError(format!(
+                "Session {} not found.",
+                session_id
+            )));
+        }
+
+        let now = SystemTime::now();
+        revoked_sessions.retain(|_, expires| *expires > now);
+        revoked_sessions.insert(session_id.to_string(), revoked_until);
+        Ok(())
+    }
+
+    #[cfg(feature = "biome-sessions")]
+    fn is_session_revoked(&self, session_id: &str) -> Result<bool, RefreshTokenError> {
+        let revoked_sessions =
+            self.revoked_sessions
+                .lock()
+                .map_err(|_| RefreshTokenError::StorageError {
+                    context: "Cannot access refresh token store: mutex lock poisoned".to_string(),
+                    source: None,
+                })?;
+
+        Ok(revoked_sessions
+            .get(session_id)
+            .map(|expires| *expires > SystemTime::now())
+            .unwrap_or(false))
+    }
 }
@@ -17,8 +17,29 @@ pub(crate) mod diesel;
 mod error;
 pub(in crate::biome) mod memory;
 
+#[cfg(feature = "biome-sessions")]
+use std::time::SystemTime;
+
 pub use error::RefreshTokenError;
 
+/// A login session, represented by the refresh token that was issued when the user logged in
+#[cfg(feature = "biome-sessions")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Session {
+    /// The unique ID of the session, carried in the tokens issued for it
+    pub session_id: String,
+    /// The user that owns the session
+    pub user_id: String,
+    /// When the session was created
+    pub created: SystemTime,
+    /// When the session's refresh token was last used
+    pub last_used: SystemTime,
+    /// The user agent of the client that created the session, if known
+    pub user_agent: Option<String>,
+    /// The IP address of the client that created the session, if known
+    pub ip_address: Option<String>,
+}
+
 /// Defines methods for CRUD operations for handling refresh tokens
 pub trait RefreshTokenStore: Send + Sync {
     /// Adds a refresh token to underlying storage
@@ -50,6 +71,67 @@ pub trait RefreshTokenStore: Send + Sync {
     ///
     ///   * `user_id` - The user whom which the token is for
     fn fetch_token(&self, user_id: &str) -> Result<String, RefreshTokenError>;
+
+    /// Adds a session and its refresh token to underlying storage
+    ///
+    /// # Arguments
+    ///
+    ///   * `session` - The session to add
+    ///   * `token` - The refresh token issued for the session
+    #[cfg(feature = "biome-sessions")]
+    fn add_session(&self, session: Session, token: &str) -> Result<(), RefreshTokenError>;
+
+    /// Fetch a session and its refresh token from underlying storage
+    ///
+    /// # Arguments
+    ///
+    ///   * `session_id` - The ID of the session
+    #[cfg(feature = "biome-sessions")]
+    fn fetch_session(&self, session_id: &str) -> Result<(Session, String), RefreshTokenError>;
+
+    /// Records the time a session's refresh token was last used
+    ///
+    /// # Arguments
+    ///
+    ///   * `session_id` - The ID of the session
+    ///   * `last_used` - The time the session's refresh token was used
+    #[cfg(feature = "biome-sessions")]
+    fn update_session_last_used(
+        &self,
+        session_id: &str,
+        last_used: SystemTime,
+    ) -> Result<(), RefreshTokenError>;
+
+    /// List the sessions of a user, ordered by creation time
+    ///
+    /// # Arguments
+    ///
+    ///   * `user_id` - The user whom which the sessions are for
+    #[cfg(feature = "biome-sessions")]
+    fn list_sessions(&self, user_id: &str) -> Result<Vec<Session>, RefreshTokenError>;
+
+    /// Removes a session and its refresh token, and adds the session to the revocation list so
+    /// that access tokens already issued for it are rejected
+    ///
+    /// # Arguments
+    ///
+    ///   * `session_id` - The ID of the session
+    ///   * `revoked_until` - When the session may be dropped from the revocation list; this should
+    ///     be no earlier than the expiration of the last access token issued for the session
+    #[cfg(feature = "biome-sessions")]
+    fn revoke_session(
+        &self,
+        session_id: &str,
+        revoked_until: SystemTime,
+    ) -> Result<(), RefreshTokenError>;
+
+    /// Checks whether a session is on the revocation list
+    ///
+    /// # Arguments
+    ///
+    ///   * `session_id` - The ID of the session
+    #[cfg(feature = "biome-sessions")]
+    fn is_session_revoked(&self, session_id: &str) -> Result<bool, RefreshTokenError>;
 }
 
 impl<RTS> RefreshTokenStore for Box<RTS>
@@ -71,4 +153,42 @@ where
     fn fetch_token(&self, user_id: &str) -> Result<String, RefreshTokenError> {
         (**self).fetch_token(user_id)
     }
+
+    #[cfg(feature = "biome-sessions")]
+    fn add_session(&self, session: Session, token: &str) -> Result<(), RefreshTokenError> {
+        (**self).add_session(session, token)
+    }
+
+    #[cfg(feature = "biome-sessions")]
+    fn fetch_session(&self, session_id: &str) -> Result<(Session, String), RefreshTokenError> {
+        (**self).fetch_session(session_id)
+    }
+
+    #[cfg(feature = "biome-sessions")]
+    fn update_session_last_used(
+        &self,
+        session_id: &str,
+        last_used: SystemTime,
+    ) -> Result<(), RefreshTokenError> {
+        (**self).update_session_last_used(session_id, last_used)
+    }
+
+    #[cfg(feature = "biome-sessions")]
+    fn list_sessions(&self, user_id: &str) -> Result<Vec<Session>, RefreshTokenError> {
+        (**self).list_sessions(user_id)
+    }
+
+    #[cfg(feature = "biome-sessions")]
+    fn revoke_session(
+        &self,
+        session_id: &str,
+        revoked_until: SystemTime,
+    ) -> Result<(), RefreshTokenError> {
+        (**self).revoke_session(session_id, revoked_until)
+    }
+
+    #[cfg(feature = "biome-sessions")]
+    fn is_session_revoked(&self, session_id: &str) -> Result<bool, RefreshTokenError> {
+        (**self).is_session_revoked(session_id)
+    }
 }
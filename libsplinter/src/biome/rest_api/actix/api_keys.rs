@@ -0,0 +1,528 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::audit::log_auth_failure;
+use super::authorize::{authorize_user, validate_claims};
+use crate::actix_web::{http, http::header, HttpRequest, HttpResponse};
+#[cfg(feature = "biome-authorization")]
+use crate::biome::authorization::{
+    store::{GroupStore, RoleStore},
+    user_permissions, Permission,
+};
+use crate::biome::credentials::api_keys::{parse_api_key, ApiKey, ApiKeyScope};
+use crate::biome::credentials::store::{CredentialsStore, CredentialsStoreError};
+use crate::biome::rest_api::resources::api_keys::{NewApiKey, ResponseApiKey};
+use crate::biome::rest_api::resources::authorize::AuthorizationResult;
+use crate::biome::rest_api::BiomeRestConfig;
+use crate::futures::{Future, IntoFuture};
+use crate::protocol;
+use crate::rest_api::{
+    into_bytes, secrets::SecretManager, sessions::default_validation, Continuation, ErrorResponse,
+    HandlerFunction, Method, ProtocolVersionRangeGuard, RequestGuard, Resource,
+};
+
+/// The authorization scheme used to present an API key, as in `Authorization: ApiKey <key>`
+const API_KEY_SCHEME: &str = "ApiKey";
+
+/// Defines a REST endpoint for creating and listing the API keys of a user
+///
+/// `POST /biome/api_keys` creates a new API key. The payload should be in the JSON format:
+///   {
+///       "display_name": <name for the key>,
+///       "scopes": [<scope>, ...],
+///       "expires_in_secs": <optional number of seconds the key may be used for>
+///   }
+///
+/// The key itself is only returned in the response to this request.
+///
+/// `GET /biome/api_keys` lists the user's API keys, without their secrets.
+pub fn make_api_keys_route(
+    credentials_store: Arc<dyn CredentialsStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> Resource {
+    Resource::build("/biome/api_keys")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_API_KEYS_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(
+            Method::Post,
+            handle_create(
+                credentials_store.clone(),
+                rest_config.clone(),
+                secret_manager.clone(),
+            ),
+        )
+        .add_method(
+            Method::Get,
+            handle_list(credentials_store, rest_config, secret_manager),
+        )
+}
+
+/// Defines a REST endpoint for revoking an API key of a user
+pub fn make_api_key_route(
+    credentials_store: Arc<dyn CredentialsStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> Resource {
+    Resource::build("/biome/api_keys/{api_key_id}")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_API_KEYS_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(
+            Method::Delete,
+            handle_revoke(credentials_store, rest_config, secret_manager),
+        )
+}
+
+/// Defines a REST endpoint method to create an API key for the authorized user
+fn handle_create(
+    credentials_store: Arc<dyn CredentialsStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> HandlerFunction {
+    Box::new(move |request, payload| {
+        let credentials_store = credentials_store.clone();
+        let validation = default_validation(&rest_config.issuer());
+        let user_id = match authorize_user(&request, &secret_manager, &validation) {
+            AuthorizationResult::Authorized(claims) => claims.user_id(),
+            AuthorizationResult::Unauthorized(msg) => {
+                return Box::new(
+                    HttpResponse::Unauthorized()
+                        .json(ErrorResponse::unauthorized(&msg))
+                        .into_future(),
+                )
+            }
+            AuthorizationResult::Failed => {
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        };
+
+        Box::new(into_bytes(payload).and_then(move |bytes| {
+            let new_api_key = match serde_json::from_slice::<NewApiKey>(&bytes) {
+                Ok(val) => val,
+                Err(err) => {
+                    debug!("Error parsing payload: {}", err);
+                    return HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(&format!(
+                            "Failed to parse payload: {}",
+                            err
+                        )))
+                        .into_future();
+                }
+            };
+
+            if new_api_key.scopes.is_empty() {
+                return HttpResponse::BadRequest()
+                    .json(ErrorResponse::bad_request(
+                        "An API key must be granted at least one scope",
+                    ))
+                    .into_future();
+            }
+            let scopes = match new_api_key
+                .scopes
+                .iter()
+                .map(|scope| scope.parse())
+                .collect::<Result<Vec<ApiKeyScope>, _>>()
+            {
+                Ok(scopes) => scopes,
+                Err(err) => {
+                    return HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(&err.to_string()))
+                        .into_future();
+                }
+            };
+            if new_api_key.expires_in_secs == Some(0) {
+                return HttpResponse::BadRequest()
+                    .json(ErrorResponse::bad_request(
+                        "expires_in_secs must be greater than zero",
+                    ))
+                    .into_future();
+            }
+
+            let (key, api_key) = ApiKey::generate(
+                &user_id,
+                &new_api_key.display_name,
+                scopes,
+                new_api_key.expires_in_secs.map(Duration::from_secs),
+            );
+            let response = json!({
+                "message": "API key created successfully",
+                "key": key,
+                "data": ResponseApiKey::from(&api_key),
+            });
+
+            match credentials_store.add_api_key(api_key) {
+                Ok(()) => HttpResponse::Ok().json(response).into_future(),
+                Err(err) => {
+                    error!("Failed to add API key: {}", err);
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future()
+                }
+            }
+        }))
+    })
+}
+
+/// Defines a REST endpoint method to list the API keys of the authorized user
+fn handle_list(
+    credentials_store: Arc<dyn CredentialsStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let validation = default_validation(&rest_config.issuer());
+        let user_id = match authorize_user(&request, &secret_manager, &validation) {
+            AuthorizationResult::Authorized(claims) => claims.user_id(),
+            AuthorizationResult::Unauthorized(msg) => {
+                return Box::new(
+                    HttpResponse::Unauthorized()
+                        .json(ErrorResponse::unauthorized(&msg))
+                        .into_future(),
+                )
+            }
+            AuthorizationResult::Failed => {
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        };
+
+        match credentials_store.list_api_keys(&user_id) {
+            Ok(api_keys) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({
+                        "data": api_keys
+                            .iter()
+                            .map(ResponseApiKey::from)
+                            .collect::<Vec<ResponseApiKey>>()
+                    }))
+                    .into_future(),
+            ),
+            Err(err) => {
+                error!("Failed to list API keys: {}", err);
+                Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                )
+            }
+        }
+    })
+}
+
+/// Defines a REST endpoint method to revoke an API key of the authorized user
+fn handle_revoke(
+    credentials_store: Arc<dyn CredentialsStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let validation = default_validation(&rest_config.issuer());
+        let user_id = match authorize_user(&request, &secret_manager, &validation) {
+            AuthorizationResult::Authorized(claims) => claims.user_id(),
+            AuthorizationResult::Unauthorized(msg) => {
+                return Box::new(
+                    HttpResponse::Unauthorized()
+                        .json(ErrorResponse::unauthorized(&msg))
+                        .into_future(),
+                )
+            }
+            AuthorizationResult::Failed => {
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        };
+
+        let api_key_id = match request.match_info().get("api_key_id") {
+            Some(id) => id.to_owned(),
+            None => {
+                error!("API key ID is not in path request");
+                return Box::new(
+                    HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(
+                            "Failed to process request: no API key ID",
+                        ))
+                        .into_future(),
+                );
+            }
+        };
+
+        // A key belonging to another user is reported as not found, so that key IDs cannot be
+        // probed
+        match credentials_store.remove_api_key(&user_id, &api_key_id) {
+            Ok(()) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({ "message": "API key successfully revoked" }))
+                    .into_future(),
+            ),
+            Err(CredentialsStoreError::NotFoundError(_)) => Box::new(
+                HttpResponse::NotFound()
+                    .json(ErrorResponse::not_found("API key not found"))
+                    .into_future(),
+            ),
+            Err(err) => {
+                error!("Failed to revoke API key: {}", err);
+                Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                )
+            }
+        }
+    })
+}
+
+/// Guards requests by requiring either an API key that has been granted a scope, presented as
+/// `Authorization: ApiKey <key>`, or a valid Biome access token, presented as
+/// `Authorization: Bearer <token>`. Read-only requests (`GET` and `HEAD`) may require a different
+/// scope than other requests.
+///
+/// Requests without credentials, or with an invalid or expired key, are rejected as unauthorized;
+/// requests with a valid key that lacks the scope are rejected as forbidden. If the guard has the
+/// authorization stores, the user an access token was issued to, or an API key belongs to, must
+/// also have been granted the permission of the same name as the scope. Access tokens issued for
+/// a revoked session are rejected by the [`SessionRevocationGuard`] of the REST API.
+///
+/// [`SessionRevocationGuard`]: struct.SessionRevocationGuard.html
+#[derive(Clone)]
+pub struct ApiKeyGuard {
+    credentials_store: Arc<dyn CredentialsStore>,
+    secret_manager: Arc<dyn SecretManager>,
+    rest_config: Arc<BiomeRestConfig>,
+    scope: ApiKeyScope,
+    read_scope: Option<ApiKeyScope>,
+    #[cfg(feature = "biome-authorization")]
+    authorization_stores: Option<(Arc<dyn RoleStore>, Arc<dyn GroupStore>)>,
+}
+
+impl ApiKeyGuard {
+    /// Creates a new guard that requires the given scope of API keys, for all requests
+    ///
+    /// # Arguments
+    ///
+    /// * `credentials_store`: The store the API keys are fetched from.
+    /// * `secret_manager`: The secret manager used to validate access tokens.
+    /// * `rest_config`: The Biome REST API configuration.
+    /// * `scope`: The scope an API key must have been granted.
+    ///
+    pub fn new(
+        credentials_store: Arc<dyn CredentialsStore>,
+        secret_manager: Arc<dyn SecretManager>,
+        rest_config: Arc<BiomeRestConfig>,
+        scope: ApiKeyScope,
+    ) -> Self {
+        Self {
+            credentials_store,
+            secret_manager,
+            rest_config,
+            scope,
+            read_scope: Some(scope),
+            #[cfg(feature = "biome-authorization")]
+            authorization_stores: None,
+        }
+    }
+
+    /// Sets the scope required of read-only requests; if `None`, an API key with any scope may
+    /// make read-only requests
+    pub fn with_read_scope(mut self, read_scope: Option<ApiKeyScope>) -> Self {
+        self.read_scope = read_scope;
+        self
+    }
+
+    /// Requires users to have been granted the permission matching the required scope, using the
+    /// given stores to look up their permissions
+    #[cfg(feature = "biome-authorization")]
+    pub fn with_authorization_stores(
+        mut self,
+        role_store: Arc<dyn RoleStore>,
+        group_store: Arc<dyn GroupStore>,
+    ) -> Self {
+        self.authorization_stores = Some((role_store, group_store));
+        self
+    }
+
+    /// Returns the scope required of the request, if any
+    fn required_scope(&self, request: &HttpRequest) -> Option<ApiKeyScope> {
+        if matches!(*request.method(), http::Method::GET | http::Method::HEAD) {
+            self.read_scope
+        } else {
+            Some(self.scope)
+        }
+    }
+
+    fn evaluate_api_key(&self, request: &HttpRequest, key: &str) -> Continuation {
+        let (id, secret) = match parse_api_key(key) {
+            Some(parts) => parts,
+            None => return unauthorized(request, None, "Invalid API key"),
+        };
+
+        let api_key = match self.credentials_store.fetch_api_key(id) {
+            Ok(api_key) => api_key,
+            Err(CredentialsStoreError::NotFoundError(_)) => {
+                return unauthorized(request, None, "Invalid API key")
+            }
+            Err(err) => {
+                error!("Failed to fetch API key: {}", err);
+                return Continuation::terminate(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        };
+
+        if !api_key.verify_secret(secret) {
+            return unauthorized(request, Some(&api_key.user_id), "Invalid API key");
+        }
+        if api_key.is_expired() {
+            return unauthorized(request, Some(&api_key.user_id), "API key has expired");
+        }
+        let scope = self.required_scope(request);
+        match scope {
+            Some(scope) if !api_key.has_scope(scope) => {
+                log_auth_failure(
+                    request,
+                    None,
+                    Some(&api_key.user_id),
+                    "API key missing scope",
+                    None,
+                );
+                Continuation::terminate(
+                    HttpResponse::Forbidden()
+                        .json(ErrorResponse::forbidden(&format!(
+                            "API key has not been granted the {} scope",
+                            scope
+                        )))
+                        .into_future(),
+                )
+            }
+            _ => self.check_permission(request, &api_key.user_id, scope),
+        }
+    }
+
+    fn evaluate_access_token(&self, request: &HttpRequest, token: &str) -> Continuation {
+        let validation = default_validation(&self.rest_config.issuer());
+        match validate_claims(token, &self.secret_manager, &validation) {
+            AuthorizationResult::Authorized(claims) => {
+                self.check_permission(request, &claims.user_id(), self.required_scope(request))
+            }
+            AuthorizationResult::Unauthorized(msg) => unauthorized(request, None, &msg),
+            AuthorizationResult::Failed => Continuation::terminate(
+                HttpResponse::InternalServerError()
+                    .json(ErrorResponse::internal_error())
+                    .into_future(),
+            ),
+        }
+    }
+
+    /// Checks that the user has been granted the permission matching the required scope, if the
+    /// guard has the authorization stores
+    #[cfg(feature = "biome-authorization")]
+    fn check_permission(
+        &self,
+        request: &HttpRequest,
+        user_id: &str,
+        scope: Option<ApiKeyScope>,
+    ) -> Continuation {
+        let (permission, (role_store, group_store)) = match (scope, &self.authorization_stores) {
+            (Some(scope), Some(stores)) => (Permission::from(scope), stores),
+            _ => return Continuation::Continue,
+        };
+        match user_permissions(&**role_store, &**group_store, user_id) {
+            Ok(permissions) if permissions.contains(&permission) => Continuation::Continue,
+            Ok(_) => {
+                log_auth_failure(
+                    request,
+                    None,
+                    Some(user_id),
+                    "User missing permission",
+                    None,
+                );
+                Continuation::terminate(
+                    HttpResponse::Forbidden()
+                        .json(ErrorResponse::forbidden(&format!(
+                            "User has not been granted the {} permission",
+                            permission
+                        )))
+                        .into_future(),
+                )
+            }
+            Err(err) => {
+                error!("Failed to fetch user permissions: {}", err);
+                Continuation::terminate(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                )
+            }
+        }
+    }
+
+    /// Accepts the request, as there are no permissions to check without Biome authorization
+    #[cfg(not(feature = "biome-authorization"))]
+    fn check_permission(
+        &self,
+        _request: &HttpRequest,
+        _user_id: &str,
+        _scope: Option<ApiKeyScope>,
+    ) -> Continuation {
+        Continuation::Continue
+    }
+}
+
+impl RequestGuard for ApiKeyGuard {
+    fn evaluate(&self, request: &HttpRequest) -> Continuation {
+        let auth_header = match request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|auth_header| auth_header.to_str().ok())
+        {
+            Some(auth_header) => auth_header,
+            None => return unauthorized(request, None, "No authorization header"),
+        };
+
+        let mut parts = auth_header.split_whitespace();
+        match (parts.next(), parts.last()) {
+            (Some(API_KEY_SCHEME), Some(key)) => self.evaluate_api_key(request, key),
+            (Some("Bearer"), Some(token)) => self.evaluate_access_token(request, token),
+            _ => unauthorized(request, None, "Invalid authorization header"),
+        }
+    }
+}
+
+/// Logs an authentication failure and rejects the request as unauthorized
+fn unauthorized(request: &HttpRequest, user_id: Option<&str>, reason: &str) -> Continuation {
+    log_auth_failure(request, None, user_id, reason, None);
+    Continuation::terminate(
+        HttpResponse::Unauthorized()
+            .json(ErrorResponse::unauthorized(reason))
+            .into_future(),
+    )
+}
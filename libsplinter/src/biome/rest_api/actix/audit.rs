@@ -0,0 +1,55 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides structured audit logging of failed authentication attempts.
+
+use crate::actix_web::HttpRequest;
+
+/// The log target for audit log entries, so that they may be routed separately from the rest of
+/// the log
+const AUDIT_TARGET: &str = "splinter::biome::audit";
+
+/// Writes an audit log entry, as a single line of JSON, for a failed authentication attempt.
+///
+/// # Arguments
+///
+/// * `request`: The request that failed to authenticate; its method, path and client address
+///   are included in the entry.
+/// * `username`: The username the attempt was made for, if known.
+/// * `user_id`: The ID of the user the attempt was made for, if known.
+/// * `reason`: Why the attempt failed.
+/// * `failed_attempts`: The number of consecutive failed attempts for the user, if tracked.
+///
+pub(crate) fn log_auth_failure(
+    request: &HttpRequest,
+    username: Option<&str>,
+    user_id: Option<&str>,
+    reason: &str,
+    failed_attempts: Option<u32>,
+) {
+    warn!(
+        target: AUDIT_TARGET,
+        "{}",
+        json!({
+            "event": "authentication_failure",
+            "method": request.method().as_str(),
+            "endpoint": request.path(),
+            "client_ip": request.peer_addr().map(|addr| addr.ip().to_string()),
+            "username": username,
+            "user_id": user_id,
+            "reason": reason,
+            "failed_attempts": failed_attempts,
+        })
+    );
+}
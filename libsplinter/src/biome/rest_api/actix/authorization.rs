@@ -0,0 +1,756 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use super::audit::log_auth_failure;
+use super::authorize::authorize_user;
+use crate::actix_web::{http, HttpRequest, HttpResponse};
+use crate::biome::authorization::{
+    store::{AuthorizationStoreError, GroupStore, RoleStore},
+    user_permissions, Assignee, Permission, Role, ADMIN_ROLE,
+};
+use crate::biome::credentials::store::{CredentialsStore, CredentialsStoreError};
+use crate::biome::rest_api::resources::authorization::{
+    NewGroup, NewRole, ResponseGroup, ResponseRole, ResponseRoleAssignments,
+};
+use crate::biome::rest_api::resources::authorize::AuthorizationResult;
+use crate::biome::rest_api::BiomeRestConfig;
+use crate::futures::{Future, IntoFuture};
+use crate::protocol;
+use crate::rest_api::{
+    into_bytes, secrets::SecretManager, sessions::default_validation, Continuation, ErrorResponse,
+    HandlerFunction, Method, ProtocolVersionRangeGuard, RequestGuard, Resource,
+};
+
+/// Guards requests by requiring a valid Biome access token for a user who has been granted a
+/// permission through their roles.
+///
+/// Requests without a valid access token are rejected as unauthorized; requests from users who
+/// lack the permission are rejected as forbidden. Access tokens issued for a revoked session are
+/// rejected by the [`SessionRevocationGuard`] of the REST API.
+///
+/// [`SessionRevocationGuard`]: struct.SessionRevocationGuard.html
+#[derive(Clone)]
+pub struct PermissionGuard {
+    role_store: Arc<dyn RoleStore>,
+    group_store: Arc<dyn GroupStore>,
+    secret_manager: Arc<dyn SecretManager>,
+    rest_config: Arc<BiomeRestConfig>,
+    permission: Permission,
+    read_permission: Option<Permission>,
+    self_access_param: Option<String>,
+}
+
+impl PermissionGuard {
+    /// Creates a new guard that requires the given permission
+    ///
+    /// # Arguments
+    ///
+    /// * `role_store`: The store the user's roles are fetched from.
+    /// * `group_store`: The store the user's groups are fetched from.
+    /// * `secret_manager`: The secret manager used to validate access tokens.
+    /// * `rest_config`: The Biome REST API configuration.
+    /// * `permission`: The permission a user must have been granted.
+    ///
+    pub fn new(
+        role_store: Arc<dyn RoleStore>,
+        group_store: Arc<dyn GroupStore>,
+        secret_manager: Arc<dyn SecretManager>,
+        rest_config: Arc<BiomeRestConfig>,
+        permission: Permission,
+    ) -> Self {
+        Self {
+            role_store,
+            group_store,
+            secret_manager,
+            rest_config,
+            permission,
+            read_permission: None,
+            self_access_param: None,
+        }
+    }
+
+    /// Requires the given permission for `GET` requests instead
+    pub fn with_read_permission(mut self, permission: Permission) -> Self {
+        self.read_permission = Some(permission);
+        self
+    }
+
+    /// Allows users without the permission through if the given path parameter is their own
+    /// user ID
+    pub fn with_self_access(mut self, param: &str) -> Self {
+        self.self_access_param = Some(param.to_string());
+        self
+    }
+}
+
+impl RequestGuard for PermissionGuard {
+    fn evaluate(&self, request: &HttpRequest) -> Continuation {
+        let validation = default_validation(&self.rest_config.issuer());
+        let user_id = match authorize_user(request, &self.secret_manager, &validation) {
+            AuthorizationResult::Authorized(claims) => claims.user_id(),
+            AuthorizationResult::Unauthorized(msg) => {
+                log_auth_failure(request, None, None, &msg, None);
+                return Continuation::terminate(
+                    HttpResponse::Unauthorized()
+                        .json(ErrorResponse::unauthorized(&msg))
+                        .into_future(),
+                );
+            }
+            AuthorizationResult::Failed => {
+                return Continuation::terminate(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                )
+            }
+        };
+
+        if let Some(param) = &self.self_access_param {
+            if request.match_info().get(param) == Some(user_id.as_str()) {
+                return Continuation::Continue;
+            }
+        }
+
+        let permission = match self.read_permission {
+            Some(read_permission) if request.method() == http::Method::GET => read_permission,
+            _ => self.permission,
+        };
+        match user_permissions(&*self.role_store, &*self.group_store, &user_id) {
+            Ok(permissions) if permissions.contains(&permission) => Continuation::Continue,
+            Ok(_) => {
+                log_auth_failure(
+                    request,
+                    None,
+                    Some(&user_id),
+                    "User missing permission",
+                    None,
+                );
+                Continuation::terminate(
+                    HttpResponse::Forbidden()
+                        .json(ErrorResponse::forbidden(&format!(
+                            "User has not been granted the {} permission",
+                            permission
+                        )))
+                        .into_future(),
+                )
+            }
+            Err(err) => {
+                error!("Failed to fetch user permissions: {}", err);
+                Continuation::terminate(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                )
+            }
+        }
+    }
+}
+
+/// Defines a REST endpoint for creating and listing roles
+///
+/// `POST /biome/roles` creates a new role. The payload should be in the JSON format:
+///   {
+///       "name": <name of the role>,
+///       "permissions": [<permission>, ...]
+///   }
+///
+/// `GET /biome/roles` lists all roles.
+pub fn make_roles_route(role_store: Arc<dyn RoleStore>, guard: PermissionGuard) -> Resource {
+    Resource::build("/biome/roles")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_AUTHORIZATION_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_request_guard(guard)
+        .add_method(Method::Post, handle_create_role(role_store.clone()))
+        .add_method(Method::Get, handle_list_roles(role_store))
+}
+
+/// Defines a REST endpoint for fetching and removing a role
+///
+/// `GET /biome/roles/{role_name}` returns the role along with the users and groups it is
+/// assigned to. The admin role cannot be removed.
+pub fn make_role_route(role_store: Arc<dyn RoleStore>, guard: PermissionGuard) -> Resource {
+    Resource::build("/biome/roles/{role_name}")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_AUTHORIZATION_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_request_guard(guard)
+        .add_method(Method::Get, handle_fetch_role(role_store.clone()))
+        .add_method(Method::Delete, handle_remove_role(role_store))
+}
+
+/// Defines a REST endpoint for assigning a role to a user, with `PUT`, and removing the
+/// assignment, with `DELETE`
+pub fn make_role_user_route(
+    role_store: Arc<dyn RoleStore>,
+    credentials_store: Arc<dyn CredentialsStore>,
+    guard: PermissionGuard,
+) -> Resource {
+    Resource::build("/biome/roles/{role_name}/users/{user_id}")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_AUTHORIZATION_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_request_guard(guard)
+        .add_method(
+            Method::Put,
+            handle_assign_role_to_user(role_store.clone(), credentials_store),
+        )
+        .add_method(
+            Method::Delete,
+            handle_unassign_role(role_store, "user_id", Assignee::User),
+        )
+}
+
+/// Defines a REST endpoint for assigning a role to a group, with `PUT`, and removing the
+/// assignment, with `DELETE`
+pub fn make_role_group_route(
+    role_store: Arc<dyn RoleStore>,
+    group_store: Arc<dyn GroupStore>,
+    guard: PermissionGuard,
+) -> Resource {
+    Resource::build("/biome/roles/{role_name}/groups/{group_name}")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_AUTHORIZATION_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_request_guard(guard)
+        .add_method(
+            Method::Put,
+            handle_assign_role_to_group(role_store.clone(), group_store),
+        )
+        .add_method(
+            Method::Delete,
+            handle_unassign_role(role_store, "group_name", Assignee::Group),
+        )
+}
+
+/// Defines a REST endpoint for creating and listing groups
+///
+/// `POST /biome/groups` creates a new, empty group. The payload should be in the JSON format:
+///   {
+///       "name": <name of the group>
+///   }
+///
+/// `GET /biome/groups` lists all groups along with their members.
+pub fn make_groups_route(group_store: Arc<dyn GroupStore>, guard: PermissionGuard) -> Resource {
+    Resource::build("/biome/groups")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_AUTHORIZATION_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_request_guard(guard)
+        .add_method(Method::Post, handle_create_group(group_store.clone()))
+        .add_method(Method::Get, handle_list_groups(group_store))
+}
+
+/// Defines a REST endpoint for removing a group, along with the roles assigned to it
+pub fn make_group_route(
+    role_store: Arc<dyn RoleStore>,
+    group_store: Arc<dyn GroupStore>,
+    guard: PermissionGuard,
+) -> Resource {
+    Resource::build("/biome/groups/{group_name}")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_AUTHORIZATION_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_request_guard(guard)
+        .add_method(Method::Delete, handle_remove_group(role_store, group_store))
+}
+
+/// Defines a REST endpoint for adding a user to a group, with `PUT`, and removing them, with
+/// `DELETE`
+pub fn make_group_member_route(
+    group_store: Arc<dyn GroupStore>,
+    credentials_store: Arc<dyn CredentialsStore>,
+    guard: PermissionGuard,
+) -> Resource {
+    Resource::build("/biome/groups/{group_name}/members/{user_id}")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_AUTHORIZATION_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_request_guard(guard)
+        .add_method(
+            Method::Put,
+            handle_add_group_member(group_store.clone(), credentials_store),
+        )
+        .add_method(Method::Delete, handle_remove_group_member(group_store))
+}
+
+/// Defines a REST endpoint method to create a role
+fn handle_create_role(role_store: Arc<dyn RoleStore>) -> HandlerFunction {
+    Box::new(move |_, payload| {
+        let role_store = role_store.clone();
+        Box::new(into_bytes(payload).and_then(move |bytes| {
+            let new_role = match serde_json::from_slice::<NewRole>(&bytes) {
+                Ok(val) => val,
+                Err(err) => {
+                    debug!("Error parsing payload: {}", err);
+                    return HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(&format!(
+                            "Failed to parse payload: {}",
+                            err
+                        )))
+                        .into_future();
+                }
+            };
+
+            if new_role.name.is_empty() {
+                return HttpResponse::BadRequest()
+                    .json(ErrorResponse::bad_request("A role must have a name"))
+                    .into_future();
+            }
+            let permissions = match new_role
+                .permissions
+                .iter()
+                .map(|permission| permission.parse())
+                .collect::<Result<Vec<Permission>, _>>()
+            {
+                Ok(permissions) => permissions,
+                Err(err) => {
+                    return HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(&err.to_string()))
+                        .into_future();
+                }
+            };
+
+            let role = Role::new(&new_role.name, permissions);
+            let response = json!({
+                "message": "Role created successfully",
+                "data": ResponseRole::from(&role),
+            });
+            match role_store.add_role(role) {
+                Ok(()) => HttpResponse::Ok().json(response).into_future(),
+                Err(err) => store_error_response(err).into_future(),
+            }
+        }))
+    })
+}
+
+/// Defines a REST endpoint method to list all roles
+fn handle_list_roles(role_store: Arc<dyn RoleStore>) -> HandlerFunction {
+    Box::new(move |_, _| match role_store.list_roles() {
+        Ok(roles) => Box::new(
+            HttpResponse::Ok()
+                .json(json!({
+                    "data": roles
+                        .iter()
+                        .map(ResponseRole::from)
+                        .collect::<Vec<ResponseRole>>()
+                }))
+                .into_future(),
+        ),
+        Err(err) => Box::new(store_error_response(err).into_future()),
+    })
+}
+
+/// Defines a REST endpoint method to fetch a role along with its assignments
+fn handle_fetch_role(role_store: Arc<dyn RoleStore>) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let role_name = match path_param(&request, "role_name") {
+            Ok(role_name) => role_name,
+            Err(response) => return Box::new(response.into_future()),
+        };
+
+        let role = match role_store.list_roles() {
+            Ok(roles) => roles.into_iter().find(|role| role.name == role_name),
+            Err(err) => return Box::new(store_error_response(err).into_future()),
+        };
+        let role = match role {
+            Some(role) => role,
+            None => {
+                return Box::new(
+                    HttpResponse::NotFound()
+                        .json(ErrorResponse::not_found(&format!(
+                            "Role not found: {}",
+                            role_name
+                        )))
+                        .into_future(),
+                )
+            }
+        };
+
+        match role_store.list_role_assignments(&role_name) {
+            Ok(assignees) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({ "data": ResponseRoleAssignments::new(&role, &assignees) }))
+                    .into_future(),
+            ),
+            Err(err) => Box::new(store_error_response(err).into_future()),
+        }
+    })
+}
+
+/// Defines a REST endpoint method to remove a role
+fn handle_remove_role(role_store: Arc<dyn RoleStore>) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let role_name = match path_param(&request, "role_name") {
+            Ok(role_name) => role_name,
+            Err(response) => return Box::new(response.into_future()),
+        };
+        if role_name == ADMIN_ROLE {
+            return Box::new(
+                HttpResponse::BadRequest()
+                    .json(ErrorResponse::bad_request(
+                        "The admin role cannot be removed",
+                    ))
+                    .into_future(),
+            );
+        }
+
+        match role_store.remove_role(&role_name) {
+            Ok(()) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({ "message": "Role removed successfully" }))
+                    .into_future(),
+            ),
+            Err(err) => Box::new(store_error_response(err).into_future()),
+        }
+    })
+}
+
+/// Defines a REST endpoint method to assign a role to a user
+fn handle_assign_role_to_user(
+    role_store: Arc<dyn RoleStore>,
+    credentials_store: Arc<dyn CredentialsStore>,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let (role_name, user_id) = match (
+            path_param(&request, "role_name"),
+            path_param(&request, "user_id"),
+        ) {
+            (Ok(role_name), Ok(user_id)) => (role_name, user_id),
+            (Err(response), _) | (_, Err(response)) => return Box::new(response.into_future()),
+        };
+
+        match credentials_store.fetch_username_by_id(&user_id) {
+            Ok(_) => (),
+            Err(CredentialsStoreError::NotFoundError(_)) => {
+                return Box::new(
+                    HttpResponse::NotFound()
+                        .json(ErrorResponse::not_found(&format!(
+                            "User ID not found: {}",
+                            user_id
+                        )))
+                        .into_future(),
+                )
+            }
+            Err(err) => {
+                error!("Failed to fetch user: {}", err);
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        }
+
+        Box::new(assign_role(&*role_store, &role_name, Assignee::User(user_id)).into_future())
+    })
+}
+
+/// Defines a REST endpoint method to assign a role to a group
+fn handle_assign_role_to_group(
+    role_store: Arc<dyn RoleStore>,
+    group_store: Arc<dyn GroupStore>,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let (role_name, group_name) = match (
+            path_param(&request, "role_name"),
+            path_param(&request, "group_name"),
+        ) {
+            (Ok(role_name), Ok(group_name)) => (role_name, group_name),
+            (Err(response), _) | (_, Err(response)) => return Box::new(response.into_future()),
+        };
+
+        match group_store.list_groups() {
+            Ok(groups) if groups.iter().any(|group| group.name == group_name) => (),
+            Ok(_) => {
+                return Box::new(
+                    HttpResponse::NotFound()
+                        .json(ErrorResponse::not_found(&format!(
+                            "Group not found: {}",
+                            group_name
+                        )))
+                        .into_future(),
+                )
+            }
+            Err(err) => return Box::new(store_error_response(err).into_future()),
+        }
+
+        Box::new(assign_role(&*role_store, &role_name, Assignee::Group(group_name)).into_future())
+    })
+}
+
+fn assign_role(role_store: &dyn RoleStore, role_name: &str, assignee: Assignee) -> HttpResponse {
+    match role_store.assign_role(role_name, &assignee) {
+        Ok(()) => HttpResponse::Ok().json(json!({ "message": "Role assigned successfully" })),
+        Err(err) => store_error_response(err),
+    }
+}
+
+/// Defines a REST endpoint method to remove the assignment of a role to a user or group, named
+/// by the given path parameter. The last assignment of the admin role cannot be removed.
+fn handle_unassign_role(
+    role_store: Arc<dyn RoleStore>,
+    assignee_param: &'static str,
+    to_assignee: fn(String) -> Assignee,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let (role_name, assignee) = match (
+            path_param(&request, "role_name"),
+            path_param(&request, assignee_param),
+        ) {
+            (Ok(role_name), Ok(assignee)) => (role_name, to_assignee(assignee)),
+            (Err(response), _) | (_, Err(response)) => return Box::new(response.into_future()),
+        };
+
+        if role_name == ADMIN_ROLE {
+            match role_store.list_role_assignments(ADMIN_ROLE) {
+                Ok(assignees) if assignees == [assignee.clone()] => {
+                    return Box::new(
+                        HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request(
+                                "The last assignment of the admin role cannot be removed",
+                            ))
+                            .into_future(),
+                    )
+                }
+                Ok(_) => (),
+                Err(err) => return Box::new(store_error_response(err).into_future()),
+            }
+        }
+
+        match role_store.unassign_role(&role_name, &assignee) {
+            Ok(()) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({ "message": "Role assignment removed successfully" }))
+                    .into_future(),
+            ),
+            Err(err) => Box::new(store_error_response(err).into_future()),
+        }
+    })
+}
+
+/// Defines a REST endpoint method to create a group
+fn handle_create_group(group_store: Arc<dyn GroupStore>) -> HandlerFunction {
+    Box::new(move |_, payload| {
+        let group_store = group_store.clone();
+        Box::new(into_bytes(payload).and_then(move |bytes| {
+            let new_group = match serde_json::from_slice::<NewGroup>(&bytes) {
+                Ok(val) => val,
+                Err(err) => {
+                    debug!("Error parsing payload: {}", err);
+                    return HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(&format!(
+                            "Failed to parse payload: {}",
+                            err
+                        )))
+                        .into_future();
+                }
+            };
+
+            if new_group.name.is_empty() {
+                return HttpResponse::BadRequest()
+                    .json(ErrorResponse::bad_request("A group must have a name"))
+                    .into_future();
+            }
+
+            match group_store.add_group(&new_group.name) {
+                Ok(()) => HttpResponse::Ok()
+                    .json(json!({ "message": "Group created successfully" }))
+                    .into_future(),
+                Err(err) => store_error_response(err).into_future(),
+            }
+        }))
+    })
+}
+
+/// Defines a REST endpoint method to list all groups
+fn handle_list_groups(group_store: Arc<dyn GroupStore>) -> HandlerFunction {
+    Box::new(move |_, _| match group_store.list_groups() {
+        Ok(groups) => Box::new(
+            HttpResponse::Ok()
+                .json(json!({
+                    "data": groups
+                        .iter()
+                        .map(ResponseGroup::from)
+                        .collect::<Vec<ResponseGroup>>()
+                }))
+                .into_future(),
+        ),
+        Err(err) => Box::new(store_error_response(err).into_future()),
+    })
+}
+
+/// Defines a REST endpoint method to remove a group, removing the roles assigned to it first.
+/// A group that holds the last assignment of the admin role cannot be removed.
+fn handle_remove_group(
+    role_store: Arc<dyn RoleStore>,
+    group_store: Arc<dyn GroupStore>,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let group_name = match path_param(&request, "group_name") {
+            Ok(group_name) => group_name,
+            Err(response) => return Box::new(response.into_future()),
+        };
+        let assignee = Assignee::Group(group_name.clone());
+
+        let result = role_store.list_roles().and_then(|roles| {
+            let mut assigned_roles = vec![];
+            for role in roles {
+                let assignees = role_store.list_role_assignments(&role.name)?;
+                if assignees.contains(&assignee) {
+                    if role.name == ADMIN_ROLE && assignees.len() == 1 {
+                        return Ok(None);
+                    }
+                    assigned_roles.push(role.name);
+                }
+            }
+            Ok(Some(assigned_roles))
+        });
+        let assigned_roles = match result {
+            Ok(Some(assigned_roles)) => assigned_roles,
+            Ok(None) => {
+                return Box::new(
+                    HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(
+                            "The group holds the last assignment of the admin role",
+                        ))
+                        .into_future(),
+                )
+            }
+            Err(err) => return Box::new(store_error_response(err).into_future()),
+        };
+
+        let result = assigned_roles
+            .iter()
+            .try_for_each(|role_name| role_store.unassign_role(role_name, &assignee))
+            .and_then(|_| group_store.remove_group(&group_name));
+        match result {
+            Ok(()) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({ "message": "Group removed successfully" }))
+                    .into_future(),
+            ),
+            Err(err) => Box::new(store_error_response(err).into_future()),
+        }
+    })
+}
+
+/// Defines a REST endpoint method to add a user to a group
+fn handle_add_group_member(
+    group_store: Arc<dyn GroupStore>,
+    credentials_store: Arc<dyn CredentialsStore>,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let (group_name, user_id) = match (
+            path_param(&request, "group_name"),
+            path_param(&request, "user_id"),
+        ) {
+            (Ok(group_name), Ok(user_id)) => (group_name, user_id),
+            (Err(response), _) | (_, Err(response)) => return Box::new(response.into_future()),
+        };
+
+        match credentials_store.fetch_username_by_id(&user_id) {
+            Ok(_) => (),
+            Err(CredentialsStoreError::NotFoundError(_)) => {
+                return Box::new(
+                    HttpResponse::NotFound()
+                        .json(ErrorResponse::not_found(&format!(
+                            "User ID not found: {}",
+                            user_id
+                        )))
+                        .into_future(),
+                )
+            }
+            Err(err) => {
+                error!("Failed to fetch user: {}", err);
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        }
+
+        match group_store.add_group_member(&group_name, &user_id) {
+            Ok(()) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({ "message": "User added to group successfully" }))
+                    .into_future(),
+            ),
+            Err(err) => Box::new(store_error_response(err).into_future()),
+        }
+    })
+}
+
+/// Defines a REST endpoint method to remove a user from a group
+fn handle_remove_group_member(group_store: Arc<dyn GroupStore>) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let (group_name, user_id) = match (
+            path_param(&request, "group_name"),
+            path_param(&request, "user_id"),
+        ) {
+            (Ok(group_name), Ok(user_id)) => (group_name, user_id),
+            (Err(response), _) | (_, Err(response)) => return Box::new(response.into_future()),
+        };
+
+        match group_store.remove_group_member(&group_name, &user_id) {
+            Ok(()) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({ "message": "User removed from group successfully" }))
+                    .into_future(),
+            ),
+            Err(err) => Box::new(store_error_response(err).into_future()),
+        }
+    })
+}
+
+/// Returns a path parameter, or a bad request response if it is missing
+fn path_param(request: &HttpRequest, name: &str) -> Result<String, HttpResponse> {
+    match request.match_info().get(name) {
+        Some(value) => Ok(value.to_string()),
+        None => {
+            error!("{} is not in path request", name);
+            Err(
+                HttpResponse::BadRequest().json(ErrorResponse::bad_request(&format!(
+                    "Failed to process request: no {}",
+                    name
+                ))),
+            )
+        }
+    }
+}
+
+fn store_error_response(err: AuthorizationStoreError) -> HttpResponse {
+    match err {
+        AuthorizationStoreError::NotFoundError(_) => {
+            HttpResponse::NotFound().json(ErrorResponse::not_found(&err.to_string()))
+        }
+        AuthorizationStoreError::DuplicateError(_) => {
+            HttpResponse::BadRequest().json(ErrorResponse::bad_request(&err.to_string()))
+        }
+        err => {
+            error!("Failed to update roles or groups: {}", err);
+            HttpResponse::InternalServerError().json(ErrorResponse::internal_error())
+        }
+    }
+}
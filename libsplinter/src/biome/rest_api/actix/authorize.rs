@@ -16,6 +16,7 @@ use std::sync::Arc;
 
 use jsonwebtoken::{decode, Validation};
 
+use super::audit::log_auth_failure;
 use crate::actix_web::HttpRequest;
 use crate::biome::rest_api::resources::authorize::AuthorizationResult;
 use crate::rest_api::get_authorization_token;
@@ -32,11 +33,16 @@ pub(crate) fn authorize_user(
         Ok(token) => token,
         Err(err) => {
             debug!("Failed to get token: {}", err);
+            log_auth_failure(request, None, None, "Missing access token", None);
             return AuthorizationResult::Unauthorized("User is not authorized".to_string());
         }
     };
 
-    validate_claims(&token, secret_manager, validation)
+    let result = validate_claims(&token, secret_manager, validation);
+    if let AuthorizationResult::Unauthorized(_) = result {
+        log_auth_failure(request, None, None, "Invalid access token", None);
+    }
+    result
 }
 
 pub(crate) fn validate_claims(
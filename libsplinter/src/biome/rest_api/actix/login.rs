@@ -13,6 +13,10 @@
 // limitations under the License.
 
 use std::sync::Arc;
+#[cfg(feature = "biome-lockout")]
+use std::time::Duration;
+#[cfg(feature = "biome-user-admin")]
+use std::time::SystemTime;
 
 use crate::actix_web::HttpResponse;
 use crate::biome::refresh_tokens::store::RefreshTokenStore;
@@ -20,11 +24,27 @@ use crate::futures::{Future, IntoFuture};
 use crate::protocol;
 use crate::rest_api::{into_bytes, ErrorResponse, Method, ProtocolVersionRangeGuard, Resource};
 
+#[cfg(any(
+    feature = "biome-lockout",
+    feature = "biome-sessions",
+    feature = "biome-user-admin"
+))]
+use crate::actix_web::HttpRequest;
+#[cfg(feature = "biome-lockout")]
+use crate::biome::credentials::lockout::LoginThrottle;
 use crate::biome::credentials::store::{CredentialsStore, CredentialsStoreError};
 use crate::biome::rest_api::resources::credentials::UsernamePassword;
 use crate::biome::rest_api::BiomeRestConfig;
+#[cfg(feature = "biome-user-admin")]
+use crate::biome::user::store::{UserStore, UserStoreError};
 use crate::rest_api::sessions::{AccessTokenIssuer, ClaimsBuilder, TokenIssuer};
 
+use super::audit::log_auth_failure;
+#[cfg(feature = "biome-mfa")]
+use super::mfa::mfa_challenge_response;
+#[cfg(feature = "biome-sessions")]
+use super::sessions::{new_session, SESSION_ID_CLAIM};
+
 /// Defines a REST endpoint for login
 ///
 /// The payload should be in the JSON format:
@@ -32,22 +52,37 @@ use crate::rest_api::sessions::{AccessTokenIssuer, ClaimsBuilder, TokenIssuer};
 ///       "username": <existing username of the user>
 ///       "hashed_password": <hash of the user's existing password>
 ///   }
+///
+/// If the user has enabled multi-factor authentication, an MFA challenge token is returned in
+/// place of the access and refresh tokens; the login is completed via `POST /biome/login/mfa`.
+///
+/// If too many attempts have failed for the user or from the client, the attempt is refused with
+/// a `429 Too Many Requests` response, whose `Retry-After` header gives the number of seconds
+/// until another attempt is allowed.
+///
+/// If the user has been disabled, the login is refused with a `403 Forbidden` response.
 pub fn make_login_route(
     credentials_store: Arc<dyn CredentialsStore>,
     refresh_token_store: Arc<dyn RefreshTokenStore>,
     rest_config: Arc<BiomeRestConfig>,
     token_issuer: Arc<AccessTokenIssuer>,
+    #[cfg(feature = "biome-lockout")] login_throttle: Arc<LoginThrottle>,
+    #[cfg(feature = "biome-user-admin")] user_store: Arc<dyn UserStore>,
 ) -> Resource {
     Resource::build("/biome/login")
         .add_request_guard(ProtocolVersionRangeGuard::new(
             protocol::BIOME_LOGIN_PROTOCOL_MIN,
             protocol::BIOME_PROTOCOL_VERSION,
         ))
-        .add_method(Method::Post, move |_, payload| {
+        .add_method(Method::Post, move |request, payload| {
             let credentials_store = credentials_store.clone();
             let rest_config = rest_config.clone();
             let token_issuer = token_issuer.clone();
             let refresh_token_store = refresh_token_store.clone();
+            #[cfg(feature = "biome-lockout")]
+            let login_throttle = login_throttle.clone();
+            #[cfg(feature = "biome-user-admin")]
+            let user_store = user_store.clone();
             Box::new(into_bytes(payload).and_then(move |bytes| {
                 let username_password = match serde_json::from_slice::<UsernamePassword>(&bytes) {
                     Ok(val) => val,
@@ -61,109 +96,114 @@ pub fn make_login_route(
                             .into_future();
                     }
                 };
+                let username = username_password.username.as_str();
 
-                let credentials = match credentials_store
-                    .fetch_credential_by_username(&username_password.username)
-                {
-                    Ok(credentials) => credentials,
+                let credentials = match credentials_store.fetch_credential_by_username(username) {
+                    Ok(credentials) => Some(credentials),
+                    Err(CredentialsStoreError::NotFoundError(_)) => None,
                     Err(err) => {
                         debug!("Failed to fetch credentials {}", err);
-                        match err {
-                            CredentialsStoreError::NotFoundError(_) => {
-                                return HttpResponse::BadRequest()
-                                    .json(ErrorResponse::bad_request(&format!(
-                                        "Username not found: {}",
-                                        username_password.username
-                                    )))
-                                    .into_future();
-                            }
-                            _ => {
-                                return HttpResponse::InternalServerError()
-                                    .json(ErrorResponse::internal_error())
-                                    .into_future()
-                            }
-                        }
+                        return HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future();
                     }
                 };
 
-                match credentials.verify_password(&username_password.hashed_password) {
-                    Ok(is_valid) => {
-                        if is_valid {
-                            let claim_builder = ClaimsBuilder::default();
-                            let claim = match claim_builder
-                                .with_user_id(&credentials.user_id)
-                                .with_issuer(&rest_config.issuer())
-                                .with_duration(rest_config.access_token_duration())
-                                .build()
-                            {
-                                Ok(claim) => claim,
-                                Err(err) => {
-                                    debug!("Failed to build claim {}", err);
-                                    return HttpResponse::InternalServerError()
-                                        .json(ErrorResponse::internal_error())
-                                        .into_future();
-                                }
-                            };
-
-                            let token = match token_issuer.issue_token_with_claims(claim) {
-                                Ok(token) => token,
-                                Err(err) => {
-                                    debug!("Failed to issue token {}", err);
-                                    return HttpResponse::InternalServerError()
-                                        .json(ErrorResponse::internal_error())
-                                        .into_future();
-                                }
-                            };
+                #[cfg(feature = "biome-lockout")]
+                {
+                    let user_id = credentials
+                        .as_ref()
+                        .map(|credentials| credentials.user_id.as_str());
+                    if let Some(response) =
+                        throttle_response(&login_throttle, &request, Some(username), user_id)
+                    {
+                        return response.into_future();
+                    }
+                }
 
-                            let refresh_claims = match ClaimsBuilder::default()
-                                .with_user_id(&credentials.user_id)
-                                .with_issuer(&rest_config.issuer())
-                                .with_duration(rest_config.refresh_token_duration())
-                                .build()
-                            {
-                                Ok(claims) => claims,
-                                Err(err) => {
-                                    debug!("Failed to build refresh claim {}", err);
-                                    return HttpResponse::InternalServerError()
-                                        .json(ErrorResponse::internal_error())
-                                        .into_future();
-                                }
-                            };
+                let credentials = match credentials {
+                    Some(credentials) => credentials,
+                    None => {
+                        #[cfg(feature = "biome-lockout")]
+                        record_login_failure(&login_throttle, &request, None);
+                        log_auth_failure(&request, Some(username), None, "Unknown username", None);
+                        return HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request(&format!(
+                                "Username not found: {}",
+                                username
+                            )))
+                            .into_future();
+                    }
+                };
 
-                            let refresh_token = match token_issuer
-                                .issue_refresh_token_with_claims(refresh_claims)
+                match credentials.verify_password(&username_password.hashed_password) {
+                    Ok(true) => {
+                        #[cfg(feature = "biome-mfa")]
+                        {
+                            // A disabled user is refused before being challenged for a second
+                            // factor
+                            #[cfg(feature = "biome-user-admin")]
                             {
-                                Ok(token) => token,
-                                Err(err) => {
-                                    debug!("Failed to issue refresh token {}", err);
-                                    return HttpResponse::InternalServerError()
-                                        .json(ErrorResponse::internal_error())
-                                        .into_future();
+                                if let Some(response) = disabled_response(
+                                    &*user_store,
+                                    &request,
+                                    Some(username),
+                                    &credentials.user_id,
+                                ) {
+                                    return response.into_future();
                                 }
-                            };
+                            }
 
-                            if let Err(err) =
-                                refresh_token_store.add_token(&credentials.user_id, &refresh_token)
-                            {
-                                debug!("Failed to store refresh token {}", err);
-                                return HttpResponse::InternalServerError()
-                                    .json(ErrorResponse::internal_error())
-                                    .into_future();
+                            // The user's failed attempts are only cleared once the second factor
+                            // has been verified
+                            if let Some(response) = mfa_challenge_response(
+                                &*credentials_store,
+                                &credentials.user_id,
+                                &rest_config,
+                                &token_issuer,
+                            ) {
+                                return response.into_future();
                             }
+                        }
 
-                            HttpResponse::Ok()
-                                .json(json!({
-                                    "message": "Successful login",
-                                    "user_id": credentials.user_id,
-                                    "token": token,
-                                    "refresh_token": refresh_token,
-                                }))
-                                .into_future()
-                        } else {
-                            HttpResponse::BadRequest()
-                                .json(ErrorResponse::bad_request("Invalid password"))
-                                .into_future()
+                        #[cfg(feature = "biome-lockout")]
+                        {
+                            if let Err(err) = login_throttle.record_success(&credentials.user_id) {
+                                error!("Failed to clear failed login attempts: {}", err);
+                            }
                         }
+
+                        login_response(
+                            &credentials.user_id,
+                            &rest_config,
+                            &token_issuer,
+                            &*refresh_token_store,
+                            #[cfg(feature = "biome-user-admin")]
+                            &*user_store,
+                            #[cfg(any(feature = "biome-sessions", feature = "biome-user-admin"))]
+                            &request,
+                        )
+                        .into_future()
+                    }
+                    Ok(false) => {
+                        #[cfg(feature = "biome-lockout")]
+                        let failed_attempts = record_login_failure(
+                            &login_throttle,
+                            &request,
+                            Some(&credentials.user_id),
+                        );
+                        #[cfg(not(feature = "biome-lockout"))]
+                        let failed_attempts = None;
+                        log_auth_failure(
+                            &request,
+                            Some(username),
+                            Some(&credentials.user_id),
+                            "Invalid password",
+                            failed_attempts,
+                        );
+                        HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request("Invalid password"))
+                            .into_future()
                     }
                     Err(err) => {
                         debug!("Failed to verify password {}", err);
@@ -175,3 +215,176 @@ pub fn make_login_route(
             }))
         })
 }
+
+/// Returns the response for a login attempt that is refused because too many attempts have
+/// failed for the user or from the client, or `None` if the attempt is allowed.
+#[cfg(feature = "biome-lockout")]
+pub(super) fn throttle_response(
+    login_throttle: &LoginThrottle,
+    request: &HttpRequest,
+    username: Option<&str>,
+    user_id: Option<&str>,
+) -> Option<HttpResponse> {
+    let client = request.peer_addr().map(|addr| addr.ip());
+    match login_throttle.check(user_id, client) {
+        Ok(None) => None,
+        Ok(Some(retry_after)) => {
+            log_auth_failure(request, username, user_id, "Too many failed attempts", None);
+            Some(
+                HttpResponse::TooManyRequests()
+                    .header("Retry-After", retry_after_secs(retry_after).to_string())
+                    .json(ErrorResponse::too_many_requests(
+                        "Too many failed login attempts; try again later",
+                    )),
+            )
+        }
+        Err(err) => {
+            error!("Failed to check failed login attempts: {}", err);
+            Some(HttpResponse::InternalServerError().json(ErrorResponse::internal_error()))
+        }
+    }
+}
+
+/// Records a failed login attempt for the user, if known, and the client, returning the user's
+/// number of consecutive failed attempts.
+#[cfg(feature = "biome-lockout")]
+pub(super) fn record_login_failure(
+    login_throttle: &LoginThrottle,
+    request: &HttpRequest,
+    user_id: Option<&str>,
+) -> Option<u32> {
+    let client = request.peer_addr().map(|addr| addr.ip());
+    match login_throttle.record_failure(user_id, client) {
+        Ok(attempts) => attempts.map(|attempts| attempts.failed_attempts),
+        Err(err) => {
+            error!("Failed to record failed login attempt: {}", err);
+            None
+        }
+    }
+}
+
+/// Rounds a wait up to whole seconds, as required by the `Retry-After` header
+#[cfg(feature = "biome-lockout")]
+fn retry_after_secs(retry_after: Duration) -> u64 {
+    if retry_after.subsec_nanos() > 0 {
+        retry_after.as_secs() + 1
+    } else {
+        retry_after.as_secs()
+    }
+}
+
+/// Returns the response for a login or token refresh that is refused because the user has been
+/// disabled, or `None` if the user is allowed to continue.
+#[cfg(feature = "biome-user-admin")]
+pub(super) fn disabled_response(
+    user_store: &dyn UserStore,
+    request: &HttpRequest,
+    username: Option<&str>,
+    user_id: &str,
+) -> Option<HttpResponse> {
+    match user_store.fetch_user(user_id) {
+        Ok(user) if !user.is_enabled() => {
+            log_auth_failure(request, username, Some(user_id), "User is disabled", None);
+            Some(HttpResponse::Forbidden().json(ErrorResponse::forbidden("User is disabled")))
+        }
+        // A user that is not in the user store has never been disabled
+        Ok(_) | Err(UserStoreError::NotFoundError(_)) => None,
+        Err(err) => {
+            error!("Failed to fetch user {}: {}", user_id, err);
+            Some(HttpResponse::InternalServerError().json(ErrorResponse::internal_error()))
+        }
+    }
+}
+
+/// Issues an access token and a refresh token for a user that has been authenticated, and returns
+/// the response for a successful login
+///
+/// The login is refused if the user has been disabled; otherwise the time of the login is
+/// recorded for the user.
+pub(super) fn login_response(
+    user_id: &str,
+    rest_config: &BiomeRestConfig,
+    token_issuer: &AccessTokenIssuer,
+    refresh_token_store: &dyn RefreshTokenStore,
+    #[cfg(feature = "biome-user-admin")] user_store: &dyn UserStore,
+    #[cfg(any(feature = "biome-sessions", feature = "biome-user-admin"))] request: &HttpRequest,
+) -> HttpResponse {
+    #[cfg(feature = "biome-user-admin")]
+    {
+        if let Some(response) = disabled_response(user_store, request, None, user_id) {
+            return response;
+        }
+    }
+
+    #[cfg(feature = "biome-sessions")]
+    let session = new_session(user_id, request);
+
+    let claims_builder = ClaimsBuilder::default()
+        .with_user_id(user_id)
+        .with_issuer(&rest_config.issuer())
+        .with_duration(rest_config.access_token_duration());
+    #[cfg(feature = "biome-sessions")]
+    let claims_builder = claims_builder.with_custom_claim(SESSION_ID_CLAIM, &session.session_id);
+    let claim = match claims_builder.build() {
+        Ok(claim) => claim,
+        Err(err) => {
+            debug!("Failed to build claim {}", err);
+            return HttpResponse::InternalServerError().json(ErrorResponse::internal_error());
+        }
+    };
+
+    let token = match token_issuer.issue_token_with_claims(claim) {
+        Ok(token) => token,
+        Err(err) => {
+            debug!("Failed to issue token {}", err);
+            return HttpResponse::InternalServerError().json(ErrorResponse::internal_error());
+        }
+    };
+
+    let refresh_claims_builder = ClaimsBuilder::default()
+        .with_user_id(user_id)
+        .with_issuer(&rest_config.issuer())
+        .with_duration(rest_config.refresh_token_duration());
+    #[cfg(feature = "biome-sessions")]
+    let refresh_claims_builder =
+        refresh_claims_builder.with_custom_claim(SESSION_ID_CLAIM, &session.session_id);
+    let refresh_claims = match refresh_claims_builder.build() {
+        Ok(claims) => claims,
+        Err(err) => {
+            debug!("Failed to build refresh claim {}", err);
+            return HttpResponse::InternalServerError().json(ErrorResponse::internal_error());
+        }
+    };
+
+    let refresh_token = match token_issuer.issue_refresh_token_with_claims(refresh_claims) {
+        Ok(token) => token,
+        Err(err) => {
+            debug!("Failed to issue refresh token {}", err);
+            return HttpResponse::InternalServerError().json(ErrorResponse::internal_error());
+        }
+    };
+
+    #[cfg(feature = "biome-sessions")]
+    let result = refresh_token_store.add_session(session, &refresh_token);
+    #[cfg(not(feature = "biome-sessions"))]
+    let result = refresh_token_store.add_token(user_id, &refresh_token);
+    if let Err(err) = result {
+        debug!("Failed to store refresh token {}", err);
+        return HttpResponse::InternalServerError().json(ErrorResponse::internal_error());
+    }
+
+    #[cfg(feature = "biome-user-admin")]
+    {
+        // The login succeeds even if its time cannot be recorded
+        if let Err(err) = user_store.update_last_login(user_id, SystemTime::now()) {
+            error!("Failed to record login for user {}: {}", user_id, err);
+        }
+    }
+
+    HttpResponse::Ok().json(json!({
+        "message": "Successful login",
+        "user_id": user_id,
+        "token": token,
+        "refresh_token": refresh_token,
+    }))
+}
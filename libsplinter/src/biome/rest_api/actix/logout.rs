@@ -27,8 +27,13 @@ use crate::rest_api::{
     ProtocolVersionRangeGuard, Resource,
 };
 
+#[cfg(feature = "biome-sessions")]
+use super::sessions::{revoked_until, session_id};
+
 /// Defines a REST endpoint to remove any refresh tokens belonging to the user.
 ///
+/// If the access token was issued for a session, only that session is revoked; the user's other
+/// sessions remain active.
 pub fn make_logout_route(
     refresh_token_store: Arc<dyn RefreshTokenStore>,
     secret_manager: Arc<dyn SecretManager>,
@@ -55,8 +60,8 @@ pub fn add_logout_route(
         let secret_manager = secret_manager.clone();
         let refresh_token_store = refresh_token_store.clone();
         let validation = default_validation(&rest_config.issuer());
-        let user_id = match authorize_user(&request, &secret_manager, &validation) {
-            AuthorizationResult::Authorized(claims) => claims.user_id(),
+        let claims = match authorize_user(&request, &secret_manager, &validation) {
+            AuthorizationResult::Authorized(claims) => claims,
             AuthorizationResult::Unauthorized(msg) => {
                 return Box::new(
                     HttpResponse::Unauthorized()
@@ -73,7 +78,19 @@ pub fn add_logout_route(
             }
         };
 
-        Box::new(match refresh_token_store.remove_token(&user_id) {
+        let user_id = claims.user_id();
+
+        #[cfg(feature = "biome-sessions")]
+        let result = match session_id(&claims) {
+            Some(session_id) => {
+                refresh_token_store.revoke_session(&session_id, revoked_until(&rest_config))
+            }
+            None => refresh_token_store.remove_token(&user_id),
+        };
+        #[cfg(not(feature = "biome-sessions"))]
+        let result = refresh_token_store.remove_token(&user_id);
+
+        Box::new(match result {
             Ok(()) => HttpResponse::Ok()
                 .json(json!({
                     "message": "User successfully logged out"
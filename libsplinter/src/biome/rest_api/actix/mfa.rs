@@ -0,0 +1,658 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use super::audit::log_auth_failure;
+use super::authorize::{authorize_user, validate_claims};
+use super::login::login_response;
+#[cfg(feature = "biome-lockout")]
+use super::login::{record_login_failure, throttle_response};
+use crate::actix_web::HttpResponse;
+#[cfg(feature = "biome-lockout")]
+use crate::biome::credentials::lockout::LoginThrottle;
+use crate::biome::credentials::mfa::{generate_recovery_codes, hash_recovery_code, MfaSecret};
+use crate::biome::credentials::store::{CredentialsStore, CredentialsStoreError};
+use crate::biome::refresh_tokens::store::RefreshTokenStore;
+use crate::biome::rest_api::resources::authorize::AuthorizationResult;
+use crate::biome::rest_api::resources::mfa::{MfaCode, MfaLogin};
+use crate::biome::rest_api::BiomeRestConfig;
+#[cfg(feature = "biome-user-admin")]
+use crate::biome::user::store::UserStore;
+use crate::futures::{Future, IntoFuture};
+use crate::protocol;
+use crate::rest_api::{
+    into_bytes,
+    secrets::SecretManager,
+    sessions::{default_validation, AccessTokenIssuer, ClaimsBuilder, TokenIssuer},
+    ErrorResponse, HandlerFunction, Method, ProtocolVersionRangeGuard, Resource,
+};
+
+/// Returns the issuer used for MFA challenge tokens. Challenge tokens are signed like access
+/// tokens, but with a different issuer, so that they are rejected everywhere an access token is
+/// expected.
+fn mfa_challenge_issuer(issuer: &str) -> String {
+    format!("{}/mfa", issuer)
+}
+
+/// Returns the response for a login by a user that has enabled MFA, containing an MFA challenge
+/// token, or `None` if the user has not enabled MFA.
+pub(super) fn mfa_challenge_response(
+    credentials_store: &dyn CredentialsStore,
+    user_id: &str,
+    rest_config: &BiomeRestConfig,
+    token_issuer: &AccessTokenIssuer,
+) -> Option<HttpResponse> {
+    match credentials_store.fetch_mfa_secret(user_id) {
+        Ok(secret) if secret.enabled => (),
+        Ok(_) | Err(CredentialsStoreError::NotFoundError(_)) => return None,
+        Err(err) => {
+            error!("Failed to fetch MFA secret: {}", err);
+            return Some(HttpResponse::InternalServerError().json(ErrorResponse::internal_error()));
+        }
+    }
+
+    let claims = match ClaimsBuilder::default()
+        .with_user_id(user_id)
+        .with_issuer(&mfa_challenge_issuer(&rest_config.issuer()))
+        .with_duration(rest_config.mfa_challenge_duration())
+        .build()
+    {
+        Ok(claims) => claims,
+        Err(err) => {
+            error!("Failed to build MFA challenge claims: {}", err);
+            return Some(HttpResponse::InternalServerError().json(ErrorResponse::internal_error()));
+        }
+    };
+
+    match token_issuer.issue_token_with_claims(claims) {
+        Ok(mfa_token) => Some(HttpResponse::Ok().json(json!({
+            "message": "MFA code required",
+            "user_id": user_id,
+            "mfa_token": mfa_token,
+        }))),
+        Err(err) => {
+            error!("Failed to issue MFA challenge token: {}", err);
+            Some(HttpResponse::InternalServerError().json(ErrorResponse::internal_error()))
+        }
+    }
+}
+
+/// Checks a TOTP code or a recovery code for a user. A TOTP code is recorded as used, and a
+/// recovery code is removed, so that neither may be used again.
+fn verify_second_factor(
+    credentials_store: &dyn CredentialsStore,
+    mut secret: MfaSecret,
+    code: &str,
+) -> Result<bool, Box<dyn Error>> {
+    if let Some(step) = secret.verify_code(code, SystemTime::now())? {
+        secret.last_used_step = Some(step);
+        credentials_store.set_mfa_secret(secret)?;
+        return Ok(true);
+    }
+
+    match credentials_store.take_mfa_recovery_code(&secret.user_id, &hash_recovery_code(code)) {
+        Ok(()) => Ok(true),
+        Err(CredentialsStoreError::NotFoundError(_)) => Ok(false),
+        Err(err) => Err(Box::new(err)),
+    }
+}
+
+/// Defines the REST endpoints for managing the authorized user's multi-factor authentication
+///
+/// `GET /biome/mfa` returns whether the user has enabled MFA.
+///
+/// `POST /biome/mfa` starts enrolling the user by generating a new TOTP secret, and returns the
+/// secret along with an `otpauth://` provisioning URI, which may be rendered as a QR code. MFA is
+/// not enabled until a code has been verified via `POST /biome/mfa/verify`.
+///
+/// `DELETE /biome/mfa` disables MFA for the user. The payload should be in the JSON format:
+///   {
+///       "code": <current TOTP code or an unused recovery code>
+///   }
+pub fn make_mfa_route(
+    credentials_store: Arc<dyn CredentialsStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> Resource {
+    Resource::build("/biome/mfa")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_MFA_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(
+            Method::Get,
+            handle_get(
+                credentials_store.clone(),
+                rest_config.clone(),
+                secret_manager.clone(),
+            ),
+        )
+        .add_method(
+            Method::Post,
+            handle_enroll(
+                credentials_store.clone(),
+                rest_config.clone(),
+                secret_manager.clone(),
+            ),
+        )
+        .add_method(
+            Method::Delete,
+            handle_disable(credentials_store, rest_config, secret_manager),
+        )
+}
+
+/// Defines a REST endpoint method to check whether the authorized user has enabled MFA
+fn handle_get(
+    credentials_store: Arc<dyn CredentialsStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let validation = default_validation(&rest_config.issuer());
+        let user_id = match authorize_user(&request, &secret_manager, &validation) {
+            AuthorizationResult::Authorized(claims) => claims.user_id(),
+            AuthorizationResult::Unauthorized(msg) => {
+                return Box::new(
+                    HttpResponse::Unauthorized()
+                        .json(ErrorResponse::unauthorized(&msg))
+                        .into_future(),
+                )
+            }
+            AuthorizationResult::Failed => {
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        };
+
+        let enabled = match credentials_store.fetch_mfa_secret(&user_id) {
+            Ok(secret) => secret.enabled,
+            Err(CredentialsStoreError::NotFoundError(_)) => false,
+            Err(err) => {
+                error!("Failed to fetch MFA secret: {}", err);
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        };
+
+        Box::new(
+            HttpResponse::Ok()
+                .json(json!({ "enabled": enabled }))
+                .into_future(),
+        )
+    })
+}
+
+/// Defines a REST endpoint method to start enrolling the authorized user in MFA
+fn handle_enroll(
+    credentials_store: Arc<dyn CredentialsStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let validation = default_validation(&rest_config.issuer());
+        let user_id = match authorize_user(&request, &secret_manager, &validation) {
+            AuthorizationResult::Authorized(claims) => claims.user_id(),
+            AuthorizationResult::Unauthorized(msg) => {
+                return Box::new(
+                    HttpResponse::Unauthorized()
+                        .json(ErrorResponse::unauthorized(&msg))
+                        .into_future(),
+                )
+            }
+            AuthorizationResult::Failed => {
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        };
+
+        match credentials_store.fetch_mfa_secret(&user_id) {
+            Ok(secret) if secret.enabled => {
+                return Box::new(
+                    HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request("MFA is already enabled"))
+                        .into_future(),
+                );
+            }
+            Ok(_) | Err(CredentialsStoreError::NotFoundError(_)) => (),
+            Err(err) => {
+                error!("Failed to fetch MFA secret: {}", err);
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        }
+
+        let username = match credentials_store.fetch_username_by_id(&user_id) {
+            Ok(username_id) => username_id.username,
+            Err(err) => {
+                error!("Failed to fetch username: {}", err);
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        };
+
+        let secret = match MfaSecret::generate(&user_id) {
+            Ok(secret) => secret,
+            Err(err) => {
+                error!("Failed to generate MFA secret: {}", err);
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        };
+        let provisioning_uri = secret.provisioning_uri(&rest_config.issuer(), &username);
+        let encoded_secret = secret.secret.clone();
+
+        match credentials_store.set_mfa_secret(secret) {
+            Ok(()) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({
+                        "message": "MFA enrollment started",
+                        "secret": encoded_secret,
+                        "provisioning_uri": provisioning_uri,
+                    }))
+                    .into_future(),
+            ),
+            Err(err) => {
+                error!("Failed to set MFA secret: {}", err);
+                Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                )
+            }
+        }
+    })
+}
+
+/// Defines a REST endpoint method to disable MFA for the authorized user
+fn handle_disable(
+    credentials_store: Arc<dyn CredentialsStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> HandlerFunction {
+    Box::new(move |request, payload| {
+        let credentials_store = credentials_store.clone();
+        let validation = default_validation(&rest_config.issuer());
+        let user_id = match authorize_user(&request, &secret_manager, &validation) {
+            AuthorizationResult::Authorized(claims) => claims.user_id(),
+            AuthorizationResult::Unauthorized(msg) => {
+                return Box::new(
+                    HttpResponse::Unauthorized()
+                        .json(ErrorResponse::unauthorized(&msg))
+                        .into_future(),
+                )
+            }
+            AuthorizationResult::Failed => {
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        };
+
+        Box::new(into_bytes(payload).and_then(move |bytes| {
+            let mfa_code = match serde_json::from_slice::<MfaCode>(&bytes) {
+                Ok(val) => val,
+                Err(err) => {
+                    debug!("Error parsing payload: {}", err);
+                    return HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(&format!(
+                            "Failed to parse payload: {}",
+                            err
+                        )))
+                        .into_future();
+                }
+            };
+
+            let secret = match credentials_store.fetch_mfa_secret(&user_id) {
+                Ok(secret) if secret.enabled => secret,
+                Ok(_) | Err(CredentialsStoreError::NotFoundError(_)) => {
+                    return HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request("MFA is not enabled"))
+                        .into_future();
+                }
+                Err(err) => {
+                    error!("Failed to fetch MFA secret: {}", err);
+                    return HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future();
+                }
+            };
+
+            match verify_second_factor(&*credentials_store, secret, &mfa_code.code) {
+                Ok(true) => (),
+                Ok(false) => {
+                    log_auth_failure(&request, None, Some(&user_id), "Invalid MFA code", None);
+                    return HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request("Invalid MFA code"))
+                        .into_future();
+                }
+                Err(err) => {
+                    error!("Failed to verify MFA code: {}", err);
+                    return HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future();
+                }
+            }
+
+            match credentials_store.remove_mfa_secret(&user_id) {
+                Ok(()) => HttpResponse::Ok()
+                    .json(json!({ "message": "MFA disabled" }))
+                    .into_future(),
+                Err(err) => {
+                    error!("Failed to remove MFA secret: {}", err);
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future()
+                }
+            }
+        }))
+    })
+}
+
+/// Defines a REST endpoint for completing MFA enrollment for the authorized user
+///
+/// Verifies a code generated from the secret returned by `POST /biome/mfa`, enables MFA, and
+/// returns a set of single-use recovery codes. The recovery codes are only returned once. The
+/// payload should be in the JSON format:
+///   {
+///       "code": <current TOTP code>
+///   }
+pub fn make_mfa_verify_route(
+    credentials_store: Arc<dyn CredentialsStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> Resource {
+    Resource::build("/biome/mfa/verify")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_MFA_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Post, move |request, payload| {
+            let credentials_store = credentials_store.clone();
+            let validation = default_validation(&rest_config.issuer());
+            let user_id = match authorize_user(&request, &secret_manager, &validation) {
+                AuthorizationResult::Authorized(claims) => claims.user_id(),
+                AuthorizationResult::Unauthorized(msg) => {
+                    return Box::new(
+                        HttpResponse::Unauthorized()
+                            .json(ErrorResponse::unauthorized(&msg))
+                            .into_future(),
+                    )
+                }
+                AuthorizationResult::Failed => {
+                    return Box::new(
+                        HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future(),
+                    );
+                }
+            };
+
+            Box::new(into_bytes(payload).and_then(move |bytes| {
+                let mfa_code = match serde_json::from_slice::<MfaCode>(&bytes) {
+                    Ok(val) => val,
+                    Err(err) => {
+                        debug!("Error parsing payload: {}", err);
+                        return HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request(&format!(
+                                "Failed to parse payload: {}",
+                                err
+                            )))
+                            .into_future();
+                    }
+                };
+
+                let mut secret = match credentials_store.fetch_mfa_secret(&user_id) {
+                    Ok(secret) if !secret.enabled => secret,
+                    Ok(_) => {
+                        return HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request("MFA is already enabled"))
+                            .into_future();
+                    }
+                    Err(CredentialsStoreError::NotFoundError(_)) => {
+                        return HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request(
+                                "MFA enrollment has not been started",
+                            ))
+                            .into_future();
+                    }
+                    Err(err) => {
+                        error!("Failed to fetch MFA secret: {}", err);
+                        return HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future();
+                    }
+                };
+
+                match secret.verify_code(&mfa_code.code, SystemTime::now()) {
+                    Ok(Some(step)) => {
+                        secret.enabled = true;
+                        secret.last_used_step = Some(step);
+                    }
+                    Ok(None) => {
+                        log_auth_failure(&request, None, Some(&user_id), "Invalid MFA code", None);
+                        return HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request("Invalid MFA code"))
+                            .into_future();
+                    }
+                    Err(err) => {
+                        error!("Failed to verify MFA code: {}", err);
+                        return HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future();
+                    }
+                }
+
+                let (recovery_codes, code_hashes) = match generate_recovery_codes() {
+                    Ok(codes) => codes,
+                    Err(err) => {
+                        error!("Failed to generate recovery codes: {}", err);
+                        return HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future();
+                    }
+                };
+
+                // The recovery codes are set before MFA is enabled, so that a user is never left
+                // with MFA enabled but without recovery codes
+                if let Err(err) = credentials_store.set_mfa_recovery_codes(&user_id, code_hashes) {
+                    error!("Failed to set recovery codes: {}", err);
+                    return HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future();
+                }
+
+                match credentials_store.set_mfa_secret(secret) {
+                    Ok(()) => HttpResponse::Ok()
+                        .json(json!({
+                            "message": "MFA enabled",
+                            "recovery_codes": recovery_codes,
+                        }))
+                        .into_future(),
+                    Err(err) => {
+                        error!("Failed to enable MFA: {}", err);
+                        HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future()
+                    }
+                }
+            }))
+        })
+}
+
+/// Defines a REST endpoint for completing a login for a user that has enabled MFA
+///
+/// The payload should be in the JSON format:
+///   {
+///       "mfa_token": <MFA challenge token returned by POST /biome/login>
+///       "code": <current TOTP code or an unused recovery code>
+///   }
+///
+/// On success, the response is the same as that of a login without MFA. Failed attempts count
+/// towards the user's lockout, as for `POST /biome/login`.
+pub fn make_mfa_login_route(
+    credentials_store: Arc<dyn CredentialsStore>,
+    refresh_token_store: Arc<dyn RefreshTokenStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    token_issuer: Arc<AccessTokenIssuer>,
+    secret_manager: Arc<dyn SecretManager>,
+    #[cfg(feature = "biome-lockout")] login_throttle: Arc<LoginThrottle>,
+    #[cfg(feature = "biome-user-admin")] user_store: Arc<dyn UserStore>,
+) -> Resource {
+    Resource::build("/biome/login/mfa")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_MFA_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Post, move |request, payload| {
+            let credentials_store = credentials_store.clone();
+            let refresh_token_store = refresh_token_store.clone();
+            let rest_config = rest_config.clone();
+            let token_issuer = token_issuer.clone();
+            let secret_manager = secret_manager.clone();
+            #[cfg(feature = "biome-lockout")]
+            let login_throttle = login_throttle.clone();
+            #[cfg(feature = "biome-user-admin")]
+            let user_store = user_store.clone();
+            Box::new(into_bytes(payload).and_then(move |bytes| {
+                let mfa_login = match serde_json::from_slice::<MfaLogin>(&bytes) {
+                    Ok(val) => val,
+                    Err(err) => {
+                        debug!("Error parsing payload: {}", err);
+                        return HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request(&format!(
+                                "Failed to parse payload: {}",
+                                err
+                            )))
+                            .into_future();
+                    }
+                };
+
+                let validation = default_validation(&mfa_challenge_issuer(&rest_config.issuer()));
+                let user_id =
+                    match validate_claims(&mfa_login.mfa_token, &secret_manager, &validation) {
+                        AuthorizationResult::Authorized(claims) => claims.user_id(),
+                        AuthorizationResult::Unauthorized(msg) => {
+                            log_auth_failure(
+                                &request,
+                                None,
+                                None,
+                                "Invalid MFA challenge token",
+                                None,
+                            );
+                            return HttpResponse::Unauthorized()
+                                .json(ErrorResponse::unauthorized(&msg))
+                                .into_future();
+                        }
+                        AuthorizationResult::Failed => {
+                            return HttpResponse::InternalServerError()
+                                .json(ErrorResponse::internal_error())
+                                .into_future();
+                        }
+                    };
+
+                #[cfg(feature = "biome-lockout")]
+                {
+                    if let Some(response) =
+                        throttle_response(&login_throttle, &request, None, Some(&user_id))
+                    {
+                        return response.into_future();
+                    }
+                }
+
+                let secret = match credentials_store.fetch_mfa_secret(&user_id) {
+                    Ok(secret) if secret.enabled => secret,
+                    Ok(_) | Err(CredentialsStoreError::NotFoundError(_)) => {
+                        return HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request("MFA is not enabled"))
+                            .into_future();
+                    }
+                    Err(err) => {
+                        error!("Failed to fetch MFA secret: {}", err);
+                        return HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future();
+                    }
+                };
+
+                match verify_second_factor(&*credentials_store, secret, &mfa_login.code) {
+                    Ok(true) => {
+                        #[cfg(feature = "biome-lockout")]
+                        {
+                            if let Err(err) = login_throttle.record_success(&user_id) {
+                                error!("Failed to clear failed login attempts: {}", err);
+                            }
+                        }
+
+                        login_response(
+                            &user_id,
+                            &rest_config,
+                            &token_issuer,
+                            &*refresh_token_store,
+                            #[cfg(feature = "biome-user-admin")]
+                            &*user_store,
+                            #[cfg(any(feature = "biome-sessions", feature = "biome-user-admin"))]
+                            &request,
+                        )
+                        .into_future()
+                    }
+                    Ok(false) => {
+                        #[cfg(feature = "biome-lockout")]
+                        let failed_attempts =
+                            record_login_failure(&login_throttle, &request, Some(&user_id));
+                        #[cfg(not(feature = "biome-lockout"))]
+                        let failed_attempts = None;
+                        log_auth_failure(
+                            &request,
+                            None,
+                            Some(&user_id),
+                            "Invalid MFA code",
+                            failed_attempts,
+                        );
+                        HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request("Invalid MFA code"))
+                            .into_future()
+                    }
+                    Err(err) => {
+                        error!("Failed to verify MFA code: {}", err);
+                        HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future()
+                    }
+                }
+            }))
+        })
+}
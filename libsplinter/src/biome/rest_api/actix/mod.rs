@@ -12,7 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-#[cfg(any(feature = "biome-key-management", feature = "biome-credentials"))]
+#[cfg(feature = "biome-api-keys")]
+pub(super) mod api_keys;
+#[cfg(any(
+    feature = "biome-key-management",
+    feature = "biome-credentials",
+    feature = "biome-notifications"
+))]
+pub(crate) mod audit;
+#[cfg(feature = "biome-authorization")]
+pub(super) mod authorization;
+#[cfg(any(
+    feature = "biome-key-management",
+    feature = "biome-credentials",
+    feature = "biome-notifications"
+))]
 pub(crate) mod authorize;
 #[cfg(feature = "biome-key-management")]
 pub(super) mod key_management;
@@ -20,8 +34,18 @@ pub(super) mod key_management;
 pub(super) mod login;
 #[cfg(feature = "biome-credentials")]
 pub(super) mod logout;
+#[cfg(feature = "biome-mfa")]
+pub(super) mod mfa;
+#[cfg(feature = "biome-notifications")]
+pub(super) mod notifications;
+#[cfg(feature = "biome-oidc")]
+pub(super) mod oidc;
+#[cfg(feature = "biome-password-reset")]
+pub(super) mod password_reset;
 #[cfg(feature = "biome-credentials")]
 pub(super) mod register;
+#[cfg(feature = "biome-sessions")]
+pub(super) mod sessions;
 #[cfg(feature = "biome-credentials")]
 pub(super) mod token;
 #[cfg(feature = "biome-credentials")]
@@ -0,0 +1,331 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use super::authorize::authorize_user;
+use crate::actix_web::HttpResponse;
+use crate::biome::notifications::store::{NotificationStore, NotificationStoreError};
+use crate::biome::rest_api::resources::authorize::AuthorizationResult;
+use crate::biome::rest_api::resources::notifications::ResponseNotification;
+use crate::biome::rest_api::BiomeRestConfig;
+use crate::futures::IntoFuture;
+use crate::protocol;
+use crate::rest_api::{secrets::SecretManager, sessions::default_validation};
+use crate::rest_api::{
+    ErrorResponse, HandlerFunction, Method, ProtocolVersionRangeGuard, Resource,
+};
+
+/// Defines a REST endpoint for listing the notifications of a user
+pub fn make_notifications_route(
+    rest_config: Arc<BiomeRestConfig>,
+    notification_store: Arc<dyn NotificationStore>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> Resource {
+    Resource::build("/biome/notifications")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_NOTIFICATIONS_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(
+            Method::Get,
+            handle_list(rest_config, notification_store, secret_manager),
+        )
+}
+
+/// Defines a REST endpoint method to list a user's notifications, newest first
+fn handle_list(
+    rest_config: Arc<BiomeRestConfig>,
+    notification_store: Arc<dyn NotificationStore>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let validation = default_validation(&rest_config.issuer());
+
+        let user_id = match authorize_user(&request, &secret_manager, &validation) {
+            AuthorizationResult::Authorized(claims) => claims.user_id(),
+            AuthorizationResult::Unauthorized(msg) => {
+                return Box::new(
+                    HttpResponse::Unauthorized()
+                        .json(ErrorResponse::unauthorized(&msg))
+                        .into_future(),
+                )
+            }
+            AuthorizationResult::Failed => {
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        };
+
+        match notification_store.list_notifications(&user_id) {
+            Ok(notifications) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!(
+                        {
+                            "data": notifications.iter()
+                                .map(ResponseNotification::from)
+                                .collect::<Vec<ResponseNotification>>()
+                        }
+                    ))
+                    .into_future(),
+            ),
+            Err(err) => {
+                error!("Failed to list notifications: {}", err);
+                Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                )
+            }
+        }
+    })
+}
+
+/// Defines a REST endpoint for managing a single notification of a user, including fetching it,
+/// marking it as read and deleting it
+pub fn make_notifications_route_with_notification_id(
+    rest_config: Arc<BiomeRestConfig>,
+    notification_store: Arc<dyn NotificationStore>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> Resource {
+    Resource::build("/biome/notifications/{notification_id}")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_NOTIFICATIONS_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(
+            Method::Get,
+            handle_fetch(
+                rest_config.clone(),
+                notification_store.clone(),
+                secret_manager.clone(),
+            ),
+        )
+        .add_method(
+            Method::Patch,
+            handle_patch(
+                rest_config.clone(),
+                notification_store.clone(),
+                secret_manager.clone(),
+            ),
+        )
+        .add_method(
+            Method::Delete,
+            handle_delete(rest_config, notification_store, secret_manager),
+        )
+}
+
+/// Defines a REST endpoint method to fetch a notification from the underlying storage
+fn handle_fetch(
+    rest_config: Arc<BiomeRestConfig>,
+    notification_store: Arc<dyn NotificationStore>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let validation = default_validation(&rest_config.issuer());
+
+        let notification_id = match request.match_info().get("notification_id") {
+            Some(id) => id.to_owned(),
+            None => {
+                error!("Notification ID is not in path request");
+                return Box::new(
+                    HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(
+                            &"Failed to process request: no notification ID".to_string(),
+                        ))
+                        .into_future(),
+                );
+            }
+        };
+
+        let user_id = match authorize_user(&request, &secret_manager, &validation) {
+            AuthorizationResult::Authorized(claims) => claims.user_id(),
+            AuthorizationResult::Unauthorized(msg) => {
+                return Box::new(
+                    HttpResponse::Unauthorized()
+                        .json(ErrorResponse::unauthorized(&msg))
+                        .into_future(),
+                )
+            }
+            AuthorizationResult::Failed => {
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        };
+
+        match notification_store.fetch_notification(&notification_id, &user_id) {
+            Ok(notification) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({ "data": ResponseNotification::from(&notification) }))
+                    .into_future(),
+            ),
+            Err(NotificationStoreError::NotFoundError(msg)) => {
+                debug!("Failed to fetch notification: {}", msg);
+                Box::new(
+                    HttpResponse::NotFound()
+                        .json(ErrorResponse::not_found(&msg))
+                        .into_future(),
+                )
+            }
+            Err(err) => {
+                error!("Failed to fetch notification: {}", err);
+                Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                )
+            }
+        }
+    })
+}
+
+/// Defines a REST endpoint method to mark a notification as read
+fn handle_patch(
+    rest_config: Arc<BiomeRestConfig>,
+    notification_store: Arc<dyn NotificationStore>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let validation = default_validation(&rest_config.issuer());
+
+        let notification_id = match request.match_info().get("notification_id") {
+            Some(id) => id.to_owned(),
+            None => {
+                error!("Notification ID is not in path request");
+                return Box::new(
+                    HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(
+                            &"Failed to process request: no notification ID".to_string(),
+                        ))
+                        .into_future(),
+                );
+            }
+        };
+
+        let user_id = match authorize_user(&request, &secret_manager, &validation) {
+            AuthorizationResult::Authorized(claims) => claims.user_id(),
+            AuthorizationResult::Unauthorized(msg) => {
+                return Box::new(
+                    HttpResponse::Unauthorized()
+                        .json(ErrorResponse::unauthorized(&msg))
+                        .into_future(),
+                )
+            }
+            AuthorizationResult::Failed => {
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        };
+
+        match notification_store.mark_read(&notification_id, &user_id) {
+            Ok(()) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({ "message": "Notification marked as read" }))
+                    .into_future(),
+            ),
+            Err(NotificationStoreError::NotFoundError(msg)) => {
+                debug!("Failed to mark notification as read: {}", msg);
+                Box::new(
+                    HttpResponse::NotFound()
+                        .json(ErrorResponse::not_found(&msg))
+                        .into_future(),
+                )
+            }
+            Err(err) => {
+                error!("Failed to mark notification as read: {}", err);
+                Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                )
+            }
+        }
+    })
+}
+
+/// Defines a REST endpoint method to delete a notification from the underlying storage
+fn handle_delete(
+    rest_config: Arc<BiomeRestConfig>,
+    notification_store: Arc<dyn NotificationStore>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let validation = default_validation(&rest_config.issuer());
+
+        let notification_id = match request.match_info().get("notification_id") {
+            Some(id) => id.to_owned(),
+            None => {
+                error!("Notification ID is not in path request");
+                return Box::new(
+                    HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(
+                            &"Failed to process request: no notification ID".to_string(),
+                        ))
+                        .into_future(),
+                );
+            }
+        };
+
+        let user_id = match authorize_user(&request, &secret_manager, &validation) {
+            AuthorizationResult::Authorized(claims) => claims.user_id(),
+            AuthorizationResult::Unauthorized(msg) => {
+                return Box::new(
+                    HttpResponse::Unauthorized()
+                        .json(ErrorResponse::unauthorized(&msg))
+                        .into_future(),
+                )
+            }
+            AuthorizationResult::Failed => {
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        };
+
+        match notification_store.remove_notification(&notification_id, &user_id) {
+            Ok(()) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({ "message": "Notification successfully deleted" }))
+                    .into_future(),
+            ),
+            Err(NotificationStoreError::NotFoundError(msg)) => {
+                debug!("Failed to delete notification: {}", msg);
+                Box::new(
+                    HttpResponse::NotFound()
+                        .json(ErrorResponse::not_found(&msg))
+                        .into_future(),
+                )
+            }
+            Err(err) => {
+                error!("Failed to delete notification: {}", err);
+                Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                )
+            }
+        }
+    })
+}
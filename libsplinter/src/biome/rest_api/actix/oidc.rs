@@ -0,0 +1,240 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::login::login_response;
+use crate::actix_web::{error::BlockingError, web, HttpRequest, HttpResponse};
+use crate::biome::oidc::{OidcClient, OidcError};
+use crate::biome::refresh_tokens::store::RefreshTokenStore;
+use crate::biome::rest_api::BiomeRestConfig;
+use crate::biome::user::store::{User, UserStore, UserStoreError};
+use crate::futures::{Future, IntoFuture};
+use crate::protocol;
+use crate::rest_api::{
+    sessions::AccessTokenIssuer, ErrorResponse, Method, ProtocolVersionRangeGuard, Resource,
+};
+
+// The cookie that binds a login to the user agent that started it
+const LOGIN_COOKIE: &str = "biome_oidc_login";
+// How long the login cookie is kept, which matches how long a login may be pending
+const LOGIN_COOKIE_MAX_AGE: u64 = 600;
+
+/// Defines a REST endpoint for starting a login through the OpenID Connect provider
+///
+/// Responds with a redirect to the provider's authorization endpoint. Once the user has
+/// authenticated, the provider redirects to `/biome/oidc/callback`. The response sets an HttpOnly
+/// cookie, scoped to the callback, that must be presented to complete the login.
+pub fn make_oidc_login_route(oidc_client: Arc<OidcClient>) -> Resource {
+    let login_cookie_attributes = login_cookie_attributes(oidc_client.redirect_url());
+    Resource::build("/biome/oidc/login")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_OIDC_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Get, move |_, _| {
+            let oidc_client = oidc_client.clone();
+            let login_cookie_attributes = login_cookie_attributes.clone();
+            Box::new(
+                web::block(move || oidc_client.start_login()).then(move |res| {
+                    Ok(match res {
+                        Ok(login) => HttpResponse::Found()
+                            .header("Location", login.authorization_url)
+                            .header(
+                                "Set-Cookie",
+                                format!(
+                                    "{}={}; {}",
+                                    LOGIN_COOKIE, login.binding, login_cookie_attributes
+                                ),
+                            )
+                            .finish(),
+                        Err(err) => {
+                            error!("Failed to start OpenID Connect login: {}", err);
+                            HttpResponse::InternalServerError()
+                                .json(ErrorResponse::internal_error())
+                        }
+                    })
+                }),
+            )
+        })
+}
+
+/// Defines a REST endpoint for completing a login through the OpenID Connect provider
+///
+/// The provider redirects to this endpoint with the query parameters `code` and `state`; the
+/// request must also include the cookie set by `/biome/oidc/login`. The code is exchanged for the
+/// user's ID token, and the user is added to Biome the first time they log in. The response is
+/// the same as a successful `POST /biome/login`.
+///
+/// Multi-factor authentication is left to the provider; Biome MFA does not apply to these
+/// logins.
+pub fn make_oidc_callback_route(
+    oidc_client: Arc<OidcClient>,
+    user_store: Arc<dyn UserStore>,
+    refresh_token_store: Arc<dyn RefreshTokenStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    token_issuer: Arc<AccessTokenIssuer>,
+) -> Resource {
+    Resource::build("/biome/oidc/callback")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_OIDC_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Get, move |request, _| {
+            let query: web::Query<HashMap<String, String>> =
+                match web::Query::from_query(request.query_string()) {
+                    Ok(query) => query,
+                    Err(_) => {
+                        return Box::new(
+                            HttpResponse::BadRequest()
+                                .json(ErrorResponse::bad_request("Invalid query"))
+                                .into_future(),
+                        )
+                    }
+                };
+
+            if let Some(error) = query.get("error") {
+                debug!("OpenID Connect provider returned error {}", error);
+                return Box::new(
+                    HttpResponse::Unauthorized()
+                        .json(ErrorResponse::unauthorized(&format!(
+                            "Login was not completed: {}",
+                            error
+                        )))
+                        .into_future(),
+                );
+            }
+
+            let (code, state) = match (query.get("code"), query.get("state")) {
+                (Some(code), Some(state)) => (code.clone(), state.clone()),
+                _ => {
+                    return Box::new(
+                        HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request(
+                                "Query must include the code and state",
+                            ))
+                            .into_future(),
+                    )
+                }
+            };
+
+            let binding = match login_cookie(&request) {
+                Some(binding) => binding,
+                None => {
+                    return Box::new(
+                        HttpResponse::Unauthorized()
+                            .json(ErrorResponse::unauthorized("Login could not be verified"))
+                            .into_future(),
+                    )
+                }
+            };
+
+            let oidc_client = oidc_client.clone();
+            let user_store = user_store.clone();
+            let refresh_token_store = refresh_token_store.clone();
+            let rest_config = rest_config.clone();
+            let token_issuer = token_issuer.clone();
+            #[cfg(feature = "biome-user-admin")]
+            let login_user_store = user_store.clone();
+            Box::new(
+                web::block(move || {
+                    oidc_client
+                        .authenticate(&code, &state, &binding)
+                        .and_then(|identity| {
+                            provision_user(&*user_store, &identity.user_id)?;
+                            Ok(identity)
+                        })
+                })
+                .then(move |res| {
+                    Ok(match res {
+                        Ok(identity) => login_response(
+                            &identity.user_id,
+                            &rest_config,
+                            &token_issuer,
+                            &*refresh_token_store,
+                            #[cfg(feature = "biome-user-admin")]
+                            &*login_user_store,
+                            #[cfg(any(feature = "biome-sessions", feature = "biome-user-admin"))]
+                            &request,
+                        ),
+                        Err(BlockingError::Error(OidcError::InvalidState(msg)))
+                        | Err(BlockingError::Error(OidcError::InvalidToken(msg))) => {
+                            debug!("Rejected OpenID Connect login: {}", msg);
+                            HttpResponse::Unauthorized()
+                                .json(ErrorResponse::unauthorized("Login could not be verified"))
+                        }
+                        Err(err) => {
+                            error!("Failed to complete OpenID Connect login: {}", err);
+                            HttpResponse::InternalServerError()
+                                .json(ErrorResponse::internal_error())
+                        }
+                    })
+                }),
+            )
+        })
+}
+
+/// Returns the attributes of the login cookie, which is only sent to the path of the redirect URL
+/// and, if the redirect URL uses HTTPS, only over HTTPS
+fn login_cookie_attributes(redirect_url: &str) -> String {
+    let (path, secure) = match url::Url::parse(redirect_url) {
+        Ok(url) => (url.path().to_string(), url.scheme() == "https"),
+        Err(_) => ("/".to_string(), false),
+    };
+    format!(
+        "Path={}; Max-Age={}; HttpOnly; SameSite=Lax{}",
+        path,
+        LOGIN_COOKIE_MAX_AGE,
+        if secure { "; Secure" } else { "" }
+    )
+}
+
+/// Returns the value of the login cookie, if the request includes it
+fn login_cookie(request: &HttpRequest) -> Option<String> {
+    request
+        .headers()
+        .get_all("Cookie")
+        .filter_map(|header| header.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .filter_map(|cookie| {
+            let mut parts = cookie.trim().splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(LOGIN_COOKIE), Some(value)) if !value.is_empty() => Some(value.to_string()),
+                _ => None,
+            }
+        })
+        .next()
+}
+
+/// Adds the user to the user store if they are logging in for the first time
+fn provision_user(user_store: &dyn UserStore, user_id: &str) -> Result<(), OidcError> {
+    match user_store.fetch_user(user_id) {
+        Ok(_) => Ok(()),
+        Err(UserStoreError::NotFoundError(_)) => {
+            user_store
+                .add_user(User::new(user_id))
+                .map_err(|err| OidcError::InternalError {
+                    context: format!("Failed to add user {}: {}", user_id, err),
+                    source: None,
+                })?;
+            info!("Added user {} on first OpenID Connect login", user_id);
+            Ok(())
+        }
+        Err(err) => Err(OidcError::InternalError {
+            context: format!("Failed to fetch user {}: {}", user_id, err),
+            source: None,
+        }),
+    }
+}
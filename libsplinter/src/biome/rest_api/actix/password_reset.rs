@@ -0,0 +1,373 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use crate::actix_web::HttpResponse;
+use crate::biome::credentials::password_reset::{
+    hash_token, PasswordResetNotifier, PasswordResetToken,
+};
+use crate::biome::credentials::store::{CredentialsStore, CredentialsStoreError};
+use crate::biome::refresh_tokens::store::{RefreshTokenError, RefreshTokenStore};
+use crate::biome::rest_api::config::BiomeRestConfig;
+use crate::futures::{Future, IntoFuture};
+use crate::protocol;
+use crate::rest_api::{
+    into_bytes, secrets::SecretManager, sessions::default_validation, ErrorResponse, Method,
+    ProtocolVersionRangeGuard, Resource,
+};
+
+use super::super::resources::authorize::AuthorizationResult;
+use super::super::resources::password_reset::{
+    PasswordChange, PasswordReset, PasswordResetRequest,
+};
+use super::audit::log_auth_failure;
+use super::authorize::authorize_user;
+#[cfg(feature = "biome-sessions")]
+use super::sessions::{revoke_all_sessions, revoked_until};
+
+const RESET_REQUESTED_MESSAGE: &str =
+    "If the user exists, a password reset token has been sent to the user";
+
+/// Defines the REST endpoints for resetting a forgotten password
+///
+/// `POST /biome/password/reset` requests a password reset token for a user. The token is
+/// delivered to the user through the configured `PasswordResetNotifier`. The same response is
+/// returned whether or not the user exists, and no more than the configured limit of tokens are
+/// issued to a user within the token duration. The payload should be in the JSON format:
+///   {
+///       "username": <existing username of the user>
+///   }
+///
+/// `PUT /biome/password/reset` sets a new password using a password reset token. The token may
+/// only be used once. On success, all of the user's refresh tokens are removed and their sessions
+/// revoked, so the user must log in again with the new password. The payload should be in the
+/// JSON format:
+///   {
+///       "token": <password reset token delivered to the user>
+///       "new_password": <hash of the user's new password>
+///   }
+pub fn make_password_reset_route(
+    credentials_store: Arc<dyn CredentialsStore>,
+    refresh_token_store: Arc<dyn RefreshTokenStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    notifier: Arc<dyn PasswordResetNotifier>,
+) -> Resource {
+    let put_credentials_store = credentials_store.clone();
+    let put_rest_config = rest_config.clone();
+    Resource::build("/biome/password/reset")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_PASSWORD_RESET_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Post, move |_, payload| {
+            let credentials_store = credentials_store.clone();
+            let rest_config = rest_config.clone();
+            let notifier = notifier.clone();
+            Box::new(into_bytes(payload).and_then(move |bytes| {
+                let reset_request = match serde_json::from_slice::<PasswordResetRequest>(&bytes) {
+                    Ok(val) => val,
+                    Err(err) => {
+                        debug!("Error parsing payload: {}", err);
+                        return HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request(&format!(
+                                "Failed to parse payload: {}",
+                                err
+                            )))
+                            .into_future();
+                    }
+                };
+
+                let credentials =
+                    match credentials_store.fetch_credential_by_username(&reset_request.username) {
+                        Ok(credentials) => credentials,
+                        Err(CredentialsStoreError::NotFoundError(_)) => {
+                            debug!(
+                                "Password reset requested for unknown user: {}",
+                                reset_request.username
+                            );
+                            return HttpResponse::Ok()
+                                .json(json!({ "message": RESET_REQUESTED_MESSAGE }))
+                                .into_future();
+                        }
+                        Err(err) => {
+                            error!("Failed to fetch credentials: {}", err);
+                            return HttpResponse::InternalServerError()
+                                .json(ErrorResponse::internal_error())
+                                .into_future();
+                        }
+                    };
+
+                let duration = rest_config.password_reset_token_duration();
+                let window_start = SystemTime::now()
+                    .checked_sub(duration)
+                    .unwrap_or(SystemTime::UNIX_EPOCH);
+                match credentials_store
+                    .count_password_reset_tokens(&credentials.user_id, window_start)
+                {
+                    Ok(count) if count >= rest_config.password_reset_limit() => {
+                        debug!(
+                            "Password reset limit reached for user: {}",
+                            credentials.user_id
+                        );
+                        return HttpResponse::Ok()
+                            .json(json!({ "message": RESET_REQUESTED_MESSAGE }))
+                            .into_future();
+                    }
+                    Ok(_) => (),
+                    Err(err) => {
+                        error!("Failed to count password reset tokens: {}", err);
+                        return HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future();
+                    }
+                }
+
+                let (token, reset_token) =
+                    PasswordResetToken::generate(&credentials.user_id, duration);
+                if let Err(err) = credentials_store.add_password_reset_token(reset_token) {
+                    error!("Failed to store password reset token: {}", err);
+                    return HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future();
+                }
+
+                if let Err(err) = notifier.send_reset_token(&credentials.username, &token) {
+                    error!("Failed to send password reset token: {}", err);
+                    return HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future();
+                }
+
+                HttpResponse::Ok()
+                    .json(json!({ "message": RESET_REQUESTED_MESSAGE }))
+                    .into_future()
+            }))
+        })
+        .add_method(Method::Put, move |request, payload| {
+            let credentials_store = put_credentials_store.clone();
+            let rest_config = put_rest_config.clone();
+            let refresh_token_store = refresh_token_store.clone();
+            Box::new(into_bytes(payload).and_then(move |bytes| {
+                let password_reset = match serde_json::from_slice::<PasswordReset>(&bytes) {
+                    Ok(val) => val,
+                    Err(err) => {
+                        debug!("Error parsing payload: {}", err);
+                        return HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request(&format!(
+                                "Failed to parse payload: {}",
+                                err
+                            )))
+                            .into_future();
+                    }
+                };
+
+                let reset_token = match credentials_store
+                    .take_password_reset_token(&hash_token(&password_reset.token))
+                {
+                    Ok(reset_token) if !reset_token.is_expired() => reset_token,
+                    Ok(_) | Err(CredentialsStoreError::NotFoundError(_)) => {
+                        log_auth_failure(
+                            &request,
+                            None,
+                            None,
+                            "Invalid or expired password reset token",
+                            None,
+                        );
+                        return HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request(
+                                "Invalid or expired password reset token",
+                            ))
+                            .into_future();
+                    }
+                    Err(err) => {
+                        error!("Failed to take password reset token: {}", err);
+                        return HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future();
+                    }
+                };
+
+                let credentials =
+                    match credentials_store.fetch_credential_by_user_id(&reset_token.user_id) {
+                        Ok(credentials) => credentials,
+                        Err(err) => {
+                            error!("Failed to fetch credentials: {}", err);
+                            return HttpResponse::InternalServerError()
+                                .json(ErrorResponse::internal_error())
+                                .into_future();
+                        }
+                    };
+
+                if let Err(err) = credentials_store.update_credentials(
+                    &credentials.user_id,
+                    &credentials.username,
+                    &password_reset.new_password,
+                    rest_config.password_encryption_cost(),
+                ) {
+                    error!("Failed to update credentials: {}", err);
+                    return HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future();
+                }
+
+                // Any other outstanding reset tokens for the user are no longer needed
+                if let Err(err) =
+                    credentials_store.remove_password_reset_tokens(&credentials.user_id)
+                {
+                    error!("Failed to remove password reset tokens: {}", err);
+                    return HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future();
+                }
+
+                #[cfg(feature = "biome-sessions")]
+                let result = revoke_all_sessions(
+                    &*refresh_token_store,
+                    &credentials.user_id,
+                    revoked_until(&rest_config),
+                );
+                #[cfg(not(feature = "biome-sessions"))]
+                let result = refresh_token_store.remove_token(&credentials.user_id);
+                match result {
+                    Ok(()) | Err(RefreshTokenError::NotFoundError(_)) => (),
+                    Err(err) => {
+                        error!("Failed to remove refresh tokens: {}", err);
+                        return HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future();
+                    }
+                }
+
+                HttpResponse::Ok()
+                    .json(json!({
+                        "message": "Password reset successfully",
+                        "user_id": credentials.user_id,
+                    }))
+                    .into_future()
+            }))
+        })
+}
+
+/// Defines a REST endpoint for changing the authorized user's password
+///
+/// The user's keys are not re-encrypted; clients that encrypt keys with the user's password
+/// should use `PUT /biome/users/{id}` instead. The payload should be in the JSON format:
+///   {
+///       "hashed_password": <hash of the user's existing password>
+///       "new_password": <hash of the user's new password>
+///   }
+pub fn make_password_route(
+    credentials_store: Arc<dyn CredentialsStore>,
+    rest_config: Arc<BiomeRestConfig>,
+    secret_manager: Arc<dyn SecretManager>,
+) -> Resource {
+    Resource::build("/biome/password")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_PASSWORD_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Put, move |request, payload| {
+            let credentials_store = credentials_store.clone();
+            let rest_config = rest_config.clone();
+            let validation = default_validation(&rest_config.issuer());
+            let user_id = match authorize_user(&request, &secret_manager, &validation) {
+                AuthorizationResult::Authorized(claims) => claims.user_id(),
+                AuthorizationResult::Unauthorized(msg) => {
+                    return Box::new(
+                        HttpResponse::Unauthorized()
+                            .json(ErrorResponse::unauthorized(&msg))
+                            .into_future(),
+                    )
+                }
+                AuthorizationResult::Failed => {
+                    error!("Failed to authorize user");
+                    return Box::new(
+                        HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future(),
+                    );
+                }
+            };
+            Box::new(into_bytes(payload).and_then(move |bytes| {
+                let password_change = match serde_json::from_slice::<PasswordChange>(&bytes) {
+                    Ok(val) => val,
+                    Err(err) => {
+                        debug!("Error parsing payload: {}", err);
+                        return HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request(&format!(
+                                "Failed to parse payload: {}",
+                                err
+                            )))
+                            .into_future();
+                    }
+                };
+
+                let credentials = match credentials_store.fetch_credential_by_user_id(&user_id) {
+                    Ok(credentials) => credentials,
+                    Err(CredentialsStoreError::NotFoundError(_)) => {
+                        return HttpResponse::NotFound()
+                            .json(ErrorResponse::not_found("User not found"))
+                            .into_future();
+                    }
+                    Err(err) => {
+                        error!("Failed to fetch credentials: {}", err);
+                        return HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future();
+                    }
+                };
+
+                match credentials.verify_password(&password_change.hashed_password) {
+                    Ok(true) => (),
+                    Ok(false) => {
+                        log_auth_failure(
+                            &request,
+                            Some(&credentials.username),
+                            Some(&credentials.user_id),
+                            "Invalid password",
+                            None,
+                        );
+                        return HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request("Invalid password"))
+                            .into_future();
+                    }
+                    Err(err) => {
+                        error!("Failed to verify password: {}", err);
+                        return HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future();
+                    }
+                }
+
+                match credentials_store.update_credentials(
+                    &credentials.user_id,
+                    &credentials.username,
+                    &password_change.new_password,
+                    rest_config.password_encryption_cost(),
+                ) {
+                    Ok(()) => HttpResponse::Ok()
+                        .json(json!({ "message": "Password changed successfully" }))
+                        .into_future(),
+                    Err(err) => {
+                        error!("Failed to update credentials: {}", err);
+                        HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future()
+                    }
+                }
+            }))
+        })
+}
@@ -0,0 +1,385 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use uuid::Uuid;
+
+use super::audit::log_auth_failure;
+use super::authorize::{authorize_user, validate_claims};
+use crate::actix_web::{http::header, HttpRequest, HttpResponse};
+use crate::biome::refresh_tokens::store::{RefreshTokenError, RefreshTokenStore, Session};
+use crate::biome::rest_api::resources::authorize::AuthorizationResult;
+use crate::biome::rest_api::resources::sessions::ResponseSession;
+use crate::biome::rest_api::BiomeRestConfig;
+use crate::futures::IntoFuture;
+use crate::protocol;
+use crate::rest_api::{
+    get_authorization_token,
+    secrets::SecretManager,
+    sessions::{default_validation, ignore_exp_validation, Claims},
+    Continuation, ErrorResponse, HandlerFunction, Method, ProtocolVersionRangeGuard, RequestGuard,
+    Resource,
+};
+
+/// The name of the custom claim that carries the session ID in access and refresh tokens
+pub(super) const SESSION_ID_CLAIM: &str = "sid";
+
+/// Creates a new session for a user that is logging in, recording the client that created it
+pub(super) fn new_session(user_id: &str, request: &HttpRequest) -> Session {
+    let now = SystemTime::now();
+    Session {
+        session_id: Uuid::new_v4().to_string(),
+        user_id: user_id.to_string(),
+        created: now,
+        last_used: now,
+        user_agent: request
+            .headers()
+            .get(header::USER_AGENT)
+            .and_then(|user_agent| user_agent.to_str().ok())
+            .map(String::from),
+        ip_address: request.peer_addr().map(|addr| addr.ip().to_string()),
+    }
+}
+
+/// Returns the ID of the session a token was issued for, if it was issued for one
+pub(super) fn session_id(claims: &Claims) -> Option<String> {
+    claims.custom_claims().remove(SESSION_ID_CLAIM)
+}
+
+/// Returns how long a revoked session must stay on the revocation list, which is until every
+/// access token issued for it has expired
+pub(super) fn revoked_until(rest_config: &BiomeRestConfig) -> SystemTime {
+    SystemTime::now() + rest_config.access_token_duration()
+}
+
+/// Revokes all of a user's sessions, along with any refresh token issued outside of a session
+pub(super) fn revoke_all_sessions(
+    refresh_token_store: &dyn RefreshTokenStore,
+    user_id: &str,
+    revoked_until: SystemTime,
+) -> Result<(), RefreshTokenError> {
+    for session in refresh_token_store.list_sessions(user_id)? {
+        match refresh_token_store.revoke_session(&session.session_id, revoked_until) {
+            Ok(()) | Err(RefreshTokenError::NotFoundError(_)) => (),
+            Err(err) => return Err(err),
+        }
+    }
+
+    match refresh_token_store.remove_token(user_id) {
+        Ok(()) | Err(RefreshTokenError::NotFoundError(_)) => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Defines a REST endpoint for listing the sessions of a user and revoking all of them
+pub fn make_sessions_route(
+    refresh_token_store: Arc<dyn RefreshTokenStore>,
+    secret_manager: Arc<dyn SecretManager>,
+    rest_config: Arc<BiomeRestConfig>,
+) -> Resource {
+    Resource::build("/biome/sessions")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_SESSIONS_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(
+            Method::Get,
+            handle_list(
+                refresh_token_store.clone(),
+                secret_manager.clone(),
+                rest_config.clone(),
+            ),
+        )
+        .add_method(
+            Method::Delete,
+            handle_revoke_all(refresh_token_store, secret_manager, rest_config),
+        )
+}
+
+/// Defines a REST endpoint for revoking a single session of a user
+pub fn make_session_route(
+    refresh_token_store: Arc<dyn RefreshTokenStore>,
+    secret_manager: Arc<dyn SecretManager>,
+    rest_config: Arc<BiomeRestConfig>,
+) -> Resource {
+    Resource::build("/biome/sessions/{session_id}")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_SESSIONS_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(
+            Method::Delete,
+            handle_revoke(refresh_token_store, secret_manager, rest_config),
+        )
+}
+
+/// Defines a REST endpoint method to list a user's sessions, marking the one the request was made
+/// with as current
+fn handle_list(
+    refresh_token_store: Arc<dyn RefreshTokenStore>,
+    secret_manager: Arc<dyn SecretManager>,
+    rest_config: Arc<BiomeRestConfig>,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let validation = default_validation(&rest_config.issuer());
+
+        let claims = match authorize_user(&request, &secret_manager, &validation) {
+            AuthorizationResult::Authorized(claims) => claims,
+            AuthorizationResult::Unauthorized(msg) => {
+                return Box::new(
+                    HttpResponse::Unauthorized()
+                        .json(ErrorResponse::unauthorized(&msg))
+                        .into_future(),
+                )
+            }
+            AuthorizationResult::Failed => {
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        };
+        let current_session_id = session_id(&claims);
+
+        match refresh_token_store.list_sessions(&claims.user_id()) {
+            Ok(sessions) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({
+                        "data": sessions
+                            .iter()
+                            .map(|session| {
+                                ResponseSession::new(session, current_session_id.as_deref())
+                            })
+                            .collect::<Vec<ResponseSession>>()
+                    }))
+                    .into_future(),
+            ),
+            Err(err) => {
+                error!("Failed to list sessions: {}", err);
+                Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                )
+            }
+        }
+    })
+}
+
+/// Defines a REST endpoint method to revoke one of a user's sessions
+fn handle_revoke(
+    refresh_token_store: Arc<dyn RefreshTokenStore>,
+    secret_manager: Arc<dyn SecretManager>,
+    rest_config: Arc<BiomeRestConfig>,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let validation = default_validation(&rest_config.issuer());
+
+        let user_id = match authorize_user(&request, &secret_manager, &validation) {
+            AuthorizationResult::Authorized(claims) => claims.user_id(),
+            AuthorizationResult::Unauthorized(msg) => {
+                return Box::new(
+                    HttpResponse::Unauthorized()
+                        .json(ErrorResponse::unauthorized(&msg))
+                        .into_future(),
+                )
+            }
+            AuthorizationResult::Failed => {
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        };
+
+        let session_id = match request.match_info().get("session_id") {
+            Some(id) => id.to_owned(),
+            None => {
+                error!("Session ID is not in path request");
+                return Box::new(
+                    HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(
+                            "Failed to process request: no session ID",
+                        ))
+                        .into_future(),
+                );
+            }
+        };
+
+        // A session belonging to another user is reported as not found, so that session IDs
+        // cannot be probed
+        match refresh_token_store.fetch_session(&session_id) {
+            Ok((session, _)) if session.user_id == user_id => (),
+            Ok(_) | Err(RefreshTokenError::NotFoundError(_)) => {
+                return Box::new(
+                    HttpResponse::NotFound()
+                        .json(ErrorResponse::not_found("Session not found"))
+                        .into_future(),
+                )
+            }
+            Err(err) => {
+                error!("Failed to fetch session: {}", err);
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        }
+
+        match refresh_token_store.revoke_session(&session_id, revoked_until(&rest_config)) {
+            Ok(()) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({ "message": "Session successfully revoked" }))
+                    .into_future(),
+            ),
+            Err(RefreshTokenError::NotFoundError(_)) => Box::new(
+                HttpResponse::NotFound()
+                    .json(ErrorResponse::not_found("Session not found"))
+                    .into_future(),
+            ),
+            Err(err) => {
+                error!("Failed to revoke session: {}", err);
+                Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                )
+            }
+        }
+    })
+}
+
+/// Defines a REST endpoint method to revoke all of a user's sessions, including the one the
+/// request was made with
+fn handle_revoke_all(
+    refresh_token_store: Arc<dyn RefreshTokenStore>,
+    secret_manager: Arc<dyn SecretManager>,
+    rest_config: Arc<BiomeRestConfig>,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        let validation = default_validation(&rest_config.issuer());
+
+        let user_id = match authorize_user(&request, &secret_manager, &validation) {
+            AuthorizationResult::Authorized(claims) => claims.user_id(),
+            AuthorizationResult::Unauthorized(msg) => {
+                return Box::new(
+                    HttpResponse::Unauthorized()
+                        .json(ErrorResponse::unauthorized(&msg))
+                        .into_future(),
+                )
+            }
+            AuthorizationResult::Failed => {
+                return Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                );
+            }
+        };
+
+        match revoke_all_sessions(&*refresh_token_store, &user_id, revoked_until(&rest_config)) {
+            Ok(()) => Box::new(
+                HttpResponse::Ok()
+                    .json(json!({ "message": "All sessions successfully revoked" }))
+                    .into_future(),
+            ),
+            Err(err) => {
+                error!("Failed to revoke sessions: {}", err);
+                Box::new(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                )
+            }
+        }
+    })
+}
+
+/// Guards requests by rejecting access tokens that were issued for a revoked session.
+///
+/// Requests without a valid access token, or with one that was not issued for a session, are left
+/// for the endpoint to handle. The guard is meant to be added to the whole REST API with
+/// `RestApiBuilder::add_request_guard`, so that a revoked session loses access to every endpoint
+/// that accepts Biome access tokens.
+#[derive(Clone)]
+pub struct SessionRevocationGuard {
+    refresh_token_store: Arc<dyn RefreshTokenStore>,
+    secret_manager: Arc<dyn SecretManager>,
+    rest_config: Arc<BiomeRestConfig>,
+}
+
+impl SessionRevocationGuard {
+    pub fn new(
+        refresh_token_store: Arc<dyn RefreshTokenStore>,
+        secret_manager: Arc<dyn SecretManager>,
+        rest_config: Arc<BiomeRestConfig>,
+    ) -> Self {
+        Self {
+            refresh_token_store,
+            secret_manager,
+            rest_config,
+        }
+    }
+}
+
+impl RequestGuard for SessionRevocationGuard {
+    fn evaluate(&self, request: &HttpRequest) -> Continuation {
+        let token = match get_authorization_token(request) {
+            Ok(token) => token,
+            Err(_) => return Continuation::Continue,
+        };
+
+        // Expired tokens are still checked, since they may be presented to refresh the session
+        let validation = ignore_exp_validation(&self.rest_config.issuer());
+        let claims = match validate_claims(&token, &self.secret_manager, &validation) {
+            AuthorizationResult::Authorized(claims) => claims,
+            _ => return Continuation::Continue,
+        };
+        let session_id = match session_id(&claims) {
+            Some(session_id) => session_id,
+            None => return Continuation::Continue,
+        };
+
+        match self.refresh_token_store.is_session_revoked(&session_id) {
+            Ok(false) => Continuation::Continue,
+            Ok(true) => {
+                log_auth_failure(
+                    request,
+                    None,
+                    Some(&claims.user_id()),
+                    "Revoked session",
+                    None,
+                );
+                Continuation::terminate(
+                    HttpResponse::Unauthorized()
+                        .json(ErrorResponse::unauthorized("Session has been revoked"))
+                        .into_future(),
+                )
+            }
+            Err(err) => {
+                error!("Failed to check session revocation: {}", err);
+                Continuation::terminate(
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future(),
+                )
+            }
+        }
+    }
+}
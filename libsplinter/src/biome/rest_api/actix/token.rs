@@ -13,8 +13,12 @@
 // limitations under the License.
 
 use std::sync::Arc;
+#[cfg(feature = "biome-sessions")]
+use std::time::SystemTime;
 
 use crate::actix_web::HttpResponse;
+#[cfg(feature = "biome-user-admin")]
+use crate::biome::user::store::UserStore;
 use crate::biome::{
     refresh_tokens::store::{RefreshTokenError, RefreshTokenStore},
     rest_api::{
@@ -34,6 +38,11 @@ use crate::rest_api::{
     ErrorResponse, Method, ProtocolVersionRangeGuard, Resource,
 };
 
+#[cfg(feature = "biome-user-admin")]
+use super::login::disabled_response;
+#[cfg(feature = "biome-sessions")]
+use super::sessions::{revoked_until, session_id, SESSION_ID_CLAIM};
+
 /// Defines a REST endpoint for requesting a new authorization token
 ///
 /// The payload should be in the JSON format:
@@ -45,12 +54,18 @@ use crate::rest_api::{
 ///   {
 ///     "token": <new auth token>
 ///   }
+///
+/// If the access token was issued for a session, the refresh token must be the one issued for that
+/// session, and the new auth token is issued for the same session.
+///
+/// If the user has been disabled, the request is refused with a `403 Forbidden` response.
 pub fn make_token_route(
     refresh_token_store: Arc<dyn RefreshTokenStore>,
     secret_manager: Arc<dyn SecretManager>,
     refresh_token_secret_manager: Arc<dyn SecretManager>,
     token_issuer: Arc<AccessTokenIssuer>,
     rest_config: Arc<BiomeRestConfig>,
+    #[cfg(feature = "biome-user-admin")] user_store: Arc<dyn UserStore>,
 ) -> Resource {
     Resource::build("/biome/token")
         .add_request_guard(ProtocolVersionRangeGuard::new(
@@ -65,6 +80,8 @@ pub fn make_token_route(
             let refresh_token_store = refresh_token_store.clone();
             let token_issuer = token_issuer.clone();
             let rest_config = rest_config.clone();
+            #[cfg(feature = "biome-user-admin")]
+            let user_store = user_store.clone();
             Box::new(into_bytes(payload).and_then(move |bytes| {
                 let claims = match authorize_user(&req, &secret_manager, &validation) {
                     AuthorizationResult::Authorized(claims) => claims,
@@ -93,8 +110,29 @@ pub fn make_token_route(
                     }
                 };
 
-                let refresh_token_from_db = match refresh_token_store.fetch_token(&claims.user_id())
-                {
+                #[cfg(feature = "biome-sessions")]
+                let session_id = session_id(&claims);
+
+                #[cfg(feature = "biome-sessions")]
+                let stored_token = match &session_id {
+                    Some(session_id) => refresh_token_store.fetch_session(session_id).and_then(
+                        |(session, token)| {
+                            if session.user_id == claims.user_id() {
+                                Ok(token)
+                            } else {
+                                Err(RefreshTokenError::NotFoundError(format!(
+                                    "Session {} not found",
+                                    session_id
+                                )))
+                            }
+                        },
+                    ),
+                    None => refresh_token_store.fetch_token(&claims.user_id()),
+                };
+                #[cfg(not(feature = "biome-sessions"))]
+                let stored_token = refresh_token_store.fetch_token(&claims.user_id());
+
+                let refresh_token_from_db = match stored_token {
                     Ok(token) => token,
                     Err(RefreshTokenError::NotFoundError(msg)) => {
                         return HttpResponse::Forbidden()
@@ -122,7 +160,15 @@ pub fn make_token_route(
                 ) {
                     AuthorizationResult::Authorized(_) => (),
                     AuthorizationResult::Unauthorized(msg) => {
-                        if let Err(err) = refresh_token_store.remove_token(&claims.user_id()) {
+                        #[cfg(feature = "biome-sessions")]
+                        let result = match &session_id {
+                            Some(session_id) => refresh_token_store
+                                .revoke_session(session_id, revoked_until(&rest_config)),
+                            None => refresh_token_store.remove_token(&claims.user_id()),
+                        };
+                        #[cfg(not(feature = "biome-sessions"))]
+                        let result = refresh_token_store.remove_token(&claims.user_id());
+                        if let Err(err) = result {
                             error!("Failed to delete refresh token {}", err);
                             return HttpResponse::InternalServerError()
                                 .json(ErrorResponse::internal_error())
@@ -139,13 +185,42 @@ pub fn make_token_route(
                             .into_future();
                     }
                 }
-                let claim_builder = ClaimsBuilder::default();
-                let claim = match claim_builder
+
+                #[cfg(feature = "biome-user-admin")]
+                {
+                    if let Some(response) =
+                        disabled_response(&*user_store, &req, None, &claims.user_id())
+                    {
+                        return response.into_future();
+                    }
+                }
+
+                #[cfg(feature = "biome-sessions")]
+                {
+                    if let Some(session_id) = &session_id {
+                        if let Err(err) = refresh_token_store
+                            .update_session_last_used(session_id, SystemTime::now())
+                        {
+                            error!("Failed to update session {}", err);
+                            return HttpResponse::InternalServerError()
+                                .json(ErrorResponse::internal_error())
+                                .into_future();
+                        }
+                    }
+                }
+
+                let claim_builder = ClaimsBuilder::default()
                     .with_user_id(&claims.user_id())
                     .with_issuer(&rest_config.issuer())
-                    .with_duration(rest_config.access_token_duration())
-                    .build()
-                {
+                    .with_duration(rest_config.access_token_duration());
+                #[cfg(feature = "biome-sessions")]
+                let claim_builder = match &session_id {
+                    Some(session_id) => {
+                        claim_builder.with_custom_claim(SESSION_ID_CLAIM, session_id)
+                    }
+                    None => claim_builder,
+                };
+                let claim = match claim_builder.build() {
                     Ok(claim) => claim,
                     Err(err) => {
                         error!("Failed to build claim {}", err);
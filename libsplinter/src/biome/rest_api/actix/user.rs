@@ -12,9 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "biome-user-admin")]
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::actix_web::HttpResponse;
+#[cfg(feature = "biome-user-admin")]
+use crate::actix_web::{web, HttpRequest};
 use crate::biome::credentials::store::{
     CredentialsBuilder, CredentialsStore, CredentialsStoreError,
 };
@@ -33,6 +37,10 @@ use crate::biome::key_management::{
     store::{KeyStore, KeyStoreError},
     Key,
 };
+#[cfg(feature = "biome-user-admin")]
+use crate::biome::rest_api::resources::user_admin::{ListUsersResponse, ResponseUser};
+#[cfg(feature = "biome-user-admin")]
+use crate::rest_api::paging::{get_response_paging_info, DEFAULT_LIMIT, DEFAULT_OFFSET};
 use crate::rest_api::secrets::SecretManager;
 
 use crate::biome::rest_api::actix::authorize::authorize_user;
@@ -40,18 +48,243 @@ use crate::biome::rest_api::actix::authorize::authorize_user;
 use crate::biome::rest_api::resources::{key_management::ResponseKey, user::ModifyUser};
 
 /// Defines a REST endpoint to list users from the db
-pub fn make_list_route(credentials_store: Arc<dyn CredentialsStore>) -> Resource {
+///
+/// With the `biome-user-admin` feature, users are listed along with their account details, one
+/// page at a time. The following query parameters are supported:
+///
+/// * `filter` - only list users whose username contains the value, ignoring case
+/// * `sort` - order users by `username` (the default), `created` or `last_login`; the value may
+///   be prefixed with `-` for descending order
+/// * `offset` and `limit` - select the page of users to return
+pub fn make_list_route(
+    credentials_store: Arc<dyn CredentialsStore>,
+    #[cfg(feature = "biome-user-admin")] user_store: Arc<dyn UserStore>,
+) -> Resource {
     Resource::build("/biome/users")
         .add_request_guard(ProtocolVersionRangeGuard::new(
             protocol::BIOME_LIST_USERS_PROTOCOL_MIN,
             protocol::BIOME_PROTOCOL_VERSION,
         ))
-        .add_method(Method::Get, move |_, _| {
-            let credentials_store = credentials_store.clone();
-            Box::new(match credentials_store.list_usernames() {
-                Ok(users) => HttpResponse::Ok().json(users).into_future(),
+        .add_method(
+            Method::Get,
+            add_list_users_method(
+                credentials_store,
+                #[cfg(feature = "biome-user-admin")]
+                user_store,
+            ),
+        )
+}
+
+/// Defines a REST endpoint to list the IDs and usernames of all users
+#[cfg(not(feature = "biome-user-admin"))]
+fn add_list_users_method(credentials_store: Arc<dyn CredentialsStore>) -> HandlerFunction {
+    Box::new(move |_, _| {
+        let credentials_store = credentials_store.clone();
+        Box::new(match credentials_store.list_usernames() {
+            Ok(users) => HttpResponse::Ok().json(users).into_future(),
+            Err(err) => {
+                debug!("Failed to get users from the database {}", err);
+                HttpResponse::InternalServerError()
+                    .json(ErrorResponse::internal_error())
+                    .into_future()
+            }
+        })
+    })
+}
+
+/// Defines a REST endpoint to list a page of users along with their account details
+#[cfg(feature = "biome-user-admin")]
+fn add_list_users_method(
+    credentials_store: Arc<dyn CredentialsStore>,
+    user_store: Arc<dyn UserStore>,
+) -> HandlerFunction {
+    Box::new(move |request, _| {
+        Box::new(list_users(&request, &*credentials_store, &*user_store).into_future())
+    })
+}
+
+/// Lists a page of users, filtered and sorted as specified by the request's query
+#[cfg(feature = "biome-user-admin")]
+fn list_users(
+    request: &HttpRequest,
+    credentials_store: &dyn CredentialsStore,
+    user_store: &dyn UserStore,
+) -> HttpResponse {
+    let query: web::Query<HashMap<String, String>> =
+        match web::Query::from_query(request.query_string()) {
+            Ok(query) => query,
+            Err(_) => {
+                return HttpResponse::BadRequest().json(ErrorResponse::bad_request("Invalid query"))
+            }
+        };
+
+    let offset = match query.get("offset") {
+        Some(value) => match value.parse::<usize>() {
+            Ok(val) => val,
+            Err(err) => {
+                return HttpResponse::BadRequest().json(ErrorResponse::bad_request(&format!(
+                    "Invalid offset value passed: {}. Error: {}",
+                    value, err
+                )))
+            }
+        },
+        None => DEFAULT_OFFSET,
+    };
+
+    let limit = match query.get("limit") {
+        Some(value) => match value.parse::<usize>() {
+            Ok(0) => {
+                return HttpResponse::BadRequest().json(ErrorResponse::bad_request(&format!(
+                    "Invalid limit value passed: {}. Error: limit must be greater than zero",
+                    value
+                )))
+            }
+            Ok(val) => val,
+            Err(err) => {
+                return HttpResponse::BadRequest().json(ErrorResponse::bad_request(&format!(
+                    "Invalid limit value passed: {}. Error: {}",
+                    value, err
+                )))
+            }
+        },
+        None => DEFAULT_LIMIT,
+    };
+
+    let mut link = format!("{}?", request.uri().path());
+
+    let filter = query.get("filter").map(|value| {
+        link.push_str(&format!("filter={}&", value));
+        value.to_lowercase()
+    });
+
+    let sort = match query.get("sort") {
+        Some(value) => {
+            link.push_str(&format!("sort={}&", value));
+            value.as_str()
+        }
+        None => "username",
+    };
+    let (sort_field, descending) = match sort.strip_prefix('-') {
+        Some(sort_field) => (sort_field, true),
+        None => (sort, false),
+    };
+    match sort_field {
+        "username" | "created" | "last_login" => (),
+        _ => {
+            return HttpResponse::BadRequest().json(ErrorResponse::bad_request(&format!(
+                "Invalid sort value passed: {}",
+                sort
+            )))
+        }
+    }
+
+    let usernames = match credentials_store.list_usernames() {
+        Ok(usernames) => usernames
+            .into_iter()
+            .map(|username_id| (username_id.user_id, username_id.username))
+            .collect::<HashMap<_, _>>(),
+        Err(err) => {
+            debug!("Failed to get usernames from the database {}", err);
+            return HttpResponse::InternalServerError().json(ErrorResponse::internal_error());
+        }
+    };
+
+    let users = match user_store.list_users() {
+        Ok(users) => users,
+        Err(err) => {
+            debug!("Failed to get users from the database {}", err);
+            return HttpResponse::InternalServerError().json(ErrorResponse::internal_error());
+        }
+    };
+
+    let mut users = users
+        .into_iter()
+        .map(|user| {
+            let username = usernames.get(&user.id()).map(String::as_str);
+            (user, username)
+        })
+        .filter(|(_, username)| match (&filter, username) {
+            (Some(filter), Some(username)) => username.to_lowercase().contains(filter.as_str()),
+            (Some(_), None) => false,
+            (None, _) => true,
+        })
+        .collect::<Vec<_>>();
+
+    users.sort_by(|(user_a, username_a), (user_b, username_b)| {
+        let order = match sort_field {
+            "created" => user_a.created().cmp(&user_b.created()),
+            "last_login" => user_a.last_login().cmp(&user_b.last_login()),
+            _ => username_a.cmp(username_b),
+        };
+        // Users that are otherwise equal are ordered by ID, so that pages are stable
+        order.then_with(|| user_a.id().cmp(&user_b.id()))
+    });
+    if descending {
+        users.reverse();
+    }
+
+    let total = users.len();
+    let data = users
+        .iter()
+        .skip(offset)
+        .take(limit)
+        .map(|(user, username)| ResponseUser::new(user, *username))
+        .collect();
+
+    HttpResponse::Ok().json(ListUsersResponse {
+        data,
+        paging: get_response_paging_info(Some(limit), Some(offset), &link, total),
+    })
+}
+
+/// Defines the `/biome/users/{id}/enable` or `/biome/users/{id}/disable` REST resource for
+/// enabling or disabling a user
+///
+/// A disabled user cannot log in or exchange a refresh token for a new access token.
+#[cfg(feature = "biome-user-admin")]
+pub fn make_user_enabled_route(user_store: Arc<dyn UserStore>, enabled: bool) -> Resource {
+    let path = if enabled {
+        "/biome/users/{id}/enable"
+    } else {
+        "/biome/users/{id}/disable"
+    };
+    Resource::build(path)
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::BIOME_USER_ADMIN_PROTOCOL_MIN,
+            protocol::BIOME_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Put, move |request, _| {
+            let user_id = if let Some(t) = request.match_info().get("id") {
+                t.to_string()
+            } else {
+                return Box::new(
+                    HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request(
+                            &"Failed to process request: no user id".to_string(),
+                        ))
+                        .into_future(),
+                );
+            };
+
+            Box::new(match user_store.set_user_enabled(&user_id, enabled) {
+                Ok(()) => {
+                    let action = if enabled { "enabled" } else { "disabled" };
+                    info!("User {} {}", user_id, action);
+                    HttpResponse::Ok()
+                        .json(json!({ "message": format!("User {} successfully", action) }))
+                        .into_future()
+                }
+                Err(UserStoreError::NotFoundError(msg)) => {
+                    debug!("User not found: {}", msg);
+                    HttpResponse::NotFound()
+                        .json(ErrorResponse::not_found(&format!(
+                            "User ID not found: {}",
+                            &user_id
+                        )))
+                        .into_future()
+                }
                 Err(err) => {
-                    debug!("Failed to get users from the database {}", err);
+                    error!("Failed to update user in database {}", err);
                     HttpResponse::InternalServerError()
                         .json(ErrorResponse::internal_error())
                         .into_future()
@@ -291,6 +524,9 @@ fn add_modify_user_method(
 }
 
 /// Defines a REST endpoint to delete a user from the database
+///
+/// With role based authorization, the user with the ID in the path is deleted, as permitted by
+/// the resource's `PermissionGuard`; otherwise the authorized user is deleted.
 fn add_delete_user_method(
     rest_config: Arc<BiomeRestConfig>,
     secret_manager: Arc<dyn SecretManager>,
@@ -299,7 +535,7 @@ fn add_delete_user_method(
     Box::new(move |request, _| {
         let user_store = user_store.clone();
         let validation = default_validation(&rest_config.issuer());
-        let user_id = match authorize_user(&request, &secret_manager, &validation) {
+        let authorized_user_id = match authorize_user(&request, &secret_manager, &validation) {
             AuthorizationResult::Authorized(claims) => claims.user_id(),
             AuthorizationResult::Unauthorized(msg) => {
                 return Box::new(
@@ -316,6 +552,13 @@ fn add_delete_user_method(
                 );
             }
         };
+        #[cfg(feature = "biome-authorization")]
+        let user_id = match request.match_info().get("id") {
+            Some(id) => id.to_string(),
+            None => authorized_user_id,
+        };
+        #[cfg(not(feature = "biome-authorization"))]
+        let user_id = authorized_user_id;
 
         Box::new(match user_store.remove_user(&user_id) {
             Ok(()) => HttpResponse::Ok()
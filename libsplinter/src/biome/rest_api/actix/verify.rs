@@ -27,6 +27,7 @@ use crate::biome::rest_api::config::BiomeRestConfig;
 
 use super::super::resources::authorize::AuthorizationResult;
 use super::super::resources::credentials::UsernamePassword;
+use super::audit::log_auth_failure;
 use super::authorize::authorize_user;
 
 /// Defines a REST endpoint to verify a user's password
@@ -100,9 +101,18 @@ pub fn make_verify_route(
                                         "user_id": credentials.user_id
                                 }))
                                 .into_future(),
-                            Ok(false) => HttpResponse::BadRequest()
-                                .json(ErrorResponse::bad_request("Invalid password"))
-                                .into_future(),
+                            Ok(false) => {
+                                log_auth_failure(
+                                    &request,
+                                    Some(&credentials.username),
+                                    Some(&credentials.user_id),
+                                    "Invalid password",
+                                    None,
+                                );
+                                HttpResponse::BadRequest()
+                                    .json(ErrorResponse::bad_request("Invalid password"))
+                                    .into_future()
+                            }
                             Err(err) => {
                                 error!("Failed to verify password: {}", err);
                                 HttpResponse::InternalServerError()
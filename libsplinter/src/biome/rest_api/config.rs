@@ -22,6 +22,18 @@ const DEFAULT_ISSUER: &str = "self-issued";
 const DEFAULT_DURATION: u64 = 5400; // in seconds = 90 minutes
 #[cfg(feature = "biome-credentials")]
 const DEFAULT_REFRESH_DURATION: u64 = 5_184_000; // in seconds = 60 days
+#[cfg(feature = "biome-lockout")]
+const DEFAULT_LOCKOUT_THRESHOLD: u32 = 5;
+#[cfg(feature = "biome-lockout")]
+const DEFAULT_LOCKOUT_DURATION: u64 = 900; // in seconds = 15 minutes
+#[cfg(feature = "biome-lockout")]
+const DEFAULT_CLIENT_LOCKOUT_THRESHOLD: u32 = 20;
+#[cfg(feature = "biome-mfa")]
+const DEFAULT_MFA_CHALLENGE_DURATION: u64 = 300; // in seconds = 5 minutes
+#[cfg(feature = "biome-password-reset")]
+const DEFAULT_PASSWORD_RESET_DURATION: u64 = 3600; // in seconds = 1 hour
+#[cfg(feature = "biome-password-reset")]
+const DEFAULT_PASSWORD_RESET_LIMIT: u64 = 3;
 
 /// Configuration for Biome REST resources
 #[derive(Deserialize, Debug)]
@@ -36,6 +48,24 @@ pub struct BiomeRestConfig {
     #[cfg(feature = "biome-credentials")]
     /// Cost for encrypting user's password
     password_encryption_cost: PasswordEncryptionCost,
+    /// Duration of password reset tokens issued by this service
+    #[cfg(feature = "biome-password-reset")]
+    password_reset_token_duration: Duration,
+    /// Maximum number of password reset tokens a user may be issued within the token duration
+    #[cfg(feature = "biome-password-reset")]
+    password_reset_limit: u64,
+    /// Duration of the MFA challenge tokens issued when a user with MFA enabled logs in
+    #[cfg(feature = "biome-mfa")]
+    mfa_challenge_duration: Duration,
+    /// Number of consecutive failed login attempts after which a user is locked out
+    #[cfg(feature = "biome-lockout")]
+    lockout_threshold: u32,
+    /// Duration of a lockout, and of how long failed login attempts are remembered
+    #[cfg(feature = "biome-lockout")]
+    lockout_duration: Duration,
+    /// Number of consecutive failed login attempts after which a client IP address is locked out
+    #[cfg(feature = "biome-lockout")]
+    client_lockout_threshold: u32,
 }
 
 impl BiomeRestConfig {
@@ -64,6 +94,48 @@ impl BiomeRestConfig {
     pub fn password_encryption_cost(&self) -> PasswordEncryptionCost {
         self.password_encryption_cost
     }
+
+    /// Returns the duration that a password reset token is valid.
+    /// Defaults to 1 hour.
+    #[cfg(feature = "biome-password-reset")]
+    pub fn password_reset_token_duration(&self) -> Duration {
+        self.password_reset_token_duration
+    }
+
+    /// Returns the maximum number of password reset tokens that may be issued to a user within
+    /// the password reset token duration. Defaults to 3.
+    #[cfg(feature = "biome-password-reset")]
+    pub fn password_reset_limit(&self) -> u64 {
+        self.password_reset_limit
+    }
+
+    /// Returns the duration that an MFA challenge token is valid, during which the user must
+    /// provide a TOTP or recovery code to complete a login. Defaults to 5 minutes.
+    #[cfg(feature = "biome-mfa")]
+    pub fn mfa_challenge_duration(&self) -> Duration {
+        self.mfa_challenge_duration
+    }
+
+    /// Returns the number of consecutive failed login attempts after which a user is locked out.
+    /// Defaults to 5.
+    #[cfg(feature = "biome-lockout")]
+    pub fn lockout_threshold(&self) -> u32 {
+        self.lockout_threshold
+    }
+
+    /// Returns the duration of a lockout; failed login attempts older than this are forgotten.
+    /// Defaults to 15 minutes.
+    #[cfg(feature = "biome-lockout")]
+    pub fn lockout_duration(&self) -> Duration {
+        self.lockout_duration
+    }
+
+    /// Returns the number of consecutive failed login attempts, for any user, after which a
+    /// client IP address is locked out. Defaults to 20.
+    #[cfg(feature = "biome-lockout")]
+    pub fn client_lockout_threshold(&self) -> u32 {
+        self.client_lockout_threshold
+    }
 }
 
 /// Builder for BiomeRestConfig
@@ -74,6 +146,18 @@ pub struct BiomeRestConfigBuilder {
     refresh_token_duration: Option<Duration>,
     #[cfg(feature = "biome-credentials")]
     password_encryption_cost: Option<String>,
+    #[cfg(feature = "biome-password-reset")]
+    password_reset_token_duration: Option<Duration>,
+    #[cfg(feature = "biome-password-reset")]
+    password_reset_limit: Option<u64>,
+    #[cfg(feature = "biome-mfa")]
+    mfa_challenge_duration: Option<Duration>,
+    #[cfg(feature = "biome-lockout")]
+    lockout_threshold: Option<u32>,
+    #[cfg(feature = "biome-lockout")]
+    lockout_duration: Option<Duration>,
+    #[cfg(feature = "biome-lockout")]
+    client_lockout_threshold: Option<u32>,
 }
 
 impl Default for BiomeRestConfigBuilder {
@@ -85,6 +169,20 @@ impl Default for BiomeRestConfigBuilder {
             refresh_token_duration: Some(Duration::from_secs(DEFAULT_REFRESH_DURATION)),
             #[cfg(feature = "biome-credentials")]
             password_encryption_cost: Some("high".to_string()),
+            #[cfg(feature = "biome-password-reset")]
+            password_reset_token_duration: Some(Duration::from_secs(
+                DEFAULT_PASSWORD_RESET_DURATION,
+            )),
+            #[cfg(feature = "biome-password-reset")]
+            password_reset_limit: Some(DEFAULT_PASSWORD_RESET_LIMIT),
+            #[cfg(feature = "biome-mfa")]
+            mfa_challenge_duration: Some(Duration::from_secs(DEFAULT_MFA_CHALLENGE_DURATION)),
+            #[cfg(feature = "biome-lockout")]
+            lockout_threshold: Some(DEFAULT_LOCKOUT_THRESHOLD),
+            #[cfg(feature = "biome-lockout")]
+            lockout_duration: Some(Duration::from_secs(DEFAULT_LOCKOUT_DURATION)),
+            #[cfg(feature = "biome-lockout")]
+            client_lockout_threshold: Some(DEFAULT_CLIENT_LOCKOUT_THRESHOLD),
         }
     }
 }
@@ -99,6 +197,18 @@ impl BiomeRestConfigBuilder {
             refresh_token_duration: None,
             #[cfg(feature = "biome-credentials")]
             password_encryption_cost: None,
+            #[cfg(feature = "biome-password-reset")]
+            password_reset_token_duration: None,
+            #[cfg(feature = "biome-password-reset")]
+            password_reset_limit: None,
+            #[cfg(feature = "biome-mfa")]
+            mfa_challenge_duration: None,
+            #[cfg(feature = "biome-lockout")]
+            lockout_threshold: None,
+            #[cfg(feature = "biome-lockout")]
+            lockout_duration: None,
+            #[cfg(feature = "biome-lockout")]
+            client_lockout_threshold: None,
         }
     }
 
@@ -129,6 +239,51 @@ impl BiomeRestConfigBuilder {
         self
     }
 
+    /// Adds a password reset token duration in seconds.
+    #[cfg(feature = "biome-password-reset")]
+    pub fn with_password_reset_token_duration_in_secs(mut self, duration: u64) -> Self {
+        self.password_reset_token_duration = Some(Duration::from_secs(duration));
+        self
+    }
+
+    /// Adds the maximum number of password reset tokens that may be issued to a user within the
+    /// password reset token duration.
+    #[cfg(feature = "biome-password-reset")]
+    pub fn with_password_reset_limit(mut self, limit: u64) -> Self {
+        self.password_reset_limit = Some(limit);
+        self
+    }
+
+    /// Adds an MFA challenge token duration in seconds.
+    #[cfg(feature = "biome-mfa")]
+    pub fn with_mfa_challenge_duration_in_secs(mut self, duration: u64) -> Self {
+        self.mfa_challenge_duration = Some(Duration::from_secs(duration));
+        self
+    }
+
+    /// Adds the number of consecutive failed login attempts after which a user is locked out;
+    /// `0` disables the lockout, leaving only the delay between attempts.
+    #[cfg(feature = "biome-lockout")]
+    pub fn with_lockout_threshold(mut self, threshold: u32) -> Self {
+        self.lockout_threshold = Some(threshold);
+        self
+    }
+
+    /// Adds a lockout duration in seconds.
+    #[cfg(feature = "biome-lockout")]
+    pub fn with_lockout_duration_in_secs(mut self, duration: u64) -> Self {
+        self.lockout_duration = Some(Duration::from_secs(duration));
+        self
+    }
+
+    /// Adds the number of consecutive failed login attempts after which a client IP address is
+    /// locked out; `0` disables the lockout, leaving only the delay between attempts.
+    #[cfg(feature = "biome-lockout")]
+    pub fn with_client_lockout_threshold(mut self, threshold: u32) -> Self {
+        self.client_lockout_threshold = Some(threshold);
+        self
+    }
+
     /// Creates a new BiomeRestConfig.
     pub fn build(self) -> Result<BiomeRestConfig, BiomeRestConfigBuilderError> {
         let issuer = self.issuer.unwrap_or_else(|| {
@@ -152,6 +307,29 @@ impl BiomeRestConfigBuilder {
             .parse()
             .map_err(BiomeRestConfigBuilderError::InvalidValue)?;
 
+        #[cfg(feature = "biome-password-reset")]
+        let password_reset_token_duration = self
+            .password_reset_token_duration
+            .unwrap_or_else(|| Duration::from_secs(DEFAULT_PASSWORD_RESET_DURATION));
+        #[cfg(feature = "biome-password-reset")]
+        let password_reset_limit = self
+            .password_reset_limit
+            .unwrap_or(DEFAULT_PASSWORD_RESET_LIMIT);
+        #[cfg(feature = "biome-mfa")]
+        let mfa_challenge_duration = self
+            .mfa_challenge_duration
+            .unwrap_or_else(|| Duration::from_secs(DEFAULT_MFA_CHALLENGE_DURATION));
+        #[cfg(feature = "biome-lockout")]
+        let lockout_threshold = self.lockout_threshold.unwrap_or(DEFAULT_LOCKOUT_THRESHOLD);
+        #[cfg(feature = "biome-lockout")]
+        let lockout_duration = self
+            .lockout_duration
+            .unwrap_or_else(|| Duration::from_secs(DEFAULT_LOCKOUT_DURATION));
+        #[cfg(feature = "biome-lockout")]
+        let client_lockout_threshold = self
+            .client_lockout_threshold
+            .unwrap_or(DEFAULT_CLIENT_LOCKOUT_THRESHOLD);
+
         Ok(BiomeRestConfig {
             issuer,
             access_token_duration,
@@ -159,6 +337,18 @@ impl BiomeRestConfigBuilder {
             refresh_token_duration,
             #[cfg(feature = "biome-credentials")]
             password_encryption_cost,
+            #[cfg(feature = "biome-password-reset")]
+            password_reset_token_duration,
+            #[cfg(feature = "biome-password-reset")]
+            password_reset_limit,
+            #[cfg(feature = "biome-mfa")]
+            mfa_challenge_duration,
+            #[cfg(feature = "biome-lockout")]
+            lockout_threshold,
+            #[cfg(feature = "biome-lockout")]
+            lockout_duration,
+            #[cfg(feature = "biome-lockout")]
+            client_lockout_threshold,
         })
     }
 }
@@ -37,6 +37,10 @@
 //!     .unwrap()
 //!     .run();
 //! ```
+//!
+//! With the `biome-sessions` feature, the guard returned by `session_revocation_guard` should be
+//! added to the `RestApiBuilder` with `add_request_guard`, so that access tokens issued for a
+//! revoked session are rejected by every endpoint.
 
 #[cfg(feature = "rest-api-actix")]
 mod actix;
@@ -54,24 +58,62 @@ use crate::rest_api::{Resource, RestResourceProvider};
 use self::actix::key_management::{
     make_key_management_route, make_key_management_route_with_public_key,
 };
+#[cfg(all(feature = "biome-notifications", feature = "rest-api-actix",))]
+use self::actix::notifications::{
+    make_notifications_route, make_notifications_route_with_notification_id,
+};
 
+#[cfg(feature = "biome-authorization")]
+use super::authorization::store::{GroupStore, RoleStore};
+#[cfg(all(feature = "biome-authorization", feature = "rest-api-actix"))]
+use super::authorization::Permission;
 #[cfg(feature = "biome-key-management")]
 use super::key_management::store::KeyStore;
+#[cfg(feature = "biome-notifications")]
+use super::notifications::store::NotificationStore;
 use super::user::store::UserStore;
 
-#[cfg(any(feature = "biome-key-management", feature = "biome-credentials",))]
+#[cfg(any(
+    feature = "biome-key-management",
+    feature = "biome-credentials",
+    feature = "biome-notifications",
+))]
 use crate::rest_api::secrets::AutoSecretManager;
 use crate::rest_api::secrets::SecretManager;
 
+#[cfg(all(feature = "biome-api-keys", feature = "rest-api-actix"))]
+pub use self::actix::api_keys::ApiKeyGuard;
+#[cfg(all(feature = "biome-authorization", feature = "rest-api-actix"))]
+pub use self::actix::authorization::PermissionGuard;
+#[cfg(all(feature = "biome-sessions", feature = "rest-api-actix"))]
+pub use self::actix::sessions::SessionRevocationGuard;
 pub use config::{BiomeRestConfig, BiomeRestConfigBuilder};
 pub use error::BiomeRestResourceManagerBuilderError;
 
+#[cfg(all(feature = "biome-api-keys", feature = "rest-api-actix"))]
+use self::actix::api_keys::{make_api_key_route, make_api_keys_route};
+#[cfg(all(feature = "biome-authorization", feature = "rest-api-actix"))]
+use self::actix::authorization::{
+    make_group_member_route, make_group_route, make_groups_route, make_role_group_route,
+    make_role_route, make_role_user_route, make_roles_route,
+};
+
 #[cfg(all(feature = "rest-api-actix", feature = "biome-credentials"))]
 use self::actix::logout::make_logout_route;
+#[cfg(all(feature = "biome-mfa", feature = "rest-api-actix"))]
+use self::actix::mfa::{make_mfa_login_route, make_mfa_route, make_mfa_verify_route};
+#[cfg(all(feature = "biome-oidc", feature = "rest-api-actix"))]
+use self::actix::oidc::{make_oidc_callback_route, make_oidc_login_route};
+#[cfg(all(feature = "biome-password-reset", feature = "rest-api-actix"))]
+use self::actix::password_reset::{make_password_reset_route, make_password_route};
 #[cfg(all(feature = "biome-credentials", feature = "rest-api-actix"))]
 use self::actix::register::make_register_route;
+#[cfg(all(feature = "biome-sessions", feature = "rest-api-actix"))]
+use self::actix::sessions::{make_session_route, make_sessions_route};
 #[cfg(all(feature = "biome-credentials", feature = "rest-api-actix"))]
 use self::actix::token::make_token_route;
+#[cfg(all(feature = "biome-user-admin", feature = "rest-api-actix"))]
+use self::actix::user::make_user_enabled_route;
 #[cfg(all(
     feature = "biome-credentials",
     feature = "biome-key-management",
@@ -80,8 +122,16 @@ use self::actix::token::make_token_route;
 use self::actix::user::make_user_routes;
 #[cfg(all(feature = "biome-credentials", feature = "rest-api-actix",))]
 use self::actix::{login::make_login_route, user::make_list_route, verify::make_verify_route};
+#[cfg(all(feature = "biome-api-keys", feature = "rest-api-actix"))]
+use super::credentials::api_keys::ApiKeyScope;
+#[cfg(feature = "biome-lockout")]
+use super::credentials::lockout::{LockoutPolicy, LoginThrottle};
+#[cfg(feature = "biome-password-reset")]
+use super::credentials::password_reset::{LogPasswordResetNotifier, PasswordResetNotifier};
 #[cfg(feature = "biome-credentials")]
 use super::credentials::store::CredentialsStore;
+#[cfg(feature = "biome-oidc")]
+use super::oidc::OidcClient;
 
 #[allow(unused_imports)]
 use crate::rest_api::sessions::AccessTokenIssuer;
@@ -98,8 +148,15 @@ use crate::rest_api::sessions::AccessTokenIssuer;
 ///    `public_key`
 /// * `DELETE /biome/keys/{public_key}` - delete a  key for an authorized user that has
 ///    `public key`
-/// * `POST /biome/login` - Login enpoint for getting access tokens and refresh tokens
-/// * `PATCH /biome/logout` - Login endpoint for removing refresh tokens
+/// * `POST /biome/login` - Login enpoint for getting access tokens and refresh tokens, or an MFA
+///    challenge token if the user has enabled MFA
+/// * `POST /biome/login/mfa` - Completes a login with a TOTP or recovery code
+/// * `GET /biome/oidc/login` - Redirects to the OpenID Connect provider to log in, if a provider
+///    is configured
+/// * `GET /biome/oidc/callback` - Completes a login through the OpenID Connect provider, returning
+///    access tokens and refresh tokens
+/// * `PATCH /biome/logout` - Login endpoint for removing refresh tokens, or for revoking the
+///    current session
 /// * `POST /biome/register - Creates credentials for a user
 /// * `POST /biome/token` - Creates a new access token for the authorized user
 /// * `POST /biome/verify` - Verify a users password
@@ -108,14 +165,69 @@ use crate::rest_api::sessions::AccessTokenIssuer;
 /// * `PUT /biome/user/{id}` - Update user with specified ID
 /// * `GET /biome/user/{id}` - Retrieve user with specified ID
 /// * `DELETE /biome/user/{id}` - Remove user with specified ID
+/// * `PUT /biome/users/{id}/disable` - Disable the user with the specified ID, refusing their
+///    logins and token refreshes
+/// * `PUT /biome/users/{id}/enable` - Re-enable the user with the specified ID
+/// * `GET /biome/mfa` - Check whether the authorized user has enabled MFA
+/// * `POST /biome/mfa` - Start MFA enrollment for the authorized user
+/// * `DELETE /biome/mfa` - Disable MFA for the authorized user
+/// * `POST /biome/mfa/verify` - Complete MFA enrollment and get recovery codes
+/// * `PUT /biome/password` - Change the password of the authorized user
+/// * `POST /biome/password/reset` - Request a password reset token for a user
+/// * `PUT /biome/password/reset` - Reset a user's password using a password reset token
+/// * `GET /biome/api_keys` - Get all API keys for the authorized user
+/// * `POST /biome/api_keys` - Create a new scoped API key for the authorized user
+/// * `DELETE /biome/api_keys/{api_key_id}` - Revoke an API key for the authorized user
+/// * `GET /biome/roles` - Get all roles
+/// * `POST /biome/roles` - Create a new role
+/// * `GET /biome/roles/{role_name}` - Retrieve a role along with the users and groups it is
+///    assigned to
+/// * `DELETE /biome/roles/{role_name}` - Remove a role
+/// * `PUT /biome/roles/{role_name}/users/{user_id}` - Assign a role to a user
+/// * `DELETE /biome/roles/{role_name}/users/{user_id}` - Remove a role from a user
+/// * `PUT /biome/roles/{role_name}/groups/{group_name}` - Assign a role to a group
+/// * `DELETE /biome/roles/{role_name}/groups/{group_name}` - Remove a role from a group
+/// * `GET /biome/groups` - Get all groups along with their members
+/// * `POST /biome/groups` - Create a new group
+/// * `DELETE /biome/groups/{group_name}` - Remove a group
+/// * `PUT /biome/groups/{group_name}/members/{user_id}` - Add a user to a group
+/// * `DELETE /biome/groups/{group_name}/members/{user_id}` - Remove a user from a group
+/// * `GET /biome/sessions` - Get all active sessions for the authorized user
+/// * `DELETE /biome/sessions` - Revoke all sessions for the authorized user
+/// * `DELETE /biome/sessions/{session_id}` - Revoke a session for the authorized user
+/// * `GET /biome/notifications` - Get all notifications for authorized user, newest first
+/// * `GET /biome/notifications/{notification_id}` - Retrieve a notification for an authorized
+///    user
+/// * `PATCH /biome/notifications/{notification_id}` - Mark a notification as read for an
+///    authorized user
+/// * `DELETE /biome/notifications/{notification_id}` - Delete a notification for an authorized
+///    user
+///
+/// With the `biome-authorization` feature, the role and group endpoints require the
+/// `roles:manage` permission. Listing users and fetching other users requires `users:read`, and
+/// updating, removing, disabling or enabling other users requires `users:manage`.
+///
+/// With the `biome-user-admin` feature, `GET /biome/users` returns a page of users along with
+/// their creation time, last login time and whether they are enabled, and supports searching by
+/// username and sorting.
 pub struct BiomeRestResourceManager {
     #[cfg(feature = "biome-credentials")]
     user_store: Arc<dyn UserStore>,
     #[cfg(feature = "biome-key-management")]
     key_store: Arc<dyn KeyStore>,
-    #[cfg(any(feature = "biome-key-management", feature = "biome-credentials",))]
+    #[cfg(feature = "biome-notifications")]
+    notification_store: Arc<dyn NotificationStore>,
+    #[cfg(any(
+        feature = "biome-key-management",
+        feature = "biome-credentials",
+        feature = "biome-notifications",
+    ))]
     rest_config: Arc<BiomeRestConfig>,
-    #[cfg(any(feature = "biome-key-management", feature = "biome-credentials",))]
+    #[cfg(any(
+        feature = "biome-key-management",
+        feature = "biome-credentials",
+        feature = "biome-notifications",
+    ))]
     token_secret_manager: Arc<dyn SecretManager>,
     #[cfg(feature = "biome-credentials")]
     refresh_token_secret_manager: Arc<dyn SecretManager>,
@@ -123,6 +235,16 @@ pub struct BiomeRestResourceManager {
     refresh_token_store: Arc<dyn RefreshTokenStore>,
     #[cfg(feature = "biome-credentials")]
     credentials_store: Arc<dyn CredentialsStore>,
+    #[cfg(feature = "biome-password-reset")]
+    password_reset_notifier: Arc<dyn PasswordResetNotifier>,
+    #[cfg(feature = "biome-oidc")]
+    oidc_client: Option<Arc<OidcClient>>,
+    #[cfg(feature = "biome-lockout")]
+    login_throttle: Arc<LoginThrottle>,
+    #[cfg(feature = "biome-authorization")]
+    role_store: Arc<dyn RoleStore>,
+    #[cfg(feature = "biome-authorization")]
+    group_store: Arc<dyn GroupStore>,
 }
 
 impl RestResourceProvider for BiomeRestResourceManager {
@@ -137,18 +259,33 @@ impl RestResourceProvider for BiomeRestResourceManager {
             feature = "rest-api-actix",
         ))]
         {
-            resources.push(make_user_routes(
+            let user_routes = make_user_routes(
                 self.rest_config.clone(),
                 self.token_secret_manager.clone(),
                 self.credentials_store.clone(),
                 self.user_store.clone(),
                 self.key_store.clone(),
-            ));
+            );
+            #[cfg(feature = "biome-authorization")]
+            let user_routes = user_routes.add_request_guard(
+                self.permission_guard(Permission::ManageUsers)
+                    .with_read_permission(Permission::ReadUsers)
+                    .with_self_access("id"),
+            );
+            resources.push(user_routes);
         }
 
         #[cfg(all(feature = "biome-credentials", feature = "rest-api-actix",))]
         {
-            resources.push(make_list_route(self.credentials_store.clone()));
+            let list_route = make_list_route(
+                self.credentials_store.clone(),
+                #[cfg(feature = "biome-user-admin")]
+                self.user_store.clone(),
+            );
+            #[cfg(feature = "biome-authorization")]
+            let list_route =
+                list_route.add_request_guard(self.permission_guard(Permission::ReadUsers));
+            resources.push(list_route);
             resources.push(make_verify_route(
                 self.credentials_store.clone(),
                 self.rest_config.clone(),
@@ -162,6 +299,10 @@ impl RestResourceProvider for BiomeRestResourceManager {
                     self.token_secret_manager.clone(),
                     self.refresh_token_secret_manager.clone(),
                 )),
+                #[cfg(feature = "biome-lockout")]
+                self.login_throttle.clone(),
+                #[cfg(feature = "biome-user-admin")]
+                self.user_store.clone(),
             ));
             resources.push(make_token_route(
                 self.refresh_token_store.clone(),
@@ -172,6 +313,8 @@ impl RestResourceProvider for BiomeRestResourceManager {
                     self.refresh_token_secret_manager.clone(),
                 )),
                 self.rest_config.clone(),
+                #[cfg(feature = "biome-user-admin")]
+                self.user_store.clone(),
             ));
             resources.push(make_logout_route(
                 self.refresh_token_store.clone(),
@@ -186,6 +329,66 @@ impl RestResourceProvider for BiomeRestResourceManager {
             ));
         }
 
+        #[cfg(all(feature = "biome-mfa", feature = "rest-api-actix",))]
+        {
+            resources.push(make_mfa_route(
+                self.credentials_store.clone(),
+                self.rest_config.clone(),
+                self.token_secret_manager.clone(),
+            ));
+            resources.push(make_mfa_verify_route(
+                self.credentials_store.clone(),
+                self.rest_config.clone(),
+                self.token_secret_manager.clone(),
+            ));
+            resources.push(make_mfa_login_route(
+                self.credentials_store.clone(),
+                self.refresh_token_store.clone(),
+                self.rest_config.clone(),
+                Arc::new(AccessTokenIssuer::new(
+                    self.token_secret_manager.clone(),
+                    self.refresh_token_secret_manager.clone(),
+                )),
+                self.token_secret_manager.clone(),
+                #[cfg(feature = "biome-lockout")]
+                self.login_throttle.clone(),
+                #[cfg(feature = "biome-user-admin")]
+                self.user_store.clone(),
+            ));
+        }
+
+        #[cfg(all(feature = "biome-oidc", feature = "rest-api-actix",))]
+        {
+            if let Some(oidc_client) = &self.oidc_client {
+                resources.push(make_oidc_login_route(oidc_client.clone()));
+                resources.push(make_oidc_callback_route(
+                    oidc_client.clone(),
+                    self.user_store.clone(),
+                    self.refresh_token_store.clone(),
+                    self.rest_config.clone(),
+                    Arc::new(AccessTokenIssuer::new(
+                        self.token_secret_manager.clone(),
+                        self.refresh_token_secret_manager.clone(),
+                    )),
+                ));
+            }
+        }
+
+        #[cfg(all(feature = "biome-password-reset", feature = "rest-api-actix",))]
+        {
+            resources.push(make_password_route(
+                self.credentials_store.clone(),
+                self.rest_config.clone(),
+                self.token_secret_manager.clone(),
+            ));
+            resources.push(make_password_reset_route(
+                self.credentials_store.clone(),
+                self.refresh_token_store.clone(),
+                self.rest_config.clone(),
+                self.password_reset_notifier.clone(),
+            ));
+        }
+
         #[cfg(all(feature = "biome-key-management", feature = "rest-api-actix",))]
         {
             resources.push(make_key_management_route(
@@ -199,7 +402,167 @@ impl RestResourceProvider for BiomeRestResourceManager {
                 self.token_secret_manager.clone(),
             ));
         }
+
+        #[cfg(all(feature = "biome-notifications", feature = "rest-api-actix",))]
+        {
+            resources.push(make_notifications_route(
+                self.rest_config.clone(),
+                self.notification_store.clone(),
+                self.token_secret_manager.clone(),
+            ));
+            resources.push(make_notifications_route_with_notification_id(
+                self.rest_config.clone(),
+                self.notification_store.clone(),
+                self.token_secret_manager.clone(),
+            ));
+        }
+
+        #[cfg(all(feature = "biome-api-keys", feature = "rest-api-actix",))]
+        {
+            resources.push(make_api_keys_route(
+                self.credentials_store.clone(),
+                self.rest_config.clone(),
+                self.token_secret_manager.clone(),
+            ));
+            resources.push(make_api_key_route(
+                self.credentials_store.clone(),
+                self.rest_config.clone(),
+                self.token_secret_manager.clone(),
+            ));
+        }
+
+        #[cfg(all(feature = "biome-authorization", feature = "rest-api-actix",))]
+        {
+            let guard = self.permission_guard(Permission::ManageRoles);
+            resources.push(make_roles_route(self.role_store.clone(), guard.clone()));
+            resources.push(make_role_route(self.role_store.clone(), guard.clone()));
+            resources.push(make_role_user_route(
+                self.role_store.clone(),
+                self.credentials_store.clone(),
+                guard.clone(),
+            ));
+            resources.push(make_role_group_route(
+                self.role_store.clone(),
+                self.group_store.clone(),
+                guard.clone(),
+            ));
+            resources.push(make_groups_route(self.group_store.clone(), guard.clone()));
+            resources.push(make_group_route(
+                self.role_store.clone(),
+                self.group_store.clone(),
+                guard.clone(),
+            ));
+            resources.push(make_group_member_route(
+                self.group_store.clone(),
+                self.credentials_store.clone(),
+                guard,
+            ));
+        }
+
+        #[cfg(all(feature = "biome-user-admin", feature = "rest-api-actix",))]
+        {
+            let guard = self.permission_guard(Permission::ManageUsers);
+            resources.push(
+                make_user_enabled_route(self.user_store.clone(), false)
+                    .add_request_guard(guard.clone()),
+            );
+            resources.push(
+                make_user_enabled_route(self.user_store.clone(), true).add_request_guard(guard),
+            );
+        }
+
+        #[cfg(all(feature = "biome-sessions", feature = "rest-api-actix",))]
+        {
+            resources.push(make_sessions_route(
+                self.refresh_token_store.clone(),
+                self.token_secret_manager.clone(),
+                self.rest_config.clone(),
+            ));
+            resources.push(make_session_route(
+                self.refresh_token_store.clone(),
+                self.token_secret_manager.clone(),
+                self.rest_config.clone(),
+            ));
+        }
+        resources
+    }
+}
+
+#[cfg(all(feature = "biome-sessions", feature = "rest-api-actix"))]
+impl BiomeRestResourceManager {
+    /// Returns a request guard that rejects access tokens issued for a revoked session. It should
+    /// be added to the whole REST API, so that it applies to the endpoints of Biome and to those
+    /// protected by Biome's other guards.
+    pub fn session_revocation_guard(&self) -> SessionRevocationGuard {
+        SessionRevocationGuard::new(
+            self.refresh_token_store.clone(),
+            self.token_secret_manager.clone(),
+            self.rest_config.clone(),
+        )
+    }
+}
+
+#[cfg(all(feature = "biome-api-keys", feature = "rest-api-actix"))]
+impl BiomeRestResourceManager {
+    /// Returns a request guard that accepts either a Biome access token or an API key that has
+    /// been granted the given scope, for protecting endpoints outside of Biome. With Biome
+    /// authorization, the user must also have been granted the permission matching the scope.
+    pub fn api_key_guard(&self, scope: ApiKeyScope) -> ApiKeyGuard {
+        let guard = ApiKeyGuard::new(
+            self.credentials_store.clone(),
+            self.token_secret_manager.clone(),
+            self.rest_config.clone(),
+            scope,
+        );
+        #[cfg(feature = "biome-authorization")]
+        let guard =
+            guard.with_authorization_stores(self.role_store.clone(), self.group_store.clone());
+        guard
+    }
+
+    /// Protects the admin, registry and scabbard endpoints among the given resources with API key
+    /// guards, so that they are only served to users with a Biome access token or to API keys
+    /// with the matching scope. Other resources are returned unchanged. With Biome authorization,
+    /// the user must also have been granted the permission of the same name as the scope.
+    ///
+    /// * `/admin` endpoints require `admin:read` for reads and `admin:submit` otherwise
+    /// * `/registry` endpoints require `registry:write` for writes; any key may read
+    /// * `/scabbard` endpoints require `scabbard:submit` for writes; any key may read
+    pub fn add_api_key_guards(&self, resources: Vec<Resource>) -> Vec<Resource> {
         resources
+            .into_iter()
+            .map(|resource| {
+                let route = resource.route().trim_start_matches('/');
+                let guard = if route.starts_with("admin/") || route.starts_with("ws/admin/") {
+                    self.api_key_guard(ApiKeyScope::AdminSubmit)
+                        .with_read_scope(Some(ApiKeyScope::AdminRead))
+                } else if route.starts_with("registry/") {
+                    self.api_key_guard(ApiKeyScope::RegistryWrite)
+                        .with_read_scope(None)
+                } else if route.starts_with("scabbard/") {
+                    self.api_key_guard(ApiKeyScope::ScabbardSubmit)
+                        .with_read_scope(None)
+                } else {
+                    return resource;
+                };
+                resource.add_request_guard(guard)
+            })
+            .collect()
+    }
+}
+
+#[cfg(all(feature = "biome-authorization", feature = "rest-api-actix"))]
+impl BiomeRestResourceManager {
+    /// Returns a request guard that requires a Biome access token for a user who has been granted
+    /// the given permission, for protecting endpoints outside of Biome
+    pub fn permission_guard(&self, permission: Permission) -> PermissionGuard {
+        PermissionGuard::new(
+            self.role_store.clone(),
+            self.group_store.clone(),
+            self.token_secret_manager.clone(),
+            self.rest_config.clone(),
+            permission,
+        )
     }
 }
 
@@ -209,6 +572,8 @@ pub struct BiomeRestResourceManagerBuilder {
     user_store: Option<Arc<dyn UserStore>>,
     #[cfg(feature = "biome-key-management")]
     key_store: Option<Arc<dyn KeyStore>>,
+    #[cfg(feature = "biome-notifications")]
+    notification_store: Option<Arc<dyn NotificationStore>>,
     rest_config: Option<BiomeRestConfig>,
     token_secret_manager: Option<Arc<dyn SecretManager>>,
     #[cfg(feature = "biome-credentials")]
@@ -217,6 +582,14 @@ pub struct BiomeRestResourceManagerBuilder {
     refresh_token_store: Option<Arc<dyn RefreshTokenStore>>,
     #[cfg(feature = "biome-credentials")]
     credentials_store: Option<Arc<dyn CredentialsStore>>,
+    #[cfg(feature = "biome-password-reset")]
+    password_reset_notifier: Option<Arc<dyn PasswordResetNotifier>>,
+    #[cfg(feature = "biome-oidc")]
+    oidc_client: Option<OidcClient>,
+    #[cfg(feature = "biome-authorization")]
+    role_store: Option<Arc<dyn RoleStore>>,
+    #[cfg(feature = "biome-authorization")]
+    group_store: Option<Arc<dyn GroupStore>>,
 }
 
 impl BiomeRestResourceManagerBuilder {
@@ -247,6 +620,21 @@ impl BiomeRestResourceManagerBuilder {
         self
     }
 
+    /// Sets a NotificationStore for the BiomeRestResourceManager
+    ///
+    /// # Arguments
+    ///
+    /// * `store`: the NotificationStore that will serve as backend for the notification
+    ///   endpoints
+    #[cfg(feature = "biome-notifications")]
+    pub fn with_notification_store(
+        mut self,
+        store: impl NotificationStore + 'static,
+    ) -> BiomeRestResourceManagerBuilder {
+        self.notification_store = Some(Arc::new(store));
+        self
+    }
+
     /// Sets a BiomeRestConfig for the BiomeRestResourceManager
     ///
     /// # Arguments
@@ -316,6 +704,60 @@ impl BiomeRestResourceManagerBuilder {
         self
     }
 
+    /// Sets a PasswordResetNotifier for the BiomeRestResourceManager
+    ///
+    /// # Arguments
+    ///
+    /// * `notifier`: the PasswordResetNotifier used to deliver password reset tokens to users
+    #[cfg(feature = "biome-password-reset")]
+    pub fn with_password_reset_notifier(
+        mut self,
+        notifier: impl PasswordResetNotifier + 'static,
+    ) -> BiomeRestResourceManagerBuilder {
+        self.password_reset_notifier = Some(Arc::new(notifier));
+        self
+    }
+
+    /// Sets an OidcClient for the BiomeRestResourceManager. The OpenID Connect login endpoints are
+    /// only provided if a client is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `client`: the OidcClient for the provider that users log in through
+    #[cfg(feature = "biome-oidc")]
+    pub fn with_oidc_client(mut self, client: OidcClient) -> BiomeRestResourceManagerBuilder {
+        self.oidc_client = Some(client);
+        self
+    }
+
+    /// Sets a RoleStore for the BiomeRestResourceManager
+    ///
+    /// # Arguments
+    ///
+    /// * `store`: the RoleStore that users' roles are fetched from and managed in
+    #[cfg(feature = "biome-authorization")]
+    pub fn with_role_store(
+        mut self,
+        store: impl RoleStore + 'static,
+    ) -> BiomeRestResourceManagerBuilder {
+        self.role_store = Some(Arc::new(store));
+        self
+    }
+
+    /// Sets a GroupStore for the BiomeRestResourceManager
+    ///
+    /// # Arguments
+    ///
+    /// * `store`: the GroupStore that users' groups are fetched from and managed in
+    #[cfg(feature = "biome-authorization")]
+    pub fn with_group_store(
+        mut self,
+        store: impl GroupStore + 'static,
+    ) -> BiomeRestResourceManagerBuilder {
+        self.group_store = Some(Arc::new(store));
+        self
+    }
+
     /// Consumes the builder and returns a BiomeRestResourceManager
     pub fn build(self) -> Result<BiomeRestResourceManager, BiomeRestResourceManagerBuilderError> {
         #[cfg(feature = "biome-credentials")]
@@ -330,7 +772,17 @@ impl BiomeRestResourceManagerBuilder {
                 "Missing key store".to_string(),
             )
         })?;
-        #[cfg(any(feature = "biome-key-management", feature = "biome-credentials",))]
+        #[cfg(feature = "biome-notifications")]
+        let notification_store = self.notification_store.ok_or_else(|| {
+            BiomeRestResourceManagerBuilderError::MissingRequiredField(
+                "Missing notification store".to_string(),
+            )
+        })?;
+        #[cfg(any(
+            feature = "biome-key-management",
+            feature = "biome-credentials",
+            feature = "biome-notifications",
+        ))]
         let rest_config = match self.rest_config {
             Some(config) => config,
             None => {
@@ -339,7 +791,11 @@ impl BiomeRestResourceManagerBuilder {
             }
         };
 
-        #[cfg(any(feature = "biome-key-management", feature = "biome-credentials",))]
+        #[cfg(any(
+            feature = "biome-key-management",
+            feature = "biome-credentials",
+            feature = "biome-notifications",
+        ))]
         let token_secret_manager = self.token_secret_manager.unwrap_or_else(|| {
             debug!("Building BiomeRestResourceManager with default SecretManager.");
             Arc::new(AutoSecretManager::default())
@@ -359,21 +815,70 @@ impl BiomeRestResourceManagerBuilder {
         })?;
 
         #[cfg(feature = "biome-credentials")]
-        #[cfg(any(feature = "biome-key-management", feature = "biome-credentials",))]
+        #[cfg(any(
+            feature = "biome-key-management",
+            feature = "biome-credentials",
+            feature = "biome-notifications",
+        ))]
         let credentials_store = self.credentials_store.ok_or_else(|| {
             BiomeRestResourceManagerBuilderError::MissingRequiredField(
                 "Missing credentials store".to_string(),
             )
         })?;
 
+        #[cfg(feature = "biome-authorization")]
+        let role_store = self.role_store.ok_or_else(|| {
+            BiomeRestResourceManagerBuilderError::MissingRequiredField(
+                "Missing role store".to_string(),
+            )
+        })?;
+        #[cfg(feature = "biome-authorization")]
+        let group_store = self.group_store.ok_or_else(|| {
+            BiomeRestResourceManagerBuilderError::MissingRequiredField(
+                "Missing group store".to_string(),
+            )
+        })?;
+
+        #[cfg(feature = "biome-password-reset")]
+        let password_reset_notifier = self.password_reset_notifier.unwrap_or_else(|| {
+            warn!(
+                "Building BiomeRestResourceManager with default PasswordResetNotifier; \
+                 password reset tokens will be written to the log"
+            );
+            Arc::new(LogPasswordResetNotifier::default())
+        });
+
+        #[cfg(feature = "biome-lockout")]
+        let login_throttle = Arc::new(LoginThrottle::new(
+            credentials_store.clone(),
+            LockoutPolicy::new(
+                rest_config.lockout_threshold(),
+                rest_config.lockout_duration(),
+            ),
+            LockoutPolicy::new(
+                rest_config.client_lockout_threshold(),
+                rest_config.lockout_duration(),
+            ),
+        ));
+
         Ok(BiomeRestResourceManager {
             #[cfg(feature = "biome-credentials")]
             user_store,
             #[cfg(feature = "biome-key-management")]
             key_store,
-            #[cfg(any(feature = "biome-key-management", feature = "biome-credentials",))]
+            #[cfg(feature = "biome-notifications")]
+            notification_store,
+            #[cfg(any(
+                feature = "biome-key-management",
+                feature = "biome-credentials",
+                feature = "biome-notifications",
+            ))]
             rest_config: Arc::new(rest_config),
-            #[cfg(any(feature = "biome-key-management", feature = "biome-credentials",))]
+            #[cfg(any(
+                feature = "biome-key-management",
+                feature = "biome-credentials",
+                feature = "biome-notifications",
+            ))]
             token_secret_manager,
             #[cfg(feature = "biome-credentials")]
             refresh_token_secret_manager,
@@ -381,6 +886,16 @@ impl BiomeRestResourceManagerBuilder {
             refresh_token_store,
             #[cfg(feature = "biome-credentials")]
             credentials_store,
+            #[cfg(feature = "biome-password-reset")]
+            password_reset_notifier,
+            #[cfg(feature = "biome-oidc")]
+            oidc_client: self.oidc_client.map(Arc::new),
+            #[cfg(feature = "biome-lockout")]
+            login_throttle,
+            #[cfg(feature = "biome-authorization")]
+            role_store,
+            #[cfg(feature = "biome-authorization")]
+            group_store,
         })
     }
 }
@@ -435,6 +950,12 @@ mod tests {
         pub username: String,
     }
 
+    #[cfg(feature = "biome-user-admin")]
+    #[derive(Deserialize)]
+    struct ListUsersResponse {
+        pub data: Vec<GetUserResponse>,
+    }
+
     #[derive(Serialize)]
     struct PutUser {
         pub username: String,
@@ -497,7 +1018,10 @@ mod tests {
         token: String,
     }
 
-    fn start_biome_rest_api() -> (RestApiShutdownHandle, thread::JoinHandle<()>) {
+    fn start_biome_rest_api<B>(configure: B) -> (RestApiShutdownHandle, thread::JoinHandle<()>)
+    where
+        B: FnOnce(BiomeRestResourceManagerBuilder) -> BiomeRestResourceManagerBuilder,
+    {
         let refresh_token_store = MemoryRefreshTokenStore::new();
         let cred_store = MemoryCredentialsStore::new();
         let user_store = MemoryUserStore::new(cred_store.clone());
@@ -507,22 +1031,69 @@ mod tests {
             .build()
             .unwrap();
 
-        let resource_manager = BiomeRestResourceManagerBuilder::default()
+        let resource_manager_builder = BiomeRestResourceManagerBuilder::default()
             .with_user_store(user_store)
             .with_refresh_token_store(refresh_token_store)
             .with_credentials_store(cred_store)
             .with_key_store(key_store)
-            .with_rest_config(config)
-            .build()
-            .unwrap();
+            .with_rest_config(config);
+        #[cfg(feature = "biome-notifications")]
+        let resource_manager_builder = resource_manager_builder
+            .with_notification_store(crate::biome::MemoryNotificationStore::default());
+        #[cfg(feature = "biome-authorization")]
+        let resource_manager_builder = resource_manager_builder
+            .with_role_store(crate::biome::MemoryRoleStore::new())
+            .with_group_store(crate::biome::MemoryGroupStore::new());
+        #[cfg(feature = "biome-password-reset")]
+        let resource_manager_builder = resource_manager_builder.with_password_reset_notifier(
+            crate::biome::credentials::password_reset::FilePasswordResetNotifier::new(
+                reset_token_file(),
+            ),
+        );
+        let resource_manager = configure(resource_manager_builder).build().unwrap();
+
+        #[allow(unused_mut)]
+        let mut resources = resource_manager.resources();
+        #[cfg(all(feature = "biome-api-keys", feature = "registry"))]
+        {
+            use crate::registry::{MemoryRegistry, RwRegistry};
+
+            // The registry's endpoints, protected by API key guards as they are by splinterd
+            let registry: Box<dyn RwRegistry> = Box::new(MemoryRegistry::new());
+            resources.append(&mut resource_manager.add_api_key_guards(registry.resources()));
+        }
 
-        RestApiBuilder::new()
+        let rest_api_builder = RestApiBuilder::new()
             .with_bind("127.0.0.1:0")
-            .add_resources(resource_manager.resources())
-            .build()
-            .unwrap()
-            .run()
-            .unwrap()
+            .add_resources(resources);
+        #[cfg(feature = "biome-sessions")]
+        let rest_api_builder =
+            rest_api_builder.add_request_guard(resource_manager.session_revocation_guard());
+
+        rest_api_builder.build().unwrap().run().unwrap()
+    }
+
+    /// Returns the file that password reset tokens are written to by the test REST API. Tests
+    /// share the file, so each test must use a unique username.
+    #[cfg(feature = "biome-password-reset")]
+    fn reset_token_file() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("biome_reset_tokens_{}", std::process::id()))
+    }
+
+    /// Returns the most recent password reset token written for the given username.
+    #[cfg(feature = "biome-password-reset")]
+    fn read_reset_token(username: &str) -> Option<String> {
+        std::fs::read_to_string(reset_token_file())
+            .ok()?
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.split(' ');
+                match (parts.next(), parts.next()) {
+                    (Some(name), Some(token)) if name == username => Some(token.to_string()),
+                    _ => None,
+                }
+            })
+            .last()
     }
 
     fn create_and_authorize_user(
@@ -558,7 +1129,17 @@ mod tests {
     where
         F: FnOnce(&str, Client) -> () + panic::UnwindSafe,
     {
-        let (handle, join_handle) = start_biome_rest_api();
+        run_test_with(|builder| builder, f)
+    }
+
+    /// Runs a test against a REST API with additional configuration applied to the resource
+    /// manager builder.
+    fn run_test_with<B, F>(configure: B, f: F)
+    where
+        B: FnOnce(BiomeRestResourceManagerBuilder) -> BiomeRestResourceManagerBuilder,
+        F: FnOnce(&str, Client) -> () + panic::UnwindSafe,
+    {
+        let (handle, join_handle) = start_biome_rest_api(configure);
 
         let port_no = handle.port_numbers()[0];
 
@@ -574,6 +1155,30 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    /// Runs a test against a REST API, passing it a function that assigns the admin role to a
+    /// user, as the node's operator would with `splinter user make-admin`.
+    fn run_admin_test<F>(f: F)
+    where
+        F: FnOnce(&str, Client, &dyn Fn(&str)) -> () + panic::UnwindSafe,
+    {
+        #[cfg(feature = "biome-authorization")]
+        {
+            let role_store = crate::biome::MemoryRoleStore::new();
+            let builder_role_store = role_store.clone();
+            run_test_with(
+                move |builder| builder.with_role_store(builder_role_store),
+                move |url, client| {
+                    f(url, client, &|user_id| {
+                        crate::biome::authorization::assign_admin(&role_store, user_id)
+                            .expect("Failed to assign admin role")
+                    })
+                },
+            )
+        }
+        #[cfg(not(feature = "biome-authorization"))]
+        run_test(|url, client| f(url, client, &|_| ()))
+    }
+
     /// Happy path test for POST /biome/register
     ///
     /// Verify that POST /biome/register creates a user
@@ -678,15 +1283,16 @@ mod tests {
     ///
     /// Procedure
     ///
-    /// 1) Create user and login as that user
+    /// 1) Create user, login as that user, and make them an administrator
     /// 2) Query for all users via GET /biome/users
     /// 3) Verify that the user_id and username of the created
     ///    user matches one of the users returned
     #[test]
     fn test_get_users() {
-        run_test(|url, client| {
+        run_admin_test(|url, client, make_admin| {
             let login =
                 create_and_authorize_user(url, &client, "test_get_users@gmail.com", "Admin2193!");
+            make_admin(&login.user_id);
 
             let users_response = client
                 .get(&format!("{}/biome/users", url))
@@ -696,6 +1302,9 @@ mod tests {
 
             assert_eq!(users_response.status().as_u16(), 200);
 
+            #[cfg(feature = "biome-user-admin")]
+            let users = users_response.json::<ListUsersResponse>().unwrap().data;
+            #[cfg(not(feature = "biome-user-admin"))]
             let users = users_response.json::<Vec<GetUserResponse>>().unwrap();
 
             assert!(users.iter().any(|user| {
@@ -756,16 +1365,17 @@ mod tests {
     ///
     /// Procedure
     ///
-    /// 1) Create user and login as that user
+    /// 1) Create user, login as that user, and make them an administrator
     /// 2) Create a second user
     /// 3) Verify that the second user exists
     /// 4) Delete the second user via DELETE /biome/users/{id}
     /// 5) Verify that the user was deleted using GET /biome/users/{id}
     #[test]
     fn test_delete_user() {
-        run_test(|url, client| {
+        run_admin_test(|url, client, make_admin| {
             let login =
                 create_and_authorize_user(url, &client, "test_delete_user@gmail.com", "Admin2193!");
+            make_admin(&login.user_id);
 
             let user_to_delete = create_and_authorize_user(
                 url,
@@ -1225,4 +1835,1017 @@ mod tests {
             token_response.json::<PostToken>().unwrap();
         });
     }
+
+    /// Test GET, PATCH and DELETE /biome/notifications for a user without notifications
+    ///
+    /// Verify that GET /biome/notifications returns an empty list and a status of 200, and that
+    /// fetching, marking as read or deleting an unknown notification returns a status of 404.
+    ///
+    /// Procedure
+    ///
+    /// 1) Create a user and log in as that user
+    /// 2) List the user's notifications via GET /biome/notifications
+    /// 3) Verify the list is empty
+    /// 4) Verify GET, PATCH and DELETE /biome/notifications/{notification_id} return 404 for an
+    ///    unknown notification
+    #[cfg(feature = "biome-notifications")]
+    #[test]
+    fn test_notifications_not_found() {
+        run_test(|url, client| {
+            let login = create_and_authorize_user(
+                url,
+                &client,
+                "test_notifications@gmail.com",
+                "Admin2193!",
+            );
+
+            let list_response = client
+                .get(&format!("{}/biome/notifications", url))
+                .header("Authorization", format!("Bearer {}", login.token))
+                .send()
+                .unwrap();
+
+            assert_eq!(list_response.status().as_u16(), 200);
+            let notifications = list_response.json::<serde_json::Value>().unwrap();
+            assert_eq!(notifications["data"], json!([]));
+
+            let notification_url = format!("{}/biome/notifications/unknown", url);
+            for request in vec![
+                client.get(&notification_url),
+                client.patch(&notification_url),
+                client.delete(&notification_url),
+            ] {
+                assert_eq!(
+                    request
+                        .header("Authorization", format!("Bearer {}", login.token))
+                        .send()
+                        .unwrap()
+                        .status()
+                        .as_u16(),
+                    404
+                );
+            }
+        });
+    }
+
+    /// Test the password reset flow
+    ///
+    /// Verify that a password reset token may be requested via POST /biome/password/reset, that
+    /// the token may be used once via PUT /biome/password/reset, and that resetting the password
+    /// removes the user's refresh tokens.
+    ///
+    /// Procedure
+    ///
+    /// 1) Create a user and log in as that user
+    /// 2) Request a password reset token via POST /biome/password/reset and verify that the same
+    ///    response is returned for an unknown user
+    /// 3) Reset the password with the delivered token and verify a status of 200
+    /// 4) Verify that reusing the token returns a status of 400
+    /// 5) Verify that the refresh token from the login can no longer be used
+    /// 6) Verify that the user can log in with the new password but not the old one
+    #[cfg(feature = "biome-password-reset")]
+    #[test]
+    fn test_password_reset() {
+        run_test(|url, client| {
+            let username = "test_password_reset@gmail.com";
+            let login = create_and_authorize_user(url, &client, username, "Admin2193!");
+
+            let reset_url = format!("{}/biome/password/reset", url);
+            let response = client
+                .post(&reset_url)
+                .json(&json!({ "username": username }))
+                .send()
+                .unwrap();
+            assert_eq!(response.status().as_u16(), 200);
+            let message = response.json::<serde_json::Value>().unwrap()["message"].clone();
+
+            let unknown_response = client
+                .post(&reset_url)
+                .json(&json!({ "username": "unknown_reset_user@gmail.com" }))
+                .send()
+                .unwrap();
+            assert_eq!(unknown_response.status().as_u16(), 200);
+            assert_eq!(
+                unknown_response.json::<serde_json::Value>().unwrap()["message"],
+                message
+            );
+
+            let token = read_reset_token(username).expect("No reset token was delivered");
+            let reset = json!({ "token": token, "new_password": "NewAdmin2193!" });
+            assert_eq!(
+                client
+                    .put(&reset_url)
+                    .json(&reset)
+                    .send()
+                    .unwrap()
+                    .status()
+                    .as_u16(),
+                200
+            );
+            assert_eq!(
+                client
+                    .put(&reset_url)
+                    .json(&reset)
+                    .send()
+                    .unwrap()
+                    .status()
+                    .as_u16(),
+                400
+            );
+
+            assert_eq!(
+                client
+                    .post(&format!("{}/biome/token", url))
+                    .header("Authorization", format!("Bearer {}", login.token))
+                    .json(&PostToken {
+                        token: login.refresh_token
+                    })
+                    .send()
+                    .unwrap()
+                    .status()
+                    .as_u16(),
+                403
+            );
+
+            for (password, status) in vec![("Admin2193!", 400), ("NewAdmin2193!", 200)] {
+                assert_eq!(
+                    client
+                        .post(&format!("{}/biome/login", url))
+                        .json(&UsernamePassword {
+                            username: username.to_string(),
+                            hashed_password: password.to_string(),
+                        })
+                        .send()
+                        .unwrap()
+                        .status()
+                        .as_u16(),
+                    status
+                );
+            }
+        });
+    }
+
+    /// Test changing the password of an authorized user via PUT /biome/password
+    ///
+    /// Procedure
+    ///
+    /// 1) Create a user and log in as that user
+    /// 2) Verify that changing the password with an incorrect current password returns 400
+    /// 3) Change the password with the correct current password and verify a status of 200
+    /// 4) Verify that the user can log in with the new password
+    #[cfg(feature = "biome-password-reset")]
+    #[test]
+    fn test_change_password() {
+        run_test(|url, client| {
+            let username = "test_change_password@gmail.com";
+            let login = create_and_authorize_user(url, &client, username, "Admin2193!");
+
+            for (current, status) in vec![("Wrong2193!", 400), ("Admin2193!", 200)] {
+                assert_eq!(
+                    client
+                        .put(&format!("{}/biome/password", url))
+                        .header("Authorization", format!("Bearer {}", login.token))
+                        .json(&json!({
+                            "hashed_password": current,
+                            "new_password": "NewAdmin2193!",
+                        }))
+                        .send()
+                        .unwrap()
+                        .status()
+                        .as_u16(),
+                    status
+                );
+            }
+
+            assert_eq!(
+                client
+                    .post(&format!("{}/biome/login", url))
+                    .json(&UsernamePassword {
+                        username: username.to_string(),
+                        hashed_password: "NewAdmin2193!".to_string(),
+                    })
+                    .send()
+                    .unwrap()
+                    .status()
+                    .as_u16(),
+                200
+            );
+        });
+    }
+
+    /// Test the MFA enrollment and login flow
+    ///
+    /// Procedure
+    ///
+    /// 1) Create a user and log in as that user
+    /// 2) Start MFA enrollment via POST /biome/mfa and verify a code generated from the returned
+    ///    secret via POST /biome/mfa/verify
+    /// 3) Verify that logging in returns an MFA challenge token rather than an access token, and
+    ///    that the challenge token cannot be used as an access token
+    /// 4) Verify that completing the login via POST /biome/login/mfa with an invalid code returns
+    ///    400
+    /// 5) Complete the login with a recovery code and verify a status of 200
+    /// 6) Verify that the recovery code cannot be used again
+    #[cfg(feature = "biome-mfa")]
+    #[test]
+    fn test_mfa_login() {
+        run_test(|url, client| {
+            let username = "test_mfa_login@gmail.com";
+            let login = create_and_authorize_user(url, &client, username, "Admin2193!");
+
+            let enroll_response = client
+                .post(&format!("{}/biome/mfa", url))
+                .header("Authorization", format!("Bearer {}", login.token))
+                .send()
+                .unwrap();
+            assert_eq!(enroll_response.status().as_u16(), 200);
+            let enrollment = enroll_response.json::<serde_json::Value>().unwrap();
+            let secret = crate::biome::credentials::mfa::MfaSecret {
+                user_id: login.user_id.clone(),
+                secret: enrollment["secret"].as_str().unwrap().to_string(),
+                enabled: false,
+                last_used_step: None,
+            };
+
+            let verify_response = client
+                .post(&format!("{}/biome/mfa/verify", url))
+                .header("Authorization", format!("Bearer {}", login.token))
+                .json(&json!({
+                    "code": secret.generate_code(std::time::SystemTime::now()).unwrap()
+                }))
+                .send()
+                .unwrap();
+            assert_eq!(verify_response.status().as_u16(), 200);
+            let recovery_codes = verify_response.json::<serde_json::Value>().unwrap()
+                ["recovery_codes"]
+                .as_array()
+                .unwrap()
+                .clone();
+            assert!(!recovery_codes.is_empty());
+
+            let login_response = client
+                .post(&format!("{}/biome/login", url))
+                .json(&UsernamePassword {
+                    username: username.to_string(),
+                    hashed_password: "Admin2193!".to_string(),
+                })
+                .send()
+                .unwrap();
+            assert_eq!(login_response.status().as_u16(), 200);
+            let challenge = login_response.json::<serde_json::Value>().unwrap();
+            assert!(challenge.get("token").is_none());
+            let mfa_token = challenge["mfa_token"].as_str().unwrap().to_string();
+
+            assert_eq!(
+                client
+                    .get(&format!("{}/biome/mfa", url))
+                    .header("Authorization", format!("Bearer {}", mfa_token))
+                    .send()
+                    .unwrap()
+                    .status()
+                    .as_u16(),
+                401
+            );
+
+            for (code, status) in vec![
+                (json!("invalid"), 400),
+                (recovery_codes[0].clone(), 200),
+                (recovery_codes[0].clone(), 400),
+            ] {
+                assert_eq!(
+                    client
+                        .post(&format!("{}/biome/login/mfa", url))
+                        .json(&json!({ "mfa_token": mfa_token, "code": code }))
+                        .send()
+                        .unwrap()
+                        .status()
+                        .as_u16(),
+                    status
+                );
+            }
+        });
+    }
+
+    /// Test the OpenID Connect login flow against a mock provider.
+    ///
+    /// Procedure
+    ///
+    /// 1) Start a login via GET /biome/oidc/login and verify that it redirects to the provider
+    /// 2) Log in at the provider and complete the login via GET /biome/oidc/callback
+    /// 3) Verify that completing the login without the login cookie is rejected
+    /// 4) Complete the login with the login cookie and verify that the issued tokens can be used
+    ///    to get a new access token via POST /biome/token
+    /// 5) Verify that reusing the state is rejected
+    #[cfg(feature = "biome-oidc")]
+    #[test]
+    fn test_oidc_login() {
+        use crate::biome::oidc::{mock_issuer::MockIssuer, OidcClientBuilder};
+
+        let issuer = MockIssuer::start("splinter");
+        let oidc_client = OidcClientBuilder::new()
+            .with_issuer_url(issuer.url())
+            .with_client_id("splinter")
+            .with_client_secret("secret")
+            .with_redirect_url("http://localhost:8080/biome/oidc/callback")
+            .build()
+            .unwrap();
+
+        run_test_with(
+            move |builder| builder.with_oidc_client(oidc_client),
+            move |url, _| {
+                let client = Client::builder()
+                    .redirect(reqwest::redirect::Policy::none())
+                    .build()
+                    .unwrap();
+
+                let login_response = client
+                    .get(&format!("{}/biome/oidc/login", url))
+                    .send()
+                    .unwrap();
+                assert_eq!(login_response.status().as_u16(), 302);
+                let authorization_url = login_response.headers()["Location"]
+                    .to_str()
+                    .unwrap()
+                    .to_string();
+                assert!(authorization_url.starts_with(issuer.url()));
+                let login_cookie = login_response.headers()["Set-Cookie"]
+                    .to_str()
+                    .unwrap()
+                    .to_string();
+                assert!(login_cookie.starts_with("biome_oidc_login="));
+                assert!(login_cookie.contains("HttpOnly"));
+                let login_cookie = login_cookie.split(';').next().unwrap().to_string();
+
+                let (code, state) = issuer.authorize(&authorization_url, "oidc_user");
+                let callback_url = format!("{}/biome/oidc/callback", url);
+                let no_cookie_response = client
+                    .get(&callback_url)
+                    .query(&[("code", &code), ("state", &state)])
+                    .send()
+                    .unwrap();
+                assert_eq!(no_cookie_response.status().as_u16(), 401);
+
+                let callback_response = client
+                    .get(&callback_url)
+                    .header("Cookie", login_cookie.as_str())
+                    .query(&[("code", &code), ("state", &state)])
+                    .send()
+                    .unwrap();
+                assert_eq!(callback_response.status().as_u16(), 200);
+                let login = callback_response.json::<LoginResponse>().unwrap();
+
+                let token_response = client
+                    .post(&format!("{}/biome/token", url))
+                    .header("Authorization", format!("Bearer {}", login.token))
+                    .json(&PostToken {
+                        token: login.refresh_token,
+                    })
+                    .send()
+                    .unwrap();
+                assert_eq!(token_response.status().as_u16(), 200);
+
+                let replay_response = client
+                    .get(&callback_url)
+                    .header("Cookie", login_cookie.as_str())
+                    .query(&[("code", &code), ("state", &state)])
+                    .send()
+                    .unwrap();
+                assert_eq!(replay_response.status().as_u16(), 401);
+            },
+        );
+    }
+
+    /// Test that failed logins delay further attempts, and that reaching the lockout threshold
+    /// locks the user out.
+    ///
+    /// Procedure
+    ///
+    /// 1) Create a user, with a lockout threshold of two failed attempts
+    /// 2) Attempt to login with an invalid password and verify that it fails with a 400
+    /// 3) Immediately attempt to login with the correct password and verify that it is refused
+    ///    with a 429 and a Retry-After header
+    /// 4) After waiting, fail again and verify that the user is locked out for the lockout
+    ///    duration, even with the correct password
+    #[cfg(feature = "biome-lockout")]
+    #[test]
+    fn test_login_lockout() {
+        let config = BiomeRestConfigBuilder::default()
+            .with_password_encryption_cost("low")
+            .with_lockout_threshold(2)
+            .with_lockout_duration_in_secs(60)
+            .build()
+            .unwrap();
+
+        run_test_with(
+            move |builder| builder.with_rest_config(config),
+            |url, client| {
+                create_and_authorize_user(url, &client, "test_lockout@gmail.com", "Admin2193!");
+
+                let login = |password: &str| {
+                    client
+                        .post(&format!("{}/biome/login", url))
+                        .json(&UsernamePassword {
+                            username: "test_lockout@gmail.com".to_string(),
+                            hashed_password: password.to_string(),
+                        })
+                        .send()
+                        .unwrap()
+                };
+
+                assert_eq!(login("wrong").status().as_u16(), 400);
+
+                let throttled = login("Admin2193!");
+                assert_eq!(throttled.status().as_u16(), 429);
+                assert_eq!(throttled.headers()["Retry-After"].to_str().unwrap(), "1");
+
+                thread::sleep(std::time::Duration::from_secs(1));
+                assert_eq!(login("wrong").status().as_u16(), 400);
+
+                thread::sleep(std::time::Duration::from_secs(2));
+                let locked = login("Admin2193!");
+                assert_eq!(locked.status().as_u16(), 429);
+                let retry_after: u64 = locked.headers()["Retry-After"]
+                    .to_str()
+                    .unwrap()
+                    .parse()
+                    .unwrap();
+                assert!(retry_after > 2 && retry_after <= 60);
+            },
+        );
+    }
+
+    /// Test that a user can list their sessions and revoke them, and that an access token issued
+    /// for a revoked session is rejected before it expires.
+    ///
+    /// Procedure
+    ///
+    /// 1) Create a user and log in a second time, creating two sessions
+    /// 2) List the sessions via GET /biome/sessions and verify that both are returned, with only
+    ///    the session of the second login marked as current
+    /// 3) Revoke the first session via DELETE /biome/sessions/{session_id}
+    /// 4) Verify that the first session's access token is rejected with a 401, and that its
+    ///    refresh token can no longer be used
+    /// 5) Revoke all sessions via DELETE /biome/sessions and verify that the second session's
+    ///    access token is rejected with a 401
+    #[cfg(feature = "biome-sessions")]
+    #[test]
+    fn test_sessions() {
+        run_test(|url, client| {
+            let first_login =
+                create_and_authorize_user(url, &client, "test_sessions@gmail.com", "Admin2193!");
+            let second_login = client
+                .post(&format!("{}/biome/login", url))
+                .json(&UsernamePassword {
+                    username: "test_sessions@gmail.com".to_string(),
+                    hashed_password: "Admin2193!".to_string(),
+                })
+                .send()
+                .unwrap()
+                .json::<LoginResponse>()
+                .unwrap();
+
+            let sessions: serde_json::Value = client
+                .get(&format!("{}/biome/sessions", url))
+                .header("Authorization", format!("Bearer {}", second_login.token))
+                .send()
+                .unwrap()
+                .json()
+                .unwrap();
+            let sessions = sessions["data"].as_array().unwrap();
+            assert_eq!(sessions.len(), 2);
+            assert_eq!(sessions[0]["current"], false);
+            assert_eq!(sessions[1]["current"], true);
+            let first_session_id = sessions[0]["session_id"].as_str().unwrap();
+
+            let revoke_response = client
+                .delete(&format!("{}/biome/sessions/{}", url, first_session_id))
+                .header("Authorization", format!("Bearer {}", second_login.token))
+                .send()
+                .unwrap();
+            assert_eq!(revoke_response.status().as_u16(), 200);
+
+            let revoked_response = client
+                .get(&format!("{}/biome/sessions", url))
+                .header("Authorization", format!("Bearer {}", first_login.token))
+                .send()
+                .unwrap();
+            assert_eq!(revoked_response.status().as_u16(), 401);
+
+            let refresh_response = client
+                .post(&format!("{}/biome/token", url))
+                .header("Authorization", format!("Bearer {}", first_login.token))
+                .json(&PostToken {
+                    token: first_login.refresh_token.clone(),
+                })
+                .send()
+                .unwrap();
+            assert_eq!(refresh_response.status().as_u16(), 401);
+
+            let revoke_all_response = client
+                .delete(&format!("{}/biome/sessions", url))
+                .header("Authorization", format!("Bearer {}", second_login.token))
+                .send()
+                .unwrap();
+            assert_eq!(revoke_all_response.status().as_u16(), 200);
+
+            let revoked_response = client
+                .get(&format!("{}/biome/sessions", url))
+                .header("Authorization", format!("Bearer {}", second_login.token))
+                .send()
+                .unwrap();
+            assert_eq!(revoked_response.status().as_u16(), 401);
+        })
+    }
+
+    /// Test that an access token issued for a revoked session is rejected by the endpoints
+    /// protected by Biome's permission and API key guards, and not only by Biome's own endpoints.
+    ///
+    /// Procedure
+    ///
+    /// 1) Create a user, make them an administrator, and verify that their access token is
+    ///    accepted by GET /biome/roles and, with API keys, by GET /registry/nodes
+    /// 2) Revoke all of the user's sessions via DELETE /biome/sessions
+    /// 3) Verify that the access token is rejected by both endpoints with a 401
+    #[cfg(all(feature = "biome-sessions", feature = "biome-authorization"))]
+    #[test]
+    fn test_revoked_session_rejected_by_guards() {
+        run_admin_test(|url, client, make_admin| {
+            let login = create_and_authorize_user(
+                url,
+                &client,
+                "test_revoked_session_rejected_by_guards@gmail.com",
+                "Admin2193!",
+            );
+            make_admin(&login.user_id);
+
+            let guarded_statuses = || {
+                #[allow(unused_mut)]
+                let mut routes = vec!["biome/roles"];
+                #[cfg(all(feature = "biome-api-keys", feature = "registry"))]
+                {
+                    routes.push("registry/nodes");
+                }
+                routes
+                    .iter()
+                    .map(|route| {
+                        client
+                            .get(&format!("{}/{}", url, route))
+                            .header("Authorization", format!("Bearer {}", login.token))
+                            .send()
+                            .unwrap()
+                            .status()
+                            .as_u16()
+                    })
+                    .collect::<Vec<_>>()
+            };
+            assert!(guarded_statuses().iter().all(|status| *status == 200));
+
+            let revoke_all_response = client
+                .delete(&format!("{}/biome/sessions", url))
+                .header("Authorization", format!("Bearer {}", login.token))
+                .send()
+                .unwrap();
+            assert_eq!(revoke_all_response.status().as_u16(), 200);
+
+            assert!(guarded_statuses().iter().all(|status| *status == 401));
+        })
+    }
+
+    /// Test that a user can create, list and revoke API keys, and that the API key guards of the
+    /// registry's endpoints accept keys with the required scope as an alternative to an access
+    /// token.
+    ///
+    /// Procedure
+    ///
+    /// 1) Create a user, make them an administrator so that they have every permission, and
+    ///    verify that an API key with an unknown scope is rejected
+    /// 2) Create an API key with the `admin:read` scope and one with the `registry:write` scope
+    ///    via POST /biome/api_keys
+    /// 3) List the keys via GET /biome/api_keys and verify that both are returned without their
+    ///    secrets
+    /// 4) Verify that GET /registry/nodes accepts the user's access token and either key, and
+    ///    rejects an unknown key or a request without credentials with a 401
+    /// 5) Verify that POST /registry/nodes rejects the `admin:read` key with a 403 and passes the
+    ///    `registry:write` key through to the registry, which rejects the empty node with a 400
+    /// 6) Revoke the `admin:read` key via DELETE /biome/api_keys/{api_key_id} and verify that it
+    ///    is rejected with a 401
+    #[cfg(all(feature = "biome-api-keys", feature = "registry"))]
+    #[test]
+    fn test_api_keys() {
+        run_admin_test(|url, client, make_admin| {
+            let login =
+                create_and_authorize_user(url, &client, "test_api_keys@gmail.com", "Admin2193!");
+            make_admin(&login.user_id);
+
+            let invalid_response = client
+                .post(&format!("{}/biome/api_keys", url))
+                .header("Authorization", format!("Bearer {}", login.token))
+                .json(&serde_json::json!({
+                    "display_name": "invalid",
+                    "scopes": ["admin:everything"],
+                }))
+                .send()
+                .unwrap();
+            assert_eq!(invalid_response.status().as_u16(), 400);
+
+            let read_key: serde_json::Value = client
+                .post(&format!("{}/biome/api_keys", url))
+                .header("Authorization", format!("Bearer {}", login.token))
+                .json(&serde_json::json!({
+                    "display_name": "ci",
+                    "scopes": ["admin:read"],
+                    "expires_in_secs": 3600,
+                }))
+                .send()
+                .unwrap()
+                .json()
+                .unwrap();
+            let read_key_id = read_key["data"]["id"].as_str().unwrap();
+            let read_key = read_key["key"].as_str().unwrap();
+
+            let write_key: serde_json::Value = client
+                .post(&format!("{}/biome/api_keys", url))
+                .header("Authorization", format!("Bearer {}", login.token))
+                .json(&serde_json::json!({
+                    "display_name": "registry",
+                    "scopes": ["registry:write"],
+                }))
+                .send()
+                .unwrap()
+                .json()
+                .unwrap();
+            let write_key = write_key["key"].as_str().unwrap();
+
+            let api_keys: serde_json::Value = client
+                .get(&format!("{}/biome/api_keys", url))
+                .header("Authorization", format!("Bearer {}", login.token))
+                .send()
+                .unwrap()
+                .json()
+                .unwrap();
+            let api_keys = api_keys["data"].as_array().unwrap();
+            assert_eq!(api_keys.len(), 2);
+            assert_eq!(api_keys[0]["id"], read_key_id);
+            assert_eq!(api_keys[0]["scopes"], serde_json::json!(["admin:read"]));
+            assert!(api_keys[0]["expires"].is_u64());
+            assert!(api_keys[1]["expires"].is_null());
+            assert!(api_keys.iter().all(|api_key| api_key.get("key").is_none()));
+
+            let list_nodes_status = |auth_header: Option<String>| {
+                let request = client.get(&format!("{}/registry/nodes", url));
+                let request = match auth_header {
+                    Some(auth_header) => request.header("Authorization", auth_header),
+                    None => request,
+                };
+                request.send().unwrap().status().as_u16()
+            };
+            assert_eq!(
+                list_nodes_status(Some(format!("Bearer {}", login.token))),
+                200
+            );
+            assert_eq!(list_nodes_status(Some(format!("ApiKey {}", read_key))), 200);
+            assert_eq!(
+                list_nodes_status(Some(format!("ApiKey {}", write_key))),
+                200
+            );
+            assert_eq!(
+                list_nodes_status(Some(format!("ApiKey {}.secret", read_key_id))),
+                401
+            );
+            assert_eq!(list_nodes_status(None), 401);
+
+            let add_node_status = |key: &str| {
+                client
+                    .post(&format!("{}/registry/nodes", url))
+                    .header("Authorization", format!("ApiKey {}", key))
+                    .json(&serde_json::json!({}))
+                    .send()
+                    .unwrap()
+                    .status()
+                    .as_u16()
+            };
+            assert_eq!(add_node_status(read_key), 403);
+            assert_eq!(add_node_status(write_key), 400);
+
+            let revoke_response = client
+                .delete(&format!("{}/biome/api_keys/{}", url, read_key_id))
+                .header("Authorization", format!("Bearer {}", login.token))
+                .send()
+                .unwrap();
+            assert_eq!(revoke_response.status().as_u16(), 200);
+            assert_eq!(list_nodes_status(Some(format!("ApiKey {}", read_key))), 401);
+        })
+    }
+
+    /// Test that the API key guards require the user that an access token was issued to, or that
+    /// an API key belongs to, to have been granted the permission matching the required scope.
+    ///
+    /// Procedure
+    ///
+    /// 1) Create a user without any roles, and an API key with the `registry:write` scope
+    /// 2) Verify that GET /registry/nodes, which requires no scope, accepts the access token and
+    ///    the key
+    /// 3) Verify that POST /registry/nodes rejects both the access token and the key with a 403
+    /// 4) Make the user an administrator and verify that both are passed through to the registry,
+    ///    which rejects the empty node with a 400
+    #[cfg(all(
+        feature = "biome-api-keys",
+        feature = "biome-authorization",
+        feature = "registry"
+    ))]
+    #[test]
+    fn test_api_key_guard_permissions() {
+        run_admin_test(|url, client, make_admin| {
+            let login = create_and_authorize_user(
+                url,
+                &client,
+                "test_api_key_guard_permissions@gmail.com",
+                "Admin2193!",
+            );
+
+            let write_key: serde_json::Value = client
+                .post(&format!("{}/biome/api_keys", url))
+                .header("Authorization", format!("Bearer {}", login.token))
+                .json(&serde_json::json!({
+                    "display_name": "registry",
+                    "scopes": ["registry:write"],
+                }))
+                .send()
+                .unwrap()
+                .json()
+                .unwrap();
+            let write_key = write_key["key"].as_str().unwrap();
+
+            let auth_headers = vec![
+                format!("Bearer {}", login.token),
+                format!("ApiKey {}", write_key),
+            ];
+            let statuses = |method: reqwest::Method| {
+                auth_headers
+                    .iter()
+                    .map(|auth_header| {
+                        client
+                            .request(method.clone(), &format!("{}/registry/nodes", url))
+                            .header("Authorization", auth_header.as_str())
+                            .json(&serde_json::json!({}))
+                            .send()
+                            .unwrap()
+                            .status()
+                            .as_u16()
+                    })
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(statuses(reqwest::Method::GET), vec![200, 200]);
+            assert_eq!(statuses(reqwest::Method::POST), vec![403, 403]);
+
+            make_admin(&login.user_id);
+            assert_eq!(statuses(reqwest::Method::POST), vec![400, 400]);
+        })
+    }
+
+    /// Test role and group based authorization
+    ///
+    /// Procedure
+    ///
+    /// 1) Register two users and assign the admin role to the first
+    /// 2) Verify that the second user can fetch themselves but is forbidden from listing users,
+    ///    fetching or deleting the first user, and listing roles
+    /// 3) As the admin, verify that a role with an invalid permission is rejected, then create
+    ///    an `auditor` role with the `users:read` permission and an `auditors` group
+    /// 4) Add the second user to the group, assign the role to the group, and verify that the
+    ///    second user can now list users but still not delete the first user
+    /// 5) Verify that GET /biome/roles/auditor lists the group as an assignee
+    /// 6) Verify that the admin cannot remove the last assignment of the admin role
+    /// 7) Remove the group and verify that the second user can no longer list users
+    #[cfg(feature = "biome-authorization")]
+    #[test]
+    fn test_roles() {
+        run_admin_test(|url, client, make_admin| {
+            let admin = create_and_authorize_user(url, &client, "test_roles_admin", "Admin2193!");
+            make_admin(&admin.user_id);
+            let user = create_and_authorize_user(url, &client, "test_roles_user", "Admin2193!");
+
+            let status = |method: reqwest::Method,
+                          path: &str,
+                          login: &LoginResponse,
+                          body: Option<serde_json::Value>| {
+                let request = client
+                    .request(method, &format!("{}{}", url, path))
+                    .header("Authorization", format!("Bearer {}", login.token));
+                let request = match body {
+                    Some(body) => request.json(&body),
+                    None => request,
+                };
+                request.send().unwrap().status().as_u16()
+            };
+            let admin_path = format!("/biome/users/{}", admin.user_id);
+            let user_path = format!("/biome/users/{}", user.user_id);
+
+            assert_eq!(status(reqwest::Method::GET, &user_path, &user, None), 200);
+            assert_eq!(
+                status(reqwest::Method::GET, "/biome/users", &user, None),
+                403
+            );
+            assert_eq!(status(reqwest::Method::GET, &admin_path, &user, None), 403);
+            assert_eq!(
+                status(reqwest::Method::DELETE, &admin_path, &user, None),
+                403
+            );
+            assert_eq!(
+                status(reqwest::Method::GET, "/biome/roles", &user, None),
+                403
+            );
+
+            let invalid_role = serde_json::json!({
+                "name": "auditor",
+                "permissions": ["users:everything"],
+            });
+            assert_eq!(
+                status(
+                    reqwest::Method::POST,
+                    "/biome/roles",
+                    &admin,
+                    Some(invalid_role)
+                ),
+                400
+            );
+            let role = serde_json::json!({
+                "name": "auditor",
+                "permissions": ["users:read"],
+            });
+            assert_eq!(
+                status(reqwest::Method::POST, "/biome/roles", &admin, Some(role)),
+                200
+            );
+            let group = serde_json::json!({ "name": "auditors" });
+            assert_eq!(
+                status(reqwest::Method::POST, "/biome/groups", &admin, Some(group)),
+                200
+            );
+            assert_eq!(
+                status(
+                    reqwest::Method::PUT,
+                    &format!("/biome/groups/auditors/members/{}", user.user_id),
+                    &admin,
+                    None
+                ),
+                200
+            );
+            assert_eq!(
+                status(
+                    reqwest::Method::PUT,
+                    "/biome/roles/auditor/groups/auditors",
+                    &admin,
+                    None
+                ),
+                200
+            );
+
+            assert_eq!(
+                status(reqwest::Method::GET, "/biome/users", &user, None),
+                200
+            );
+            assert_eq!(status(reqwest::Method::GET, &admin_path, &user, None), 200);
+            assert_eq!(
+                status(reqwest::Method::DELETE, &admin_path, &user, None),
+                403
+            );
+
+            let auditor: serde_json::Value = client
+                .get(&format!("{}/biome/roles/auditor", url))
+                .header("Authorization", format!("Bearer {}", admin.token))
+                .send()
+                .unwrap()
+                .json()
+                .unwrap();
+            assert_eq!(
+                auditor["data"]["permissions"],
+                serde_json::json!(["users:read"])
+            );
+            assert_eq!(auditor["data"]["groups"], serde_json::json!(["auditors"]));
+
+            assert_eq!(
+                status(
+                    reqwest::Method::DELETE,
+                    &format!("/biome/roles/admin/users/{}", admin.user_id),
+                    &admin,
+                    None
+                ),
+                400
+            );
+
+            assert_eq!(
+                status(
+                    reqwest::Method::DELETE,
+                    "/biome/groups/auditors",
+                    &admin,
+                    None
+                ),
+                200
+            );
+            assert_eq!(
+                status(reqwest::Method::GET, "/biome/users", &user, None),
+                403
+            );
+        })
+    }
+
+    /// Test paging, searching and sorting users, and disabling and re-enabling a user
+    ///
+    /// Procedure
+    ///
+    /// 1) Register two users and assign the admin role to the first
+    /// 2) Verify that searching for the second user's username, ignoring case, returns only that
+    ///    user, along with their account details
+    /// 3) Verify that users can be sorted in descending order and paged, and that an invalid sort
+    ///    is rejected
+    /// 4) Verify that the second user is forbidden from disabling the admin
+    /// 5) Disable the second user and verify that they can no longer log in or refresh their
+    ///    access token, and that they are listed as disabled
+    /// 6) Re-enable the second user and verify that they can log in again
+    #[cfg(feature = "biome-user-admin")]
+    #[test]
+    fn test_user_admin() {
+        run_admin_test(|url, client, make_admin| {
+            let admin =
+                create_and_authorize_user(url, &client, "test_user_admin_admin", "Admin2193!");
+            make_admin(&admin.user_id);
+            let user =
+                create_and_authorize_user(url, &client, "test_user_admin_user", "Admin2193!");
+
+            let list = |query: &str| {
+                client
+                    .get(&format!("{}/biome/users?{}", url, query))
+                    .header("Authorization", format!("Bearer {}", admin.token))
+                    .send()
+                    .unwrap()
+            };
+            let login = || {
+                client
+                    .post(&format!("{}/biome/login", url))
+                    .json(&UsernamePassword {
+                        username: "test_user_admin_user".to_string(),
+                        hashed_password: "Admin2193!".to_string(),
+                    })
+                    .send()
+                    .unwrap()
+                    .status()
+                    .as_u16()
+            };
+
+            let users: serde_json::Value = list("filter=ADMIN_USER").json().unwrap();
+            assert_eq!(users["paging"]["total"], 1);
+            assert_eq!(users["data"][0]["user_id"], user.user_id.as_str());
+            assert_eq!(users["data"][0]["username"], "test_user_admin_user");
+            assert_eq!(users["data"][0]["enabled"], true);
+            assert!(users["data"][0]["created"].is_u64());
+            assert!(users["data"][0]["last_login"].is_u64());
+
+            let users: serde_json::Value = list("sort=-username&limit=1").json().unwrap();
+            assert_eq!(users["paging"]["total"], 2);
+            assert_eq!(users["data"].as_array().unwrap().len(), 1);
+            assert_eq!(users["data"][0]["username"], "test_user_admin_user");
+            assert!(users["paging"]["next"]
+                .as_str()
+                .unwrap()
+                .contains("sort=-username"));
+            assert_eq!(list("sort=password").status().as_u16(), 400);
+
+            let forbidden_response = client
+                .put(&format!("{}/biome/users/{}/disable", url, admin.user_id))
+                .header("Authorization", format!("Bearer {}", user.token))
+                .send()
+                .unwrap();
+            assert_eq!(forbidden_response.status().as_u16(), 403);
+
+            let disable_response = client
+                .put(&format!("{}/biome/users/{}/disable", url, user.user_id))
+                .header("Authorization", format!("Bearer {}", admin.token))
+                .send()
+                .unwrap();
+            assert_eq!(disable_response.status().as_u16(), 200);
+
+            assert_eq!(login(), 403);
+            let token_response = client
+                .post(&format!("{}/biome/token", url))
+                .header("Authorization", format!("Bearer {}", user.token))
+                .json(&PostToken {
+                    token: user.refresh_token.clone(),
+                })
+                .send()
+                .unwrap();
+            assert_eq!(token_response.status().as_u16(), 403);
+
+            let users: serde_json::Value = list("filter=admin_user").json().unwrap();
+            assert_eq!(users["data"][0]["enabled"], false);
+
+            let enable_response = client
+                .put(&format!("{}/biome/users/{}/enable", url, user.user_id))
+                .header("Authorization", format!("Bearer {}", admin.token))
+                .send()
+                .unwrap();
+            assert_eq!(enable_response.status().as_u16(), 200);
+            assert_eq!(login(), 200);
+        })
+    }
 }
@@ -0,0 +1,56 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines the payloads used to create API keys and the representation of API keys in responses.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::biome::credentials::api_keys::ApiKey;
+
+#[derive(Deserialize)]
+pub(crate) struct NewApiKey {
+    pub display_name: String,
+    pub scopes: Vec<String>,
+    /// How long the key may be used for, in seconds; the key does not expire if this is omitted
+    pub expires_in_secs: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct ResponseApiKey<'a> {
+    id: &'a str,
+    display_name: &'a str,
+    scopes: Vec<&'static str>,
+    /// The time the key was created, in milliseconds since the Unix epoch
+    created: u64,
+    /// The time the key expires, in milliseconds since the Unix epoch
+    expires: Option<u64>,
+}
+
+impl<'a> From<&'a ApiKey> for ResponseApiKey<'a> {
+    fn from(api_key: &'a ApiKey) -> Self {
+        ResponseApiKey {
+            id: &api_key.id,
+            display_name: &api_key.display_name,
+            scopes: api_key.scopes.iter().map(|scope| scope.as_str()).collect(),
+            created: to_millis(api_key.created),
+            expires: api_key.expires.map(to_millis),
+        }
+    }
+}
+
+fn to_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
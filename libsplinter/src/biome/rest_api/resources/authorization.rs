@@ -0,0 +1,90 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines the payloads used to manage roles and groups and their representation in responses.
+
+use crate::biome::authorization::{Assignee, Group, Role};
+
+#[derive(Deserialize)]
+pub(crate) struct NewRole {
+    pub name: String,
+    pub permissions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct NewGroup {
+    pub name: String,
+}
+
+#[derive(Serialize)]
+pub(crate) struct ResponseRole<'a> {
+    name: &'a str,
+    permissions: Vec<&'static str>,
+}
+
+impl<'a> From<&'a Role> for ResponseRole<'a> {
+    fn from(role: &'a Role) -> Self {
+        ResponseRole {
+            name: &role.name,
+            permissions: role
+                .permissions
+                .iter()
+                .map(|permission| permission.as_str())
+                .collect(),
+        }
+    }
+}
+
+/// A role along with the users and groups it is assigned to
+#[derive(Serialize)]
+pub(crate) struct ResponseRoleAssignments<'a> {
+    #[serde(flatten)]
+    role: ResponseRole<'a>,
+    users: Vec<&'a str>,
+    groups: Vec<&'a str>,
+}
+
+impl<'a> ResponseRoleAssignments<'a> {
+    pub fn new(role: &'a Role, assignees: &'a [Assignee]) -> Self {
+        let mut users = vec![];
+        let mut groups = vec![];
+        for assignee in assignees {
+            match assignee {
+                Assignee::User(user_id) => users.push(user_id.as_str()),
+                Assignee::Group(name) => groups.push(name.as_str()),
+            }
+        }
+
+        ResponseRoleAssignments {
+            role: ResponseRole::from(role),
+            users,
+            groups,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct ResponseGroup<'a> {
+    name: &'a str,
+    members: &'a [String],
+}
+
+impl<'a> From<&'a Group> for ResponseGroup<'a> {
+    fn from(group: &'a Group) -> Self {
+        ResponseGroup {
+            name: &group.name,
+            members: &group.members,
+        }
+    }
+}
@@ -0,0 +1,27 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines the payloads used to manage multi-factor authentication and to complete a login with
+//! a second factor.
+
+#[derive(Deserialize)]
+pub(crate) struct MfaCode {
+    pub code: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct MfaLogin {
+    pub mfa_token: String,
+    pub code: String,
+}
@@ -14,13 +14,31 @@
 
 //! Provides structures for the REST resources.
 
-#[cfg(any(feature = "biome-key-management", feature = "biome-credentials"))]
+#[cfg(feature = "biome-api-keys")]
+pub(in crate::biome::rest_api) mod api_keys;
+#[cfg(feature = "biome-authorization")]
+pub(in crate::biome::rest_api) mod authorization;
+#[cfg(any(
+    feature = "biome-key-management",
+    feature = "biome-credentials",
+    feature = "biome-notifications"
+))]
 pub(in crate::biome::rest_api) mod authorize;
 #[cfg(feature = "biome-credentials")]
 pub(in crate::biome::rest_api) mod credentials;
 #[cfg(feature = "biome-key-management")]
 pub(in crate::biome::rest_api) mod key_management;
+#[cfg(feature = "biome-mfa")]
+pub(in crate::biome::rest_api) mod mfa;
+#[cfg(feature = "biome-notifications")]
+pub(in crate::biome::rest_api) mod notifications;
+#[cfg(feature = "biome-password-reset")]
+pub(in crate::biome::rest_api) mod password_reset;
+#[cfg(feature = "biome-sessions")]
+pub(in crate::biome::rest_api) mod sessions;
 #[cfg(feature = "biome-credentials")]
 pub(in crate::biome::rest_api) mod token;
 #[cfg(all(feature = "biome-key-management", feature = "biome-credentials"))]
 pub(in crate::biome::rest_api) mod user;
+#[cfg(feature = "biome-user-admin")]
+pub(in crate::biome::rest_api) mod user_admin;
@@ -0,0 +1,50 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines structures used in notifications.
+
+use std::collections::HashMap;
+use std::time::UNIX_EPOCH;
+
+use crate::biome::notifications::UserNotification;
+
+#[derive(Serialize)]
+pub(crate) struct ResponseNotification<'a> {
+    id: &'a str,
+    payload_title: &'a str,
+    payload_body: &'a str,
+    /// The time the notification was created, in milliseconds since the Unix epoch
+    created: u64,
+    properties: &'a HashMap<String, String>,
+    unread: bool,
+}
+
+impl<'a> From<&'a UserNotification> for ResponseNotification<'a> {
+    fn from(notification: &'a UserNotification) -> Self {
+        let created = notification
+            .created
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or_default();
+
+        ResponseNotification {
+            id: &notification.id,
+            payload_title: &notification.payload_title,
+            payload_body: &notification.payload_body,
+            created,
+            properties: &notification.properties,
+            unread: notification.unread,
+        }
+    }
+}
@@ -0,0 +1,32 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines the payloads used to reset and change a user's password.
+
+#[derive(Deserialize)]
+pub(crate) struct PasswordResetRequest {
+    pub username: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct PasswordReset {
+    pub token: String,
+    pub new_password: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct PasswordChange {
+    pub hashed_password: String,
+    pub new_password: String,
+}
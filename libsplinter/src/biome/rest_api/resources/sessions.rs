@@ -0,0 +1,51 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines structures used in session management.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::biome::refresh_tokens::store::Session;
+
+#[derive(Serialize)]
+pub(crate) struct ResponseSession<'a> {
+    session_id: &'a str,
+    /// The time the session was created, in milliseconds since the Unix epoch
+    created: u64,
+    /// The time the session's refresh token was last used, in milliseconds since the Unix epoch
+    last_used: u64,
+    user_agent: Option<&'a str>,
+    ip_address: Option<&'a str>,
+    /// Whether the session is the one the request was made with
+    current: bool,
+}
+
+impl<'a> ResponseSession<'a> {
+    pub fn new(session: &'a Session, current_session_id: Option<&str>) -> Self {
+        ResponseSession {
+            session_id: &session.session_id,
+            created: to_millis(session.created),
+            last_used: to_millis(session.last_used),
+            user_agent: session.user_agent.as_deref(),
+            ip_address: session.ip_address.as_deref(),
+            current: current_session_id == Some(session.session_id.as_str()),
+        }
+    }
+}
+
+fn to_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
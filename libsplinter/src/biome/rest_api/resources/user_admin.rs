@@ -0,0 +1,56 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines structures used in user administration.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::biome::user::store::User;
+use crate::rest_api::paging::Paging;
+
+#[derive(Serialize)]
+pub(crate) struct ResponseUser<'a> {
+    user_id: String,
+    /// The user's username, if the user has credentials
+    username: Option<&'a str>,
+    /// The time the user was created, in milliseconds since the Unix epoch
+    created: u64,
+    /// The time the user last logged in, in milliseconds since the Unix epoch
+    last_login: Option<u64>,
+    enabled: bool,
+}
+
+impl<'a> ResponseUser<'a> {
+    pub fn new(user: &User, username: Option<&'a str>) -> Self {
+        ResponseUser {
+            user_id: user.id(),
+            username,
+            created: to_millis(user.created()),
+            last_login: user.last_login().map(to_millis),
+            enabled: user.is_enabled(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct ListUsersResponse<'a> {
+    pub data: Vec<ResponseUser<'a>>,
+    pub paging: Paging,
+}
+
+fn to_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
@@ -16,6 +16,9 @@ pub(in crate::biome) mod models;
 mod operations;
 pub(in crate::biome) mod schema;
 
+#[cfg(feature = "biome-user-admin")]
+use std::time::SystemTime;
+
 use diesel::r2d2::{ConnectionManager, Pool};
 
 use super::{User, UserStore, UserStoreError};
@@ -24,6 +27,10 @@ use operations::add_user::UserStoreAddUserOperation as _;
 use operations::delete_user::UserStoreDeleteUserOperation as _;
 use operations::fetch_user::UserStoreFetchUserOperation as _;
 use operations::list_users::UserStoreListUsersOperation as _;
+#[cfg(feature = "biome-user-admin")]
+use operations::set_user_enabled::UserStoreSetUserEnabledOperation as _;
+#[cfg(feature = "biome-user-admin")]
+use operations::update_last_login::UserStoreUpdateLastLoginOperation as _;
 use operations::update_user::UserStoreUpdateUserOperation as _;
 use operations::UserStoreOperations;
 
@@ -67,6 +74,16 @@ impl UserStore for DieselUserStore<diesel::pg::PgConnection> {
     fn list_users(&self) -> Result<Vec<User>, UserStoreError> {
         UserStoreOperations::new(&*self.connection_pool.get()?).list_users()
     }
+
+    #[cfg(feature = "biome-user-admin")]
+    fn set_user_enabled(&self, id: &str, enabled: bool) -> Result<(), UserStoreError> {
+        UserStoreOperations::new(&*self.connection_pool.get()?).set_user_enabled(id, enabled)
+    }
+
+    #[cfg(feature = "biome-user-admin")]
+    fn update_last_login(&self, id: &str, last_login: SystemTime) -> Result<(), UserStoreError> {
+        UserStoreOperations::new(&*self.connection_pool.get()?).update_last_login(id, last_login)
+    }
 }
 
 #[cfg(feature = "sqlite")]
@@ -90,6 +107,16 @@ impl UserStore for DieselUserStore<diesel::sqlite::SqliteConnection> {
     fn list_users(&self) -> Result<Vec<User>, UserStoreError> {
         UserStoreOperations::new(&*self.connection_pool.get()?).list_users()
     }
+
+    #[cfg(feature = "biome-user-admin")]
+    fn set_user_enabled(&self, id: &str, enabled: bool) -> Result<(), UserStoreError> {
+        UserStoreOperations::new(&*self.connection_pool.get()?).set_user_enabled(id, enabled)
+    }
+
+    #[cfg(feature = "biome-user-admin")]
+    fn update_last_login(&self, id: &str, last_login: SystemTime) -> Result<(), UserStoreError> {
+        UserStoreOperations::new(&*self.connection_pool.get()?).update_last_login(id, last_login)
+    }
 }
 
 #[cfg(all(test, feature = "sqlite"))]
@@ -205,6 +232,67 @@ pub mod tests {
         assert!(!users.contains(&user3));
     }
 
+    /// Verify that a SQLite-backed `DieselUserStore` correctly supports disabling users and
+    /// recording their logins.
+    ///
+    /// 1. Create a connection pool for an in-memory SQLite database and run migrations.
+    /// 2. Create the `DieselUserStore`.
+    /// 3. Add a user and verify that it is enabled and has never logged in.
+    /// 4. Disable the user and record a login, then verify that the fetched user reflects both.
+    /// 5. Re-enable the user and verify that the fetched user is enabled.
+    /// 6. Verify that both methods return a `UserStoreError::NotFoundError` for a non-existent
+    ///    user.
+    #[cfg(feature = "biome-user-admin")]
+    #[test]
+    fn sqlite_enabled_and_last_login() {
+        let pool = create_connection_pool_and_migrate();
+
+        let store = DieselUserStore::new(pool);
+
+        let user = User::new("user1");
+        store.add_user(user.clone()).expect("Failed to add user1");
+
+        let fetched = store.fetch_user("user1").expect("Failed to fetch user1");
+        assert_eq!(fetched.created(), user.created());
+        assert!(fetched.is_enabled());
+        assert_eq!(fetched.last_login(), None);
+
+        let last_login = fetched.created() + std::time::Duration::from_secs(60);
+        store
+            .set_user_enabled("user1", false)
+            .expect("Failed to disable user1");
+        store
+            .update_last_login("user1", last_login)
+            .expect("Failed to update last login");
+
+        let fetched = store.fetch_user("user1").expect("Failed to fetch user1");
+        assert!(!fetched.is_enabled());
+        assert_eq!(fetched.last_login(), Some(last_login));
+
+        store
+            .set_user_enabled("user1", true)
+            .expect("Failed to enable user1");
+        assert!(store
+            .fetch_user("user1")
+            .expect("Failed to fetch user1")
+            .is_enabled());
+
+        match store.set_user_enabled("user2", false) {
+            Err(UserStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(UserStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+        match store.update_last_login("user2", last_login) {
+            Err(UserStoreError::NotFoundError(_)) => {}
+            res => panic!(
+                "Expected Err(UserStoreError::NotFoundError), got {:?} instead",
+                res
+            ),
+        }
+    }
+
     /// Creates a conneciton pool for an in-memory SQLite database with only a single connection
     /// available. Each connection is backed by a different in-memory SQLite database, so limiting
     /// the pool to a single connection insures that the same DB is used for all operations.
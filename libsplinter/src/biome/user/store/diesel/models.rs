@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use crate::biome::user::store::diesel::schema::*;
 use crate::biome::user::store::diesel::User;
 
@@ -20,16 +22,38 @@ use crate::biome::user::store::diesel::User;
 #[primary_key(id)]
 pub struct UserModel {
     pub id: String,
+    pub created: i64,
+    pub last_login: Option<i64>,
+    pub enabled: bool,
 }
 
 impl From<UserModel> for User {
     fn from(user: UserModel) -> Self {
-        User { id: user.id }
+        User {
+            id: user.id,
+            created: UNIX_EPOCH + Duration::from_millis(user.created as u64),
+            last_login: user
+                .last_login
+                .map(|last_login| UNIX_EPOCH + Duration::from_millis(last_login as u64)),
+            enabled: user.enabled,
+        }
     }
 }
 
 impl Into<UserModel> for User {
     fn into(self) -> UserModel {
-        UserModel { id: self.id }
+        UserModel {
+            id: self.id,
+            created: to_millis(self.created),
+            last_login: self.last_login.map(to_millis),
+            enabled: self.enabled,
+        }
     }
 }
+
+/// Converts a time to the milliseconds since the Unix epoch, as it is stored
+pub(super) fn to_millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or_default()
+}
@@ -27,6 +27,9 @@ impl<'a, C> UserStoreDeleteUserOperation for UserStoreOperations<'a, C>
 where
     C: diesel::Connection,
     String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+    bool: diesel::deserialize::FromSql<diesel::sql_types::Bool, C::Backend>,
+    C::Backend: diesel::sql_types::HasSqlType<diesel::sql_types::Bool>,
 {
     fn delete_user(&self, user_id: &str) -> Result<(), UserStoreError> {
         let user = splinter_user::table
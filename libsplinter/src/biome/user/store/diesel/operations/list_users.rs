@@ -28,6 +28,9 @@ impl<'a, C> UserStoreListUsersOperation for UserStoreOperations<'a, C>
 where
     C: diesel::Connection,
     String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+    bool: diesel::deserialize::FromSql<diesel::sql_types::Bool, C::Backend>,
+    C::Backend: diesel::sql_types::HasSqlType<diesel::sql_types::Bool>,
 {
     fn list_users(&self) -> Result<Vec<User>, UserStoreError> {
         let users = splinter_user::table
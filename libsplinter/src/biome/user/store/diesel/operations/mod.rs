@@ -16,6 +16,10 @@ pub(super) mod add_user;
 pub(super) mod delete_user;
 pub(super) mod fetch_user;
 pub(super) mod list_users;
+#[cfg(feature = "biome-user-admin")]
+pub(super) mod set_user_enabled;
+#[cfg(feature = "biome-user-admin")]
+pub(super) mod update_last_login;
 pub(super) mod update_user;
 
 pub(super) struct UserStoreOperations<'a, C> {
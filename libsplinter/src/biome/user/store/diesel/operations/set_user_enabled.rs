@@ -0,0 +1,48 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::UserStoreOperations;
+use crate::biome::user::store::diesel::schema::splinter_user;
+use crate::biome::user::store::error::UserStoreError;
+
+use diesel::{dsl::update, prelude::*};
+
+pub(in crate::biome::user) trait UserStoreSetUserEnabledOperation {
+    fn set_user_enabled(&self, user_id: &str, enabled: bool) -> Result<(), UserStoreError>;
+}
+
+impl<'a, C> UserStoreSetUserEnabledOperation for UserStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    bool: diesel::serialize::ToSql<diesel::sql_types::Bool, C::Backend>,
+    C::Backend: diesel::sql_types::HasSqlType<diesel::sql_types::Bool>,
+{
+    fn set_user_enabled(&self, user_id: &str, enabled: bool) -> Result<(), UserStoreError> {
+        let updated = update(splinter_user::table.find(user_id))
+            .set(splinter_user::enabled.eq(enabled))
+            .execute(self.conn)
+            .map_err(|err| UserStoreError::OperationError {
+                context: "Failed to update user".to_string(),
+                source: Box::new(err),
+            })?;
+
+        if updated == 0 {
+            return Err(UserStoreError::NotFoundError(format!(
+                "Failed to find user: {}",
+                user_id
+            )));
+        }
+        Ok(())
+    }
+}
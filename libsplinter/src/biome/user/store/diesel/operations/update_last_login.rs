@@ -0,0 +1,56 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::SystemTime;
+
+use super::UserStoreOperations;
+use crate::biome::user::store::diesel::{models::to_millis, schema::splinter_user};
+use crate::biome::user::store::error::UserStoreError;
+
+use diesel::{dsl::update, prelude::*};
+
+pub(in crate::biome::user) trait UserStoreUpdateLastLoginOperation {
+    fn update_last_login(
+        &self,
+        user_id: &str,
+        last_login: SystemTime,
+    ) -> Result<(), UserStoreError>;
+}
+
+impl<'a, C> UserStoreUpdateLastLoginOperation for UserStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+{
+    fn update_last_login(
+        &self,
+        user_id: &str,
+        last_login: SystemTime,
+    ) -> Result<(), UserStoreError> {
+        let updated = update(splinter_user::table.find(user_id))
+            .set(splinter_user::last_login.eq(Some(to_millis(last_login))))
+            .execute(self.conn)
+            .map_err(|err| UserStoreError::OperationError {
+                context: "Failed to update user".to_string(),
+                source: Box::new(err),
+            })?;
+
+        if updated == 0 {
+            return Err(UserStoreError::NotFoundError(format!(
+                "Failed to find user: {}",
+                user_id
+            )));
+        }
+        Ok(())
+    }
+}
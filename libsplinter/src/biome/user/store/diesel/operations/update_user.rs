@@ -28,9 +28,14 @@ impl<'a, C> UserStoreUpdateUserOperation for UserStoreOperations<'a, C>
 where
     C: diesel::Connection,
     String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+    bool: diesel::deserialize::FromSql<diesel::sql_types::Bool, C::Backend>,
+    bool: diesel::serialize::ToSql<diesel::sql_types::Bool, C::Backend>,
+    C::Backend: diesel::sql_types::HasSqlType<diesel::sql_types::Bool>,
 {
     fn update_user(&self, updated_user: User) -> Result<(), UserStoreError> {
-        let id = updated_user.id();
+        let updated_user: UserModel = updated_user.into();
+        let id = updated_user.id.clone();
         let user = splinter_user::table
             .find(&id)
             .first::<UserModel>(self.conn)
@@ -49,7 +54,12 @@ where
         }
 
         update(splinter_user::table.filter(splinter_user::id.eq(&id)))
-            .set((splinter_user::id.eq(&id),))
+            .set((
+                splinter_user::id.eq(&id),
+                splinter_user::created.eq(updated_user.created),
+                splinter_user::last_login.eq(updated_user.last_login),
+                splinter_user::enabled.eq(updated_user.enabled),
+            ))
             .execute(self.conn)
             .map(|_| ())
             .map_err(|err| UserStoreError::OperationError {
@@ -15,5 +15,8 @@
 table! {
     splinter_user (id) {
         id -> Text,
+        created -> BigInt,
+        last_login -> Nullable<BigInt>,
+        enabled -> Bool,
     }
 }
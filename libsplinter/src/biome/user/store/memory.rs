@@ -14,6 +14,8 @@
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+#[cfg(feature = "biome-user-admin")]
+use std::time::SystemTime;
 
 #[cfg(feature = "biome-credentials")]
 use crate::biome::credentials::store::{memory::MemoryCredentialsStore, CredentialsStore};
@@ -136,4 +138,46 @@ impl UserStore for MemoryUserStore {
 
         Ok(inner.iter().map(|(_, v)| v.clone()).collect())
     }
+
+    #[cfg(feature = "biome-user-admin")]
+    fn set_user_enabled(&self, id: &str, enabled: bool) -> Result<(), UserStoreError> {
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|_| UserStoreError::StorageError {
+                context: "Cannot access user store: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+
+        if let Some(user) = inner.get_mut(id) {
+            user.enabled = enabled;
+            Ok(())
+        } else {
+            Err(UserStoreError::NotFoundError(format!(
+                "User {} not found.",
+                id
+            )))
+        }
+    }
+
+    #[cfg(feature = "biome-user-admin")]
+    fn update_last_login(&self, id: &str, last_login: SystemTime) -> Result<(), UserStoreError> {
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|_| UserStoreError::StorageError {
+                context: "Cannot access user store: mutex lock poisoned".to_string(),
+                source: None,
+            })?;
+
+        if let Some(user) = inner.get_mut(id) {
+            user.last_login = Some(last_login);
+            Ok(())
+        } else {
+            Err(UserStoreError::NotFoundError(format!(
+                "User {} not found.",
+                id
+            )))
+        }
+    }
 }
@@ -19,12 +19,17 @@ pub(in crate::biome) mod diesel;
 mod error;
 pub(in crate::biome) mod memory;
 
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 pub use error::UserStoreError;
 
 /// Represents a user of a splinter application
 #[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct User {
     id: String,
+    created: SystemTime,
+    last_login: Option<SystemTime>,
+    enabled: bool,
 }
 
 impl User {
@@ -34,8 +39,15 @@ impl User {
     ///
     /// * `user_id`: unique identifier for the user being created
     pub fn new(user_id: &str) -> Self {
+        // Times are stored with millisecond precision
+        let since_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
         User {
             id: user_id.to_string(),
+            created: UNIX_EPOCH + Duration::from_millis(since_epoch.as_millis() as u64),
+            last_login: None,
+            enabled: true,
         }
     }
 
@@ -43,6 +55,21 @@ impl User {
     pub fn id(&self) -> String {
         self.id.to_string()
     }
+
+    /// Returns the time the user was created.
+    pub fn created(&self) -> SystemTime {
+        self.created
+    }
+
+    /// Returns the time the user last logged in, if the user has ever logged in.
+    pub fn last_login(&self) -> Option<SystemTime> {
+        self.last_login
+    }
+
+    /// Returns whether the user is allowed to log in.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
 }
 
 /// Defines methods for CRUD operations and fetching and listing users
@@ -78,6 +105,24 @@ pub trait UserStore: Send + Sync {
 
     /// List all users from the underlying storage
     fn list_users(&self) -> Result<Vec<User>, UserStoreError>;
+
+    /// Enables or disables a user; a disabled user is not allowed to log in
+    ///
+    /// # Arguments
+    ///
+    ///  * `id` - The unique id of the user to be updated
+    ///  * `enabled` - Whether the user is enabled
+    #[cfg(feature = "biome-user-admin")]
+    fn set_user_enabled(&self, id: &str, enabled: bool) -> Result<(), UserStoreError>;
+
+    /// Records the time a user last logged in
+    ///
+    /// # Arguments
+    ///
+    ///  * `id` - The unique id of the user that logged in
+    ///  * `last_login` - The time of the login
+    #[cfg(feature = "biome-user-admin")]
+    fn update_last_login(&self, id: &str, last_login: SystemTime) -> Result<(), UserStoreError>;
 }
 
 pub trait CloneBoxUserStore: UserStore {
@@ -113,4 +158,14 @@ where
     fn list_users(&self) -> Result<Vec<User>, UserStoreError> {
         (**self).list_users()
     }
+
+    #[cfg(feature = "biome-user-admin")]
+    fn set_user_enabled(&self, id: &str, enabled: bool) -> Result<(), UserStoreError> {
+        (**self).set_user_enabled(id, enabled)
+    }
+
+    #[cfg(feature = "biome-user-admin")]
+    fn update_last_login(&self, id: &str, last_login: SystemTime) -> Result<(), UserStoreError> {
+        (**self).update_last_login(id, last_login)
+    }
 }
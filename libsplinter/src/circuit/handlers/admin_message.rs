@@ -13,6 +13,8 @@
 // limitations under the License.
 
 use crate::circuit::handlers::create_message;
+#[cfg(feature = "circuit-relay")]
+use crate::circuit::NodeRoute;
 use crate::circuit::SplinterState;
 use crate::network::dispatch::{DispatchError, Handler, MessageContext, MessageSender, PeerId};
 use crate::protos::circuit::{
@@ -131,17 +133,41 @@ impl AdminDirectMessageHandler {
             let node_id = &recipient[ADMIN_SERVICE_ID_PREFIX.len()..];
             // If the service is on this node send message to the service, otherwise
             // send the message to the node the service is connected to
-            let target_node = if node_id != self.node_id {
-                node_id
+            if node_id != self.node_id {
+                self.route_to_node(&msg, context, node_id)?
+            } else if let Some(relay_error) = self.check_relay(&msg, context.source_peer_id())? {
+                let msg_bytes = create_circuit_error_msg(
+                    &msg,
+                    CircuitError_Error::ERROR_INVALID_RELAY,
+                    relay_error,
+                )?;
+
+                let network_msg_bytes =
+                    create_message(msg_bytes, CircuitMessageType::CIRCUIT_ERROR_MESSAGE)?;
+                (network_msg_bytes, context.source_peer_id().to_string())
+            } else if !is_authentic_sender(&msg, context.source_peer_id()) {
+                // the admin service trusts the sender to be the admin service of the node the
+                // message came from
+                let msg_bytes = create_circuit_error_msg(
+                    &msg,
+                    CircuitError_Error::ERROR_SENDER_NOT_IN_CIRCUIT_ROSTER,
+                    format!(
+                        "Sender {} is not the admin service of {}",
+                        msg_sender,
+                        context.source_peer_id()
+                    ),
+                )?;
+
+                let network_msg_bytes =
+                    create_message(msg_bytes, CircuitMessageType::CIRCUIT_ERROR_MESSAGE)?;
+                (network_msg_bytes, context.source_peer_id().to_string())
             } else {
                 // The internal admin service is at the node id with an identical name
-                recipient
-            };
-
-            let msg_bytes = context.message_bytes().to_vec();
-            let network_msg_bytes =
-                create_message(msg_bytes, CircuitMessageType::ADMIN_DIRECT_MESSAGE)?;
-            (network_msg_bytes, target_node.to_string())
+                let msg_bytes = self.open_relayed(&msg, context)?;
+                let network_msg_bytes =
+                    create_message(msg_bytes, CircuitMessageType::ADMIN_DIRECT_MESSAGE)?;
+                (network_msg_bytes, recipient.to_string())
+            }
         } else {
             // if the circuit does not exist, send circuit error
             let msg_bytes = create_circuit_error_msg(
@@ -156,6 +182,255 @@ impl AdminDirectMessageHandler {
         };
         Ok(response)
     }
+
+    /// Returns the message and peer ID for sending an admin message to the given node.
+    ///
+    /// A message addressed to the node through this node as its relay is forwarded with its
+    /// origin recorded, if either node registered a circuit or proposal naming both as members
+    /// with this node. Otherwise, if the node is reached through a relay, the message is sent to
+    /// the relay node, which forwards it to the node. Its payload is encrypted for the node once
+    /// the node's public key has been received, which it sends in reply to the first message.
+    #[cfg(feature = "circuit-relay")]
+    fn route_to_node(
+        &self,
+        msg: &AdminDirectMessage,
+        context: &MessageContext<PeerId, CircuitMessageType>,
+        node_id: &str,
+    ) -> Result<(Vec<u8>, String), DispatchError> {
+        let source_peer_id = context.source_peer_id();
+        if msg.get_relay_destination() == node_id && source_peer_id != node_id {
+            if !self
+                .state
+                .relays_admin_messages(source_peer_id, node_id)
+                .map_err(|err| DispatchError::HandleError(err.context()))?
+            {
+                let msg_bytes = create_circuit_error_msg(
+                    msg,
+                    CircuitError_Error::ERROR_INVALID_RELAY,
+                    format!(
+                        "No circuit relays admin messages from {} to {} through this node",
+                        source_peer_id, node_id
+                    ),
+                )?;
+                return Ok((
+                    create_message(msg_bytes, CircuitMessageType::CIRCUIT_ERROR_MESSAGE)?,
+                    source_peer_id.to_string(),
+                ));
+            }
+
+            debug!(
+                "Relaying admin message from {} to {}",
+                source_peer_id, node_id
+            );
+
+            let mut relayed_msg = msg.clone();
+            relayed_msg.set_relay_origin(source_peer_id.to_string());
+            let network_msg_bytes = create_message(
+                relayed_msg.write_to_bytes()?,
+                CircuitMessageType::ADMIN_DIRECT_MESSAGE,
+            )?;
+            return Ok((network_msg_bytes, node_id.to_string()));
+        }
+
+        if let Some(relay_node) = self
+            .state
+            .relay_route(node_id)
+            .map_err(|err| DispatchError::HandleError(err.context()))?
+        {
+            let mut relayed_msg = msg.clone();
+            relayed_msg.set_relay_destination(node_id.to_string());
+            // The origin is only set by the relay node
+            relayed_msg.clear_relay_origin();
+            relayed_msg.set_relay_public_key(
+                self.state
+                    .relay_public_key()
+                    .map_err(|err| DispatchError::HandleError(err.context()))?,
+            );
+            if let Some(payload) = self
+                .state
+                .encrypt_relayed_payload(
+                    node_id,
+                    msg.get_circuit(),
+                    msg.get_sender(),
+                    msg.get_recipient(),
+                    msg.get_payload(),
+                )
+                .map_err(|err| DispatchError::HandleError(err.context()))?
+            {
+                relayed_msg.set_payload(payload);
+                relayed_msg.set_relay_encrypted(true);
+            }
+
+            let network_msg_bytes = create_message(
+                relayed_msg.write_to_bytes()?,
+                CircuitMessageType::ADMIN_DIRECT_MESSAGE,
+            )?;
+            return Ok((network_msg_bytes, relay_node));
+        }
+
+        let network_msg_bytes = create_message(
+            context.message_bytes().to_vec(),
+            CircuitMessageType::ADMIN_DIRECT_MESSAGE,
+        )?;
+        Ok((network_msg_bytes, node_id.to_string()))
+    }
+
+    #[cfg(not(feature = "circuit-relay"))]
+    fn route_to_node(
+        &self,
+        _msg: &AdminDirectMessage,
+        context: &MessageContext<PeerId, CircuitMessageType>,
+        node_id: &str,
+    ) -> Result<(Vec<u8>, String), DispatchError> {
+        let network_msg_bytes = create_message(
+            context.message_bytes().to_vec(),
+            CircuitMessageType::ADMIN_DIRECT_MESSAGE,
+        )?;
+        Ok((network_msg_bytes, node_id.to_string()))
+    }
+
+    /// Checks a message that was forwarded by a relay node, returning the reason it is invalid
+    /// if the sender is not the admin service of the origin node, or if no known circuit or
+    /// proposal names the relay node as the route to the origin node.
+    ///
+    /// A node that is not a member of any known circuit or proposal, such as a node proposing a
+    /// new circuit, has the relay node recorded as its route, so that replies are sent back
+    /// through the same relay until the proposal is known.
+    #[cfg(feature = "circuit-relay")]
+    fn check_relay(
+        &self,
+        msg: &AdminDirectMessage,
+        source_peer_id: &str,
+    ) -> Result<Option<String>, DispatchError> {
+        let relay_origin = msg.get_relay_origin();
+        if relay_origin.is_empty() {
+            return Ok(None);
+        }
+
+        if relay_origin == source_peer_id {
+            return Ok(Some(format!(
+                "Message from {} was not received through a relay",
+                relay_origin
+            )));
+        }
+
+        if msg.get_sender() != format!("{}{}", ADMIN_SERVICE_ID_PREFIX, relay_origin) {
+            return Ok(Some(format!(
+                "Sender {} is not the admin service of relayed node {}",
+                msg.get_sender(),
+                relay_origin
+            )));
+        }
+
+        match self
+            .state
+            .node_route(relay_origin)
+            .map_err(|err| DispatchError::HandleError(err.context()))?
+        {
+            NodeRoute::Relayed(relay_nodes)
+                if relay_nodes
+                    .iter()
+                    .any(|relay_node| relay_node == source_peer_id) =>
+            {
+                Ok(None)
+            }
+            NodeRoute::Relayed(_) => Ok(Some(format!(
+                "Admin messages from {} are not relayed through {}",
+                relay_origin, source_peer_id
+            ))),
+            NodeRoute::Direct => Ok(Some(format!(
+                "Admin messages from {} are not relayed",
+                relay_origin
+            ))),
+            NodeRoute::Unknown => {
+                if self
+                    .state
+                    .learn_relay_route(relay_origin.to_string(), source_peer_id.to_string())
+                    .map_err(|err| DispatchError::HandleError(err.context()))?
+                {
+                    Ok(None)
+                } else {
+                    Ok(Some(format!(
+                        "Too many unknown nodes are relayed to record a route to {}",
+                        relay_origin
+                    )))
+                }
+            }
+        }
+    }
+
+    #[cfg(not(feature = "circuit-relay"))]
+    fn check_relay(
+        &self,
+        _msg: &AdminDirectMessage,
+        _source_peer_id: &str,
+    ) -> Result<Option<String>, DispatchError> {
+        Ok(None)
+    }
+
+    /// Returns the message bytes for delivering a message to the local admin service.
+    ///
+    /// The payload of a message received through a relay is decrypted, and the public key of the
+    /// origin node is recorded so that replies to it are encrypted.
+    #[cfg(feature = "circuit-relay")]
+    fn open_relayed(
+        &self,
+        msg: &AdminDirectMessage,
+        context: &MessageContext<PeerId, CircuitMessageType>,
+    ) -> Result<Vec<u8>, DispatchError> {
+        let relay_origin = msg.get_relay_origin();
+        if relay_origin.is_empty() {
+            return Ok(context.message_bytes().to_vec());
+        }
+
+        let mut opened_msg = msg.clone();
+        if msg.get_relay_encrypted() {
+            let payload = self
+                .state
+                .decrypt_relayed_payload(
+                    relay_origin,
+                    msg.get_relay_public_key(),
+                    msg.get_circuit(),
+                    msg.get_sender(),
+                    msg.get_recipient(),
+                    msg.get_payload(),
+                )
+                .map_err(|err| DispatchError::HandleError(err.context()))?;
+            opened_msg.set_payload(payload);
+            opened_msg.set_relay_encrypted(false);
+        } else {
+            self.state
+                .add_relay_public_key(relay_origin, msg.get_relay_public_key())
+                .map_err(|err| DispatchError::HandleError(err.context()))?;
+        }
+        opened_msg.clear_relay_public_key();
+
+        Ok(opened_msg.write_to_bytes()?)
+    }
+
+    #[cfg(not(feature = "circuit-relay"))]
+    fn open_relayed(
+        &self,
+        _msg: &AdminDirectMessage,
+        context: &MessageContext<PeerId, CircuitMessageType>,
+    ) -> Result<Vec<u8>, DispatchError> {
+        Ok(context.message_bytes().to_vec())
+    }
+}
+
+/// Returns whether the sender of a message delivered to the local admin service is the local
+/// admin service, or the admin service of the node it was received from. A message received
+/// through a relay has its sender checked against its origin node by `check_relay`.
+fn is_authentic_sender(msg: &AdminDirectMessage, source_peer_id: &str) -> bool {
+    #[cfg(feature = "circuit-relay")]
+    {
+        if !msg.get_relay_origin().is_empty() {
+            return true;
+        }
+    }
+
+    msg.get_sender() == source_peer_id
+        || msg.get_sender() == format!("{}{}", ADMIN_SERVICE_ID_PREFIX, source_peer_id)
 }
 
 fn create_circuit_error_msg(
@@ -445,7 +720,7 @@ mod tests {
         assert_eq!(
             Ok(()),
             dispatcher.dispatch(
-                "1234".into(),
+                "5678".into(),
                 &CircuitMessageType::ADMIN_DIRECT_MESSAGE,
                 direct_bytes
             )
@@ -466,6 +741,322 @@ mod tests {
         )
     }
 
+    /// Send an admin message between two nodes that are only connected through a relay node, and
+    /// send the reply back. Expect that each node's handler sends the message through the relay,
+    /// that the relay forwards it with its origin set, that the destination node learns the route
+    /// back to the origin through the relay, and that the reply is encrypted for the origin.
+    #[cfg(feature = "circuit-relay")]
+    #[test]
+    fn test_admin_direct_message_via_relay() {
+        let node_a_sender = MockSender::new();
+        let mut node_a = Dispatcher::new(Box::new(node_a_sender.clone()));
+        let node_a_state = SplinterState::new("memory".to_string(), CircuitDirectory::new());
+        node_a_state
+            .add_circuit_route(
+                "alpha".into(),
+                vec!["1234".into(), "5678".into()],
+                Some("relay".into()),
+            )
+            .expect("Unable to add circuit route");
+        node_a.set_handler(Box::new(AdminDirectMessageHandler::new(
+            "1234".into(),
+            node_a_state,
+        )));
+
+        let relay_sender = MockSender::new();
+        let mut relay = Dispatcher::new(Box::new(relay_sender.clone()));
+        let relay_state = SplinterState::new("memory".to_string(), CircuitDirectory::new());
+        relay_state
+            .register_relayed_circuit(
+                "alpha".into(),
+                "1234".into(),
+                vec!["1234".into(), "5678".into()],
+            )
+            .expect("Unable to register circuit");
+        relay.set_handler(Box::new(AdminDirectMessageHandler::new(
+            "relay".into(),
+            relay_state,
+        )));
+
+        let node_b_sender = MockSender::new();
+        let mut node_b = Dispatcher::new(Box::new(node_b_sender.clone()));
+        let node_b_state = SplinterState::new("memory".to_string(), CircuitDirectory::new());
+        node_b.set_handler(Box::new(AdminDirectMessageHandler::new(
+            "5678".into(),
+            node_b_state.clone(),
+        )));
+
+        let mut direct_message = AdminDirectMessage::new();
+        direct_message.set_circuit("admin".into());
+        direct_message.set_sender("admin::1234".into());
+        direct_message.set_recipient("admin::5678".into());
+        direct_message.set_payload(b"request".to_vec());
+        direct_message.set_correlation_id("random_corr_id".into());
+
+        // node 1234's admin service sends the message, which goes to the relay
+        let msg = deliver(
+            &node_a,
+            &node_a_sender,
+            "admin::1234",
+            direct_message.write_to_bytes().unwrap(),
+            "relay",
+        );
+        assert_eq!(msg.get_relay_destination(), "5678");
+        assert_eq!(msg.get_relay_origin(), "");
+
+        // the relay forwards it to node 5678
+        let msg = deliver(
+            &relay,
+            &relay_sender,
+            "1234",
+            msg.write_to_bytes().unwrap(),
+            "5678",
+        );
+        assert_eq!(msg.get_relay_origin(), "1234");
+
+        // node 5678 delivers it to its admin service, and records the route back to 1234
+        let msg = deliver(
+            &node_b,
+            &node_b_sender,
+            "relay",
+            msg.write_to_bytes().unwrap(),
+            "admin::5678",
+        );
+        assert_eq!(msg.get_sender(), "admin::1234");
+        assert_eq!(msg.get_payload(), b"request");
+        assert_eq!(
+            node_b_state
+                .relay_route("1234")
+                .expect("Unable to get relay route"),
+            Some("relay".to_string())
+        );
+
+        // the reply goes back through the relay
+        let mut reply = AdminDirectMessage::new();
+        reply.set_circuit("admin".into());
+        reply.set_sender("admin::5678".into());
+        reply.set_recipient("admin::1234".into());
+        reply.set_payload(b"reply".to_vec());
+        reply.set_correlation_id("random_corr_id".into());
+
+        let msg = deliver(
+            &node_b,
+            &node_b_sender,
+            "admin::5678",
+            reply.write_to_bytes().unwrap(),
+            "relay",
+        );
+        assert!(msg.get_relay_encrypted());
+        assert_ne!(msg.get_payload(), b"reply");
+        let msg = deliver(
+            &relay,
+            &relay_sender,
+            "5678",
+            msg.write_to_bytes().unwrap(),
+            "1234",
+        );
+        let msg = deliver(
+            &node_a,
+            &node_a_sender,
+            "relay",
+            msg.write_to_bytes().unwrap(),
+            "admin::1234",
+        );
+        assert_eq!(msg.get_sender(), "admin::5678");
+        assert_eq!(msg.get_payload(), b"reply");
+    }
+
+    /// Send relayed admin messages through a node other than the relay named by the circuit, with
+    /// a sender that is not the origin node's admin service, and from a node that the circuits
+    /// reach directly. Expect that all are rejected with an invalid relay error.
+    #[cfg(feature = "circuit-relay")]
+    #[test]
+    fn test_admin_direct_message_invalid_relay() {
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+        let state = SplinterState::new("memory".to_string(), CircuitDirectory::new());
+        state
+            .add_circuit_route(
+                "alpha".into(),
+                vec!["1234".into(), "5678".into()],
+                Some("relay".into()),
+            )
+            .expect("Unable to add circuit route");
+        state
+            .add_circuit_route("beta".into(), vec!["4321".into(), "5678".into()], None)
+            .expect("Unable to add circuit route");
+        dispatcher.set_handler(Box::new(AdminDirectMessageHandler::new(
+            "5678".into(),
+            state,
+        )));
+
+        for (source, origin, sender) in &[
+            ("other-relay", "1234", "admin::1234"),
+            ("relay", "1234", "admin::9999"),
+            ("relay", "4321", "admin::4321"),
+        ] {
+            let mut direct_message = AdminDirectMessage::new();
+            direct_message.set_circuit("admin".into());
+            direct_message.set_sender(sender.to_string());
+            direct_message.set_recipient("admin::5678".into());
+            direct_message.set_payload(b"test".to_vec());
+            direct_message.set_correlation_id("random_corr_id".into());
+            direct_message.set_relay_destination("5678".into());
+            direct_message.set_relay_origin(origin.to_string());
+
+            assert_eq!(
+                Ok(()),
+                dispatcher.dispatch(
+                    source.to_string().into(),
+                    &CircuitMessageType::ADMIN_DIRECT_MESSAGE,
+                    direct_message.write_to_bytes().unwrap()
+                )
+            );
+
+            let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+            assert_network_message(
+                message,
+                id.into(),
+                source,
+                CircuitMessageType::CIRCUIT_ERROR_MESSAGE,
+                |error_msg: CircuitError| {
+                    assert_eq!(
+                        error_msg.get_error(),
+                        CircuitError_Error::ERROR_INVALID_RELAY
+                    );
+                },
+            )
+        }
+    }
+
+    /// Send an admin message to be relayed between two nodes that have not registered a circuit
+    /// naming both as members with the relay. Expect that the relay does not forward it, and
+    /// replies with an invalid relay error.
+    #[cfg(feature = "circuit-relay")]
+    #[test]
+    fn test_admin_direct_message_unregistered_relay() {
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+        let state = SplinterState::new("memory".to_string(), CircuitDirectory::new());
+        state
+            .register_relayed_circuit(
+                "alpha".into(),
+                "1234".into(),
+                vec!["1234".into(), "9999".into()],
+            )
+            .expect("Unable to register circuit");
+        dispatcher.set_handler(Box::new(AdminDirectMessageHandler::new(
+            "relay".into(),
+            state,
+        )));
+
+        let mut direct_message = AdminDirectMessage::new();
+        direct_message.set_circuit("admin".into());
+        direct_message.set_sender("admin::1234".into());
+        direct_message.set_recipient("admin::5678".into());
+        direct_message.set_payload(b"test".to_vec());
+        direct_message.set_correlation_id("random_corr_id".into());
+        direct_message.set_relay_destination("5678".into());
+
+        assert_eq!(
+            Ok(()),
+            dispatcher.dispatch(
+                "1234".into(),
+                &CircuitMessageType::ADMIN_DIRECT_MESSAGE,
+                direct_message.write_to_bytes().unwrap()
+            )
+        );
+
+        let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+        assert_network_message(
+            message,
+            id.into(),
+            "1234",
+            CircuitMessageType::CIRCUIT_ERROR_MESSAGE,
+            |error_msg: CircuitError| {
+                assert_eq!(
+                    error_msg.get_error(),
+                    CircuitError_Error::ERROR_INVALID_RELAY
+                );
+            },
+        )
+    }
+
+    /// Send an admin message to the local admin service from a node whose admin service is not
+    /// the sender. Expect that it is rejected with a sender error.
+    #[test]
+    fn test_admin_direct_message_spoofed_sender() {
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+        let state = SplinterState::new("memory".to_string(), CircuitDirectory::new());
+        dispatcher.set_handler(Box::new(AdminDirectMessageHandler::new(
+            "1234".into(),
+            state,
+        )));
+
+        let mut direct_message = AdminDirectMessage::new();
+        direct_message.set_circuit("admin".into());
+        direct_message.set_sender("admin::5678".into());
+        direct_message.set_recipient("admin::1234".into());
+        direct_message.set_payload(b"test".to_vec());
+        direct_message.set_correlation_id("random_corr_id".into());
+
+        assert_eq!(
+            Ok(()),
+            dispatcher.dispatch(
+                "9999".into(),
+                &CircuitMessageType::ADMIN_DIRECT_MESSAGE,
+                direct_message.write_to_bytes().unwrap()
+            )
+        );
+
+        let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+        assert_network_message(
+            message,
+            id.into(),
+            "9999",
+            CircuitMessageType::CIRCUIT_ERROR_MESSAGE,
+            |error_msg: CircuitError| {
+                assert_eq!(
+                    error_msg.get_error(),
+                    CircuitError_Error::ERROR_SENDER_NOT_IN_CIRCUIT_ROSTER
+                );
+            },
+        )
+    }
+
+    /// Dispatches the admin message bytes as received from the given source, and returns the
+    /// admin message the handler sent, asserting that it was sent to the expected recipient.
+    #[cfg(feature = "circuit-relay")]
+    fn deliver(
+        dispatcher: &Dispatcher<CircuitMessageType>,
+        sender: &MockSender,
+        source: &str,
+        msg_bytes: Vec<u8>,
+        expected_recipient: &str,
+    ) -> AdminDirectMessage {
+        assert_eq!(
+            Ok(()),
+            dispatcher.dispatch(
+                source.into(),
+                &CircuitMessageType::ADMIN_DIRECT_MESSAGE,
+                msg_bytes
+            )
+        );
+
+        let (id, message) = sender.next_outbound().expect("No message was sent");
+        assert_eq!(expected_recipient, &String::from(id));
+
+        let network_msg: NetworkMessage = protobuf::parse_from_bytes(&message).unwrap();
+        let circuit_msg: CircuitMessage =
+            protobuf::parse_from_bytes(network_msg.get_payload()).unwrap();
+        assert_eq!(
+            CircuitMessageType::ADMIN_DIRECT_MESSAGE,
+            circuit_msg.get_message_type()
+        );
+        protobuf::parse_from_bytes(circuit_msg.get_payload()).unwrap()
+    }
+
     fn assert_network_message<M: protobuf::Message, F: Fn(M)>(
         message: Vec<u8>,
         recipient: String,
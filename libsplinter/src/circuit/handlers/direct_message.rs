@@ -13,7 +13,9 @@
 // limitations under the License.
 
 use crate::circuit::handlers::create_message;
-use crate::circuit::{ServiceId, SplinterState};
+#[cfg(feature = "circuit-relay")]
+use crate::circuit::RouteType;
+use crate::circuit::{Circuit, ServiceId, SplinterState};
 use crate::network::dispatch::{DispatchError, Handler, MessageContext, MessageSender, PeerId};
 use crate::protos::circuit::{
     CircuitDirectMessage, CircuitError, CircuitError_Error, CircuitMessageType,
@@ -110,6 +112,23 @@ impl Handler for CircuitDirectMessageHandler {
                         recipient
                     ));
 
+                    let msg_bytes = error_message.write_to_bytes()?;
+                    let network_msg_bytes =
+                        create_message(msg_bytes, CircuitMessageType::CIRCUIT_ERROR_MESSAGE)?;
+                    (network_msg_bytes, context.source_peer_id().to_string())
+                } else if let Some(relay_error) =
+                    self.check_relay(&circuit, &msg, context.source_peer_id())?
+                {
+                    // if the message was relayed by a node that is not the circuit's relay, or
+                    // did not originate from the node the sender is connected to, send circuit
+                    // error
+                    let mut error_message = CircuitError::new();
+                    error_message.set_correlation_id(msg.get_correlation_id().to_string());
+                    error_message.set_service_id(msg_sender.into());
+                    error_message.set_circuit_name(circuit_name.into());
+                    error_message.set_error(CircuitError_Error::ERROR_INVALID_RELAY);
+                    error_message.set_error_message(relay_error);
+
                     let msg_bytes = error_message.write_to_bytes()?;
                     let network_msg_bytes =
                         create_message(msg_bytes, CircuitMessageType::CIRCUIT_ERROR_MESSAGE)?;
@@ -125,14 +144,9 @@ impl Handler for CircuitDirectMessageHandler {
                         // If the service is on this node send message to the service, otherwise
                         // send the message to the node the service is connected to
                         if node_id != self.node_id {
-                            let msg_bytes = context.message_bytes().to_vec();
-                            let network_msg_bytes = create_message(
-                                msg_bytes,
-                                CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
-                            )?;
-                            (network_msg_bytes, node_id)
+                            self.route_to_node(&circuit, &msg, context, node_id)?
                         } else {
-                            let msg_bytes = context.message_bytes().to_vec();
+                            let msg_bytes = self.open_relayed(&msg, context)?;
                             let network_msg_bytes = create_message(
                                 msg_bytes,
                                 CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
@@ -185,6 +199,11 @@ impl Handler for CircuitDirectMessageHandler {
                         create_message(msg_bytes, CircuitMessageType::CIRCUIT_ERROR_MESSAGE)?;
                     (network_msg_bytes, context.source_peer_id().to_string())
                 }
+            } else if let Some(relayed_message) =
+                self.relay_message(&msg, context.source_peer_id())?
+            {
+                // this node is not a member of the circuit, but relays its messages
+                relayed_message
             } else {
                 // if the circuit does not exist, send circuit error
                 let mut error_message = CircuitError::new();
@@ -216,6 +235,260 @@ impl CircuitDirectMessageHandler {
     pub fn new(node_id: String, state: SplinterState) -> Self {
         CircuitDirectMessageHandler { node_id, state }
     }
+
+    /// Returns the message and peer ID for sending a direct message to the given member node.
+    ///
+    /// On a circuit with relayed routes, the message is sent to the circuit's relay node, which
+    /// forwards it to the member. The payload is encrypted for the member, whose public key is
+    /// received with the admin messages exchanged when the circuit is proposed; if it has not
+    /// been received, the message is not sent.
+    #[cfg(feature = "circuit-relay")]
+    fn route_to_node(
+        &self,
+        circuit: &Circuit,
+        msg: &CircuitDirectMessage,
+        context: &MessageContext<PeerId, CircuitMessageType>,
+        node_id: String,
+    ) -> Result<(Vec<u8>, String), DispatchError> {
+        if let RouteType::Relayed { relay_node } = circuit.routes() {
+            let payload = match self
+                .state
+                .encrypt_relayed_payload(
+                    &node_id,
+                    msg.get_circuit(),
+                    msg.get_sender(),
+                    msg.get_recipient(),
+                    msg.get_payload(),
+                )
+                .map_err(|err| DispatchError::HandleError(err.context()))?
+            {
+                Some(payload) => payload,
+                None => {
+                    let mut error_message = CircuitError::new();
+                    error_message.set_correlation_id(msg.get_correlation_id().to_string());
+                    error_message.set_service_id(msg.get_sender().into());
+                    error_message.set_circuit_name(msg.get_circuit().into());
+                    error_message.set_error(CircuitError_Error::ERROR_INVALID_RELAY);
+                    error_message.set_error_message(format!(
+                        "No public key has been received from relayed node {}",
+                        node_id
+                    ));
+
+                    let msg_bytes = error_message.write_to_bytes()?;
+                    let network_msg_bytes =
+                        create_message(msg_bytes, CircuitMessageType::CIRCUIT_ERROR_MESSAGE)?;
+                    return Ok((network_msg_bytes, context.source_peer_id().to_string()));
+                }
+            };
+
+            let mut relayed_msg = msg.clone();
+            relayed_msg.set_relay_destination(node_id);
+            // The origin is only set by the relay node
+            relayed_msg.clear_relay_origin();
+            relayed_msg.set_payload(payload);
+            relayed_msg.set_relay_encrypted(true);
+            relayed_msg.set_relay_public_key(
+                self.state
+                    .relay_public_key()
+                    .map_err(|err| DispatchError::HandleError(err.context()))?,
+            );
+
+            let network_msg_bytes = create_message(
+                relayed_msg.write_to_bytes()?,
+                CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+            )?;
+            return Ok((network_msg_bytes, relay_node.to_string()));
+        }
+
+        let network_msg_bytes = create_message(
+            context.message_bytes().to_vec(),
+            CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+        )?;
+        Ok((network_msg_bytes, node_id))
+    }
+
+    #[cfg(not(feature = "circuit-relay"))]
+    fn route_to_node(
+        &self,
+        _circuit: &Circuit,
+        _msg: &CircuitDirectMessage,
+        context: &MessageContext<PeerId, CircuitMessageType>,
+        node_id: String,
+    ) -> Result<(Vec<u8>, String), DispatchError> {
+        let network_msg_bytes = create_message(
+            context.message_bytes().to_vec(),
+            CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+        )?;
+        Ok((network_msg_bytes, node_id))
+    }
+
+    /// Checks a message that was forwarded by a relay node, returning the reason it is invalid
+    /// if the circuit does not relay messages through the node it was received from, if the
+    /// message did not originate from the node the sender is connected to, or if its payload is
+    /// not encrypted.
+    #[cfg(feature = "circuit-relay")]
+    fn check_relay(
+        &self,
+        circuit: &Circuit,
+        msg: &CircuitDirectMessage,
+        source_peer_id: &str,
+    ) -> Result<Option<String>, DispatchError> {
+        let relay_origin = msg.get_relay_origin();
+        if relay_origin.is_empty() {
+            return Ok(None);
+        }
+
+        match circuit.routes() {
+            RouteType::Relayed { relay_node } if relay_node == source_peer_id => (),
+            _ => {
+                return Ok(Some(format!(
+                    "Circuit {} does not relay messages through {}",
+                    circuit.id(),
+                    source_peer_id
+                )))
+            }
+        }
+
+        if !msg.get_relay_encrypted() {
+            return Ok(Some(format!(
+                "Message relayed from {} is not encrypted",
+                relay_origin
+            )));
+        }
+
+        let sender_id = ServiceId::new(circuit.id().to_string(), msg.get_sender().to_string());
+        match self
+            .state
+            .get_service(&sender_id)
+            .map_err(|err| DispatchError::HandleError(err.context()))?
+        {
+            Some(service) if service.node().id() == relay_origin => Ok(None),
+            _ => Ok(Some(format!(
+                "Sender {} is not connected to relayed node {}",
+                msg.get_sender(),
+                relay_origin
+            ))),
+        }
+    }
+
+    #[cfg(not(feature = "circuit-relay"))]
+    fn check_relay(
+        &self,
+        _circuit: &Circuit,
+        _msg: &CircuitDirectMessage,
+        _source_peer_id: &str,
+    ) -> Result<Option<String>, DispatchError> {
+        Ok(None)
+    }
+
+    /// Returns the message bytes for delivering a message to a service connected to this node,
+    /// decrypting the payload of a message received through a relay node.
+    #[cfg(feature = "circuit-relay")]
+    fn open_relayed(
+        &self,
+        msg: &CircuitDirectMessage,
+        context: &MessageContext<PeerId, CircuitMessageType>,
+    ) -> Result<Vec<u8>, DispatchError> {
+        if msg.get_relay_origin().is_empty() {
+            return Ok(context.message_bytes().to_vec());
+        }
+
+        let payload = self
+            .state
+            .decrypt_relayed_payload(
+                msg.get_relay_origin(),
+                msg.get_relay_public_key(),
+                msg.get_circuit(),
+                msg.get_sender(),
+                msg.get_recipient(),
+                msg.get_payload(),
+            )
+            .map_err(|err| DispatchError::HandleError(err.context()))?;
+
+        let mut opened_msg = msg.clone();
+        opened_msg.set_payload(payload);
+        opened_msg.set_relay_encrypted(false);
+        opened_msg.clear_relay_public_key();
+        Ok(opened_msg.write_to_bytes()?)
+    }
+
+    #[cfg(not(feature = "circuit-relay"))]
+    fn open_relayed(
+        &self,
+        _msg: &CircuitDirectMessage,
+        context: &MessageContext<PeerId, CircuitMessageType>,
+    ) -> Result<Vec<u8>, DispatchError> {
+        Ok(context.message_bytes().to_vec())
+    }
+
+    /// Returns the message and peer ID for forwarding a message on a circuit this node is not a
+    /// member of, if the message is addressed to another node through this node as a relay.
+    ///
+    /// The relay does not have the circuit's roster, so it only forwards the message if both
+    /// nodes registered the circuit with it, naming both as members. The destination node checks
+    /// that the circuit relays messages through this node, and that the sender is connected to
+    /// the origin node recorded here. The payload is encrypted for the destination node.
+    #[cfg(feature = "circuit-relay")]
+    fn relay_message(
+        &self,
+        msg: &CircuitDirectMessage,
+        source_peer_id: &str,
+    ) -> Result<Option<(Vec<u8>, String)>, DispatchError> {
+        let relay_destination = msg.get_relay_destination();
+        if relay_destination.is_empty()
+            || relay_destination == self.node_id
+            || relay_destination == source_peer_id
+        {
+            return Ok(None);
+        }
+
+        if !self
+            .state
+            .relays_circuit(msg.get_circuit(), source_peer_id, relay_destination)
+            .map_err(|err| DispatchError::HandleError(err.context()))?
+        {
+            let mut error_message = CircuitError::new();
+            error_message.set_correlation_id(msg.get_correlation_id().to_string());
+            error_message.set_service_id(msg.get_sender().into());
+            error_message.set_circuit_name(msg.get_circuit().into());
+            error_message.set_error(CircuitError_Error::ERROR_INVALID_RELAY);
+            error_message.set_error_message(format!(
+                "Circuit {} does not relay messages from {} to {} through this node",
+                msg.get_circuit(),
+                source_peer_id,
+                relay_destination
+            ));
+
+            let msg_bytes = error_message.write_to_bytes()?;
+            let network_msg_bytes =
+                create_message(msg_bytes, CircuitMessageType::CIRCUIT_ERROR_MESSAGE)?;
+            return Ok(Some((network_msg_bytes, source_peer_id.to_string())));
+        }
+
+        debug!(
+            "Relaying message on {} from {} to {}",
+            msg.get_circuit(),
+            source_peer_id,
+            relay_destination
+        );
+
+        let mut relayed_msg = msg.clone();
+        relayed_msg.set_relay_origin(source_peer_id.to_string());
+        let network_msg_bytes = create_message(
+            relayed_msg.write_to_bytes()?,
+            CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+        )?;
+        Ok(Some((network_msg_bytes, relay_destination.to_string())))
+    }
+
+    #[cfg(not(feature = "circuit-relay"))]
+    fn relay_message(
+        &self,
+        _msg: &CircuitDirectMessage,
+        _source_peer_id: &str,
+    ) -> Result<Option<(Vec<u8>, String)>, DispatchError> {
+        Ok(None)
+    }
 }
 
 #[cfg(test)]
@@ -723,6 +996,283 @@ mod tests {
         )
     }
 
+    // Test that a direct message on a circuit with relayed routes is sent to the relay node,
+    // addressed to the node the recipient service is connected to, with its payload encrypted for
+    // that node, and that it is not sent before that node's public key has been received
+    #[cfg(feature = "circuit-relay")]
+    #[test]
+    fn test_circuit_direct_message_handler_relayed_route() {
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+
+        let state = relayed_state();
+        let handler = CircuitDirectMessageHandler::new("345".to_string(), state.clone());
+        dispatcher.set_handler(Box::new(handler));
+
+        dispatcher
+            .dispatch(
+                "def".into(),
+                &CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+                relayed_direct_message("", "").write_to_bytes().unwrap(),
+            )
+            .unwrap();
+
+        let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+        assert_network_message(
+            message,
+            id.into(),
+            "def",
+            CircuitMessageType::CIRCUIT_ERROR_MESSAGE,
+            |msg: CircuitError| {
+                assert_eq!(msg.get_error(), CircuitError_Error::ERROR_INVALID_RELAY);
+            },
+        );
+
+        let destination_state = relayed_state();
+        state
+            .add_relay_public_key("123", &destination_state.relay_public_key().unwrap())
+            .unwrap();
+
+        let direct_message = relayed_direct_message("", "");
+        dispatcher
+            .dispatch(
+                "def".into(),
+                &CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+                direct_message.write_to_bytes().unwrap(),
+            )
+            .unwrap();
+
+        let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+        assert_network_message(
+            message,
+            id.into(),
+            "relay",
+            CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+            |msg: CircuitDirectMessage| {
+                assert_eq!(msg.get_sender(), "def");
+                assert_eq!(msg.get_recipient(), "abc");
+                assert_eq!(msg.get_relay_destination(), "123");
+                assert_eq!(msg.get_relay_origin(), "");
+                assert!(msg.get_relay_encrypted());
+                assert_eq!(
+                    destination_state
+                        .decrypt_relayed_payload(
+                            "345",
+                            msg.get_relay_public_key(),
+                            "alpha",
+                            "def",
+                            "abc",
+                            msg.get_payload()
+                        )
+                        .expect("Unable to decrypt payload"),
+                    b"test".to_vec()
+                );
+            },
+        )
+    }
+
+    // Test that a relay node, which does not have the circuit, forwards a direct message to its
+    // destination node and records the node it was received from, once both nodes have
+    // registered the circuit with it
+    #[cfg(feature = "circuit-relay")]
+    #[test]
+    fn test_circuit_direct_message_handler_relay() {
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+
+        let state = SplinterState::new("memory".to_string(), CircuitDirectory::new());
+        let handler = CircuitDirectMessageHandler::new("relay".to_string(), state.clone());
+        dispatcher.set_handler(Box::new(handler));
+
+        state
+            .register_relayed_circuit(
+                "alpha".into(),
+                "345".into(),
+                vec!["123".into(), "345".into()],
+            )
+            .unwrap();
+        dispatcher
+            .dispatch(
+                "345".into(),
+                &CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+                relayed_direct_message("123", "").write_to_bytes().unwrap(),
+            )
+            .unwrap();
+
+        let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+        assert_network_message(
+            message,
+            id.into(),
+            "345",
+            CircuitMessageType::CIRCUIT_ERROR_MESSAGE,
+            |msg: CircuitError| {
+                assert_eq!(msg.get_error(), CircuitError_Error::ERROR_INVALID_RELAY);
+            },
+        );
+
+        state
+            .register_relayed_circuit(
+                "alpha".into(),
+                "123".into(),
+                vec!["123".into(), "345".into()],
+            )
+            .unwrap();
+
+        // The origin set by the sending node is replaced by the relay
+        let direct_message = relayed_direct_message("123", "999");
+        dispatcher
+            .dispatch(
+                "345".into(),
+                &CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+                direct_message.write_to_bytes().unwrap(),
+            )
+            .unwrap();
+
+        let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+        assert_network_message(
+            message,
+            id.into(),
+            "123",
+            CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+            |msg: CircuitDirectMessage| {
+                assert_eq!(msg.get_relay_destination(), "123");
+                assert_eq!(msg.get_relay_origin(), "345");
+                assert_eq!(msg.get_payload().to_vec(), b"test".to_vec());
+            },
+        )
+    }
+
+    // Test that a direct message received from the circuit's relay node is sent to the recipient
+    // service with its payload decrypted, and that a message relayed by any other node, from a
+    // node the sender is not connected to, or with an unencrypted payload, is rejected
+    #[cfg(feature = "circuit-relay")]
+    #[test]
+    fn test_circuit_direct_message_handler_relayed_delivery() {
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+
+        let state = relayed_state();
+        let handler = CircuitDirectMessageHandler::new("123".to_string(), state.clone());
+        dispatcher.set_handler(Box::new(handler));
+
+        let origin_state = relayed_state();
+        origin_state
+            .add_relay_public_key("123", &state.relay_public_key().unwrap())
+            .unwrap();
+        let mut direct_message = relayed_direct_message("123", "345");
+        direct_message.set_payload(
+            origin_state
+                .encrypt_relayed_payload("123", "alpha", "def", "abc", b"test")
+                .unwrap()
+                .expect("No public key for node 123"),
+        );
+        direct_message.set_relay_encrypted(true);
+        direct_message.set_relay_public_key(origin_state.relay_public_key().unwrap());
+        dispatcher
+            .dispatch(
+                "relay".into(),
+                &CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+                direct_message.write_to_bytes().unwrap(),
+            )
+            .unwrap();
+
+        let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+        assert_network_message(
+            message,
+            id.into(),
+            "abc_network",
+            CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+            |msg: CircuitDirectMessage| {
+                assert_eq!(msg.get_sender(), "def");
+                assert_eq!(msg.get_recipient(), "abc");
+                assert_eq!(msg.get_payload().to_vec(), b"test".to_vec());
+                assert!(!msg.get_relay_encrypted());
+            },
+        );
+
+        for (source, relay_origin, encrypted) in &[
+            ("other", "345", true),
+            ("relay", "999", true),
+            ("relay", "345", false),
+        ] {
+            let mut direct_message = relayed_direct_message("123", relay_origin);
+            direct_message.set_relay_encrypted(*encrypted);
+            dispatcher
+                .dispatch(
+                    (*source).into(),
+                    &CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+                    direct_message.write_to_bytes().unwrap(),
+                )
+                .unwrap();
+
+            let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+            assert_network_message(
+                message,
+                id.into(),
+                source,
+                CircuitMessageType::CIRCUIT_ERROR_MESSAGE,
+                |msg: CircuitError| {
+                    assert_eq!(msg.get_service_id(), "def");
+                    assert_eq!(msg.get_error(), CircuitError_Error::ERROR_INVALID_RELAY);
+                    assert_eq!(msg.get_correlation_id(), "1234");
+                },
+            );
+        }
+    }
+
+    // Create the state of a circuit member, with services abc on node 123 and def on node 345,
+    // for a circuit that relays messages through the node "relay"
+    #[cfg(feature = "circuit-relay")]
+    fn relayed_state() -> SplinterState {
+        let circuit = Circuit::builder()
+            .with_id("alpha".into())
+            .with_auth(AuthorizationType::Trust)
+            .with_members(vec!["123".into(), "345".into()])
+            .with_roster(vec!["abc".into(), "def".into()])
+            .with_persistence(PersistenceType::Any)
+            .with_durability(DurabilityType::NoDurability)
+            .with_routes(RouteType::Relayed {
+                relay_node: "relay".into(),
+            })
+            .with_circuit_management_type("circuit_direct_test_app".into())
+            .build()
+            .expect("Should have built a correct circuit");
+
+        let mut circuit_directory = CircuitDirectory::new();
+        circuit_directory.add_circuit("alpha".to_string(), circuit);
+
+        let state = SplinterState::new("memory".to_string(), circuit_directory);
+
+        let node_123 = SplinterNode::new("123".to_string(), vec!["123.0.0.1:0".to_string()]);
+        let node_345 = SplinterNode::new("345".to_string(), vec!["123.0.0.1:1".to_string()]);
+        let service_abc =
+            Service::new("abc".to_string(), Some("abc_network".to_string()), node_123);
+        let service_def =
+            Service::new("def".to_string(), Some("def_network".to_string()), node_345);
+        state
+            .add_service(ServiceId::new("alpha".into(), "abc".into()), service_abc)
+            .unwrap();
+        state
+            .add_service(ServiceId::new("alpha".into(), "def".into()), service_def)
+            .unwrap();
+
+        state
+    }
+
+    // Create a direct message from def to abc, with the given relay fields
+    #[cfg(feature = "circuit-relay")]
+    fn relayed_direct_message(relay_destination: &str, relay_origin: &str) -> CircuitDirectMessage {
+        let mut direct_message = CircuitDirectMessage::new();
+        direct_message.set_circuit("alpha".into());
+        direct_message.set_sender("def".into());
+        direct_message.set_recipient("abc".into());
+        direct_message.set_payload(b"test".to_vec());
+        direct_message.set_correlation_id("1234".into());
+        direct_message.set_relay_destination(relay_destination.into());
+        direct_message.set_relay_origin(relay_origin.into());
+        direct_message
+    }
+
     fn assert_network_message<M: protobuf::Message, F: Fn(M)>(
         message: Vec<u8>,
         recipient: String,
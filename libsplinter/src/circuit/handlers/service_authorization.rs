@@ -0,0 +1,204 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Authorization of services connecting to a splinter node.
+//!
+//! A `ServiceAuthorizationPolicy` decides whether a service connection, identified by the peer
+//! it arrived on, may register as a given service on a given circuit. A service is authorized
+//! either by the identity of its connection or by a registration token signed by a trusted key.
+
+use std::fmt;
+
+use crate::hex::parse_hex;
+use crate::protos::circuit::ServiceConnectRequest;
+use crate::service::ServiceRegistrationToken;
+use crate::signing::SignatureVerifier;
+
+const WILDCARD: &str = "*";
+
+/// The policy applied to `ServiceConnectRequest` messages by the
+/// `ServiceConnectRequestHandler`.
+///
+/// Trusted identities should only be used for connections whose identity is verified by the
+/// transport, for example with mutually authenticated TLS; see
+/// `AuthorizationManager::with_verified_identities`.
+pub struct ServiceAuthorizationPolicy {
+    trusted_identities: Vec<TrustedIdentity>,
+    trusted_keys: Vec<Vec<u8>>,
+    verifier: Box<dyn SignatureVerifier>,
+}
+
+struct TrustedIdentity {
+    identity: String,
+    circuit: String,
+    service_id: String,
+}
+
+impl ServiceAuthorizationPolicy {
+    /// Constructs a policy which authorizes no services.
+    ///
+    /// The verifier is used to check the signatures of registration tokens.
+    pub fn new(verifier: Box<dyn SignatureVerifier>) -> Self {
+        Self {
+            trusted_identities: vec![],
+            trusted_keys: vec![],
+            verifier,
+        }
+    }
+
+    /// Authorizes connections with the given identity to register the given service on the given
+    /// circuit. Either the circuit or the service ID may be `*`, which matches any value.
+    pub fn with_trusted_identity(
+        mut self,
+        identity: &str,
+        circuit: &str,
+        service_id: &str,
+    ) -> Self {
+        self.trusted_identities.push(TrustedIdentity {
+            identity: identity.into(),
+            circuit: circuit.into(),
+            service_id: service_id.into(),
+        });
+        self
+    }
+
+    /// Authorizes any service presenting an unexpired registration token, for its circuit and
+    /// service ID, that is signed by the given public key.
+    pub fn with_trusted_key(mut self, public_key: Vec<u8>) -> Self {
+        self.trusted_keys.push(public_key);
+        self
+    }
+
+    /// Authorizes any service presenting an unexpired registration token, for its circuit and
+    /// service ID, that is signed by the given hex-encoded public key.
+    pub fn with_trusted_hex_key(self, public_key: &str) -> Result<Self, InvalidKeyError> {
+        let public_key =
+            parse_hex(public_key).map_err(|err| InvalidKeyError(format!("{}", err)))?;
+        Ok(self.with_trusted_key(public_key))
+    }
+
+    /// Returns whether the peer may connect the service in the given request.
+    pub fn is_authorized(&self, peer_id: &str, request: &ServiceConnectRequest) -> bool {
+        let circuit = request.get_circuit();
+        let service_id = request.get_service_id();
+
+        if self.trusted_identities.iter().any(|trusted| {
+            trusted.identity == peer_id
+                && (trusted.circuit == WILDCARD || trusted.circuit == circuit)
+                && (trusted.service_id == WILDCARD || trusted.service_id == service_id)
+        }) {
+            return true;
+        }
+
+        if !request.has_token() {
+            return false;
+        }
+
+        let token = ServiceRegistrationToken::from(request.get_token().clone());
+        if !self
+            .trusted_keys
+            .iter()
+            .any(|key| key.as_slice() == token.public_key())
+        {
+            return false;
+        }
+
+        match token.verify(circuit, service_id, &*self.verifier) {
+            Ok(valid) => valid,
+            Err(err) => {
+                warn!(
+                    "Unable to verify registration token for {}::{}: {}",
+                    circuit, service_id, err
+                );
+                false
+            }
+        }
+    }
+}
+
+/// Returned when a trusted public key is not valid hex.
+#[derive(Debug)]
+pub struct InvalidKeyError(pub String);
+
+impl std::error::Error for InvalidKeyError {}
+
+impl fmt::Display for InvalidKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid public key: {}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::protos::circuit;
+    use crate::signing::hash::{HashSigner, HashVerifier};
+    use crate::signing::Signer;
+
+    /// Test that trusted identities authorize only the circuits and services they match.
+    #[test]
+    fn test_trusted_identities() {
+        let policy = ServiceAuthorizationPolicy::new(Box::new(HashVerifier))
+            .with_trusted_identity("service-host", "alpha", "abc")
+            .with_trusted_identity("all-services", "*", "*");
+
+        assert!(policy.is_authorized("service-host", &connect_request("alpha", "abc", None)));
+        assert!(!policy.is_authorized("service-host", &connect_request("alpha", "def", None)));
+        assert!(!policy.is_authorized("service-host", &connect_request("beta", "abc", None)));
+        assert!(policy.is_authorized("all-services", &connect_request("beta", "def", None)));
+        assert!(!policy.is_authorized("unknown", &connect_request("alpha", "abc", None)));
+    }
+
+    /// Test that registration tokens authorize only when signed by a trusted key for the
+    /// requested circuit and service.
+    #[test]
+    fn test_registration_tokens() {
+        let trusted = ServiceAuthorizationPolicy::new(Box::new(HashVerifier))
+            .with_trusted_key(HashSigner.public_key().to_vec());
+        let untrusted = ServiceAuthorizationPolicy::new(Box::new(HashVerifier))
+            .with_trusted_key(b"another key".to_vec());
+
+        let token = ServiceRegistrationToken::new(
+            "alpha",
+            "abc",
+            std::time::Duration::from_secs(60),
+            &HashSigner,
+        )
+        .unwrap();
+
+        assert!(trusted.is_authorized("peer", &connect_request("alpha", "abc", Some(&token))));
+        assert!(!trusted.is_authorized("peer", &connect_request("alpha", "def", Some(&token))));
+        assert!(!trusted.is_authorized("peer", &connect_request("alpha", "abc", None)));
+        assert!(!untrusted.is_authorized("peer", &connect_request("alpha", "abc", Some(&token))));
+
+        assert!(ServiceAuthorizationPolicy::new(Box::new(HashVerifier))
+            .with_trusted_hex_key("not hex")
+            .is_err());
+    }
+
+    fn connect_request(
+        circuit: &str,
+        service_id: &str,
+        token: Option<&ServiceRegistrationToken>,
+    ) -> ServiceConnectRequest {
+        let mut request = ServiceConnectRequest::new();
+        request.set_circuit(circuit.into());
+        request.set_service_id(service_id.into());
+        if let Some(token) = token {
+            request.set_token(circuit::ServiceRegistrationToken::from(token.clone()));
+        }
+        request
+    }
+}
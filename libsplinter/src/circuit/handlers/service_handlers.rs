@@ -24,11 +24,16 @@ use crate::protos::circuit::{
 
 use protobuf::Message;
 
+#[cfg(feature = "service-auth")]
+use super::ServiceAuthorizationPolicy;
+
 // Implements a handler that handles ServiceConnectRequest
 pub struct ServiceConnectRequestHandler {
     node_id: String,
     endpoints: Vec<String>,
     state: SplinterState,
+    #[cfg(feature = "service-auth")]
+    authorization_policy: Option<ServiceAuthorizationPolicy>,
 }
 
 impl Handler for ServiceConnectRequestHandler {
@@ -62,7 +67,15 @@ impl Handler for ServiceConnectRequestHandler {
             .circuit(circuit_name)
             .map_err(|err| DispatchError::HandleError(err.context()))?;
 
-        if let Some(circuit) = circuit_result {
+        // Services that fail authorization are rejected before any information about the circuit
+        // is returned
+        if !self.is_authorized(context.source_peer_id(), &msg) {
+            response.set_status(ServiceConnectResponse_Status::ERROR_UNAUTHORIZED);
+            response.set_error_message(format!(
+                "Service is not authorized to connect: {}",
+                unique_id
+            ))
+        } else if let Some(circuit) = circuit_result {
             // If the circuit has the service in its roster and the service is not yet connected
             // forward the connection to the rest of the nodes on the circuit and add the service
             // to splinter state
@@ -155,8 +168,31 @@ impl ServiceConnectRequestHandler {
             node_id,
             endpoints,
             state,
+            #[cfg(feature = "service-auth")]
+            authorization_policy: None,
         }
     }
+
+    /// Requires that connecting services are authorized by the given policy.  Connections that
+    /// fail the policy receive an `ERROR_UNAUTHORIZED` response.
+    #[cfg(feature = "service-auth")]
+    pub fn with_authorization_policy(mut self, policy: ServiceAuthorizationPolicy) -> Self {
+        self.authorization_policy = Some(policy);
+        self
+    }
+
+    #[cfg(feature = "service-auth")]
+    fn is_authorized(&self, peer_id: &str, request: &ServiceConnectRequest) -> bool {
+        self.authorization_policy
+            .as_ref()
+            .map(|policy| policy.is_authorized(peer_id, request))
+            .unwrap_or(true)
+    }
+
+    #[cfg(not(feature = "service-auth"))]
+    fn is_authorized(&self, _peer_id: &str, _request: &ServiceConnectRequest) -> bool {
+        true
+    }
 }
 
 // Implements a handler that handles ServiceDisconnectRequest
@@ -485,6 +521,101 @@ mod tests {
         )
     }
 
+    #[cfg(feature = "service-auth")]
+    #[test]
+    // Test that if an authorization policy is set, a service that does not satisfy it is
+    // returned a ServiceConnectResponse with ERROR_UNAUTHORIZED and is not added, while a service
+    // presenting a valid registration token is returned an OK
+    fn test_service_connect_request_handler_authorization() {
+        use crate::protos::circuit::ServiceRegistrationToken as ProtoToken;
+        use crate::service::ServiceRegistrationToken;
+        use crate::signing::hash::{HashSigner, HashVerifier};
+        use crate::signing::Signer;
+
+        // Set up dispatcher and mock sender
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+
+        let circuit = build_circuit();
+
+        let mut circuit_directory = CircuitDirectory::new();
+        circuit_directory.add_circuit("alpha".to_string(), circuit);
+
+        let state = SplinterState::new("memory".to_string(), circuit_directory);
+        let handler = ServiceConnectRequestHandler::new(
+            "123".to_string(),
+            vec!["127.0.0.1:0".to_string()],
+            state.clone(),
+        )
+        .with_authorization_policy(
+            ServiceAuthorizationPolicy::new(Box::new(HashVerifier))
+                .with_trusted_key(HashSigner.public_key().to_vec()),
+        );
+
+        dispatcher.set_handler(Box::new(handler));
+        let mut connect_request = ServiceConnectRequest::new();
+        connect_request.set_circuit("alpha".into());
+        connect_request.set_service_id("abc".into());
+        let connect_bytes = connect_request.write_to_bytes().unwrap();
+
+        dispatcher
+            .dispatch(
+                "abc".into(),
+                &CircuitMessageType::SERVICE_CONNECT_REQUEST,
+                connect_bytes,
+            )
+            .unwrap();
+
+        let id = ServiceId::new("alpha".into(), "abc".into());
+        assert!(state.get_service(&id).unwrap().is_none());
+
+        let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+        assert_network_message(
+            message,
+            id.into(),
+            "abc",
+            CircuitMessageType::SERVICE_CONNECT_RESPONSE,
+            |msg: ServiceConnectResponse| {
+                assert_eq!(
+                    msg.get_status(),
+                    ServiceConnectResponse_Status::ERROR_UNAUTHORIZED
+                );
+            },
+        );
+
+        let token = ServiceRegistrationToken::new(
+            "alpha",
+            "abc",
+            std::time::Duration::from_secs(60),
+            &HashSigner,
+        )
+        .unwrap();
+        connect_request.set_token(ProtoToken::from(token));
+        let connect_bytes = connect_request.write_to_bytes().unwrap();
+
+        dispatcher
+            .dispatch(
+                "abc".into(),
+                &CircuitMessageType::SERVICE_CONNECT_REQUEST,
+                connect_bytes,
+            )
+            .unwrap();
+
+        let id = ServiceId::new("alpha".into(), "abc".into());
+        assert!(state.get_service(&id).unwrap().is_some());
+
+        let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+        assert_network_message(
+            message,
+            id.into(),
+            "abc",
+            CircuitMessageType::SERVICE_CONNECT_RESPONSE,
+            |msg: ServiceConnectResponse| {
+                assert_eq!(msg.get_status(), ServiceConnectResponse_Status::OK);
+            },
+        )
+    }
+
     #[test]
     // Test that if the circuit does not exist, a ServiceDisconnectResponse is returned with
     // a ERROR_CIRCUIT_DOES_NOT_EXIST
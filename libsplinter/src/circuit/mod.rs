@@ -16,6 +16,8 @@
 pub mod component;
 pub mod directory;
 pub mod handlers;
+#[cfg(feature = "circuit-relay")]
+mod relay;
 #[cfg(feature = "routing-table")]
 pub mod routing;
 pub mod service;
@@ -29,9 +31,17 @@ use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{self, Display, Formatter};
+#[cfg(feature = "routing-table")]
+use std::sync::Mutex;
 use std::sync::{Arc, RwLock};
 
 use crate::circuit::directory::CircuitDirectory;
+#[cfg(feature = "circuit-relay")]
+pub use crate::circuit::relay::NodeRoute;
+#[cfg(feature = "circuit-relay")]
+use crate::circuit::relay::{relay_aad, RelayCipher, RelayRegistrations, RelayRoutes};
+#[cfg(feature = "routing-table")]
+use crate::circuit::routing::{RoutingTableReader, RoutingTableWriter};
 use crate::circuit::service::{Service, ServiceId, SplinterNode};
 use crate::circuit::store::{CircuitFilter, CircuitIter, CircuitStore, CircuitStoreError};
 use crate::storage::get_storage;
@@ -360,6 +370,23 @@ pub enum DurabilityType {
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub enum RouteType {
     Any,
+    /// Messages between members are forwarded through the relay node, which is not a member of
+    /// the circuit.
+    #[cfg(feature = "circuit-relay")]
+    Relayed {
+        relay_node: String,
+    },
+}
+
+impl RouteType {
+    /// Returns the relay node that messages between members are forwarded through, if any.
+    #[cfg(feature = "circuit-relay")]
+    pub fn relay_node(&self) -> Option<&str> {
+        match self {
+            RouteType::Any => None,
+            RouteType::Relayed { relay_node } => Some(relay_node),
+        }
+    }
 }
 
 pub enum RosterIter<'r> {
@@ -395,17 +422,76 @@ pub struct SplinterState {
     circuit_directory: Arc<RwLock<CircuitDirectory>>,
     // Service id to Service that contains the node the service is connected to. Not persisted.
     service_directory: Arc<RwLock<HashMap<ServiceId, Service>>>,
+    // The routes that admin messages to the members of circuits and proposals are sent through.
+    // Not persisted.
+    #[cfg(feature = "circuit-relay")]
+    relay_routes: Arc<RwLock<RelayRoutes>>,
+    // The circuits that other nodes have registered with this node as their relay node. Not
+    // persisted.
+    #[cfg(feature = "circuit-relay")]
+    relay_registrations: Arc<RwLock<RelayRegistrations>>,
+    // The key pair used to encrypt the payloads of relayed messages end to end
+    #[cfg(feature = "circuit-relay")]
+    relay_cipher: Arc<RelayCipher>,
+    // The routing table that circuits and nodes are written through to. If set, it decides which
+    // circuits messages are routed on.
+    #[cfg(feature = "routing-table")]
+    routing_table: Option<SharedRoutingTable>,
+}
+
+#[cfg(feature = "routing-table")]
+#[derive(Clone)]
+struct SharedRoutingTable {
+    reader: Arc<dyn RoutingTableReader>,
+    writer: Arc<Mutex<Box<dyn RoutingTableWriter>>>,
 }
 
 impl SplinterState {
     pub fn new(storage_location: String, circuit_directory: CircuitDirectory) -> Self {
+        #[cfg(feature = "circuit-relay")]
+        let mut relay_routes = RelayRoutes::default();
+        #[cfg(feature = "circuit-relay")]
+        for (circuit_id, circuit) in circuit_directory.circuits() {
+            relay_routes.add_circuit_route(
+                circuit_id.to_string(),
+                circuit.members().to_vec(),
+                circuit.routes().relay_node().map(String::from),
+            );
+        }
+
         SplinterState {
             storage_location,
             circuit_directory: Arc::new(RwLock::new(circuit_directory)),
             service_directory: Arc::new(RwLock::new(HashMap::new())),
+            #[cfg(feature = "circuit-relay")]
+            relay_routes: Arc::new(RwLock::new(relay_routes)),
+            #[cfg(feature = "circuit-relay")]
+            relay_registrations: Arc::new(RwLock::new(RelayRegistrations::default())),
+            #[cfg(feature = "circuit-relay")]
+            relay_cipher: Arc::new(RelayCipher::default()),
+            #[cfg(feature = "routing-table")]
+            routing_table: None,
         }
     }
 
+    /// Sets the routing table that circuits and nodes are written through to.
+    ///
+    /// The routing table is expected to already contain the circuits and nodes of the circuit
+    /// directory. Once set, only circuits in the routing table are returned by `circuit` and
+    /// `has_circuit`, so a circuit is not routed on until it has been added to the table.
+    #[cfg(feature = "routing-table")]
+    pub fn with_routing_table(
+        mut self,
+        reader: Box<dyn RoutingTableReader>,
+        writer: Box<dyn RoutingTableWriter>,
+    ) -> Self {
+        self.routing_table = Some(SharedRoutingTable {
+            reader: Arc::from(reader),
+            writer: Arc::new(Mutex::new(writer)),
+        });
+        self
+    }
+
     pub fn storage_location(&self) -> &str {
         &self.storage_location
     }
@@ -426,6 +512,37 @@ impl SplinterState {
         Ok(())
     }
 
+    #[cfg(feature = "routing-table")]
+    fn routing_table_writer(
+        &self,
+    ) -> Result<Option<std::sync::MutexGuard<'_, Box<dyn RoutingTableWriter>>>, SplinterStateError>
+    {
+        self.routing_table
+            .as_ref()
+            .map(|routing_table| {
+                routing_table.writer.lock().map_err(|_| {
+                    SplinterStateError::new("Failed to lock routing table writer".into())
+                })
+            })
+            .transpose()
+    }
+
+    // Returns whether the circuit is in the routing table; true if there is no routing table.
+    #[cfg(feature = "routing-table")]
+    fn is_routed_circuit(&self, circuit_id: &str) -> Result<bool, SplinterStateError> {
+        let routing_table = match &self.routing_table {
+            Some(routing_table) => routing_table,
+            None => return Ok(true),
+        };
+        routing_table
+            .reader
+            .fetch_circuit(circuit_id)
+            .map(|circuit| circuit.is_some())
+            .map_err(|err| {
+                SplinterStateError::from_source("Failed to read routing table".into(), err)
+            })
+    }
+
     // ---------- methods to access service directory ----------
 
     pub fn get_service(&self, id: &ServiceId) -> Result<Option<Service>, SplinterStateError> {
@@ -470,6 +587,285 @@ impl SplinterState {
         Ok(())
     }
 
+    // ---------- methods to access relay routes ----------
+
+    /// Returns how admin messages to the given node are sent, according to the circuits and
+    /// proposals that have the node as a member.
+    #[cfg(feature = "circuit-relay")]
+    pub fn node_route(&self, node_id: &str) -> Result<NodeRoute, SplinterStateError> {
+        let relay_routes = self
+            .relay_routes
+            .read()
+            .map_err(|_| SplinterStateError::new("Failed to read relay routes".into()))?;
+
+        Ok(relay_routes.node_route(node_id))
+    }
+
+    /// Returns the relay node that admin messages to the given node are sent through, if the node
+    /// is not reached directly.
+    #[cfg(feature = "circuit-relay")]
+    pub fn relay_route(&self, node_id: &str) -> Result<Option<String>, SplinterStateError> {
+        let relay_routes = self
+            .relay_routes
+            .read()
+            .map_err(|_| SplinterStateError::new("Failed to read relay routes".into()))?;
+
+        Ok(relay_routes.relay_route(node_id))
+    }
+
+    /// Records the members of a circuit proposal and the relay node, if any, that admin messages
+    /// to them are sent through. The routes of circuits are recorded when they are added.
+    #[cfg(feature = "circuit-relay")]
+    pub fn add_circuit_route(
+        &self,
+        circuit_id: String,
+        members: Vec<String>,
+        relay_node: Option<String>,
+    ) -> Result<(), SplinterStateError> {
+        let mut relay_routes = self.relay_routes.write().map_err(|_| {
+            SplinterStateError::new("Failed to get write guard for relay routes".into())
+        })?;
+
+        relay_routes.add_circuit_route(circuit_id, members, relay_node);
+
+        Ok(())
+    }
+
+    /// Removes the routes of a circuit proposal, unless the circuit has been added.
+    #[cfg(feature = "circuit-relay")]
+    pub fn remove_circuit_route(&self, circuit_id: &str) -> Result<(), SplinterStateError> {
+        let circuit_directory = self
+            .circuit_directory
+            .read()
+            .map_err(|_| SplinterStateError::new("Failed to read circuit directory".into()))?;
+        if circuit_directory.circuit(circuit_id).is_some() {
+            return Ok(());
+        }
+
+        let mut relay_routes = self.relay_routes.write().map_err(|_| {
+            SplinterStateError::new("Failed to get write guard for relay routes".into())
+        })?;
+
+        relay_routes.remove_circuit_route(circuit_id);
+
+        Ok(())
+    }
+
+    /// Records that an admin message from a node that is not a member of any known circuit or
+    /// proposal was received through the relay node, so replies to it are sent back through the
+    /// same relay. Returns false if too many such routes have already been recorded.
+    #[cfg(feature = "circuit-relay")]
+    pub fn learn_relay_route(
+        &self,
+        node_id: String,
+        relay_node: String,
+    ) -> Result<bool, SplinterStateError> {
+        let mut relay_routes = self.relay_routes.write().map_err(|_| {
+            SplinterStateError::new("Failed to get write guard for relay routes".into())
+        })?;
+
+        Ok(relay_routes.learn_relay_route(node_id, relay_node))
+    }
+
+    /// Removes the routes learned from admin messages received through the relay node.
+    #[cfg(feature = "circuit-relay")]
+    pub fn remove_learned_relay_routes(&self, relay_node: &str) -> Result<(), SplinterStateError> {
+        let mut relay_routes = self.relay_routes.write().map_err(|_| {
+            SplinterStateError::new("Failed to get write guard for relay routes".into())
+        })?;
+
+        relay_routes.remove_learned_routes(relay_node);
+
+        Ok(())
+    }
+
+    /// Returns the nodes that admin messages are sent to through the given relay node.
+    #[cfg(feature = "circuit-relay")]
+    pub fn relayed_nodes(&self, relay_node: &str) -> Result<Vec<String>, SplinterStateError> {
+        let relay_routes = self
+            .relay_routes
+            .read()
+            .map_err(|_| SplinterStateError::new("Failed to read relay routes".into()))?;
+
+        Ok(relay_routes.relayed_nodes(relay_node))
+    }
+
+    /// Returns the relay node of the circuit or proposal, if its route has been recorded and is
+    /// relayed.
+    #[cfg(feature = "circuit-relay")]
+    pub fn circuit_relay_node(
+        &self,
+        circuit_id: &str,
+    ) -> Result<Option<String>, SplinterStateError> {
+        let relay_routes = self
+            .relay_routes
+            .read()
+            .map_err(|_| SplinterStateError::new("Failed to read relay routes".into()))?;
+
+        Ok(relay_routes.circuit_relay_node(circuit_id))
+    }
+
+    /// Returns the circuits and proposals with relayed routes through the given relay node, with
+    /// their members.
+    #[cfg(feature = "circuit-relay")]
+    pub fn relayed_circuits(
+        &self,
+        relay_node: &str,
+    ) -> Result<Vec<(String, Vec<String>)>, SplinterStateError> {
+        let relay_routes = self
+            .relay_routes
+            .read()
+            .map_err(|_| SplinterStateError::new("Failed to read relay routes".into()))?;
+
+        Ok(relay_routes.relayed_circuits(relay_node))
+    }
+
+    // ---------- methods to access relay registrations ----------
+
+    /// Records the members of a circuit or proposal that a node registered with this node as its
+    /// relay node. Returns false if the node has already registered too many circuits.
+    #[cfg(feature = "circuit-relay")]
+    pub fn register_relayed_circuit(
+        &self,
+        circuit_id: String,
+        node_id: String,
+        members: Vec<String>,
+    ) -> Result<bool, SplinterStateError> {
+        let mut relay_registrations = self.relay_registrations.write().map_err(|_| {
+            SplinterStateError::new("Failed to get write guard for relay registrations".into())
+        })?;
+
+        Ok(relay_registrations.register(circuit_id, node_id, members))
+    }
+
+    /// Removes a circuit or proposal that a node registered with this node as its relay node.
+    #[cfg(feature = "circuit-relay")]
+    pub fn unregister_relayed_circuit(
+        &self,
+        circuit_id: &str,
+        node_id: &str,
+    ) -> Result<(), SplinterStateError> {
+        let mut relay_registrations = self.relay_registrations.write().map_err(|_| {
+            SplinterStateError::new("Failed to get write guard for relay registrations".into())
+        })?;
+
+        relay_registrations.unregister(circuit_id, node_id);
+
+        Ok(())
+    }
+
+    /// Removes all circuits and proposals the node registered with this node as its relay node.
+    #[cfg(feature = "circuit-relay")]
+    pub fn remove_relay_registrations(&self, node_id: &str) -> Result<(), SplinterStateError> {
+        let mut relay_registrations = self.relay_registrations.write().map_err(|_| {
+            SplinterStateError::new("Failed to get write guard for relay registrations".into())
+        })?;
+
+        relay_registrations.remove_node(node_id);
+
+        Ok(())
+    }
+
+    /// Returns whether this node relays circuit messages on the circuit between the nodes, which
+    /// is the case if both registered the circuit with this node, naming both as members.
+    #[cfg(feature = "circuit-relay")]
+    pub fn relays_circuit(
+        &self,
+        circuit_id: &str,
+        source: &str,
+        destination: &str,
+    ) -> Result<bool, SplinterStateError> {
+        let relay_registrations = self
+            .relay_registrations
+            .read()
+            .map_err(|_| SplinterStateError::new("Failed to read relay registrations".into()))?;
+
+        Ok(relay_registrations.has_circuit_members(circuit_id, source, destination))
+    }
+
+    /// Returns whether this node relays admin messages between the nodes, which is the case if
+    /// either registered a circuit or proposal with this node, naming both as members.
+    #[cfg(feature = "circuit-relay")]
+    pub fn relays_admin_messages(
+        &self,
+        source: &str,
+        destination: &str,
+    ) -> Result<bool, SplinterStateError> {
+        let relay_registrations = self
+            .relay_registrations
+            .read()
+            .map_err(|_| SplinterStateError::new("Failed to read relay registrations".into()))?;
+
+        Ok(relay_registrations.has_members(source, destination))
+    }
+
+    // ---------- methods to encrypt relayed payloads ----------
+
+    /// Returns this node's public key, which is sent with messages sent through a relay node so
+    /// that the destination node can decrypt them and encrypt its replies.
+    #[cfg(feature = "circuit-relay")]
+    pub fn relay_public_key(&self) -> Result<Vec<u8>, SplinterStateError> {
+        self.relay_cipher.public_key().map_err(|err| {
+            SplinterStateError::from_source("Failed to get relay public key".into(), err)
+        })
+    }
+
+    /// Records the public key sent by a node with a message received through a relay node.
+    #[cfg(feature = "circuit-relay")]
+    pub fn add_relay_public_key(
+        &self,
+        node_id: &str,
+        public_key: &[u8],
+    ) -> Result<(), SplinterStateError> {
+        self.relay_cipher
+            .add_public_key(node_id, public_key)
+            .map_err(|err| {
+                SplinterStateError::from_source("Failed to add relay public key".into(), err)
+            })
+    }
+
+    /// Encrypts the payload of a message sent to a node through a relay node, returning `None`
+    /// if the node's public key has not been received.
+    #[cfg(feature = "circuit-relay")]
+    pub fn encrypt_relayed_payload(
+        &self,
+        node_id: &str,
+        circuit: &str,
+        sender: &str,
+        recipient: &str,
+        payload: &[u8],
+    ) -> Result<Option<Vec<u8>>, SplinterStateError> {
+        self.relay_cipher
+            .encrypt(node_id, &relay_aad(circuit, sender, recipient), payload)
+            .map_err(|err| {
+                SplinterStateError::from_source("Failed to encrypt relayed payload".into(), err)
+            })
+    }
+
+    /// Decrypts the payload of a message received from a node through a relay node, and records
+    /// the public key it was sent with.
+    #[cfg(feature = "circuit-relay")]
+    pub fn decrypt_relayed_payload(
+        &self,
+        node_id: &str,
+        public_key: &[u8],
+        circuit: &str,
+        sender: &str,
+        recipient: &str,
+        payload: &[u8],
+    ) -> Result<Vec<u8>, SplinterStateError> {
+        self.relay_cipher
+            .decrypt(
+                node_id,
+                public_key,
+                &relay_aad(circuit, sender, recipient),
+                payload,
+            )
+            .map_err(|err| {
+                SplinterStateError::from_source("Failed to decrypt relayed payload".into(), err)
+            })
+    }
+
     // ---------- methods to access circuit directory ----------
 
     pub fn circuit_directory(&self) -> Result<CircuitDirectory, SplinterStateError> {
@@ -490,9 +886,23 @@ impl SplinterState {
                 SplinterStateError::new("Failed to get write guard for circuit directory".into())
             })?;
 
-            circuit_directory.add_node(id, node);
+            circuit_directory.add_node(id.clone(), node.clone());
         }
         self.commit_circuit_directory()?;
+
+        #[cfg(feature = "routing-table")]
+        {
+            if let Some(mut writer) = self.routing_table_writer()? {
+                writer
+                    .add_node(id, routing::CircuitNode::from(&node))
+                    .map_err(|err| {
+                        SplinterStateError::from_source(
+                            "Failed to add node to routing table".into(),
+                            err,
+                        )
+                    })?;
+            }
+        }
         Ok(())
     }
 
@@ -501,13 +911,41 @@ impl SplinterState {
         name: String,
         circuit: Circuit,
     ) -> Result<(), SplinterStateError> {
+        #[cfg(feature = "routing-table")]
+        let routed_circuit = routing::Circuit::from(&circuit);
+        #[cfg(any(feature = "circuit-relay", feature = "routing-table"))]
+        let members = circuit.members().to_vec();
+        #[cfg(feature = "circuit-relay")]
+        let relay_node = circuit.routes().relay_node().map(String::from);
         {
             let mut circuit_directory = self.circuit_directory.write().map_err(|_| {
                 SplinterStateError::new("Failed to get write guard for circuit directory".into())
             })?;
-            circuit_directory.add_circuit(name, circuit);
+            circuit_directory.add_circuit(name.clone(), circuit);
         }
         self.commit_circuit_directory()?;
+
+        #[cfg(feature = "circuit-relay")]
+        self.add_circuit_route(name.clone(), members.clone(), relay_node)?;
+
+        #[cfg(feature = "routing-table")]
+        {
+            if let Some(mut writer) = self.routing_table_writer()? {
+                let nodes = members
+                    .iter()
+                    .filter_map(|member| self.node(member).transpose())
+                    .map(|node| node.map(|node| routing::CircuitNode::from(&node)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                writer
+                    .add_circuit(name, routed_circuit, nodes)
+                    .map_err(|err| {
+                        SplinterStateError::from_source(
+                            "Failed to add circuit to routing table".into(),
+                            err,
+                        )
+                    })?;
+            }
+        }
         Ok(())
     }
 
@@ -519,6 +957,18 @@ impl SplinterState {
             circuit_directory.remove_node(id);
         }
         self.commit_circuit_directory()?;
+
+        #[cfg(feature = "routing-table")]
+        {
+            if let Some(mut writer) = self.routing_table_writer()? {
+                writer.remove_node(id).map_err(|err| {
+                    SplinterStateError::from_source(
+                        "Failed to remove node from routing table".into(),
+                        err,
+                    )
+                })?;
+            }
+        }
         Ok(())
     }
 
@@ -530,6 +980,21 @@ impl SplinterState {
             circuit_directory.remove_circuit(name);
         }
         self.commit_circuit_directory()?;
+
+        #[cfg(feature = "circuit-relay")]
+        self.remove_circuit_route(name)?;
+
+        #[cfg(feature = "routing-table")]
+        {
+            if let Some(mut writer) = self.routing_table_writer()? {
+                writer.remove_circuit(name).map_err(|err| {
+                    SplinterStateError::from_source(
+                        "Failed to remove circuit from routing table".into(),
+                        err,
+                    )
+                })?;
+            }
+        }
         Ok(())
     }
 
@@ -558,6 +1023,13 @@ impl SplinterState {
     }
 
     pub fn circuit(&self, circuit_name: &str) -> Result<Option<Circuit>, SplinterStateError> {
+        #[cfg(feature = "routing-table")]
+        {
+            if !self.is_routed_circuit(circuit_name)? {
+                return Ok(None);
+            }
+        }
+
         let circuit_directory = self
             .circuit_directory
             .read()
@@ -566,6 +1038,13 @@ impl SplinterState {
     }
 
     pub fn has_circuit(&self, circuit_name: &str) -> Result<bool, SplinterStateError> {
+        #[cfg(feature = "routing-table")]
+        {
+            if !self.is_routed_circuit(circuit_name)? {
+                return Ok(false);
+            }
+        }
+
         let circuit_directory = self
             .circuit_directory
             .read()
@@ -796,4 +1275,82 @@ mod tests {
         // Check that state does not have any nodes
         assert!(storage.read().nodes().len() == 0);
     }
+
+    // Verify that circuits and nodes added through SplinterState are written through to a
+    // database-backed routing table, and are still routed on after a restart.
+    //
+    // 1. Create a SplinterState with a routing table backed by a SQLite database
+    // 2. Add a node and a circuit through the state, and verify they are in the routing table
+    // 3. Drop the state and routing table, then create new ones from the same storage file and
+    //    database, simulating a restart
+    // 4. Verify the node and circuit are still in the routing table and the circuit is returned
+    //    by the state
+    // 5. Remove the circuit and verify it is no longer returned
+    #[cfg(all(feature = "routing-table-database", feature = "sqlite"))]
+    #[test]
+    fn test_routing_table_survives_restart() {
+        use diesel::r2d2::{ConnectionManager, Pool};
+        use diesel::sqlite::SqliteConnection;
+
+        use crate::circuit::routing::diesel::{
+            migrations::run_sqlite_migrations, DieselRoutingTable,
+        };
+
+        let temp_dir = TempDir::new("test_routing_table_survives_restart").unwrap();
+        let path = setup_storage(temp_dir.path().to_path_buf());
+
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(ConnectionManager::<SqliteConnection>::new(":memory:"))
+            .expect("Failed to build connection pool");
+        run_sqlite_migrations(&*pool.get().expect("Failed to get connection for migrations"))
+            .expect("Failed to run migrations");
+
+        let node = SplinterNode::new("123".into(), vec!["tcp://127.0.0.1:8000".into()]);
+        let circuit = Circuit::builder()
+            .with_id("alpha".into())
+            .with_auth(AuthorizationType::Trust)
+            .with_members(vec!["123".into()])
+            .with_roster(vec!["abc".into(), "def".into()])
+            .with_persistence(PersistenceType::Any)
+            .with_durability(DurabilityType::NoDurability)
+            .with_routes(RouteType::Any)
+            .with_circuit_management_type("test_app".into())
+            .build()
+            .expect("Should have built a correct circuit");
+
+        {
+            let mut storage = get_storage(&path, CircuitDirectory::new).unwrap();
+            let circuit_directory = storage.write().clone();
+            let routing_table = DieselRoutingTable::new(pool.clone());
+            let mut state = SplinterState::new(path.to_string(), circuit_directory)
+                .with_routing_table(Box::new(routing_table.clone()), Box::new(routing_table));
+
+            state.add_node("123".into(), node.clone()).unwrap();
+            state.add_circuit("alpha".into(), circuit.clone()).unwrap();
+        }
+
+        // restart with a new routing table, which has an empty cache
+        let routing_table = DieselRoutingTable::new(pool);
+        assert_eq!(
+            routing_table.fetch_node("123").unwrap(),
+            Some(routing::CircuitNode::from(&node))
+        );
+        assert_eq!(
+            routing_table.fetch_circuit("alpha").unwrap(),
+            Some(routing::Circuit::from(&circuit))
+        );
+
+        let mut storage = get_storage(&path, CircuitDirectory::new).unwrap();
+        let circuit_directory = storage.write().clone();
+        let mut state = SplinterState::new(path.to_string(), circuit_directory).with_routing_table(
+            Box::new(routing_table.clone()),
+            Box::new(routing_table.clone()),
+        );
+        assert_eq!(state.circuit("alpha").unwrap(), Some(circuit));
+
+        state.remove_circuit("alpha").unwrap();
+        assert_eq!(state.circuit("alpha").unwrap(), None);
+        assert_eq!(routing_table.fetch_circuit("alpha").unwrap(), None);
+    }
 }
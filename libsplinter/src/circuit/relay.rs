@@ -0,0 +1,545 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The routes that messages take to nodes that are reached through a relay node, the circuits a
+//! relay node forwards messages for, and the encryption of relayed payloads.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use openssl::derive::Deriver;
+use openssl::ec::{EcGroup, EcKey};
+use openssl::error::ErrorStack;
+use openssl::nid::Nid;
+use openssl::pkey::{PKey, Private};
+use openssl::rand::rand_bytes;
+use openssl::sha::Sha256;
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+
+/// The maximum number of routes learned from relayed admin messages
+const MAX_LEARNED_ROUTES: usize = 1024;
+/// The maximum number of circuits a node may register with its relay node
+const MAX_REGISTRATIONS_PER_NODE: usize = 1024;
+
+const IV_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+/// How admin messages are sent to a node
+#[derive(Clone, Debug, PartialEq)]
+pub enum NodeRoute {
+    /// The node is a member of a circuit or proposal whose members are connected to directly
+    Direct,
+    /// The node is only a member of circuits or proposals with relayed routes, which name these
+    /// relay nodes
+    Relayed(Vec<String>),
+    /// The node is not a member of any circuit or proposal that this node knows about
+    Unknown,
+}
+
+struct CircuitRoute {
+    members: Vec<String>,
+    relay_node: Option<String>,
+}
+
+/// The routes to the members of the circuits and proposals this node knows about.
+///
+/// The circuits and proposals decide which relay node, if any, a member is reached through. A
+/// route learned from a relayed admin message is only used for nodes that are not a member of any
+/// of them, so that the node can reply to a message from a node proposing a circuit it does not
+/// know about yet.
+#[derive(Default)]
+pub(crate) struct RelayRoutes {
+    // Circuit or proposal ID to its members and relay node
+    circuits: BTreeMap<String, CircuitRoute>,
+    // Node ID to the relay node an admin message from it was received through
+    learned: HashMap<String, String>,
+}
+
+impl RelayRoutes {
+    pub fn add_circuit_route(
+        &mut self,
+        circuit_id: String,
+        members: Vec<String>,
+        relay_node: Option<String>,
+    ) {
+        self.circuits.insert(
+            circuit_id,
+            CircuitRoute {
+                members,
+                relay_node,
+            },
+        );
+    }
+
+    pub fn remove_circuit_route(&mut self, circuit_id: &str) {
+        self.circuits.remove(circuit_id);
+    }
+
+    pub fn node_route(&self, node_id: &str) -> NodeRoute {
+        let mut relays: Vec<String> = vec![];
+        for route in self
+            .circuits
+            .values()
+            .filter(|route| route.members.iter().any(|member| member == node_id))
+        {
+            match &route.relay_node {
+                None => return NodeRoute::Direct,
+                Some(relay_node) => {
+                    if !relays.contains(relay_node) {
+                        relays.push(relay_node.to_string())
+                    }
+                }
+            }
+        }
+
+        if relays.is_empty() {
+            NodeRoute::Unknown
+        } else {
+            NodeRoute::Relayed(relays)
+        }
+    }
+
+    pub fn relay_route(&self, node_id: &str) -> Option<String> {
+        match self.node_route(node_id) {
+            NodeRoute::Direct => None,
+            NodeRoute::Relayed(relays) => relays.into_iter().next(),
+            NodeRoute::Unknown => self.learned.get(node_id).cloned(),
+        }
+    }
+
+    /// Records the relay node an admin message from an unknown node was received through.
+    /// Returns false if the route was not recorded because too many routes have been learned.
+    pub fn learn_relay_route(&mut self, node_id: String, relay_node: String) -> bool {
+        if self.learned.len() >= MAX_LEARNED_ROUTES && !self.learned.contains_key(&node_id) {
+            return false;
+        }
+
+        self.learned.insert(node_id, relay_node);
+        true
+    }
+
+    pub fn remove_learned_routes(&mut self, relay_node: &str) {
+        self.learned.retain(|_, relay| relay != relay_node);
+    }
+
+    /// Returns the relay node of the circuit or proposal, if it has relayed routes.
+    pub fn circuit_relay_node(&self, circuit_id: &str) -> Option<String> {
+        self.circuits
+            .get(circuit_id)
+            .and_then(|route| route.relay_node.clone())
+    }
+
+    /// Returns the circuits and proposals with relayed routes through the relay node, with their
+    /// members.
+    pub fn relayed_circuits(&self, relay_node: &str) -> Vec<(String, Vec<String>)> {
+        self.circuits
+            .iter()
+            .filter(|(_, route)| route.relay_node.as_deref() == Some(relay_node))
+            .map(|(circuit_id, route)| (circuit_id.to_string(), route.members.clone()))
+            .collect()
+    }
+
+    pub fn relayed_nodes(&self, relay_node: &str) -> Vec<String> {
+        let mut nodes: Vec<String> = vec![];
+        for route in self
+            .circuits
+            .values()
+            .filter(|route| route.relay_node.as_deref() == Some(relay_node))
+        {
+            for member in route.members.iter() {
+                if !nodes.contains(member)
+                    && self.relay_route(member).as_deref() == Some(relay_node)
+                {
+                    nodes.push(member.to_string());
+                }
+            }
+        }
+        for (node_id, relay) in self.learned.iter() {
+            if relay == relay_node && !nodes.contains(node_id) {
+                nodes.push(node_id.to_string());
+            }
+        }
+        nodes
+    }
+}
+
+/// The circuits and proposals that nodes have registered with this node as their relay node.
+///
+/// The relay node does not have the circuits it forwards messages for, so it only forwards
+/// messages between nodes that the registrations name as members of the same circuit.
+#[derive(Default)]
+pub(crate) struct RelayRegistrations {
+    // Circuit or proposal ID to the registering node to the members it registered
+    circuits: HashMap<String, HashMap<String, Vec<String>>>,
+}
+
+impl RelayRegistrations {
+    /// Records the members of a circuit registered by one of them. Returns false if the node
+    /// has already registered too many circuits.
+    pub fn register(&mut self, circuit_id: String, node_id: String, members: Vec<String>) -> bool {
+        let registered = self
+            .circuits
+            .values()
+            .filter(|registrations| registrations.contains_key(&node_id))
+            .count();
+        let is_new = self
+            .circuits
+            .get(&circuit_id)
+            .map(|registrations| !registrations.contains_key(&node_id))
+            .unwrap_or(true);
+        if is_new && registered >= MAX_REGISTRATIONS_PER_NODE {
+            return false;
+        }
+
+        self.circuits
+            .entry(circuit_id)
+            .or_default()
+            .insert(node_id, members);
+        true
+    }
+
+    pub fn unregister(&mut self, circuit_id: &str, node_id: &str) {
+        if let Some(registrations) = self.circuits.get_mut(circuit_id) {
+            registrations.remove(node_id);
+            if registrations.is_empty() {
+                self.circuits.remove(circuit_id);
+            }
+        }
+    }
+
+    pub fn remove_node(&mut self, node_id: &str) {
+        for registrations in self.circuits.values_mut() {
+            registrations.remove(node_id);
+        }
+        self.circuits
+            .retain(|_, registrations| !registrations.is_empty());
+    }
+
+    /// Returns whether both nodes registered the circuit, each naming both as members.
+    pub fn has_circuit_members(&self, circuit_id: &str, source: &str, destination: &str) -> bool {
+        self.circuits
+            .get(circuit_id)
+            .map(|registrations| {
+                [source, destination].iter().all(|node_id| {
+                    registrations
+                        .get(*node_id)
+                        .map(|members| {
+                            is_member(members, source) && is_member(members, destination)
+                        })
+                        .unwrap_or(false)
+                })
+            })
+            .unwrap_or(false)
+    }
+
+    /// Returns whether either node registered a circuit or proposal naming both as members.
+    pub fn has_members(&self, source: &str, destination: &str) -> bool {
+        self.circuits.values().any(|registrations| {
+            [source, destination].iter().any(|node_id| {
+                registrations
+                    .get(*node_id)
+                    .map(|members| is_member(members, source) && is_member(members, destination))
+                    .unwrap_or(false)
+            })
+        })
+    }
+}
+
+fn is_member(members: &[String], node_id: &str) -> bool {
+    members.iter().any(|member| member == node_id)
+}
+
+/// Encrypts the payloads of relayed messages between the origin and destination nodes, so that
+/// the relay node forwarding them cannot read them.
+///
+/// Each node has a P-256 key pair, which is generated when first used and is not persisted. A
+/// node's public key is sent with every message it sends through a relay, and the destination
+/// records the latest key received from each node. A payload is encrypted with AES-256-GCM, using
+/// a key derived from the ECDH shared secret of the two nodes' keys, and is authenticated along
+/// with the circuit, sender and recipient of the message.
+///
+/// The public keys are not signed, so this only protects payloads from a relay node that reads
+/// the messages it forwards, not from one that replaces the public keys in them.
+#[derive(Default)]
+pub(crate) struct RelayCipher {
+    key: Mutex<Option<PKey<Private>>>,
+    // Node ID to the DER-encoded public key last received from it
+    public_keys: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl RelayCipher {
+    /// Returns the DER-encoded public key of this node.
+    pub fn public_key(&self) -> Result<Vec<u8>, RelayCipherError> {
+        self.with_key(|key| Ok(key.public_key_to_der()?))
+    }
+
+    /// Records the public key received from the node, if it is a valid key.
+    pub fn add_public_key(&self, node_id: &str, public_key: &[u8]) -> Result<(), RelayCipherError> {
+        PKey::public_key_from_der(public_key)?;
+        self.public_keys
+            .lock()
+            .map_err(|_| RelayCipherError::PoisonedLock)?
+            .insert(node_id.to_string(), public_key.to_vec());
+        Ok(())
+    }
+
+    /// Encrypts the payload for the node, returning `None` if no public key has been received
+    /// from the node.
+    pub fn encrypt(
+        &self,
+        node_id: &str,
+        aad: &[u8],
+        payload: &[u8],
+    ) -> Result<Option<Vec<u8>>, RelayCipherError> {
+        let public_key = match self
+            .public_keys
+            .lock()
+            .map_err(|_| RelayCipherError::PoisonedLock)?
+            .get(node_id)
+        {
+            Some(public_key) => public_key.clone(),
+            None => return Ok(None),
+        };
+
+        let secret = self.shared_secret(&public_key)?;
+        let mut iv = [0; IV_LEN];
+        rand_bytes(&mut iv)?;
+        let mut tag = [0; TAG_LEN];
+        let ciphertext = encrypt_aead(
+            Cipher::aes_256_gcm(),
+            &secret,
+            Some(&iv),
+            aad,
+            payload,
+            &mut tag,
+        )?;
+
+        let mut encrypted = iv.to_vec();
+        encrypted.extend(ciphertext);
+        encrypted.extend(&tag);
+        Ok(Some(encrypted))
+    }
+
+    /// Decrypts a payload from the node that sent the given public key, and records the key.
+    pub fn decrypt(
+        &self,
+        node_id: &str,
+        public_key: &[u8],
+        aad: &[u8],
+        encrypted: &[u8],
+    ) -> Result<Vec<u8>, RelayCipherError> {
+        if encrypted.len() < IV_LEN + TAG_LEN {
+            return Err(RelayCipherError::InvalidPayload);
+        }
+
+        let secret = self.shared_secret(public_key)?;
+        let (iv, rest) = encrypted.split_at(IV_LEN);
+        let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LEN);
+        let payload = decrypt_aead(
+            Cipher::aes_256_gcm(),
+            &secret,
+            Some(iv),
+            aad,
+            ciphertext,
+            tag,
+        )?;
+
+        self.add_public_key(node_id, public_key)?;
+        Ok(payload)
+    }
+
+    fn shared_secret(&self, public_key: &[u8]) -> Result<Vec<u8>, RelayCipherError> {
+        let public_key = PKey::public_key_from_der(public_key)?;
+        let secret = self.with_key(|key| {
+            let mut deriver = Deriver::new(key)?;
+            deriver.set_peer(&public_key)?;
+            Ok(deriver.derive_to_vec()?)
+        })?;
+
+        let mut hasher = Sha256::new();
+        hasher.update(&secret);
+        hasher.update(b"splinter-relay");
+        Ok(hasher.finish().to_vec())
+    }
+
+    fn with_key<T>(
+        &self,
+        f: impl FnOnce(&PKey<Private>) -> Result<T, RelayCipherError>,
+    ) -> Result<T, RelayCipherError> {
+        let mut key = self
+            .key
+            .lock()
+            .map_err(|_| RelayCipherError::PoisonedLock)?;
+        if key.is_none() {
+            let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?;
+            *key = Some(PKey::from_ec_key(EcKey::generate(&group)?)?);
+        }
+        match key.as_ref() {
+            Some(key) => f(key),
+            None => Err(RelayCipherError::PoisonedLock),
+        }
+    }
+}
+
+/// Returns the data that is authenticated with an encrypted payload.
+pub(crate) fn relay_aad(circuit: &str, sender: &str, recipient: &str) -> Vec<u8> {
+    [circuit, sender, recipient].join("\0").into_bytes()
+}
+
+#[derive(Debug)]
+pub enum RelayCipherError {
+    Crypto(ErrorStack),
+    InvalidPayload,
+    PoisonedLock,
+}
+
+impl std::error::Error for RelayCipherError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RelayCipherError::Crypto(err) => Some(err),
+            RelayCipherError::InvalidPayload => None,
+            RelayCipherError::PoisonedLock => None,
+        }
+    }
+}
+
+impl std::fmt::Display for RelayCipherError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RelayCipherError::Crypto(err) => write!(f, "relayed payload cipher failed: {}", err),
+            RelayCipherError::InvalidPayload => f.write_str("relayed payload is too short"),
+            RelayCipherError::PoisonedLock => f.write_str("relay cipher lock was poisoned"),
+        }
+    }
+}
+
+impl From<ErrorStack> for RelayCipherError {
+    fn from(err: ErrorStack) -> Self {
+        RelayCipherError::Crypto(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that a member reached directly through any circuit is not routed through a relay,
+    /// that a member of relayed circuits is routed through a relay they name, and that a learned
+    /// route is only used for unknown nodes.
+    #[test]
+    fn test_node_route() {
+        let mut routes = RelayRoutes::default();
+        routes.add_circuit_route(
+            "alpha".into(),
+            vec!["a".into(), "b".into(), "c".into()],
+            Some("relay".into()),
+        );
+        routes.add_circuit_route("beta".into(), vec!["a".into(), "c".into()], None);
+
+        assert_eq!(
+            NodeRoute::Relayed(vec!["relay".into()]),
+            routes.node_route("b")
+        );
+        assert_eq!(Some("relay".to_string()), routes.relay_route("b"));
+        assert_eq!(NodeRoute::Direct, routes.node_route("c"));
+        assert_eq!(None, routes.relay_route("c"));
+        assert_eq!(NodeRoute::Unknown, routes.node_route("d"));
+        assert_eq!(None, routes.relay_route("d"));
+        assert_eq!(vec!["b".to_string()], routes.relayed_nodes("relay"));
+
+        assert!(routes.learn_relay_route("d".into(), "other".into()));
+        assert!(routes.learn_relay_route("b".into(), "other".into()));
+        assert_eq!(Some("other".to_string()), routes.relay_route("d"));
+        assert_eq!(Some("relay".to_string()), routes.relay_route("b"));
+
+        routes.remove_learned_routes("other");
+        assert_eq!(None, routes.relay_route("d"));
+
+        routes.remove_circuit_route("alpha");
+        assert_eq!(NodeRoute::Unknown, routes.node_route("b"));
+        assert!(routes.relayed_nodes("relay").is_empty());
+    }
+
+    /// Test that a relay node only allows circuit messages between nodes that both registered
+    /// the circuit, and admin messages between nodes that either registered, and that a node's
+    /// registrations are removed with it.
+    #[test]
+    fn test_relay_registrations() {
+        let mut registrations = RelayRegistrations::default();
+        assert!(registrations.register("alpha".into(), "a".into(), vec!["a".into(), "b".into()]));
+
+        assert!(!registrations.has_circuit_members("alpha", "a", "b"));
+        assert!(registrations.has_members("a", "b"));
+        assert!(registrations.has_members("b", "a"));
+        assert!(!registrations.has_members("a", "c"));
+
+        assert!(registrations.register("alpha".into(), "b".into(), vec!["a".into(), "b".into()]));
+        assert!(registrations.has_circuit_members("alpha", "a", "b"));
+        assert!(registrations.has_circuit_members("alpha", "b", "a"));
+        assert!(!registrations.has_circuit_members("beta", "a", "b"));
+
+        registrations.unregister("alpha", "b");
+        assert!(!registrations.has_circuit_members("alpha", "a", "b"));
+
+        registrations.remove_node("a");
+        assert!(!registrations.has_members("a", "b"));
+    }
+
+    /// Test that a payload encrypted for a node is decrypted by it with the sender's public key,
+    /// and that decrypting fails with another circuit, sender or recipient than the payload was
+    /// encrypted with.
+    #[test]
+    fn test_relay_cipher() {
+        let alice = RelayCipher::default();
+        let bob = RelayCipher::default();
+
+        let aad = relay_aad("alpha", "abc", "def");
+        assert_eq!(
+            None,
+            alice
+                .encrypt("bob", &aad, b"hello")
+                .expect("Unable to encrypt")
+        );
+
+        alice
+            .add_public_key("bob", &bob.public_key().expect("Unable to get key"))
+            .expect("Unable to add key");
+        let encrypted = alice
+            .encrypt("bob", &aad, b"hello")
+            .expect("Unable to encrypt")
+            .expect("No key for bob");
+        assert_ne!(b"hello".to_vec(), encrypted);
+
+        let alice_key = alice.public_key().expect("Unable to get key");
+        assert!(bob
+            .decrypt(
+                "alice",
+                &alice_key,
+                &relay_aad("alpha", "abc", "xyz"),
+                &encrypted
+            )
+            .is_err());
+        assert_eq!(
+            b"hello".to_vec(),
+            bob.decrypt("alice", &alice_key, &aad, &encrypted)
+                .expect("Unable to decrypt")
+        );
+
+        // bob can now reply, as alice's key was recorded
+        assert!(bob
+            .encrypt("alice", &aad, b"hi")
+            .expect("Unable to encrypt")
+            .is_some());
+    }
+}
@@ -0,0 +1,202 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module contains a set of benchmark tests for storing a large number of circuits, services,
+//! and nodes in the SQLite-backed DieselRoutingTable. They mirror the benchmarks of the in-memory
+//! RoutingTable, so the two implementations can be compared directly.
+
+use super::{
+    migrations::run_sqlite_migrations, CircuitNode, DieselRoutingTable, RoutingTableReader,
+    RoutingTableWriter,
+};
+
+extern crate test;
+
+use test::Bencher;
+
+use diesel::{
+    r2d2::{ConnectionManager, Pool},
+    sqlite::SqliteConnection,
+};
+
+use crate::circuit::routing::{memory::benchmarks::generate_circuits, Circuit};
+
+use std::cmp::min;
+
+// Creates a routing table backed by an in-memory SQLite database, with the given cache capacity.
+fn create_routing_table(cache_capacity: usize) -> DieselRoutingTable<SqliteConnection> {
+    let connection_manager = ConnectionManager::<SqliteConnection>::new(":memory:");
+    let pool = Pool::builder()
+        .max_size(1)
+        .build(connection_manager)
+        .expect("Failed to build connection pool");
+    run_sqlite_migrations(&*pool.get().expect("Failed to get connection for migrations"))
+        .expect("Failed to run migrations");
+
+    DieselRoutingTable::with_cache_capacity(pool, cache_capacity)
+}
+
+// Adds the given nodes and circuits to the routing table, 1000 circuits at a time.
+fn load_circuits(
+    writer: &mut dyn RoutingTableWriter,
+    circuits: &[Circuit],
+    used_nodes: Vec<CircuitNode>,
+) {
+    let (mut circuit_to_add, mut circuits) = circuits.split_at(min(1000, circuits.len()));
+    writer.add_nodes(used_nodes).expect("Unable to add nodes");
+    while !circuit_to_add.is_empty() {
+        writer
+            .add_circuits(circuit_to_add.to_vec())
+            .expect("Unable to write circuits");
+        let (new, old) = circuits.split_at(min(1000, circuits.len()));
+        circuits = old;
+        circuit_to_add = new;
+    }
+}
+
+// Test that a routing table that has been loaded with 2^14 circuits still functions.
+//
+// After the the circuits are loaded, verify that a circuit, service, and node can be fetched
+// from the routing table.
+#[test]
+fn test_high_load_2_to_14_circuits() {
+    let base: i64 = 2;
+    let (circuits, used_nodes) = generate_circuits(base.pow(14), base.pow(7));
+
+    let first_circuit = circuits.get(0).expect("Unable to get 1st circuit").clone();
+    let first_node = used_nodes.get(0).expect("Unable to get 1st node").clone();
+
+    let mut table = create_routing_table(super::DEFAULT_CACHE_CAPACITY);
+    load_circuits(&mut table, &circuits, used_nodes);
+
+    let fetched_circuit = table
+        .fetch_circuit(&first_circuit.circuit_id)
+        .expect("Unable to fetch 1st circuit");
+    assert_eq!(fetched_circuit, Some(first_circuit.clone()));
+
+    let fetched_node = table
+        .fetch_node(&first_node.node_id)
+        .expect("Unable to fetch node");
+    assert_eq!(fetched_node, Some(first_node));
+}
+
+// Benchmark the time it takes to load 2^14 circuits with 2^7 nodes.
+//
+// The circuits are added 1000 at a time.
+#[bench]
+fn test_high_load_start_up_cost(b: &mut Bencher) {
+    let base: i64 = 2;
+    let (circuits, used_nodes) = generate_circuits(base.pow(14), base.pow(7));
+
+    let mut table = create_routing_table(super::DEFAULT_CACHE_CAPACITY);
+
+    b.iter(|| load_circuits(&mut table, &circuits, used_nodes.clone()));
+}
+
+// --------- Write benchmark tests -----------------
+//
+// The following benchmark tests benchmark the time it takes to add a new circuit to a loaded
+// routing table. The routing table is loaded with 2^x circuits, with 2^7 nodes.
+
+#[bench]
+fn test_high_load_performance_write_circuit_3_node_7(b: &mut Bencher) {
+    run_write_test(3, 7, b);
+}
+
+#[bench]
+fn test_high_load_performance_write_circuit_8_node_7(b: &mut Bencher) {
+    run_write_test(8, 7, b);
+}
+
+#[bench]
+fn test_high_load_performance_write_circuit_14_node_7(b: &mut Bencher) {
+    run_write_test(14, 7, b);
+}
+
+fn run_write_test(circuit_pow: u32, node_pow: u32, b: &mut Bencher) {
+    let base: i64 = 2;
+    let (circuits, used_nodes) = generate_circuits(base.pow(circuit_pow), base.pow(node_pow));
+
+    let (new_circuit_vec, new_used_nodes) = generate_circuits(1, base.pow(node_pow));
+    let new_circuit = new_circuit_vec.get(0).expect("Unable to get new circuit");
+
+    let mut table = create_routing_table(super::DEFAULT_CACHE_CAPACITY);
+    load_circuits(&mut table, &circuits, used_nodes);
+
+    b.iter(|| {
+        table
+            .add_circuit(
+                new_circuit.circuit_id.to_string(),
+                new_circuit.clone(),
+                new_used_nodes.clone(),
+            )
+            .expect("Unable to add circuit");
+    });
+}
+
+// --------- Read benchmark tests -----------------
+//
+// The following benchmark tests benchmark the time it takes to fetch a circuit from a loaded
+// routing table, both when the circuit is served from the cache and when it must be read from the
+// database. The routing table is loaded with 2^x circuits, with 2^7 nodes.
+
+#[bench]
+fn test_high_load_performance_read_circuit_3_node_7_cached(b: &mut Bencher) {
+    run_read_test(3, 7, super::DEFAULT_CACHE_CAPACITY, b);
+}
+
+#[bench]
+fn test_high_load_performance_read_circuit_8_node_7_cached(b: &mut Bencher) {
+    run_read_test(8, 7, super::DEFAULT_CACHE_CAPACITY, b);
+}
+
+#[bench]
+fn test_high_load_performance_read_circuit_14_node_7_cached(b: &mut Bencher) {
+    run_read_test(14, 7, super::DEFAULT_CACHE_CAPACITY, b);
+}
+
+#[bench]
+fn test_high_load_performance_read_circuit_3_node_7_uncached(b: &mut Bencher) {
+    run_read_test(3, 7, 0, b);
+}
+
+#[bench]
+fn test_high_load_performance_read_circuit_8_node_7_uncached(b: &mut Bencher) {
+    run_read_test(8, 7, 0, b);
+}
+
+#[bench]
+fn test_high_load_performance_read_circuit_14_node_7_uncached(b: &mut Bencher) {
+    run_read_test(14, 7, 0, b);
+}
+
+fn run_read_test(circuit_pow: u32, node_pow: u32, cache_capacity: usize, b: &mut Bencher) {
+    let base: i64 = 2;
+    let (circuits, used_nodes) = generate_circuits(base.pow(circuit_pow), base.pow(node_pow));
+
+    let first_circuit = circuits.get(1).expect("Unable to get 1st circuit").clone();
+
+    let mut table = create_routing_table(cache_capacity);
+    load_circuits(&mut table, &circuits, used_nodes);
+
+    let mut fetched_circuit = None;
+
+    b.iter(|| {
+        fetched_circuit = table
+            .fetch_circuit(&first_circuit.circuit_id)
+            .expect("Unable to fetch circuits");
+    });
+
+    assert_eq!(fetched_circuit, Some(first_circuit));
+}
@@ -0,0 +1,101 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A bounded cache used to keep recently read routing table entries in memory.
+
+use std::collections::{HashMap, VecDeque};
+
+/// A map with a maximum number of entries. Once full, the oldest entry is evicted to make room
+/// for a new one.
+pub(super) struct BoundedCache<V> {
+    capacity: usize,
+    entries: HashMap<String, V>,
+    insertion_order: VecDeque<String>,
+}
+
+impl<V: Clone> BoundedCache<V> {
+    pub fn new(capacity: usize) -> Self {
+        BoundedCache {
+            capacity,
+            entries: HashMap::new(),
+            insertion_order: VecDeque::new(),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<V> {
+        self.entries.get(key).cloned()
+    }
+
+    pub fn insert(&mut self, key: String, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.entries.insert(key.clone(), value).is_none() {
+            self.insertion_order.push_back(key);
+            while self.insertion_order.len() > self.capacity {
+                if let Some(evicted) = self.insertion_order.pop_front() {
+                    self.entries.remove(&evicted);
+                }
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        if self.entries.remove(key).is_some() {
+            self.insertion_order.retain(|existing| existing != key);
+        }
+    }
+
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Verify that the cache evicts the oldest entries once its capacity is reached, and that
+    // replacing an existing entry does not count against the capacity.
+    #[test]
+    fn test_eviction() {
+        let mut cache = BoundedCache::new(2);
+        cache.insert("a".to_string(), 1);
+        cache.insert("b".to_string(), 2);
+        cache.insert("a".to_string(), 3);
+        assert_eq!(cache.get("a"), Some(3));
+        assert_eq!(cache.len(), 2);
+
+        cache.insert("c".to_string(), 4);
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.get("b"), Some(2));
+        assert_eq!(cache.get("c"), Some(4));
+
+        cache.remove("b");
+        cache.insert("d".to_string(), 5);
+        assert_eq!(cache.get("c"), Some(4));
+        assert_eq!(cache.get("d"), Some(5));
+        assert_eq!(cache.len(), 2);
+    }
+
+    // Verify that a cache with no capacity never stores entries.
+    #[test]
+    fn test_zero_capacity() {
+        let mut cache = BoundedCache::new(0);
+        cache.insert("a".to_string(), 1);
+        assert_eq!(cache.get("a"), None);
+    }
+}
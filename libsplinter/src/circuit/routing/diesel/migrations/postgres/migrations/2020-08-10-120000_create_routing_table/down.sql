@@ -0,0 +1,22 @@
+---- Copyright 2018-2020 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- -----------------------------------------------------------------------------
+
+DROP TABLE IF EXISTS routing_node_endpoints;
+DROP TABLE IF EXISTS routing_nodes;
+DROP TABLE IF EXISTS routing_service_arguments;
+DROP TABLE IF EXISTS routing_service_allowed_nodes;
+DROP TABLE IF EXISTS routing_services;
+DROP TABLE IF EXISTS routing_circuit_members;
+DROP TABLE IF EXISTS routing_circuits;
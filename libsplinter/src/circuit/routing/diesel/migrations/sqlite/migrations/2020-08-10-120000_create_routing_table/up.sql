@@ -0,0 +1,66 @@
+---- Copyright 2018-2020 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- -----------------------------------------------------------------------------
+
+CREATE TABLE IF NOT EXISTS routing_circuits (
+    circuit_id        TEXT     PRIMARY KEY
+);
+
+CREATE TABLE IF NOT EXISTS routing_circuit_members (
+    circuit_id        TEXT     NOT NULL,
+    position          INTEGER  NOT NULL,
+    node_id           TEXT     NOT NULL,
+    PRIMARY KEY (circuit_id, position),
+    FOREIGN KEY (circuit_id) REFERENCES routing_circuits(circuit_id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS routing_services (
+    circuit_id        TEXT     NOT NULL,
+    position          INTEGER  NOT NULL,
+    service_id        TEXT     NOT NULL,
+    service_type      TEXT     NOT NULL,
+    PRIMARY KEY (circuit_id, position),
+    FOREIGN KEY (circuit_id) REFERENCES routing_circuits(circuit_id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS routing_service_allowed_nodes (
+    circuit_id        TEXT     NOT NULL,
+    service_position  INTEGER  NOT NULL,
+    position          INTEGER  NOT NULL,
+    node_id           TEXT     NOT NULL,
+    PRIMARY KEY (circuit_id, service_position, position),
+    FOREIGN KEY (circuit_id) REFERENCES routing_circuits(circuit_id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS routing_service_arguments (
+    circuit_id        TEXT     NOT NULL,
+    service_position  INTEGER  NOT NULL,
+    position          INTEGER  NOT NULL,
+    key               TEXT     NOT NULL,
+    value             TEXT     NOT NULL,
+    PRIMARY KEY (circuit_id, service_position, position),
+    FOREIGN KEY (circuit_id) REFERENCES routing_circuits(circuit_id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS routing_nodes (
+    node_id           TEXT     PRIMARY KEY
+);
+
+CREATE TABLE IF NOT EXISTS routing_node_endpoints (
+    node_id           TEXT     NOT NULL,
+    position          INTEGER  NOT NULL,
+    endpoint          TEXT     NOT NULL,
+    PRIMARY KEY (node_id, position),
+    FOREIGN KEY (node_id) REFERENCES routing_nodes(node_id) ON DELETE CASCADE
+);
@@ -0,0 +1,766 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A database-backed routing table, powered by [`Diesel`](https://crates.io/crates/diesel).
+//!
+//! This module contains the [`DieselRoutingTable`], which provides an implementation of the
+//! [`RoutingTableReader`] and [`RoutingTableWriter`] traits. Circuits and nodes are stored in
+//! a SQLite or PostgreSQL database, so a node with a large number of circuits does not need to
+//! hold all of them in memory or rebuild them on start up. Recently read circuits and nodes are
+//! kept in a bounded, in-memory read-through cache.
+//!
+//! As with the in-memory [`RoutingTable`], services added or removed individually through the
+//! writer are not persisted.
+//!
+//! [`DieselRoutingTable`]: struct.DieselRoutingTable.html
+//! [`RoutingTable`]: ../memory/struct.RoutingTable.html
+//! [`RoutingTableReader`]: ../trait.RoutingTableReader.html
+//! [`RoutingTableWriter`]: ../trait.RoutingTableWriter.html
+
+#[cfg(all(feature = "benchmark", feature = "sqlite", test))]
+mod benchmarks;
+mod cache;
+pub mod migrations;
+mod models;
+mod operations;
+mod schema;
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use diesel::r2d2::{ConnectionManager, Pool, PooledConnection};
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use diesel::Connection as _;
+
+use super::error::{
+    AddCircuitError, AddCircuitsError, AddNodeError, AddNodesError, AddServiceError,
+    FetchCircuitError, FetchNodeError, FetchServiceError, ListCircuitsError, ListNodesError,
+    ListServiceError, RemoveCircuitError, RemoveNodeError, RemoveServiceError,
+};
+use super::{
+    Circuit, CircuitIter, CircuitNode, CircuitNodeIter, RoutingTableReader, RoutingTableWriter,
+    Service, ServiceId,
+};
+
+use cache::BoundedCache;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use operations::delete_circuit::RoutingTableDeleteCircuitOperation as _;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use operations::delete_node::RoutingTableDeleteNodeOperation as _;
+use operations::fetch_circuit::RoutingTableFetchCircuitOperation as _;
+use operations::fetch_node::RoutingTableFetchNodeOperation as _;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use operations::insert_circuits::RoutingTableInsertCircuitsOperation as _;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use operations::insert_node::RoutingTableInsertNodeOperation as _;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use operations::insert_nodes::RoutingTableInsertNodesOperation as _;
+use operations::list_circuits::RoutingTableListCircuitsOperation as _;
+use operations::list_nodes::RoutingTableListNodesOperation as _;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+use operations::OperationError;
+use operations::RoutingTableOperations;
+
+/// The default number of circuits, and separately of nodes, kept in the read-through cache
+pub const DEFAULT_CACHE_CAPACITY: usize = 1024;
+
+struct CacheState {
+    circuits: BoundedCache<Circuit>,
+    nodes: BoundedCache<CircuitNode>,
+    // Services added or removed directly through the writer. These take precedence over the
+    // rosters of the stored circuits; a `None` entry marks a removed service.
+    service_overrides: HashMap<ServiceId, Option<Service>>,
+    // Incremented by every write to the database. A value read from the database is only cached
+    // if no write happened while it was being read, so a stale read never replaces newer data.
+    generation: u64,
+}
+
+impl CacheState {
+    fn new(capacity: usize) -> Self {
+        CacheState {
+            circuits: BoundedCache::new(capacity),
+            nodes: BoundedCache::new(capacity),
+            service_overrides: HashMap::new(),
+            generation: 0,
+        }
+    }
+
+    #[cfg(any(feature = "postgres", feature = "sqlite"))]
+    fn clear_service_overrides(&mut self, circuit_id: &str) {
+        self.service_overrides
+            .retain(|service_id, _| service_id.circuit() != circuit_id);
+    }
+}
+
+/// A database-backed routing table, powered by [`Diesel`](https://crates.io/crates/diesel).
+pub struct DieselRoutingTable<C: diesel::Connection + 'static> {
+    connection_pool: Pool<ConnectionManager<C>>,
+    cache: Arc<RwLock<CacheState>>,
+}
+
+impl<C: diesel::Connection> DieselRoutingTable<C> {
+    /// Creates a new `DieselRoutingTable` with a cache of `DEFAULT_CACHE_CAPACITY` circuits and
+    /// nodes.
+    ///
+    /// # Arguments
+    ///
+    ///  * `connection_pool`: connection pool for the database
+    pub fn new(connection_pool: Pool<ConnectionManager<C>>) -> Self {
+        Self::with_cache_capacity(connection_pool, DEFAULT_CACHE_CAPACITY)
+    }
+
+    /// Creates a new `DieselRoutingTable` with the given cache capacity.
+    ///
+    /// # Arguments
+    ///
+    ///  * `connection_pool`: connection pool for the database
+    ///  * `cache_capacity`: the maximum number of circuits, and separately of nodes, to keep in
+    ///    memory; a capacity of 0 disables the cache
+    pub fn with_cache_capacity(
+        connection_pool: Pool<ConnectionManager<C>>,
+        cache_capacity: usize,
+    ) -> Self {
+        DieselRoutingTable {
+            connection_pool,
+            cache: Arc::new(RwLock::new(CacheState::new(cache_capacity))),
+        }
+    }
+
+    fn connection(&self) -> Result<PooledConnection<ConnectionManager<C>>, String> {
+        self.connection_pool
+            .get()
+            .map_err(|err| format!("Unable to get database connection: {}", err))
+    }
+
+    fn cache(&self) -> Result<RwLockReadGuard<'_, CacheState>, String> {
+        self.cache
+            .read()
+            .map_err(|_| String::from("DieselRoutingTable cache lock poisoned"))
+    }
+
+    fn cache_mut(&self) -> Result<RwLockWriteGuard<'_, CacheState>, String> {
+        self.cache
+            .write()
+            .map_err(|_| String::from("DieselRoutingTable cache lock poisoned"))
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl Clone for DieselRoutingTable<diesel::pg::PgConnection> {
+    fn clone(&self) -> Self {
+        Self {
+            connection_pool: self.connection_pool.clone(),
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl Clone for DieselRoutingTable<diesel::sqlite::SqliteConnection> {
+    fn clone(&self) -> Self {
+        Self {
+            connection_pool: self.connection_pool.clone(),
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+impl<C> RoutingTableReader for DieselRoutingTable<C>
+where
+    C: diesel::Connection,
+    i32: diesel::deserialize::FromSql<diesel::sql_types::Integer, C::Backend>,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn fetch_service(&self, service_id: &ServiceId) -> Result<Option<Service>, FetchServiceError> {
+        if let Some(service) = self
+            .cache()
+            .map_err(FetchServiceError)?
+            .service_overrides
+            .get(service_id)
+        {
+            return Ok(service.clone());
+        }
+
+        Ok(self
+            .fetch_circuit(service_id.circuit())
+            .map_err(|err| FetchServiceError(err.0))?
+            .and_then(|circuit| {
+                circuit
+                    .roster
+                    .into_iter()
+                    .find(|service| service.service_id == service_id.service_id())
+            }))
+    }
+
+    fn list_service(&self, circuit_id: &str) -> Result<Vec<Service>, ListServiceError> {
+        match self
+            .fetch_circuit(circuit_id)
+            .map_err(|err| ListServiceError::InternalError(err.0))?
+        {
+            Some(circuit) => Ok(circuit.roster),
+            None => Err(ListServiceError::CircuitNotFound(circuit_id.to_string())),
+        }
+    }
+
+    fn list_nodes(&self) -> Result<CircuitNodeIter, ListNodesError> {
+        let nodes = RoutingTableOperations::new(&*self.connection().map_err(ListNodesError)?)
+            .list_nodes()
+            .map_err(|err| ListNodesError(err.to_string()))?;
+        Ok(Box::new(
+            nodes
+                .into_iter()
+                .map(|node| (node.node_id.clone(), node))
+                .collect::<Vec<_>>()
+                .into_iter(),
+        ))
+    }
+
+    fn fetch_node(&self, node_id: &str) -> Result<Option<CircuitNode>, FetchNodeError> {
+        let generation = {
+            let cache = self.cache().map_err(FetchNodeError)?;
+            if let Some(node) = cache.nodes.get(node_id) {
+                return Ok(Some(node));
+            }
+            cache.generation
+        };
+
+        // The database is read without holding the cache lock, so that other readers are not
+        // blocked behind the query.
+        let node = RoutingTableOperations::new(&*self.connection().map_err(FetchNodeError)?)
+            .fetch_node(node_id)
+            .map_err(|err| FetchNodeError(err.to_string()))?;
+        if let Some(node) = &node {
+            let mut cache = self.cache_mut().map_err(FetchNodeError)?;
+            if cache.generation == generation {
+                cache.nodes.insert(node_id.to_string(), node.clone());
+            }
+        }
+        Ok(node)
+    }
+
+    fn list_circuits(&self) -> Result<CircuitIter, ListCircuitsError> {
+        let circuits = RoutingTableOperations::new(&*self.connection().map_err(ListCircuitsError)?)
+            .list_circuits()
+            .map_err(|err| ListCircuitsError(err.to_string()))?;
+        Ok(Box::new(
+            circuits
+                .into_iter()
+                .map(|circuit| (circuit.circuit_id.clone(), circuit))
+                .collect::<Vec<_>>()
+                .into_iter(),
+        ))
+    }
+
+    fn fetch_circuit(&self, circuit_id: &str) -> Result<Option<Circuit>, FetchCircuitError> {
+        let generation = {
+            let cache = self.cache().map_err(FetchCircuitError)?;
+            if let Some(circuit) = cache.circuits.get(circuit_id) {
+                return Ok(Some(circuit));
+            }
+            cache.generation
+        };
+
+        // The database is read without holding the cache lock, so that other readers are not
+        // blocked behind the query.
+        let circuit = RoutingTableOperations::new(&*self.connection().map_err(FetchCircuitError)?)
+            .fetch_circuit(circuit_id)
+            .map_err(|err| FetchCircuitError(err.to_string()))?;
+        if let Some(circuit) = &circuit {
+            let mut cache = self.cache_mut().map_err(FetchCircuitError)?;
+            if cache.generation == generation {
+                cache
+                    .circuits
+                    .insert(circuit_id.to_string(), circuit.clone());
+            }
+        }
+        Ok(circuit)
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl RoutingTableWriter for DieselRoutingTable<diesel::pg::PgConnection> {
+    fn add_service(
+        &mut self,
+        service_id: ServiceId,
+        service: Service,
+    ) -> Result<(), AddServiceError> {
+        self.cache_mut()
+            .map_err(AddServiceError)?
+            .service_overrides
+            .insert(service_id, Some(service));
+        Ok(())
+    }
+
+    fn remove_service(&mut self, service_id: &ServiceId) -> Result<(), RemoveServiceError> {
+        self.cache_mut()
+            .map_err(RemoveServiceError)?
+            .service_overrides
+            .insert(service_id.clone(), None);
+        Ok(())
+    }
+
+    fn add_circuit(
+        &mut self,
+        circuit_id: String,
+        circuit: Circuit,
+        nodes: Vec<CircuitNode>,
+    ) -> Result<(), AddCircuitError> {
+        let circuit = Circuit {
+            circuit_id,
+            ..circuit
+        };
+        let mut cache = self.cache_mut().map_err(AddCircuitError)?;
+        let conn = self.connection().map_err(AddCircuitError)?;
+        let operations = RoutingTableOperations::new(&*conn);
+        conn.transaction::<_, OperationError, _>(|| {
+            operations.insert_circuits(std::slice::from_ref(&circuit))?;
+            operations.insert_nodes(&nodes)
+        })
+        .map_err(|err| AddCircuitError(err.to_string()))?;
+
+        cache.generation += 1;
+        cache.clear_service_overrides(&circuit.circuit_id);
+        cache.circuits.insert(circuit.circuit_id.clone(), circuit);
+        Ok(())
+    }
+
+    fn add_circuits(&mut self, circuits: Vec<Circuit>) -> Result<(), AddCircuitsError> {
+        let mut cache = self.cache_mut().map_err(AddCircuitsError)?;
+        RoutingTableOperations::new(&*self.connection().map_err(AddCircuitsError)?)
+            .insert_circuits(&circuits)
+            .map_err(|err| AddCircuitsError(err.to_string()))?;
+
+        cache.generation += 1;
+
+        // Bulk loads invalidate rather than populate the cache, so that they do not evict the
+        // entries that are actually being read.
+        for circuit in circuits.iter() {
+            cache.clear_service_overrides(&circuit.circuit_id);
+            cache.circuits.remove(&circuit.circuit_id);
+        }
+        Ok(())
+    }
+
+    fn remove_circuit(&mut self, circuit_id: &str) -> Result<(), RemoveCircuitError> {
+        let mut cache = self.cache_mut().map_err(RemoveCircuitError)?;
+        RoutingTableOperations::new(&*self.connection().map_err(RemoveCircuitError)?)
+            .delete_circuit(circuit_id)
+            .map_err(|err| RemoveCircuitError(err.to_string()))?;
+
+        cache.generation += 1;
+        cache.clear_service_overrides(circuit_id);
+        cache.circuits.remove(circuit_id);
+        Ok(())
+    }
+
+    fn add_node(&mut self, id: String, node: CircuitNode) -> Result<(), AddNodeError> {
+        let node = CircuitNode {
+            node_id: id,
+            ..node
+        };
+        let mut cache = self.cache_mut().map_err(AddNodeError)?;
+        RoutingTableOperations::new(&*self.connection().map_err(AddNodeError)?)
+            .insert_node(&node)
+            .map_err(|err| AddNodeError(err.to_string()))?;
+
+        cache.generation += 1;
+        cache.nodes.insert(node.node_id.clone(), node);
+        Ok(())
+    }
+
+    fn add_nodes(&mut self, nodes: Vec<CircuitNode>) -> Result<(), AddNodesError> {
+        // Existing nodes are not modified, so any cached entries remain valid
+        let mut cache = self.cache_mut().map_err(AddNodesError)?;
+        RoutingTableOperations::new(&*self.connection().map_err(AddNodesError)?)
+            .insert_nodes(&nodes)
+            .map_err(|err| AddNodesError(err.to_string()))?;
+
+        cache.generation += 1;
+        Ok(())
+    }
+
+    fn remove_node(&mut self, node_id: &str) -> Result<(), RemoveNodeError> {
+        let mut cache = self.cache_mut().map_err(RemoveNodeError)?;
+        RoutingTableOperations::new(&*self.connection().map_err(RemoveNodeError)?)
+            .delete_node(node_id)
+            .map_err(|err| RemoveNodeError(err.to_string()))?;
+
+        cache.generation += 1;
+        cache.nodes.remove(node_id);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl RoutingTableWriter for DieselRoutingTable<diesel::sqlite::SqliteConnection> {
+    fn add_service(
+        &mut self,
+        service_id: ServiceId,
+        service: Service,
+    ) -> Result<(), AddServiceError> {
+        self.cache_mut()
+            .map_err(AddServiceError)?
+            .service_overrides
+            .insert(service_id, Some(service));
+        Ok(())
+    }
+
+    fn remove_service(&mut self, service_id: &ServiceId) -> Result<(), RemoveServiceError> {
+        self.cache_mut()
+            .map_err(RemoveServiceError)?
+            .service_overrides
+            .insert(service_id.clone(), None);
+        Ok(())
+    }
+
+    fn add_circuit(
+        &mut self,
+        circuit_id: String,
+        circuit: Circuit,
+        nodes: Vec<CircuitNode>,
+    ) -> Result<(), AddCircuitError> {
+        let circuit = Circuit {
+            circuit_id,
+            ..circuit
+        };
+        let mut cache = self.cache_mut().map_err(AddCircuitError)?;
+        let conn = self.connection().map_err(AddCircuitError)?;
+        let operations = RoutingTableOperations::new(&*conn);
+        conn.transaction::<_, OperationError, _>(|| {
+            operations.insert_circuits(std::slice::from_ref(&circuit))?;
+            operations.insert_nodes(&nodes)
+        })
+        .map_err(|err| AddCircuitError(err.to_string()))?;
+
+        cache.generation += 1;
+        cache.clear_service_overrides(&circuit.circuit_id);
+        cache.circuits.insert(circuit.circuit_id.clone(), circuit);
+        Ok(())
+    }
+
+    fn add_circuits(&mut self, circuits: Vec<Circuit>) -> Result<(), AddCircuitsError> {
+        let mut cache = self.cache_mut().map_err(AddCircuitsError)?;
+        RoutingTableOperations::new(&*self.connection().map_err(AddCircuitsError)?)
+            .insert_circuits(&circuits)
+            .map_err(|err| AddCircuitsError(err.to_string()))?;
+
+        cache.generation += 1;
+
+        // Bulk loads invalidate rather than populate the cache, so that they do not evict the
+        // entries that are actually being read.
+        for circuit in circuits.iter() {
+            cache.clear_service_overrides(&circuit.circuit_id);
+            cache.circuits.remove(&circuit.circuit_id);
+        }
+        Ok(())
+    }
+
+    fn remove_circuit(&mut self, circuit_id: &str) -> Result<(), RemoveCircuitError> {
+        let mut cache = self.cache_mut().map_err(RemoveCircuitError)?;
+        RoutingTableOperations::new(&*self.connection().map_err(RemoveCircuitError)?)
+            .delete_circuit(circuit_id)
+            .map_err(|err| RemoveCircuitError(err.to_string()))?;
+
+        cache.generation += 1;
+        cache.clear_service_overrides(circuit_id);
+        cache.circuits.remove(circuit_id);
+        Ok(())
+    }
+
+    fn add_node(&mut self, id: String, node: CircuitNode) -> Result<(), AddNodeError> {
+        let node = CircuitNode {
+            node_id: id,
+            ..node
+        };
+        let mut cache = self.cache_mut().map_err(AddNodeError)?;
+        RoutingTableOperations::new(&*self.connection().map_err(AddNodeError)?)
+            .insert_node(&node)
+            .map_err(|err| AddNodeError(err.to_string()))?;
+
+        cache.generation += 1;
+        cache.nodes.insert(node.node_id.clone(), node);
+        Ok(())
+    }
+
+    fn add_nodes(&mut self, nodes: Vec<CircuitNode>) -> Result<(), AddNodesError> {
+        // Existing nodes are not modified, so any cached entries remain valid
+        let mut cache = self.cache_mut().map_err(AddNodesError)?;
+        RoutingTableOperations::new(&*self.connection().map_err(AddNodesError)?)
+            .insert_nodes(&nodes)
+            .map_err(|err| AddNodesError(err.to_string()))?;
+
+        cache.generation += 1;
+        Ok(())
+    }
+
+    fn remove_node(&mut self, node_id: &str) -> Result<(), RemoveNodeError> {
+        let mut cache = self.cache_mut().map_err(RemoveNodeError)?;
+        RoutingTableOperations::new(&*self.connection().map_err(RemoveNodeError)?)
+            .delete_node(node_id)
+            .map_err(|err| RemoveNodeError(err.to_string()))?;
+
+        cache.generation += 1;
+        cache.nodes.remove(node_id);
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use diesel::{
+        r2d2::{ConnectionManager, Pool},
+        sqlite::SqliteConnection,
+    };
+
+    use migrations::run_sqlite_migrations;
+
+    // Verify that circuits and nodes written to the routing table can be fetched and listed, that
+    // the order of their fields is preserved, and that they are removed correctly.
+    //
+    // 1. Add a circuit along with its nodes
+    // 2. Verify the circuit, its services, and its nodes can be fetched and listed
+    // 3. Remove the circuit and a node, and verify they are no longer returned
+    #[test]
+    fn test_add_fetch_remove() {
+        let pool = create_connection_pool_and_migrate();
+        let mut table = DieselRoutingTable::new(pool);
+
+        let (circuit, nodes) = build_circuit("abcde-01234");
+        table
+            .add_circuit(circuit.circuit_id.clone(), circuit.clone(), nodes.clone())
+            .expect("Unable to add circuit");
+
+        assert_eq!(
+            table
+                .fetch_circuit("abcde-01234")
+                .expect("Unable to fetch circuit"),
+            Some(circuit.clone())
+        );
+        assert_eq!(
+            table
+                .list_service("abcde-01234")
+                .expect("Unable to list services"),
+            circuit.roster
+        );
+        assert_eq!(
+            table
+                .fetch_service(&ServiceId::new(
+                    "abcde-01234".to_string(),
+                    "BBBB".to_string()
+                ))
+                .expect("Unable to fetch service"),
+            Some(circuit.roster[1].clone())
+        );
+        assert_eq!(
+            table.fetch_node("node-2").expect("Unable to fetch node"),
+            Some(nodes[1].clone())
+        );
+        assert_eq!(
+            table
+                .list_nodes()
+                .expect("Unable to list nodes")
+                .collect::<Vec<_>>(),
+            vec![
+                ("node-1".to_string(), nodes[0].clone()),
+                ("node-2".to_string(), nodes[1].clone()),
+            ]
+        );
+        assert_eq!(
+            table
+                .list_circuits()
+                .expect("Unable to list circuits")
+                .collect::<Vec<_>>(),
+            vec![("abcde-01234".to_string(), circuit)]
+        );
+
+        table
+            .remove_circuit("abcde-01234")
+            .expect("Unable to remove circuit");
+        table.remove_node("node-1").expect("Unable to remove node");
+
+        assert_eq!(
+            table
+                .fetch_circuit("abcde-01234")
+                .expect("Unable to fetch circuit"),
+            None
+        );
+        assert_eq!(
+            table.list_service("abcde-01234"),
+            Err(ListServiceError::CircuitNotFound("abcde-01234".to_string()))
+        );
+        assert_eq!(
+            table.fetch_node("node-1").expect("Unable to fetch node"),
+            None
+        );
+        assert_eq!(table.list_nodes().expect("Unable to list nodes").len(), 1);
+    }
+
+    // Verify that entries written by one routing table are read from the database by another
+    // routing table sharing the same database, and that `add_nodes` does not replace existing
+    // nodes while `add_node` does.
+    #[test]
+    fn test_read_through() {
+        let pool = create_connection_pool_and_migrate();
+        let mut writer = DieselRoutingTable::new(pool.clone());
+        let reader = DieselRoutingTable::with_cache_capacity(pool, 0);
+
+        let (circuit, nodes) = build_circuit("abcde-01234");
+        writer
+            .add_circuits(vec![circuit.clone()])
+            .expect("Unable to add circuits");
+        writer
+            .add_nodes(nodes.clone())
+            .expect("Unable to add nodes");
+
+        assert_eq!(
+            reader
+                .fetch_circuit("abcde-01234")
+                .expect("Unable to fetch circuit"),
+            Some(circuit)
+        );
+
+        let updated_node = CircuitNode::new(
+            "node-1".to_string(),
+            vec!["tcps://127.0.0.1:18044".to_string()],
+        );
+        writer
+            .add_nodes(vec![updated_node.clone()])
+            .expect("Unable to add nodes");
+        assert_eq!(
+            reader.fetch_node("node-1").expect("Unable to fetch node"),
+            Some(nodes[0].clone())
+        );
+
+        writer
+            .add_node("node-1".to_string(), updated_node.clone())
+            .expect("Unable to add node");
+        assert_eq!(
+            reader.fetch_node("node-1").expect("Unable to fetch node"),
+            Some(updated_node)
+        );
+    }
+
+    // Verify that services added and removed through the writer take precedence over the
+    // circuit roster until the circuit is written again.
+    #[test]
+    fn test_service_overrides() {
+        let pool = create_connection_pool_and_migrate();
+        let mut table = DieselRoutingTable::new(pool);
+
+        let (circuit, nodes) = build_circuit("abcde-01234");
+        table
+            .add_circuit(circuit.circuit_id.clone(), circuit.clone(), nodes.clone())
+            .expect("Unable to add circuit");
+
+        let service_id = ServiceId::new("abcde-01234".to_string(), "AAAA".to_string());
+        table
+            .remove_service(&service_id)
+            .expect("Unable to remove service");
+        assert_eq!(
+            table
+                .fetch_service(&service_id)
+                .expect("Unable to fetch service"),
+            None
+        );
+
+        let new_service_id = ServiceId::new("abcde-01234".to_string(), "CCCC".to_string());
+        let new_service = Service::new(
+            "CCCC".to_string(),
+            "test".to_string(),
+            vec!["node-1".to_string()],
+            vec![],
+        );
+        table
+            .add_service(new_service_id.clone(), new_service.clone())
+            .expect("Unable to add service");
+        assert_eq!(
+            table
+                .fetch_service(&new_service_id)
+                .expect("Unable to fetch service"),
+            Some(new_service)
+        );
+
+        table
+            .add_circuit(circuit.circuit_id.clone(), circuit.clone(), nodes)
+            .expect("Unable to add circuit");
+        assert_eq!(
+            table
+                .fetch_service(&service_id)
+                .expect("Unable to fetch service"),
+            Some(circuit.roster[0].clone())
+        );
+        assert_eq!(
+            table
+                .fetch_service(&new_service_id)
+                .expect("Unable to fetch service"),
+            None
+        );
+    }
+
+    fn build_circuit(circuit_id: &str) -> (Circuit, Vec<CircuitNode>) {
+        let nodes = vec![
+            CircuitNode::new(
+                "node-1".to_string(),
+                vec![
+                    "tcps://127.0.0.1:8044".to_string(),
+                    "tcps://127.0.0.1:9044".to_string(),
+                ],
+            ),
+            CircuitNode::new(
+                "node-2".to_string(),
+                vec!["tcps://127.0.0.2:8044".to_string()],
+            ),
+        ];
+        let circuit = Circuit::new(
+            circuit_id.to_string(),
+            vec![
+                Service::new(
+                    "AAAA".to_string(),
+                    "test".to_string(),
+                    vec!["node-2".to_string()],
+                    vec![
+                        ("peer_services".to_string(), "BBBB".to_string()),
+                        ("admin_keys".to_string(), "0123".to_string()),
+                    ],
+                ),
+                Service::new(
+                    "BBBB".to_string(),
+                    "test".to_string(),
+                    vec!["node-1".to_string()],
+                    vec![("peer_services".to_string(), "AAAA".to_string())],
+                ),
+            ],
+            vec!["node-2".to_string(), "node-1".to_string()],
+        );
+        (circuit, nodes)
+    }
+
+    /// Creates a connection pool for an in-memory SQLite database with only a single connection
+    /// available. Each connection is backed by a different in-memory SQLite database, so limiting
+    /// the pool to a single connection insures that the same DB is used for all operations.
+    fn create_connection_pool_and_migrate() -> Pool<ConnectionManager<SqliteConnection>> {
+        let connection_manager = ConnectionManager::<SqliteConnection>::new(":memory:");
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(connection_manager)
+            .expect("Failed to build connection pool");
+
+        run_sqlite_migrations(&*pool.get().expect("Failed to get connection for migrations"))
+            .expect("Failed to run migrations");
+
+        pool
+    }
+}
@@ -0,0 +1,196 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides database models for the `DieselRoutingTable`.
+
+use crate::circuit::routing::{Circuit, CircuitNode};
+
+use super::schema::{
+    routing_circuit_members, routing_circuits, routing_node_endpoints, routing_nodes,
+    routing_service_allowed_nodes, routing_service_arguments, routing_services,
+};
+
+#[derive(Debug, PartialEq, Associations, Identifiable, Insertable, Queryable)]
+#[table_name = "routing_circuits"]
+#[primary_key(circuit_id)]
+pub struct CircuitModel {
+    pub circuit_id: String,
+}
+
+#[derive(Debug, PartialEq, Associations, Identifiable, Insertable, Queryable)]
+#[table_name = "routing_circuit_members"]
+#[belongs_to(CircuitModel, foreign_key = "circuit_id")]
+#[primary_key(circuit_id, position)]
+pub struct CircuitMemberModel {
+    pub circuit_id: String,
+    pub position: i32,
+    pub node_id: String,
+}
+
+#[derive(Debug, PartialEq, Associations, Identifiable, Insertable, Queryable)]
+#[table_name = "routing_services"]
+#[belongs_to(CircuitModel, foreign_key = "circuit_id")]
+#[primary_key(circuit_id, position)]
+pub struct ServiceModel {
+    pub circuit_id: String,
+    pub position: i32,
+    pub service_id: String,
+    pub service_type: String,
+}
+
+#[derive(Debug, PartialEq, Associations, Identifiable, Insertable, Queryable)]
+#[table_name = "routing_service_allowed_nodes"]
+#[belongs_to(CircuitModel, foreign_key = "circuit_id")]
+#[primary_key(circuit_id, service_position, position)]
+pub struct ServiceAllowedNodeModel {
+    pub circuit_id: String,
+    pub service_position: i32,
+    pub position: i32,
+    pub node_id: String,
+}
+
+#[derive(Debug, PartialEq, Associations, Identifiable, Insertable, Queryable)]
+#[table_name = "routing_service_arguments"]
+#[belongs_to(CircuitModel, foreign_key = "circuit_id")]
+#[primary_key(circuit_id, service_position, position)]
+pub struct ServiceArgumentModel {
+    pub circuit_id: String,
+    pub service_position: i32,
+    pub position: i32,
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, PartialEq, Associations, Identifiable, Insertable, Queryable)]
+#[table_name = "routing_nodes"]
+#[primary_key(node_id)]
+pub struct NodeModel {
+    pub node_id: String,
+}
+
+#[derive(Debug, PartialEq, Associations, Identifiable, Insertable, Queryable)]
+#[table_name = "routing_node_endpoints"]
+#[belongs_to(NodeModel, foreign_key = "node_id")]
+#[primary_key(node_id, position)]
+pub struct NodeEndpointModel {
+    pub node_id: String,
+    pub position: i32,
+    pub endpoint: String,
+}
+
+impl From<&Circuit> for CircuitModel {
+    fn from(circuit: &Circuit) -> Self {
+        CircuitModel {
+            circuit_id: circuit.circuit_id.clone(),
+        }
+    }
+}
+
+impl From<&Circuit> for Vec<CircuitMemberModel> {
+    fn from(circuit: &Circuit) -> Self {
+        circuit
+            .members
+            .iter()
+            .enumerate()
+            .map(|(position, node_id)| CircuitMemberModel {
+                circuit_id: circuit.circuit_id.clone(),
+                position: position as i32,
+                node_id: node_id.clone(),
+            })
+            .collect()
+    }
+}
+
+impl From<&Circuit> for Vec<ServiceModel> {
+    fn from(circuit: &Circuit) -> Self {
+        circuit
+            .roster
+            .iter()
+            .enumerate()
+            .map(|(position, service)| ServiceModel {
+                circuit_id: circuit.circuit_id.clone(),
+                position: position as i32,
+                service_id: service.service_id.clone(),
+                service_type: service.service_type.clone(),
+            })
+            .collect()
+    }
+}
+
+impl From<&Circuit> for Vec<ServiceAllowedNodeModel> {
+    fn from(circuit: &Circuit) -> Self {
+        circuit
+            .roster
+            .iter()
+            .enumerate()
+            .flat_map(|(service_position, service)| {
+                service
+                    .allowed_nodes
+                    .iter()
+                    .enumerate()
+                    .map(move |(position, node_id)| ServiceAllowedNodeModel {
+                        circuit_id: circuit.circuit_id.clone(),
+                        service_position: service_position as i32,
+                        position: position as i32,
+                        node_id: node_id.clone(),
+                    })
+            })
+            .collect()
+    }
+}
+
+impl From<&Circuit> for Vec<ServiceArgumentModel> {
+    fn from(circuit: &Circuit) -> Self {
+        circuit
+            .roster
+            .iter()
+            .enumerate()
+            .flat_map(|(service_position, service)| {
+                service
+                    .arguments
+                    .iter()
+                    .enumerate()
+                    .map(move |(position, (key, value))| ServiceArgumentModel {
+                        circuit_id: circuit.circuit_id.clone(),
+                        service_position: service_position as i32,
+                        position: position as i32,
+                        key: key.clone(),
+                        value: value.clone(),
+                    })
+            })
+            .collect()
+    }
+}
+
+impl From<&CircuitNode> for NodeModel {
+    fn from(node: &CircuitNode) -> Self {
+        NodeModel {
+            node_id: node.node_id.clone(),
+        }
+    }
+}
+
+impl From<&CircuitNode> for Vec<NodeEndpointModel> {
+    fn from(node: &CircuitNode) -> Self {
+        node.endpoints
+            .iter()
+            .enumerate()
+            .map(|(position, endpoint)| NodeEndpointModel {
+                node_id: node.node_id.clone(),
+                position: position as i32,
+                endpoint: endpoint.clone(),
+            })
+            .collect()
+    }
+}
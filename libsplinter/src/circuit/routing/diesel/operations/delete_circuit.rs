@@ -0,0 +1,66 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "delete circuit" operation for the `DieselRoutingTable`.
+
+use diesel::{dsl::delete, prelude::*};
+
+use crate::circuit::routing::diesel::schema::{
+    routing_circuit_members, routing_circuits, routing_service_allowed_nodes,
+    routing_service_arguments, routing_services,
+};
+
+use super::{OperationError, RoutingTableOperations};
+
+pub(in crate::circuit::routing::diesel) trait RoutingTableDeleteCircuitOperation {
+    fn delete_circuit(&self, circuit_id: &str) -> Result<(), OperationError>;
+}
+
+impl<'a, C> RoutingTableDeleteCircuitOperation for RoutingTableOperations<'a, C>
+where
+    C: diesel::Connection,
+{
+    fn delete_circuit(&self, circuit_id: &str) -> Result<(), OperationError> {
+        // The child rows are removed explicitly, as SQLite does not enforce foreign keys by
+        // default.
+        self.conn.transaction::<_, _, _>(|| {
+            delete(
+                routing_service_arguments::table
+                    .filter(routing_service_arguments::circuit_id.eq(circuit_id)),
+            )
+            .execute(self.conn)
+            .map_err(|err| OperationError::new("Failed to remove service arguments", err))?;
+            delete(
+                routing_service_allowed_nodes::table
+                    .filter(routing_service_allowed_nodes::circuit_id.eq(circuit_id)),
+            )
+            .execute(self.conn)
+            .map_err(|err| OperationError::new("Failed to remove service allowed nodes", err))?;
+            delete(routing_services::table.filter(routing_services::circuit_id.eq(circuit_id)))
+                .execute(self.conn)
+                .map_err(|err| OperationError::new("Failed to remove circuit services", err))?;
+            delete(
+                routing_circuit_members::table
+                    .filter(routing_circuit_members::circuit_id.eq(circuit_id)),
+            )
+            .execute(self.conn)
+            .map_err(|err| OperationError::new("Failed to remove circuit members", err))?;
+            delete(routing_circuits::table.find(circuit_id))
+                .execute(self.conn)
+                .map_err(|err| OperationError::new("Failed to remove circuit", err))?;
+
+            Ok(())
+        })
+    }
+}
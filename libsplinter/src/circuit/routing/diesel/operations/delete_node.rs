@@ -0,0 +1,45 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "delete node" operation for the `DieselRoutingTable`.
+
+use diesel::{dsl::delete, prelude::*};
+
+use crate::circuit::routing::diesel::schema::{routing_node_endpoints, routing_nodes};
+
+use super::{OperationError, RoutingTableOperations};
+
+pub(in crate::circuit::routing::diesel) trait RoutingTableDeleteNodeOperation {
+    fn delete_node(&self, node_id: &str) -> Result<(), OperationError>;
+}
+
+impl<'a, C> RoutingTableDeleteNodeOperation for RoutingTableOperations<'a, C>
+where
+    C: diesel::Connection,
+{
+    fn delete_node(&self, node_id: &str) -> Result<(), OperationError> {
+        self.conn.transaction::<_, _, _>(|| {
+            delete(
+                routing_node_endpoints::table.filter(routing_node_endpoints::node_id.eq(node_id)),
+            )
+            .execute(self.conn)
+            .map_err(|err| OperationError::new("Failed to remove node endpoints", err))?;
+            delete(routing_nodes::table.find(node_id))
+                .execute(self.conn)
+                .map_err(|err| OperationError::new("Failed to remove node", err))?;
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "fetch circuit" operation for the `DieselRoutingTable`.
+
+use diesel::prelude::*;
+
+use crate::circuit::routing::{
+    diesel::{
+        models::{
+            CircuitMemberModel, CircuitModel, ServiceAllowedNodeModel, ServiceArgumentModel,
+            ServiceModel,
+        },
+        schema::{
+            routing_circuit_members, routing_circuits, routing_service_allowed_nodes,
+            routing_service_arguments, routing_services,
+        },
+    },
+    Circuit,
+};
+
+use super::{circuits_from_models, OperationError, RoutingTableOperations};
+
+pub(in crate::circuit::routing::diesel) trait RoutingTableFetchCircuitOperation {
+    fn fetch_circuit(&self, circuit_id: &str) -> Result<Option<Circuit>, OperationError>;
+}
+
+impl<'a, C> RoutingTableFetchCircuitOperation for RoutingTableOperations<'a, C>
+where
+    C: diesel::Connection,
+    i32: diesel::deserialize::FromSql<diesel::sql_types::Integer, C::Backend>,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn fetch_circuit(&self, circuit_id: &str) -> Result<Option<Circuit>, OperationError> {
+        self.conn.transaction::<_, _, _>(|| {
+            let circuit = match routing_circuits::table
+                .find(circuit_id)
+                .first::<CircuitModel>(self.conn)
+                .optional()
+                .map_err(|err| OperationError::new("Failed to fetch circuit", err))?
+            {
+                Some(circuit) => circuit,
+                None => return Ok(None),
+            };
+
+            let members = routing_circuit_members::table
+                .filter(routing_circuit_members::circuit_id.eq(circuit_id))
+                .order(routing_circuit_members::position)
+                .load::<CircuitMemberModel>(self.conn)
+                .map_err(|err| OperationError::new("Failed to fetch circuit members", err))?;
+            let services = routing_services::table
+                .filter(routing_services::circuit_id.eq(circuit_id))
+                .order(routing_services::position)
+                .load::<ServiceModel>(self.conn)
+                .map_err(|err| OperationError::new("Failed to fetch circuit services", err))?;
+            let allowed_nodes = routing_service_allowed_nodes::table
+                .filter(routing_service_allowed_nodes::circuit_id.eq(circuit_id))
+                .order((
+                    routing_service_allowed_nodes::service_position,
+                    routing_service_allowed_nodes::position,
+                ))
+                .load::<ServiceAllowedNodeModel>(self.conn)
+                .map_err(|err| OperationError::new("Failed to fetch service allowed nodes", err))?;
+            let arguments = routing_service_arguments::table
+                .filter(routing_service_arguments::circuit_id.eq(circuit_id))
+                .order((
+                    routing_service_arguments::service_position,
+                    routing_service_arguments::position,
+                ))
+                .load::<ServiceArgumentModel>(self.conn)
+                .map_err(|err| OperationError::new("Failed to fetch service arguments", err))?;
+
+            Ok(
+                circuits_from_models(vec![circuit], members, services, allowed_nodes, arguments)
+                    .pop(),
+            )
+        })
+    }
+}
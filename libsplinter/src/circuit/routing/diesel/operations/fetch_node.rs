@@ -0,0 +1,60 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "fetch node" operation for the `DieselRoutingTable`.
+
+use diesel::prelude::*;
+
+use crate::circuit::routing::{
+    diesel::{
+        models::{NodeEndpointModel, NodeModel},
+        schema::{routing_node_endpoints, routing_nodes},
+    },
+    CircuitNode,
+};
+
+use super::{nodes_from_models, OperationError, RoutingTableOperations};
+
+pub(in crate::circuit::routing::diesel) trait RoutingTableFetchNodeOperation {
+    fn fetch_node(&self, node_id: &str) -> Result<Option<CircuitNode>, OperationError>;
+}
+
+impl<'a, C> RoutingTableFetchNodeOperation for RoutingTableOperations<'a, C>
+where
+    C: diesel::Connection,
+    i32: diesel::deserialize::FromSql<diesel::sql_types::Integer, C::Backend>,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn fetch_node(&self, node_id: &str) -> Result<Option<CircuitNode>, OperationError> {
+        self.conn.transaction::<_, _, _>(|| {
+            let node = match routing_nodes::table
+                .find(node_id)
+                .first::<NodeModel>(self.conn)
+                .optional()
+                .map_err(|err| OperationError::new("Failed to fetch node", err))?
+            {
+                Some(node) => node,
+                None => return Ok(None),
+            };
+
+            let endpoints = routing_node_endpoints::table
+                .filter(routing_node_endpoints::node_id.eq(node_id))
+                .order(routing_node_endpoints::position)
+                .load::<NodeEndpointModel>(self.conn)
+                .map_err(|err| OperationError::new("Failed to fetch node endpoints", err))?;
+
+            Ok(nodes_from_models(vec![node], endpoints).pop())
+        })
+    }
+}
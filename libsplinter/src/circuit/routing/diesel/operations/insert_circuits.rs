@@ -0,0 +1,129 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "insert circuits" operation for the `DieselRoutingTable`.
+
+use diesel::{dsl::insert_into, prelude::*};
+
+use crate::circuit::routing::{
+    diesel::{
+        models::{
+            CircuitMemberModel, CircuitModel, ServiceAllowedNodeModel, ServiceArgumentModel,
+            ServiceModel,
+        },
+        schema::{
+            routing_circuit_members, routing_circuits, routing_service_allowed_nodes,
+            routing_service_arguments, routing_services,
+        },
+    },
+    Circuit,
+};
+
+use super::{
+    delete_circuit::RoutingTableDeleteCircuitOperation as _, OperationError, RoutingTableOperations,
+};
+
+pub(in crate::circuit::routing::diesel) trait RoutingTableInsertCircuitsOperation {
+    fn insert_circuits(&self, circuits: &[Circuit]) -> Result<(), OperationError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> RoutingTableInsertCircuitsOperation
+    for RoutingTableOperations<'a, diesel::pg::PgConnection>
+{
+    fn insert_circuits(&self, circuits: &[Circuit]) -> Result<(), OperationError> {
+        self.conn.transaction::<_, _, _>(|| {
+            for circuit in circuits {
+                // Replace any previous version of the circuit
+                self.delete_circuit(&circuit.circuit_id)?;
+
+                insert_into(routing_circuits::table)
+                    .values(CircuitModel::from(circuit))
+                    .execute(self.conn)
+                    .map_err(|err| OperationError::new("Failed to insert circuit", err))?;
+                let members: Vec<CircuitMemberModel> = Vec::from(circuit);
+                insert_into(routing_circuit_members::table)
+                    .values(&members)
+                    .execute(self.conn)
+                    .map_err(|err| OperationError::new("Failed to insert circuit members", err))?;
+                let services: Vec<ServiceModel> = Vec::from(circuit);
+                insert_into(routing_services::table)
+                    .values(&services)
+                    .execute(self.conn)
+                    .map_err(|err| OperationError::new("Failed to insert circuit services", err))?;
+                let allowed_nodes: Vec<ServiceAllowedNodeModel> = Vec::from(circuit);
+                insert_into(routing_service_allowed_nodes::table)
+                    .values(&allowed_nodes)
+                    .execute(self.conn)
+                    .map_err(|err| {
+                        OperationError::new("Failed to insert service allowed nodes", err)
+                    })?;
+                let arguments: Vec<ServiceArgumentModel> = Vec::from(circuit);
+                insert_into(routing_service_arguments::table)
+                    .values(&arguments)
+                    .execute(self.conn)
+                    .map_err(|err| {
+                        OperationError::new("Failed to insert service arguments", err)
+                    })?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> RoutingTableInsertCircuitsOperation
+    for RoutingTableOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn insert_circuits(&self, circuits: &[Circuit]) -> Result<(), OperationError> {
+        self.conn.transaction::<_, _, _>(|| {
+            for circuit in circuits {
+                // Replace any previous version of the circuit
+                self.delete_circuit(&circuit.circuit_id)?;
+
+                insert_into(routing_circuits::table)
+                    .values(CircuitModel::from(circuit))
+                    .execute(self.conn)
+                    .map_err(|err| OperationError::new("Failed to insert circuit", err))?;
+                let members: Vec<CircuitMemberModel> = Vec::from(circuit);
+                insert_into(routing_circuit_members::table)
+                    .values(&members)
+                    .execute(self.conn)
+                    .map_err(|err| OperationError::new("Failed to insert circuit members", err))?;
+                let services: Vec<ServiceModel> = Vec::from(circuit);
+                insert_into(routing_services::table)
+                    .values(&services)
+                    .execute(self.conn)
+                    .map_err(|err| OperationError::new("Failed to insert circuit services", err))?;
+                let allowed_nodes: Vec<ServiceAllowedNodeModel> = Vec::from(circuit);
+                insert_into(routing_service_allowed_nodes::table)
+                    .values(&allowed_nodes)
+                    .execute(self.conn)
+                    .map_err(|err| {
+                        OperationError::new("Failed to insert service allowed nodes", err)
+                    })?;
+                let arguments: Vec<ServiceArgumentModel> = Vec::from(circuit);
+                insert_into(routing_service_arguments::table)
+                    .values(&arguments)
+                    .execute(self.conn)
+                    .map_err(|err| {
+                        OperationError::new("Failed to insert service arguments", err)
+                    })?;
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,98 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "insert nodes" operation for the `DieselRoutingTable`.
+
+use diesel::{dsl::insert_into, prelude::*};
+
+use crate::circuit::routing::{
+    diesel::{
+        models::{NodeEndpointModel, NodeModel},
+        schema::{routing_node_endpoints, routing_nodes},
+    },
+    CircuitNode,
+};
+
+use super::{OperationError, RoutingTableOperations};
+
+pub(in crate::circuit::routing::diesel) trait RoutingTableInsertNodesOperation {
+    /// Inserts the given nodes, skipping any node that is already present
+    fn insert_nodes(&self, nodes: &[CircuitNode]) -> Result<(), OperationError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> RoutingTableInsertNodesOperation for RoutingTableOperations<'a, diesel::pg::PgConnection> {
+    fn insert_nodes(&self, nodes: &[CircuitNode]) -> Result<(), OperationError> {
+        self.conn.transaction::<_, _, _>(|| {
+            for node in nodes {
+                // Existing nodes are left untouched
+                let exists = routing_nodes::table
+                    .find(&node.node_id)
+                    .first::<NodeModel>(self.conn)
+                    .optional()
+                    .map_err(|err| OperationError::new("Failed to check if node exists", err))?
+                    .is_some();
+                if exists {
+                    continue;
+                }
+
+                insert_into(routing_nodes::table)
+                    .values(NodeModel::from(node))
+                    .execute(self.conn)
+                    .map_err(|err| OperationError::new("Failed to insert node", err))?;
+                let endpoints: Vec<NodeEndpointModel> = Vec::from(node);
+                insert_into(routing_node_endpoints::table)
+                    .values(&endpoints)
+                    .execute(self.conn)
+                    .map_err(|err| OperationError::new("Failed to insert node endpoints", err))?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> RoutingTableInsertNodesOperation
+    for RoutingTableOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn insert_nodes(&self, nodes: &[CircuitNode]) -> Result<(), OperationError> {
+        self.conn.transaction::<_, _, _>(|| {
+            for node in nodes {
+                // Existing nodes are left untouched
+                let exists = routing_nodes::table
+                    .find(&node.node_id)
+                    .first::<NodeModel>(self.conn)
+                    .optional()
+                    .map_err(|err| OperationError::new("Failed to check if node exists", err))?
+                    .is_some();
+                if exists {
+                    continue;
+                }
+
+                insert_into(routing_nodes::table)
+                    .values(NodeModel::from(node))
+                    .execute(self.conn)
+                    .map_err(|err| OperationError::new("Failed to insert node", err))?;
+                let endpoints: Vec<NodeEndpointModel> = Vec::from(node);
+                insert_into(routing_node_endpoints::table)
+                    .values(&endpoints)
+                    .execute(self.conn)
+                    .map_err(|err| OperationError::new("Failed to insert node endpoints", err))?;
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "list circuits" operation for the `DieselRoutingTable`.
+
+use diesel::prelude::*;
+
+use crate::circuit::routing::{
+    diesel::{
+        models::{
+            CircuitMemberModel, CircuitModel, ServiceAllowedNodeModel, ServiceArgumentModel,
+            ServiceModel,
+        },
+        schema::{
+            routing_circuit_members, routing_circuits, routing_service_allowed_nodes,
+            routing_service_arguments, routing_services,
+        },
+    },
+    Circuit,
+};
+
+use super::{circuits_from_models, OperationError, RoutingTableOperations};
+
+pub(in crate::circuit::routing::diesel) trait RoutingTableListCircuitsOperation {
+    fn list_circuits(&self) -> Result<Vec<Circuit>, OperationError>;
+}
+
+impl<'a, C> RoutingTableListCircuitsOperation for RoutingTableOperations<'a, C>
+where
+    C: diesel::Connection,
+    i32: diesel::deserialize::FromSql<diesel::sql_types::Integer, C::Backend>,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn list_circuits(&self) -> Result<Vec<Circuit>, OperationError> {
+        self.conn.transaction::<_, _, _>(|| {
+            let circuits = routing_circuits::table
+                .order(routing_circuits::circuit_id)
+                .load::<CircuitModel>(self.conn)
+                .map_err(|err| OperationError::new("Failed to list circuits", err))?;
+            let members = routing_circuit_members::table
+                .order((
+                    routing_circuit_members::circuit_id,
+                    routing_circuit_members::position,
+                ))
+                .load::<CircuitMemberModel>(self.conn)
+                .map_err(|err| OperationError::new("Failed to list circuit members", err))?;
+            let services = routing_services::table
+                .order((routing_services::circuit_id, routing_services::position))
+                .load::<ServiceModel>(self.conn)
+                .map_err(|err| OperationError::new("Failed to list circuit services", err))?;
+            let allowed_nodes = routing_service_allowed_nodes::table
+                .order((
+                    routing_service_allowed_nodes::circuit_id,
+                    routing_service_allowed_nodes::service_position,
+                    routing_service_allowed_nodes::position,
+                ))
+                .load::<ServiceAllowedNodeModel>(self.conn)
+                .map_err(|err| OperationError::new("Failed to list service allowed nodes", err))?;
+            let arguments = routing_service_arguments::table
+                .order((
+                    routing_service_arguments::circuit_id,
+                    routing_service_arguments::service_position,
+                    routing_service_arguments::position,
+                ))
+                .load::<ServiceArgumentModel>(self.conn)
+                .map_err(|err| OperationError::new("Failed to list service arguments", err))?;
+
+            Ok(circuits_from_models(
+                circuits,
+                members,
+                services,
+                allowed_nodes,
+                arguments,
+            ))
+        })
+    }
+}
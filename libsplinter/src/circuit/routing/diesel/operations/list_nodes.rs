@@ -0,0 +1,56 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "list nodes" operation for the `DieselRoutingTable`.
+
+use diesel::prelude::*;
+
+use crate::circuit::routing::{
+    diesel::{
+        models::{NodeEndpointModel, NodeModel},
+        schema::{routing_node_endpoints, routing_nodes},
+    },
+    CircuitNode,
+};
+
+use super::{nodes_from_models, OperationError, RoutingTableOperations};
+
+pub(in crate::circuit::routing::diesel) trait RoutingTableListNodesOperation {
+    fn list_nodes(&self) -> Result<Vec<CircuitNode>, OperationError>;
+}
+
+impl<'a, C> RoutingTableListNodesOperation for RoutingTableOperations<'a, C>
+where
+    C: diesel::Connection,
+    i32: diesel::deserialize::FromSql<diesel::sql_types::Integer, C::Backend>,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+{
+    fn list_nodes(&self) -> Result<Vec<CircuitNode>, OperationError> {
+        self.conn.transaction::<_, _, _>(|| {
+            let nodes = routing_nodes::table
+                .order(routing_nodes::node_id)
+                .load::<NodeModel>(self.conn)
+                .map_err(|err| OperationError::new("Failed to list nodes", err))?;
+            let endpoints = routing_node_endpoints::table
+                .order((
+                    routing_node_endpoints::node_id,
+                    routing_node_endpoints::position,
+                ))
+                .load::<NodeEndpointModel>(self.conn)
+                .map_err(|err| OperationError::new("Failed to list node endpoints", err))?;
+
+            Ok(nodes_from_models(nodes, endpoints))
+        })
+    }
+}
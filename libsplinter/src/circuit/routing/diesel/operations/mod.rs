@@ -0,0 +1,160 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides database operations for the `DieselRoutingTable`.
+
+pub(super) mod delete_circuit;
+pub(super) mod delete_node;
+pub(super) mod fetch_circuit;
+pub(super) mod fetch_node;
+pub(super) mod insert_circuits;
+pub(super) mod insert_node;
+pub(super) mod insert_nodes;
+pub(super) mod list_circuits;
+pub(super) mod list_nodes;
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+use crate::circuit::routing::{Circuit, CircuitNode, Service};
+
+use super::models::{
+    CircuitMemberModel, CircuitModel, NodeEndpointModel, NodeModel, ServiceAllowedNodeModel,
+    ServiceArgumentModel, ServiceModel,
+};
+
+pub struct RoutingTableOperations<'a, C> {
+    conn: &'a C,
+}
+
+impl<'a, C: diesel::Connection> RoutingTableOperations<'a, C> {
+    pub fn new(conn: &'a C) -> Self {
+        RoutingTableOperations { conn }
+    }
+}
+
+/// An error raised by a routing table database operation
+#[derive(Debug)]
+pub struct OperationError {
+    pub context: String,
+    pub source: diesel::result::Error,
+}
+
+impl OperationError {
+    fn new(context: &str, source: diesel::result::Error) -> Self {
+        OperationError {
+            context: context.to_string(),
+            source,
+        }
+    }
+}
+
+impl Error for OperationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+impl fmt::Display for OperationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.context, self.source)
+    }
+}
+
+impl From<diesel::result::Error> for OperationError {
+    fn from(err: diesel::result::Error) -> Self {
+        OperationError::new("Database transaction failed", err)
+    }
+}
+
+/// Assembles routing table circuits from their rows. Child rows must be ordered by position;
+/// circuits are returned in the order of the given circuit rows.
+fn circuits_from_models(
+    circuits: Vec<CircuitModel>,
+    members: Vec<CircuitMemberModel>,
+    services: Vec<ServiceModel>,
+    allowed_nodes: Vec<ServiceAllowedNodeModel>,
+    arguments: Vec<ServiceArgumentModel>,
+) -> Vec<Circuit> {
+    let mut members_by_circuit: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for member in members {
+        members_by_circuit
+            .entry(member.circuit_id)
+            .or_default()
+            .push(member.node_id);
+    }
+
+    let mut allowed_nodes_by_service: BTreeMap<(String, i32), Vec<String>> = BTreeMap::new();
+    for allowed_node in allowed_nodes {
+        allowed_nodes_by_service
+            .entry((allowed_node.circuit_id, allowed_node.service_position))
+            .or_default()
+            .push(allowed_node.node_id);
+    }
+
+    let mut arguments_by_service: BTreeMap<(String, i32), Vec<(String, String)>> = BTreeMap::new();
+    for argument in arguments {
+        arguments_by_service
+            .entry((argument.circuit_id, argument.service_position))
+            .or_default()
+            .push((argument.key, argument.value));
+    }
+
+    let mut roster_by_circuit: BTreeMap<String, Vec<Service>> = BTreeMap::new();
+    for service in services {
+        let key = (service.circuit_id, service.position);
+        let allowed_nodes = allowed_nodes_by_service.remove(&key).unwrap_or_default();
+        let arguments = arguments_by_service.remove(&key).unwrap_or_default();
+        roster_by_circuit.entry(key.0).or_default().push(Service {
+            service_id: service.service_id,
+            service_type: service.service_type,
+            allowed_nodes,
+            arguments,
+        });
+    }
+
+    circuits
+        .into_iter()
+        .map(|circuit| Circuit {
+            roster: roster_by_circuit
+                .remove(&circuit.circuit_id)
+                .unwrap_or_default(),
+            members: members_by_circuit
+                .remove(&circuit.circuit_id)
+                .unwrap_or_default(),
+            circuit_id: circuit.circuit_id,
+        })
+        .collect()
+}
+
+/// Assembles routing table nodes from their rows. Endpoint rows must be ordered by position;
+/// nodes are returned in the order of the given node rows.
+fn nodes_from_models(nodes: Vec<NodeModel>, endpoints: Vec<NodeEndpointModel>) -> Vec<CircuitNode> {
+    let mut endpoints_by_node: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for endpoint in endpoints {
+        endpoints_by_node
+            .entry(endpoint.node_id)
+            .or_default()
+            .push(endpoint.endpoint);
+    }
+
+    nodes
+        .into_iter()
+        .map(|node| CircuitNode {
+            endpoints: endpoints_by_node.remove(&node.node_id).unwrap_or_default(),
+            node_id: node.node_id,
+        })
+        .collect()
+}
@@ -0,0 +1,71 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides database schemas for the `DieselRoutingTable`.
+
+table! {
+    routing_circuits (circuit_id) {
+        circuit_id -> Text,
+    }
+}
+
+table! {
+    routing_circuit_members (circuit_id, position) {
+        circuit_id -> Text,
+        position -> Integer,
+        node_id -> Text,
+    }
+}
+
+table! {
+    routing_services (circuit_id, position) {
+        circuit_id -> Text,
+        position -> Integer,
+        service_id -> Text,
+        service_type -> Text,
+    }
+}
+
+table! {
+    routing_service_allowed_nodes (circuit_id, service_position, position) {
+        circuit_id -> Text,
+        service_position -> Integer,
+        position -> Integer,
+        node_id -> Text,
+    }
+}
+
+table! {
+    routing_service_arguments (circuit_id, service_position, position) {
+        circuit_id -> Text,
+        service_position -> Integer,
+        position -> Integer,
+        key -> Text,
+        value -> Text,
+    }
+}
+
+table! {
+    routing_nodes (node_id) {
+        node_id -> Text,
+    }
+}
+
+table! {
+    routing_node_endpoints (node_id, position) {
+        node_id -> Text,
+        position -> Integer,
+        endpoint -> Text,
+    }
+}
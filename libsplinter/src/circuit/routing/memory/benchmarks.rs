@@ -31,7 +31,10 @@ use std::cmp::min;
 
 // Helper function for generating a large number of nodes with the associated services, that are
 // then added to circuits. The circuits contain a random number of nodes up to 10.
-fn generate_circuits(num_circuits: i64, total_num_node: i64) -> (Vec<Circuit>, Vec<CircuitNode>) {
+pub(in crate::circuit::routing) fn generate_circuits(
+    num_circuits: i64,
+    total_num_node: i64,
+) -> (Vec<Circuit>, Vec<CircuitNode>) {
     // generate nodes and their associated services
     let mut nodes = vec![];
     for i in 0..total_num_node {
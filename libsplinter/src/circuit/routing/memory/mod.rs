@@ -20,7 +20,7 @@
 //! [`RoutingTable`]: struct.RoutingTable.html
 
 #[cfg(all(feature = "benchmark", test))]
-mod benchmarks;
+pub(super) mod benchmarks;
 
 use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, RwLock};
@@ -16,22 +16,29 @@
 //!
 //! The public interface includes the traits [`RoutingTableReader`] and [`RoutingTableWriter`] and
 //! the structs [`Service`], [`ServiceId`], [`Circuit`], and [`CircuitNode`]. It also includes
-//! a RwLock implmentation of the traits [`RoutingTable`].
+//! a RwLock implmentation of the traits [`RoutingTable`] and, with the `routing-table-database`
+//! feature, a database-backed implementation [`DieselRoutingTable`].
 //!
 //! [`Circuit`]: struct.Circuit.html
 //! [`CircuitNode`]: struct.CircuitNode.html
+//! [`DieselRoutingTable`]: diesel/struct.DieselRoutingTable.html
 //! [`RoutingTable`]: memory/struct.RoutingTable.html
 //! [`RoutingTableReader`]: trait.RoutingTableReader.html
 //! [`RoutingTableWriter`]: trait.RoutingTableWriter.html
 //! [`Service`]: struct.Service.html
 //! [`ServiceId`]: struct.ServiceId.html
 
+#[cfg(feature = "routing-table-database")]
+pub mod diesel;
 mod error;
 pub mod memory;
 
 use std::cmp::Ordering;
 use std::fmt;
 
+use super::service::SplinterNode;
+use super::{Circuit as SplinterCircuit, ServiceDefinition};
+
 use self::error::{
     AddCircuitError, AddCircuitsError, AddNodeError, AddNodesError, AddServiceError,
     FetchCircuitError, FetchNodeError, FetchServiceError, ListCircuitsError, ListNodesError,
@@ -64,6 +71,16 @@ impl Circuit {
     }
 }
 
+impl From<&SplinterCircuit> for Circuit {
+    fn from(circuit: &SplinterCircuit) -> Self {
+        Circuit::new(
+            circuit.id().to_string(),
+            circuit.roster().iter().map(Service::from).collect(),
+            circuit.members().to_vec(),
+        )
+    }
+}
+
 /// The routing table representation of a node
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct CircuitNode {
@@ -83,6 +100,12 @@ impl CircuitNode {
     }
 }
 
+impl From<&SplinterNode> for CircuitNode {
+    fn from(node: &SplinterNode) -> Self {
+        CircuitNode::new(node.id().to_string(), node.endpoints().to_vec())
+    }
+}
+
 impl Ord for CircuitNode {
     fn cmp(&self, other: &Self) -> Ordering {
         self.node_id.cmp(&other.node_id)
@@ -128,6 +151,21 @@ impl Service {
     }
 }
 
+impl From<&ServiceDefinition> for Service {
+    fn from(service: &ServiceDefinition) -> Self {
+        Service::new(
+            service.service_id().to_string(),
+            service.service_type().to_string(),
+            service.allowed_nodes().to_vec(),
+            service
+                .arguments()
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        )
+    }
+}
+
 /// The unique ID of a service made up of a circuit ID and service ID
 #[derive(Clone, Debug, Hash, PartialEq)]
 pub struct ServiceId {
@@ -253,7 +291,10 @@ pub type CircuitNodeIter = Box<dyn ExactSizeIterator<Item = (String, CircuitNode
 pub type CircuitIter = Box<dyn ExactSizeIterator<Item = (String, Circuit)> + Send>;
 
 /// The trait that defines a reader for reading the in-memory routing table
-pub trait RoutingTableReader: Send {
+///
+/// Readers are shared between threads without a lock, so lookups on the routing table are not
+/// serialized.
+pub trait RoutingTableReader: Send + Sync {
     // ---------- methods to access service directory ----------
 
     /// Returns the service with the provided ID
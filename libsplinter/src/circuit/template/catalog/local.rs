@@ -0,0 +1,247 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A template catalog backed by directories on the local filesystem.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::super::{yaml_parser::TemplateMetadata, CircuitTemplateError};
+use super::{TemplateCatalog, TemplateEntry};
+
+/// A template catalog backed by one or more local directories.
+///
+/// Every `.yaml` file in the directories is a template. The name of a template is the `name`
+/// defined by the template, or the file name without the `.yaml` extension if the template does
+/// not define a name. Several versions of the same template may be provided by files with
+/// different names, such as `scabbard-1.0.yaml` and `scabbard-1.1.yaml`, that define the same
+/// `name` and different `template-version` values.
+#[derive(Clone)]
+pub struct LocalTemplateCatalog {
+    dirs: Vec<PathBuf>,
+}
+
+impl LocalTemplateCatalog {
+    /// Constructs a `LocalTemplateCatalog` from the given directories.
+    ///
+    /// Returns an error if any of the paths is not a directory.
+    pub fn new(dirs: &[&str]) -> Result<Self, CircuitTemplateError> {
+        let dirs = dirs
+            .iter()
+            .map(|dir| {
+                if Path::new(dir).is_dir() {
+                    Ok(PathBuf::from(dir))
+                } else {
+                    Err(CircuitTemplateError::new(&format!(
+                        "{} is not a valid directory",
+                        dir
+                    )))
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(LocalTemplateCatalog { dirs })
+    }
+
+    /// Reads the catalog entry of the template file at `path`.
+    fn read_entry(path: &Path) -> Result<TemplateEntry, CircuitTemplateError> {
+        let data = fs::read(path).map_err(|err| {
+            CircuitTemplateError::new_with_source(
+                &format!("Unable to read circuit template file {}", path.display()),
+                Box::new(err),
+            )
+        })?;
+        let metadata = TemplateMetadata::deserialize(&data)?;
+
+        let name = match metadata.name {
+            Some(name) => name,
+            None => path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .map(String::from)
+                .ok_or_else(|| {
+                    CircuitTemplateError::new(&format!(
+                        "Unable to read circuit template file name: {}",
+                        path.display()
+                    ))
+                })?,
+        };
+        let version = metadata
+            .template_version
+            .map(|version| version.parse())
+            .transpose()?;
+
+        Ok(TemplateEntry::new(
+            &name,
+            version,
+            metadata.description,
+            metadata.deprecated,
+            Some(path.to_string_lossy().into_owned()),
+        ))
+    }
+}
+
+impl TemplateCatalog for LocalTemplateCatalog {
+    fn list_templates(&self) -> Result<Vec<TemplateEntry>, CircuitTemplateError> {
+        let mut entries = vec![];
+        for dir in self.dirs.iter() {
+            let files = dir.read_dir().map_err(|err| {
+                CircuitTemplateError::new_with_source(
+                    &format!("Failed to read circuit template files in {}", dir.display()),
+                    Box::new(err),
+                )
+            })?;
+
+            for file in files {
+                let path = match file {
+                    Ok(file) => file.path(),
+                    Err(err) => {
+                        error!("Unable to read circuit template file: {}", err);
+                        continue;
+                    }
+                };
+
+                if path.extension().map(|ext| ext != "yaml").unwrap_or(true) {
+                    continue;
+                }
+
+                match Self::read_entry(&path) {
+                    Ok(entry) => entries.push(entry),
+                    Err(err) => error!("Skipping invalid circuit template: {}", err),
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    fn fetch_template_yaml(&self, entry: &TemplateEntry) -> Result<Vec<u8>, CircuitTemplateError> {
+        let path = entry.location().ok_or_else(|| {
+            CircuitTemplateError::new(&format!(
+                "Template {} does not have a location",
+                entry.template_ref()
+            ))
+        })?;
+
+        if !self.dirs.iter().any(|dir| Path::new(path).starts_with(dir)) {
+            return Err(CircuitTemplateError::new(&format!(
+                "Template {} is not part of this catalog",
+                entry.template_ref()
+            )));
+        }
+
+        fs::read(path).map_err(|err| {
+            CircuitTemplateError::new_with_source(
+                "Error reading data from template file",
+                Box::new(err),
+            )
+        })
+    }
+
+    fn clone_box(&self) -> Box<dyn TemplateCatalog> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use tempdir::TempDir;
+
+    use crate::circuit::template::catalog::TemplateVersion;
+
+    const TEMPLATE_V1_YAML: &[u8] = b"version: v1
+args: []
+rules:
+    set-management-type:
+        management-type: gameroom
+";
+
+    const TEMPLATE_V2_1_0_YAML: &[u8] = b"version: v2
+name: scabbard
+template-version: 1.0.0
+deprecated: Use version 1.1 or later
+include:
+    - gameroom
+";
+
+    const TEMPLATE_V2_1_1_YAML: &[u8] = b"version: v2
+name: scabbard
+template-version: 1.1.0
+include:
+    - gameroom
+rules:
+    set-comments:
+        comments: version 1.1
+";
+
+    /// Verify that a `LocalTemplateCatalog` lists the templates in its directory, with the names
+    /// and versions defined by the templates, and that templates and their includes are loaded
+    /// through the catalog.
+    ///
+    /// 1. Write a v1 template and two versions of a v2 template to a temporary directory.
+    /// 2. Verify the catalog lists the three templates with the expected names and versions.
+    /// 3. Verify the deprecated version can be pinned and that loading the template resolves its
+    ///    `gameroom` include.
+    /// 4. Verify the unpinned reference loads the latest version.
+    #[test]
+    fn test_local_catalog() {
+        let temp_dir = TempDir::new("test_local_catalog").expect("Failed to create temp dir");
+        let dir = temp_dir.path();
+        fs::write(dir.join("gameroom.yaml"), TEMPLATE_V1_YAML).expect("Failed to write file");
+        fs::write(dir.join("scabbard-1.0.yaml"), TEMPLATE_V2_1_0_YAML)
+            .expect("Failed to write file");
+        fs::write(dir.join("scabbard-1.1.yaml"), TEMPLATE_V2_1_1_YAML)
+            .expect("Failed to write file");
+        fs::write(dir.join("README.md"), b"not a template").expect("Failed to write file");
+
+        let catalog = LocalTemplateCatalog::new(&[dir.to_str().expect("Invalid temp dir")])
+            .expect("Failed to create catalog");
+
+        let mut entries = catalog.list_templates().expect("Failed to list templates");
+        entries.sort_by_key(|entry| entry.template_ref());
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| entry.template_ref())
+                .collect::<Vec<_>>(),
+            vec!["gameroom", "scabbard@1.0.0", "scabbard@1.1.0"]
+        );
+
+        let deprecated = catalog
+            .find_template("scabbard@1.0")
+            .expect("Failed to find template");
+        assert_eq!(
+            deprecated.deprecated(),
+            Some(&"Use version 1.1 or later".to_string())
+        );
+
+        let template = catalog
+            .load_template("scabbard@1.0")
+            .expect("Failed to load template");
+        assert_eq!(template.version(), "v2");
+
+        let latest = catalog
+            .find_template("scabbard")
+            .expect("Failed to find template");
+        assert_eq!(latest.version(), Some(&TemplateVersion::new(1, 1, 0)));
+        assert!(latest.deprecated().is_none());
+
+        let yaml = catalog
+            .load_raw_yaml("scabbard")
+            .expect("Failed to load template yaml");
+        assert!(yaml.contains("version 1.1"));
+    }
+}
@@ -0,0 +1,303 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Catalogs of versioned circuit templates.
+//!
+//! A [`TemplateCatalog`] lists the available templates and provides their YAML definitions.
+//! Templates may be loaded from local directories with the [`LocalTemplateCatalog`], from an HTTP(S)
+//! catalog index with the [`RemoteTemplateCatalog`] (including the `/admin/templates` endpoint of a
+//! splinterd node), and several catalogs may be combined with the [`UnifiedTemplateCatalog`].
+//!
+//! Templates are referenced by name, optionally followed by a version requirement: `scabbard`
+//! refers to the latest version of the `scabbard` template, while `scabbard@1.2` refers to the
+//! latest `1.2.x` version.
+//!
+//! [`TemplateCatalog`]: trait.TemplateCatalog.html
+//! [`LocalTemplateCatalog`]: struct.LocalTemplateCatalog.html
+//! [`RemoteTemplateCatalog`]: struct.RemoteTemplateCatalog.html
+//! [`UnifiedTemplateCatalog`]: struct.UnifiedTemplateCatalog.html
+
+mod local;
+#[cfg(feature = "circuit-template-remote")]
+mod remote;
+#[cfg(feature = "rest-api")]
+mod rest_api;
+mod unified;
+mod version;
+
+use super::{CircuitCreateTemplate, CircuitTemplateError};
+
+pub use local::LocalTemplateCatalog;
+#[cfg(feature = "circuit-template-remote")]
+pub use remote::RemoteTemplateCatalog;
+pub use unified::UnifiedTemplateCatalog;
+pub use version::{TemplateVersion, TemplateVersionReq};
+
+/// Information about a single version of a template in a catalog.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TemplateEntry {
+    name: String,
+    /// Version of the template; unversioned templates are only selected when no version is
+    /// requested.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<TemplateVersion>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// Deprecation notice of the template version.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deprecated: Option<String>,
+    /// Catalog-specific location of the template, such as a file path or a URL.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    location: Option<String>,
+    /// Position of the catalog that listed this entry in each enclosing
+    /// `UnifiedTemplateCatalog`, outermost first, so that the template is fetched from that
+    /// catalog directly.
+    #[serde(skip)]
+    catalog_path: Vec<usize>,
+}
+
+impl TemplateEntry {
+    pub fn new(
+        name: &str,
+        version: Option<TemplateVersion>,
+        description: Option<String>,
+        deprecated: Option<String>,
+        location: Option<String>,
+    ) -> Self {
+        TemplateEntry {
+            name: name.to_string(),
+            version,
+            description,
+            deprecated,
+            location,
+            catalog_path: vec![],
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> Option<&TemplateVersion> {
+        self.version.as_ref()
+    }
+
+    pub fn description(&self) -> Option<&String> {
+        self.description.as_ref()
+    }
+
+    pub fn deprecated(&self) -> Option<&String> {
+        self.deprecated.as_ref()
+    }
+
+    pub fn location(&self) -> Option<&String> {
+        self.location.as_ref()
+    }
+
+    /// Returns the reference to this exact template version, in the form `name@version`.
+    pub fn template_ref(&self) -> String {
+        match &self.version {
+            Some(version) => format!("{}@{}", self.name, version),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// A source of circuit templates.
+pub trait TemplateCatalog: Send + Sync {
+    /// Lists every version of every template in the catalog.
+    fn list_templates(&self) -> Result<Vec<TemplateEntry>, CircuitTemplateError>;
+
+    /// Returns the YAML definition of the given template version.
+    fn fetch_template_yaml(&self, entry: &TemplateEntry) -> Result<Vec<u8>, CircuitTemplateError>;
+
+    /// Clones the catalog into a boxed trait object.
+    fn clone_box(&self) -> Box<dyn TemplateCatalog>;
+
+    /// Finds the latest version of a template that satisfies the reference.
+    ///
+    /// # Arguments
+    ///
+    /// * `template_ref` - Name of the template, optionally followed by `@` and a version
+    ///   requirement, such as `scabbard@1.2`.
+    fn find_template(&self, template_ref: &str) -> Result<TemplateEntry, CircuitTemplateError> {
+        let (name, version_req) = parse_template_ref(template_ref)?;
+
+        self.list_templates()?
+            .into_iter()
+            .filter(|entry| entry.name == name)
+            .filter(|entry| match (&version_req, &entry.version) {
+                (Some(req), Some(version)) => req.matches(version),
+                (Some(_), None) => false,
+                (None, _) => true,
+            })
+            // Keep the first of several equal versions, so earlier catalogs take precedence
+            .fold(None, |latest: Option<TemplateEntry>, entry| match latest {
+                Some(latest) if latest.version >= entry.version => Some(latest),
+                _ => Some(entry),
+            })
+            .ok_or_else(|| match version_req {
+                Some(req) => CircuitTemplateError::new(&format!(
+                    "Template {} with version {} was not found",
+                    name, req
+                )),
+                None => CircuitTemplateError::new(&format!("Template {} was not found", name)),
+            })
+    }
+
+    /// Loads the YAML definition of the latest template version that satisfies the reference.
+    fn load_raw_yaml(&self, template_ref: &str) -> Result<String, CircuitTemplateError> {
+        let entry = self.find_template(template_ref)?;
+        let data = self.fetch_template_yaml(&entry)?;
+        String::from_utf8(data).map_err(|err| {
+            CircuitTemplateError::new_with_source("Template is not valid UTF-8", Box::new(err))
+        })
+    }
+
+    /// Loads the latest template version that satisfies the reference into a
+    /// `CircuitCreateTemplate`.
+    ///
+    /// Templates included by the loaded template are also resolved through this catalog.
+    fn load_template(
+        &self,
+        template_ref: &str,
+    ) -> Result<CircuitCreateTemplate, CircuitTemplateError> {
+        let entry = self.find_template(template_ref)?;
+        let data = self.fetch_template_yaml(&entry)?;
+
+        CircuitCreateTemplate::load_with_includes(
+            &entry.template_ref(),
+            &data,
+            &|_, include_ref| {
+                let include = self.find_template(include_ref)?;
+                Ok((include.template_ref(), self.fetch_template_yaml(&include)?))
+            },
+            &mut vec![],
+        )
+    }
+}
+
+impl Clone for Box<dyn TemplateCatalog> {
+    fn clone(&self) -> Box<dyn TemplateCatalog> {
+        self.clone_box()
+    }
+}
+
+/// Splits a template reference of the form `name[@version]` into its name and optional version
+/// requirement.
+pub fn parse_template_ref(
+    template_ref: &str,
+) -> Result<(String, Option<TemplateVersionReq>), CircuitTemplateError> {
+    let mut parts = template_ref.splitn(2, '@');
+    let name = parts.next().unwrap_or_default();
+    if name.is_empty() {
+        return Err(CircuitTemplateError::new(&format!(
+            "Invalid template reference {}: name cannot be empty",
+            template_ref
+        )));
+    }
+
+    let version_req = parts.next().map(str::parse).transpose()?;
+
+    Ok((name.to_string(), version_req))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Catalog with a fixed list of entries, used to test the provided methods of the
+    /// `TemplateCatalog` trait.
+    #[derive(Clone)]
+    struct StaticCatalog {
+        entries: Vec<TemplateEntry>,
+    }
+
+    impl TemplateCatalog for StaticCatalog {
+        fn list_templates(&self) -> Result<Vec<TemplateEntry>, CircuitTemplateError> {
+            Ok(self.entries.clone())
+        }
+
+        fn fetch_template_yaml(
+            &self,
+            entry: &TemplateEntry,
+        ) -> Result<Vec<u8>, CircuitTemplateError> {
+            Ok(entry.location().cloned().unwrap_or_default().into_bytes())
+        }
+
+        fn clone_box(&self) -> Box<dyn TemplateCatalog> {
+            Box::new(self.clone())
+        }
+    }
+
+    /// Verify that template references are parsed into a name and an optional version
+    /// requirement, and that references without a name are rejected.
+    #[test]
+    fn test_parse_template_ref() {
+        let (name, req) = parse_template_ref("scabbard").expect("Failed to parse reference");
+        assert_eq!(name, "scabbard");
+        assert!(req.is_none());
+
+        let (name, req) = parse_template_ref("scabbard@1.2").expect("Failed to parse reference");
+        assert_eq!(name, "scabbard");
+        assert_eq!(
+            req,
+            Some("1.2".parse().expect("Failed to parse requirement"))
+        );
+
+        assert!(parse_template_ref("@1.2").is_err());
+        assert!(parse_template_ref("scabbard@one").is_err());
+    }
+
+    /// Verify that `find_template` returns the latest version matching the reference, and returns
+    /// an error if no version matches.
+    #[test]
+    fn test_find_template() {
+        let catalog = StaticCatalog {
+            entries: vec![
+                entry("scabbard", Some(TemplateVersion::new(1, 2, 0))),
+                entry("scabbard", Some(TemplateVersion::new(1, 2, 3))),
+                entry("scabbard", Some(TemplateVersion::new(2, 0, 0))),
+                entry("gameroom", None),
+            ],
+        };
+
+        let found = catalog
+            .find_template("scabbard")
+            .expect("Failed to find template");
+        assert_eq!(found.version(), Some(&TemplateVersion::new(2, 0, 0)));
+
+        let found = catalog
+            .find_template("scabbard@1.2")
+            .expect("Failed to find template");
+        assert_eq!(found.version(), Some(&TemplateVersion::new(1, 2, 3)));
+
+        let found = catalog
+            .find_template("gameroom")
+            .expect("Failed to find template");
+        assert_eq!(found.version(), None);
+
+        assert!(catalog.find_template("scabbard@3").is_err());
+        assert!(catalog.find_template("gameroom@1").is_err());
+        assert!(catalog.find_template("unknown").is_err());
+    }
+
+    fn entry(name: &str, version: Option<TemplateVersion>) -> TemplateEntry {
+        TemplateEntry::new(name, version, None, None, None)
+    }
+}
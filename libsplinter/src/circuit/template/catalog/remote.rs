@@ -0,0 +1,208 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A template catalog served over HTTP(S).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use openssl::hash::{hash, MessageDigest};
+use url::Url;
+
+use crate::hex::to_hex;
+
+use super::super::CircuitTemplateError;
+use super::{TemplateCatalog, TemplateEntry};
+
+/// A template catalog served over HTTP(S).
+///
+/// The catalog is defined by an index file at the catalog's URL, which is a YAML (or JSON) list of
+/// template entries. The `location` of each entry is the URL of the template's YAML definition,
+/// relative to the URL of the index. The `/admin/templates` endpoint of a splinterd node serves
+/// such an index.
+///
+/// The index and templates are cached in the `cache_dir`. A cached file is used without
+/// contacting the remote catalog until it is older than the `cache_ttl`. If the remote catalog
+/// cannot be reached, the cached files are used regardless of their age.
+#[derive(Clone)]
+pub struct RemoteTemplateCatalog {
+    url: Url,
+    cache_dir: PathBuf,
+    cache_ttl: Option<Duration>,
+}
+
+impl RemoteTemplateCatalog {
+    /// Constructs a new `RemoteTemplateCatalog`.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - URL of the catalog's index file.
+    /// * `cache_dir` - Directory the index and templates are cached in.
+    /// * `cache_ttl` - Age after which cached files are fetched again. If `None`, the remote
+    ///   catalog is contacted every time it is used.
+    pub fn new(
+        url: &str,
+        cache_dir: &str,
+        cache_ttl: Option<Duration>,
+    ) -> Result<Self, CircuitTemplateError> {
+        let url = Url::parse(url).map_err(|err| {
+            CircuitTemplateError::new_with_source(
+                &format!("Invalid template catalog URL {}", url),
+                Box::new(err),
+            )
+        })?;
+
+        fs::create_dir_all(cache_dir).map_err(|err| {
+            CircuitTemplateError::new_with_source(
+                &format!("Unable to create template cache directory {}", cache_dir),
+                Box::new(err),
+            )
+        })?;
+
+        Ok(RemoteTemplateCatalog {
+            url,
+            cache_dir: PathBuf::from(cache_dir),
+            cache_ttl,
+        })
+    }
+
+    /// Returns the contents of the file at `url`, from the cache if it is recent enough.
+    fn fetch_cached(&self, url: &Url) -> Result<Vec<u8>, CircuitTemplateError> {
+        let cache_file = compute_cache_filename(url, &self.cache_dir)?;
+
+        if let Some(ttl) = self.cache_ttl {
+            let is_fresh = fs::metadata(&cache_file)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .map(|age| age < ttl)
+                .unwrap_or(false);
+            if is_fresh {
+                if let Ok(data) = fs::read(&cache_file) {
+                    return Ok(data);
+                }
+            }
+        }
+
+        match fetch_from_remote(url) {
+            Ok(data) => {
+                if let Err(err) = fs::write(&cache_file, &data) {
+                    warn!(
+                        "Unable to cache {} in {}: {}",
+                        url,
+                        cache_file.display(),
+                        err
+                    );
+                }
+                Ok(data)
+            }
+            Err(err) => match fs::read(&cache_file) {
+                Ok(data) => {
+                    warn!("{}; using cached copy", err);
+                    Ok(data)
+                }
+                Err(_) => Err(err),
+            },
+        }
+    }
+}
+
+impl TemplateCatalog for RemoteTemplateCatalog {
+    fn list_templates(&self) -> Result<Vec<TemplateEntry>, CircuitTemplateError> {
+        let data = self.fetch_cached(&self.url)?;
+        let entries: Vec<TemplateEntry> = serde_yaml::from_slice(&data).map_err(|err| {
+            CircuitTemplateError::new_with_source(
+                &format!("Invalid template catalog index at {}", self.url),
+                Box::new(err),
+            )
+        })?;
+
+        entries
+            .into_iter()
+            .map(|entry| {
+                let location = entry.location().ok_or_else(|| {
+                    CircuitTemplateError::new(&format!(
+                        "Template {} in catalog {} does not have a location",
+                        entry.template_ref(),
+                        self.url
+                    ))
+                })?;
+                let location = self.url.join(location).map_err(|err| {
+                    CircuitTemplateError::new_with_source(
+                        &format!("Invalid location of template {}", entry.template_ref()),
+                        Box::new(err),
+                    )
+                })?;
+
+                Ok(TemplateEntry::new(
+                    entry.name(),
+                    entry.version().cloned(),
+                    entry.description().cloned(),
+                    entry.deprecated().cloned(),
+                    Some(location.to_string()),
+                ))
+            })
+            .collect()
+    }
+
+    fn fetch_template_yaml(&self, entry: &TemplateEntry) -> Result<Vec<u8>, CircuitTemplateError> {
+        let location = entry.location().ok_or_else(|| {
+            CircuitTemplateError::new(&format!(
+                "Template {} does not have a location",
+                entry.template_ref()
+            ))
+        })?;
+        let url = Url::parse(location).map_err(|err| {
+            CircuitTemplateError::new_with_source(
+                &format!("Invalid location of template {}", entry.template_ref()),
+                Box::new(err),
+            )
+        })?;
+
+        self.fetch_cached(&url)
+    }
+
+    fn clone_box(&self) -> Box<dyn TemplateCatalog> {
+        Box::new(self.clone())
+    }
+}
+
+// Derive the filename for the cache from a hash of the URL; this makes the location deterministic,
+// which allows the cache to be used across invocations.
+fn compute_cache_filename(url: &Url, cache_dir: &Path) -> Result<PathBuf, CircuitTemplateError> {
+    let hash = hash(MessageDigest::sha256(), url.as_str().as_bytes())
+        .map(|digest| to_hex(&*digest))
+        .map_err(|err| {
+            CircuitTemplateError::new_with_source(
+                "Failed to hash URL for cache file",
+                Box::new(err),
+            )
+        })?;
+    Ok(cache_dir.join(format!("template_catalog_{}.yaml", hash)))
+}
+
+/// Fetch the file at the given URL.
+fn fetch_from_remote(url: &Url) -> Result<Vec<u8>, CircuitTemplateError> {
+    reqwest::blocking::get(url.as_str())
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.bytes())
+        .map(|bytes| bytes.to_vec())
+        .map_err(|err| {
+            CircuitTemplateError::new_with_source(
+                &format!("Failed to fetch {} from remote template catalog", url),
+                Box::new(err),
+            )
+        })
+}
@@ -0,0 +1,70 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module provides the `GET /admin/templates` endpoint for listing the templates of a
+//! template catalog.
+
+use crate::actix_web::{web, Error, HttpRequest, HttpResponse};
+use crate::futures::Future;
+use crate::protocol;
+use crate::rest_api::{ErrorResponse, Method, ProtocolVersionRangeGuard, Resource};
+
+use super::super::super::{TemplateCatalog, TemplateEntry};
+
+pub fn make_templates_resource(catalog: Box<dyn TemplateCatalog>) -> Resource {
+    Resource::build("/admin/templates")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::ADMIN_LIST_TEMPLATES_MIN,
+            protocol::ADMIN_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Get, move |r, _| {
+            list_templates(r, web::Data::new(catalog.clone()))
+        })
+}
+
+fn list_templates(
+    _: HttpRequest,
+    catalog: web::Data<Box<dyn TemplateCatalog>>,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    Box::new(web::block(move || catalog.list_templates()).then(|res| {
+        Ok(match res {
+            Ok(entries) => HttpResponse::Ok().json(
+                entries
+                    .iter()
+                    .map(|entry| {
+                        // The location is relative to this endpoint, so it can be resolved by
+                        // a remote template catalog
+                        let location = match entry.version() {
+                            Some(version) => {
+                                format!("templates/{}?version={}", entry.name(), version)
+                            }
+                            None => format!("templates/{}", entry.name()),
+                        };
+                        TemplateEntry::new(
+                            entry.name(),
+                            entry.version().cloned(),
+                            entry.description().cloned(),
+                            entry.deprecated().cloned(),
+                            Some(location),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            Err(err) => {
+                error!("Unable to list templates: {}", err);
+                HttpResponse::InternalServerError().json(ErrorResponse::internal_error())
+            }
+        })
+    }))
+}
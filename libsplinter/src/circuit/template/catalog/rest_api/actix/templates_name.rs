@@ -0,0 +1,82 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module provides the `GET /admin/templates/{name}` endpoint for fetching the YAML
+//! definition of a template.
+
+use std::collections::HashMap;
+
+use crate::actix_web::{web, Error, HttpRequest, HttpResponse};
+use crate::futures::{future::IntoFuture, Future};
+use crate::protocol;
+use crate::rest_api::{ErrorResponse, Method, ProtocolVersionRangeGuard, Resource};
+
+use super::super::super::TemplateCatalog;
+
+pub fn make_templates_name_resource(catalog: Box<dyn TemplateCatalog>) -> Resource {
+    Resource::build("/admin/templates/{name}")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::ADMIN_FETCH_TEMPLATE_MIN,
+            protocol::ADMIN_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Get, move |r, _| {
+            fetch_template(r, web::Data::new(catalog.clone()))
+        })
+}
+
+fn fetch_template(
+    request: HttpRequest,
+    catalog: web::Data<Box<dyn TemplateCatalog>>,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    let query: web::Query<HashMap<String, String>> =
+        if let Ok(q) = web::Query::from_query(request.query_string()) {
+            q
+        } else {
+            return Box::new(
+                HttpResponse::BadRequest()
+                    .json(ErrorResponse::bad_request("Invalid query"))
+                    .into_future(),
+            );
+        };
+
+    let name = request.match_info().get("name").unwrap_or("").to_string();
+    let template_ref = match query.get("version") {
+        Some(version) => format!("{}@{}", name, version),
+        None => name,
+    };
+
+    Box::new(
+        web::block(move || {
+            let entry = catalog.find_template(&template_ref).ok();
+            match entry {
+                Some(entry) => catalog.fetch_template_yaml(&entry).map(Some),
+                None => Ok(None),
+            }
+        })
+        .then(|res| {
+            Ok(match res {
+                Ok(Some(yaml)) => HttpResponse::Ok()
+                    .content_type("application/x-yaml")
+                    .body(yaml),
+                Ok(None) => {
+                    HttpResponse::NotFound().json(ErrorResponse::not_found("Template not found"))
+                }
+                Err(err) => {
+                    error!("Unable to fetch template: {}", err);
+                    HttpResponse::InternalServerError().json(ErrorResponse::internal_error())
+                }
+            })
+        }),
+    )
+}
@@ -0,0 +1,52 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module defines the REST API endpoints for serving a template catalog.
+
+#[cfg(feature = "rest-api-actix")]
+mod actix;
+
+use crate::rest_api::{Resource, RestResourceProvider};
+
+use super::TemplateCatalog;
+
+/// The `TemplateCatalog` trait provides the following endpoints as REST API resources:
+///
+/// * `GET /admin/templates` - List the templates in the catalog
+/// * `GET /admin/templates/{name}` - Fetch the YAML definition of a template; the `version`
+///   query parameter selects the template version, otherwise the latest version is returned
+///
+/// The list of templates is a catalog index that may be used by a `RemoteTemplateCatalog`.
+///
+/// These endpoints are only available if the following REST API backend feature is enabled:
+///
+/// * `rest-api-actix`
+impl RestResourceProvider for dyn TemplateCatalog {
+    fn resources(&self) -> Vec<Resource> {
+        // Allowing unused_mut because resources must be mutable if feature rest-api-actix is
+        // enabled
+        #[allow(unused_mut)]
+        let mut resources = Vec::new();
+
+        #[cfg(feature = "rest-api-actix")]
+        {
+            resources.append(&mut vec![
+                actix::templates::make_templates_resource(self.clone_box()),
+                actix::templates_name::make_templates_name_resource(self.clone_box()),
+            ]);
+        }
+
+        resources
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A template catalog that combines several catalogs.
+
+use super::super::CircuitTemplateError;
+use super::{TemplateCatalog, TemplateEntry};
+
+/// A template catalog that combines several catalogs.
+///
+/// The templates of all catalogs are listed. If several catalogs provide the same version of a
+/// template, the catalog that was added first takes precedence. A catalog that can't be listed is
+/// logged and skipped, so that the templates of the other catalogs remain available.
+#[derive(Clone, Default)]
+pub struct UnifiedTemplateCatalog {
+    catalogs: Vec<Box<dyn TemplateCatalog>>,
+}
+
+impl UnifiedTemplateCatalog {
+    pub fn new() -> Self {
+        UnifiedTemplateCatalog::default()
+    }
+
+    /// Adds a catalog; templates of earlier catalogs take precedence over those of this catalog.
+    pub fn add_catalog(&mut self, catalog: Box<dyn TemplateCatalog>) {
+        self.catalogs.push(catalog);
+    }
+
+    pub fn with_catalog(mut self, catalog: Box<dyn TemplateCatalog>) -> Self {
+        self.add_catalog(catalog);
+        self
+    }
+}
+
+impl TemplateCatalog for UnifiedTemplateCatalog {
+    /// Lists the templates of every catalog, skipping catalogs that can't be listed.
+    ///
+    /// An error is only returned if every catalog failed to be listed.
+    fn list_templates(&self) -> Result<Vec<TemplateEntry>, CircuitTemplateError> {
+        let mut entries = vec![];
+        let mut errors = vec![];
+        for (index, catalog) in self.catalogs.iter().enumerate() {
+            match catalog.list_templates() {
+                Ok(catalog_entries) => {
+                    entries.extend(catalog_entries.into_iter().map(|mut entry| {
+                        entry.catalog_path.insert(0, index);
+                        entry
+                    }))
+                }
+                Err(err) => {
+                    warn!("Unable to list templates of catalog {}: {}", index, err);
+                    errors.push(err);
+                }
+            }
+        }
+
+        if !errors.is_empty() && errors.len() == self.catalogs.len() {
+            Err(errors.remove(0))
+        } else {
+            Ok(entries)
+        }
+    }
+
+    fn fetch_template_yaml(&self, entry: &TemplateEntry) -> Result<Vec<u8>, CircuitTemplateError> {
+        let mut entry = entry.clone();
+        if entry.catalog_path.is_empty() {
+            return Err(CircuitTemplateError::new(&format!(
+                "Template {} was not listed by this catalog",
+                entry.template_ref()
+            )));
+        }
+        let index = entry.catalog_path.remove(0);
+
+        self.catalogs
+            .get(index)
+            .ok_or_else(|| {
+                CircuitTemplateError::new(&format!(
+                    "Template {} is not part of this catalog",
+                    entry.template_ref()
+                ))
+            })?
+            .fetch_template_yaml(&entry)
+    }
+
+    fn clone_box(&self) -> Box<dyn TemplateCatalog> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Catalog that counts how often it is listed, and fails to be listed if it has no entries.
+    #[derive(Clone)]
+    struct CountingCatalog {
+        entries: Vec<TemplateEntry>,
+        list_count: Arc<AtomicUsize>,
+    }
+
+    impl TemplateCatalog for CountingCatalog {
+        fn list_templates(&self) -> Result<Vec<TemplateEntry>, CircuitTemplateError> {
+            self.list_count.fetch_add(1, Ordering::SeqCst);
+            if self.entries.is_empty() {
+                Err(CircuitTemplateError::new("catalog is unavailable"))
+            } else {
+                Ok(self.entries.clone())
+            }
+        }
+
+        fn fetch_template_yaml(
+            &self,
+            entry: &TemplateEntry,
+        ) -> Result<Vec<u8>, CircuitTemplateError> {
+            Ok(entry.location().cloned().unwrap_or_default().into_bytes())
+        }
+
+        fn clone_box(&self) -> Box<dyn TemplateCatalog> {
+            Box::new(self.clone())
+        }
+    }
+
+    /// Verify that a catalog that fails to be listed is skipped, that a template is fetched from
+    /// the catalog that listed it without listing the catalogs again, and that listing fails if
+    /// every catalog fails.
+    #[test]
+    fn test_unified_catalog() {
+        let list_count = Arc::new(AtomicUsize::new(0));
+        let catalog = |entries| {
+            Box::new(CountingCatalog {
+                entries,
+                list_count: list_count.clone(),
+            })
+        };
+
+        let unified = UnifiedTemplateCatalog::new()
+            .with_catalog(catalog(vec![]))
+            .with_catalog(catalog(vec![TemplateEntry::new(
+                "scabbard",
+                None,
+                None,
+                None,
+                Some("scabbard yaml".into()),
+            )]));
+
+        let entry = unified
+            .find_template("scabbard")
+            .expect("Failed to find template");
+        assert_eq!(list_count.load(Ordering::SeqCst), 2);
+
+        let nested = UnifiedTemplateCatalog::new().with_catalog(Box::new(unified.clone()));
+        let nested_entry = nested
+            .find_template("scabbard")
+            .expect("Failed to find template");
+        let listed = list_count.load(Ordering::SeqCst);
+
+        assert_eq!(
+            unified
+                .fetch_template_yaml(&entry)
+                .expect("Failed to fetch template"),
+            b"scabbard yaml".to_vec()
+        );
+        assert_eq!(
+            nested
+                .fetch_template_yaml(&nested_entry)
+                .expect("Failed to fetch template"),
+            b"scabbard yaml".to_vec()
+        );
+        assert_eq!(list_count.load(Ordering::SeqCst), listed);
+
+        assert!(UnifiedTemplateCatalog::new()
+            .with_catalog(catalog(vec![]))
+            .list_templates()
+            .is_err());
+    }
+}
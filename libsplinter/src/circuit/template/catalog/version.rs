@@ -0,0 +1,198 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Semantic versions of circuit templates.
+
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use super::super::CircuitTemplateError;
+
+/// The semantic version of a circuit template, in the form `major.minor.patch`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TemplateVersion {
+    major: u64,
+    minor: u64,
+    patch: u64,
+}
+
+impl TemplateVersion {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        TemplateVersion {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    pub fn major(&self) -> u64 {
+        self.major
+    }
+
+    pub fn minor(&self) -> u64 {
+        self.minor
+    }
+
+    pub fn patch(&self) -> u64 {
+        self.patch
+    }
+}
+
+impl Ord for TemplateVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch).cmp(&(other.major, other.minor, other.patch))
+    }
+}
+
+impl PartialOrd for TemplateVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for TemplateVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for TemplateVersion {
+    type Err = CircuitTemplateError;
+
+    /// Parses a version of the form `major.minor.patch`. Omitted components default to 0, so
+    /// `1.2` is parsed as `1.2.0`.
+    fn from_str(version: &str) -> Result<Self, Self::Err> {
+        let parts = parse_version_parts(version)?;
+        Ok(TemplateVersion {
+            major: parts[0],
+            minor: parts.get(1).copied().unwrap_or(0),
+            patch: parts.get(2).copied().unwrap_or(0),
+        })
+    }
+}
+
+impl TryFrom<String> for TemplateVersion {
+    type Error = CircuitTemplateError;
+
+    fn try_from(version: String) -> Result<Self, Self::Error> {
+        version.parse()
+    }
+}
+
+impl From<TemplateVersion> for String {
+    fn from(version: TemplateVersion) -> Self {
+        version.to_string()
+    }
+}
+
+/// A requirement on the version of a template, such as `1`, `1.2` or `1.2.3`.
+///
+/// A version matches the requirement if all components given by the requirement are equal, so
+/// `1.2` is matched by `1.2.0` and `1.2.7` but not by `1.3.0`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemplateVersionReq {
+    parts: Vec<u64>,
+}
+
+impl TemplateVersionReq {
+    /// Returns true if the version satisfies the requirement.
+    pub fn matches(&self, version: &TemplateVersion) -> bool {
+        let components = [version.major, version.minor, version.patch];
+        self.parts
+            .iter()
+            .zip(components.iter())
+            .all(|(required, actual)| required == actual)
+    }
+}
+
+impl fmt::Display for TemplateVersionReq {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parts = self
+            .parts
+            .iter()
+            .map(|part| part.to_string())
+            .collect::<Vec<_>>();
+        f.write_str(&parts.join("."))
+    }
+}
+
+impl FromStr for TemplateVersionReq {
+    type Err = CircuitTemplateError;
+
+    fn from_str(version: &str) -> Result<Self, Self::Err> {
+        Ok(TemplateVersionReq {
+            parts: parse_version_parts(version)?,
+        })
+    }
+}
+
+fn parse_version_parts(version: &str) -> Result<Vec<u64>, CircuitTemplateError> {
+    let parts = version
+        .split('.')
+        .map(|part| part.parse::<u64>())
+        .collect::<Result<Vec<u64>, _>>()
+        .map_err(|_| {
+            CircuitTemplateError::new(&format!(
+                "Invalid template version {}: must be of the form major.minor.patch",
+                version
+            ))
+        })?;
+
+    if parts.is_empty() || parts.len() > 3 {
+        return Err(CircuitTemplateError::new(&format!(
+            "Invalid template version {}: must be of the form major.minor.patch",
+            version
+        )));
+    }
+
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Verify that template versions are parsed with omitted components set to 0, that invalid
+    /// versions are rejected, and that versions are ordered by major, minor and patch number.
+    #[test]
+    fn test_parse_and_order_versions() {
+        let version: TemplateVersion = "1.2".parse().expect("Failed to parse version");
+        assert_eq!(version, TemplateVersion::new(1, 2, 0));
+        assert_eq!(version.to_string(), "1.2.0");
+
+        assert!("".parse::<TemplateVersion>().is_err());
+        assert!("1.2.3.4".parse::<TemplateVersion>().is_err());
+        assert!("1.x".parse::<TemplateVersion>().is_err());
+
+        assert!(TemplateVersion::new(1, 10, 0) > TemplateVersion::new(1, 9, 5));
+        assert!(TemplateVersion::new(2, 0, 0) > TemplateVersion::new(1, 10, 0));
+    }
+
+    /// Verify that a version requirement matches every version with the same leading components.
+    #[test]
+    fn test_version_req_matches() {
+        let req: TemplateVersionReq = "1.2".parse().expect("Failed to parse requirement");
+        assert!(req.matches(&TemplateVersion::new(1, 2, 0)));
+        assert!(req.matches(&TemplateVersion::new(1, 2, 7)));
+        assert!(!req.matches(&TemplateVersion::new(1, 3, 0)));
+        assert!(!req.matches(&TemplateVersion::new(2, 2, 0)));
+
+        let req: TemplateVersionReq = "1.2.3".parse().expect("Failed to parse requirement");
+        assert!(req.matches(&TemplateVersion::new(1, 2, 3)));
+        assert!(!req.matches(&TemplateVersion::new(1, 2, 4)));
+    }
+}
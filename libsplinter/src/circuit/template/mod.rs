@@ -15,11 +15,13 @@
 //! Data structures that manage and use templates to create circuit templates.
 //!
 //! The public interface includes the structs [`CircuitTemplateManager`], and
-//! [`CircuitCreateTemplate`].
+//! [`CircuitCreateTemplate`], and the [`catalog`] module of versioned template catalogs.
 //!
 //! [`CircuitTemplateManager`]: struct.CircuitTemplateManager.html
 //! [`CircuitCreateTemplate`]: struct.CircuitCreateTemplate.html
+//! [`catalog`]: catalog/index.html
 
+pub mod catalog;
 mod error;
 mod rules;
 mod yaml_parser;
@@ -32,9 +34,14 @@ pub use error::CircuitTemplateError;
 pub use rules::RuleArgument;
 use rules::Rules;
 
-use yaml_parser::{v1, CircuitTemplate};
+use yaml_parser::{v1, v2, CircuitTemplate};
 
-pub(self) use crate::admin::messages::{CreateCircuitBuilder, SplinterServiceBuilder};
+#[cfg(feature = "circuit-relay")]
+pub(self) use crate::admin::messages::SplinterNode;
+pub(self) use crate::admin::messages::{
+    AuthorizationType, CreateCircuitBuilder, DurabilityType, PersistenceType, RouteType,
+    SplinterServiceBuilder,
+};
 
 /// Default file location for circuit templates
 pub const DEFAULT_TEMPLATE_DIR: &str = "/usr/share/splinter/circuit-templates";
@@ -90,14 +97,16 @@ impl CircuitTemplateManager {
     pub fn load_raw_yaml(&self, name: &str) -> Result<String, CircuitTemplateError> {
         let path = format!("{}/{}.yaml", self.path, name);
         let template = CircuitTemplate::load_from_file(&path)?;
-        match template {
-            CircuitTemplate::V1(template) => serde_yaml::to_string(&template).map_err(|err| {
-                CircuitTemplateError::new_with_source(
-                    "Failed to load template to yaml string",
-                    Box::new(err),
-                )
-            }),
-        }
+        let yaml = match template {
+            CircuitTemplate::V1(template) => serde_yaml::to_string(&template),
+            CircuitTemplate::V2(template) => serde_yaml::to_string(&template),
+        };
+        yaml.map_err(|err| {
+            CircuitTemplateError::new_with_source(
+                "Failed to load template to yaml string",
+                Box::new(err),
+            )
+        })
     }
 
     /// Lists all available circuit templates found in the `path` of the `CircuitTemplateManager`.
@@ -149,10 +158,104 @@ impl CircuitCreateTemplate {
     /// # Arguments
     ///
     /// * `path` - Path of the circuit template file.
+    ///
+    /// If the template includes other templates, the included templates are loaded from the same
+    /// directory as the template file and their `arguments` and `rules` are merged into the
+    /// returned template.
     pub fn from_yaml_file(path: &str) -> Result<Self, CircuitTemplateError> {
-        let circuit_template = CircuitTemplate::load_from_file(path)?;
-        match circuit_template {
-            CircuitTemplate::V1(template) => Ok(Self::try_from(template)?),
+        let (key, data) = read_template_file(Path::new(path))?;
+        Self::load_with_includes(
+            &key,
+            &data,
+            &|including, name| {
+                let dir = Path::new(including)
+                    .parent()
+                    .unwrap_or_else(|| Path::new("."));
+                if name.ends_with(".yaml") {
+                    read_template_file(&dir.join(name))
+                } else {
+                    read_template_file(&dir.join(format!("{}.yaml", name)))
+                }
+            },
+            &mut vec![],
+        )
+    }
+
+    /// Loads the serialized template `data` and all of its included templates.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Unique identifier of the template, such as its canonical path.
+    /// * `data` - Serialized template.
+    /// * `resolve_include` - Returns the key and the serialized data of an included template,
+    ///   given the key of the including template and the name of the included template.
+    /// * `loading` - Keys of the templates that are currently being loaded, which is used to
+    ///   detect circular includes.
+    fn load_with_includes<F>(
+        key: &str,
+        data: &[u8],
+        resolve_include: &F,
+        loading: &mut Vec<String>,
+    ) -> Result<Self, CircuitTemplateError>
+    where
+        F: Fn(&str, &str) -> Result<(String, Vec<u8>), CircuitTemplateError>,
+    {
+        if loading.iter().any(|loading_key| loading_key == key) {
+            return Err(CircuitTemplateError::new(&format!(
+                "Circular template include detected: {}",
+                key
+            )));
+        }
+        loading.push(key.to_string());
+
+        let template = match CircuitTemplate::deserialize(data)? {
+            CircuitTemplate::V1(template) => Self::try_from(template)?,
+            CircuitTemplate::V2(template) => {
+                let mut included: Option<Self> = None;
+                for name in template.include() {
+                    let (include_key, include_data) = resolve_include(key, name)?;
+                    let include = Self::load_with_includes(
+                        &include_key,
+                        &include_data,
+                        resolve_include,
+                        loading,
+                    )?;
+                    included = Some(match included {
+                        Some(base) => base.merge(include),
+                        None => include,
+                    });
+                }
+
+                let template = Self::try_from(template)?;
+                match included {
+                    Some(base) => base.merge(template),
+                    None => template,
+                }
+            }
+        };
+
+        loading.pop();
+        Ok(template)
+    }
+
+    /// Merges two templates. The `arguments` and `rules` of `other` take precedence over those of
+    /// `self`.
+    fn merge(self, other: Self) -> Self {
+        let mut arguments = self.arguments;
+        for argument in other.arguments {
+            match arguments
+                .iter()
+                .position(|arg| arg.name() == argument.name())
+            {
+                Some(index) => arguments[index] = argument,
+                None => arguments.push(argument),
+            }
+        }
+
+        CircuitCreateTemplate {
+            version: other.version,
+            arguments,
+            rules: self.rules.merge(other.rules),
         }
     }
 
@@ -216,6 +319,29 @@ impl CircuitCreateTemplate {
     }
 }
 
+/// Reads a template file, returning its canonical path and its contents.
+fn read_template_file(path: &Path) -> Result<(String, Vec<u8>), CircuitTemplateError> {
+    if !path.is_file() {
+        return Err(CircuitTemplateError::new(&format!(
+            "File does not exist or is inaccessible: {}",
+            path.display()
+        )));
+    }
+
+    let canonical_path = path.canonicalize().map_err(|err| {
+        CircuitTemplateError::new_with_source(
+            &format!("Unable to resolve template path {}", path.display()),
+            Box::new(err),
+        )
+    })?;
+
+    let data = std::fs::read(&canonical_path).map_err(|err| {
+        CircuitTemplateError::new_with_source("Error reading data from template file", err.into())
+    })?;
+
+    Ok((canonical_path.to_string_lossy().into_owned(), data))
+}
+
 impl TryFrom<v1::CircuitCreateTemplate> for CircuitCreateTemplate {
     type Error = CircuitTemplateError;
     fn try_from(create_circuit_template: v1::CircuitCreateTemplate) -> Result<Self, Self::Error> {
@@ -232,6 +358,22 @@ impl TryFrom<v1::CircuitCreateTemplate> for CircuitCreateTemplate {
     }
 }
 
+impl TryFrom<v2::CircuitCreateTemplate> for CircuitCreateTemplate {
+    type Error = CircuitTemplateError;
+    fn try_from(create_circuit_template: v2::CircuitCreateTemplate) -> Result<Self, Self::Error> {
+        Ok(CircuitCreateTemplate {
+            version: create_circuit_template.version().to_string(),
+            arguments: create_circuit_template
+                .args()
+                .to_owned()
+                .into_iter()
+                .map(RuleArgument::try_from)
+                .collect::<Result<_, CircuitTemplateError>>()?,
+            rules: Rules::from(create_circuit_template.rules().clone()),
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -364,6 +506,175 @@ rules:
             .any(|(key, value)| key == "peer_services" && value == "[\"a000\"]"));
     }
 
+    /// Example version 2 circuit template YAML file, which includes `EXAMPLE_TEMPLATE_YAML`.
+    const EXAMPLE_TEMPLATE_V2_YAML: &[u8] = br##"version: v2
+include:
+    - example_template
+args:
+    - name: GAMEROOM_NAME
+      required: false
+      default: "default gameroom"
+    - name: COMMENTS
+      required: false
+rules:
+    set-authorization-type:
+        authorization-type: trust
+    set-route-type:
+        route-type: any
+    conditional:
+        - when:
+            argument: COMMENTS
+          rules:
+            set-comments:
+                comments: $(COMMENTS)
+        - when:
+            argument: GAMEROOM_NAME
+            equals: "default gameroom"
+          rules:
+            set-management-type:
+                management-type: "default-gameroom" "##;
+
+    /// Verifies the builder can be parsed from a template v2 that includes a template v1, and that
+    /// the included and conditional `rules` are applied.
+    ///
+    /// The test follows the procedure below:
+    /// 1. Writes the `EXAMPLE_TEMPLATE_YAML` and `EXAMPLE_TEMPLATE_V2_YAML` files to a temporary
+    ///    directory.
+    /// 2. Builds a `CircuitCreateTemplate` from the v2 file and verifies the arguments of both
+    ///    templates are available, with `GAMEROOM_NAME` overridden by the v2 template.
+    /// 3. Applies the template with the `COMMENTS` argument set and verifies the included rules,
+    ///    the v2 rules and the first conditional rule are applied, while the second conditional
+    ///    rule is not.
+    #[test]
+    fn test_builds_template_v2_with_include() {
+        let temp_dir = TempDir::new("test_builds_template_v2_with_include").unwrap();
+        let temp_dir = temp_dir.path().to_path_buf();
+        write_yaml_file(&get_file_path(temp_dir.clone()), EXAMPLE_TEMPLATE_YAML);
+        let mut file_path = temp_dir;
+        file_path.push("example_template_v2.yaml");
+        let file_path = file_path.to_str().unwrap().to_string();
+        write_yaml_file(&file_path, EXAMPLE_TEMPLATE_V2_YAML);
+
+        let mut template =
+            CircuitCreateTemplate::from_yaml_file(&file_path).expect("failed to parse template");
+
+        assert_eq!(template.version(), "v2");
+        assert_eq!(template.arguments().len(), 5);
+        let gameroom_name = template
+            .arguments()
+            .iter()
+            .find(|arg| arg.name() == "gameroom_name")
+            .expect("gameroom_name argument not found");
+        assert!(!gameroom_name.required());
+
+        template
+            .set_argument_value("nodes", "alpha-node-000,beta-node-000")
+            .expect("Error setting argument");
+        template
+            .set_argument_value("signer_pub_key", "signer_key")
+            .expect("Error setting argument");
+        template
+            .set_argument_value("gameroom_name", "my gameroom")
+            .expect("Error setting argument");
+        template
+            .set_argument_value("comments", "my comments")
+            .expect("Error setting argument");
+
+        let circuit_create_builder = template
+            .apply_to_builder(CreateCircuitBuilder::new())
+            .expect("Error getting builders from templates");
+
+        assert_eq!(
+            circuit_create_builder.circuit_management_type(),
+            Some("gameroom".to_string())
+        );
+        assert_eq!(
+            circuit_create_builder.authorization_type(),
+            Some(AuthorizationType::Trust)
+        );
+        assert_eq!(circuit_create_builder.routes(), Some(RouteType::Any));
+        assert_eq!(
+            circuit_create_builder.comments(),
+            Some("my comments".to_string())
+        );
+        assert_eq!(
+            circuit_create_builder
+                .roster()
+                .expect("Unable to get roster")
+                .len(),
+            2
+        );
+    }
+
+    /// Verifies that the `set-route-type` rule builds a relayed route from the relay node and
+    /// endpoint arguments, and that a relayed route without a relay node is rejected.
+    #[cfg(feature = "circuit-relay")]
+    #[test]
+    fn test_template_v2_relayed_route_type() {
+        let temp_dir = TempDir::new("test_template_v2_relayed_route_type").unwrap();
+        let file_path = get_file_path(temp_dir.path().to_path_buf());
+        write_yaml_file(
+            &file_path,
+            br##"version: v2
+args:
+    - name: RELAY_NODE
+      required: true
+    - name: RELAY_ENDPOINTS
+      required: true
+rules:
+    set-route-type:
+        route-type: relayed
+        relay-node: $(RELAY_NODE)
+        relay-endpoints: $(RELAY_ENDPOINTS)"##,
+        );
+
+        let mut template =
+            CircuitCreateTemplate::from_yaml_file(&file_path).expect("failed to parse template");
+        template
+            .set_argument_value("relay_node", "hub-node-000")
+            .expect("Error setting argument");
+        template
+            .set_argument_value("relay_endpoints", "tcps://hub:8044, tcps://hub-backup:8044")
+            .expect("Error setting argument");
+
+        let circuit_create_builder = template
+            .apply_to_builder(CreateCircuitBuilder::new())
+            .expect("Error getting builders from templates");
+        assert_eq!(
+            circuit_create_builder.routes(),
+            Some(RouteType::Relayed {
+                relay_node: SplinterNode {
+                    node_id: "hub-node-000".into(),
+                    endpoints: vec!["tcps://hub:8044".into(), "tcps://hub-backup:8044".into()],
+                }
+            })
+        );
+
+        write_yaml_file(
+            &file_path,
+            b"version: v2\nargs: []\nrules:\n    set-route-type:\n        route-type: relayed\n",
+        );
+        let template =
+            CircuitCreateTemplate::from_yaml_file(&file_path).expect("failed to parse template");
+        assert!(template
+            .apply_to_builder(CreateCircuitBuilder::new())
+            .is_err());
+    }
+
+    /// Verifies that a template which includes itself is rejected instead of being loaded
+    /// recursively.
+    #[test]
+    fn test_template_v2_circular_include() {
+        let temp_dir = TempDir::new("test_template_v2_circular_include").unwrap();
+        let file_path = get_file_path(temp_dir.path().to_path_buf());
+        write_yaml_file(
+            &file_path,
+            b"version: v2\ninclude:\n    - example_template\n",
+        );
+
+        assert!(CircuitCreateTemplate::from_yaml_file(&file_path).is_err());
+    }
+
     fn get_file_path(mut temp_dir: PathBuf) -> String {
         temp_dir.push("example_template.yaml");
         let path = temp_dir.to_str().unwrap().to_string();
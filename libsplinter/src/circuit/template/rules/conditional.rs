@@ -0,0 +1,173 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Provides functionality to apply a set of `rules` only when a condition on the circuit template
+//! arguments is met.
+
+use super::super::{yaml_parser::v2, CircuitTemplateError};
+use super::{get_optional_argument_value, RuleArgument, Rules};
+
+/// Data structure holding a set of `rules` and the condition under which they are applied.
+pub(super) struct ConditionalRule {
+    condition: Condition,
+    rules: Rules,
+}
+
+impl ConditionalRule {
+    /// Returns the `rules` to apply if the condition is met by the template arguments, otherwise
+    /// returns `None`.
+    pub fn apply_rule(
+        &self,
+        template_arguments: &[RuleArgument],
+    ) -> Result<Option<&Rules>, CircuitTemplateError> {
+        if self.condition.is_met(template_arguments)? {
+            Ok(Some(&self.rules))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+impl From<v2::ConditionalRule> for ConditionalRule {
+    fn from(yaml_conditional_rule: v2::ConditionalRule) -> Self {
+        ConditionalRule {
+            condition: Condition::from(yaml_conditional_rule.when().clone()),
+            rules: Rules::from(yaml_conditional_rule.rules().clone()),
+        }
+    }
+}
+
+/// Condition on the value of a circuit template argument.
+struct Condition {
+    argument: String,
+    equals: Option<String>,
+    not_equals: Option<String>,
+}
+
+impl Condition {
+    /// Evaluates the condition against the template arguments.
+    ///
+    /// If neither `equals` nor `not_equals` is set, the condition is met when the argument has a
+    /// non-empty value.
+    fn is_met(&self, template_arguments: &[RuleArgument]) -> Result<bool, CircuitTemplateError> {
+        let value = get_optional_argument_value(&self.argument, template_arguments)?;
+
+        if let Some(equals) = &self.equals {
+            if value.as_ref() != Some(equals) {
+                return Ok(false);
+            }
+        }
+
+        if let Some(not_equals) = &self.not_equals {
+            if value.as_ref() == Some(not_equals) {
+                return Ok(false);
+            }
+        }
+
+        if self.equals.is_none() && self.not_equals.is_none() {
+            return Ok(value.map(|value| !value.is_empty()).unwrap_or(false));
+        }
+
+        Ok(true)
+    }
+}
+
+impl From<v2::Condition> for Condition {
+    fn from(yaml_condition: v2::Condition) -> Self {
+        Condition {
+            argument: yaml_condition.argument().to_string(),
+            equals: yaml_condition.equals().cloned(),
+            not_equals: yaml_condition.not_equals().cloned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Verify that a `Condition` is evaluated correctly against the template arguments.
+    ///
+    /// 1. Verify an `equals` condition is met by a matching user value and not met by a
+    ///    different value.
+    /// 2. Verify a `not_equals` condition is met when the argument has no value.
+    /// 3. Verify a condition without `equals` or `not_equals` is met when the argument has a
+    ///    value, falling back to the default value, and not met when it has none.
+    /// 4. Verify a condition on an argument that is not defined by the template returns an error.
+    #[test]
+    fn test_condition_is_met() {
+        let template_arguments = make_rule_arguments();
+
+        let equals = make_condition("durable", Some("true"), None);
+        assert!(equals
+            .is_met(&template_arguments)
+            .expect("Failed to evaluate"));
+        let equals = make_condition("durable", Some("false"), None);
+        assert!(!equals
+            .is_met(&template_arguments)
+            .expect("Failed to evaluate"));
+
+        let not_equals = make_condition("comments", None, Some("none"));
+        assert!(not_equals
+            .is_met(&template_arguments)
+            .expect("Failed to evaluate"));
+
+        let is_set = make_condition("$(ROUTE)", None, None);
+        assert!(is_set
+            .is_met(&template_arguments)
+            .expect("Failed to evaluate"));
+        let is_set = make_condition("comments", None, None);
+        assert!(!is_set
+            .is_met(&template_arguments)
+            .expect("Failed to evaluate"));
+
+        let undefined = make_condition("undefined", None, None);
+        assert!(undefined.is_met(&template_arguments).is_err());
+    }
+
+    fn make_condition(argument: &str, equals: Option<&str>, not_equals: Option<&str>) -> Condition {
+        Condition {
+            argument: argument.to_string(),
+            equals: equals.map(String::from),
+            not_equals: not_equals.map(String::from),
+        }
+    }
+
+    fn make_rule_arguments() -> Vec<RuleArgument> {
+        let durable = RuleArgument {
+            name: "durable".to_string(),
+            required: false,
+            default_value: Some("false".to_string()),
+            description: None,
+            user_value: Some("true".to_string()),
+        };
+
+        let route = RuleArgument {
+            name: "route".to_string(),
+            required: false,
+            default_value: Some("any".to_string()),
+            description: None,
+            user_value: None,
+        };
+
+        let comments = RuleArgument {
+            name: "comments".to_string(),
+            required: false,
+            default_value: None,
+            description: None,
+            user_value: None,
+        };
+
+        vec![durable, route, comments]
+    }
+}
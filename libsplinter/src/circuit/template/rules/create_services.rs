@@ -17,7 +17,10 @@
 use crate::admin::messages::is_valid_service_id;
 use crate::base62::next_base62_string;
 
-use super::super::{yaml_parser::v1, CircuitTemplateError, SplinterServiceBuilder};
+use super::super::{
+    yaml_parser::{v1, v2},
+    CircuitTemplateError, SplinterServiceBuilder,
+};
 use super::{get_argument_value, is_arg_value, RuleArgument, Value};
 
 const ALL_OTHER_SERVICES: &str = "$(ALL_OTHER_SERVICES)";
@@ -29,6 +32,7 @@ pub(super) struct CreateServices {
     service_type: String,
     service_args: Vec<ServiceArgument>,
     first_service: String,
+    node_service_args: Vec<NodeServiceArguments>,
 }
 
 impl CreateServices {
@@ -73,35 +77,12 @@ impl CreateServices {
         let mut new_service_args = Vec::new();
         for arg in self.service_args.iter() {
             match &arg.value {
-                Value::Single(value) => {
-                    if arg.key == PEER_SERVICES_ARG && value == ALL_OTHER_SERVICES {
-                        service_builders = all_services(service_builders)?;
-                    } else {
-                        let value = if is_arg_value(&value) {
-                            get_argument_value(&value, template_arguments)?
-                        } else {
-                            value.clone()
-                        };
-                        new_service_args.push((arg.key.clone(), value));
-                    }
-                }
-                Value::List(values) => {
-                    let vals = values
-                        .iter()
-                        .try_fold::<_, _, Result<_, CircuitTemplateError>>(
-                            Vec::new(),
-                            |mut acc, value| {
-                                let value = if is_arg_value(&value) {
-                                    get_argument_value(&value, template_arguments)?
-                                } else {
-                                    value.to_string()
-                                };
-                                acc.push(format!("\"{}\"", value));
-                                Ok(acc)
-                            },
-                        )?;
-                    new_service_args.push((arg.key.clone(), format!("[{}]", vals.join(","))));
+                Value::Single(value)
+                    if arg.key == PEER_SERVICES_ARG && value == ALL_OTHER_SERVICES =>
+                {
+                    service_builders = all_services(service_builders)?;
                 }
+                _ => new_service_args.push(arg.resolve(template_arguments)?),
             }
         }
 
@@ -114,6 +95,34 @@ impl CreateServices {
             })
             .collect::<Vec<SplinterServiceBuilder>>();
 
+        for node_service_args in self.node_service_args.iter() {
+            let node = if is_arg_value(&node_service_args.node) {
+                get_argument_value(&node_service_args.node, template_arguments)?
+            } else {
+                node_service_args.node.clone()
+            };
+
+            let node_args = node_service_args
+                .service_args
+                .iter()
+                .map(|arg| arg.resolve(template_arguments))
+                .collect::<Result<Vec<(String, String)>, CircuitTemplateError>>()?;
+
+            let builder = service_builders
+                .iter_mut()
+                .find(|builder| builder.allowed_nodes() == Some(vec![node.clone()]))
+                .ok_or_else(|| {
+                    CircuitTemplateError::new(&format!(
+                        "Service arguments were set for node {}, which is not a circuit member",
+                        node
+                    ))
+                })?;
+
+            let mut service_args = builder.arguments().unwrap_or_default();
+            service_args.extend(node_args);
+            *builder = builder.clone().with_arguments(&service_args);
+        }
+
         Ok(service_builders)
     }
 }
@@ -124,6 +133,50 @@ struct ServiceArgument {
     value: Value,
 }
 
+impl ServiceArgument {
+    /// Returns the key and value of the service argument, replacing any template arguments with
+    /// their values.
+    fn resolve(
+        &self,
+        template_arguments: &[RuleArgument],
+    ) -> Result<(String, String), CircuitTemplateError> {
+        match &self.value {
+            Value::Single(value) => {
+                let value = if is_arg_value(value) {
+                    get_argument_value(value, template_arguments)?
+                } else {
+                    value.clone()
+                };
+                Ok((self.key.clone(), value))
+            }
+            Value::List(values) => {
+                let vals = values
+                    .iter()
+                    .try_fold::<_, _, Result<_, CircuitTemplateError>>(
+                        Vec::new(),
+                        |mut acc, value| {
+                            let value = if is_arg_value(value) {
+                                get_argument_value(value, template_arguments)?
+                            } else {
+                                value.to_string()
+                            };
+                            acc.push(format!("\"{}\"", value));
+                            Ok(acc)
+                        },
+                    )?;
+                Ok((self.key.clone(), format!("[{}]", vals.join(","))))
+            }
+        }
+    }
+}
+
+/// Data structure holding the service arguments that only apply to the service of a single node.
+#[derive(Debug)]
+struct NodeServiceArguments {
+    node: String,
+    service_args: Vec<ServiceArgument>,
+}
+
 impl From<v1::CreateServices> for CreateServices {
     fn from(yaml_create_services: v1::CreateServices) -> Self {
         CreateServices {
@@ -135,6 +188,42 @@ impl From<v1::CreateServices> for CreateServices {
                 .map(ServiceArgument::from)
                 .collect(),
             first_service: yaml_create_services.first_service().to_string(),
+            node_service_args: vec![],
+        }
+    }
+}
+
+impl From<v2::CreateServices> for CreateServices {
+    fn from(yaml_create_services: v2::CreateServices) -> Self {
+        CreateServices {
+            service_type: yaml_create_services.service_type().to_string(),
+            service_args: yaml_create_services
+                .service_args()
+                .to_owned()
+                .into_iter()
+                .map(ServiceArgument::from)
+                .collect(),
+            first_service: yaml_create_services.first_service().to_string(),
+            node_service_args: yaml_create_services
+                .node_service_args()
+                .to_owned()
+                .into_iter()
+                .map(NodeServiceArguments::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<v2::NodeServiceArguments> for NodeServiceArguments {
+    fn from(yaml_node_service_args: v2::NodeServiceArguments) -> Self {
+        NodeServiceArguments {
+            node: yaml_node_service_args.node().to_string(),
+            service_args: yaml_node_service_args
+                .service_args()
+                .to_owned()
+                .into_iter()
+                .map(ServiceArgument::from)
+                .collect(),
         }
     }
 }
@@ -300,6 +389,54 @@ mod test {
         assert!(invalid_char.apply_rule(&template_arguments).is_err());
     }
 
+    /// Verify that the `node_service_args` of a `CreateServices` object are only added to the
+    /// service of the matching node, and that an error is returned when the node is not part of
+    /// the circuit.
+    ///
+    /// The test follows the procedure below:
+    /// 1. Generate a `CreateServices` object with a node-specific argument for the node provided
+    ///    by the `signer_node` argument.
+    /// 2. Use the `apply_rule` method and verify only the beta node's service has the argument.
+    /// 3. Set the node-specific argument for an unknown node and verify `apply_rule` fails.
+    #[test]
+    fn test_create_service_apply_rules_node_service_args() {
+        let mut template_arguments = make_rule_arguments();
+        template_arguments.push(RuleArgument {
+            name: "signer_node".to_string(),
+            required: true,
+            default_value: None,
+            description: None,
+            user_value: Some("beta-node-000".to_string()),
+        });
+
+        let mut create_services = make_create_service();
+        create_services.node_service_args = vec![NodeServiceArguments {
+            node: "$(SIGNER_NODE)".to_string(),
+            service_args: vec![ServiceArgument {
+                key: "signer".to_string(),
+                value: Value::Single("true".to_string()),
+            }],
+        }];
+
+        let service_builders = create_services
+            .apply_rule(&template_arguments)
+            .expect("Failed to apply rules");
+
+        let alpha_args = service_builders[0]
+            .arguments()
+            .expect("Services args were not set");
+        assert!(!alpha_args.iter().any(|(key, _)| key == "signer"));
+
+        let beta_args = service_builders[1]
+            .arguments()
+            .expect("Services args were not set");
+        assert_eq!(beta_args.len(), 3);
+        assert_eq!(beta_args[2], ("signer".to_string(), "true".to_string()));
+
+        create_services.node_service_args[0].node = "gamma-node-000".to_string();
+        assert!(create_services.apply_rule(&template_arguments).is_err());
+    }
+
     fn make_create_service() -> CreateServices {
         let peer_services_arg = ServiceArgument {
             key: PEER_SERVICES_ARG.to_string(),
@@ -314,6 +451,7 @@ mod test {
             service_type: "scabbard".to_string(),
             service_args: vec![peer_services_arg, admin_keys_arg],
             first_service: "a000".to_string(),
+            node_service_args: vec![],
         }
     }
 
@@ -16,17 +16,32 @@
 //! from a circuit template. Also provides the general functionality to apply the circuit template
 //! `rules`.
 
+mod conditional;
 mod create_services;
+mod set_authorization_type;
+mod set_comments;
+mod set_durability_type;
 mod set_management_type;
 mod set_metadata;
+mod set_persistence_type;
+mod set_route_type;
 
 use std::convert::TryFrom;
 
-use super::{yaml_parser::v1, CircuitTemplateError, CreateCircuitBuilder};
+use super::{
+    yaml_parser::{v1, v2},
+    CircuitTemplateError, CreateCircuitBuilder,
+};
 
+use conditional::ConditionalRule;
 use create_services::CreateServices;
+use set_authorization_type::SetAuthorizationType;
+use set_comments::SetComments;
+use set_durability_type::SetDurabilityType;
 use set_management_type::CircuitManagement;
 use set_metadata::SetMetadata;
+use set_persistence_type::SetPersistenceType;
+use set_route_type::SetRouteType;
 
 /// Available `rules` used to create the value for entries of a builder, based on the circuit
 /// template arguments that have been set.
@@ -34,11 +49,20 @@ pub struct Rules {
     set_management_type: Option<CircuitManagement>,
     create_services: Option<CreateServices>,
     set_metadata: Option<SetMetadata>,
+    set_authorization_type: Option<SetAuthorizationType>,
+    set_persistence_type: Option<SetPersistenceType>,
+    set_durability_type: Option<SetDurabilityType>,
+    set_route_type: Option<SetRouteType>,
+    set_comments: Option<SetComments>,
+    conditional: Vec<ConditionalRule>,
 }
 
 impl Rules {
     /// Applies all available `Rules` for the circuit template. This updates all builders,
     /// including the `SplinterServiceBuilder` objects and `CreateCircuitBuilder`.
+    ///
+    /// Conditional rules are applied last, in the order they are defined, so they may override
+    /// the values set by the unconditional rules.
     pub fn apply_rules(
         &self,
         mut circuit_builder: CreateCircuitBuilder,
@@ -71,8 +95,58 @@ impl Rules {
                 .with_application_metadata(&set_metadata.apply_rule(template_arguments)?);
         }
 
+        if let Some(set_authorization_type) = &self.set_authorization_type {
+            circuit_builder = circuit_builder
+                .with_authorization_type(&set_authorization_type.apply_rule(template_arguments)?);
+        }
+
+        if let Some(set_persistence_type) = &self.set_persistence_type {
+            circuit_builder = circuit_builder
+                .with_persistence(&set_persistence_type.apply_rule(template_arguments)?);
+        }
+
+        if let Some(set_durability_type) = &self.set_durability_type {
+            circuit_builder = circuit_builder
+                .with_durability(&set_durability_type.apply_rule(template_arguments)?);
+        }
+
+        if let Some(set_route_type) = &self.set_route_type {
+            circuit_builder =
+                circuit_builder.with_routes(&set_route_type.apply_rule(template_arguments)?);
+        }
+
+        if let Some(set_comments) = &self.set_comments {
+            circuit_builder =
+                circuit_builder.with_comments(&set_comments.apply_rule(template_arguments)?);
+        }
+
+        for conditional in self.conditional.iter() {
+            if let Some(rules) = conditional.apply_rule(template_arguments)? {
+                circuit_builder = rules.apply_rules(circuit_builder, template_arguments)?;
+            }
+        }
+
         Ok(circuit_builder)
     }
+
+    /// Merges two sets of `Rules`. Rules set in `other` take precedence over the rules set in
+    /// `self`, and the conditional rules of `other` are applied after those of `self`.
+    pub(super) fn merge(self, other: Rules) -> Rules {
+        let mut conditional = self.conditional;
+        conditional.extend(other.conditional);
+
+        Rules {
+            set_management_type: other.set_management_type.or(self.set_management_type),
+            create_services: other.create_services.or(self.create_services),
+            set_metadata: other.set_metadata.or(self.set_metadata),
+            set_authorization_type: other.set_authorization_type.or(self.set_authorization_type),
+            set_persistence_type: other.set_persistence_type.or(self.set_persistence_type),
+            set_durability_type: other.set_durability_type.or(self.set_durability_type),
+            set_route_type: other.set_route_type.or(self.set_route_type),
+            set_comments: other.set_comments.or(self.set_comments),
+            conditional,
+        }
+    }
 }
 
 impl From<v1::Rules> for Rules {
@@ -87,6 +161,49 @@ impl From<v1::Rules> for Rules {
             set_metadata: rules
                 .set_metadata()
                 .map(|val| SetMetadata::from(val.clone())),
+            set_authorization_type: None,
+            set_persistence_type: None,
+            set_durability_type: None,
+            set_route_type: None,
+            set_comments: None,
+            conditional: vec![],
+        }
+    }
+}
+
+impl From<v2::Rules> for Rules {
+    fn from(rules: v2::Rules) -> Self {
+        Rules {
+            set_management_type: rules
+                .set_management_type()
+                .map(|val| CircuitManagement::from(val.clone())),
+            create_services: rules
+                .create_services()
+                .map(|val| CreateServices::from(val.clone())),
+            set_metadata: rules
+                .set_metadata()
+                .map(|val| SetMetadata::from(val.clone())),
+            set_authorization_type: rules
+                .set_authorization_type()
+                .map(|val| SetAuthorizationType::from(val.clone())),
+            set_persistence_type: rules
+                .set_persistence_type()
+                .map(|val| SetPersistenceType::from(val.clone())),
+            set_durability_type: rules
+                .set_durability_type()
+                .map(|val| SetDurabilityType::from(val.clone())),
+            set_route_type: rules
+                .set_route_type()
+                .map(|val| SetRouteType::from(val.clone())),
+            set_comments: rules
+                .set_comments()
+                .map(|val| SetComments::from(val.clone())),
+            conditional: rules
+                .conditional()
+                .iter()
+                .cloned()
+                .map(ConditionalRule::from)
+                .collect(),
         }
     }
 }
@@ -210,3 +327,26 @@ fn get_argument_value(
 
     Ok(value)
 }
+
+/// Returns the value of an argument, or `None` if the argument has neither a user value nor a
+/// default value. Returns an error if the argument is not defined by the template.
+fn get_optional_argument_value(
+    key: &str,
+    template_arguments: &[RuleArgument],
+) -> Result<Option<String>, CircuitTemplateError> {
+    let key = strip_arg_marker(key);
+    match template_arguments.iter().find(|arg| arg.name == key) {
+        Some(arg) => match (arg.user_value(), arg.default_value()) {
+            (Some(val), _) => Ok(Some(val.to_string())),
+            (None, Some(default_value)) if is_arg_value(default_value) => {
+                get_optional_argument_value(default_value, template_arguments)
+            }
+            (None, Some(default_value)) => Ok(Some(default_value.to_string())),
+            (None, None) => Ok(None),
+        },
+        None => Err(CircuitTemplateError::new(&format!(
+            "Invalid template. Argument \"{}\" was expected but not provided",
+            key
+        ))),
+    }
+}
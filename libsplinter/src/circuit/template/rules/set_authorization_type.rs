@@ -0,0 +1,52 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Provides functionality to set a `CreateCircuitBuilder` `authorization_type`.
+
+use super::super::{yaml_parser::v2, AuthorizationType, CircuitTemplateError};
+use super::{get_argument_value, is_arg_value, RuleArgument};
+
+/// Data structure holding the circuit's intended `authorization_type`.
+pub(super) struct SetAuthorizationType {
+    authorization_type: String,
+}
+
+impl SetAuthorizationType {
+    /// Returns the `AuthorizationType` for the `CreateCircuitBuilder`.
+    pub fn apply_rule(
+        &self,
+        template_arguments: &[RuleArgument],
+    ) -> Result<AuthorizationType, CircuitTemplateError> {
+        let authorization_type = if is_arg_value(&self.authorization_type) {
+            get_argument_value(&self.authorization_type, template_arguments)?
+        } else {
+            self.authorization_type.clone()
+        };
+
+        match authorization_type.to_lowercase().as_ref() {
+            "trust" => Ok(AuthorizationType::Trust),
+            _ => Err(CircuitTemplateError::new(&format!(
+                "Invalid authorization type: {}. The supported types are: trust",
+                authorization_type
+            ))),
+        }
+    }
+}
+
+impl From<v2::SetAuthorizationType> for SetAuthorizationType {
+    fn from(yaml_authorization_type: v2::SetAuthorizationType) -> Self {
+        SetAuthorizationType {
+            authorization_type: yaml_authorization_type.authorization_type().to_string(),
+        }
+    }
+}
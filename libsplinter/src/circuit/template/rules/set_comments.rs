@@ -0,0 +1,44 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Provides functionality to set the `comments` field of a `CreateCircuitBuilder`.
+
+use super::super::{yaml_parser::v2, CircuitTemplateError};
+use super::{get_argument_value, is_arg_value, RuleArgument};
+
+/// Data structure holding the circuit's intended `comments`.
+pub(super) struct SetComments {
+    comments: String,
+}
+
+impl SetComments {
+    /// Returns the `comments` for the `CreateCircuitBuilder`.
+    pub fn apply_rule(
+        &self,
+        template_arguments: &[RuleArgument],
+    ) -> Result<String, CircuitTemplateError> {
+        if is_arg_value(&self.comments) {
+            get_argument_value(&self.comments, template_arguments)
+        } else {
+            Ok(self.comments.clone())
+        }
+    }
+}
+
+impl From<v2::SetComments> for SetComments {
+    fn from(yaml_comments: v2::SetComments) -> Self {
+        SetComments {
+            comments: yaml_comments.comments().to_string(),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Provides functionality to set a `CreateCircuitBuilder` `durability`.
+
+use super::super::{yaml_parser::v2, CircuitTemplateError, DurabilityType};
+use super::{get_argument_value, is_arg_value, RuleArgument};
+
+/// Data structure holding the circuit's intended `durability` type.
+pub(super) struct SetDurabilityType {
+    durability_type: String,
+}
+
+impl SetDurabilityType {
+    /// Returns the `DurabilityType` for the `CreateCircuitBuilder`.
+    pub fn apply_rule(
+        &self,
+        template_arguments: &[RuleArgument],
+    ) -> Result<DurabilityType, CircuitTemplateError> {
+        let durability_type = if is_arg_value(&self.durability_type) {
+            get_argument_value(&self.durability_type, template_arguments)?
+        } else {
+            self.durability_type.clone()
+        };
+
+        match durability_type.to_lowercase().as_ref() {
+            "no-durability" => Ok(DurabilityType::NoDurability),
+            _ => Err(CircuitTemplateError::new(&format!(
+                "Invalid durability type: {}. The supported types are: no-durability",
+                durability_type
+            ))),
+        }
+    }
+}
+
+impl From<v2::SetDurabilityType> for SetDurabilityType {
+    fn from(yaml_durability_type: v2::SetDurabilityType) -> Self {
+        SetDurabilityType {
+            durability_type: yaml_durability_type.durability_type().to_string(),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Provides functionality to set a `CreateCircuitBuilder` `persistence`.
+
+use super::super::{yaml_parser::v2, CircuitTemplateError, PersistenceType};
+use super::{get_argument_value, is_arg_value, RuleArgument};
+
+/// Data structure holding the circuit's intended `persistence` type.
+pub(super) struct SetPersistenceType {
+    persistence_type: String,
+}
+
+impl SetPersistenceType {
+    /// Returns the `PersistenceType` for the `CreateCircuitBuilder`.
+    pub fn apply_rule(
+        &self,
+        template_arguments: &[RuleArgument],
+    ) -> Result<PersistenceType, CircuitTemplateError> {
+        let persistence_type = if is_arg_value(&self.persistence_type) {
+            get_argument_value(&self.persistence_type, template_arguments)?
+        } else {
+            self.persistence_type.clone()
+        };
+
+        match persistence_type.to_lowercase().as_ref() {
+            "any" => Ok(PersistenceType::Any),
+            _ => Err(CircuitTemplateError::new(&format!(
+                "Invalid persistence type: {}. The supported types are: any",
+                persistence_type
+            ))),
+        }
+    }
+}
+
+impl From<v2::SetPersistenceType> for SetPersistenceType {
+    fn from(yaml_persistence_type: v2::SetPersistenceType) -> Self {
+        SetPersistenceType {
+            persistence_type: yaml_persistence_type.persistence_type().to_string(),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Provides functionality to set a `CreateCircuitBuilder` `routes`.
+
+#[cfg(feature = "circuit-relay")]
+use super::super::SplinterNode;
+use super::super::{yaml_parser::v2, CircuitTemplateError, RouteType};
+use super::{get_argument_value, is_arg_value, RuleArgument};
+
+/// Data structure holding the circuit's intended `routes` type.
+pub(super) struct SetRouteType {
+    route_type: String,
+    relay_node: Option<String>,
+    relay_endpoints: Option<String>,
+}
+
+impl SetRouteType {
+    /// Returns the `RouteType` for the `CreateCircuitBuilder`.
+    pub fn apply_rule(
+        &self,
+        template_arguments: &[RuleArgument],
+    ) -> Result<RouteType, CircuitTemplateError> {
+        let route_type = resolve_value(&self.route_type, template_arguments)?;
+
+        match route_type.to_lowercase().as_ref() {
+            "any" => Ok(RouteType::Any),
+            #[cfg(feature = "circuit-relay")]
+            "relayed" => {
+                let node_id = match &self.relay_node {
+                    Some(relay_node) => resolve_value(relay_node, template_arguments)?,
+                    None => {
+                        return Err(CircuitTemplateError::new(
+                            "The relayed route type requires a relay-node",
+                        ))
+                    }
+                };
+                if node_id.is_empty() {
+                    return Err(CircuitTemplateError::new(
+                        "The relayed route type requires a relay-node",
+                    ));
+                }
+                let endpoints = match &self.relay_endpoints {
+                    Some(relay_endpoints) => resolve_value(relay_endpoints, template_arguments)?
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|endpoint| !endpoint.is_empty())
+                        .map(String::from)
+                        .collect(),
+                    None => vec![],
+                };
+                Ok(RouteType::Relayed {
+                    relay_node: SplinterNode { node_id, endpoints },
+                })
+            }
+            _ => Err(CircuitTemplateError::new(&format!(
+                "Invalid route type: {}. The supported types are: {}",
+                route_type, SUPPORTED_ROUTE_TYPES
+            ))),
+        }
+    }
+}
+
+#[cfg(feature = "circuit-relay")]
+const SUPPORTED_ROUTE_TYPES: &str = "any, relayed";
+#[cfg(not(feature = "circuit-relay"))]
+const SUPPORTED_ROUTE_TYPES: &str = "any";
+
+fn resolve_value(
+    value: &str,
+    template_arguments: &[RuleArgument],
+) -> Result<String, CircuitTemplateError> {
+    if is_arg_value(value) {
+        get_argument_value(value, template_arguments)
+    } else {
+        Ok(value.to_string())
+    }
+}
+
+impl From<v2::SetRouteType> for SetRouteType {
+    fn from(yaml_route_type: v2::SetRouteType) -> Self {
+        SetRouteType {
+            route_type: yaml_route_type.route_type().to_string(),
+            relay_node: yaml_route_type.relay_node().map(String::from),
+            relay_endpoints: yaml_route_type.relay_endpoints().map(String::from),
+        }
+    }
+}
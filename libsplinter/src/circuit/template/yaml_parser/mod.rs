@@ -17,6 +17,7 @@
 //! template object is using a compatible circuit definition.
 
 pub mod v1;
+pub mod v2;
 
 use std::fs::File;
 use std::io::Read;
@@ -30,12 +31,41 @@ struct TemplateVersionGuard {
     version: String,
 }
 
+/// Catalog information about a template.
+///
+/// Only version 2 templates define these fields; for version 1 templates all fields are `None`.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct TemplateMetadata {
+    pub name: Option<String>,
+    pub template_version: Option<String>,
+    pub description: Option<String>,
+    pub deprecated: Option<String>,
+}
+
+impl TemplateMetadata {
+    /// Reads the catalog information from serialized template bytes.
+    pub fn deserialize(data: &[u8]) -> Result<Self, CircuitTemplateError> {
+        let version_guard: TemplateVersionGuard = serde_yaml::from_slice(data)?;
+        match version_guard.version.as_ref() {
+            "v1" => Ok(Self::default()),
+            "v2" => Ok(serde_yaml::from_slice(data)?),
+            _ => Err(CircuitTemplateError::new(&format!(
+                "Invalid template version: {}. The supported versions are: v1, v2",
+                version_guard.version
+            ))),
+        }
+    }
+}
+
 /// Enum of the version options currently implemented. Each variant holds the circuit templates
 /// implemented for the corresponding circuit version.
 #[derive(Deserialize, Debug)]
 pub enum CircuitTemplate {
     /// Circuit version 1.0 used for a `CircuitCreateTemplate`.
     V1(v1::CircuitCreateTemplate),
+    /// Circuit version 2.0 used for a `CircuitCreateTemplate`.
+    V2(v2::CircuitCreateTemplate),
 }
 
 impl CircuitTemplate {
@@ -61,7 +91,7 @@ impl CircuitTemplate {
     }
 
     /// Creates a `CircuitTemplate` from serialized bytes.
-    fn deserialize(mut reader: impl Read) -> Result<Self, CircuitTemplateError> {
+    pub fn deserialize(mut reader: impl Read) -> Result<Self, CircuitTemplateError> {
         let mut data = Vec::new();
         reader.read_to_end(&mut data).map_err(|err| {
             CircuitTemplateError::new_with_source(
@@ -76,8 +106,12 @@ impl CircuitTemplate {
                 let template: v1::CircuitCreateTemplate = serde_yaml::from_slice(&data)?;
                 Ok(Self::V1(template))
             }
+            "v2" => {
+                let template: v2::CircuitCreateTemplate = serde_yaml::from_slice(&data)?;
+                Ok(Self::V2(template))
+            }
             _ => Err(CircuitTemplateError::new(&format!(
-                "Invalid template version: {}. The supported versions are: v1",
+                "Invalid template version: {}. The supported versions are: v1, v2",
                 version_guard.version
             ))),
         }
@@ -119,6 +153,36 @@ rules:
             - key: "alias"
               value: "$(GAMEROOM-NAME)" "##;
 
+    /// Example version 2 circuit template YAML file.
+    const EXAMPLE_TEMPLATE_V2_YAML: &[u8] = br##"version: v2
+include:
+    - scabbard
+args:
+    - name: durable
+      required: false
+      default: "false"
+rules:
+    set-authorization-type:
+        authorization-type: trust
+    set-comments:
+        comments: $(COMMENTS)
+    create-services:
+        service-type: 'scabbard'
+        service-args: []
+        first-service: 'a000'
+        node-service-args:
+            - node: alpha-node-000
+              service-args:
+                - key: 'version'
+                  value: '2'
+    conditional:
+        - when:
+            argument: DURABLE
+            equals: "false"
+          rules:
+            set-durability-type:
+                durability-type: no-durability "##;
+
     /// Verifies load_template correctly loads a `CircuitTemplate` with version 1.
     ///
     /// The test follows the procedure below:
@@ -189,9 +253,78 @@ rules:
                     }
                 }
             }
+            CircuitTemplate::V2(_) => panic!("Expected a v1 template"),
         }
     }
 
+    /// Verifies load_template correctly loads a `CircuitTemplate` with version 2.
+    ///
+    /// The test follows the procedure below:
+    /// 1. Sets up a temporary directory, to write a circuit template YAML file from the
+    ///    `EXAMPLE_TEMPLATE_V2_YAML`.
+    /// 2. Uses the `load_from_file` method to load a `v2` `CircuitTemplate`.
+    /// 3. Asserts the values of the `include` list, the rules added in version 2 and the
+    ///    conditional rules.
+    #[test]
+    fn test_parse_template_v2() {
+        let temp_dir = TempDir::new("test_parse_template_v2").unwrap();
+        let temp_dir = temp_dir.path().to_path_buf();
+        let file_path = get_file_path(temp_dir);
+
+        write_yaml_file(&file_path, EXAMPLE_TEMPLATE_V2_YAML);
+
+        let template_version =
+            CircuitTemplate::load_from_file(&file_path).expect("failed to load template");
+        let template = match template_version {
+            CircuitTemplate::V2(template) => template,
+            CircuitTemplate::V1(_) => panic!("Expected a v2 template"),
+        };
+
+        assert_eq!(template.version(), "v2");
+        assert_eq!(template.include(), &["scabbard".to_string()]);
+        assert_eq!(template.args().len(), 1);
+
+        let rules = template.rules();
+        assert_eq!(
+            rules
+                .set_authorization_type()
+                .expect("Authorization type was not deserialized correctly")
+                .authorization_type(),
+            "trust"
+        );
+        assert_eq!(
+            rules
+                .set_comments()
+                .expect("Comments were not deserialized correctly")
+                .comments(),
+            "$(COMMENTS)"
+        );
+
+        let create_services = rules
+            .create_services()
+            .expect("Did not parse create_services rule");
+        let node_service_args = create_services.node_service_args();
+        assert_eq!(node_service_args.len(), 1);
+        assert_eq!(node_service_args[0].node(), "alpha-node-000");
+        assert!(node_service_args[0]
+            .service_args()
+            .iter()
+            .any(|arg| arg.key() == "version" && arg.value() == &Value::Single("2".to_string())));
+
+        assert_eq!(rules.conditional().len(), 1);
+        let conditional = &rules.conditional()[0];
+        assert_eq!(conditional.when().argument(), "DURABLE");
+        assert_eq!(conditional.when().equals(), Some(&"false".to_string()));
+        assert_eq!(
+            conditional
+                .rules()
+                .set_durability_type()
+                .expect("Durability type was not deserialized correctly")
+                .durability_type(),
+            "no-durability"
+        );
+    }
+
     fn get_file_path(mut temp_dir: PathBuf) -> String {
         temp_dir.push("example_template.yaml");
         let path = temp_dir.to_str().unwrap().to_string();
@@ -0,0 +1,336 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Defines the version 2 `CircuitCreateTemplate`.
+//!
+//! Version 2 is a superset of version 1. It adds rules for the remaining `CreateCircuitBuilder`
+//! fields, per-node service arguments, conditional rules and the ability to include other
+//! templates.
+
+pub use super::v1::{CircuitManagement, RuleArgument, ServiceArgument, SetMetadata};
+
+/// Struct to hold the necessary `rules` and `args` required to create a `CreateCircuitBuilder`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct CircuitCreateTemplate {
+    /// Version of the circuit definition.
+    version: String,
+    /// Name of the template in a template catalog. If not set, the file name is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    /// Semantic version of the template itself, used to pin a template version in a catalog.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    template_version: Option<String>,
+    /// Optional description of the template.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// Deprecation notice, if the template version should no longer be used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deprecated: Option<String>,
+    /// Names of the templates whose `args` and `rules` are included in this template. Included
+    /// templates are applied in order, and this template's own values take precedence.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    include: Vec<String>,
+    /// Required data to fill out the circuit template.
+    #[serde(default)]
+    args: Vec<RuleArgument>,
+    /// Automated process to define more complex entries of the `CreateCircuitBuilder`.
+    #[serde(default)]
+    rules: Rules,
+}
+
+impl CircuitCreateTemplate {
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    pub fn template_version(&self) -> Option<&String> {
+        self.template_version.as_ref()
+    }
+
+    pub fn description(&self) -> Option<&String> {
+        self.description.as_ref()
+    }
+
+    pub fn deprecated(&self) -> Option<&String> {
+        self.deprecated.as_ref()
+    }
+
+    pub fn include(&self) -> &[String] {
+        &self.include
+    }
+
+    pub fn args(&self) -> &[RuleArgument] {
+        &self.args
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+}
+
+/// Struct to hold the defined `rules`, which are automated processes to define entries of the
+/// `CreateCircuitBuilder` based on the `args` values.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct Rules {
+    /// Process for defining the `circuit_management_type` of a circuit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    set_management_type: Option<CircuitManagement>,
+    /// Process for defining the services of a circuit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    create_services: Option<CreateServices>,
+    /// Process for defining the `metadata` field of a circuit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    set_metadata: Option<SetMetadata>,
+    /// Process for defining the `authorization_type` of a circuit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    set_authorization_type: Option<SetAuthorizationType>,
+    /// Process for defining the `persistence` of a circuit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    set_persistence_type: Option<SetPersistenceType>,
+    /// Process for defining the `durability` of a circuit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    set_durability_type: Option<SetDurabilityType>,
+    /// Process for defining the `routes` of a circuit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    set_route_type: Option<SetRouteType>,
+    /// Process for defining the `comments` of a circuit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    set_comments: Option<SetComments>,
+    /// Rules that are only applied if their condition is met by the argument values.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    conditional: Vec<ConditionalRule>,
+}
+
+impl Rules {
+    pub fn set_management_type(&self) -> Option<&CircuitManagement> {
+        self.set_management_type.as_ref()
+    }
+
+    pub fn create_services(&self) -> Option<&CreateServices> {
+        self.create_services.as_ref()
+    }
+
+    pub fn set_metadata(&self) -> Option<&SetMetadata> {
+        self.set_metadata.as_ref()
+    }
+
+    pub fn set_authorization_type(&self) -> Option<&SetAuthorizationType> {
+        self.set_authorization_type.as_ref()
+    }
+
+    pub fn set_persistence_type(&self) -> Option<&SetPersistenceType> {
+        self.set_persistence_type.as_ref()
+    }
+
+    pub fn set_durability_type(&self) -> Option<&SetDurabilityType> {
+        self.set_durability_type.as_ref()
+    }
+
+    pub fn set_route_type(&self) -> Option<&SetRouteType> {
+        self.set_route_type.as_ref()
+    }
+
+    pub fn set_comments(&self) -> Option<&SetComments> {
+        self.set_comments.as_ref()
+    }
+
+    pub fn conditional(&self) -> &[ConditionalRule] {
+        &self.conditional
+    }
+}
+
+/// Struct to wrap the information used to define a `SplinterService`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct CreateServices {
+    /// Type of the `SplinterService` being constructed.
+    service_type: String,
+    /// Arguments required to build the `SplinterService`.
+    service_args: Vec<ServiceArgument>,
+    first_service: String,
+    /// Arguments only added to the service of a specific node.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    node_service_args: Vec<NodeServiceArguments>,
+}
+
+impl CreateServices {
+    pub fn service_type(&self) -> &str {
+        &self.service_type
+    }
+
+    pub fn service_args(&self) -> &[ServiceArgument] {
+        &self.service_args
+    }
+
+    pub fn first_service(&self) -> &str {
+        &self.first_service
+    }
+
+    pub fn node_service_args(&self) -> &[NodeServiceArguments] {
+        &self.node_service_args
+    }
+}
+
+/// Struct to wrap the service arguments that only apply to the service of a single node.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct NodeServiceArguments {
+    /// ID of the node, or an argument that evaluates to the ID of the node.
+    node: String,
+    service_args: Vec<ServiceArgument>,
+}
+
+impl NodeServiceArguments {
+    pub fn node(&self) -> &str {
+        &self.node
+    }
+
+    pub fn service_args(&self) -> &[ServiceArgument] {
+        &self.service_args
+    }
+}
+
+/// The `authorization_type` used in the `set_authorization_type` rule.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetAuthorizationType {
+    authorization_type: String,
+}
+
+impl SetAuthorizationType {
+    pub fn authorization_type(&self) -> &str {
+        &self.authorization_type
+    }
+}
+
+/// The `persistence_type` used in the `set_persistence_type` rule.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetPersistenceType {
+    persistence_type: String,
+}
+
+impl SetPersistenceType {
+    pub fn persistence_type(&self) -> &str {
+        &self.persistence_type
+    }
+}
+
+/// The `durability_type` used in the `set_durability_type` rule.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetDurabilityType {
+    durability_type: String,
+}
+
+impl SetDurabilityType {
+    pub fn durability_type(&self) -> &str {
+        &self.durability_type
+    }
+}
+
+/// The `route_type` used in the `set_route_type` rule.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct SetRouteType {
+    route_type: String,
+    /// ID of the relay node, or an argument that evaluates to the ID of the relay node. Required
+    /// for the `relayed` route type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    relay_node: Option<String>,
+    /// Comma-separated endpoints of the relay node, or an argument that evaluates to them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    relay_endpoints: Option<String>,
+}
+
+impl SetRouteType {
+    pub fn route_type(&self) -> &str {
+        &self.route_type
+    }
+
+    pub fn relay_node(&self) -> Option<&str> {
+        self.relay_node.as_deref()
+    }
+
+    pub fn relay_endpoints(&self) -> Option<&str> {
+        self.relay_endpoints.as_deref()
+    }
+}
+
+/// The `comments` used in the `set_comments` rule.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SetComments {
+    comments: String,
+}
+
+impl SetComments {
+    pub fn comments(&self) -> &str {
+        &self.comments
+    }
+}
+
+/// Struct to wrap a set of `rules` that are applied only when the `when` condition is met.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConditionalRule {
+    when: Condition,
+    rules: Rules,
+}
+
+impl ConditionalRule {
+    pub fn when(&self) -> &Condition {
+        &self.when
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+}
+
+/// Condition on the value of a template argument.
+///
+/// If neither `equals` nor `not-equals` is provided, the condition is met when the argument has a
+/// non-empty value.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct Condition {
+    /// Name of the argument the condition is evaluated against.
+    argument: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    equals: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    not_equals: Option<String>,
+}
+
+impl Condition {
+    pub fn argument(&self) -> &str {
+        &self.argument
+    }
+
+    pub fn equals(&self) -> Option<&String> {
+        self.equals.as_ref()
+    }
+
+    pub fn not_equals(&self) -> Option<&String> {
+        self.not_equals.as_ref()
+    }
+}
@@ -43,7 +43,9 @@ mod timing;
 
 use std::collections::{HashSet, VecDeque};
 use std::iter::FromIterator;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::sync::Arc;
 use std::time::Duration;
 
 use protobuf::Message;
@@ -113,22 +115,61 @@ impl TwoPhaseProposal {
     }
 }
 
+/// Changes the coordinator timeout of a `TwoPhaseEngine` while it is running.
+///
+/// A new timeout applies to proposals that start being evaluated after it is set.
+#[derive(Clone, Debug)]
+pub struct CoordinatorTimeoutHandle {
+    millis: Arc<AtomicU64>,
+}
+
+impl CoordinatorTimeoutHandle {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            millis: Arc::new(AtomicU64::new(duration.as_millis() as u64)),
+        }
+    }
+
+    /// Returns the current coordinator timeout.
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.millis.load(Ordering::SeqCst))
+    }
+
+    /// Sets the coordinator timeout.
+    pub fn set_timeout(&self, duration: Duration) {
+        self.millis
+            .store(duration.as_millis() as u64, Ordering::SeqCst)
+    }
+}
+
 pub struct TwoPhaseEngine {
     id: PeerId,
     peers: HashSet<PeerId>,
     state: State,
     coordinator_timeout: Timeout,
+    coordinator_timeout_handle: CoordinatorTimeoutHandle,
     proposal_backlog: VecDeque<TwoPhaseProposal>,
     verification_request_backlog: VecDeque<ProposalId>,
 }
 
 impl TwoPhaseEngine {
     pub fn new(coordinator_timeout_duration: Duration) -> Self {
+        Self::with_coordinator_timeout_handle(CoordinatorTimeoutHandle::new(
+            coordinator_timeout_duration,
+        ))
+    }
+
+    /// Creates a new engine whose coordinator timeout is read from the given handle, so that it
+    /// can be changed while the engine is running.
+    pub fn with_coordinator_timeout_handle(
+        coordinator_timeout_handle: CoordinatorTimeoutHandle,
+    ) -> Self {
         TwoPhaseEngine {
             id: PeerId::default(),
             peers: HashSet::new(),
             state: State::Idle,
-            coordinator_timeout: Timeout::new(coordinator_timeout_duration),
+            coordinator_timeout: Timeout::new(coordinator_timeout_handle.timeout()),
+            coordinator_timeout_handle,
             proposal_backlog: VecDeque::new(),
             verification_request_backlog: VecDeque::new(),
         }
@@ -395,6 +436,8 @@ impl TwoPhaseEngine {
         match proposal_manager.check_proposal(tpc_proposal.proposal_id()) {
             Ok(_) => {
                 self.state = State::EvaluatingProposal(tpc_proposal);
+                self.coordinator_timeout
+                    .set_duration(self.coordinator_timeout_handle.timeout());
                 self.coordinator_timeout.start();
             }
             Err(err) => {
@@ -1326,4 +1369,83 @@ pub mod tests {
             .expect("failed to send shutdown");
         thread.join().expect("failed to join engine thread");
     }
+
+    /// Test that a coordinator timeout set through the engine's `CoordinatorTimeoutHandle` is used
+    /// when the coordinator starts evaluating a commit.
+    #[test]
+    fn test_coordinator_timeout_handle() {
+        let (update_tx, update_rx) = channel();
+        let (_consensus_msg_tx, consensus_msg_rx) = channel();
+
+        let manager = MockProposalManager::new(update_tx.clone());
+        let network = MockConsensusNetworkSender::new();
+        let startup_state = StartupState {
+            id: vec![0].into(),
+            peer_ids: vec![vec![1].into(), vec![2].into()],
+            last_proposal: None,
+        };
+
+        // Start engine with a long coordinator timeout, then shorten it through the handle
+        let handle = CoordinatorTimeoutHandle::new(Duration::from_secs(3600));
+        let mut engine = TwoPhaseEngine::with_coordinator_timeout_handle(handle.clone());
+        handle.set_timeout(Duration::from_millis(10));
+        let network_clone = network.clone();
+        let manager_clone = manager.clone();
+        let thread = std::thread::spawn(move || {
+            engine
+                .run(
+                    consensus_msg_rx,
+                    update_rx,
+                    Box::new(network_clone),
+                    Box::new(manager_clone),
+                    startup_state,
+                )
+                .expect("engine failed")
+        });
+
+        // Check that a proposal verification request is sent
+        loop {
+            if let Some(msg) = network.broadcast_messages().get(0) {
+                let msg: TwoPhaseMessage =
+                    protobuf::parse_from_bytes(msg).expect("failed to parse message");
+                assert_eq!(
+                    msg.get_message_type(),
+                    TwoPhaseMessage_Type::PROPOSAL_VERIFICATION_REQUEST
+                );
+                assert_eq!(msg.get_proposal_id(), vec![1].as_slice());
+                break;
+            }
+        }
+
+        // Verify the Reject message is sent for the proposal (due to the timeout)
+        loop {
+            if let Some(msg) = network.broadcast_messages().get(1) {
+                let msg: TwoPhaseMessage =
+                    protobuf::parse_from_bytes(msg).expect("failed to parse message");
+                assert_eq!(
+                    msg.get_message_type(),
+                    TwoPhaseMessage_Type::PROPOSAL_RESULT
+                );
+                assert_eq!(
+                    msg.get_proposal_result(),
+                    TwoPhaseMessage_ProposalResult::REJECT
+                );
+                assert_eq!(msg.get_proposal_id(), vec![1].as_slice());
+                break;
+            }
+        }
+
+        // Verify the proposal was rejected
+        loop {
+            if let Some(id) = manager.rejected_proposals().get(0) {
+                assert_eq!(id, &vec![1].into());
+                break;
+            }
+        }
+
+        update_tx
+            .send(ProposalUpdate::Shutdown)
+            .expect("failed to send shutdown");
+        thread.join().expect("failed to join engine thread");
+    }
 }
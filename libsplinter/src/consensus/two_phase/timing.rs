@@ -51,6 +51,12 @@ impl Timeout {
         }
     }
 
+    /// Change the duration of the timer; an active timer is not restarted, but will expire based on
+    /// the new duration
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration = duration;
+    }
+
     pub fn start(&mut self) {
         self.state = TimeoutState::Active;
         self.start = Instant::now();
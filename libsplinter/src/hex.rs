@@ -37,12 +37,14 @@ pub fn parse_hex(hex: &str) -> Result<Vec<u8>, HexError> {
 
     let mut res = vec![];
     for i in (0..hex.len()).step_by(2) {
-        res.push(
-            u8::from_str_radix(&hex[i..i + 2], 16).map_err(|err| HexError {
-                context: format!("{} contains invalid hex", hex),
-                source: Some(Box::new(err)),
-            })?,
-        );
+        let digits = hex.get(i..i + 2).ok_or_else(|| HexError {
+            context: format!("{} contains invalid hex", hex),
+            source: None,
+        })?;
+        res.push(u8::from_str_radix(digits, 16).map_err(|err| HexError {
+            context: format!("{} contains invalid hex", hex),
+            source: Some(Box::new(err)),
+        })?);
     }
 
     Ok(res)
@@ -139,6 +141,9 @@ mod tests {
         // check that invalid digits fails
         assert!(parse_hex("0G").is_err());
 
+        // check that non-ASCII characters fail rather than panic
+        assert!(parse_hex("0é").is_err());
+
         // check round trip
         assert_eq!(
             "abcdef",
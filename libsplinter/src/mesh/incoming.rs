@@ -12,27 +12,136 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::time::Duration;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use crossbeam_channel::{Receiver, Select, Sender, TryRecvError, TrySendError};
+
+use super::priority::{lane_order, Priority, PriorityClassifier, LANES};
 use super::InternalEnvelope;
 
+/// Create the queues for envelopes received by the mesh, with the given capacity for each
+/// priority lane.
+pub(super) fn channel(
+    capacity: usize,
+    classifier: PriorityClassifier,
+) -> (IncomingSender, Incoming) {
+    let mut senders = Vec::with_capacity(LANES);
+    let mut receivers = Vec::with_capacity(LANES);
+    for _ in 0..LANES {
+        let (tx, rx) = crossbeam_channel::bounded(capacity);
+        senders.push(tx);
+        receivers.push(rx);
+    }
+
+    (
+        IncomingSender {
+            lanes: senders,
+            classifier,
+        },
+        Incoming {
+            lanes: Arc::new(receivers),
+            turn: Arc::new(AtomicUsize::new(0)),
+        },
+    )
+}
+
+/// Handle for the reactor to queue envelopes received from connections
+pub(super) struct IncomingSender {
+    lanes: Vec<Sender<InternalEnvelope>>,
+    classifier: PriorityClassifier,
+}
+
+impl IncomingSender {
+    /// Returns the priority of the payload.
+    pub fn classify(&self, payload: &[u8]) -> Priority {
+        (self.classifier)(payload)
+    }
+
+    pub fn try_send(
+        &self,
+        envelope: InternalEnvelope,
+        priority: Priority,
+    ) -> Result<(), TrySendError<InternalEnvelope>> {
+        self.lanes[priority.index()].try_send(envelope)
+    }
+
+    /// Send the shutdown envelope, ahead of any envelopes that are not control messages.
+    pub fn send_shutdown(&self) -> Result<(), InternalEnvelope> {
+        self.lanes[Priority::Control.index()]
+            .send(InternalEnvelope::Shutdown)
+            .map_err(|err| err.into_inner())
+    }
+}
+
 /// Handle for receiving envelopes from the mesh
 #[derive(Clone)]
 pub(super) struct Incoming {
-    rx: crossbeam_channel::Receiver<InternalEnvelope>,
+    lanes: Arc<Vec<Receiver<InternalEnvelope>>>,
+    turn: Arc<AtomicUsize>,
 }
 
 impl Incoming {
-    pub(super) fn new(rx: crossbeam_channel::Receiver<InternalEnvelope>) -> Self {
-        Incoming { rx }
-    }
-
     pub fn recv(&self) -> Result<InternalEnvelope, RecvError> {
-        self.rx.recv().map_err(|_| RecvError {})
+        loop {
+            match self.try_recv() {
+                Ok(envelope) => return Ok(envelope),
+                Err(TryRecvError::Disconnected) => return Err(RecvError {}),
+                Err(TryRecvError::Empty) => {
+                    let mut select = self.select();
+                    select.ready();
+                }
+            }
+        }
     }
 
     pub fn recv_timeout(&self, timeout: Duration) -> Result<InternalEnvelope, RecvTimeoutError> {
-        Ok(self.rx.recv_timeout(timeout)?)
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.try_recv() {
+                Ok(envelope) => return Ok(envelope),
+                Err(TryRecvError::Disconnected) => return Err(RecvTimeoutError::Disconnected),
+                Err(TryRecvError::Empty) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(RecvTimeoutError::Timeout);
+                    }
+                    let mut select = self.select();
+                    if select.ready_timeout(deadline - now).is_err() {
+                        return Err(RecvTimeoutError::Timeout);
+                    }
+                }
+            }
+        }
+    }
+
+    // Receive an envelope if one is queued, taking turns between the lanes.
+    fn try_recv(&self) -> Result<InternalEnvelope, TryRecvError> {
+        let turn = self.turn.fetch_add(1, Ordering::Relaxed);
+        let mut disconnected = 0;
+        for priority in lane_order(turn).iter() {
+            match self.lanes[priority.index()].try_recv() {
+                Ok(envelope) => return Ok(envelope),
+                Err(TryRecvError::Empty) => (),
+                Err(TryRecvError::Disconnected) => disconnected += 1,
+            }
+        }
+
+        if disconnected == LANES {
+            Err(TryRecvError::Disconnected)
+        } else {
+            Err(TryRecvError::Empty)
+        }
+    }
+
+    // Create a selection over all lanes, for waiting until one of them is ready.
+    fn select(&self) -> Select<'_> {
+        let mut select = Select::new();
+        for lane in self.lanes.iter() {
+            select.recv(lane);
+        }
+        select
     }
 }
 
@@ -45,12 +154,3 @@ pub enum RecvTimeoutError {
     Disconnected,
     Timeout,
 }
-
-impl From<crossbeam_channel::RecvTimeoutError> for RecvTimeoutError {
-    fn from(err: crossbeam_channel::RecvTimeoutError) -> Self {
-        match err {
-            crossbeam_channel::RecvTimeoutError::Disconnected => RecvTimeoutError::Disconnected,
-            crossbeam_channel::RecvTimeoutError::Timeout => RecvTimeoutError::Timeout,
-        }
-    }
-}
@@ -64,18 +64,33 @@ impl ConnectionMatrixLifeCycle for MeshLifeCycle {
 /// Mesh specific implementation of ConnectionMatrixSender
 pub struct MeshMatrixSender {
     mesh: Mesh,
+    send_timeout: Option<Duration>,
 }
 
 impl MeshMatrixSender {
     pub fn new(mesh: Mesh) -> Self {
-        MeshMatrixSender { mesh }
+        MeshMatrixSender {
+            mesh,
+            send_timeout: None,
+        }
+    }
+
+    /// Wait up to the given timeout for room in a connection's queue when sending, rather than
+    /// failing immediately if the queue is full.
+    pub fn with_send_timeout(mut self, timeout: Duration) -> Self {
+        self.send_timeout = Some(timeout);
+        self
     }
 }
 
 impl ConnectionMatrixSender for MeshMatrixSender {
     fn send(&self, id: String, message: Vec<u8>) -> Result<(), ConnectionMatrixSendError> {
         let envelope = ConnectionMatrixEnvelope::new(id, message);
-        self.mesh.send(envelope).map_err(|err| {
+        let result = match self.send_timeout {
+            Some(timeout) => self.mesh.send_timeout(envelope, timeout),
+            None => self.mesh.send(envelope),
+        };
+        result.map_err(|err| {
             ConnectionMatrixSendError::new(
                 "Unable to send message to connection".to_string(),
                 Some(Box::new(err)),
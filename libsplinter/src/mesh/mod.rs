@@ -48,12 +48,18 @@
 //!    be a more efficient implementation.
 //! 3. Backpressure should be built in. This means all queues should be bounded so that a
 //!    backpressure error can be returned when the queue is full.
+//! 4. Messages should not be held up by less important traffic. Each message is classified as
+//!    control, consensus or application traffic, and each class has its own incoming queue and its
+//!    own outgoing queue per Connection. The queues of each class are served in turn, weighted
+//!    towards control messages, so that a flood of application messages can neither delay nor
+//!    cause the dropping of heartbeats and consensus messages.
 
 mod control;
 mod incoming;
 mod matrix;
 mod outgoing;
 mod pool;
+mod priority;
 mod reactor;
 
 use std::collections::HashMap;
@@ -68,7 +74,10 @@ use crate::mesh::incoming::Incoming;
 pub use crate::mesh::matrix::{
     MeshLifeCycle, MeshMatrixReceiver, MeshMatrixSender, MeshMatrixShutdown,
 };
+pub use crate::mesh::outgoing::ConnectionMetrics;
 use crate::mesh::outgoing::Outgoing;
+use crate::mesh::priority::default_classifier;
+pub use crate::mesh::priority::{Priority, PriorityClassifier};
 pub use crate::transport::matrix::ConnectionMatrixEnvelope as Envelope;
 
 use crate::collections::BiHashMap;
@@ -113,17 +122,33 @@ pub struct Mesh {
     state: Arc<RwLock<MeshState>>,
     incoming: Incoming,
     ctrl: Control,
+    classifier: PriorityClassifier,
 }
 
 impl Mesh {
     /// Create a new mesh, spawning a background thread for sending and receiving, and setting up
     /// channels to communicate with it.
+    ///
+    /// All messages are treated as application messages.
     pub fn new(incoming_capacity: usize, outgoing_capacity: usize) -> Self {
-        let (ctrl, incoming) = Reactor::spawn(incoming_capacity, outgoing_capacity);
+        Self::new_with_classifier(incoming_capacity, outgoing_capacity, default_classifier)
+    }
+
+    /// Create a new mesh that uses the given classifier to determine the priority of the
+    /// messages that are sent and received.
+    ///
+    /// The capacities apply to each priority's queue.
+    pub fn new_with_classifier(
+        incoming_capacity: usize,
+        outgoing_capacity: usize,
+        classifier: PriorityClassifier,
+    ) -> Self {
+        let (ctrl, incoming) = Reactor::spawn(incoming_capacity, outgoing_capacity, classifier);
         Mesh {
             state: Arc::new(RwLock::new(MeshState::new())),
             incoming,
             ctrl,
+            classifier,
         }
     }
 
@@ -166,24 +191,61 @@ impl Mesh {
         }
     }
 
-    /// Send the envelope on the mesh.
+    /// Send the envelope on the mesh, with the priority determined by the mesh's classifier.
     ///
-    /// This is a convenience function and is equivalent to
-    /// `mesh.outgoing(envelope.id()).send(Vec::from(envelope))`.
+    /// Fails with `SendError::Full` if the connection's queue for the priority is full.
     pub fn send(&self, envelope: Envelope) -> Result<(), SendError> {
-        let state = &self.state.read().map_err(|_| SendError::PoisonedLock)?;
+        let priority = (self.classifier)(envelope.payload());
+        self.send_with_priority(envelope, priority)
+    }
+
+    /// Send the envelope on the mesh with the given priority.
+    ///
+    /// Fails with `SendError::Full` if the connection's queue for the priority is full.
+    pub fn send_with_priority(
+        &self,
+        envelope: Envelope,
+        priority: Priority,
+    ) -> Result<(), SendError> {
         let id = envelope.id().to_string();
-        if let Some(mesh_id) = state.unique_ids.get_by_key(&id) {
-            match state.outgoings.get(mesh_id) {
-                Some(ref outgoing) => match outgoing.send(Vec::from(envelope)) {
-                    Ok(()) => Ok(()),
-                    Err(err) => Err(SendError::from_outgoing_send_error(err, id)),
-                },
-                None => Err(SendError::NotFound),
-            }
-        } else {
-            Err(SendError::NotFound)
-        }
+        self.outgoing(&id)?
+            .send(Vec::from(envelope), priority)
+            .map_err(|err| SendError::from_outgoing_send_error(err, id))
+    }
+
+    /// Send the envelope on the mesh, with the priority determined by the mesh's classifier,
+    /// waiting up to the given timeout for room in the connection's queue for the priority.
+    ///
+    /// Fails with `SendError::Full` if there is still no room when the timeout expires.
+    pub fn send_timeout(&self, envelope: Envelope, timeout: Duration) -> Result<(), SendError> {
+        let priority = (self.classifier)(envelope.payload());
+        let id = envelope.id().to_string();
+        self.outgoing(&id)?
+            .send_timeout(Vec::from(envelope), priority, timeout)
+            .map_err(|err| SendError::from_outgoing_send_error(err, id))
+    }
+
+    /// Returns the queue depths and drop counts of the connection with the given unique id.
+    pub fn metrics(&self, unique_id: &str) -> Result<ConnectionMetrics, MetricsError> {
+        let state = self.state.read().map_err(|_| MetricsError::PoisonedLock)?;
+        state
+            .unique_ids
+            .get_by_key(unique_id)
+            .and_then(|mesh_id| state.outgoings.get(mesh_id))
+            .map(Outgoing::metrics)
+            .ok_or(MetricsError::NotFound)
+    }
+
+    // Returns the outgoing handle of the connection, so that the lock on the mesh state is not
+    // held while sending.
+    fn outgoing(&self, unique_id: &str) -> Result<Outgoing, SendError> {
+        let state = self.state.read().map_err(|_| SendError::PoisonedLock)?;
+        state
+            .unique_ids
+            .get_by_key(unique_id)
+            .and_then(|mesh_id| state.outgoings.get(mesh_id))
+            .cloned()
+            .ok_or(SendError::NotFound)
     }
 
     /// Receive a new envelope from the mesh.
@@ -308,6 +370,23 @@ impl SendError {
     }
 }
 
+#[derive(Debug)]
+pub enum MetricsError {
+    NotFound,
+    PoisonedLock,
+}
+
+impl Error for MetricsError {}
+
+impl std::fmt::Display for MetricsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MetricsError::NotFound => write!(f, "requested connection cannot be found"),
+            MetricsError::PoisonedLock => write!(f, "MeshState lock was poisoned"),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum RecvError {
     Disconnected,
@@ -546,6 +625,52 @@ mod tests {
         test_add_remove_connections(tls, "127.0.0.1:0");
     }
 
+    // Test that messages received while their incoming lane is full are held until there is room
+    // in the lane, rather than dropped, and that the connection reports the pause in its metrics.
+    //
+    // 1. Add a connection to a mesh whose incoming lanes hold two messages each
+    // 2. From the other end, send more messages than the lane can hold
+    // 3. Wait for the lane to fill, then verify that every message is received, in order
+    #[test]
+    fn test_full_incoming_lane() {
+        const MESSAGES: u8 = 16;
+
+        let mut transport = TcpTransport::default();
+        let mut listener = assert_ok(transport.listen("127.0.0.1:0"));
+        let endpoint = listener.endpoint();
+
+        let handle = thread::spawn(move || {
+            let client_mesh = Mesh::new(1, MESSAGES as usize);
+            assert_ok(client_mesh.add(
+                assert_ok(transport.connect(&endpoint)),
+                "client".to_string(),
+            ));
+
+            for i in 0..MESSAGES {
+                assert_ok(client_mesh.send(Envelope::new("client".to_string(), vec![i])));
+            }
+
+            client_mesh
+        });
+
+        let mesh = Mesh::new(2, 2);
+        let conn = assert_ok(listener.accept());
+        assert_ok(mesh.add(conn, "server".to_string()));
+
+        // Keep the client's connection open until everything has been received
+        let _client_mesh = handle.join().unwrap();
+
+        thread::sleep(Duration::from_millis(100));
+
+        for i in 0..MESSAGES {
+            let envelope = assert_ok(mesh.recv_timeout(Duration::from_secs(5)));
+            assert_eq!(&[i], envelope.payload());
+        }
+
+        let metrics = assert_ok(mesh.metrics("server"));
+        assert!(metrics.incoming_blocked(Priority::Application) > 0);
+    }
+
     #[test]
     // Test that mesh can be shutdown after sending and receiving a message.
     //
@@ -12,35 +12,260 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use mio_extras::channel::{SyncSender, TrySendError};
-
 use std::io;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crossbeam_channel::{Receiver, SendTimeoutError, Sender, TryRecvError, TrySendError};
+use mio::{Evented, Poll, PollOpt, Ready, Registration, SetReadiness, Token};
+
+use super::priority::{lane_order, Priority, LANES};
+
+/// Create the queues for sending to a connection, with the given capacity for each priority lane.
+pub(super) fn channel(capacity: usize) -> (OutgoingSenders, OutgoingReceiver) {
+    let (registration, set_readiness) = Registration::new2();
+    let shared = Arc::new(Shared {
+        pending: AtomicUsize::new(0),
+        set_readiness,
+        dropped: Default::default(),
+        incoming_blocked: Default::default(),
+    });
+
+    let mut senders = Vec::with_capacity(LANES);
+    let mut receivers = Vec::with_capacity(LANES);
+    for _ in 0..LANES {
+        let (tx, rx) = crossbeam_channel::bounded(capacity);
+        senders.push(tx);
+        receivers.push(rx);
+    }
 
-use super::InternalEnvelope;
+    (
+        OutgoingSenders {
+            lanes: senders,
+            shared: shared.clone(),
+        },
+        OutgoingReceiver {
+            lanes: receivers,
+            shared,
+            registration,
+            turn: AtomicUsize::new(0),
+        },
+    )
+}
 
 /// Handle for sending to a specific connection in the mesh
 #[derive(Clone)]
 pub struct Outgoing {
     id: usize,
-    tx: SyncSender<InternalEnvelope>,
+    senders: Arc<OutgoingSenders>,
 }
 
 impl Outgoing {
-    pub(super) fn new(id: usize, tx: SyncSender<InternalEnvelope>) -> Self {
-        Outgoing { id, tx }
+    pub(super) fn new(id: usize, senders: OutgoingSenders) -> Self {
+        Outgoing {
+            id,
+            senders: Arc::new(senders),
+        }
     }
 
-    pub fn send(&self, payload: Vec<u8>) -> Result<(), SendError> {
-        self.tx.try_send(InternalEnvelope::Message {
-            id: self.id,
-            payload,
-        })?;
-        Ok(())
+    /// Queue the payload in the lane for the given priority, failing immediately if the lane is
+    /// full.
+    pub fn send(&self, payload: Vec<u8>, priority: Priority) -> Result<(), SendError> {
+        let lane = &self.senders.lanes[priority.index()];
+        match lane.try_send(payload) {
+            Ok(()) => {
+                self.senders.shared.inc()?;
+                Ok(())
+            }
+            Err(TrySendError::Full(payload)) => {
+                self.senders.shared.dropped[priority.index()].fetch_add(1, Ordering::Relaxed);
+                Err(SendError::Full(payload))
+            }
+            Err(TrySendError::Disconnected(payload)) => Err(SendError::Disconnected(payload)),
+        }
+    }
+
+    /// Queue the payload in the lane for the given priority, waiting up to the timeout for room
+    /// in the lane if it is full.
+    pub fn send_timeout(
+        &self,
+        payload: Vec<u8>,
+        priority: Priority,
+        timeout: Duration,
+    ) -> Result<(), SendError> {
+        let lane = &self.senders.lanes[priority.index()];
+        match lane.send_timeout(payload, timeout) {
+            Ok(()) => {
+                self.senders.shared.inc()?;
+                Ok(())
+            }
+            Err(SendTimeoutError::Timeout(payload)) => {
+                self.senders.shared.dropped[priority.index()].fetch_add(1, Ordering::Relaxed);
+                Err(SendError::Full(payload))
+            }
+            Err(SendTimeoutError::Disconnected(payload)) => Err(SendError::Disconnected(payload)),
+        }
     }
 
     pub fn id(&self) -> usize {
         self.id
     }
+
+    /// Returns the current queue depths and drop counts of the connection's lanes.
+    pub fn metrics(&self) -> ConnectionMetrics {
+        let mut metrics = ConnectionMetrics::default();
+        for priority in Priority::ALL.iter() {
+            let index = priority.index();
+            metrics.queue_depths[index] = self.senders.lanes[index].len();
+            metrics.dropped[index] = self.senders.shared.dropped[index].load(Ordering::Relaxed);
+            metrics.incoming_blocked[index] =
+                self.senders.shared.incoming_blocked[index].load(Ordering::Relaxed);
+        }
+        metrics
+    }
+}
+
+/// The queue depths and drop counts of a connection's priority lanes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConnectionMetrics {
+    queue_depths: [usize; LANES],
+    dropped: [u64; LANES],
+    incoming_blocked: [u64; LANES],
+}
+
+impl ConnectionMetrics {
+    /// The number of messages waiting to be sent in the lane.
+    pub fn queue_depth(&self, priority: Priority) -> usize {
+        self.queue_depths[priority.index()]
+    }
+
+    /// The number of messages that could not be queued for sending in the lane, because it was
+    /// full.
+    pub fn dropped(&self, priority: Priority) -> u64 {
+        self.dropped[priority.index()]
+    }
+
+    /// The number of times reading from the connection was paused because the mesh's incoming
+    /// lane was full.
+    pub fn incoming_blocked(&self, priority: Priority) -> u64 {
+        self.incoming_blocked[priority.index()]
+    }
+}
+
+/// The state shared between the senders and the receiver of a connection's lanes.
+///
+/// The number of pending messages across all lanes determines the readiness of the receiver, in
+/// the same way as mio_extras' channels.
+struct Shared {
+    pending: AtomicUsize,
+    set_readiness: SetReadiness,
+    dropped: [AtomicU64; LANES],
+    incoming_blocked: [AtomicU64; LANES],
+}
+
+impl Shared {
+    fn inc(&self) -> io::Result<()> {
+        if self.pending.fetch_add(1, Ordering::AcqRel) == 0 {
+            self.set_readiness.set_readiness(Ready::readable())?;
+        }
+        Ok(())
+    }
+
+    fn dec(&self) -> io::Result<()> {
+        let first = self.pending.load(Ordering::Acquire);
+        if first == 1 {
+            self.set_readiness.set_readiness(Ready::empty())?;
+        }
+
+        let second = self.pending.fetch_sub(1, Ordering::AcqRel);
+        // A message was queued after the readiness was cleared
+        if first == 1 && second > 1 {
+            self.set_readiness.set_readiness(Ready::readable())?;
+        }
+        Ok(())
+    }
+}
+
+/// The sending side of a connection's lanes, shared by all clones of its `Outgoing`.
+pub(super) struct OutgoingSenders {
+    lanes: Vec<Sender<Vec<u8>>>,
+    shared: Arc<Shared>,
+}
+
+impl Drop for OutgoingSenders {
+    fn drop(&mut self) {
+        // Disconnect the lanes, then wake the receiver so that it sees they are disconnected
+        self.lanes.clear();
+        if let Err(err) = self.shared.set_readiness.set_readiness(Ready::readable()) {
+            error!("Unable to wake outgoing receiver: {}", err);
+        }
+    }
+}
+
+/// The receiving side of a connection's lanes, which is polled by the reactor.
+pub(super) struct OutgoingReceiver {
+    lanes: Vec<Receiver<Vec<u8>>>,
+    shared: Arc<Shared>,
+    registration: Registration,
+    turn: AtomicUsize,
+}
+
+impl OutgoingReceiver {
+    /// Receive the next payload to send to the connection, taking turns between the lanes.
+    pub fn try_recv(&self) -> Result<Vec<u8>, TryRecvError> {
+        let turn = self.turn.fetch_add(1, Ordering::Relaxed);
+        let mut disconnected = 0;
+        for priority in lane_order(turn).iter() {
+            match self.lanes[priority.index()].try_recv() {
+                Ok(payload) => {
+                    if let Err(err) = self.shared.dec() {
+                        error!("Unable to update outgoing readiness: {}", err);
+                    }
+                    return Ok(payload);
+                }
+                Err(TryRecvError::Empty) => (),
+                Err(TryRecvError::Disconnected) => disconnected += 1,
+            }
+        }
+
+        if disconnected == LANES {
+            Err(TryRecvError::Disconnected)
+        } else {
+            Err(TryRecvError::Empty)
+        }
+    }
+
+    /// Record that reading from the connection was paused because an incoming lane was full.
+    pub fn record_incoming_blocked(&self, priority: Priority) {
+        self.shared.incoming_blocked[priority.index()].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Evented for OutgoingReceiver {
+    fn register(
+        &self,
+        poll: &Poll,
+        token: Token,
+        interest: Ready,
+        opts: PollOpt,
+    ) -> io::Result<()> {
+        self.registration.register(poll, token, interest, opts)
+    }
+
+    fn reregister(
+        &self,
+        poll: &Poll,
+        token: Token,
+        interest: Ready,
+        opts: PollOpt,
+    ) -> io::Result<()> {
+        self.registration.reregister(poll, token, interest, opts)
+    }
+
+    fn deregister(&self, poll: &Poll) -> io::Result<()> {
+        poll.deregister(&self.registration)
+    }
 }
 
 #[derive(Debug)]
@@ -50,24 +275,86 @@ pub enum SendError {
     Disconnected(Vec<u8>),
 }
 
-impl From<TrySendError<InternalEnvelope>> for SendError {
-    fn from(err: TrySendError<InternalEnvelope>) -> Self {
-        match err {
-            TrySendError::Full(envelope) => {
-                match envelope {
-                    InternalEnvelope::Message { payload, .. } => SendError::Full(payload),
-                    // this will not be sent
-                    InternalEnvelope::Shutdown => unreachable!(),
-                }
-            }
-            TrySendError::Disconnected(envelope) => {
-                match envelope {
-                    InternalEnvelope::Message { payload, .. } => SendError::Disconnected(payload),
-                    // this will not be sent
-                    InternalEnvelope::Shutdown => unreachable!(),
-                }
-            }
-            TrySendError::Io(err) => SendError::IoError(err),
+impl From<io::Error> for SendError {
+    fn from(err: io::Error) -> Self {
+        SendError::IoError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that the receiver takes turns between the lanes while they all have messages queued,
+    // and drains the remaining lanes once the others are empty
+    #[test]
+    fn test_lane_scheduling() {
+        let (senders, receiver) = channel(8);
+        let outgoing = Outgoing::new(0, senders);
+
+        for i in 0..4u8 {
+            outgoing.send(vec![i], Priority::Application).unwrap();
+        }
+        for i in 0..4u8 {
+            outgoing.send(vec![10 + i], Priority::Consensus).unwrap();
+        }
+        for i in 0..8u8 {
+            outgoing.send(vec![20 + i], Priority::Control).unwrap();
+        }
+
+        let received = (0..16)
+            .map(|_| receiver.try_recv().unwrap()[0])
+            .collect::<Vec<u8>>();
+        assert_eq!(
+            received,
+            vec![20, 10, 21, 0, 22, 11, 23, 24, 12, 25, 1, 26, 13, 27, 2, 3]
+        );
+        match receiver.try_recv() {
+            Err(TryRecvError::Empty) => (),
+            res => panic!("Expected the lanes to be empty, got {:?}", res),
+        }
+
+        drop(outgoing);
+        match receiver.try_recv() {
+            Err(TryRecvError::Disconnected) => (),
+            res => panic!("Expected the lanes to be disconnected, got {:?}", res),
         }
     }
+
+    // Test that a full lane does not prevent sending in the other lanes, and that the queue
+    // depths and dropped messages are reported for each lane
+    #[test]
+    fn test_full_lane() {
+        let (senders, receiver) = channel(2);
+        let outgoing = Outgoing::new(0, senders);
+
+        outgoing.send(vec![0], Priority::Application).unwrap();
+        outgoing.send(vec![1], Priority::Application).unwrap();
+        match outgoing.send(vec![2], Priority::Application) {
+            Err(SendError::Full(payload)) => assert_eq!(payload, vec![2]),
+            res => panic!("Expected the lane to be full, got {:?}", res),
+        }
+        match outgoing.send_timeout(vec![3], Priority::Application, Duration::from_millis(10)) {
+            Err(SendError::Full(payload)) => assert_eq!(payload, vec![3]),
+            res => panic!("Expected the lane to be full, got {:?}", res),
+        }
+        outgoing.send(vec![4], Priority::Control).unwrap();
+        receiver.record_incoming_blocked(Priority::Consensus);
+
+        let metrics = outgoing.metrics();
+        assert_eq!(metrics.queue_depth(Priority::Application), 2);
+        assert_eq!(metrics.dropped(Priority::Application), 2);
+        assert_eq!(metrics.queue_depth(Priority::Control), 1);
+        assert_eq!(metrics.dropped(Priority::Control), 0);
+        assert_eq!(metrics.incoming_blocked(Priority::Consensus), 1);
+
+        assert_eq!(receiver.try_recv().unwrap(), vec![4]);
+        assert_eq!(receiver.try_recv().unwrap(), vec![0]);
+
+        // A blocked sender is able to queue its message once there is room in the lane
+        outgoing
+            .send_timeout(vec![5], Priority::Application, Duration::from_millis(10))
+            .unwrap();
+        assert_eq!(outgoing.metrics().queue_depth(Priority::Application), 2);
+    }
 }
@@ -12,20 +12,25 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crossbeam_channel::TrySendError;
+use crossbeam_channel::{TryRecvError, TrySendError};
 use mio::{Event, Evented, Events, Poll, PollOpt, Ready, Token};
-use mio_extras::channel as mio_channel;
 
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt;
 use std::io;
-use std::sync::mpsc::TryRecvError;
+use std::time::Duration;
 
 use crate::transport::{Connection, RecvError, SendError};
 
+use super::incoming::IncomingSender;
+use super::outgoing::OutgoingReceiver;
+use super::priority::Priority;
 use super::InternalEnvelope;
 
+// How long to wait between attempts to queue a message whose incoming lane was full
+const HELD_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
 /// A structure for holding onto many connections and receivers and assigning new connections
 /// unique ids
 pub(super) struct Pool {
@@ -63,7 +68,7 @@ impl Pool {
     pub fn add(
         &mut self,
         connection: Box<dyn Connection>,
-        outgoing: mio_channel::Receiver<InternalEnvelope>,
+        outgoing: OutgoingReceiver,
     ) -> Result<usize, io::Error> {
         let connection_token = self.next_token();
         let outgoing_token = self.next_token();
@@ -123,28 +128,55 @@ impl Pool {
     }
 
     /// Poll all connections, outgoings, and externally registered types
+    ///
+    /// If any connection is holding a message for a full incoming lane, the poll times out so that
+    /// the message can be retried, as the incoming lanes do not wake the poll when they drain.
     pub fn poll(&self, events: &mut Events) -> Result<usize, io::Error> {
-        self.poll.poll(events, None)
+        if self.entries.values().any(Entry::is_holding) {
+            self.poll.poll(events, Some(HELD_RETRY_INTERVAL))
+        } else {
+            self.poll.poll(events, None)
+        }
     }
 
-    pub fn handle_event(
-        &mut self,
-        event: &Event,
-        incoming_tx: &crossbeam_channel::Sender<InternalEnvelope>,
-    ) {
+    /// Retry queuing the messages held by connections whose incoming lanes were full, resuming
+    /// reading from each connection whose message is queued.
+    pub fn retry_held(&mut self, incoming_tx: &IncomingSender) {
+        let failed = self
+            .entries
+            .values()
+            .filter(|entry| entry.is_holding())
+            .filter_map(|entry| {
+                entry
+                    .try_queue_held(incoming_tx, &self.poll)
+                    .err()
+                    .map(|err| (entry.id(), err))
+            })
+            .collect::<Vec<_>>();
+
+        for (id, err) in failed {
+            self.remove_failed(id, err);
+        }
+    }
+
+    pub fn handle_event(&mut self, event: &Event, incoming_tx: &IncomingSender) {
         if let Err((id, err)) = self.try_handle_event(event, incoming_tx) {
-            debug!(
-                "Removing Connection {} due to error handling event: {:?}",
-                id, err
-            );
-            match self.remove(id) {
-                Ok(connection) => {
-                    self.disconnected.insert(id, connection);
-                }
-                Err(err) => {
-                    error!("Error removing connection: {:?}", err);
-                    self.disconnected.insert(id, None);
-                }
+            self.remove_failed(id, err);
+        }
+    }
+
+    fn remove_failed(&mut self, id: usize, err: TryEventError) {
+        debug!(
+            "Removing Connection {} due to error handling event: {:?}",
+            id, err
+        );
+        match self.remove(id) {
+            Ok(connection) => {
+                self.disconnected.insert(id, connection);
+            }
+            Err(err) => {
+                error!("Error removing connection: {:?}", err);
+                self.disconnected.insert(id, None);
             }
         }
     }
@@ -152,7 +184,7 @@ impl Pool {
     fn try_handle_event(
         &self,
         event: &Event,
-        incoming_tx: &crossbeam_channel::Sender<InternalEnvelope>,
+        incoming_tx: &IncomingSender,
     ) -> Result<(), (usize, TryEventError)> {
         if let Some(entry) = self.entry_by_token(event.token()) {
             entry
@@ -186,9 +218,10 @@ struct Entry {
     id: usize,
     connection: RefCell<Box<dyn Connection>>,
     connection_token: Token,
-    outgoing: mio_channel::Receiver<InternalEnvelope>,
+    outgoing: OutgoingReceiver,
     outgoing_token: Token,
     cached: RefCell<Option<Vec<u8>>>,
+    held: RefCell<Option<(InternalEnvelope, Priority)>>,
     write_evented_guard: RefCell<bool>,
 }
 
@@ -196,8 +229,12 @@ impl fmt::Debug for Entry {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "Entry {{ id: {:?}, connection: {:?}, outgoing: {:?}, cached: {:?} }}",
-            self.id, self.connection_token, self.outgoing_token, self.cached,
+            "Entry {{ id: {:?}, connection: {:?}, outgoing: {:?}, cached: {:?}, holding: {:?} }}",
+            self.id,
+            self.connection_token,
+            self.outgoing_token,
+            self.cached,
+            self.is_holding(),
         )
     }
 }
@@ -207,7 +244,7 @@ impl Entry {
         id: usize,
         connection: Box<dyn Connection>,
         connection_token: Token,
-        outgoing: mio_channel::Receiver<InternalEnvelope>,
+        outgoing: OutgoingReceiver,
         outgoing_token: Token,
     ) -> Self {
         Entry {
@@ -217,6 +254,7 @@ impl Entry {
             outgoing,
             outgoing_token,
             cached: RefCell::new(None),
+            held: RefCell::new(None),
             write_evented_guard: RefCell::new(false),
         }
     }
@@ -233,14 +271,18 @@ impl Entry {
         self.outgoing_token
     }
 
-    fn into_evented(self) -> (Box<dyn Connection>, mio_channel::Receiver<InternalEnvelope>) {
+    fn is_holding(&self) -> bool {
+        self.held.borrow().is_some()
+    }
+
+    fn into_evented(self) -> (Box<dyn Connection>, OutgoingReceiver) {
         (self.connection.into_inner(), self.outgoing)
     }
 
     fn try_event(
         &self,
         event: &Event,
-        incoming_tx: &crossbeam_channel::Sender<InternalEnvelope>,
+        incoming_tx: &IncomingSender,
         poll: &Poll,
     ) -> Result<(), TryEventError> {
         if self.outgoing_wants_read(event) {
@@ -248,7 +290,7 @@ impl Entry {
         } else if self.connection_wants_write(event) {
             self.try_send_connection_from_cached(poll)
         } else if self.connection_wants_read(event) {
-            self.try_read_connection(incoming_tx)
+            self.try_read_connection(incoming_tx, poll)
         } else {
             Ok(())
         }
@@ -263,18 +305,10 @@ impl Entry {
     }
 
     fn try_read_outgoing(&self, poll: &Poll) -> Result<(), TryEventError> {
-        let envelope = match self.outgoing.try_recv() {
-            Ok(envelope) => envelope,
-            Err(TryRecvError::Empty) => return Ok(()),
-            Err(TryRecvError::Disconnected) => return Err(TryEventError::OutgoingDisconnected),
-        };
-
-        match envelope {
-            InternalEnvelope::Message { payload, .. } => {
-                self.try_send_connection_or_cache(payload, poll)
-            }
-            // won't be sent outgoing
-            InternalEnvelope::Shutdown => unreachable!(),
+        match self.outgoing.try_recv() {
+            Ok(payload) => self.try_send_connection_or_cache(payload, poll),
+            Err(TryRecvError::Empty) => Ok(()),
+            Err(TryRecvError::Disconnected) => Err(TryEventError::OutgoingDisconnected),
         }
     }
 
@@ -315,28 +349,14 @@ impl Entry {
             Ok(()) => {
                 // Return to readable only.
                 if self.write_evented_guard.replace(false) {
-                    poll.reregister(
-                        connection.evented(),
-                        self.connection_token,
-                        Ready::readable(),
-                        PollOpt::level(),
-                    )
-                    .map_err(TryEventError::IoError)?;
+                    self.reregister_connection(&**connection, poll)?;
                 }
                 Ok(())
             }
             Err(SendError::WouldBlock) => {
                 self.cached.replace(Some(payload));
-                if !*self.write_evented_guard.borrow() {
-                    poll.reregister(
-                        connection.evented(),
-                        self.connection_token,
-                        Ready::readable() | Ready::writable(),
-                        PollOpt::level(),
-                    )
-                    .map_err(TryEventError::IoError)?;
-
-                    self.write_evented_guard.replace(true);
+                if !self.write_evented_guard.replace(true) {
+                    self.reregister_connection(&**connection, poll)?;
                 }
 
                 Ok(())
@@ -347,41 +367,110 @@ impl Entry {
         }
     }
 
+    // The connection is readable unless it is holding a message for a full incoming lane, and
+    // writable while it has a message cached to send.
+    fn reregister_connection(
+        &self,
+        connection: &dyn Connection,
+        poll: &Poll,
+    ) -> Result<(), TryEventError> {
+        let mut interest = Ready::empty();
+        if !self.is_holding() {
+            interest |= Ready::readable();
+        }
+        if *self.write_evented_guard.borrow() {
+            interest |= Ready::writable();
+        }
+
+        poll.reregister(
+            connection.evented(),
+            self.connection_token,
+            interest,
+            PollOpt::level(),
+        )
+        .map_err(TryEventError::IoError)
+    }
+
+    // A message is read from the connection and queued in its incoming lane.  If the lane is
+    // full, the message is held and the connection is not read from until the message has been
+    // queued, so that no messages are dropped and the connection's messages stay in order.
     fn try_read_connection(
         &self,
-        incoming_tx: &crossbeam_channel::Sender<InternalEnvelope>,
+        incoming_tx: &IncomingSender,
+        poll: &Poll,
     ) -> Result<(), TryEventError> {
-        if !incoming_tx.is_full() {
-            let mut connection = match self.connection.try_borrow_mut() {
-                Ok(conn) => conn,
-                Err(_) => {
-                    error!("Attempting to mutably borrow connection {} again", self.id);
-                    return Ok(());
-                }
-            };
-            match connection.recv() {
-                Ok(payload) => {
-                    match incoming_tx.try_send(InternalEnvelope::Message {
+        if self.is_holding() {
+            return Ok(());
+        }
+
+        let mut connection = match self.connection.try_borrow_mut() {
+            Ok(conn) => conn,
+            Err(_) => {
+                error!("Attempting to mutably borrow connection {} again", self.id);
+                return Ok(());
+            }
+        };
+        match connection.recv() {
+            Ok(payload) => {
+                let priority = incoming_tx.classify(&payload);
+                match incoming_tx.try_send(
+                    InternalEnvelope::Message {
                         id: self.id,
                         payload,
-                    }) {
-                        Err(TrySendError::Full(_)) => {
-                            warn!("Dropped message due to full incoming queue");
-                            Ok(())
-                        }
-                        Err(TrySendError::Disconnected(_)) => {
-                            Err(TryEventError::IncomingDisconnected)
-                        }
-                        Ok(()) => Ok(()),
+                    },
+                    priority,
+                ) {
+                    Err(TrySendError::Full(envelope)) => {
+                        debug!(
+                            "Incoming {:?} queue is full; pausing reads from connection {}",
+                            priority, self.id
+                        );
+                        self.outgoing.record_incoming_blocked(priority);
+                        self.held.replace(Some((envelope, priority)));
+                        self.reregister_connection(&**connection, poll)
                     }
+                    Err(TrySendError::Disconnected(_)) => Err(TryEventError::IncomingDisconnected),
+                    Ok(()) => Ok(()),
                 }
-                Err(RecvError::WouldBlock) => Ok(()),
-                Err(RecvError::Disconnected) => Err(TryEventError::ConnectionDisconnected),
-                Err(RecvError::ProtocolError(err)) => Err(TryEventError::ProtocolError(err)),
-                Err(RecvError::IoError(err)) => Err(TryEventError::IoError(err)),
             }
-        } else {
-            Ok(())
+            Err(RecvError::WouldBlock) => Ok(()),
+            Err(RecvError::Disconnected) => Err(TryEventError::ConnectionDisconnected),
+            Err(RecvError::ProtocolError(err)) => Err(TryEventError::ProtocolError(err)),
+            Err(RecvError::IoError(err)) => Err(TryEventError::IoError(err)),
+        }
+    }
+
+    // Queue the held message if its incoming lane now has room, and resume reading from the
+    // connection.
+    fn try_queue_held(
+        &self,
+        incoming_tx: &IncomingSender,
+        poll: &Poll,
+    ) -> Result<(), TryEventError> {
+        let (envelope, priority) = match self.held.replace(None) {
+            Some(held) => held,
+            None => return Ok(()),
+        };
+
+        match incoming_tx.try_send(envelope, priority) {
+            Ok(()) => {
+                let connection = match self.connection.try_borrow() {
+                    Ok(conn) => conn,
+                    Err(_) => {
+                        error!(
+                            "Attempting to borrow connection {} while it is mutably borrowed",
+                            self.id
+                        );
+                        return Ok(());
+                    }
+                };
+                self.reregister_connection(&**connection, poll)
+            }
+            Err(TrySendError::Full(envelope)) => {
+                self.held.replace(Some((envelope, priority)));
+                Ok(())
+            }
+            Err(TrySendError::Disconnected(_)) => Err(TryEventError::IncomingDisconnected),
         }
     }
 }
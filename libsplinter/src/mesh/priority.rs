@@ -0,0 +1,124 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Message priority classes, and the schedule used to share a connection between them.
+
+/// The number of priority classes, each of which has its own queues in the mesh.
+pub(super) const LANES: usize = 3;
+
+/// The order in which the lanes are served, while all of them have messages queued.
+///
+/// This is a smooth weighted round robin over the weights 4:2:1, so that control messages are
+/// sent first, and application messages can delay neither control nor consensus messages, but
+/// are never starved by them.
+const SCHEDULE: [Priority; 7] = [
+    Priority::Control,
+    Priority::Consensus,
+    Priority::Control,
+    Priority::Application,
+    Priority::Control,
+    Priority::Consensus,
+    Priority::Control,
+];
+
+/// The priority class of a message sent or received over the mesh.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Priority {
+    /// Messages that keep connections alive and authorized, such as heartbeats.
+    Control,
+    /// Messages between nodes to agree on changes, such as admin consensus messages.
+    Consensus,
+    /// Messages between services.
+    Application,
+}
+
+impl Priority {
+    /// All priorities, from highest to lowest.
+    pub const ALL: [Priority; LANES] = [
+        Priority::Control,
+        Priority::Consensus,
+        Priority::Application,
+    ];
+
+    pub(super) fn index(self) -> usize {
+        match self {
+            Priority::Control => 0,
+            Priority::Consensus => 1,
+            Priority::Application => 2,
+        }
+    }
+}
+
+/// A function that returns the priority class of a message's payload.
+pub type PriorityClassifier = fn(&[u8]) -> Priority;
+
+/// The classifier used by a mesh that was not given one, which places every message in the
+/// application lane.
+pub(super) fn default_classifier(_payload: &[u8]) -> Priority {
+    Priority::Application
+}
+
+/// Returns the order in which the lanes should be checked for the given turn: the lane that is
+/// scheduled for the turn, followed by the others from highest to lowest priority, so that no
+/// turn is wasted on an empty lane.
+pub(super) fn lane_order(turn: usize) -> [Priority; LANES] {
+    let scheduled = SCHEDULE[turn % SCHEDULE.len()];
+    let mut order = [scheduled; LANES];
+    let mut others = Priority::ALL
+        .iter()
+        .filter(|priority| **priority != scheduled);
+    for lane in order.iter_mut().skip(1) {
+        if let Some(priority) = others.next() {
+            *lane = *priority;
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test that every turn checks each lane exactly once, starting with the scheduled lane, and
+    // that the lanes are scheduled in proportion to their weights.
+    #[test]
+    fn test_lane_order() {
+        assert_eq!(
+            lane_order(0),
+            [
+                Priority::Control,
+                Priority::Consensus,
+                Priority::Application
+            ]
+        );
+        assert_eq!(
+            lane_order(3),
+            [
+                Priority::Application,
+                Priority::Control,
+                Priority::Consensus
+            ]
+        );
+
+        let mut turns = [0; LANES];
+        for turn in 0..70 {
+            let order = lane_order(turn);
+            for priority in Priority::ALL.iter() {
+                assert_eq!(order.iter().filter(|p| *p == priority).count(), 1);
+            }
+            turns[order[0].index()] += 1;
+        }
+        assert_eq!(turns, [40, 20, 10]);
+    }
+}
@@ -23,10 +23,10 @@ use crate::mesh::{
         AddError, AddRequest, AddResponse, Control, ControlRequest, RemoveError, RemoveRequest,
         RemoveResponse,
     },
-    incoming::Incoming,
-    outgoing::Outgoing,
+    incoming::{self, Incoming, IncomingSender},
+    outgoing::{self, Outgoing},
     pool::Pool,
-    InternalEnvelope,
+    priority::PriorityClassifier,
 };
 use crate::transport::Connection;
 
@@ -37,7 +37,7 @@ pub struct Reactor {
     pool: Pool,
     ctrl_rx: mio_channel::Receiver<ControlRequest>,
     ctrl_token: Token,
-    incoming_tx: crossbeam_channel::Sender<InternalEnvelope>,
+    incoming_tx: IncomingSender,
     outgoing_capacity: usize,
 }
 
@@ -49,7 +49,7 @@ enum Turn {
 impl Reactor {
     fn new(
         ctrl_rx: mio_channel::Receiver<ControlRequest>,
-        incoming_tx: crossbeam_channel::Sender<InternalEnvelope>,
+        incoming_tx: IncomingSender,
         outgoing_capacity: usize,
     ) -> Self {
         let mut pool = Pool::new();
@@ -67,9 +67,13 @@ impl Reactor {
         }
     }
 
-    pub(super) fn spawn(incoming_capacity: usize, outgoing_capacity: usize) -> (Control, Incoming) {
+    pub(super) fn spawn(
+        incoming_capacity: usize,
+        outgoing_capacity: usize,
+        classifier: PriorityClassifier,
+    ) -> (Control, Incoming) {
         let (ctrl_tx, ctrl_rx) = mio_channel::channel();
-        let (incoming_tx, incoming_rx) = crossbeam_channel::bounded(incoming_capacity);
+        let (incoming_tx, incoming_rx) = incoming::channel(incoming_capacity, classifier);
 
         thread::Builder::new()
             .name(String::from("mesh::Reactor"))
@@ -79,7 +83,7 @@ impl Reactor {
             })
             .expect("Failed to spawn mesh::Reactor thread");
 
-        (Control::new(ctrl_tx), Incoming::new(incoming_rx))
+        (Control::new(ctrl_tx), incoming_rx)
     }
 
     fn run(&mut self) {
@@ -98,6 +102,8 @@ impl Reactor {
             return Turn::Shutdown;
         }
 
+        self.pool.retry_held(&self.incoming_tx);
+
         for event in events.iter() {
             if let Turn::Shutdown = self.handle_event(&event) {
                 return Turn::Shutdown;
@@ -137,7 +143,7 @@ impl Reactor {
                 Turn::Continue
             }
             Ok(ControlRequest::Shutdown) => {
-                if self.incoming_tx.send(InternalEnvelope::Shutdown).is_err() {
+                if self.incoming_tx.send_shutdown().is_err() {
                     error!("Unable to send shutdown envelope to Mesh")
                 }
                 Turn::Shutdown
@@ -148,10 +154,10 @@ impl Reactor {
     }
 
     fn add_connection(&mut self, connection: Box<dyn Connection>) -> AddResponse {
-        let (tx, rx) = mio_channel::sync_channel(self.outgoing_capacity);
+        let (senders, receiver) = outgoing::channel(self.outgoing_capacity);
 
-        match self.pool.add(connection, rx) {
-            Ok(id) => Ok(Outgoing::new(id, tx)),
+        match self.pool.add(connection, receiver) {
+            Ok(id) => Ok(Outgoing::new(id, senders)),
             Err(err) => Err(AddError::Io(err)),
         }
     }
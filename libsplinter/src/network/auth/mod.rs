@@ -16,7 +16,7 @@ mod connection_manager;
 mod handlers;
 mod pool;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::sync::{mpsc, Arc, Mutex};
 
@@ -115,6 +115,8 @@ impl fmt::Display for AuthorizationManagerError {
 /// Manages authorization states for connections on a network.
 pub struct AuthorizationManager {
     local_identity: String,
+    verified_identities: Arc<HashSet<String>>,
+    reserved_identities: Arc<HashSet<String>>,
     thread_pool: ThreadPool,
     shared: Arc<Mutex<ManagedAuthorizations>>,
 }
@@ -134,9 +136,37 @@ impl AuthorizationManager {
             thread_pool,
             shared,
             local_identity,
+            verified_identities: Arc::new(HashSet::new()),
+            reserved_identities: Arc::new(HashSet::new()),
         })
     }
 
+    /// Requires that connections claiming any of the given identities have had that identity
+    /// verified by the transport, such as by the common name of a TLS client certificate.
+    ///
+    /// Connections that claim one of these identities without a matching verified identity are
+    /// unauthorized.  Claims of any other identity are unaffected.
+    pub fn with_verified_identities<I>(mut self, identities: I) -> Self
+    where
+        I: IntoIterator<Item = String>,
+    {
+        self.verified_identities = Arc::new(identities.into_iter().collect());
+        self
+    }
+
+    /// Reserves the given identities for connections that are authorized elsewhere, such as the
+    /// node's in-process service connections.
+    ///
+    /// Connections authorized by this manager that claim one of these identities are always
+    /// unauthorized, regardless of what the transport has verified.
+    pub fn with_reserved_identities<I>(mut self, identities: I) -> Self
+    where
+        I: IntoIterator<Item = String>,
+    {
+        self.reserved_identities = Arc::new(identities.into_iter().collect());
+        self
+    }
+
     pub fn shutdown_signaler(&self) -> ShutdownSignaler {
         ShutdownSignaler {
             thread_pool_signaler: self.thread_pool.shutdown_signaler(),
@@ -150,6 +180,8 @@ impl AuthorizationManager {
     pub fn authorization_connector(&self) -> AuthorizationConnector {
         AuthorizationConnector {
             local_identity: self.local_identity.clone(),
+            verified_identities: Arc::clone(&self.verified_identities),
+            reserved_identities: Arc::clone(&self.reserved_identities),
             shared: Arc::clone(&self.shared),
             executor: self.thread_pool.executor(),
         }
@@ -171,6 +203,8 @@ type Callback =
 
 pub struct AuthorizationConnector {
     local_identity: String,
+    verified_identities: Arc<HashSet<String>>,
+    reserved_identities: Arc<HashSet<String>>,
     shared: Arc<Mutex<ManagedAuthorizations>>,
     executor: pool::JobExecutor,
 }
@@ -192,6 +226,8 @@ impl AuthorizationConnector {
         let msg_sender = AuthorizationMessageSender { sender: tx };
         let dispatcher =
             create_authorization_dispatcher(self.local_identity.clone(), state_machine, msg_sender);
+        let verified_identities = Arc::clone(&self.verified_identities);
+        let reserved_identities = Arc::clone(&self.reserved_identities);
         self.executor.execute(move || {
             let connect_request_bytes = match connect_msg_bytes() {
                 Ok(bytes) => bytes,
@@ -275,6 +311,24 @@ impl AuthorizationConnector {
                 }
             };
 
+            let authed_identity = authed_identity.filter(|identity| {
+                match check_claimed_identity(
+                    identity,
+                    connection.peer_identity().as_deref(),
+                    &verified_identities,
+                    &reserved_identities,
+                ) {
+                    Ok(()) => true,
+                    Err(reason) => {
+                        warn!(
+                            "Connection {} claimed identity {} {}; unauthorizing",
+                            &connection_id, identity, reason
+                        );
+                        false
+                    }
+                }
+            });
+
             let auth_state = if let Some(identity) = authed_identity {
                 ConnectionAuthorizationState::Authorized {
                     connection_id,
@@ -297,6 +351,25 @@ impl AuthorizationConnector {
     }
 }
 
+/// Checks whether a connection may be authorized with the identity it claimed, returning the
+/// reason if it may not.
+fn check_claimed_identity(
+    identity: &str,
+    peer_identity: Option<&str>,
+    verified_identities: &HashSet<String>,
+    reserved_identities: &HashSet<String>,
+) -> Result<(), &'static str> {
+    if reserved_identities.contains(identity) {
+        return Err("that is reserved for internal connections");
+    }
+
+    if verified_identities.contains(identity) && peer_identity != Some(identity) {
+        return Err("without verifying it");
+    }
+
+    Ok(())
+}
+
 fn connect_msg_bytes() -> Result<Vec<u8>, AuthorizationManagerError> {
     let mut network_msg = NetworkMessage::new();
     network_msg.set_message_type(NetworkMessageType::AUTHORIZATION);
@@ -496,6 +569,30 @@ pub(in crate::network) mod tests {
         }
     }
 
+    /// Verify that claimed identities are only accepted when they are neither reserved for
+    /// internal connections nor required to be verified by a transport that did not verify them.
+    #[test]
+    fn test_check_claimed_identity() {
+        let verified = ["verified".to_string()]
+            .iter()
+            .cloned()
+            .collect::<HashSet<_>>();
+        let reserved = ["admin::node".to_string()]
+            .iter()
+            .cloned()
+            .collect::<HashSet<_>>();
+
+        assert!(check_claimed_identity("other", None, &verified, &reserved).is_ok());
+        assert!(check_claimed_identity("verified", Some("verified"), &verified, &reserved).is_ok());
+        assert!(check_claimed_identity("verified", None, &verified, &reserved).is_err());
+        assert!(check_claimed_identity("verified", Some("other"), &verified, &reserved).is_err());
+        assert!(check_claimed_identity("admin::node", None, &verified, &reserved).is_err());
+        assert!(
+            check_claimed_identity("admin::node", Some("admin::node"), &verified, &reserved)
+                .is_err()
+        );
+    }
+
     pub(in crate::network) fn negotiation_connection_auth(
         mesh: &Mesh,
         connection_id: &str,
@@ -14,6 +14,7 @@
 
 use std::sync::mpsc::{channel, Sender};
 use std::thread;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 use protobuf::Message;
 
@@ -251,6 +252,21 @@ fn handle_request<T: ConnectionMatrixLifeCycle, U: ConnectionMatrixSender>(
                 warn!("connector dropped before receiving result of list connections");
             }
         }
+        CmRequest::ListConnectionDetails { sender } => {
+            if sender
+                .send(Ok(state
+                    .connection_metadata()
+                    .values()
+                    .map(|metadata| metadata.details())
+                    .collect()))
+                .is_err()
+            {
+                warn!("connector dropped before receiving result of list connection details");
+            }
+        }
+        CmRequest::HeartbeatReply { identity, sent_at } => {
+            state.on_heartbeat_reply(&identity, sent_at, subscribers)
+        }
         CmRequest::AddInboundConnection { sender, connection } => {
             state.add_inbound_connection(connection, sender, internal_sender, authorizer)
         }
@@ -302,7 +318,13 @@ fn send_heartbeats<T: ConnectionMatrixLifeCycle, U: ConnectionMatrixSender>(
     authorizer: &dyn Authorizer,
     internal_sender: Sender<CmMessage>,
 ) {
-    let heartbeat_message = match create_heartbeat() {
+    // The time is only used to match replies to the heartbeat they answer, so it does not matter
+    // if the clock is wrong; the round-trip time is measured with a monotonic clock
+    let sent_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_micros() as u64)
+        .unwrap_or(0);
+    let heartbeat_message = match create_heartbeat(sent_at) {
         Ok(h) => h,
         Err(err) => {
             error!("Failed to create heartbeat message: {:?}", err);
@@ -327,6 +349,7 @@ fn send_heartbeats<T: ConnectionMatrixLifeCycle, U: ConnectionMatrixSender>(
                     }
                 } else {
                     trace!("Sending heartbeat to {}", endpoint);
+                    metadata.pending_heartbeat = Some((sent_at, Instant::now()));
                     if let Err(err) = matrix_sender
                         .send(metadata.connection_id.clone(), heartbeat_message.clone())
                     {
@@ -347,6 +370,7 @@ fn send_heartbeats<T: ConnectionMatrixLifeCycle, U: ConnectionMatrixSender>(
                 ref mut disconnected,
             } => {
                 trace!("Sending heartbeat to {}", endpoint);
+                metadata.pending_heartbeat = Some((sent_at, Instant::now()));
                 if let Err(err) =
                     matrix_sender.send(metadata.connection_id.clone(), heartbeat_message.clone())
                 {
@@ -381,9 +405,11 @@ fn send_heartbeats<T: ConnectionMatrixLifeCycle, U: ConnectionMatrixSender>(
     }
 }
 
-/// Creates NetworkHeartbeat message and serializes it into a byte array.
-fn create_heartbeat() -> Result<Vec<u8>, ConnectionManagerError> {
-    let heartbeat = NetworkHeartbeat::new().write_to_bytes().map_err(|_| {
+/// Creates NetworkHeartbeat message, sent at the given time, and serializes it into a byte array.
+fn create_heartbeat(sent_at: u64) -> Result<Vec<u8>, ConnectionManagerError> {
+    let mut heartbeat = NetworkHeartbeat::new();
+    heartbeat.set_sent_at(sent_at);
+    let heartbeat = heartbeat.write_to_bytes().map_err(|_| {
         ConnectionManagerError::HeartbeatError("cannot create NetworkHeartbeat message".to_string())
     })?;
     let mut heartbeat_message = NetworkMessage::new();
@@ -0,0 +1,103 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Round-trip latency statistics for connections, measured from heartbeat replies.
+
+use std::cmp::{max, min};
+use std::time::Duration;
+
+/// The round-trip time and jitter of a connection, measured from the replies to the heartbeats
+/// sent over it.
+///
+/// The smoothed round-trip time is an exponentially weighted moving average of the samples, and
+/// the jitter is the mean deviation between consecutive samples, both as in RFC 6298 and RFC 3550.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConnectionLatency {
+    rtt: Duration,
+    smoothed_rtt: Duration,
+    jitter: Duration,
+    samples: u64,
+}
+
+impl ConnectionLatency {
+    /// Creates the latency of a connection from its first round-trip time sample.
+    pub(super) fn new(rtt: Duration) -> Self {
+        Self {
+            rtt,
+            smoothed_rtt: rtt,
+            jitter: Duration::from_secs(0),
+            samples: 1,
+        }
+    }
+
+    /// Adds a round-trip time sample.
+    pub(super) fn update(&mut self, rtt: Duration) {
+        let deviation = max(rtt, self.rtt) - min(rtt, self.rtt);
+
+        self.jitter = self.jitter * 15 / 16 + deviation / 16;
+        self.smoothed_rtt = self.smoothed_rtt * 7 / 8 + rtt / 8;
+        self.rtt = rtt;
+        self.samples += 1;
+    }
+
+    /// Returns the most recent round-trip time sample.
+    pub fn rtt(&self) -> Duration {
+        self.rtt
+    }
+
+    /// Returns the smoothed round-trip time.
+    pub fn smoothed_rtt(&self) -> Duration {
+        self.smoothed_rtt
+    }
+
+    /// Returns the jitter of the round-trip time.
+    pub fn jitter(&self) -> Duration {
+        self.jitter
+    }
+
+    /// Returns the number of round-trip time samples that have been taken.
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that a steady round-trip time has no jitter, and that a change in it is reflected
+    /// gradually in the smoothed round-trip time and the jitter.
+    #[test]
+    fn test_latency_update() {
+        let mut latency = ConnectionLatency::new(Duration::from_millis(80));
+        latency.update(Duration::from_millis(80));
+
+        assert_eq!(latency.rtt(), Duration::from_millis(80));
+        assert_eq!(latency.smoothed_rtt(), Duration::from_millis(80));
+        assert_eq!(latency.jitter(), Duration::from_secs(0));
+        assert_eq!(latency.samples(), 2);
+
+        latency.update(Duration::from_millis(160));
+
+        assert_eq!(latency.rtt(), Duration::from_millis(160));
+        assert_eq!(latency.smoothed_rtt(), Duration::from_millis(90));
+        assert_eq!(latency.jitter(), Duration::from_millis(5));
+
+        latency.update(Duration::from_millis(80));
+
+        assert_eq!(latency.smoothed_rtt(), Duration::from_micros(88_750));
+        assert_eq!(latency.jitter(), Duration::from_nanos(9_687_500));
+        assert_eq!(latency.samples(), 4);
+    }
+}
@@ -15,6 +15,7 @@
 pub mod authorizers;
 mod builder;
 mod error;
+mod latency;
 mod notification;
 
 use std::cmp::min;
@@ -25,8 +26,10 @@ use std::time::Instant;
 
 use uuid::Uuid;
 
+pub use crate::threading::pacemaker::IntervalHandle;
 pub use builder::ConnectionManagerBuilder;
 pub use error::{AuthorizerError, ConnectionManagerError};
+pub use latency::ConnectionLatency;
 pub use notification::ConnectionManagerNotification;
 
 use crate::threading::pacemaker;
@@ -126,6 +129,13 @@ enum CmRequest {
     ListConnections {
         sender: Sender<Result<Vec<String>, ConnectionManagerError>>,
     },
+    ListConnectionDetails {
+        sender: Sender<Result<Vec<ConnectionDetails>, ConnectionManagerError>>,
+    },
+    HeartbeatReply {
+        identity: String,
+        sent_at: u64,
+    },
     AddInboundConnection {
         connection: Box<dyn Connection>,
         sender: Sender<Result<(), ConnectionManagerError>>,
@@ -179,6 +189,12 @@ impl ConnectionManager {
         }
     }
 
+    /// Returns a handle that can change the heartbeat interval while the connection manager is
+    /// running.
+    pub fn heartbeat_interval_handle(&self) -> IntervalHandle {
+        self.pacemaker.interval_handle()
+    }
+
     pub fn shutdown_signaler(&self) -> ShutdownSignaler {
         ShutdownSignaler {
             sender: self.sender.clone(),
@@ -371,6 +387,64 @@ impl Connector {
         })?
     }
 
+    /// List the connections available to this Connector instance, with their details.
+    ///
+    /// # Returns
+    ///
+    /// Returns a vector of the details of each connection, including its latency, if it has been
+    /// measured.
+    ///
+    /// # Errors
+    ///
+    /// Returns a ConnectionManagerError if the connections cannot be queried.
+    pub fn list_connection_details(
+        &self,
+    ) -> Result<Vec<ConnectionDetails>, ConnectionManagerError> {
+        let (sender, recv) = channel();
+        self.sender
+            .send(CmMessage::Request(CmRequest::ListConnectionDetails {
+                sender,
+            }))
+            .map_err(|_| {
+                ConnectionManagerError::SendMessageError(
+                    "The connection manager is no longer running".into(),
+                )
+            })?;
+
+        recv.recv().map_err(|_| {
+            ConnectionManagerError::SendMessageError(
+                "The connection manager is no longer running".into(),
+            )
+        })?
+    }
+
+    /// Report a reply to a heartbeat, received from the connection with the given identity.
+    ///
+    /// The `sent_at` value is the time echoed by the reply. If it matches the last heartbeat sent
+    /// to a connection with that identity, the time since that heartbeat was sent is recorded as
+    /// a round-trip time sample for the connection, and a
+    /// `ConnectionManagerNotification::LatencyUpdated` is sent to subscribers.
+    ///
+    /// # Errors
+    ///
+    /// Returns a ConnectionManagerError if the connection manager is no longer running.
+    pub fn record_heartbeat_reply(
+        &self,
+        identity: &str,
+        sent_at: u64,
+    ) -> Result<(), ConnectionManagerError> {
+        self.sender
+            .send(CmMessage::Request(CmRequest::HeartbeatReply {
+                identity: identity.to_string(),
+                sent_at,
+            }))
+            .map_err(|_| {
+                ConnectionManagerError::SendMessageError(
+                    "The connection manager is no longer running".into(),
+                )
+            })
+    }
+
     /// Add a new inbound connection.
     ///
     /// # Error
@@ -419,6 +493,45 @@ impl ShutdownSignaler {
     }
 }
 
+/// The details of a connection managed by the connection manager.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConnectionDetails {
+    endpoint: String,
+    connection_id: String,
+    identity: String,
+    outbound: bool,
+    latency: Option<ConnectionLatency>,
+}
+
+impl ConnectionDetails {
+    /// Returns the endpoint of the connection.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Returns the ID of the connection.
+    pub fn connection_id(&self) -> &str {
+        &self.connection_id
+    }
+
+    /// Returns the identity of the remote end of the connection.
+    pub fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    /// Returns whether the connection was requested by this connection manager, rather than
+    /// accepted from a remote end.
+    pub fn is_outbound(&self) -> bool {
+        self.outbound
+    }
+
+    /// Returns the latency of the connection, or `None` if no heartbeat sent over it has been
+    /// replied to.
+    pub fn latency(&self) -> Option<&ConnectionLatency> {
+        self.latency.as_ref()
+    }
+}
+
 /// Metadata describing a connection managed by the connection manager.
 #[derive(Clone, Debug)]
 struct ConnectionMetadata {
@@ -426,6 +539,9 @@ struct ConnectionMetadata {
     endpoint: String,
     identity: String,
     extended_metadata: ConnectionMetadataExt,
+    // the time value of the last heartbeat sent to the connection, and when it was sent
+    pending_heartbeat: Option<(u64, Instant)>,
+    latency: Option<ConnectionLatency>,
 }
 
 impl ConnectionMetadata {
@@ -444,6 +560,16 @@ impl ConnectionMetadata {
     fn identity(&self) -> &str {
         &self.identity
     }
+
+    fn details(&self) -> ConnectionDetails {
+        ConnectionDetails {
+            endpoint: self.endpoint.clone(),
+            connection_id: self.connection_id.clone(),
+            identity: self.identity.clone(),
+            outbound: self.is_outbound(),
+            latency: self.latency,
+        }
+    }
 }
 
 /// Enum describing metadata that is specific to the two different connection
@@ -672,6 +798,8 @@ where
                             last_connection_attempt: Instant::now(),
                             reconnection_attempts: 0,
                         },
+                        pending_heartbeat: None,
+                        latency: None,
                     },
                 );
 
@@ -736,6 +864,8 @@ where
                         extended_metadata: ConnectionMetadataExt::Inbound {
                             disconnected: false,
                         },
+                        pending_heartbeat: None,
+                        latency: None,
                     },
                 );
 
@@ -873,6 +1003,49 @@ where
         Ok(())
     }
 
+    /// Records a round-trip time sample for each connection with the given identity whose last
+    /// heartbeat was sent at the given time, and notifies subscribers of its updated latency.
+    ///
+    /// Replies to earlier heartbeats are ignored, as the time they took is no longer known.
+    fn on_heartbeat_reply(
+        &mut self,
+        identity: &str,
+        sent_at: u64,
+        subscribers: &mut SubscriberMap,
+    ) {
+        for (endpoint, metadata) in self.connections.iter_mut() {
+            if metadata.identity != identity {
+                continue;
+            }
+
+            let rtt = match metadata.pending_heartbeat {
+                Some((pending_sent_at, instant)) if pending_sent_at == sent_at => instant.elapsed(),
+                _ => continue,
+            };
+            metadata.pending_heartbeat = None;
+
+            let latency = match metadata.latency {
+                Some(ref mut latency) => {
+                    latency.update(rtt);
+                    *latency
+                }
+                None => *metadata.latency.get_or_insert(ConnectionLatency::new(rtt)),
+            };
+            trace!(
+                "Heartbeat round-trip time to {} was {:?} (jitter {:?})",
+                endpoint,
+                rtt,
+                latency.jitter()
+            );
+
+            subscribers.broadcast(ConnectionManagerNotification::LatencyUpdated {
+                endpoint: endpoint.clone(),
+                identity: identity.to_string(),
+                latency,
+            });
+        }
+    }
+
     fn connection_metadata(&self) -> &HashMap<String, ConnectionMetadata> {
         &self.connections
     }
@@ -895,7 +1068,7 @@ mod tests {
     use crate::mesh::Mesh;
     use crate::network::auth::tests::negotiation_connection_auth;
     use crate::network::auth::AuthorizationManager;
-    use crate::protos::network::{NetworkMessage, NetworkMessageType};
+    use crate::protos::network::{NetworkHeartbeat, NetworkMessage, NetworkMessageType};
     use crate::transport::inproc::InprocTransport;
     use crate::transport::socket::TcpTransport;
 
@@ -1018,6 +1191,87 @@ mod tests {
         cm.await_shutdown();
     }
 
+    /// Test that a reply to the last heartbeat sent to a connection is recorded as a sample of
+    /// its round-trip time, which is broadcast to subscribers and included in the connection's
+    /// details, and that a repeated reply is ignored.
+    #[test]
+    fn test_heartbeat_latency() {
+        let mut transport = Box::new(InprocTransport::default());
+        let mut listener = transport.listen("inproc://test").unwrap();
+        let mesh = Mesh::new(512, 128);
+        let mesh_clone = mesh.clone();
+
+        thread::spawn(move || {
+            let conn = listener.accept().unwrap();
+            mesh_clone.add(conn, "test_id".to_string()).unwrap();
+        });
+
+        let cm = ConnectionManager::builder()
+            .with_authorizer(Box::new(NoopAuthorizer::new("test_identity")))
+            .with_matrix_life_cycle(mesh.get_life_cycle())
+            .with_matrix_sender(mesh.get_sender())
+            .with_transport(transport)
+            .start()
+            .expect("Unable to start Connection Manager");
+
+        let connector = cm.connector();
+        let (subs_tx, subs_rx) = mpsc::channel();
+        connector
+            .subscribe(subs_tx)
+            .expect("Unable to subscribe to notifications");
+
+        connector
+            .request_connection("inproc://test", "test_id")
+            .expect("A connection could not be created");
+
+        let envelope = mesh.recv().unwrap();
+        let network_msg: NetworkMessage = protobuf::parse_from_bytes(&envelope.payload()).unwrap();
+        let heartbeat: NetworkHeartbeat =
+            protobuf::parse_from_bytes(network_msg.get_payload()).unwrap();
+        assert_ne!(heartbeat.get_sent_at(), 0);
+        assert!(!heartbeat.get_reply());
+
+        connector
+            .record_heartbeat_reply("test_identity", heartbeat.get_sent_at())
+            .expect("Unable to record heartbeat reply");
+
+        let latency = loop {
+            match subs_rx
+                .recv_timeout(std::time::Duration::from_secs(2))
+                .expect("Did not receive a latency notification")
+            {
+                ConnectionManagerNotification::LatencyUpdated {
+                    endpoint,
+                    identity,
+                    latency,
+                } => {
+                    assert_eq!(endpoint, "inproc://test");
+                    assert_eq!(identity, "test_identity");
+                    break latency;
+                }
+                _ => continue,
+            }
+        };
+        assert_eq!(latency.samples(), 1);
+
+        // a second reply to the same heartbeat is ignored
+        connector
+            .record_heartbeat_reply("test_identity", heartbeat.get_sent_at())
+            .expect("Unable to record heartbeat reply");
+
+        let details = connector
+            .list_connection_details()
+            .expect("Unable to list connection details");
+        assert_eq!(details.len(), 1);
+        assert_eq!(details[0].endpoint(), "inproc://test");
+        assert_eq!(details[0].identity(), "test_identity");
+        assert!(details[0].is_outbound());
+        assert_eq!(details[0].latency(), Some(&latency));
+
+        cm.shutdown_signaler().shutdown();
+        cm.await_shutdown();
+    }
+
     /// Test that heartbeats are correctly sent to tcp connections
     #[test]
     fn test_heartbeat_raw_tcp() {
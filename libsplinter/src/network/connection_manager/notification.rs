@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use super::error::ConnectionManagerError;
+use super::latency::ConnectionLatency;
 
 /// Messages that will be dispatched to all subscription handlers
 #[derive(Debug, PartialEq, Clone)]
@@ -40,4 +41,9 @@ pub enum ConnectionManagerNotification {
         attempts: u64,
         identity: String,
     },
+    LatencyUpdated {
+        endpoint: String,
+        identity: String,
+        latency: ConnectionLatency,
+    },
 }
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::network::connection_manager::Connector;
 use crate::network::dispatch::{DispatchError, Handler, MessageContext, MessageSender, PeerId};
 use crate::protos::network::{NetworkEcho, NetworkHeartbeat, NetworkMessage, NetworkMessageType};
 
@@ -77,8 +78,14 @@ impl NetworkEchoHandler {
 }
 
 // Implements a handler that handles NetworkHeartbeat Messages
+//
+// Heartbeats that carry the time they were sent are replied to with the same time, so that the
+// sender can measure the round-trip time of its connection. If the handler has a connector,
+// replies to this node's heartbeats are reported to its connection manager.
 #[derive(Default)]
-pub struct NetworkHeartbeatHandler {}
+pub struct NetworkHeartbeatHandler {
+    connector: Option<Connector>,
+}
 
 impl Handler for NetworkHeartbeatHandler {
     type Source = PeerId;
@@ -91,18 +98,59 @@ impl Handler for NetworkHeartbeatHandler {
 
     fn handle(
         &self,
-        _msg: Self::Message,
+        mut msg: Self::Message,
         context: &MessageContext<Self::Source, Self::MessageType>,
-        _sender: &dyn MessageSender<Self::Source>,
+        sender: &dyn MessageSender<Self::Source>,
     ) -> Result<(), DispatchError> {
         trace!("Received Heartbeat from {}", context.source_peer_id());
+
+        if msg.get_sent_at() == 0 {
+            return Ok(());
+        }
+
+        if msg.get_reply() {
+            if let Some(connector) = &self.connector {
+                connector
+                    .record_heartbeat_reply(context.source_peer_id(), msg.get_sent_at())
+                    .map_err(|err| DispatchError::HandleError(err.to_string()))?;
+            }
+            return Ok(());
+        }
+
+        msg.set_reply(true);
+        let heartbeat_bytes = msg
+            .write_to_bytes()
+            .map_err(|err| DispatchError::SerializationError(err.to_string()))?;
+
+        let mut network_msg = NetworkMessage::new();
+        network_msg.set_message_type(NetworkMessageType::NETWORK_HEARTBEAT);
+        network_msg.set_payload(heartbeat_bytes);
+        let network_msg_bytes = network_msg
+            .write_to_bytes()
+            .map_err(|err| DispatchError::SerializationError(err.to_string()))?;
+
+        sender
+            .send(
+                context.source_peer_id().to_string().into(),
+                network_msg_bytes,
+            )
+            .map_err(|(recipient, payload)| {
+                DispatchError::NetworkSendError((recipient.into(), payload))
+            })?;
         Ok(())
     }
 }
 
 impl NetworkHeartbeatHandler {
     pub fn new() -> Self {
-        NetworkHeartbeatHandler {}
+        NetworkHeartbeatHandler { connector: None }
+    }
+
+    /// Report the replies to this node's heartbeats to the connection manager of the given
+    /// connector, which uses them to measure the round-trip time of its connections.
+    pub fn with_connector(mut self, connector: Connector) -> Self {
+        self.connector = Some(connector);
+        self
     }
 }
 
@@ -157,6 +205,63 @@ mod tests {
         assert_eq!(echo.get_payload().to_vec(), b"HelloWorld".to_vec());
     }
 
+    /// Test that a heartbeat that carries the time it was sent is replied to with the same time,
+    /// and that heartbeats without a time, and replies, are not.
+    #[test]
+    fn heartbeat_reply() {
+        let network_sender = MockSender::new();
+        let mut dispatcher: Dispatcher<NetworkMessageType> =
+            Dispatcher::new(Box::new(network_sender.clone()));
+
+        dispatcher.set_handler(Box::new(NetworkHeartbeatHandler::new()));
+
+        let mut heartbeat = NetworkHeartbeat::new();
+        assert_eq!(
+            Ok(()),
+            dispatcher.dispatch(
+                "OTHER_PEER".into(),
+                &NetworkMessageType::NETWORK_HEARTBEAT,
+                heartbeat.write_to_bytes().unwrap()
+            )
+        );
+        assert!(network_sender.next_outbound().is_none());
+
+        heartbeat.set_sent_at(1_591_000_000_000_000);
+        assert_eq!(
+            Ok(()),
+            dispatcher.dispatch(
+                "OTHER_PEER".into(),
+                &NetworkMessageType::NETWORK_HEARTBEAT,
+                heartbeat.write_to_bytes().unwrap()
+            )
+        );
+
+        let (recipient, network_message) = network_sender
+            .next_outbound()
+            .expect("Unable to get expected message");
+        assert_eq!(&*recipient, "OTHER_PEER");
+
+        let network_msg: NetworkMessage = protobuf::parse_from_bytes(&network_message).unwrap();
+        assert_eq!(
+            network_msg.get_message_type(),
+            NetworkMessageType::NETWORK_HEARTBEAT
+        );
+        let reply: NetworkHeartbeat =
+            protobuf::parse_from_bytes(network_msg.get_payload()).unwrap();
+        assert_eq!(reply.get_sent_at(), 1_591_000_000_000_000);
+        assert!(reply.get_reply());
+
+        assert_eq!(
+            Ok(()),
+            dispatcher.dispatch(
+                "OTHER_PEER".into(),
+                &NetworkMessageType::NETWORK_HEARTBEAT,
+                reply.write_to_bytes().unwrap()
+            )
+        );
+        assert!(network_sender.next_outbound().is_none());
+    }
+
     #[derive(Clone)]
     struct MockSender {
         outbound: Arc<Mutex<VecDeque<(PeerId, Vec<u8>)>>>,
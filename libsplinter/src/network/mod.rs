@@ -16,4 +16,5 @@ pub mod auth;
 pub mod connection_manager;
 pub mod dispatch;
 pub mod handlers;
+pub mod priority;
 pub(crate) mod reply;
@@ -0,0 +1,188 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Classification of network messages into the mesh's priority classes.
+
+use std::convert::TryFrom;
+
+use protobuf::ProtobufEnum;
+
+use crate::mesh::Priority;
+use crate::protos::circuit::CircuitMessageType;
+use crate::protos::network::NetworkMessageType;
+
+/// Returns the priority of a serialized `NetworkMessage`, for use as a mesh's classifier.
+///
+/// Heartbeats, echoes, authorization messages, and circuit messages that manage service
+/// connections or report errors are control messages. Admin direct messages, which carry the
+/// admin service's consensus, are consensus messages. Circuit direct messages between services,
+/// and anything that cannot be parsed, are application messages.
+pub fn network_message_priority(payload: &[u8]) -> Priority {
+    let (message_type, payload) = match read_envelope(payload) {
+        Some(envelope) => envelope,
+        None => return Priority::Application,
+    };
+
+    match NetworkMessageType::from_i32(message_type) {
+        Some(NetworkMessageType::NETWORK_ECHO)
+        | Some(NetworkMessageType::NETWORK_HEARTBEAT)
+        | Some(NetworkMessageType::AUTHORIZATION) => Priority::Control,
+        Some(NetworkMessageType::CIRCUIT) => circuit_message_priority(payload),
+        _ => Priority::Application,
+    }
+}
+
+fn circuit_message_priority(payload: &[u8]) -> Priority {
+    let message_type = read_envelope(payload)
+        .and_then(|(message_type, _)| CircuitMessageType::from_i32(message_type));
+
+    match message_type {
+        Some(CircuitMessageType::ADMIN_DIRECT_MESSAGE) => Priority::Consensus,
+        Some(CircuitMessageType::CIRCUIT_DIRECT_MESSAGE)
+        | Some(CircuitMessageType::UNSET_CIRCUIT_MESSAGE_TYPE)
+        | None => Priority::Application,
+        Some(_) => Priority::Control,
+    }
+}
+
+/// Reads the type and payload of an envelope message, such as a `NetworkMessage` or a
+/// `CircuitMessage`, which has its type as field 1 and its payload as field 2.
+///
+/// The fields are read in place, rather than by parsing the message, so that classifying a
+/// message does not copy its payload.
+fn read_envelope(bytes: &[u8]) -> Option<(i32, &[u8])> {
+    let mut message_type = 0;
+    let mut payload: &[u8] = &[];
+
+    let mut rest = bytes;
+    while !rest.is_empty() {
+        let (key, len) = read_varint(rest)?;
+        rest = &rest[len..];
+
+        let field = key >> 3;
+        match key & 0x7 {
+            // varint
+            0 => {
+                let (value, len) = read_varint(rest)?;
+                rest = &rest[len..];
+                if field == 1 {
+                    message_type = value as i32;
+                }
+            }
+            // 64-bit
+            1 => rest = rest.get(8..)?,
+            // length-delimited
+            2 => {
+                let (length, len) = read_varint(rest)?;
+                rest = &rest[len..];
+                let length = usize::try_from(length).ok()?;
+                let value = rest.get(..length)?;
+                rest = &rest[length..];
+                if field == 2 {
+                    payload = value;
+                }
+            }
+            // 32-bit
+            5 => rest = rest.get(4..)?,
+            _ => return None,
+        }
+    }
+
+    Some((message_type, payload))
+}
+
+/// Reads a varint, returning its value and the number of bytes it was encoded in.
+fn read_varint(bytes: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0;
+    for (i, byte) in bytes.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use protobuf::Message;
+
+    use crate::protos::circuit::CircuitMessage;
+    use crate::protos::network::NetworkMessage;
+
+    // Test that network messages are classified by their type, and circuit messages by the type
+    // of the circuit message they contain
+    #[test]
+    fn test_network_message_priority() {
+        assert_eq!(
+            network_message_priority(&network_message(
+                NetworkMessageType::NETWORK_HEARTBEAT,
+                vec![]
+            )),
+            Priority::Control
+        );
+        assert_eq!(
+            network_message_priority(&network_message(
+                NetworkMessageType::AUTHORIZATION,
+                vec![1, 2, 3]
+            )),
+            Priority::Control
+        );
+        assert_eq!(
+            network_message_priority(&circuit_message(CircuitMessageType::ADMIN_DIRECT_MESSAGE)),
+            Priority::Consensus
+        );
+        assert_eq!(
+            network_message_priority(&circuit_message(CircuitMessageType::CIRCUIT_DIRECT_MESSAGE)),
+            Priority::Application
+        );
+        assert_eq!(
+            network_message_priority(&circuit_message(
+                CircuitMessageType::SERVICE_CONNECT_REQUEST
+            )),
+            Priority::Control
+        );
+    }
+
+    // Test that payloads that are not network messages are classified as application messages
+    #[test]
+    fn test_invalid_message_priority() {
+        assert_eq!(network_message_priority(&[]), Priority::Application);
+        assert_eq!(network_message_priority(&[0xff]), Priority::Application);
+        // A length-delimited field that is longer than the message
+        assert_eq!(
+            network_message_priority(&[0x08, 0x64, 0x12, 0x10, 0x08]),
+            Priority::Application
+        );
+    }
+
+    fn network_message(message_type: NetworkMessageType, payload: Vec<u8>) -> Vec<u8> {
+        let mut network_msg = NetworkMessage::new();
+        network_msg.set_message_type(message_type);
+        network_msg.set_payload(payload);
+        network_msg.write_to_bytes().unwrap()
+    }
+
+    fn circuit_message(message_type: CircuitMessageType) -> Vec<u8> {
+        let mut circuit_msg = CircuitMessage::new();
+        circuit_msg.set_message_type(message_type);
+        circuit_msg.set_payload(vec![0; 256]);
+        network_message(
+            NetworkMessageType::CIRCUIT,
+            circuit_msg.write_to_bytes().unwrap(),
+        )
+    }
+}
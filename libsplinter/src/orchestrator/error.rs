@@ -61,6 +61,7 @@ impl std::fmt::Display for OrchestratorError {
 
 #[derive(Debug)]
 pub enum InitializeServiceError {
+    AlreadyRunning,
     InitializationFailed(Box<dyn Error + Send>),
     LockPoisoned,
     UnknownType,
@@ -69,6 +70,7 @@ pub enum InitializeServiceError {
 impl Error for InitializeServiceError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
+            InitializeServiceError::AlreadyRunning => None,
             InitializeServiceError::InitializationFailed(err) => Some(&**err),
             InitializeServiceError::LockPoisoned => None,
             InitializeServiceError::UnknownType => None,
@@ -79,6 +81,7 @@ impl Error for InitializeServiceError {
 impl std::fmt::Display for InitializeServiceError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
+            InitializeServiceError::AlreadyRunning => write!(f, "service is already running"),
             InitializeServiceError::InitializationFailed(err) => {
                 write!(f, "failed to initialize service: {}", err)
             }
@@ -145,3 +148,40 @@ impl std::fmt::Display for ListServicesError {
         }
     }
 }
+
+#[cfg(feature = "service-supervisor")]
+#[derive(Debug)]
+pub enum ServiceProcessError {
+    InvalidRequest(Box<dyn Error + Send>),
+    ServiceFailed(Box<dyn Error + Send>),
+    ServicePanicked,
+    UnknownType(String),
+}
+
+#[cfg(feature = "service-supervisor")]
+impl Error for ServiceProcessError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ServiceProcessError::InvalidRequest(err) => Some(&**err),
+            ServiceProcessError::ServiceFailed(err) => Some(&**err),
+            ServiceProcessError::ServicePanicked => None,
+            ServiceProcessError::UnknownType(_) => None,
+        }
+    }
+}
+
+#[cfg(feature = "service-supervisor")]
+impl std::fmt::Display for ServiceProcessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ServiceProcessError::InvalidRequest(err) => {
+                write!(f, "invalid service process request: {}", err)
+            }
+            ServiceProcessError::ServiceFailed(err) => write!(f, "service failed: {}", err),
+            ServiceProcessError::ServicePanicked => write!(f, "service thread panicked"),
+            ServiceProcessError::UnknownType(service_type) => {
+                write!(f, "service type unknown: {}", service_type)
+            }
+        }
+    }
+}
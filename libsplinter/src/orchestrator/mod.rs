@@ -15,6 +15,8 @@
 mod error;
 #[cfg(feature = "rest-api")]
 mod rest_api;
+#[cfg(feature = "service-supervisor")]
+mod supervisor;
 
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -38,22 +40,40 @@ use crate::service::{
 };
 use crate::transport::Connection;
 
+#[cfg(feature = "service-supervisor")]
+pub use self::error::ServiceProcessError;
 pub use self::error::{
     InitializeServiceError, ListServicesError, NewOrchestratorError, OrchestratorError,
     ShutdownServiceError,
 };
+#[cfg(feature = "service-supervisor")]
+use self::supervisor::ServiceSupervisor;
+#[cfg(feature = "service-supervisor")]
+pub use self::supervisor::{run_service_process, ServiceProcessConfig, ServiceProcessRequest};
 
 // Recv timeout in secs
 const TIMEOUT_SEC: u64 = 2;
 
 /// Identifies a unique service instance from the perspective of the orchestrator
-#[derive(Clone, Eq, Hash, PartialEq, Debug)]
+#[derive(Clone, Eq, Hash, PartialEq, Debug, Serialize, Deserialize)]
 pub struct ServiceDefinition {
     pub circuit: String,
     pub service_id: String,
     pub service_type: String,
 }
 
+/// The lifecycle status of a service managed by the orchestrator
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum ServiceStatus {
+    /// The service is running
+    Running,
+    /// The service's process exited and is waiting to be restarted; `restarts` counts the restarts
+    /// of the service so far
+    Restarting { restarts: u32 },
+    /// The service's process exited too many times in a row and will not be restarted
+    Failed { restarts: u32 },
+}
+
 /// Stores a service and other structures that are used to manage it
 struct ManagedService {
     pub service: Box<dyn Service>,
@@ -72,6 +92,9 @@ pub struct ServiceOrchestrator {
     inbound_router: InboundRouter<CircuitMessageType>,
     /// `running` and `join_handles` are used to shutdown the orchestrator's background threads
     running: Arc<AtomicBool>,
+    /// Runs services in supervised child processes, if process supervision is enabled
+    #[cfg(feature = "service-supervisor")]
+    supervisor: Option<ServiceSupervisor>,
 }
 
 impl ServiceOrchestrator {
@@ -174,6 +197,8 @@ impl ServiceOrchestrator {
                 network_sender,
                 inbound_router,
                 running,
+                #[cfg(feature = "service-supervisor")]
+                supervisor: None,
             },
             JoinHandles::new(vec![
                 incoming_join_handle,
@@ -183,6 +208,19 @@ impl ServiceOrchestrator {
         ))
     }
 
+    /// Run services in supervised child processes instead of in this process.
+    ///
+    /// Each service initialized by the orchestrator is run by a child process started with the
+    /// given configuration, which connects back to the splinter node over the service transport.
+    /// Crashed processes are restarted with an exponential backoff, and the status of each
+    /// service is reported by `list_services`. The REST API resources of services are only
+    /// available for services that run in this process.
+    #[cfg(feature = "service-supervisor")]
+    pub fn with_process_supervision(mut self, config: ServiceProcessConfig) -> Self {
+        self.supervisor = Some(ServiceSupervisor::new(config));
+        self
+    }
+
     /// Initialize (create and start) a service according to the specified definition. The
     /// arguments provided must match those required to create the service.
     pub fn initialize_service(
@@ -201,6 +239,13 @@ impl ServiceOrchestrator {
             })
             .ok_or(InitializeServiceError::UnknownType)?;
 
+        #[cfg(feature = "service-supervisor")]
+        {
+            if let Some(supervisor) = &self.supervisor {
+                return supervisor.start_service(service_definition, args);
+            }
+        }
+
         // Create the service.
         let mut service = factory.create(
             service_definition.service_id.clone(),
@@ -234,15 +279,27 @@ impl ServiceOrchestrator {
         &self,
         service_definition: &ServiceDefinition,
     ) -> Result<(), ShutdownServiceError> {
-        let ManagedService {
-            mut service,
-            registry,
-        } = self
+        let managed_service = self
             .services
             .lock()
             .map_err(|_| ShutdownServiceError::LockPoisoned)?
-            .remove(service_definition)
-            .ok_or(ShutdownServiceError::UnknownService)?;
+            .remove(service_definition);
+
+        let ManagedService {
+            mut service,
+            registry,
+        } = match managed_service {
+            Some(managed_service) => managed_service,
+            None => {
+                #[cfg(feature = "service-supervisor")]
+                {
+                    if let Some(supervisor) = &self.supervisor {
+                        return supervisor.stop_service(service_definition);
+                    }
+                }
+                return Err(ShutdownServiceError::UnknownService);
+            }
+        };
 
         service.stop(&registry).map_err(|err| {
             ShutdownServiceError::ShutdownFailed((service_definition.clone(), Box::new(err)))
@@ -274,33 +331,49 @@ impl ServiceOrchestrator {
                 ShutdownServiceError::ShutdownFailed((service_definition, Box::new(err)))
             })?;
         }
+
+        #[cfg(feature = "service-supervisor")]
+        {
+            if let Some(supervisor) = &self.supervisor {
+                supervisor.stop_all_services()?;
+            }
+        }
+
         self.running.store(false, Ordering::SeqCst);
 
         Ok(())
     }
 
-    /// List services managed by this `ServiceOrchestrator`; filters may be provided to only show
-    /// services on specified circuit(s) and of given service type(s).
+    /// List services managed by this `ServiceOrchestrator`, along with their lifecycle status;
+    /// filters may be provided to only show services on specified circuit(s) and of given service
+    /// type(s).
     pub fn list_services(
         &self,
         circuits: Vec<String>,
         service_types: Vec<String>,
-    ) -> Result<Vec<ServiceDefinition>, ListServicesError> {
-        Ok(self
+    ) -> Result<Vec<(ServiceDefinition, ServiceStatus)>, ListServicesError> {
+        #[allow(unused_mut)]
+        let mut services = self
             .services
             .lock()
             .map_err(|_| ListServicesError::LockPoisoned)?
-            .iter()
-            .filter_map(|(service, _)| {
-                if (circuits.is_empty() || circuits.contains(&service.circuit))
+            .keys()
+            .map(|service| (service.clone(), ServiceStatus::Running))
+            .collect::<Vec<_>>();
+
+        #[cfg(feature = "service-supervisor")]
+        {
+            if let Some(supervisor) = &self.supervisor {
+                services.append(&mut supervisor.list_services()?);
+            }
+        }
+
+        Ok(services
+            .into_iter()
+            .filter(|(service, _)| {
+                (circuits.is_empty() || circuits.contains(&service.circuit))
                     && (service_types.is_empty() || service_types.contains(&service.service_type))
-                {
-                    Some(service)
-                } else {
-                    None
-                }
             })
-            .cloned()
             .collect())
     }
 
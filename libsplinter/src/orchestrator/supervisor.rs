@@ -0,0 +1,657 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Supervision of services that run in child processes.
+//!
+//! When process supervision is enabled, the `ServiceOrchestrator` does not run services in its
+//! own process. Instead, each service is run by a child process, which receives the service's
+//! definition and arguments as JSON on its standard input and connects back to the splinter node
+//! over the service transport (see [`run_service_process`]). If a child process exits, it is
+//! restarted with an exponential backoff.
+//!
+//! If the splinter node requires services to authenticate, the supervisor may be given a signer
+//! that is trusted by the node; each child process then receives a registration token for its
+//! service, which it presents when connecting.
+//!
+//! [`run_service_process`]: fn.run_service_process.html
+
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "service-auth")]
+use crate::service::ServiceRegistrationToken;
+use crate::service::{ServiceFactory, ServiceProcessor};
+#[cfg(feature = "service-auth")]
+use crate::signing::Signer;
+use crate::transport::Connection;
+
+use super::{
+    InitializeServiceError, ListServicesError, ServiceDefinition, ServiceProcessError,
+    ServiceStatus, ShutdownServiceError,
+};
+
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(60);
+// How often a supervisor thread checks whether its child process has exited
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// How long the registration token given to a service process is valid; the token is only used
+// when the process connects its service, and a new token is issued when the process restarts
+#[cfg(feature = "service-auth")]
+const REGISTRATION_TOKEN_VALIDITY: Duration = Duration::from_secs(300);
+
+const SERVICE_PROCESSOR_INCOMING_CAPACITY: usize = 8;
+const SERVICE_PROCESSOR_OUTGOING_CAPACITY: usize = 8;
+const SERVICE_PROCESSOR_CHANNEL_CAPACITY: usize = 8;
+
+/// Configuration of the child processes that run supervised services.
+#[derive(Clone)]
+pub struct ServiceProcessConfig {
+    program: PathBuf,
+    args: Vec<String>,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    max_restarts: Option<u32>,
+    #[cfg(feature = "service-auth")]
+    registration_signer: Option<Arc<dyn Signer + Send + Sync>>,
+}
+
+impl ServiceProcessConfig {
+    /// Creates a configuration that runs services with the given program.
+    ///
+    /// The program must read a `ServiceProcessRequest` from its standard input and run the
+    /// requested service; see [`run_service_process`].
+    ///
+    /// [`run_service_process`]: fn.run_service_process.html
+    pub fn new(program: &str) -> Self {
+        ServiceProcessConfig {
+            program: PathBuf::from(program),
+            args: vec![],
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            max_restarts: None,
+            #[cfg(feature = "service-auth")]
+            registration_signer: None,
+        }
+    }
+
+    /// Sets the command line arguments of the program.
+    pub fn with_args(mut self, args: Vec<String>) -> Self {
+        self.args = args;
+        self
+    }
+
+    /// Sets the delay before the first restart of a crashed service process, and the maximum
+    /// delay the backoff doubles up to. A process that ran for longer than the maximum delay
+    /// before exiting is restarted after the initial delay again.
+    pub fn with_backoff(mut self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Sets the number of consecutive restarts after which a service is marked as failed and is no
+    /// longer restarted. By default, services are restarted indefinitely.
+    pub fn with_max_restarts(mut self, max_restarts: u32) -> Self {
+        self.max_restarts = Some(max_restarts);
+        self
+    }
+
+    /// Sets the signer of the registration tokens given to service processes. The signer's public
+    /// key must be trusted by the splinter node's service authorization policy.
+    #[cfg(feature = "service-auth")]
+    pub fn with_registration_signer(mut self, signer: Arc<dyn Signer + Send + Sync>) -> Self {
+        self.registration_signer = Some(signer);
+        self
+    }
+}
+
+/// The request a service process receives on its standard input.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ServiceProcessRequest {
+    pub service_definition: ServiceDefinition,
+    pub args: HashMap<String, String>,
+    /// Registration token, in its string form, that the service presents when it connects
+    #[cfg(feature = "service-auth")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registration_token: Option<String>,
+}
+
+impl ServiceProcessRequest {
+    /// Reads a JSON `ServiceProcessRequest`, such as the standard input of a service process.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, ServiceProcessError> {
+        serde_json::from_reader(reader)
+            .map_err(|err| ServiceProcessError::InvalidRequest(Box::new(err)))
+    }
+}
+
+/// Runs the service described by the request until its connection to the splinter node is closed.
+///
+/// This is the entry point of a service process: the service is created by the factory that
+/// supports its type and is run by a `ServiceProcessor` over the given connection to the
+/// splinter node's service endpoint.
+pub fn run_service_process(
+    service_factories: &[Box<dyn ServiceFactory>],
+    request: ServiceProcessRequest,
+    connection: Box<dyn Connection>,
+) -> Result<(), ServiceProcessError> {
+    let service_definition = request.service_definition;
+    let args = request.args;
+
+    let factory = service_factories
+        .iter()
+        .find(|factory| {
+            factory
+                .available_service_types()
+                .contains(&service_definition.service_type)
+        })
+        .ok_or_else(|| {
+            ServiceProcessError::UnknownType(service_definition.service_type.to_string())
+        })?;
+
+    let service = factory
+        .create(
+            service_definition.service_id.clone(),
+            &service_definition.service_type,
+            &service_definition.circuit,
+            args,
+        )
+        .map_err(|err| ServiceProcessError::ServiceFailed(Box::new(err)))?;
+
+    let mut processor = ServiceProcessor::new(
+        connection,
+        service_definition.circuit.clone(),
+        SERVICE_PROCESSOR_INCOMING_CAPACITY,
+        SERVICE_PROCESSOR_OUTGOING_CAPACITY,
+        SERVICE_PROCESSOR_CHANNEL_CAPACITY,
+        Arc::new(AtomicBool::new(true)),
+    )
+    .map_err(|err| ServiceProcessError::ServiceFailed(Box::new(err)))?;
+    processor
+        .add_service(service)
+        .map_err(|err| ServiceProcessError::ServiceFailed(Box::new(err)))?;
+    #[cfg(feature = "service-auth")]
+    {
+        if let Some(token) = request.registration_token {
+            let token = token
+                .parse::<ServiceRegistrationToken>()
+                .map_err(|err| ServiceProcessError::InvalidRequest(Box::new(err)))?;
+            processor.add_registration_token(&service_definition.service_id, token);
+        }
+    }
+    let (_, join_handles) = processor
+        .start()
+        .map_err(|err| ServiceProcessError::ServiceFailed(Box::new(err)))?;
+
+    for result in join_handles
+        .join_all()
+        .map_err(|_| ServiceProcessError::ServicePanicked)?
+    {
+        result.map_err(|err| ServiceProcessError::ServiceFailed(Box::new(err)))?;
+    }
+
+    Ok(())
+}
+
+/// A service whose process is supervised by a thread of the `ServiceSupervisor`.
+struct SupervisedService {
+    status: Arc<Mutex<ServiceStatus>>,
+    running: Arc<AtomicBool>,
+    join_handle: JoinHandle<()>,
+}
+
+/// Starts, restarts and stops the child processes of supervised services.
+pub(super) struct ServiceSupervisor {
+    config: ServiceProcessConfig,
+    services: Mutex<HashMap<ServiceDefinition, SupervisedService>>,
+}
+
+impl ServiceSupervisor {
+    pub fn new(config: ServiceProcessConfig) -> Self {
+        ServiceSupervisor {
+            config,
+            services: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Starts the process of a service and a thread that restarts it whenever it exits.
+    pub fn start_service(
+        &self,
+        service_definition: ServiceDefinition,
+        args: HashMap<String, String>,
+    ) -> Result<(), InitializeServiceError> {
+        let request = ServiceProcessRequest {
+            service_definition: service_definition.clone(),
+            args,
+            #[cfg(feature = "service-auth")]
+            registration_token: None,
+        };
+
+        let mut services = self
+            .services
+            .lock()
+            .map_err(|_| InitializeServiceError::LockPoisoned)?;
+        if services.contains_key(&service_definition) {
+            return Err(InitializeServiceError::AlreadyRunning);
+        }
+
+        // The first process is started here, so that a misconfigured program is reported to the
+        // caller
+        let child = spawn_service_process(&self.config, &request)
+            .map_err(|err| InitializeServiceError::InitializationFailed(Box::new(err)))?;
+
+        let status = Arc::new(Mutex::new(ServiceStatus::Running));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let thread_config = self.config.clone();
+        let thread_status = status.clone();
+        let thread_running = running.clone();
+        let name = format!(
+            "{}::{}",
+            service_definition.circuit, service_definition.service_id
+        );
+        let join_handle = thread::Builder::new()
+            .name(format!("Service Supervisor {}", name))
+            .spawn(move || {
+                supervise_service_process(
+                    &name,
+                    &thread_config,
+                    &request,
+                    child,
+                    &thread_status,
+                    &thread_running,
+                )
+            })
+            .map_err(|err| InitializeServiceError::InitializationFailed(Box::new(err)))?;
+
+        services.insert(
+            service_definition,
+            SupervisedService {
+                status,
+                running,
+                join_handle,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Stops the process of a service, and stops restarting it.
+    pub fn stop_service(
+        &self,
+        service_definition: &ServiceDefinition,
+    ) -> Result<(), ShutdownServiceError> {
+        let service = self
+            .services
+            .lock()
+            .map_err(|_| ShutdownServiceError::LockPoisoned)?
+            .remove(service_definition)
+            .ok_or(ShutdownServiceError::UnknownService)?;
+
+        stop_supervised_service(service_definition, service);
+
+        Ok(())
+    }
+
+    /// Stops the processes of all supervised services.
+    pub fn stop_all_services(&self) -> Result<(), ShutdownServiceError> {
+        let mut services = self
+            .services
+            .lock()
+            .map_err(|_| ShutdownServiceError::LockPoisoned)?;
+
+        for (service_definition, service) in services.drain() {
+            stop_supervised_service(&service_definition, service);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the definitions and current statuses of all supervised services.
+    pub fn list_services(
+        &self,
+    ) -> Result<Vec<(ServiceDefinition, ServiceStatus)>, ListServicesError> {
+        self.services
+            .lock()
+            .map_err(|_| ListServicesError::LockPoisoned)?
+            .iter()
+            .map(|(service_definition, service)| {
+                let status = service
+                    .status
+                    .lock()
+                    .map_err(|_| ListServicesError::LockPoisoned)?
+                    .clone();
+                Ok((service_definition.clone(), status))
+            })
+            .collect()
+    }
+}
+
+fn stop_supervised_service(service_definition: &ServiceDefinition, service: SupervisedService) {
+    service.running.store(false, Ordering::SeqCst);
+    if service.join_handle.join().is_err() {
+        error!(
+            "Supervisor thread of service {:?} panicked",
+            service_definition
+        );
+    }
+}
+
+/// Starts a service process and writes its request to the process's standard input. A new
+/// registration token is issued for each process, if the configuration has a signer.
+fn spawn_service_process(
+    config: &ServiceProcessConfig,
+    request: &ServiceProcessRequest,
+) -> Result<Child, io::Error> {
+    #[cfg_attr(not(feature = "service-auth"), allow(unused_mut))]
+    let mut request = request.clone();
+    #[cfg(feature = "service-auth")]
+    {
+        if let Some(signer) = &config.registration_signer {
+            let token = ServiceRegistrationToken::new(
+                &request.service_definition.circuit,
+                &request.service_definition.service_id,
+                REGISTRATION_TOKEN_VALIDITY,
+                &**signer,
+            )
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+            request.registration_token = Some(token.to_string());
+        }
+    }
+    let request = serde_json::to_vec(&request)?;
+
+    let mut child = Command::new(&config.program)
+        .args(&config.args)
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    // Dropping stdin closes it, which ends the request
+    if let Some(mut stdin) = child.stdin.take() {
+        if let Err(err) = stdin.write_all(&request) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(err);
+        }
+    }
+
+    Ok(child)
+}
+
+/// Waits for the child process to exit or for the supervision to be stopped, restarting the
+/// process with an exponential backoff whenever it exits.
+fn supervise_service_process(
+    name: &str,
+    config: &ServiceProcessConfig,
+    request: &ServiceProcessRequest,
+    child: Child,
+    status: &Mutex<ServiceStatus>,
+    running: &AtomicBool,
+) {
+    let set_status = |new_status: ServiceStatus| match status.lock() {
+        Ok(mut status) => *status = new_status,
+        Err(_) => error!("Status lock of service {} was poisoned", name),
+    };
+
+    let mut child = Some(child);
+    let mut backoff = config.initial_backoff;
+    let mut restarts = 0;
+    let mut consecutive_restarts = 0;
+
+    loop {
+        if let Some(mut process) = child.take() {
+            let started = Instant::now();
+            match wait_for_exit(&mut process, running) {
+                None => {
+                    debug!("Stopped service process {}", name);
+                    return;
+                }
+                Some(Ok(exit_status)) => {
+                    warn!("Service process {} exited: {}", name, exit_status)
+                }
+                Some(Err(err)) => error!("Unable to wait for service process {}: {}", name, err),
+            }
+
+            // A process that ran for a while is not crash looping; reset the backoff
+            if started.elapsed() >= config.max_backoff {
+                backoff = config.initial_backoff;
+                consecutive_restarts = 0;
+            }
+        }
+
+        if let Some(max_restarts) = config.max_restarts {
+            if consecutive_restarts >= max_restarts {
+                error!(
+                    "Service process {} failed {} times in a row; it will not be restarted",
+                    name,
+                    consecutive_restarts + 1
+                );
+                set_status(ServiceStatus::Failed { restarts });
+                return;
+            }
+        }
+
+        restarts += 1;
+        consecutive_restarts += 1;
+        set_status(ServiceStatus::Restarting { restarts });
+
+        if !sleep_while_running(backoff, running) {
+            return;
+        }
+        backoff = std::cmp::min(backoff * 2, config.max_backoff);
+
+        match spawn_service_process(config, request) {
+            Ok(process) => {
+                info!("Restarted service process {}", name);
+                child = Some(process);
+                set_status(ServiceStatus::Running);
+            }
+            Err(err) => error!("Unable to restart service process {}: {}", name, err),
+        }
+    }
+}
+
+/// Waits for the process to exit. Returns `None` if supervision was stopped first, in which case
+/// the process is killed.
+fn wait_for_exit(
+    child: &mut Child,
+    running: &AtomicBool,
+) -> Option<Result<std::process::ExitStatus, std::io::Error>> {
+    loop {
+        if !running.load(Ordering::SeqCst) {
+            if let Err(err) = child.kill() {
+                debug!("Unable to kill service process: {}", err);
+            }
+            let _ = child.wait();
+            return None;
+        }
+
+        match child.try_wait() {
+            Ok(Some(exit_status)) => return Some(Ok(exit_status)),
+            Ok(None) => thread::sleep(POLL_INTERVAL),
+            Err(err) => return Some(Err(err)),
+        }
+    }
+}
+
+/// Sleeps for the given duration, returning early with `false` if supervision is stopped.
+fn sleep_while_running(duration: Duration, running: &AtomicBool) -> bool {
+    let deadline = Instant::now() + duration;
+    while Instant::now() < deadline {
+        if !running.load(Ordering::SeqCst) {
+            return false;
+        }
+        thread::sleep(std::cmp::min(
+            POLL_INTERVAL,
+            deadline.saturating_duration_since(Instant::now()),
+        ));
+    }
+    running.load(Ordering::SeqCst)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn service_definition() -> ServiceDefinition {
+        ServiceDefinition {
+            circuit: "alpha".into(),
+            service_id: "abcd".into(),
+            service_type: "test".into(),
+        }
+    }
+
+    fn shell_config(script: &str) -> ServiceProcessConfig {
+        ServiceProcessConfig::new("sh")
+            .with_args(vec!["-c".into(), script.into()])
+            .with_backoff(Duration::from_millis(10), Duration::from_secs(10))
+    }
+
+    fn wait_for_status(
+        supervisor: &ServiceSupervisor,
+        predicate: impl Fn(&ServiceStatus) -> bool,
+    ) -> ServiceStatus {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            let services = supervisor.list_services().expect("Failed to list services");
+            let (_, status) = &services[0];
+            if predicate(status) {
+                return status.clone();
+            }
+            assert!(
+                Instant::now() < deadline,
+                "Timed out; status is {:?}",
+                status
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    /// Verify that a service process that keeps crashing is restarted until the maximum number
+    /// of restarts is reached, after which it is reported as failed.
+    #[test]
+    fn test_crashing_service_is_restarted_then_fails() {
+        let supervisor =
+            ServiceSupervisor::new(shell_config("cat > /dev/null; exit 1").with_max_restarts(3));
+
+        supervisor
+            .start_service(service_definition(), HashMap::new())
+            .expect("Failed to start service");
+
+        let status = wait_for_status(&supervisor, |status| {
+            matches!(status, ServiceStatus::Failed { .. })
+        });
+        assert_eq!(status, ServiceStatus::Failed { restarts: 3 });
+
+        supervisor
+            .stop_service(&service_definition())
+            .expect("Failed to stop service");
+        assert!(supervisor
+            .list_services()
+            .expect("Failed to list services")
+            .is_empty());
+    }
+
+    /// Verify that a service process receives its request on standard input, is reported as
+    /// running, and is stopped by `stop_service`.
+    #[test]
+    fn test_running_service_is_stopped() {
+        let temp_dir = tempdir::TempDir::new("test_running_service_is_stopped")
+            .expect("Failed to create temp dir");
+        let request_file = temp_dir.path().join("request.json");
+        let supervisor = ServiceSupervisor::new(shell_config(&format!(
+            "cat > {}; sleep 30",
+            request_file.display()
+        )));
+
+        let mut args = HashMap::new();
+        args.insert("key".to_string(), "value".to_string());
+        supervisor
+            .start_service(service_definition(), args.clone())
+            .expect("Failed to start service");
+        assert!(supervisor
+            .start_service(service_definition(), args.clone())
+            .is_err());
+
+        assert_eq!(
+            wait_for_status(&supervisor, |_| true),
+            ServiceStatus::Running
+        );
+
+        let request = wait_for_request(&request_file);
+        assert_eq!(request.service_definition, service_definition());
+        assert_eq!(request.args, args);
+
+        supervisor
+            .stop_all_services()
+            .expect("Failed to stop services");
+        assert!(supervisor
+            .list_services()
+            .expect("Failed to list services")
+            .is_empty());
+    }
+
+    /// Verify that a service process receives a registration token for its service, signed by
+    /// the configured signer.
+    #[cfg(feature = "service-auth")]
+    #[test]
+    fn test_service_receives_registration_token() {
+        use crate::signing::hash::{HashSigner, HashVerifier};
+
+        let temp_dir = tempdir::TempDir::new("test_service_receives_registration_token")
+            .expect("Failed to create temp dir");
+        let request_file = temp_dir.path().join("request.json");
+        let supervisor = ServiceSupervisor::new(
+            shell_config(&format!("cat > {}; sleep 30", request_file.display()))
+                .with_registration_signer(Arc::new(HashSigner)),
+        );
+
+        supervisor
+            .start_service(service_definition(), HashMap::new())
+            .expect("Failed to start service");
+
+        let token = wait_for_request(&request_file)
+            .registration_token
+            .expect("Request has no registration token")
+            .parse::<ServiceRegistrationToken>()
+            .expect("Failed to parse registration token");
+        assert!(token
+            .verify("alpha", "abcd", &HashVerifier)
+            .expect("Failed to verify token"));
+
+        supervisor
+            .stop_all_services()
+            .expect("Failed to stop services");
+    }
+
+    fn wait_for_request(request_file: &std::path::Path) -> ServiceProcessRequest {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            if let Ok(request) = std::fs::read(request_file)
+                .map_err(|_| ())
+                .and_then(|data| ServiceProcessRequest::from_reader(&data[..]).map_err(|_| ()))
+            {
+                return request;
+            }
+            assert!(Instant::now() < deadline, "Timed out waiting for request");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
@@ -1006,6 +1006,17 @@ fn handle_notifications(
                 max_retry_attempts,
             )
         }
+        ConnectionManagerNotification::LatencyUpdated {
+            endpoint,
+            identity,
+            latency,
+        } => trace!(
+            "Round-trip time to peer {} at {} is {:?} (jitter {:?})",
+            identity,
+            endpoint,
+            latency.smoothed_rtt(),
+            latency.jitter()
+        ),
     }
 }
 
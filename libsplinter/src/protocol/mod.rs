@@ -35,6 +35,10 @@ pub(crate) const ADMIN_LIST_PROPOSALS_PROTOCOL_MIN: u32 = 1;
 pub(crate) const ADMIN_LIST_CIRCUITS_MIN: u32 = 1;
 #[cfg(feature = "rest-api-actix")]
 pub(crate) const ADMIN_FETCH_CIRCUIT_MIN: u32 = 1;
+#[cfg(all(feature = "circuit-template", feature = "rest-api-actix"))]
+pub(crate) const ADMIN_LIST_TEMPLATES_MIN: u32 = 1;
+#[cfg(all(feature = "circuit-template", feature = "rest-api-actix"))]
+pub(crate) const ADMIN_FETCH_TEMPLATE_MIN: u32 = 1;
 
 #[cfg(feature = "registry")]
 pub const REGISTRY_PROTOCOL_VERSION: u32 = 1;
@@ -58,5 +62,31 @@ pub(crate) const BIOME_LIST_USERS_PROTOCOL_MIN: u32 = 1;
 #[cfg(all(feature = "biome-credentials", feature = "rest-api"))]
 pub(crate) const BIOME_VERIFY_PROTOCOL_MIN: u32 = 1;
 
+#[cfg(all(feature = "biome-api-keys", feature = "rest-api",))]
+pub(crate) const BIOME_API_KEYS_PROTOCOL_MIN: u32 = 1;
+
+#[cfg(all(feature = "biome-authorization", feature = "rest-api",))]
+pub(crate) const BIOME_AUTHORIZATION_PROTOCOL_MIN: u32 = 1;
+
 #[cfg(all(feature = "biome-key-management", feature = "rest-api",))]
 pub(crate) const BIOME_KEYS_PROTOCOL_MIN: u32 = 1;
+
+#[cfg(all(feature = "biome-mfa", feature = "rest-api",))]
+pub(crate) const BIOME_MFA_PROTOCOL_MIN: u32 = 1;
+
+#[cfg(all(feature = "biome-notifications", feature = "rest-api",))]
+pub(crate) const BIOME_NOTIFICATIONS_PROTOCOL_MIN: u32 = 1;
+
+#[cfg(all(feature = "biome-oidc", feature = "rest-api",))]
+pub(crate) const BIOME_OIDC_PROTOCOL_MIN: u32 = 1;
+
+#[cfg(all(feature = "biome-password-reset", feature = "rest-api",))]
+pub(crate) const BIOME_PASSWORD_PROTOCOL_MIN: u32 = 1;
+#[cfg(all(feature = "biome-password-reset", feature = "rest-api",))]
+pub(crate) const BIOME_PASSWORD_RESET_PROTOCOL_MIN: u32 = 1;
+
+#[cfg(all(feature = "biome-sessions", feature = "rest-api",))]
+pub(crate) const BIOME_SESSIONS_PROTOCOL_MIN: u32 = 1;
+
+#[cfg(all(feature = "biome-user-admin", feature = "rest-api",))]
+pub(crate) const BIOME_USER_ADMIN_PROTOCOL_MIN: u32 = 1;
@@ -0,0 +1,216 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An in-memory, read/write registry.
+//!
+//! This module contains the [`MemoryRegistry`], which provides an implementation of the
+//! [`RwRegistry`] trait.
+//!
+//! [`MemoryRegistry`]: struct.MemoryRegistry.html
+//! [`RwRegistry`]: ../trait.RwRegistry.html
+
+use std::sync::{Arc, Mutex};
+
+use super::{
+    validate_nodes, MetadataPredicate, Node, NodeIter, RegistryError, RegistryReader,
+    RegistryWriter, RwRegistry,
+};
+
+/// An in-memory, read/write registry.
+///
+/// The `MemoryRegistry` holds its nodes in memory only, so its contents are lost when the last
+/// clone of the registry is dropped. The same validity criteria that apply to the other registry
+/// implementations are enforced when nodes are inserted (see [`Node`]).
+///
+/// [`Node`]: ../struct.Node.html
+#[derive(Clone, Default)]
+pub struct MemoryRegistry {
+    nodes: Arc<Mutex<Vec<Node>>>,
+}
+
+impl MemoryRegistry {
+    /// Construct a new, empty `MemoryRegistry`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get all nodes in the registry.
+    fn get_nodes(&self) -> Result<Vec<Node>, RegistryError> {
+        Ok(self
+            .nodes
+            .lock()
+            .map_err(|_| RegistryError::general_error("Memory registry's internal lock poisoned"))?
+            .clone())
+    }
+}
+
+impl RegistryReader for MemoryRegistry {
+    fn fetch_node(&self, identity: &str) -> Result<Option<Node>, RegistryError> {
+        Ok(self
+            .get_nodes()?
+            .into_iter()
+            .find(|node| node.identity == identity))
+    }
+
+    fn list_nodes<'a, 'b: 'a>(
+        &'b self,
+        predicates: &'a [MetadataPredicate],
+    ) -> Result<NodeIter<'a>, RegistryError> {
+        let mut nodes = self.get_nodes()?;
+        nodes.retain(|node| predicates.iter().all(|predicate| predicate.apply(node)));
+        Ok(Box::new(nodes.into_iter()))
+    }
+
+    fn count_nodes(&self, predicates: &[MetadataPredicate]) -> Result<u32, RegistryError> {
+        Ok(self
+            .get_nodes()?
+            .iter()
+            .filter(move |node| predicates.iter().all(|predicate| predicate.apply(node)))
+            .count() as u32)
+    }
+}
+
+impl RegistryWriter for MemoryRegistry {
+    fn insert_node(&self, node: Node) -> Result<(), RegistryError> {
+        let mut nodes = self.nodes.lock().map_err(|_| {
+            RegistryError::general_error("Memory registry's internal lock poisoned")
+        })?;
+
+        let mut updated_nodes = nodes.clone();
+        // If a node with the same identity already exists, remove it
+        updated_nodes.retain(|existing_node| existing_node.identity != node.identity);
+        updated_nodes.push(node);
+        validate_nodes(&updated_nodes)?;
+
+        *nodes = updated_nodes;
+        Ok(())
+    }
+
+    fn delete_node(&self, identity: &str) -> Result<Option<Node>, RegistryError> {
+        let mut nodes = self.nodes.lock().map_err(|_| {
+            RegistryError::general_error("Memory registry's internal lock poisoned")
+        })?;
+
+        Ok(nodes
+            .iter()
+            .position(|node| node.identity == identity)
+            .map(|index| nodes.remove(index)))
+    }
+}
+
+impl RwRegistry for MemoryRegistry {
+    fn clone_box(&self) -> Box<dyn RwRegistry> {
+        Box::new(self.clone())
+    }
+
+    fn clone_box_as_reader(&self) -> Box<dyn RegistryReader> {
+        Box::new(Clone::clone(self))
+    }
+
+    fn clone_box_as_writer(&self) -> Box<dyn RegistryWriter> {
+        Box::new(Clone::clone(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that inserted nodes can be fetched, listed, counted and deleted, and that inserting
+    /// a node with an existing identity replaces it.
+    #[test]
+    fn test_insert_fetch_delete() {
+        let registry = MemoryRegistry::new();
+
+        registry
+            .insert_node(get_node_1())
+            .expect("Unable to insert node 1");
+        registry
+            .insert_node(get_node_2())
+            .expect("Unable to insert node 2");
+
+        assert_eq!(
+            registry.fetch_node("Node-123").expect("Unable to fetch"),
+            Some(get_node_1())
+        );
+        assert_eq!(registry.count_nodes(&[]).expect("Unable to count"), 2);
+        assert_eq!(
+            registry
+                .list_nodes(&[MetadataPredicate::eq("company", "Bitwise IO")])
+                .expect("Unable to list")
+                .collect::<Vec<_>>(),
+            vec![get_node_2()]
+        );
+
+        let mut updated_node = get_node_1();
+        updated_node.display_name = "Updated Node 1".into();
+        registry
+            .insert_node(updated_node.clone())
+            .expect("Unable to update node 1");
+        assert_eq!(registry.count_nodes(&[]).expect("Unable to count"), 2);
+        assert_eq!(
+            registry.fetch_node("Node-123").expect("Unable to fetch"),
+            Some(updated_node.clone())
+        );
+
+        assert_eq!(
+            registry.delete_node("Node-123").expect("Unable to delete"),
+            Some(updated_node)
+        );
+        assert_eq!(
+            registry.delete_node("Node-123").expect("Unable to delete"),
+            None
+        );
+        assert!(!registry.has_node("Node-123").expect("Unable to check"));
+    }
+
+    /// Verify that a node that would make the registry invalid is rejected and leaves the
+    /// registry unchanged.
+    #[test]
+    fn test_insert_invalid_node() {
+        let registry = MemoryRegistry::new();
+        registry
+            .insert_node(get_node_1())
+            .expect("Unable to insert node 1");
+
+        let mut duplicate_endpoint = get_node_2();
+        duplicate_endpoint.endpoints = get_node_1().endpoints;
+        match registry.insert_node(duplicate_endpoint) {
+            Err(RegistryError::InvalidNode(_)) => (),
+            res => panic!("Expected InvalidNode error, got {:?}", res),
+        }
+
+        assert_eq!(registry.count_nodes(&[]).expect("Unable to count"), 1);
+    }
+
+    fn get_node_1() -> Node {
+        Node::builder("Node-123")
+            .with_endpoint("tcps://12.0.0.123:8431")
+            .with_display_name("Bitwise IO - Node 1")
+            .with_key("abcd")
+            .with_metadata("company", "Cargill")
+            .build()
+            .expect("Failed to build node1")
+    }
+
+    fn get_node_2() -> Node {
+        Node::builder("Node-456")
+            .with_endpoint("tcps://12.0.0.123:8434")
+            .with_display_name("Bitwise IO - Node 2")
+            .with_key("0123")
+            .with_metadata("company", "Bitwise IO")
+            .build()
+            .expect("Failed to build node2")
+    }
+}
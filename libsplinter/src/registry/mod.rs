@@ -27,6 +27,7 @@
 #[cfg(feature = "registry-database")]
 mod diesel;
 mod error;
+mod memory;
 #[cfg(feature = "rest-api")]
 mod rest_api;
 mod unified;
@@ -42,6 +43,7 @@ pub use self::diesel::migrations::run_sqlite_migrations;
 #[cfg(feature = "registry-database")]
 pub use self::diesel::DieselRegistry;
 pub use error::{InvalidNodeError, RegistryError};
+pub use memory::MemoryRegistry;
 pub use unified::UnifiedRegistry;
 pub use yaml::LocalYamlRegistry;
 #[cfg(feature = "registry-remote")]
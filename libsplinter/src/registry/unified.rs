@@ -21,7 +21,7 @@
 //! [`RwRegistry`]: ../trait.RwRegistry.html
 
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use super::{
     MetadataPredicate, Node, NodeIter, RegistryError, RegistryReader, RegistryWriter, RwRegistry,
@@ -49,6 +49,10 @@ use super::{
 ///
 /// If reading a source registry fails, the error will be logged and the registry will be ignored.
 ///
+/// The read-only registries may be replaced while the registry is in use (see
+/// [`set_read_only_registries`]); clones of a `UnifiedRegistry` share the same set of read-only
+/// registries.
+///
 /// ## Registry Precedence
 ///
 /// The internal read-write registry has the highest precedence, followed by the read-only
@@ -68,10 +72,11 @@ use super::{
 /// [`identity`]: ../struct.Node.html#structfield.identity
 /// [`metadata`]: ../struct.Node.html#structfield.metadata
 /// [`Metadata Merging`]: #metadata-merging
+/// [`set_read_only_registries`]: #method.set_read_only_registries
 #[derive(Clone)]
 pub struct UnifiedRegistry {
     internal_source: Arc<dyn RwRegistry>,
-    external_sources: Vec<Arc<dyn RegistryReader>>,
+    external_sources: Arc<RwLock<Vec<Arc<dyn RegistryReader>>>>,
 }
 
 impl UnifiedRegistry {
@@ -83,15 +88,45 @@ impl UnifiedRegistry {
     ) -> Self {
         Self {
             internal_source: internal_source.into(),
-            external_sources: external_sources.into_iter().map(Arc::from).collect(),
+            external_sources: Arc::new(RwLock::new(
+                external_sources.into_iter().map(Arc::from).collect(),
+            )),
         }
     }
 
+    /// Replaces the read-only registries, keeping the internal read-write registry.
+    ///
+    /// The new registries take effect for all subsequent read operations, on this registry and on
+    /// all of its clones.
+    pub fn set_read_only_registries(
+        &self,
+        external_sources: Vec<Box<dyn RegistryReader>>,
+    ) -> Result<(), RegistryError> {
+        *self.external_sources.write().map_err(|_| {
+            RegistryError::general_error("Unified registry's read-only sources lock poisoned")
+        })? = external_sources.into_iter().map(Arc::from).collect();
+        Ok(())
+    }
+
+    /// Gets a snapshot of the current read-only sources.
+    fn external_sources(&self) -> Result<Vec<Arc<dyn RegistryReader>>, RegistryError> {
+        Ok(self
+            .external_sources
+            .read()
+            .map_err(|_| {
+                RegistryError::general_error("Unified registry's read-only sources lock poisoned")
+            })?
+            .clone())
+    }
+
     /// Gets all nodes from all sources (in ascending order of precedence) without deduplication.
-    fn all_nodes<'a>(&'a self) -> Box<dyn Iterator<Item = Node> + 'a> {
+    fn all_nodes<'a>(
+        &'a self,
+        external_sources: &'a [Arc<dyn RegistryReader>],
+    ) -> Box<dyn Iterator<Item = Node> + 'a> {
         Box::new(
             // Get node iterators from all read-only sources
-            self.external_sources
+            external_sources
                 .iter()
                 .map(|registry| registry.list_nodes(&[]))
                 // Reverse the sources, so lowest precedence is first
@@ -115,9 +150,10 @@ impl RegistryReader for UnifiedRegistry {
         &'b self,
         predicates: &'a [MetadataPredicate],
     ) -> Result<NodeIter<'a>, RegistryError> {
+        let external_sources = self.external_sources()?;
         let mut id_map = self
             // Get all nodes from all sources
-            .all_nodes()
+            .all_nodes(&external_sources)
             // Deduplicate and merge metadata
             .fold(HashMap::<String, Node>::new(), |mut acc, mut node| {
                 // If the node is already present, merge metadata
@@ -144,7 +180,7 @@ impl RegistryReader for UnifiedRegistry {
     fn fetch_node(&self, identity: &str) -> Result<Option<Node>, RegistryError> {
         // Get node from all read-only sources
         Ok(self
-            .external_sources
+            .external_sources()?
             .iter()
             .map(|registry| registry.fetch_node(identity))
             // Reverse the sources, so lowest precedence is first
@@ -187,7 +223,7 @@ impl RegistryReader for UnifiedRegistry {
                 );
                 false
             })
-            || self.external_sources.iter().any(|source| {
+            || self.external_sources()?.iter().any(|source| {
                 source.has_node(identity).unwrap_or_else(|err| {
                     debug!(
                         "Failed to check for existence of node in source registry: {}",
@@ -666,6 +702,57 @@ mod test {
             .expect("Unable to check writeable for node1"));
     }
 
+    /// Verify that replacing the read-only registries affects all clones of the unified registry,
+    /// and leaves the internal registry untouched.
+    ///
+    /// 1. Create a unified registry with one node in the internal registry and one in a read-only
+    ///    registry.
+    /// 2. Replace the read-only registries through a clone of the unified registry.
+    /// 3. Verify that the original unified registry no longer has the old read-only node, has the
+    ///    new read-only node, and still has the internal node.
+    #[test]
+    fn set_read_only_registries() {
+        let node1 = new_node("node1", "endpoint1", &[("meta_a", "val_a")]);
+        let node2 = new_node("node2", "endpoint2", &[("meta_b", "val_b")]);
+        let node3 = new_node("node3", "endpoint3", &[("meta_c", "val_c")]);
+
+        let writeable = MemRegistry::default();
+        writeable
+            .insert_node(node1.clone())
+            .expect("Unable to insert node1");
+
+        let old_readable = MemRegistry::default();
+        old_readable
+            .insert_node(node2.clone())
+            .expect("Unable to insert node2");
+
+        let new_readable = MemRegistry::default();
+        new_readable
+            .insert_node(node3.clone())
+            .expect("Unable to insert node3");
+
+        let unified = UnifiedRegistry::new(Box::new(writeable), vec![Box::new(old_readable)]);
+
+        unified
+            .clone()
+            .set_read_only_registries(vec![Box::new(new_readable)])
+            .expect("Unable to set read-only registries");
+
+        assert!(unified
+            .has_node(&node1.identity)
+            .expect("Unable to check unified for node1"));
+        assert!(!unified
+            .has_node(&node2.identity)
+            .expect("Unable to check unified for node2"));
+        assert_eq!(
+            Some(node3.clone()),
+            unified
+                .fetch_node(&node3.identity)
+                .expect("Unable to fetch node3")
+        );
+        assert_eq!(2, unified.count_nodes(&[]).expect("Unable to get count"));
+    }
+
     #[derive(Clone, Default)]
     struct MemRegistry {
         nodes: Arc<Mutex<HashMap<String, Node>>>,
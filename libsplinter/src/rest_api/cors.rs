@@ -13,6 +13,8 @@
 // limitations under the License.
 
 //! Provides CORS support for the REST API
+use std::sync::{Arc, RwLock};
+
 use actix_web::dev::*;
 use actix_web::{
     http::header, http::header::HeaderValue, http::Method, Error as ActixError, HttpResponse,
@@ -23,22 +25,36 @@ use futures::{
 };
 
 /// Configuration for CORS support
+///
+/// Clones of a `Cors` share the same whitelist, so it can be replaced while the REST API is
+/// running.
 #[derive(Clone)]
 pub struct Cors {
-    whitelist: Vec<String>,
+    whitelist: Arc<RwLock<Vec<String>>>,
 }
 
 impl Cors {
     /// Initialize the CORS preflight check with a set of allowed domains.
     pub fn new(whitelist: Vec<String>) -> Self {
         debug!("Creating CORS with whitelist: {:?}", whitelist);
-        Cors { whitelist }
+        Cors {
+            whitelist: Arc::new(RwLock::new(whitelist)),
+        }
     }
 
     /// Initialize the CORS preflight check with "*" domains.
     pub fn new_allow_any() -> Self {
         Cors::new(vec!["*".into()])
     }
+
+    /// Replace the set of allowed domains; "*" allows any domain.
+    pub fn set_whitelist(&self, whitelist: Vec<String>) {
+        debug!("Updating CORS whitelist: {:?}", whitelist);
+        match self.whitelist.write() {
+            Ok(mut current) => *current = whitelist,
+            Err(_) => error!("CORS whitelist lock poisoned; whitelist not updated"),
+        }
+    }
 }
 
 impl<S, B> Transform<S> for Cors
@@ -65,7 +81,7 @@ where
 #[doc(hidden)]
 pub struct CorsMiddleware<S> {
     service: S,
-    whitelist: Vec<String>,
+    whitelist: Arc<RwLock<Vec<String>>>,
 }
 
 impl<S, B> Service for CorsMiddleware<S>
@@ -98,8 +114,13 @@ where
                     .cloned();
                 let allowed_origin = self
                     .whitelist
-                    .iter()
-                    .any(|domain| domain == "*" || origin.contains(domain));
+                    .read()
+                    .map(|whitelist| {
+                        whitelist
+                            .iter()
+                            .any(|domain| domain == "*" || origin.contains(domain))
+                    })
+                    .unwrap_or(false);
                 // This verifies if a client is making a preflight check with the OPTIONS
                 // http request method and the origin is allowed, the preflight check responds
                 // with a 200 OK status.
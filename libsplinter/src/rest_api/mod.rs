@@ -229,6 +229,11 @@ impl Resource {
         }
     }
 
+    /// Returns the route of the resource, as it was given to `build`
+    pub fn route(&self) -> &str {
+        &self.route
+    }
+
     pub fn add_method<F>(mut self, method: Method, handle: F) -> Self
     where
         F: Fn(
@@ -457,13 +462,34 @@ impl RequestGuard for ProtocolVersionRangeGuard {
     }
 }
 
+/// Guards requests so that they are only served to clients on the local host.
+///
+/// Requests that do not come from a loopback address are rejected with `403 Forbidden`.
+#[derive(Clone, Default)]
+pub struct LocalRequestGuard;
+
+impl RequestGuard for LocalRequestGuard {
+    fn evaluate(&self, req: &HttpRequest) -> Continuation {
+        match req.peer_addr() {
+            Some(addr) if addr.ip().is_loopback() => Continuation::Continue,
+            _ => Continuation::terminate(
+                HttpResponse::Forbidden()
+                    .json(json!({
+                        "message": "This endpoint is only available from the local host",
+                    }))
+                    .into_future(),
+            ),
+        }
+    }
+}
+
 /// `RestApi` is used to create an instance of a restful web server.
 #[derive(Clone)]
 pub struct RestApi {
     resources: Vec<Resource>,
     bind: String,
     #[cfg(feature = "rest-api-cors")]
-    whitelist: Option<Vec<String>>,
+    cors: Option<cors::Cors>,
 }
 
 impl RestApi {
@@ -475,7 +501,10 @@ impl RestApi {
         let bind_url = self.bind.to_owned();
         let resources = self.resources.to_owned();
         #[cfg(feature = "rest-api-cors")]
-        let whitelist = self.whitelist.to_owned();
+        let cors = self
+            .cors
+            .to_owned()
+            .unwrap_or_else(cors::Cors::new_allow_any);
         let join_handle = thread::Builder::new()
             .name("SplinterDRestApi".into())
             .spawn(move || {
@@ -484,12 +513,9 @@ impl RestApi {
                     // Actix's type definitions require this to be chained, otherwise, the generic
                     // type of App is changed as the values are returned.
                     #[cfg(feature = "rest-api-cors")]
-                    let cors = match &whitelist {
-                        Some(list) => cors::Cors::new(list.to_vec()),
-                        None => cors::Cors::new_allow_any(),
-                    };
-                    #[cfg(feature = "rest-api-cors")]
-                    let mut app = App::new().wrap(middleware::Logger::default()).wrap(cors);
+                    let mut app = App::new()
+                        .wrap(middleware::Logger::default())
+                        .wrap(cors.clone());
 
                     #[cfg(not(feature = "rest-api-cors"))]
                     let mut app = App::new().wrap(middleware::Logger::default());
@@ -561,18 +587,20 @@ impl RestApi {
 /// Builder `struct` for `RestApi`.
 pub struct RestApiBuilder {
     resources: Vec<Resource>,
+    request_guards: Vec<Arc<dyn RequestGuard>>,
     bind: Option<String>,
     #[cfg(feature = "rest-api-cors")]
-    whitelist: Option<Vec<String>>,
+    cors: Option<cors::Cors>,
 }
 
 impl Default for RestApiBuilder {
     fn default() -> Self {
         Self {
             resources: Vec::new(),
+            request_guards: Vec::new(),
             bind: None,
             #[cfg(feature = "rest-api-cors")]
-            whitelist: None,
+            cors: None,
         }
     }
 }
@@ -597,9 +625,28 @@ impl RestApiBuilder {
         self
     }
 
+    /// Adds a RequestGuard that applies to all resources of the REST API.
+    ///
+    /// These guards are evaluated before the guards of the resources themselves.
+    pub fn add_request_guard<RG>(mut self, guard: RG) -> Self
+    where
+        RG: RequestGuard + Clone + 'static,
+    {
+        self.request_guards.push(Arc::new(guard));
+        self
+    }
+
     #[cfg(feature = "rest-api-cors")]
     pub fn with_whitelist(mut self, values: Vec<String>) -> Self {
-        self.whitelist = Some(values);
+        self.cors = Some(cors::Cors::new(values));
+        self
+    }
+
+    /// Use the given CORS configuration; the whitelist of a `Cors` may be changed through any of
+    /// its clones while the REST API is running.
+    #[cfg(feature = "rest-api-cors")]
+    pub fn with_cors(mut self, cors: cors::Cors) -> Self {
+        self.cors = Some(cors);
         self
     }
 
@@ -608,11 +655,23 @@ impl RestApiBuilder {
             .bind
             .ok_or_else(|| RestApiServerError::MissingField("bind".to_string()))?;
 
+        let request_guards = self.request_guards;
+        let resources = self
+            .resources
+            .into_iter()
+            .map(|mut resource| {
+                let mut guards = request_guards.clone();
+                guards.append(&mut resource.request_guards);
+                resource.request_guards = guards;
+                resource
+            })
+            .collect();
+
         Ok(RestApi {
             bind,
-            resources: self.resources,
+            resources,
             #[cfg(feature = "rest-api-cors")]
-            whitelist: self.whitelist,
+            cors: self.cors,
         })
     }
 }
@@ -18,7 +18,11 @@ mod claims;
 mod error;
 mod token_issuer;
 
-#[cfg(any(feature = "biome-key-management", feature = "biome-credentials",))]
+#[cfg(any(
+    feature = "biome-key-management",
+    feature = "biome-credentials",
+    feature = "biome-notifications",
+))]
 use jsonwebtoken::Validation;
 use serde::Serialize;
 
@@ -26,7 +30,11 @@ pub use claims::{Claims, ClaimsBuilder};
 pub use error::{ClaimsBuildError, TokenIssuerError, TokenValidationError};
 pub use token_issuer::AccessTokenIssuer;
 
-#[cfg(any(feature = "biome-key-management", feature = "biome-credentials",))]
+#[cfg(any(
+    feature = "biome-key-management",
+    feature = "biome-credentials",
+    feature = "biome-notifications",
+))]
 const DEFAULT_LEEWAY: i64 = 10; // default leeway in seconds.
 
 /// Implementers can issue JWT tokens
@@ -38,7 +46,11 @@ pub trait TokenIssuer<T: Serialize> {
     fn issue_refresh_token_with_claims(&self, claims: T) -> Result<String, TokenIssuerError>;
 }
 
-#[cfg(any(feature = "biome-key-management", feature = "biome-credentials",))]
+#[cfg(any(
+    feature = "biome-key-management",
+    feature = "biome-credentials",
+    feature = "biome-notifications",
+))]
 pub(crate) fn default_validation(issuer: &str) -> Validation {
     let mut validation = Validation::default();
     validation.leeway = DEFAULT_LEEWAY;
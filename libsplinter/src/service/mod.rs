@@ -40,6 +40,8 @@ mod factory;
 #[cfg(feature = "service-network")]
 pub mod network;
 mod processor;
+#[cfg(feature = "service-auth")]
+mod registration;
 #[cfg(feature = "rest-api")]
 pub mod rest_api;
 #[cfg(feature = "service-arg-validation")]
@@ -52,6 +54,8 @@ pub use processor::registry::StandardServiceNetworkRegistry;
 pub use processor::JoinHandles;
 pub use processor::ServiceProcessor;
 pub use processor::ShutdownHandle;
+#[cfg(feature = "service-auth")]
+pub use registration::{InvalidTokenError, ServiceRegistrationToken};
 
 pub use error::{
     FactoryCreateError, ServiceConnectionError, ServiceDestroyError, ServiceDisconnectionError,
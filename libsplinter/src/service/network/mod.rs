@@ -569,6 +569,8 @@ impl ServiceConnectionAgent {
                     );
                 }
             }
+            // Service processors do not reply to heartbeats
+            ConnectionManagerNotification::LatencyUpdated { .. } => (),
         }
 
         Ok(())
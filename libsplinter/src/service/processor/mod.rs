@@ -33,6 +33,8 @@ use crate::protos::circuit::{
 };
 use crate::protos::network::{NetworkMessage, NetworkMessageType};
 use crate::service::error::ServiceProcessorError;
+#[cfg(feature = "service-auth")]
+use crate::service::ServiceRegistrationToken;
 use crate::service::{Service, ServiceMessageContext};
 use crate::transport::Connection;
 use crate::{rwlock_read_unwrap, rwlock_write_unwrap};
@@ -83,6 +85,8 @@ pub struct ServiceProcessor {
     inbound_router: InboundRouter<CircuitMessageType>,
     inbound_receiver: Receiver<Result<(CircuitMessageType, Vec<u8>), channel::RecvError>>,
     channel_capacity: usize,
+    #[cfg(feature = "service-auth")]
+    registration_tokens: HashMap<String, ServiceRegistrationToken>,
 }
 
 impl ServiceProcessor {
@@ -115,6 +119,8 @@ impl ServiceProcessor {
             inbound_router: InboundRouter::new(Box::new(inbound_sender)),
             inbound_receiver,
             channel_capacity,
+            #[cfg(feature = "service-auth")]
+            registration_tokens: HashMap::new(),
         })
     }
 
@@ -138,6 +144,14 @@ impl ServiceProcessor {
         }
     }
 
+    /// Adds a registration token which is presented to the splinter node when the service with
+    /// the given ID connects.
+    #[cfg(feature = "service-auth")]
+    pub fn add_registration_token(&mut self, service_id: &str, token: ServiceRegistrationToken) {
+        self.registration_tokens
+            .insert(service_id.to_string(), token);
+    }
+
     /// Once the service processor is started it will handle incoming messages from the splinter
     /// node and route it to a running service.
     ///
@@ -156,16 +170,18 @@ impl ServiceProcessor {
             let service_id = service.service_id().to_string();
 
             let (send, recv) = crossbeam_channel::bounded(self.channel_capacity);
-            let network_sender = self.network_sender.clone();
-            let circuit = self.circuit.clone();
-            let inbound_router = self.inbound_router.clone();
+            let registry = StandardServiceNetworkRegistry::new(
+                self.circuit.clone(),
+                self.network_sender.clone(),
+                self.inbound_router.clone(),
+            );
+            #[cfg(feature = "service-auth")]
+            let registry = registry.with_registration_tokens(self.registration_tokens.clone());
             let join_handle = thread::Builder::new()
                 .name(format!("Service {}", service_id))
                 .spawn(move || {
                     let service_id = service.service_id().to_string();
-                    if let Err(err) =
-                        run_service_loop(circuit, service, network_sender, recv, inbound_router)
-                    {
+                    if let Err(err) = run_service_loop(service, registry, recv) {
                         error!("Terminating service {} due to error: {}", service_id, err);
                         Err(err)
                     } else {
@@ -470,14 +486,11 @@ impl ShutdownHandle {
 }
 
 fn run_service_loop(
-    circuit: String,
     mut service: Box<dyn Service>,
-    network_sender: Sender<Vec<u8>>,
+    registry: StandardServiceNetworkRegistry,
     service_recv: Receiver<ProcessorMessage>,
-    inbound_router: InboundRouter<CircuitMessageType>,
 ) -> Result<(), ServiceProcessorError> {
     info!("Starting Service: {}", service.service_id());
-    let registry = StandardServiceNetworkRegistry::new(circuit, network_sender, inbound_router);
     service.start(&registry).map_err(to_process_err!(
         "unable to start service {}",
         service.service_id()
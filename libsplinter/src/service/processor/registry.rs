@@ -11,6 +11,9 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+#[cfg(feature = "service-auth")]
+use std::collections::HashMap;
+
 use crossbeam_channel::Sender;
 use protobuf::Message;
 use uuid::Uuid;
@@ -22,6 +25,8 @@ use crate::protos::circuit::{
     ServiceDisconnectResponse_Status,
 };
 use crate::service::error::{ServiceConnectionError, ServiceDisconnectionError};
+#[cfg(feature = "service-auth")]
+use crate::service::ServiceRegistrationToken;
 use crate::service::{ServiceNetworkRegistry, ServiceNetworkSender};
 
 use super::sender::create_message;
@@ -33,6 +38,8 @@ pub struct StandardServiceNetworkRegistry {
     circuit: String,
     outgoing_sender: Sender<Vec<u8>>,
     inbound_router: InboundRouter<CircuitMessageType>,
+    #[cfg(feature = "service-auth")]
+    registration_tokens: HashMap<String, ServiceRegistrationToken>,
 }
 
 /// This is an implementation of ServiceNetworkRegistry that can be used by a standard service
@@ -47,8 +54,21 @@ impl StandardServiceNetworkRegistry {
             circuit,
             outgoing_sender,
             inbound_router,
+            #[cfg(feature = "service-auth")]
+            registration_tokens: HashMap::new(),
         }
     }
+
+    /// Sets the registration tokens, keyed by service ID, that are presented when the services
+    /// connect.
+    #[cfg(feature = "service-auth")]
+    pub fn with_registration_tokens(
+        mut self,
+        registration_tokens: HashMap<String, ServiceRegistrationToken>,
+    ) -> Self {
+        self.registration_tokens = registration_tokens;
+        self
+    }
 }
 
 impl ServiceNetworkRegistry for StandardServiceNetworkRegistry {
@@ -63,6 +83,12 @@ impl ServiceNetworkRegistry for StandardServiceNetworkRegistry {
         connect_msg.set_circuit(self.circuit.to_string());
         connect_msg.set_service_id(service_id.to_string());
         connect_msg.set_correlation_id(correlation_id.clone());
+        #[cfg(feature = "service-auth")]
+        {
+            if let Some(token) = self.registration_tokens.get(service_id) {
+                connect_msg.set_token(token.clone().into());
+            }
+        }
 
         let connect_msg_bytes = connect_msg
             .write_to_bytes()
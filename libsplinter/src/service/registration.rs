@@ -0,0 +1,288 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Registration tokens for services connecting to a splinter node.
+//!
+//! A registration token is a signature over a circuit and service ID, along with the time the
+//! token was issued and the time it expires, made by a key that the splinter node trusts.  A
+//! service processor presents the token when connecting a service, so that the node may verify
+//! that the processor is allowed to run that service.  As tokens expire, a captured token can't
+//! be used to register the service indefinitely, and short-lived tokens should be preferred.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::hex::{parse_hex, to_hex};
+use crate::protos::circuit;
+use crate::signing::{self, SignatureVerifier, Signer};
+
+/// How far ahead of the node's clock a token's issue time may be, to allow for clock skew between
+/// the token's issuer and the node.
+const MAX_CLOCK_SKEW_SECS: u64 = 60;
+
+/// A signed token authorizing a service to connect as a specific service on a specific circuit,
+/// until the token expires.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ServiceRegistrationToken {
+    public_key: Vec<u8>,
+    signature: Vec<u8>,
+    issued_at: u64,
+    expires_at: u64,
+}
+
+impl ServiceRegistrationToken {
+    /// Creates a token for the given circuit and service ID, signed by the given signer, which is
+    /// valid from now for the given duration.
+    pub fn new(
+        circuit: &str,
+        service_id: &str,
+        valid_for: Duration,
+        signer: &dyn Signer,
+    ) -> Result<Self, signing::Error> {
+        let issued_at = now();
+        let expires_at = issued_at.saturating_add(valid_for.as_secs());
+        Ok(Self {
+            public_key: signer.public_key().to_vec(),
+            signature: signer.sign(&token_message(circuit, service_id, issued_at, expires_at))?,
+            issued_at,
+            expires_at,
+        })
+    }
+
+    /// Creates a token from an existing public key, signature, issue time and expiry time; the
+    /// times are in seconds since the Unix epoch.
+    pub fn from_parts(
+        public_key: Vec<u8>,
+        signature: Vec<u8>,
+        issued_at: u64,
+        expires_at: u64,
+    ) -> Self {
+        Self {
+            public_key,
+            signature,
+            issued_at,
+            expires_at,
+        }
+    }
+
+    /// Returns the public key of the token's signer.
+    pub fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+
+    /// Returns the token's signature.
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+
+    /// Returns when the token was issued, in seconds since the Unix epoch.
+    pub fn issued_at(&self) -> u64 {
+        self.issued_at
+    }
+
+    /// Returns when the token expires, in seconds since the Unix epoch.
+    pub fn expires_at(&self) -> u64 {
+        self.expires_at
+    }
+
+    /// Verifies that the token was signed for the given circuit and service ID, and that it is
+    /// valid at the current time.
+    pub fn verify(
+        &self,
+        circuit: &str,
+        service_id: &str,
+        verifier: &dyn SignatureVerifier,
+    ) -> Result<bool, signing::Error> {
+        self.verify_at(circuit, service_id, now(), verifier)
+    }
+
+    fn verify_at(
+        &self,
+        circuit: &str,
+        service_id: &str,
+        now: u64,
+        verifier: &dyn SignatureVerifier,
+    ) -> Result<bool, signing::Error> {
+        if now.saturating_add(MAX_CLOCK_SKEW_SECS) < self.issued_at || now >= self.expires_at {
+            return Ok(false);
+        }
+
+        verifier.verify(
+            &token_message(circuit, service_id, self.issued_at, self.expires_at),
+            &self.signature,
+            &self.public_key,
+        )
+    }
+}
+
+/// The token is displayed as the hex-encoded public key and signature, followed by the issue and
+/// expiry times, separated by `.`s.
+impl fmt::Display for ServiceRegistrationToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}.{}",
+            to_hex(&self.public_key),
+            to_hex(&self.signature),
+            self.issued_at,
+            self.expires_at
+        )
+    }
+}
+
+impl FromStr for ServiceRegistrationToken {
+    type Err = InvalidTokenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(4, '.');
+        let public_key = parts
+            .next()
+            .filter(|part| !part.is_empty())
+            .ok_or_else(|| InvalidTokenError("missing public key".into()))?;
+        let signature = parts
+            .next()
+            .filter(|part| !part.is_empty())
+            .ok_or_else(|| InvalidTokenError("missing signature".into()))?;
+        let issued_at = parts
+            .next()
+            .ok_or_else(|| InvalidTokenError("missing issue time".into()))?
+            .parse()
+            .map_err(|err| InvalidTokenError(format!("invalid issue time: {}", err)))?;
+        let expires_at = parts
+            .next()
+            .ok_or_else(|| InvalidTokenError("missing expiry time".into()))?
+            .parse()
+            .map_err(|err| InvalidTokenError(format!("invalid expiry time: {}", err)))?;
+
+        Ok(Self {
+            public_key: parse_hex(public_key)
+                .map_err(|err| InvalidTokenError(format!("invalid public key: {}", err)))?,
+            signature: parse_hex(signature)
+                .map_err(|err| InvalidTokenError(format!("invalid signature: {}", err)))?,
+            issued_at,
+            expires_at,
+        })
+    }
+}
+
+impl From<circuit::ServiceRegistrationToken> for ServiceRegistrationToken {
+    fn from(mut token: circuit::ServiceRegistrationToken) -> Self {
+        Self {
+            public_key: token.take_public_key(),
+            signature: token.take_signature(),
+            issued_at: token.get_issued_at(),
+            expires_at: token.get_expires_at(),
+        }
+    }
+}
+
+impl From<ServiceRegistrationToken> for circuit::ServiceRegistrationToken {
+    fn from(token: ServiceRegistrationToken) -> Self {
+        let mut proto = circuit::ServiceRegistrationToken::new();
+        proto.set_public_key(token.public_key);
+        proto.set_signature(token.signature);
+        proto.set_issued_at(token.issued_at);
+        proto.set_expires_at(token.expires_at);
+        proto
+    }
+}
+
+/// Returned when a string cannot be parsed as a `ServiceRegistrationToken`.
+#[derive(Debug)]
+pub struct InvalidTokenError(pub String);
+
+impl std::error::Error for InvalidTokenError {}
+
+impl fmt::Display for InvalidTokenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid registration token: {}", self.0)
+    }
+}
+
+fn token_message(circuit: &str, service_id: &str, issued_at: u64, expires_at: u64) -> Vec<u8> {
+    format!("{}::{}::{}::{}", circuit, service_id, issued_at, expires_at).into_bytes()
+}
+
+/// Returns the current time in seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::signing::hash::{HashSigner, HashVerifier};
+
+    /// Test that a token verifies only for the circuit and service ID it was signed for.
+    #[test]
+    fn test_token_verify() {
+        let token =
+            ServiceRegistrationToken::new("alpha", "abc", Duration::from_secs(60), &HashSigner)
+                .expect("Unable to create token");
+
+        assert!(token.verify("alpha", "abc", &HashVerifier).unwrap());
+        assert!(!token.verify("alpha", "def", &HashVerifier).unwrap());
+        assert!(!token.verify("beta", "abc", &HashVerifier).unwrap());
+    }
+
+    /// Test that a token only verifies between its issue time, allowing for clock skew, and its
+    /// expiry time, and that its times can't be changed without invalidating the signature.
+    #[test]
+    fn test_token_expiry() {
+        let token =
+            ServiceRegistrationToken::new("alpha", "abc", Duration::from_secs(60), &HashSigner)
+                .expect("Unable to create token");
+        let issued_at = token.issued_at();
+        assert_eq!(token.expires_at(), issued_at + 60);
+
+        let verify_at = |token: &ServiceRegistrationToken, now| {
+            token.verify_at("alpha", "abc", now, &HashVerifier).unwrap()
+        };
+        assert!(verify_at(&token, issued_at + 59));
+        assert!(!verify_at(&token, issued_at + 60));
+        assert!(verify_at(&token, issued_at - MAX_CLOCK_SKEW_SECS));
+        assert!(!verify_at(&token, issued_at - MAX_CLOCK_SKEW_SECS - 1));
+
+        let extended = ServiceRegistrationToken::from_parts(
+            token.public_key().to_vec(),
+            token.signature().to_vec(),
+            issued_at,
+            issued_at + 3600,
+        );
+        assert!(!verify_at(&extended, issued_at + 120));
+    }
+
+    /// Test that a token may be written to and parsed from a string.
+    #[test]
+    fn test_token_string_round_trip() {
+        let token = ServiceRegistrationToken::from_parts(vec![1, 2, 3], vec![4, 5, 6], 100, 160);
+        let token_str = token.to_string();
+        assert_eq!("010203.040506.100.160", token_str);
+        assert_eq!(token, token_str.parse().unwrap());
+
+        assert!("010203".parse::<ServiceRegistrationToken>().is_err());
+        assert!("010203.040506".parse::<ServiceRegistrationToken>().is_err());
+        assert!("zz.040506.100.160"
+            .parse::<ServiceRegistrationToken>()
+            .is_err());
+        assert!("010203.040506.100.later"
+            .parse::<ServiceRegistrationToken>()
+            .is_err());
+    }
+}
@@ -15,7 +15,7 @@
 //! Signing trait implementations backed by the Sawtooth SDK.
 //!
 //! Requires the "sawtooth-signing-compat" feature enabled
-use sawtooth_sdk::signing::{secp256k1, Context};
+use sawtooth_sdk::signing::{secp256k1, Context, PrivateKey};
 
 use crate::hex;
 
@@ -69,6 +69,66 @@ impl<'c> Signer for SawtoothSecp256k1RefSigner<'c> {
     }
 }
 
+/// A Sawtooth Secp256k Signer that owns a context.
+///
+/// The SawtoothSecp256k1Signer provides an implementation of the Signer trait, that uses its own
+/// Secp256k1Context, so that it may be shared between threads.
+pub struct SawtoothSecp256k1Signer {
+    context: secp256k1::Secp256k1Context,
+    private_key: secp256k1::Secp256k1PrivateKey,
+    public_key: Vec<u8>,
+}
+
+impl SawtoothSecp256k1Signer {
+    pub fn new(private_key: secp256k1::Secp256k1PrivateKey) -> Result<Self, Error> {
+        let context = secp256k1::Secp256k1Context::new();
+        let public_key = context
+            .get_public_key(&private_key)
+            .map_err(|err| Error::SigningError(format!("Unable to extract public key: {}", err)))?
+            .as_slice()
+            .to_vec();
+        Ok(Self {
+            context,
+            private_key,
+            public_key,
+        })
+    }
+
+    /// Creates a signer with a newly generated private key.
+    pub fn new_random() -> Result<Self, Error> {
+        let private_key = secp256k1::Secp256k1Context::new()
+            .new_random_private_key()
+            .map_err(|err| {
+                Error::SigningError(format!("Unable to generate private key: {}", err))
+            })?;
+        let private_key =
+            secp256k1::Secp256k1PrivateKey::from_hex(&private_key.as_hex()).map_err(|err| {
+                Error::SigningError(format!("Unable to read generated private key: {}", err))
+            })?;
+        Self::new(private_key)
+    }
+}
+
+impl Signer for SawtoothSecp256k1Signer {
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, Error> {
+        self.context
+            .sign(message, &self.private_key)
+            .map_err(|err| Error::SigningError(format!("Failed to sign message: {}", err)))
+            .and_then(|signature| {
+                hex::parse_hex(&signature).map_err(|err| {
+                    Error::SigningError(format!(
+                        "Unable to parse sawtooth signature {} into bytes: {}",
+                        signature, err
+                    ))
+                })
+            })
+    }
+
+    fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+}
+
 /// A Sawtooth Secp256k SignatureVerifier that references a context.
 ///
 /// The SawtoothSecp256k1RefSignatureVerifier provides an implementation of the SignatureVerifier
@@ -153,4 +213,13 @@ mod tests {
 
         test_signer_implementation(&sawtooth_signer, &sawtooth_verifier);
     }
+
+    #[test]
+    fn test_sawtooth_secp256k1_owned_signer() {
+        let sawtooth_signer =
+            SawtoothSecp256k1Signer::new_random().expect("Unable to create signer");
+        let sawtooth_verifier = SawtoothSecp256k1SignatureVerifier::new();
+
+        test_signer_implementation(&sawtooth_signer, &sawtooth_verifier);
+    }
 }
@@ -12,26 +12,58 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "admin-service-store")]
+use std::num::NonZeroUsize;
+#[cfg(feature = "admin-service-store")]
+use std::time::SystemTime;
+
+#[cfg(feature = "admin-service-store")]
+use crate::admin::service::{EventEntry, DEFAULT_IN_MEMORY_EVENT_LIMIT};
+#[cfg(feature = "admin-service-store")]
+use crate::admin::store::{memory::MemoryAdminServiceStore, AdminServiceStore};
 #[cfg(feature = "biome-credentials")]
 use crate::biome::{
     CredentialsStore, MemoryCredentialsStore, MemoryRefreshTokenStore, RefreshTokenStore,
 };
+#[cfg(feature = "biome-authorization")]
+use crate::biome::{GroupStore, MemoryGroupStore, MemoryRoleStore, RoleStore};
 #[cfg(feature = "biome-key-management")]
 use crate::biome::{KeyStore, MemoryKeyStore};
+#[cfg(feature = "biome-notifications")]
+use crate::biome::{MemoryNotificationStore, NotificationStore};
 use crate::biome::{MemoryUserStore, UserStore};
+#[cfg(feature = "routing-table-database")]
+use crate::circuit::routing::{memory::RoutingTable, RoutingTableReader, RoutingTableWriter};
+#[cfg(feature = "registry-database")]
+use crate::registry::{MemoryRegistry, RwRegistry};
+#[cfg(feature = "admin-service-store")]
+use crate::storage::sets::{mem::DurableBTreeSet, DurableOrderedSet};
 
-use super::StoreFactory;
+use super::{StoreFactory, StoreMigrationError};
 
 /// A `StoryFactory` backed by memory.
-#[derive(Default)]
 pub struct MemoryStoreFactory {
+    #[cfg(feature = "admin-service-store")]
+    admin_service_event_set: Box<dyn DurableOrderedSet<EventEntry, SystemTime>>,
+    #[cfg(feature = "admin-service-store")]
+    admin_service_store: MemoryAdminServiceStore,
     #[cfg(feature = "biome-credentials")]
     biome_credentials_store: MemoryCredentialsStore,
+    #[cfg(feature = "biome-authorization")]
+    biome_group_store: MemoryGroupStore,
     #[cfg(feature = "biome-key-management")]
     biome_key_store: MemoryKeyStore,
+    #[cfg(feature = "biome-notifications")]
+    biome_notification_store: MemoryNotificationStore,
     #[cfg(feature = "biome-credentials")]
     biome_refresh_token_store: MemoryRefreshTokenStore,
+    #[cfg(feature = "biome-authorization")]
+    biome_role_store: MemoryRoleStore,
     biome_user_store: MemoryUserStore,
+    #[cfg(feature = "registry-database")]
+    registry_store: MemoryRegistry,
+    #[cfg(feature = "routing-table-database")]
+    routing_table: RoutingTable,
 }
 
 impl MemoryStoreFactory {
@@ -50,34 +82,102 @@ impl MemoryStoreFactory {
         let biome_user_store = MemoryUserStore::new();
 
         Self {
+            #[cfg(feature = "admin-service-store")]
+            admin_service_event_set: DurableBTreeSet::new_boxed_with_bound(
+                NonZeroUsize::new(DEFAULT_IN_MEMORY_EVENT_LIMIT)
+                    .expect("The default event limit is not zero"),
+            ),
+            #[cfg(feature = "admin-service-store")]
+            admin_service_store: MemoryAdminServiceStore::new(),
             #[cfg(feature = "biome-credentials")]
             biome_credentials_store,
+            #[cfg(feature = "biome-authorization")]
+            biome_group_store: MemoryGroupStore::new(),
             #[cfg(feature = "biome-key-management")]
             biome_key_store,
+            #[cfg(feature = "biome-notifications")]
+            biome_notification_store: MemoryNotificationStore::new(),
             #[cfg(feature = "biome-credentials")]
             biome_refresh_token_store: MemoryRefreshTokenStore::new(),
+            #[cfg(feature = "biome-authorization")]
+            biome_role_store: MemoryRoleStore::new(),
             biome_user_store,
+            #[cfg(feature = "registry-database")]
+            registry_store: MemoryRegistry::new(),
+            #[cfg(feature = "routing-table-database")]
+            routing_table: RoutingTable::default(),
         }
     }
 }
 
+impl Default for MemoryStoreFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl StoreFactory for MemoryStoreFactory {
+    #[cfg(feature = "admin-service-store")]
+    fn get_admin_service_event_set(&self) -> Box<dyn DurableOrderedSet<EventEntry, SystemTime>> {
+        self.admin_service_event_set.clone()
+    }
+
+    #[cfg(feature = "admin-service-store")]
+    fn get_admin_service_store(&self) -> Box<dyn AdminServiceStore> {
+        Box::new(self.admin_service_store.clone())
+    }
+
     #[cfg(feature = "biome-credentials")]
     fn get_biome_credentials_store(&self) -> Box<dyn CredentialsStore> {
         Box::new(self.biome_credentials_store.clone())
     }
 
+    #[cfg(feature = "biome-authorization")]
+    fn get_biome_group_store(&self) -> Box<dyn GroupStore> {
+        Box::new(self.biome_group_store.clone())
+    }
+
     #[cfg(feature = "biome-key-management")]
     fn get_biome_key_store(&self) -> Box<dyn KeyStore> {
         Box::new(self.biome_key_store.clone())
     }
 
+    #[cfg(feature = "biome-notifications")]
+    fn get_biome_notification_store(&self) -> Box<dyn NotificationStore> {
+        Box::new(self.biome_notification_store.clone())
+    }
+
     #[cfg(feature = "biome-credentials")]
     fn get_biome_refresh_token_store(&self) -> Box<dyn RefreshTokenStore> {
         Box::new(self.biome_refresh_token_store.clone())
     }
 
+    #[cfg(feature = "biome-authorization")]
+    fn get_biome_role_store(&self) -> Box<dyn RoleStore> {
+        Box::new(self.biome_role_store.clone())
+    }
+
     fn get_biome_user_store(&self) -> Box<dyn UserStore> {
         Box::new(self.biome_user_store.clone())
     }
+
+    #[cfg(feature = "registry-database")]
+    fn get_registry_store(&self) -> Box<dyn RwRegistry> {
+        Box::new(self.registry_store.clone())
+    }
+
+    #[cfg(feature = "routing-table-database")]
+    fn get_routing_table_reader(&self) -> Box<dyn RoutingTableReader> {
+        Box::new(self.routing_table.clone())
+    }
+
+    #[cfg(feature = "routing-table-database")]
+    fn get_routing_table_writer(&self) -> Box<dyn RoutingTableWriter> {
+        Box::new(self.routing_table.clone())
+    }
+
+    fn run_migrations(&self) -> Result<(), StoreMigrationError> {
+        // Memory stores have no schema to migrate
+        Ok(())
+    }
 }
@@ -17,30 +17,68 @@ use diesel::{
     sqlite::SqliteConnection,
 };
 
-use super::StoreFactory;
+use super::{StoreFactory, StoreMigrationError};
 
 /// A `StoryFactory` backed by a SQLite database.
 pub struct SqliteStoreFactory {
     pool: Pool<ConnectionManager<SqliteConnection>>,
+    // The reader and writer share one table so that they share its cache
+    #[cfg(feature = "routing-table-database")]
+    routing_table: crate::circuit::routing::diesel::DieselRoutingTable<SqliteConnection>,
 }
 
 impl SqliteStoreFactory {
     pub fn new(pool: Pool<ConnectionManager<SqliteConnection>>) -> Self {
-        Self { pool }
+        Self {
+            #[cfg(feature = "routing-table-database")]
+            routing_table: crate::circuit::routing::diesel::DieselRoutingTable::new(pool.clone()),
+            pool,
+        }
     }
 }
 
 impl StoreFactory for SqliteStoreFactory {
+    #[cfg(feature = "admin-service-store")]
+    fn get_admin_service_event_set(
+        &self,
+    ) -> Box<
+        dyn crate::storage::sets::DurableOrderedSet<
+            crate::admin::service::EventEntry,
+            std::time::SystemTime,
+        >,
+    > {
+        Box::new(crate::admin::store::diesel::DieselAdminServiceEventSet::new(self.pool.clone()))
+    }
+
+    #[cfg(feature = "admin-service-store")]
+    fn get_admin_service_store(&self) -> Box<dyn crate::admin::store::AdminServiceStore> {
+        Box::new(crate::admin::store::diesel::DieselAdminServiceStore::new(
+            self.pool.clone(),
+        ))
+    }
+
     #[cfg(feature = "biome-credentials")]
     fn get_biome_credentials_store(&self) -> Box<dyn crate::biome::CredentialsStore> {
         Box::new(crate::biome::DieselCredentialsStore::new(self.pool.clone()))
     }
 
+    #[cfg(feature = "biome-authorization")]
+    fn get_biome_group_store(&self) -> Box<dyn crate::biome::GroupStore> {
+        Box::new(crate::biome::DieselGroupStore::new(self.pool.clone()))
+    }
+
     #[cfg(feature = "biome-key-management")]
     fn get_biome_key_store(&self) -> Box<dyn crate::biome::KeyStore> {
         Box::new(crate::biome::DieselKeyStore::new(self.pool.clone()))
     }
 
+    #[cfg(feature = "biome-notifications")]
+    fn get_biome_notification_store(&self) -> Box<dyn crate::biome::NotificationStore> {
+        Box::new(crate::biome::DieselNotificationStore::new(
+            self.pool.clone(),
+        ))
+    }
+
     #[cfg(feature = "biome-credentials")]
     fn get_biome_refresh_token_store(&self) -> Box<dyn crate::biome::RefreshTokenStore> {
         Box::new(crate::biome::DieselRefreshTokenStore::new(
@@ -48,7 +86,50 @@ impl StoreFactory for SqliteStoreFactory {
         ))
     }
 
+    #[cfg(feature = "biome-authorization")]
+    fn get_biome_role_store(&self) -> Box<dyn crate::biome::RoleStore> {
+        Box::new(crate::biome::DieselRoleStore::new(self.pool.clone()))
+    }
+
     fn get_biome_user_store(&self) -> Box<dyn crate::biome::UserStore> {
         Box::new(crate::biome::DieselUserStore::new(self.pool.clone()))
     }
+
+    #[cfg(feature = "registry-database")]
+    fn get_registry_store(&self) -> Box<dyn crate::registry::RwRegistry> {
+        Box::new(crate::registry::DieselRegistry::new(self.pool.clone()))
+    }
+
+    #[cfg(feature = "routing-table-database")]
+    fn get_routing_table_reader(&self) -> Box<dyn crate::circuit::routing::RoutingTableReader> {
+        Box::new(self.routing_table.clone())
+    }
+
+    #[cfg(feature = "routing-table-database")]
+    fn get_routing_table_writer(&self) -> Box<dyn crate::circuit::routing::RoutingTableWriter> {
+        Box::new(self.routing_table.clone())
+    }
+
+    fn run_migrations(&self) -> Result<(), StoreMigrationError> {
+        let connection = self.pool.get().map_err(|err| {
+            StoreMigrationError(format!("Failed to get database connection: {}", err))
+        })?;
+
+        #[cfg(feature = "admin-service-store")]
+        crate::admin::store::diesel::migrations::run_sqlite_migrations(&connection)
+            .map_err(|err| StoreMigrationError(format!("Admin service store: {}", err)))?;
+
+        crate::biome::migrations::run_sqlite_migrations(&connection)
+            .map_err(|err| StoreMigrationError(format!("Biome stores: {}", err)))?;
+
+        #[cfg(feature = "registry-database")]
+        crate::registry::run_sqlite_migrations(&connection)
+            .map_err(|err| StoreMigrationError(format!("Registry: {}", err)))?;
+
+        #[cfg(feature = "routing-table-database")]
+        crate::circuit::routing::diesel::migrations::run_sqlite_migrations(&connection)
+            .map_err(|err| StoreMigrationError(format!("Routing table: {}", err)))?;
+
+        Ok(())
+    }
 }
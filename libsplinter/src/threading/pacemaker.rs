@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
+    atomic::{AtomicBool, AtomicU64, Ordering},
     mpsc::Sender,
     Arc,
 };
@@ -79,10 +79,11 @@ where
         let running = Arc::new(AtomicBool::new(true));
 
         let running_clone = running.clone();
-        let interval = self
-            .interval
-            .take()
-            .ok_or_else(|| PacemakerStartError("No interval provided".into()))?;
+        let interval =
+            Arc::new(AtomicU64::new(self.interval.take().ok_or_else(|| {
+                PacemakerStartError("No interval provided".into())
+            })?));
+        let interval_clone = interval.clone();
         let sender = self
             .sender
             .take()
@@ -97,9 +98,9 @@ where
             .spawn(move || {
                 let mut start = Instant::now();
                 let loop_duration = Duration::from_secs(1);
-                let pace_duration = Duration::from_secs(interval);
 
                 while running_clone.load(Ordering::SeqCst) {
+                    let pace_duration = Duration::from_secs(interval_clone.load(Ordering::SeqCst));
                     if start.elapsed() >= pace_duration {
                         start = Instant::now();
                         if let Err(err) = sender.send(new_message()) {
@@ -118,6 +119,7 @@ where
         Ok(Pacemaker {
             join_handle,
             shutdown_signaler: ShutdownSignaler { running },
+            interval_handle: IntervalHandle { interval },
         })
     }
 }
@@ -128,6 +130,7 @@ where
 pub struct Pacemaker {
     join_handle: thread::JoinHandle<()>,
     shutdown_signaler: ShutdownSignaler,
+    interval_handle: IntervalHandle,
 }
 
 impl Pacemaker {
@@ -144,6 +147,11 @@ impl Pacemaker {
         self.shutdown_signaler.clone()
     }
 
+    /// Returns a handle that can change the firing interval while the pacemaker is running.
+    pub fn interval_handle(&self) -> IntervalHandle {
+        self.interval_handle.clone()
+    }
+
     pub fn await_shutdown(self) {
         if let Err(err) = self.join_handle.join() {
             error!("Failed to shutdown heartbeat monitor gracefully: {:?}", err);
@@ -161,3 +169,24 @@ impl ShutdownSignaler {
         self.running.store(false, Ordering::SeqCst)
     }
 }
+
+/// Changes the firing interval of a running `Pacemaker`.
+///
+/// The new interval takes effect on the pacemaker's next tick, measured from the last message
+/// fired.
+#[derive(Clone)]
+pub struct IntervalHandle {
+    interval: Arc<AtomicU64>,
+}
+
+impl IntervalHandle {
+    /// Returns the current firing interval in seconds.
+    pub fn interval(&self) -> u64 {
+        self.interval.load(Ordering::SeqCst)
+    }
+
+    /// Sets the firing interval in seconds.
+    pub fn set_interval(&self, interval: u64) {
+        self.interval.store(interval, Ordering::SeqCst)
+    }
+}
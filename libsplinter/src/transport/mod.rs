@@ -80,6 +80,16 @@ pub trait Connection: Send {
 
     /// Returns a `mio::event::Evented` for this connection which can be used for polling.
     fn evented(&self) -> &dyn Evented;
+
+    /// Return the identity of the remote peer, if it was verified by the transport.
+    ///
+    /// For TLS connections, this is the common name of the peer's certificate, provided the
+    /// certificate was verified against the configured certificate authority. For Unix domain
+    /// socket connections accepted by a listener, this is the peer's user ID, as `uid:<uid>`.
+    /// Connection types that do not verify the remote peer return `None`.
+    fn peer_identity(&self) -> Option<String> {
+        None
+    }
 }
 
 pub trait Listener: Send {
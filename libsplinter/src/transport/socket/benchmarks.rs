@@ -0,0 +1,86 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module contains benchmark tests for the round-trip latency of messages sent over socket
+//! connections, where each end waits for its connection to become readable before receiving, as
+//! the mesh does.
+
+extern crate test;
+
+use std::thread;
+
+use mio::{Events, Poll, PollOpt, Ready, Token};
+use test::Bencher;
+
+use crate::transport::{Connection, RecvError, Transport};
+
+use super::tls::tests::create_test_tls_transport;
+use super::TcpTransport;
+
+/// The size of the message sent in each direction, which is typical of a two-phase commit
+/// consensus message.
+const MESSAGE_SIZE: usize = 512;
+
+#[bench]
+fn bench_tcp_round_trip(b: &mut Bencher) {
+    bench_round_trip(b, TcpTransport::default(), "tcp://127.0.0.1:0");
+}
+
+#[bench]
+fn bench_tls_round_trip(b: &mut Bencher) {
+    bench_round_trip(b, create_test_tls_transport(true), "tcps://127.0.0.1:0");
+}
+
+// Measure the time taken to send a message from a client to a server and back again.
+fn bench_round_trip<T: Transport + Send + 'static>(b: &mut Bencher, mut transport: T, bind: &str) {
+    let mut listener = transport.listen(bind).expect("Unable to listen");
+    let endpoint = listener.endpoint();
+
+    let handle = thread::spawn(move || transport.connect(&endpoint).expect("Unable to connect"));
+    let mut server = listener.accept().expect("Unable to accept connection");
+    let mut client = handle.join().expect("Unable to join connect thread");
+
+    let poll = Poll::new().expect("Unable to create poll");
+    for (connection, token) in &[(&client, Token(0)), (&server, Token(1))] {
+        poll.register(
+            connection.evented(),
+            *token,
+            Ready::readable(),
+            PollOpt::level(),
+        )
+        .expect("Unable to register connection");
+    }
+    let mut events = Events::with_capacity(8);
+
+    let message = vec![0u8; MESSAGE_SIZE];
+    b.iter(|| {
+        client.send(&message).expect("Unable to send request");
+        let request = recv(&poll, &mut events, &mut server);
+        server.send(&request).expect("Unable to send response");
+        recv(&poll, &mut events, &mut client)
+    });
+}
+
+// Receive a message from the connection, waiting on the poll whenever the connection would block.
+fn recv(poll: &Poll, events: &mut Events, connection: &mut Box<dyn Connection>) -> Vec<u8> {
+    loop {
+        match connection.recv() {
+            Ok(message) => return message,
+            Err(RecvError::WouldBlock) => {
+                poll.poll(events, None).expect("Unable to poll");
+            }
+            Err(err) => panic!("Unable to receive message: {}", err),
+        }
+    }
+}
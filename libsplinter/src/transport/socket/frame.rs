@@ -12,13 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::borrow::Cow;
 use std::io::{self, Cursor, Read, Write};
 use std::thread;
 use std::time::Duration;
 
-use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use byteorder::{BigEndian, ByteOrder, ReadBytesExt, WriteBytesExt};
+#[cfg(feature = "frame-v2")]
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
 
 const HEADER_LENGTH: usize = 6;
+/// The length of a v2 frame header: version, flags, data length and data checksum.
+#[cfg(feature = "frame-v2")]
+const HEADER_V2_LENGTH: usize = 11;
+/// The v2 header flag set when the frame data has been deflate-compressed.
+#[cfg(feature = "frame-v2")]
+const FLAG_COMPRESSED: u8 = 0b0000_0001;
+/// Frame data smaller than this is sent uncompressed, as it is unlikely to shrink.
+#[cfg(feature = "frame-v2")]
+const COMPRESSION_THRESHOLD: usize = 1024;
+
+/// The largest amount of data, in bytes, that a v2 frame may carry, both as transmitted and after
+/// decompression.
+#[cfg(feature = "frame-v2")]
+pub const MAX_FRAME_SIZE: usize = 64 * 1024 * 1024;
 
 /// An error that may be returned during frame-related operations
 #[derive(Debug)]
@@ -28,6 +45,12 @@ pub enum FrameError {
     InvalidHeaderLength(usize),
     UnsupportedVersion,
     HandshakeFailure(String),
+    #[cfg(feature = "frame-v2")]
+    InvalidDataChecksum,
+    #[cfg(feature = "frame-v2")]
+    FrameTooLarge,
+    #[cfg(feature = "frame-v2")]
+    CompressionError(String),
 }
 
 impl std::fmt::Display for FrameError {
@@ -42,6 +65,16 @@ impl std::fmt::Display for FrameError {
             ),
             FrameError::UnsupportedVersion => f.write_str("Unsupported frame version"),
             FrameError::HandshakeFailure(msg) => f.write_str(&msg),
+            #[cfg(feature = "frame-v2")]
+            FrameError::InvalidDataChecksum => f.write_str("Invalid checksum for frame data"),
+            #[cfg(feature = "frame-v2")]
+            FrameError::FrameTooLarge => write!(
+                f,
+                "Frame exceeds the maximum frame size of {} bytes",
+                MAX_FRAME_SIZE
+            ),
+            #[cfg(feature = "frame-v2")]
+            FrameError::CompressionError(msg) => write!(f, "Frame compression error: {}", msg),
         }
     }
 }
@@ -54,6 +87,12 @@ impl std::error::Error for FrameError {
             FrameError::InvalidHeaderLength(_) => None,
             FrameError::UnsupportedVersion => None,
             FrameError::HandshakeFailure(_) => None,
+            #[cfg(feature = "frame-v2")]
+            FrameError::InvalidDataChecksum => None,
+            #[cfg(feature = "frame-v2")]
+            FrameError::FrameTooLarge => None,
+            #[cfg(feature = "frame-v2")]
+            FrameError::CompressionError(_) => None,
         }
     }
 }
@@ -68,9 +107,35 @@ impl From<io::Error> for FrameError {
 ///
 /// This specifies the version of the frame, based on what value is sent during frame transmission.
 /// It indicates header style and data requirements.
+///
+/// - `V1` frames carry a length-prefixed payload, with a checksum over the header only.
+/// - `V2` frames add a CRC32C checksum of the data, optional deflate compression of the data and
+///   a maximum frame size of [`MAX_FRAME_SIZE`].
+///
+/// [`MAX_FRAME_SIZE`]: constant.MAX_FRAME_SIZE.html
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum FrameVersion {
     V1 = 1,
+    #[cfg(feature = "frame-v2")]
+    V2 = 2,
+}
+
+impl FrameVersion {
+    /// The most recent frame version supported, which is offered during frame negotiation.
+    #[cfg(not(feature = "frame-v2"))]
+    pub const LATEST: FrameVersion = FrameVersion::V1;
+    /// The most recent frame version supported, which is offered during frame negotiation.
+    #[cfg(feature = "frame-v2")]
+    pub const LATEST: FrameVersion = FrameVersion::V2;
+
+    fn from_u16(version: u16) -> Option<Self> {
+        match version {
+            1 => Some(FrameVersion::V1),
+            #[cfg(feature = "frame-v2")]
+            2 => Some(FrameVersion::V2),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for FrameVersion {
@@ -92,57 +157,125 @@ impl Frame {
         self.data
     }
 
-    /// Read a frame from the given reader.
+    /// Construct a frame from the data received after the given header.
+    ///
+    /// For v2 frames, the data is verified against its checksum and decompressed.
+    fn from_data(frame_header: &FrameHeader, data: Vec<u8>) -> Result<Self, FrameError> {
+        match *frame_header {
+            FrameHeader::V1 { .. } => Ok(Self { data }),
+            #[cfg(feature = "frame-v2")]
+            FrameHeader::V2 {
+                compressed,
+                checksum,
+                ..
+            } => {
+                if crc32c(&data) != checksum {
+                    return Err(FrameError::InvalidDataChecksum);
+                }
+
+                if compressed {
+                    Ok(Self {
+                        data: decompress(&data)?,
+                    })
+                } else {
+                    Ok(Self { data })
+                }
+            }
+        }
+    }
+}
+
+/// Reads frames from a non-blocking stream.
+///
+/// Rather than waiting for the stream when a frame has only partially arrived, the reader keeps
+/// the bytes received so far and returns a `WouldBlock` error. Reading resumes from the buffered
+/// bytes on the next call, which should be made once the stream is reported as readable again.
+///
+/// The reader never reads past the end of the current frame, so any following frames remain in
+/// the stream and continue to be reported as readable.
+#[derive(Default)]
+pub struct FrameReader {
+    buffer: Vec<u8>,
+}
+
+impl FrameReader {
+    /// Construct a new FrameReader with an empty buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the next frame, continuing from any partially received frame.
     ///
     /// # Errors
     ///
     /// This function returns an error if:
     ///
+    /// - a complete frame has not been received and the reader would block, in which case the
+    ///   error is an `IoError` of kind `WouldBlock`
+    /// - the reader is closed, in which case the error is an `IoError` of kind `UnexpectedEof`
     /// - the header is malformed
-    /// - the data length doesn't match the header length
+    /// - the data fails its checksum, is too large or cannot be decompressed (v2 only)
     /// - an IO error occurs
-    pub fn read<R: Read>(reader: &mut R) -> Result<Self, FrameError> {
-        let frame_header = loop {
-            match FrameHeader::read(reader) {
-                Err(FrameError::IoError(ref e)) if e.kind() == io::ErrorKind::WouldBlock => {
-                    thread::sleep(Duration::from_millis(100));
-                    continue;
-                }
-                Err(err) => return Err(err),
-                Ok(header) => break header,
-            };
-        };
+    pub fn read<R: Read>(&mut self, reader: &mut R) -> Result<Frame, FrameError> {
+        loop {
+            let remaining = self.remaining_length()?;
+            if remaining == 0 {
+                return self.take_frame();
+            }
 
-        match frame_header {
-            FrameHeader::V1 { length } => {
-                let mut buffer = vec![0; length as usize];
-                let mut remaining = &mut buffer[..];
-
-                while !remaining.is_empty() {
-                    match reader.read(remaining) {
-                        Ok(0) => break,
-                        Ok(n) => {
-                            let tmp = remaining;
-                            remaining = &mut tmp[n..];
-                        }
-                        Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => {}
-                        Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                            thread::sleep(Duration::from_millis(100));
-                        }
-                        Err(e) => return Err(FrameError::IoError(e)),
-                    }
+            let start = self.buffer.len();
+            self.buffer.resize(start + remaining, 0);
+            let result = reader.read(&mut self.buffer[start..]);
+            self.buffer
+                .truncate(start + result.as_ref().copied().unwrap_or(0));
+
+            match result {
+                Ok(0) if start == 0 => {
+                    return Err(FrameError::IoError(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "Connection closed",
+                    )))
                 }
-                if !remaining.is_empty() {
-                    Err(FrameError::IoError(io::Error::new(
+                Ok(0) => {
+                    return Err(FrameError::IoError(io::Error::new(
                         io::ErrorKind::UnexpectedEof,
                         "Could not receive complete frame",
                     )))
-                } else {
-                    Ok(Self { data: buffer })
                 }
+                Ok(_) => (),
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => (),
+                Err(e) => return Err(FrameError::IoError(e)),
             }
         }
     }
+
+    /// The number of bytes that must still be read to complete the current frame.
+    ///
+    /// Until the header has been received, this is the number of bytes remaining in the header.
+    fn remaining_length(&self) -> Result<usize, FrameError> {
+        let version_length = std::mem::size_of::<u16>();
+        if self.buffer.len() < version_length {
+            return Ok(version_length - self.buffer.len());
+        }
+
+        let header_length = FrameHeader::encoded_length(BigEndian::read_u16(&self.buffer))?;
+        if self.buffer.len() < header_length {
+            return Ok(header_length - self.buffer.len());
+        }
+
+        let frame_header = FrameHeader::read(&mut Cursor::new(&self.buffer[..]))?;
+        Ok(header_length + frame_header.data_length()? - self.buffer.len())
+    }
+
+    /// Remove the completed frame from the buffer.
+    fn take_frame(&mut self) -> Result<Frame, FrameError> {
+        let mut cursor = Cursor::new(&self.buffer[..]);
+        let frame_header = FrameHeader::read(&mut cursor)?;
+        let data = self.buffer.split_off(cursor.position() as usize);
+        self.buffer.clear();
+
+        Frame::from_data(&frame_header, data)
+    }
 }
 
 /// A Frame of referenced data to be transmitted using a specified version.
@@ -164,10 +297,29 @@ impl<'a> FrameRef<'a> {
     ///
     /// # Errors
     ///
-    /// Returns a FrameError if an IO error occurs.
+    /// Returns a FrameError if an IO error occurs, or if a v2 frame is too large or cannot be
+    /// compressed.
     pub fn write<W: Write>(self, writer: &mut W) -> Result<(), FrameError> {
-        let frame_header = match self.version {
-            FrameVersion::V1 => FrameHeader::v1(self.data.len() as u32),
+        let (frame_header, data): (FrameHeader, Cow<[u8]>) = match self.version {
+            FrameVersion::V1 => (
+                FrameHeader::v1(self.data.len() as u32),
+                Cow::Borrowed(self.data),
+            ),
+            #[cfg(feature = "frame-v2")]
+            FrameVersion::V2 => {
+                if self.data.len() > MAX_FRAME_SIZE {
+                    return Err(FrameError::FrameTooLarge);
+                }
+
+                let (compressed, data) = match compress(self.data)? {
+                    Some(compressed_data) => (true, Cow::Owned(compressed_data)),
+                    None => (false, Cow::Borrowed(self.data)),
+                };
+                (
+                    FrameHeader::v2(compressed, data.len() as u32, crc32c(&data)),
+                    data,
+                )
+            }
         };
         loop {
             match frame_header.write(writer) {
@@ -180,7 +332,7 @@ impl<'a> FrameRef<'a> {
             }
         }
 
-        let mut buffer = &self.data[..];
+        let mut buffer = &data[..];
         while !buffer.is_empty() {
             match writer.write(buffer) {
                 Ok(0) => {
@@ -207,7 +359,15 @@ impl<'a> FrameRef<'a> {
 /// Each variant corresponds to the implementation for a given version.
 #[derive(Debug, PartialEq)]
 enum FrameHeader {
-    V1 { length: u32 },
+    V1 {
+        length: u32,
+    },
+    #[cfg(feature = "frame-v2")]
+    V2 {
+        compressed: bool,
+        length: u32,
+        checksum: u32,
+    },
 }
 
 impl FrameHeader {
@@ -216,6 +376,49 @@ impl FrameHeader {
         FrameHeader::V1 { length }
     }
 
+    /// Construct a version 2 frame header for data of the given length and CRC32C checksum.
+    #[cfg(feature = "frame-v2")]
+    fn v2(compressed: bool, length: u32, checksum: u32) -> Self {
+        FrameHeader::V2 {
+            compressed,
+            length,
+            checksum,
+        }
+    }
+
+    /// The number of bytes in an encoded header of the given version, including its checksum.
+    ///
+    /// # Errors
+    ///
+    /// Returns a FrameError if the version does not match any of the existing variants.
+    fn encoded_length(version: u16) -> Result<usize, FrameError> {
+        match version {
+            1 => Ok(HEADER_LENGTH + 1),
+            #[cfg(feature = "frame-v2")]
+            2 => Ok(HEADER_V2_LENGTH + 1),
+            _ => Err(FrameError::UnsupportedVersion),
+        }
+    }
+
+    /// The number of data bytes that follow this header.
+    ///
+    /// # Errors
+    ///
+    /// Returns a FrameError if the length exceeds the maximum frame size (v2 only).
+    fn data_length(&self) -> Result<usize, FrameError> {
+        match *self {
+            FrameHeader::V1 { length } => Ok(length as usize),
+            #[cfg(feature = "frame-v2")]
+            FrameHeader::V2 { length, .. } => {
+                if length as usize > MAX_FRAME_SIZE {
+                    Err(FrameError::FrameTooLarge)
+                } else {
+                    Ok(length as usize)
+                }
+            }
+        }
+    }
+
     /// Read a FrameHeader from the given reader.
     ///
     /// This function uses the first 2 bytes of the stream to read the version, and constructs the
@@ -252,6 +455,30 @@ impl FrameHeader {
                     length: cursor.read_u32::<BigEndian>()?,
                 })
             }
+            #[cfg(feature = "frame-v2")]
+            2 => {
+                // Header length + checksum byte
+                let mut buffer = [0u8; HEADER_V2_LENGTH + 1];
+                let mut cursor = Cursor::new(&mut buffer[..]);
+                cursor.write_u16::<BigEndian>(2u16)?;
+
+                let n = reader.read(&mut cursor.get_mut()[std::mem::size_of::<u16>()..])?;
+                if n != HEADER_V2_LENGTH + 1 - std::mem::size_of::<u16>() {
+                    return Err(FrameError::InvalidHeaderLength(n));
+                }
+
+                let checksum = compute_checksum(&cursor.get_ref()[..HEADER_V2_LENGTH]);
+                if checksum != cursor.get_ref()[HEADER_V2_LENGTH] {
+                    return Err(FrameError::InvalidChecksum);
+                }
+
+                let flags = cursor.read_u8()?;
+                Ok(FrameHeader::V2 {
+                    compressed: flags & FLAG_COMPRESSED != 0,
+                    length: cursor.read_u32::<BigEndian>()?,
+                    checksum: cursor.read_u32::<BigEndian>()?,
+                })
+            }
             _ => Err(FrameError::UnsupportedVersion),
         }
     }
@@ -279,6 +506,25 @@ impl FrameHeader {
 
                 writer.write_all(&cursor.into_inner()[..])?;
             }
+            #[cfg(feature = "frame-v2")]
+            FrameHeader::V2 {
+                compressed,
+                length,
+                checksum,
+            } => {
+                let mut header_bytes = [0u8; HEADER_V2_LENGTH + 1];
+                let mut cursor = Cursor::new(&mut header_bytes[..]);
+
+                cursor.write_u16::<BigEndian>(2)?;
+                cursor.write_u8(if compressed { FLAG_COMPRESSED } else { 0 })?;
+                cursor.write_u32::<BigEndian>(length)?;
+                cursor.write_u32::<BigEndian>(checksum)?;
+
+                cursor.get_mut()[HEADER_V2_LENGTH] =
+                    compute_checksum(&cursor.get_ref()[..HEADER_V2_LENGTH]);
+
+                writer.write_all(&cursor.into_inner()[..])?;
+            }
         }
 
         Ok(())
@@ -304,6 +550,85 @@ fn compute_checksum(buffer: &[u8]) -> u8 {
     lrc as u8
 }
 
+/// The reversed CRC-32C (Castagnoli) polynomial
+#[cfg(feature = "frame-v2")]
+const CRC32C_POLYNOMIAL: u32 = 0x82F6_3B78;
+
+/// The CRC-32C remainder of each byte value, so that checksums are computed a byte at a time
+#[cfg(feature = "frame-v2")]
+const CRC32C_TABLE: [u32; 256] = crc32c_table();
+
+#[cfg(feature = "frame-v2")]
+const fn crc32c_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ CRC32C_POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+}
+
+/// Compute a CRC-32C (Castagnoli) checksum
+#[cfg(feature = "frame-v2")]
+fn crc32c(buffer: &[u8]) -> u32 {
+    !buffer.iter().fold(!0u32, |crc, b| {
+        CRC32C_TABLE[((crc ^ u32::from(*b)) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+/// Deflate the given frame data.
+///
+/// Returns `None` if the data is too small to be worth compressing, or if compressing it did not
+/// make it any smaller.
+#[cfg(feature = "frame-v2")]
+fn compress(data: &[u8]) -> Result<Option<Vec<u8>>, FrameError> {
+    if data.len() < COMPRESSION_THRESHOLD {
+        return Ok(None);
+    }
+
+    let mut encoder = DeflateEncoder::new(Vec::with_capacity(data.len() / 2), Compression::fast());
+    encoder
+        .write_all(data)
+        .map_err(|err| FrameError::CompressionError(err.to_string()))?;
+    let compressed = encoder
+        .finish()
+        .map_err(|err| FrameError::CompressionError(err.to_string()))?;
+
+    if compressed.len() < data.len() {
+        Ok(Some(compressed))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Inflate the given frame data, refusing to produce more than `MAX_FRAME_SIZE` bytes.
+#[cfg(feature = "frame-v2")]
+fn decompress(data: &[u8]) -> Result<Vec<u8>, FrameError> {
+    let mut decompressed = vec![];
+    DeflateDecoder::new(data)
+        .take(MAX_FRAME_SIZE as u64 + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|err| FrameError::CompressionError(err.to_string()))?;
+
+    if decompressed.len() > MAX_FRAME_SIZE {
+        return Err(FrameError::FrameTooLarge);
+    }
+
+    Ok(decompressed)
+}
+
 /// Negotiate the frame version for a given socket connection.
 pub enum FrameNegotiation {
     /// The Outbound variant transmits the min and max supported version, and expects to receive
@@ -313,8 +638,8 @@ pub enum FrameNegotiation {
         min: FrameVersion,
         max: FrameVersion,
     },
-    /// The Inbound variant transmits receives the min and max and decides if it should send its
-    /// version or `0`, depending on whether or not it falls in the range.
+    /// The Inbound variant receives the min and max and sends the highest version it supports
+    /// within that range, or `0` if its versions do not fall in the range.
     Inbound { version: FrameVersion },
 }
 
@@ -324,7 +649,8 @@ impl FrameNegotiation {
         FrameNegotiation::Outbound { min, max }
     }
 
-    /// Construct the inbound side of a negotiation with the given version.
+    /// Construct the inbound side of a negotiation with the given version, which is the highest
+    /// version the inbound side supports.
     pub fn inbound(version: FrameVersion) -> Self {
         FrameNegotiation::Inbound { version }
     }
@@ -349,23 +675,30 @@ impl FrameNegotiation {
 
                 let frame_version = stream.read_u16::<BigEndian>().map_err(Self::map_io_err)?;
 
-                match frame_version {
-                    0 => Err(FrameError::UnsupportedVersion),
-                    1 => Ok(FrameVersion::V1),
-                    _ => Err(FrameError::UnsupportedVersion),
+                if frame_version < min as u16 || frame_version > max as u16 {
+                    return Err(FrameError::UnsupportedVersion);
                 }
+
+                FrameVersion::from_u16(frame_version).ok_or(FrameError::UnsupportedVersion)
             }
             FrameNegotiation::Inbound { version } => {
                 let min = stream.read_u16::<BigEndian>().map_err(Self::map_io_err)?;
                 let max = stream.read_u16::<BigEndian>().map_err(Self::map_io_err)?;
-                if min > version as u16 || max < version as u16 {
-                    stream.write_u16::<BigEndian>(0).map_err(Self::map_io_err)?;
-                    Err(FrameError::UnsupportedVersion)
-                } else {
-                    stream
-                        .write_u16::<BigEndian>(version as u16)
-                        .map_err(Self::map_io_err)?;
-                    Ok(version)
+
+                // Older peers may only offer v1, so select the highest version both ends support
+                match FrameVersion::from_u16(std::cmp::min(max, version as u16))
+                    .filter(|selected| *selected as u16 >= min)
+                {
+                    Some(selected) => {
+                        stream
+                            .write_u16::<BigEndian>(selected as u16)
+                            .map_err(Self::map_io_err)?;
+                        Ok(selected)
+                    }
+                    None => {
+                        stream.write_u16::<BigEndian>(0).map_err(Self::map_io_err)?;
+                        Err(FrameError::UnsupportedVersion)
+                    }
                 }
             }
         }
@@ -498,10 +831,10 @@ mod tests {
             .expect("Unable to write frame header");
 
         header_cursor.set_position(0);
-        let FrameHeader::V1 { length } =
-            FrameHeader::read(&mut header_cursor).expect("Unable to read header");
-
-        assert_eq!(100, length);
+        assert_eq!(
+            FrameHeader::v1(100),
+            FrameHeader::read(&mut header_cursor).expect("Unable to read header")
+        );
     }
 
     /// Test that outbound frame version negotiation works:
@@ -641,7 +974,7 @@ mod tests {
     }
 
     /// Read a frame from a stream.  The stream will be constructed with a valid header and a short
-    /// data payload. Frame::read should result in a valid frame, with the expected data.
+    /// data payload. FrameReader::read should result in a valid frame, with the expected data.
     #[test]
     fn read_frame_v1() {
         let input = b"hello";
@@ -654,7 +987,9 @@ mod tests {
 
         cursor.set_position(0);
 
-        let frame = Frame::read(&mut cursor).expect("Unable to read frame");
+        let frame = FrameReader::new()
+            .read(&mut cursor)
+            .expect("Unable to read frame");
 
         assert_eq!(input.to_vec(), frame.data);
     }
@@ -671,11 +1006,244 @@ mod tests {
 
         cursor.set_position(0);
 
-        let frame = Frame::read(&mut cursor).expect("Unable to read frame");
+        let frame = FrameReader::new()
+            .read(&mut cursor)
+            .expect("Unable to read frame");
 
         assert_eq!(input.to_vec(), frame.data);
     }
 
+    /// Test that a FrameReader buffers partially received frames and resumes reading them:
+    /// 1. Provide part of a frame header and verify that the read would block
+    /// 2. Provide the rest of the header and part of the data, and verify the read would block
+    /// 3. Provide the rest of the frame and a second frame, and verify that the first frame is
+    ///    read without reading any of the second frame
+    /// 4. Verify that the second frame is read, and the read then blocks
+    /// 5. Close the stream and verify that the read reports the end of the stream
+    #[test]
+    fn frame_reader_partial_frames() {
+        let mut frames = Cursor::new(vec![]);
+        FrameRef::new(FrameVersion::V1, b"hello")
+            .write(&mut frames)
+            .expect("Unable to write first frame");
+        FrameRef::new(FrameVersion::V1, b"world")
+            .write(&mut frames)
+            .expect("Unable to write second frame");
+        let frames = frames.into_inner();
+        let frame_length = frames.len() / 2;
+
+        let mut stream = NonBlockingStream::default();
+        let mut reader = FrameReader::new();
+
+        stream.available.extend_from_slice(&frames[..3]);
+        assert_would_block(reader.read(&mut stream));
+
+        stream
+            .available
+            .extend_from_slice(&frames[3..HEADER_LENGTH + 3]);
+        assert_would_block(reader.read(&mut stream));
+
+        stream
+            .available
+            .extend_from_slice(&frames[HEADER_LENGTH + 3..]);
+        let frame = reader
+            .read(&mut stream)
+            .expect("Unable to read first frame");
+        assert_eq!(b"hello".to_vec(), frame.into_inner());
+        assert_eq!(frame_length, stream.available.len());
+
+        let frame = reader
+            .read(&mut stream)
+            .expect("Unable to read second frame");
+        assert_eq!(b"world".to_vec(), frame.into_inner());
+        assert_would_block(reader.read(&mut stream));
+
+        stream.closed = true;
+        match reader.read(&mut stream) {
+            Err(FrameError::IoError(ref e)) if e.kind() == io::ErrorKind::UnexpectedEof => (),
+            Err(err) => panic!("Produced invalid error: {}", err),
+            Ok(_) => panic!("Should not have read a frame"),
+        }
+    }
+
+    fn assert_would_block(result: Result<Frame, FrameError>) {
+        match result {
+            Err(FrameError::IoError(ref e)) if e.kind() == io::ErrorKind::WouldBlock => (),
+            Err(err) => panic!("Produced invalid error: {}", err),
+            Ok(_) => panic!("Should not have read a frame"),
+        }
+    }
+
+    /// A stream that returns only the bytes made available to it, and would block otherwise.
+    #[derive(Default)]
+    struct NonBlockingStream {
+        available: Vec<u8>,
+        closed: bool,
+    }
+
+    impl Read for NonBlockingStream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.closed {
+                return Ok(0);
+            }
+            if self.available.is_empty() {
+                return Err(io::Error::new(io::ErrorKind::WouldBlock, "would block"));
+            }
+
+            let n = std::cmp::min(buf.len(), self.available.len());
+            buf[..n].copy_from_slice(&self.available[..n]);
+            self.available.drain(..n);
+            Ok(n)
+        }
+    }
+
+    /// Test that the CRC32C checksum produces the standard check value for "123456789".
+    #[cfg(feature = "frame-v2")]
+    #[test]
+    fn crc32c_check_value() {
+        assert_eq!(0xE306_9283, crc32c(b"123456789"));
+        assert_eq!(0, crc32c(b""));
+        assert_eq!(0x8A91_36AA, crc32c(&[0u8; 32]));
+        assert_eq!(0x62A8_AB43, crc32c(&[0xffu8; 32]));
+    }
+
+    /// Test a round-trip write and read of a v2 FrameHeader, including the compression flag and
+    /// data checksum.
+    #[cfg(feature = "frame-v2")]
+    #[test]
+    fn round_trip_v2() {
+        let mut header_cursor = Cursor::new(vec![0u8; HEADER_V2_LENGTH + 1]);
+
+        let frame_header = FrameHeader::v2(true, 100, 0xDEAD_BEEF);
+        frame_header
+            .write(&mut header_cursor)
+            .expect("Unable to write frame header");
+
+        header_cursor.set_position(0);
+        assert_eq!(
+            frame_header,
+            FrameHeader::read(&mut header_cursor).expect("Unable to read header")
+        );
+    }
+
+    /// Write v2 frames to a stream and verify that equivalent frames are read back, where:
+    /// 1. a small frame is sent uncompressed
+    /// 2. a large, repetitive frame is sent compressed
+    #[cfg(feature = "frame-v2")]
+    #[test]
+    fn frame_round_trip_v2() {
+        let small = b"hello world".to_vec();
+        let large = vec![0u8; 64 * 1024];
+
+        let mut cursor = Cursor::new(vec![]);
+        FrameRef::new(FrameVersion::V2, &small)
+            .write(&mut cursor)
+            .expect("Unable to write small frame");
+        let small_frame_length = cursor.get_ref().len();
+        FrameRef::new(FrameVersion::V2, &large)
+            .write(&mut cursor)
+            .expect("Unable to write large frame");
+
+        assert_eq!(HEADER_V2_LENGTH + 1 + small.len(), small_frame_length);
+        assert!(cursor.get_ref().len() - small_frame_length < large.len());
+
+        cursor.set_position(0);
+        match FrameHeader::read(&mut cursor).expect("Unable to read header") {
+            FrameHeader::V2 { compressed, .. } => assert!(!compressed),
+            header => panic!("Unexpected header: {:?}", header),
+        }
+        cursor.set_position(small_frame_length as u64);
+        match FrameHeader::read(&mut cursor).expect("Unable to read header") {
+            FrameHeader::V2 { compressed, .. } => assert!(compressed),
+            header => panic!("Unexpected header: {:?}", header),
+        }
+
+        cursor.set_position(0);
+        assert_eq!(
+            small,
+            FrameReader::new()
+                .read(&mut cursor)
+                .expect("Unable to read small frame")
+                .into_inner()
+        );
+        assert_eq!(
+            large,
+            FrameReader::new()
+                .read(&mut cursor)
+                .expect("Unable to read large frame")
+                .into_inner()
+        );
+    }
+
+    /// Test that a v2 frame whose data has been corrupted in transit fails its data checksum.
+    #[cfg(feature = "frame-v2")]
+    #[test]
+    fn corrupt_frame_v2() {
+        let mut cursor = Cursor::new(vec![]);
+        FrameRef::new(FrameVersion::V2, b"hello world")
+            .write(&mut cursor)
+            .expect("Unable to write frame");
+
+        let last = cursor.get_ref().len() - 1;
+        cursor.get_mut()[last] ^= 0xff;
+        cursor.set_position(0);
+
+        match FrameReader::new().read(&mut cursor) {
+            Err(FrameError::InvalidDataChecksum) => (),
+            Err(err) => panic!("Produced invalid error: {}", err),
+            Ok(_) => panic!("Should not have read a corrupt frame"),
+        }
+    }
+
+    /// Test that a v2 frame header advertising more than the maximum frame size is rejected
+    /// before its data is read.
+    #[cfg(feature = "frame-v2")]
+    #[test]
+    fn oversized_frame_v2() {
+        let mut cursor = Cursor::new(vec![]);
+        FrameHeader::v2(false, MAX_FRAME_SIZE as u32 + 1, 0)
+            .write(&mut cursor)
+            .expect("Unable to write frame header");
+        cursor.set_position(0);
+
+        match FrameReader::new().read(&mut cursor) {
+            Err(FrameError::FrameTooLarge) => (),
+            Err(err) => panic!("Produced invalid error: {}", err),
+            Ok(_) => panic!("Should not have read an oversized frame"),
+        }
+    }
+
+    /// Test that frame negotiation selects the highest version supported by both ends:
+    /// 1. an outbound end offering only v1 and an inbound end supporting v2 agree on v1
+    /// 2. an outbound end offering v1-v2 and an inbound end supporting only v1 agree on v1
+    /// 3. both ends supporting v2 agree on v2
+    #[cfg(feature = "frame-v2")]
+    #[test]
+    fn negotiate_v2() {
+        let cases = vec![
+            (FrameVersion::V1, FrameVersion::V2, FrameVersion::V1),
+            (FrameVersion::V2, FrameVersion::V1, FrameVersion::V1),
+            (FrameVersion::V2, FrameVersion::V2, FrameVersion::V2),
+        ];
+
+        for (outbound_max, inbound_version, expected) in cases {
+            let (mut tx, mut rx) = stream::byte_stream_pair();
+
+            let join_handle = thread::spawn(move || {
+                FrameNegotiation::inbound(inbound_version)
+                    .negotiate(&mut rx)
+                    .expect("Should have successfully negotiated")
+            });
+
+            let version = FrameNegotiation::outbound(FrameVersion::V1, outbound_max)
+                .negotiate(&mut tx)
+                .expect("Unable to negotiate a valid version");
+
+            assert_eq!(expected, version);
+            assert_eq!(expected, join_handle.join().expect("Unable to join thread"));
+        }
+    }
+
     #[cfg(not(target_os = "unix"))]
     mod stream {
         use std::io::{Error as IoError, Read, Write};
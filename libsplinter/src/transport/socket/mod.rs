@@ -12,12 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(all(feature = "benchmark", test))]
+mod benchmarks;
 mod frame;
 mod tcp;
 mod tls;
+#[cfg(feature = "uds-transport")]
+mod uds;
 
 pub use tcp::TcpTransport;
 pub use tls::{TlsConnection, TlsInitError, TlsTransport};
+#[cfg(feature = "uds-transport")]
+pub use uds::UdsTransport;
 
 #[cfg(test)]
 pub mod tests {
@@ -21,7 +21,7 @@ use crate::transport::{
     SendError, Transport,
 };
 
-use super::frame::{Frame, FrameError, FrameNegotiation, FrameRef, FrameVersion};
+use super::frame::{FrameError, FrameNegotiation, FrameReader, FrameRef, FrameVersion};
 
 const PROTOCOL_PREFIX: &str = "tcp://";
 
@@ -48,8 +48,10 @@ impl Transport for TcpTransport {
         };
         // Connect a std::net::TcpStream to make sure connect() block
         let mut stream = TcpStream::connect(address)?;
+        // Frames are written as a header followed by the data, which must not be held back
+        stream.set_nodelay(true)?;
 
-        let frame_version = FrameNegotiation::outbound(FrameVersion::V1, FrameVersion::V1)
+        let frame_version = FrameNegotiation::outbound(FrameVersion::V1, FrameVersion::LATEST)
             .negotiate(&mut stream)
             .map_err(|err| match err {
                 FrameError::UnsupportedVersion => ConnectError::ProtocolError(
@@ -62,6 +64,7 @@ impl Transport for TcpTransport {
         let mio_stream = MioTcpStream::from_stream(stream)?;
         Ok(Box::new(TcpConnection {
             frame_version,
+            reader: FrameReader::new(),
             stream: mio_stream,
         }))
     }
@@ -95,14 +98,15 @@ struct TcpListener {
 impl Listener for TcpListener {
     fn accept(&mut self) -> Result<Box<dyn Connection>, AcceptError> {
         let (mut stream, _) = self.listener.accept()?;
+        stream.set_nodelay(true)?;
 
-        let frame_version = FrameNegotiation::inbound(FrameVersion::V1)
+        let frame_version = FrameNegotiation::inbound(FrameVersion::LATEST)
             .negotiate(&mut stream)
             .map_err(|err| match err {
                 FrameError::UnsupportedVersion => AcceptError::ProtocolError(format!(
                     "Local {} protocol version {} not supported by remote",
                     PROTOCOL_PREFIX,
-                    FrameVersion::V1
+                    FrameVersion::LATEST
                 )),
                 FrameError::IoError(err) => AcceptError::from(err),
                 err => AcceptError::ProtocolError(format!("Unexpected protocol error: {}", err)),
@@ -110,6 +114,7 @@ impl Listener for TcpListener {
 
         let connection = TcpConnection {
             frame_version,
+            reader: FrameReader::new(),
             stream: MioTcpStream::from_stream(stream)?,
         };
         Ok(Box::new(connection))
@@ -122,6 +127,7 @@ impl Listener for TcpListener {
 
 struct TcpConnection {
     frame_version: FrameVersion,
+    reader: FrameReader,
     stream: MioTcpStream,
 }
 
@@ -135,7 +141,7 @@ impl Connection for TcpConnection {
     }
 
     fn recv(&mut self) -> Result<Vec<u8>, RecvError> {
-        match Frame::read(&mut self.stream) {
+        match self.reader.read(&mut self.stream) {
             Err(FrameError::IoError(e)) => Err(RecvError::from(e)),
             Err(err) => Err(RecvError::ProtocolError(err.to_string())),
             Ok(frame) => Ok(frame.into_inner()),
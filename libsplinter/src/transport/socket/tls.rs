@@ -14,10 +14,12 @@
 
 use mio::{unix::EventedFd, Evented, Poll, PollOpt, Ready, Token};
 use openssl::error::ErrorStack;
+use openssl::nid::Nid;
 use openssl::ssl::{
     Error as OpensslError, HandshakeError, SslAcceptor, SslConnector, SslFiletype, SslMethod,
     SslStream, SslVerifyMode,
 };
+use openssl::x509::X509VerifyResult;
 use url::{ParseError, Url};
 
 use std::error::Error;
@@ -27,20 +29,23 @@ use std::net::{Ipv4Addr, Ipv6Addr, TcpListener, TcpStream};
 use std::os::unix::io::{AsRawFd, RawFd};
 use std::path::Path;
 
+use crate::transport::tls::{
+    set_peer_verification, unauthorized_peer_reason, TlsConfig, TlsConfigBuilder, TlsContexts,
+    TlsReloadHandle,
+};
 use crate::transport::{
     AcceptError, ConnectError, Connection, DisconnectError, ListenError, Listener, RecvError,
     SendError, Transport,
 };
 
-use super::frame::{Frame, FrameError, FrameNegotiation, FrameRef, FrameVersion};
+use super::frame::{FrameError, FrameNegotiation, FrameReader, FrameRef, FrameVersion};
 
 /// tls:// is deprecated, tcps:// should be used instead
 const DEPRECATED_PROTOCOL_PREFIX: &str = "tls://";
 const PROTOCOL_PREFIX: &str = "tcps://";
 
 pub struct TlsTransport {
-    connector: SslConnector,
-    acceptor: SslAcceptor,
+    contexts: TlsReloadHandle,
 }
 
 impl TlsTransport {
@@ -51,48 +56,76 @@ impl TlsTransport {
         server_key: String,
         server_cert: String,
     ) -> Result<Self, TlsInitError> {
-        let client_cert_path = Path::new(&client_cert);
-        let client_key_path = Path::new(&client_key);
-        let server_cert_path = Path::new(&server_cert);
-        let server_key_path = Path::new(&server_key);
-
-        // Build TLS Connector
-        let mut connector = SslConnector::builder(SslMethod::tls())?;
-        connector.set_private_key_file(&client_key_path, SslFiletype::PEM)?;
-        connector.set_certificate_chain_file(client_cert_path)?;
-        connector.check_private_key()?;
-
-        // Build TLS Acceptor
-        let mut acceptor = SslAcceptor::mozilla_modern(SslMethod::tls())?;
-        acceptor.set_private_key_file(server_key_path, SslFiletype::PEM)?;
-        acceptor.set_certificate_chain_file(&server_cert_path)?;
-        acceptor.check_private_key()?;
-
-        // if ca_cert is provided set as accept cert, otherwise set verify to none
-        let (acceptor, connector) = {
-            if let Some(ca_cert) = ca_cert {
-                let ca_cert_path = Path::new(&ca_cert);
-                acceptor.set_ca_file(ca_cert_path)?;
-                connector.set_ca_file(ca_cert_path)?;
-                connector.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
-                acceptor.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
-                let connector = connector.build();
-                let acceptor = acceptor.build();
-                (acceptor, connector)
-            } else {
-                connector.set_verify(SslVerifyMode::NONE);
-                acceptor.set_verify(SslVerifyMode::NONE);
-                let connector = connector.build();
-                let acceptor = acceptor.build();
-                (acceptor, connector)
-            }
-        };
+        let mut builder = TlsConfigBuilder::new()
+            .with_client_private_key_file(client_key)
+            .with_client_cert_file(client_cert)
+            .with_server_private_key_file(server_key)
+            .with_server_cert_file(server_cert);
+        if let Some(ca_cert) = ca_cert {
+            builder = builder.with_ca_certs_file(ca_cert);
+        }
+        let config = builder
+            .build()
+            .map_err(|err| TlsInitError::ProtocolError(err.to_string()))?;
+
+        Self::from_config(&config)
+    }
+
+    /// Creates a new `TlsTransport` from a `TlsConfig`.
+    ///
+    /// Peers are verified against the configuration's CA certificates, if any, in which case the
+    /// configured certificate revocation list and denied fingerprints are enforced for both
+    /// inbound and outbound connections.
+    pub fn from_config(config: &TlsConfig) -> Result<Self, TlsInitError> {
+        let config = config.clone();
+        let contexts = TlsReloadHandle::new(config.files(), move || build_contexts(&config))?;
 
-        Ok(TlsTransport {
-            connector,
-            acceptor,
-        })
+        Ok(TlsTransport { contexts })
     }
+
+    /// Returns a handle that can be used to reload this transport's certificates and keys.
+    ///
+    /// Listeners created by this transport use the reloaded material for subsequent handshakes.
+    pub fn reload_handle(&self) -> TlsReloadHandle {
+        self.contexts.clone()
+    }
+}
+
+fn build_contexts(config: &TlsConfig) -> Result<TlsContexts, ErrorStack> {
+    let client_cert_path = Path::new(config.client_cert_file());
+    let client_key_path = Path::new(config.client_private_key_file());
+    let server_cert_path = Path::new(config.server_cert_file());
+    let server_key_path = Path::new(config.server_private_key_file());
+
+    // Build TLS Connector
+    let mut connector = SslConnector::builder(SslMethod::tls())?;
+    connector.set_private_key_file(client_key_path, SslFiletype::PEM)?;
+    connector.set_certificate_chain_file(client_cert_path)?;
+    connector.check_private_key()?;
+
+    // Build TLS Acceptor
+    let mut acceptor = SslAcceptor::mozilla_modern(SslMethod::tls())?;
+    acceptor.set_private_key_file(server_key_path, SslFiletype::PEM)?;
+    acceptor.set_certificate_chain_file(server_cert_path)?;
+    acceptor.check_private_key()?;
+
+    // if ca_cert is provided set as accept cert, otherwise set verify to none
+    if let Some(ca_cert) = config.ca_certs_file() {
+        let ca_cert_path = Path::new(ca_cert);
+        acceptor.set_ca_file(ca_cert_path)?;
+        connector.set_ca_file(ca_cert_path)?;
+        let mode = SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT;
+        set_peer_verification(&mut connector, mode, config)?;
+        set_peer_verification(&mut acceptor, mode, config)?;
+    } else {
+        connector.set_verify(SslVerifyMode::NONE);
+        acceptor.set_verify(SslVerifyMode::NONE);
+    }
+
+    Ok(TlsContexts {
+        acceptor: acceptor.build(),
+        connector: connector.build(),
+    })
 }
 
 fn endpoint_to_dns_name(endpoint: &str) -> Result<String, ParseError> {
@@ -134,9 +167,11 @@ impl Transport for TlsTransport {
         let dns_name = endpoint_to_dns_name(address)?;
 
         let stream = TcpStream::connect(address)?;
-        let mut tls_stream = self.connector.connect(&dns_name, stream)?;
+        // Frames are written as a header followed by the data, which must not be held back
+        stream.set_nodelay(true)?;
+        let mut tls_stream = self.contexts.connector().connect(&dns_name, stream)?;
 
-        let frame_version = FrameNegotiation::outbound(FrameVersion::V1, FrameVersion::V1)
+        let frame_version = FrameNegotiation::outbound(FrameVersion::V1, FrameVersion::LATEST)
             .negotiate(&mut tls_stream)
             .map_err(|err| match err {
                 FrameError::UnsupportedVersion => ConnectError::ProtocolError(
@@ -149,6 +184,7 @@ impl Transport for TlsTransport {
         tls_stream.get_ref().set_nonblocking(true)?;
         let connection = TlsConnection {
             frame_version,
+            reader: FrameReader::new(),
             stream: tls_stream,
         };
         Ok(Box::new(connection))
@@ -174,28 +210,29 @@ impl Transport for TlsTransport {
             listener: TcpListener::bind(address).map_err(|err| {
                 ListenError::IoError(format!("Failed to bind to {}", address), err)
             })?,
-            acceptor: self.acceptor.clone(),
+            contexts: self.contexts.clone(),
         }))
     }
 }
 
 pub struct TlsListener {
     listener: TcpListener,
-    acceptor: SslAcceptor,
+    contexts: TlsReloadHandle,
 }
 
 impl Listener for TlsListener {
     fn accept(&mut self) -> Result<Box<dyn Connection>, AcceptError> {
         let (stream, _) = self.listener.accept()?;
-        let mut tls_stream = self.acceptor.accept(stream)?;
+        stream.set_nodelay(true)?;
+        let mut tls_stream = self.contexts.acceptor().accept(stream)?;
 
-        let frame_version = FrameNegotiation::inbound(FrameVersion::V1)
+        let frame_version = FrameNegotiation::inbound(FrameVersion::LATEST)
             .negotiate(&mut tls_stream)
             .map_err(|err| match err {
                 FrameError::UnsupportedVersion => AcceptError::ProtocolError(format!(
                     "Local {} protocol version {} not supported by remote",
                     PROTOCOL_PREFIX,
-                    FrameVersion::V1
+                    FrameVersion::LATEST
                 )),
                 FrameError::IoError(err) => AcceptError::from(err),
                 err => AcceptError::ProtocolError(format!("Unexpected protocol error: {}", err)),
@@ -204,6 +241,7 @@ impl Listener for TlsListener {
         tls_stream.get_ref().set_nonblocking(true)?;
         let connection = TlsConnection {
             frame_version,
+            reader: FrameReader::new(),
             stream: tls_stream,
         };
         Ok(Box::new(connection))
@@ -216,6 +254,7 @@ impl Listener for TlsListener {
 
 pub struct TlsConnection {
     frame_version: FrameVersion,
+    reader: FrameReader,
     stream: SslStream<TcpStream>,
 }
 
@@ -229,7 +268,7 @@ impl Connection for TlsConnection {
     }
 
     fn recv(&mut self) -> Result<Vec<u8>, RecvError> {
-        match Frame::read(&mut self.stream) {
+        match self.reader.read(&mut self.stream) {
             Err(FrameError::IoError(e)) => Err(RecvError::from(e)),
             Err(err) => Err(RecvError::ProtocolError(err.to_string())),
             Ok(frame) => Ok(frame.into_inner()),
@@ -253,6 +292,22 @@ impl Connection for TlsConnection {
     fn evented(&self) -> &dyn Evented {
         self
     }
+
+    fn peer_identity(&self) -> Option<String> {
+        let ssl = self.stream.ssl();
+        // A peer certificate that was not verified against the CA does not establish an identity
+        if ssl.verify_result() != X509VerifyResult::OK {
+            return None;
+        }
+
+        let cert = ssl.peer_certificate()?;
+        let common_name = cert.subject_name().entries_by_nid(Nid::COMMONNAME).next()?;
+        common_name
+            .data()
+            .as_utf8()
+            .ok()
+            .map(|name| name.to_string())
+    }
 }
 
 impl TlsConnection {
@@ -264,6 +319,7 @@ impl TlsConnection {
     pub fn new(stream: SslStream<TcpStream>) -> Self {
         TlsConnection {
             frame_version: FrameVersion::V1,
+            reader: FrameReader::new(),
             stream,
         }
     }
@@ -324,14 +380,23 @@ impl From<ErrorStack> for TlsInitError {
 
 impl From<HandshakeError<TcpStream>> for AcceptError {
     fn from(handshake_error: HandshakeError<TcpStream>) -> Self {
-        AcceptError::ProtocolError(format!("TLS Handshake Err: {}", handshake_error))
+        AcceptError::ProtocolError(handshake_error_message(&handshake_error))
     }
 }
 
 impl From<HandshakeError<TcpStream>> for ConnectError {
     fn from(handshake_error: HandshakeError<TcpStream>) -> Self {
-        ConnectError::ProtocolError(format!("TLS Handshake Err: {}", handshake_error))
+        ConnectError::ProtocolError(handshake_error_message(&handshake_error))
+    }
+}
+
+fn handshake_error_message(handshake_error: &HandshakeError<TcpStream>) -> String {
+    if let HandshakeError::Failure(stream) = handshake_error {
+        if let Some(reason) = unauthorized_peer_reason(stream.ssl()) {
+            return format!("TLS peer is not authorized: {}", reason);
+        }
     }
+    format!("TLS Handshake Err: {}", handshake_error)
 }
 
 impl From<ParseError> for ConnectError {
@@ -350,12 +415,19 @@ impl From<OpensslError> for DisconnectError {
 pub(crate) mod tests {
     use super::*;
 
+    use openssl::asn1::Asn1Time;
+    use openssl::bn::BigNum;
+    use openssl::hash::MessageDigest;
+    use openssl::x509::extension::{AuthorityKeyIdentifier, CrlNumber};
+    use openssl::x509::{X509CrlBuilder, X509RevokedBuilder, X509};
+
     use crate::transport::tests;
     use crate::transport::tls::tests::{make_ca_cert, make_ca_signed_cert};
 
     use std::fs::File;
     use std::io::Write;
     use std::path::PathBuf;
+    use std::time::Duration;
     use tempdir::TempDir;
 
     fn write_file(mut temp_dir: PathBuf, file_name: &str, bytes: &[u8]) -> String {
@@ -457,6 +529,284 @@ pub(crate) mod tests {
         tests::test_transport(transport, "127.0.0.1:0");
     }
 
+    #[test]
+    // Test that the peer identity is the common name of the peer's certificate when the
+    // certificate is verified, and is not provided when the transport does not verify peers.
+    fn test_peer_identity() {
+        let mut transport = create_test_tls_transport(false);
+        let mut listener = transport.listen("tcps://127.0.0.1:0").unwrap();
+        let endpoint = listener.endpoint();
+
+        let handle = std::thread::spawn(move || {
+            let mut client = transport.connect(&endpoint).unwrap();
+            assert_eq!(client.peer_identity(), Some("localhost".to_string()));
+            recv_message(&mut client);
+        });
+
+        let mut server = listener.accept().unwrap();
+        assert_eq!(server.peer_identity(), Some("localhost".to_string()));
+        server.send(b"done").unwrap();
+        handle.join().unwrap();
+
+        let mut transport = create_test_tls_transport(true);
+        let mut listener = transport.listen("tcps://127.0.0.1:0").unwrap();
+        let endpoint = listener.endpoint();
+
+        let handle = std::thread::spawn(move || {
+            let mut client = transport.connect(&endpoint).unwrap();
+            recv_message(&mut client);
+        });
+
+        let mut server = listener.accept().unwrap();
+        assert_eq!(server.peer_identity(), None);
+        server.send(b"done").unwrap();
+        handle.join().unwrap();
+    }
+
+    // Write a new CA and the client and server certificates and keys signed by it to the given
+    // directory, returning the paths to the files in the order expected by `TlsTransport::new`.
+    fn write_test_certs(dir: PathBuf) -> (String, String, String, String, String) {
+        let (ca_key, ca_cert) = make_ca_cert();
+        let (client_key, client_cert) = make_ca_signed_cert(&ca_cert, &ca_key);
+        let (server_key, server_cert) = make_ca_signed_cert(&ca_cert, &ca_key);
+
+        (
+            write_file(dir.clone(), "ca.cert", &ca_cert.to_pem().unwrap()),
+            write_file(
+                dir.clone(),
+                "client.key",
+                &client_key.private_key_to_pem_pkcs8().unwrap(),
+            ),
+            write_file(dir.clone(), "client.cert", &client_cert.to_pem().unwrap()),
+            write_file(
+                dir.clone(),
+                "server.key",
+                &server_key.private_key_to_pem_pkcs8().unwrap(),
+            ),
+            write_file(dir, "server.cert", &server_cert.to_pem().unwrap()),
+        )
+    }
+
+    #[test]
+    // Test that reloading a transport's certificates applies to new handshakes on an existing
+    // listener, that connections made before the reload are unaffected, and that a transport
+    // still using the old certificates can no longer connect.
+    fn test_reload() {
+        let temp_dir = TempDir::new("tls-transport-reload").unwrap();
+        let (ca, client_key, client_cert, server_key, server_cert) =
+            write_test_certs(temp_dir.path().to_path_buf());
+
+        let mut transport = TlsTransport::new(
+            Some(ca.clone()),
+            client_key.clone(),
+            client_cert.clone(),
+            server_key.clone(),
+            server_cert.clone(),
+        )
+        .unwrap();
+        let mut stale_transport =
+            TlsTransport::new(Some(ca), client_key, client_cert, server_key, server_cert).unwrap();
+        let reload_handle = transport.reload_handle();
+
+        let mut listener = transport.listen("tcps://127.0.0.1:0").unwrap();
+        let endpoint = listener.endpoint();
+
+        let connect_endpoint = endpoint.clone();
+        let handle = std::thread::spawn(move || {
+            let client = transport.connect(&connect_endpoint).unwrap();
+            (transport, client)
+        });
+        let mut existing_server = listener.accept().unwrap();
+        let (mut transport, mut existing_client) = handle.join().unwrap();
+
+        write_test_certs(temp_dir.path().to_path_buf());
+        reload_handle.reload().unwrap();
+
+        let connect_endpoint = endpoint.clone();
+        let handle =
+            std::thread::spawn(move || stale_transport.connect(&connect_endpoint).is_err());
+        assert!(listener.accept().is_err());
+        assert!(handle.join().unwrap());
+
+        let handle = std::thread::spawn(move || {
+            let mut client = transport.connect(&endpoint).unwrap();
+            recv_message(&mut client)
+        });
+        let mut server = listener.accept().unwrap();
+        server.send(b"reloaded").unwrap();
+        assert_eq!(handle.join().unwrap(), b"reloaded".to_vec());
+
+        existing_client.send(b"still connected").unwrap();
+        assert_eq!(
+            recv_message(&mut existing_server),
+            b"still connected".to_vec()
+        );
+    }
+
+    // Receive a message from the non-blocking connection, waiting until one has arrived.
+    fn recv_message(connection: &mut Box<dyn Connection>) -> Vec<u8> {
+        loop {
+            match connection.recv() {
+                Err(RecvError::WouldBlock) => std::thread::sleep(Duration::from_millis(10)),
+                result => return result.unwrap(),
+            }
+        }
+    }
+
+    // Accept a single connection attempt from a transport without any revocation settings to a
+    // listener that uses the given configuration, returning the listener's accept result.
+    fn accept_from_unrestricted_peer(
+        server_config: &TlsConfig,
+        client_config: &TlsConfig,
+    ) -> Result<Box<dyn Connection>, AcceptError> {
+        let mut server_transport = TlsTransport::from_config(server_config).unwrap();
+        let mut client_transport = TlsTransport::from_config(client_config).unwrap();
+
+        let mut listener = server_transport.listen("tcps://127.0.0.1:0").unwrap();
+        let endpoint = listener.endpoint();
+        let handle = std::thread::spawn(move || {
+            // The client may or may not see the failure, depending on when the server rejects it
+            let _ = client_transport.connect(&endpoint);
+        });
+
+        let result = listener.accept();
+        handle.join().unwrap();
+        result
+    }
+
+    #[test]
+    // Test that a peer whose certificate fingerprint is denied is rejected with an authorization
+    // error, even though it is signed by the trusted CA, while other peers may still connect.
+    fn test_denied_fingerprint() {
+        let temp_dir = TempDir::new("tls-transport-denied").unwrap();
+        let (ca, client_key, client_cert, server_key, server_cert) =
+            write_test_certs(temp_dir.path().to_path_buf());
+
+        let client_fingerprint = X509::from_pem(&std::fs::read(&client_cert).unwrap())
+            .unwrap()
+            .digest(MessageDigest::sha256())
+            .unwrap()
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect::<Vec<_>>()
+            .join(":");
+
+        let config = TlsConfigBuilder::new()
+            .with_ca_certs_file(ca)
+            .with_client_private_key_file(client_key)
+            .with_client_cert_file(client_cert)
+            .with_server_private_key_file(server_key)
+            .with_server_cert_file(server_cert);
+        let client_config = config.clone().build().unwrap();
+        let denied_config = config
+            .clone()
+            .with_denied_fingerprints(vec![client_fingerprint])
+            .build()
+            .unwrap();
+        let other_denied_config = config
+            .with_denied_fingerprints(vec!["00".repeat(32)])
+            .build()
+            .unwrap();
+
+        match accept_from_unrestricted_peer(&denied_config, &client_config) {
+            Err(AcceptError::ProtocolError(msg)) => assert_eq!(
+                msg,
+                "TLS peer is not authorized: certificate fingerprint has been denied"
+            ),
+            Err(err) => panic!("unexpected error: {}", err),
+            Ok(_) => panic!("connection from a denied peer was accepted"),
+        }
+
+        assert!(accept_from_unrestricted_peer(&other_denied_config, &client_config).is_ok());
+    }
+
+    #[test]
+    // Test that a peer whose certificate has been revoked by the configured CRL is rejected with
+    // an authorization error.
+    fn test_revoked_certificate() {
+        let temp_dir = TempDir::new("tls-transport-revoked").unwrap();
+        let dir = temp_dir.path().to_path_buf();
+
+        let (ca_key, ca_cert) = make_ca_cert();
+        let (client_key, client_cert) = make_ca_signed_cert(&ca_cert, &ca_key);
+        let (server_key, server_cert) = make_ca_signed_cert(&ca_cert, &ca_key);
+
+        let mut revoked = X509RevokedBuilder::new().unwrap();
+        revoked
+            .set_serial_number(client_cert.serial_number())
+            .unwrap();
+        revoked
+            .set_revocation_date(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        let mut crl = X509CrlBuilder::new().unwrap();
+        crl.set_issuer_name(ca_cert.subject_name()).unwrap();
+        crl.set_last_update(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        crl.set_next_update(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        crl.add_revoked(revoked.build()).unwrap();
+        let context_builder = X509::builder().unwrap();
+        crl.append_extension(
+            AuthorityKeyIdentifier::new()
+                .issuer(true)
+                .build(&context_builder.x509v3_context(Some(&ca_cert), None))
+                .unwrap(),
+        )
+        .unwrap();
+        crl.append_extension(
+            CrlNumber::new(BigNum::from_u32(1).unwrap())
+                .unwrap()
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+        crl.sign(&ca_key, MessageDigest::sha256()).unwrap();
+        let crl = crl.build().unwrap();
+
+        let config = TlsConfigBuilder::new()
+            .with_ca_certs_file(write_file(
+                dir.clone(),
+                "ca.cert",
+                &ca_cert.to_pem().unwrap(),
+            ))
+            .with_client_private_key_file(write_file(
+                dir.clone(),
+                "client.key",
+                &client_key.private_key_to_pem_pkcs8().unwrap(),
+            ))
+            .with_client_cert_file(write_file(
+                dir.clone(),
+                "client.cert",
+                &client_cert.to_pem().unwrap(),
+            ))
+            .with_server_private_key_file(write_file(
+                dir.clone(),
+                "server.key",
+                &server_key.private_key_to_pem_pkcs8().unwrap(),
+            ))
+            .with_server_cert_file(write_file(
+                dir.clone(),
+                "server.cert",
+                &server_cert.to_pem().unwrap(),
+            ));
+        let client_config = config.clone().build().unwrap();
+        let crl_config = config
+            .with_crl_file(write_file(dir, "crl.pem", &crl.to_pem().unwrap()))
+            .build()
+            .unwrap();
+
+        match accept_from_unrestricted_peer(&crl_config, &client_config) {
+            Err(AcceptError::ProtocolError(msg)) => {
+                assert_eq!(
+                    msg,
+                    "TLS peer is not authorized: certificate has been revoked"
+                )
+            }
+            Err(err) => panic!("unexpected error: {}", err),
+            Ok(_) => panic!("connection from a revoked peer was accepted"),
+        }
+    }
+
     #[cfg(not(unix))]
     #[test]
     fn test_poll_no_verify() {
@@ -0,0 +1,487 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A transport over Unix domain sockets, for connections between processes on the same host.
+//!
+//! Endpoints take the form `uds://<path>`, where the path is the location of the socket file,
+//! such as `uds:///var/run/splinter/splinterd.sock`. Messages are framed in the same way as for
+//! the TCP and TLS transports.
+//!
+//! Local peers are authenticated in two ways: the socket file is created with restrictive file
+//! permissions, and the listener checks the credentials of each connecting process, as reported
+//! by the operating system, against the users and groups it allows.
+
+use std::fs::{self, Permissions};
+use std::io;
+use std::net::Shutdown;
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::os::unix::net::{UnixListener as StdUnixListener, UnixStream};
+use std::path::PathBuf;
+
+use mio::{unix::EventedFd, Evented, Poll, PollOpt, Ready, Token};
+
+use crate::transport::{
+    AcceptError, ConnectError, Connection, DisconnectError, ListenError, Listener, RecvError,
+    SendError, Transport,
+};
+
+use super::frame::{FrameError, FrameNegotiation, FrameReader, FrameRef, FrameVersion};
+
+const PROTOCOL_PREFIX: &str = "uds://";
+
+/// The default file permissions of a listener's socket file: read and write for the owner and
+/// group only.
+const DEFAULT_SOCKET_MODE: u32 = 0o660;
+
+/// A transport for connections over Unix domain sockets.
+///
+/// By default, the transport's listeners only accept connections from processes running as the
+/// same user as this process. Additional users and groups may be allowed with
+/// `with_allowed_uids` and `with_allowed_gids`.
+pub struct UdsTransport {
+    socket_mode: u32,
+    allowed_uids: Vec<u32>,
+    allowed_gids: Vec<u32>,
+}
+
+impl UdsTransport {
+    /// Construct a new transport that only allows peers running as the current user.
+    pub fn new() -> Self {
+        UdsTransport {
+            socket_mode: DEFAULT_SOCKET_MODE,
+            allowed_uids: vec![current_uid()],
+            allowed_gids: vec![],
+        }
+    }
+
+    /// Set the file permissions of the socket files created by this transport's listeners.
+    ///
+    /// Defaults to `0o660`.
+    pub fn with_socket_mode(mut self, socket_mode: u32) -> Self {
+        self.socket_mode = socket_mode;
+        self
+    }
+
+    /// Allow peers running as any of the given user IDs to connect.
+    pub fn with_allowed_uids(mut self, uids: Vec<u32>) -> Self {
+        self.allowed_uids.extend(uids);
+        self
+    }
+
+    /// Allow peers running with any of the given group IDs to connect.
+    pub fn with_allowed_gids(mut self, gids: Vec<u32>) -> Self {
+        self.allowed_gids.extend(gids);
+        self
+    }
+}
+
+impl Default for UdsTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transport for UdsTransport {
+    fn accepts(&self, address: &str) -> bool {
+        address.starts_with(PROTOCOL_PREFIX)
+    }
+
+    fn connect(&mut self, endpoint: &str) -> Result<Box<dyn Connection>, ConnectError> {
+        if !self.accepts(endpoint) {
+            return Err(ConnectError::ProtocolError(format!(
+                "Invalid protocol \"{}\"",
+                endpoint
+            )));
+        }
+
+        let path = &endpoint[PROTOCOL_PREFIX.len()..];
+        let mut stream = UnixStream::connect(path)?;
+
+        let frame_version = FrameNegotiation::outbound(FrameVersion::V1, FrameVersion::LATEST)
+            .negotiate(&mut stream)
+            .map_err(|err| match err {
+                FrameError::UnsupportedVersion => ConnectError::ProtocolError(
+                    "Unable to connect; remote version is not with in range".into(),
+                ),
+                FrameError::IoError(err) => ConnectError::from(err),
+                e => ConnectError::ProtocolError(format!("Unexpected protocol error: {}", e)),
+            })?;
+
+        stream.set_nonblocking(true)?;
+        Ok(Box::new(UdsConnection {
+            frame_version,
+            reader: FrameReader::new(),
+            stream,
+            local_endpoint: format!("{}{}#{}", PROTOCOL_PREFIX, path, std::process::id()),
+            remote_endpoint: endpoint.to_string(),
+            peer_uid: None,
+        }))
+    }
+
+    fn listen(&mut self, bind: &str) -> Result<Box<dyn Listener>, ListenError> {
+        if !self.accepts(bind) {
+            return Err(ListenError::ProtocolError(format!(
+                "Invalid protocol \"{}\"",
+                bind
+            )));
+        }
+
+        let path = PathBuf::from(&bind[PROTOCOL_PREFIX.len()..]);
+        remove_stale_socket(&path)?;
+
+        let listener = StdUnixListener::bind(&path).map_err(|err| {
+            ListenError::IoError(format!("Failed to bind to {}", path.display()), err)
+        })?;
+        fs::set_permissions(&path, Permissions::from_mode(self.socket_mode)).map_err(|err| {
+            ListenError::IoError(
+                format!("Failed to set permissions of {}", path.display()),
+                err,
+            )
+        })?;
+
+        Ok(Box::new(UdsListener {
+            listener,
+            path,
+            allowed_uids: self.allowed_uids.clone(),
+            allowed_gids: self.allowed_gids.clone(),
+            accepted: 0,
+        }))
+    }
+}
+
+/// Remove a socket file left behind by a listener that was not shut down cleanly.
+///
+/// A socket file that still has a listener, or a file that is not a socket, is left in place.
+fn remove_stale_socket(path: &PathBuf) -> Result<(), ListenError> {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => {
+            return Err(ListenError::IoError(
+                format!("Failed to check {}", path.display()),
+                err,
+            ))
+        }
+    };
+
+    if !metadata.file_type().is_socket() || UnixStream::connect(path).is_ok() {
+        return Err(ListenError::ProtocolError(format!(
+            "{} is already in use",
+            path.display()
+        )));
+    }
+
+    fs::remove_file(path).map_err(|err| {
+        ListenError::IoError(
+            format!("Failed to remove stale socket {}", path.display()),
+            err,
+        )
+    })
+}
+
+struct UdsListener {
+    listener: StdUnixListener,
+    path: PathBuf,
+    allowed_uids: Vec<u32>,
+    allowed_gids: Vec<u32>,
+    accepted: u64,
+}
+
+impl UdsListener {
+    fn is_allowed(&self, credentials: &PeerCredentials) -> bool {
+        self.allowed_uids.contains(&credentials.uid) || self.allowed_gids.contains(&credentials.gid)
+    }
+}
+
+impl Listener for UdsListener {
+    fn accept(&mut self) -> Result<Box<dyn Connection>, AcceptError> {
+        let (mut stream, _) = self.listener.accept()?;
+
+        let credentials = peer_credentials(&stream)?;
+        if !self.is_allowed(&credentials) {
+            return Err(AcceptError::ProtocolError(format!(
+                "Local peer with uid {} and gid {} is not allowed to connect to {}",
+                credentials.uid,
+                credentials.gid,
+                self.endpoint()
+            )));
+        }
+
+        let frame_version = FrameNegotiation::inbound(FrameVersion::LATEST)
+            .negotiate(&mut stream)
+            .map_err(|err| match err {
+                FrameError::UnsupportedVersion => AcceptError::ProtocolError(format!(
+                    "Local {} protocol version {} not supported by remote",
+                    PROTOCOL_PREFIX,
+                    FrameVersion::LATEST
+                )),
+                FrameError::IoError(err) => AcceptError::from(err),
+                err => AcceptError::ProtocolError(format!("Unexpected protocol error: {}", err)),
+            })?;
+
+        stream.set_nonblocking(true)?;
+        // Connecting processes are not bound to a path, so number them to tell them apart
+        self.accepted += 1;
+        Ok(Box::new(UdsConnection {
+            frame_version,
+            reader: FrameReader::new(),
+            stream,
+            local_endpoint: self.endpoint(),
+            remote_endpoint: format!("{}#{}", self.endpoint(), self.accepted),
+            peer_uid: Some(credentials.uid),
+        }))
+    }
+
+    fn endpoint(&self) -> String {
+        format!("{}{}", PROTOCOL_PREFIX, self.path.display())
+    }
+}
+
+impl Drop for UdsListener {
+    fn drop(&mut self) {
+        if let Err(err) = fs::remove_file(&self.path) {
+            warn!("Unable to remove socket {}: {}", self.path.display(), err);
+        }
+    }
+}
+
+struct UdsConnection {
+    frame_version: FrameVersion,
+    reader: FrameReader,
+    stream: UnixStream,
+    local_endpoint: String,
+    remote_endpoint: String,
+    peer_uid: Option<u32>,
+}
+
+impl Connection for UdsConnection {
+    fn send(&mut self, message: &[u8]) -> Result<(), SendError> {
+        match FrameRef::new(self.frame_version, message).write(&mut self.stream) {
+            Err(FrameError::IoError(e)) => Err(SendError::from(e)),
+            Err(err) => Err(SendError::ProtocolError(err.to_string())),
+            Ok(_) => Ok(()),
+        }
+    }
+
+    fn recv(&mut self) -> Result<Vec<u8>, RecvError> {
+        match self.reader.read(&mut self.stream) {
+            Err(FrameError::IoError(e)) => Err(RecvError::from(e)),
+            Err(err) => Err(RecvError::ProtocolError(err.to_string())),
+            Ok(frame) => Ok(frame.into_inner()),
+        }
+    }
+
+    fn remote_endpoint(&self) -> String {
+        self.remote_endpoint.clone()
+    }
+
+    fn local_endpoint(&self) -> String {
+        self.local_endpoint.clone()
+    }
+
+    fn disconnect(&mut self) -> Result<(), DisconnectError> {
+        self.stream
+            .shutdown(Shutdown::Both)
+            .map_err(DisconnectError::from)
+    }
+
+    fn evented(&self) -> &dyn Evented {
+        self
+    }
+
+    /// The identity of a peer accepted by a listener is its user ID, in the form `uid:<uid>`.
+    fn peer_identity(&self) -> Option<String> {
+        self.peer_uid.map(|uid| format!("uid:{}", uid))
+    }
+}
+
+impl AsRawFd for UdsConnection {
+    fn as_raw_fd(&self) -> RawFd {
+        self.stream.as_raw_fd()
+    }
+}
+
+impl Evented for UdsConnection {
+    fn register(
+        &self,
+        poll: &Poll,
+        token: Token,
+        interest: Ready,
+        opts: PollOpt,
+    ) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).register(poll, token, interest, opts)
+    }
+
+    fn reregister(
+        &self,
+        poll: &Poll,
+        token: Token,
+        interest: Ready,
+        opts: PollOpt,
+    ) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).reregister(poll, token, interest, opts)
+    }
+
+    fn deregister(&self, poll: &Poll) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).deregister(poll)
+    }
+}
+
+/// The credentials of the process on the other end of a Unix domain socket.
+struct PeerCredentials {
+    uid: u32,
+    gid: u32,
+}
+
+fn current_uid() -> u32 {
+    // geteuid cannot fail
+    unsafe { libc::geteuid() }
+}
+
+#[cfg(target_os = "linux")]
+fn peer_credentials(stream: &UnixStream) -> io::Result<PeerCredentials> {
+    let mut credentials = libc::ucred {
+        pid: 0,
+        uid: 0,
+        gid: 0,
+    };
+    let mut length = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+
+    // The kernel fills in the credentials of the peer as they were when it connected
+    let result = unsafe {
+        libc::getsockopt(
+            stream.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PEERCRED,
+            &mut credentials as *mut libc::ucred as *mut libc::c_void,
+            &mut length,
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(PeerCredentials {
+        uid: credentials.uid,
+        gid: credentials.gid,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn peer_credentials(stream: &UnixStream) -> io::Result<PeerCredentials> {
+    let mut uid = 0;
+    let mut gid = 0;
+
+    let result = unsafe { libc::getpeereid(stream.as_raw_fd(), &mut uid, &mut gid) };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(PeerCredentials { uid, gid })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::tests;
+
+    use tempdir::TempDir;
+
+    fn socket_endpoint(temp_dir: &TempDir) -> String {
+        format!(
+            "{}{}",
+            PROTOCOL_PREFIX,
+            temp_dir.path().join("test.sock").display()
+        )
+    }
+
+    #[test]
+    fn test_accepts() {
+        let transport = UdsTransport::default();
+        assert!(transport.accepts("uds:///var/run/splinter/splinterd.sock"));
+        assert!(transport.accepts("uds://splinterd.sock"));
+
+        assert!(!transport.accepts("127.0.0.1:0"));
+        assert!(!transport.accepts("tcp://127.0.0.1:0"));
+        assert!(!transport.accepts("tcps://127.0.0.1:0"));
+    }
+
+    #[test]
+    fn test_transport() {
+        let temp_dir = TempDir::new("uds-transport").unwrap();
+        tests::test_transport(UdsTransport::default(), &socket_endpoint(&temp_dir));
+    }
+
+    #[test]
+    fn test_poll() {
+        let temp_dir = TempDir::new("uds-poll").unwrap();
+        tests::test_poll(UdsTransport::default(), &socket_endpoint(&temp_dir));
+    }
+
+    // Test that the socket file is created with the configured permissions, that an accepted
+    // peer is identified by its user ID, and that the socket file is removed with the listener.
+    #[test]
+    fn test_socket_file() {
+        let temp_dir = TempDir::new("uds-socket-file").unwrap();
+        let endpoint = socket_endpoint(&temp_dir);
+        let path = temp_dir.path().join("test.sock");
+
+        let mut transport = UdsTransport::default().with_socket_mode(0o600);
+        let mut listener = transport.listen(&endpoint).unwrap();
+        assert_eq!(
+            fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+            0o600
+        );
+
+        let handle = std::thread::spawn(move || transport.connect(&endpoint).unwrap());
+        let server = listener.accept().unwrap();
+        let client = handle.join().unwrap();
+
+        assert_eq!(
+            server.peer_identity(),
+            Some(format!("uid:{}", current_uid()))
+        );
+        assert_eq!(client.peer_identity(), None);
+
+        drop(listener);
+        assert!(!path.exists());
+    }
+
+    // Test that a listener rejects peers whose credentials are not allowed, and that a socket
+    // file left behind by a previous listener is replaced.
+    #[test]
+    fn test_unauthorized_peer() {
+        let temp_dir = TempDir::new("uds-unauthorized").unwrap();
+        let endpoint = socket_endpoint(&temp_dir);
+
+        // Leave a stale socket file in place of the listener
+        drop(StdUnixListener::bind(temp_dir.path().join("test.sock")).unwrap());
+
+        let mut transport = UdsTransport {
+            socket_mode: DEFAULT_SOCKET_MODE,
+            allowed_uids: vec![],
+            allowed_gids: vec![],
+        };
+        let mut listener = transport.listen(&endpoint).unwrap();
+
+        let handle = std::thread::spawn(move || transport.connect(&endpoint));
+        match listener.accept() {
+            Err(AcceptError::ProtocolError(msg)) => assert!(msg.contains("is not allowed")),
+            Err(err) => panic!("Unexpected error: {}", err),
+            Ok(_) => panic!("Should not have accepted the connection"),
+        }
+        assert!(handle.join().unwrap().is_err());
+    }
+}
@@ -12,20 +12,51 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+//! Configuration and certificate management shared by the TLS-enabled transports.
+//!
+//! Transports that use TLS keep their certificates and keys behind a [`TlsReloadHandle`], which
+//! can be used to load new material without recreating the transport. A [`TlsFileWatcher`] can be
+//! used to reload automatically whenever the certificate or key files change on disk.
+//!
+//! [`TlsReloadHandle`]: struct.TlsReloadHandle.html
+//! [`TlsFileWatcher`]: struct.TlsFileWatcher.html
+
+use std::collections::HashSet;
+use std::fs;
 #[cfg(feature = "ws-transport")]
 use std::path::Path;
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::{Arc, PoisonError, RwLock};
+use std::thread;
+use std::time::{Duration, SystemTime};
 
-#[cfg(feature = "ws-transport")]
 use openssl::error::ErrorStack;
+use openssl::hash::MessageDigest;
+use openssl::nid::Nid;
 #[cfg(feature = "ws-transport")]
-use openssl::ssl::{SslAcceptor, SslConnector, SslFiletype, SslMethod, SslVerifyMode};
+use openssl::ssl::SslMethod;
+use openssl::ssl::{
+    SslAcceptor, SslConnector, SslContextBuilder, SslFiletype, SslRef, SslVerifyMode,
+};
+use openssl::x509::store::X509Lookup;
+use openssl::x509::verify::X509VerifyFlags;
+use openssl::x509::{X509Ref, X509VerifyResult};
+
+use crate::hex::{parse_hex, to_hex};
+
+/// The verification error OpenSSL reports for a certificate revoked by a CRL
+/// (`X509_V_ERR_CERT_REVOKED`).
+const X509_V_ERR_CERT_REVOKED: i32 = 23;
 
+#[derive(Clone)]
 pub struct TlsConfig {
     ca_certs_file: Option<String>,
     server_cert_file: String,
     server_private_key_file: String,
     client_cert_file: String,
     client_private_key_file: String,
+    crl_file: Option<String>,
+    denied_fingerprints: Vec<String>,
 }
 
 impl TlsConfig {
@@ -48,15 +79,41 @@ impl TlsConfig {
     pub fn client_private_key_file(&self) -> &str {
         &self.client_private_key_file
     }
+
+    /// Returns the certificate revocation list that peer certificates are checked against.
+    pub fn crl_file(&self) -> Option<&str> {
+        self.crl_file.as_deref()
+    }
+
+    /// Returns the SHA-256 fingerprints, as lowercase hex, of the peer certificates that are
+    /// always rejected.
+    pub fn denied_fingerprints(&self) -> &[String] {
+        &self.denied_fingerprints
+    }
+
+    /// Returns the files read when building the TLS contexts from this configuration.
+    pub(crate) fn files(&self) -> Vec<String> {
+        let mut files = vec![
+            self.server_cert_file.clone(),
+            self.server_private_key_file.clone(),
+            self.client_cert_file.clone(),
+            self.client_private_key_file.clone(),
+        ];
+        files.extend(self.ca_certs_file.clone());
+        files.extend(self.crl_file.clone());
+        files
+    }
 }
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct TlsConfigBuilder {
     ca_certs_file: Option<String>,
     server_cert_file: Option<String>,
     server_private_key_file: Option<String>,
     client_cert_file: Option<String>,
     client_private_key_file: Option<String>,
+    crl_file: Option<String>,
+    denied_fingerprints: Vec<String>,
 }
 
 impl TlsConfigBuilder {
@@ -67,6 +124,8 @@ impl TlsConfigBuilder {
             server_private_key_file: None,
             client_cert_file: None,
             client_private_key_file: None,
+            crl_file: None,
+            denied_fingerprints: vec![],
         }
     }
 
@@ -95,6 +154,26 @@ impl TlsConfigBuilder {
         self
     }
 
+    /// Sets a PEM-encoded certificate revocation list; peer certificates that it revokes are
+    /// rejected.
+    ///
+    /// The list is read again whenever the transport's certificates are reloaded, so it can be
+    /// refreshed on disk while the transport is running. Requires a CA certificates file.
+    pub fn with_crl_file(mut self, crl_file: String) -> Self {
+        self.crl_file = Some(crl_file);
+        self
+    }
+
+    /// Sets the SHA-256 fingerprints of peer certificates that are always rejected, even if they
+    /// are signed by a trusted CA.
+    ///
+    /// Fingerprints are hex-encoded and may separate bytes with colons, as printed by
+    /// `openssl x509 -fingerprint -sha256`. Requires a CA certificates file.
+    pub fn with_denied_fingerprints(mut self, denied_fingerprints: Vec<String>) -> Self {
+        self.denied_fingerprints = denied_fingerprints;
+        self
+    }
+
     pub fn build(self) -> Result<TlsConfig, TlsConfigBuilderError> {
         let ca_certs_file = self.ca_certs_file;
         let server_cert_file = self
@@ -110,12 +189,36 @@ impl TlsConfigBuilder {
             TlsConfigBuilderError::MissingField("client_private_key_file".to_string())
         })?;
 
+        // Revocation is only checked for peers that are verified against a CA
+        if ca_certs_file.is_none()
+            && (self.crl_file.is_some() || !self.denied_fingerprints.is_empty())
+        {
+            return Err(TlsConfigBuilderError::InvalidField(
+                "crl_file and denied_fingerprints require ca_certs_file".to_string(),
+            ));
+        }
+
+        let denied_fingerprints = self
+            .denied_fingerprints
+            .iter()
+            .map(|fingerprint| {
+                normalize_fingerprint(fingerprint).ok_or_else(|| {
+                    TlsConfigBuilderError::InvalidField(format!(
+                        "'{}' is not a SHA-256 certificate fingerprint",
+                        fingerprint
+                    ))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
         Ok(TlsConfig {
             ca_certs_file,
             server_cert_file,
             server_private_key_file,
             client_cert_file,
             client_private_key_file,
+            crl_file: self.crl_file,
+            denied_fingerprints,
         })
     }
 }
@@ -123,6 +226,7 @@ impl TlsConfigBuilder {
 #[derive(Debug)]
 pub enum TlsConfigBuilderError {
     MissingField(String),
+    InvalidField(String),
 }
 
 impl std::error::Error for TlsConfigBuilderError {}
@@ -133,10 +237,107 @@ impl std::fmt::Display for TlsConfigBuilderError {
             TlsConfigBuilderError::MissingField(ref s) => {
                 write!(f, "Missing required field '{}' in TLS configuration", s)
             }
+            TlsConfigBuilderError::InvalidField(ref s) => {
+                write!(f, "Invalid TLS configuration: {}", s)
+            }
         }
     }
 }
 
+// Returns the fingerprint as lowercase hex without separators, if it is a valid SHA-256
+// fingerprint
+fn normalize_fingerprint(fingerprint: &str) -> Option<String> {
+    let hex = fingerprint.replace(':', "").to_lowercase();
+    match parse_hex(&hex) {
+        Ok(bytes) if bytes.len() == 32 => Some(hex),
+        _ => None,
+    }
+}
+
+/// Sets how the context verifies peer certificates.
+///
+/// In addition to the given verify mode, peers are checked against the configuration's
+/// certificate revocation list and denied fingerprints, if any.
+pub(crate) fn set_peer_verification(
+    builder: &mut SslContextBuilder,
+    mode: SslVerifyMode,
+    config: &TlsConfig,
+) -> Result<(), ErrorStack> {
+    if let Some(crl_file) = config.crl_file() {
+        builder
+            .cert_store_mut()
+            .add_lookup(X509Lookup::file())?
+            .load_crl_file(crl_file, SslFiletype::PEM)?;
+        builder
+            .cert_store_mut()
+            .set_flags(X509VerifyFlags::CRL_CHECK)?;
+    }
+
+    if config.denied_fingerprints().is_empty() {
+        builder.set_verify(mode);
+        return Ok(());
+    }
+
+    let denied_fingerprints: HashSet<String> =
+        config.denied_fingerprints().iter().cloned().collect();
+    builder.set_verify_callback(mode, move |preverified, context| {
+        if !preverified {
+            return false;
+        }
+
+        // Every certificate in the peer's chain is checked, so denying an intermediate CA rejects
+        // all of the certificates it has issued
+        let cert = match context.current_cert() {
+            Some(cert) => cert,
+            None => return true,
+        };
+        match certificate_fingerprint(cert) {
+            Ok(fingerprint) if denied_fingerprints.contains(&fingerprint) => {
+                warn!(
+                    "Rejecting peer certificate {} ({}): fingerprint is denied",
+                    common_name(cert).unwrap_or_else(|| "unknown".into()),
+                    fingerprint
+                );
+                context.set_error(X509VerifyResult::APPLICATION_VERIFICATION);
+                false
+            }
+            Ok(_) => true,
+            Err(err) => {
+                error!("Unable to compute peer certificate fingerprint: {}", err);
+                context.set_error(X509VerifyResult::APPLICATION_VERIFICATION);
+                false
+            }
+        }
+    });
+
+    Ok(())
+}
+
+fn certificate_fingerprint(cert: &X509Ref) -> Result<String, ErrorStack> {
+    Ok(to_hex(&cert.digest(MessageDigest::sha256())?))
+}
+
+fn common_name(cert: &X509Ref) -> Option<String> {
+    cert.subject_name()
+        .entries_by_nid(Nid::COMMONNAME)
+        .next()
+        .and_then(|entry| entry.data().as_utf8().ok())
+        .map(|name| name.to_string())
+}
+
+/// Returns a description of why the peer was not authorized, if a handshake failed because the
+/// peer's certificate has been revoked or denied.
+pub(crate) fn unauthorized_peer_reason(ssl: &SslRef) -> Option<&'static str> {
+    let result = ssl.verify_result();
+    if result.as_raw() == X509_V_ERR_CERT_REVOKED {
+        Some("certificate has been revoked")
+    } else if result == X509VerifyResult::APPLICATION_VERIFICATION {
+        Some("certificate fingerprint has been denied")
+    } else {
+        None
+    }
+}
+
 #[cfg(feature = "ws-transport")]
 pub(super) fn build_connector(config: &TlsConfig) -> Result<SslConnector, ErrorStack> {
     let mut builder = SslConnector::builder(SslMethod::tls())?;
@@ -150,6 +351,7 @@ pub(super) fn build_connector(config: &TlsConfig) -> Result<SslConnector, ErrorS
 
     if let Some(ca_certs_file) = config.ca_certs_file() {
         builder.set_ca_file(Path::new(ca_certs_file))?;
+        set_peer_verification(&mut builder, SslVerifyMode::PEER, config)?;
     } else {
         builder.set_verify(SslVerifyMode::NONE);
     }
@@ -170,6 +372,14 @@ pub(super) fn build_acceptor(config: &TlsConfig) -> Result<SslAcceptor, ErrorSta
 
     if let Some(ca_certs_file) = config.ca_certs_file() {
         builder.set_ca_file(Path::new(ca_certs_file))?;
+        // Client certificates are only requested when they need to be checked for revocation
+        if config.crl_file().is_some() || !config.denied_fingerprints().is_empty() {
+            set_peer_verification(
+                &mut builder,
+                SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT,
+                config,
+            )?;
+        }
     } else {
         builder.set_verify(SslVerifyMode::NONE);
     }
@@ -177,6 +387,203 @@ pub(super) fn build_acceptor(config: &TlsConfig) -> Result<SslAcceptor, ErrorSta
     Ok(builder.build())
 }
 
+/// The acceptor and connector used for new TLS handshakes.
+pub(crate) struct TlsContexts {
+    pub acceptor: SslAcceptor,
+    pub connector: SslConnector,
+}
+
+type TlsContextsLoader = dyn Fn() -> Result<TlsContexts, ErrorStack> + Send + Sync;
+
+/// A handle for reloading the certificates and keys used by a TLS-enabled transport.
+///
+/// A reload only affects handshakes that start after it completes; connections that are already
+/// established keep the material they were created with. If the new material cannot be loaded,
+/// the transport continues to use the previous material.
+#[derive(Clone)]
+pub struct TlsReloadHandle {
+    files: Arc<Vec<String>>,
+    loader: Arc<TlsContextsLoader>,
+    contexts: Arc<RwLock<TlsContexts>>,
+}
+
+impl TlsReloadHandle {
+    /// Creates a new handle, loading the initial contexts with the given loader.
+    ///
+    /// # Arguments
+    ///
+    /// * `files` - The certificate and key files read by the loader
+    /// * `loader` - Builds the acceptor and connector from the files
+    pub(crate) fn new<F>(files: Vec<String>, loader: F) -> Result<Self, ErrorStack>
+    where
+        F: Fn() -> Result<TlsContexts, ErrorStack> + Send + Sync + 'static,
+    {
+        let contexts = loader()?;
+
+        Ok(TlsReloadHandle {
+            files: Arc::new(files),
+            loader: Arc::new(loader),
+            contexts: Arc::new(RwLock::new(contexts)),
+        })
+    }
+
+    /// Returns the certificate and key files that are read when reloading.
+    pub fn files(&self) -> &[String] {
+        &self.files
+    }
+
+    /// Reloads the certificates and keys from their files.
+    ///
+    /// Returns an error, and leaves the current material in place, if any of the files cannot be
+    /// read or do not form a valid configuration (for example, a certificate that does not match
+    /// its private key).
+    pub fn reload(&self) -> Result<(), TlsReloadError> {
+        let contexts = (self.loader)().map_err(|err| TlsReloadError::LoadError(err.to_string()))?;
+
+        // The contexts are only ever replaced as a whole, so a poisoned lock still holds a valid
+        // value.
+        *self
+            .contexts
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = contexts;
+
+        Ok(())
+    }
+
+    pub(crate) fn acceptor(&self) -> SslAcceptor {
+        self.contexts
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .acceptor
+            .clone()
+    }
+
+    pub(crate) fn connector(&self) -> SslConnector {
+        self.contexts
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .connector
+            .clone()
+    }
+}
+
+/// Polls the files of a set of `TlsReloadHandle`s and reloads a handle when any of its files
+/// change.
+///
+/// Files are compared by their modification time. Certificates that are replaced one file at a
+/// time may briefly fail to load (for example, a new certificate with the old key); such a reload
+/// is logged and the next change to the remaining files will trigger another attempt.
+pub struct TlsFileWatcher {
+    join_handle: thread::JoinHandle<()>,
+    shutdown_signaler: TlsFileWatcherShutdownSignaler,
+}
+
+impl TlsFileWatcher {
+    /// Starts watching the files of the given handles, checking for changes every
+    /// `poll_interval`.
+    pub fn start(
+        handles: Vec<TlsReloadHandle>,
+        poll_interval: Duration,
+    ) -> Result<Self, TlsReloadError> {
+        let (sender, receiver) = channel();
+
+        let mut watched: Vec<(TlsReloadHandle, Vec<Option<SystemTime>>)> = handles
+            .into_iter()
+            .map(|handle| {
+                let modified = modified_times(handle.files());
+                (handle, modified)
+            })
+            .collect();
+
+        let join_handle = thread::Builder::new()
+            .name("TlsFileWatcher".into())
+            .spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) = receiver.recv_timeout(poll_interval) {
+                    for (handle, last_modified) in watched.iter_mut() {
+                        let modified = modified_times(handle.files());
+                        if &modified == last_modified {
+                            continue;
+                        }
+                        *last_modified = modified;
+
+                        match handle.reload() {
+                            Ok(()) => info!("Reloaded TLS files {}", handle.files().join(", ")),
+                            Err(err) => error!("Unable to reload changed TLS files: {}", err),
+                        }
+                    }
+                }
+            })
+            .map_err(|err| {
+                TlsReloadError::WatcherError(format!("Unable to start watcher thread: {}", err))
+            })?;
+
+        Ok(TlsFileWatcher {
+            join_handle,
+            shutdown_signaler: TlsFileWatcherShutdownSignaler { sender },
+        })
+    }
+
+    /// Returns a `TlsFileWatcherShutdownSignaler` for this watcher.
+    pub fn shutdown_signaler(&self) -> TlsFileWatcherShutdownSignaler {
+        self.shutdown_signaler.clone()
+    }
+
+    /// Waits for the watcher thread to shutdown.
+    pub fn await_shutdown(self) {
+        debug!("Shutting down TLS file watcher...");
+        if let Err(err) = self.join_handle.join() {
+            error!(
+                "TLS file watcher thread did not shutdown correctly: {:?}",
+                err
+            );
+        }
+        debug!("Shutting down TLS file watcher (complete)");
+    }
+}
+
+/// Signals a `TlsFileWatcher` to stop watching.
+#[derive(Clone)]
+pub struct TlsFileWatcherShutdownSignaler {
+    sender: Sender<()>,
+}
+
+impl TlsFileWatcherShutdownSignaler {
+    pub fn shutdown(&self) {
+        // The watcher also stops if it has already exited and the receiver is gone
+        let _ = self.sender.send(());
+    }
+}
+
+fn modified_times(files: &[String]) -> Vec<Option<SystemTime>> {
+    files
+        .iter()
+        .map(|file| {
+            fs::metadata(file)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+pub enum TlsReloadError {
+    LoadError(String),
+    WatcherError(String),
+}
+
+impl std::error::Error for TlsReloadError {}
+
+impl std::fmt::Display for TlsReloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TlsReloadError::LoadError(msg) => {
+                write!(f, "Unable to load TLS certificates and keys: {}", msg)
+            }
+            TlsReloadError::WatcherError(msg) => write!(f, "TLS file watcher error: {}", msg),
+        }
+    }
+}
+
 #[cfg(test)]
 pub(super) mod tests {
     use openssl::asn1::Asn1Time;
@@ -211,7 +618,7 @@ pub(super) mod tests {
             .append_extension(BasicConstraints::new().critical().ca().build().unwrap())
             .unwrap();
         cert_builder
-            .append_extension(KeyUsage::new().key_cert_sign().build().unwrap())
+            .append_extension(KeyUsage::new().key_cert_sign().crl_sign().build().unwrap())
             .unwrap();
 
         cert_builder
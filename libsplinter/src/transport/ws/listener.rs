@@ -16,9 +16,9 @@ use std::net::TcpListener;
 use std::thread;
 use std::time::Duration;
 
-use openssl::ssl::SslAcceptor;
 use tungstenite::{accept, handshake::HandshakeError};
 
+use crate::transport::tls::TlsReloadHandle;
 use crate::transport::{AcceptError, Connection, Listener};
 
 use super::connection::WsConnection;
@@ -28,19 +28,19 @@ use super::transport::WS_PROTOCOL_PREFIX;
 pub(super) struct WsListener {
     listener: TcpListener,
     local_endpoint: String,
-    acceptor: Option<SslAcceptor>,
+    tls: Option<TlsReloadHandle>,
 }
 
 impl WsListener {
     pub fn new(
         listener: TcpListener,
         local_endpoint: String,
-        acceptor: Option<SslAcceptor>,
+        tls: Option<TlsReloadHandle>,
     ) -> Self {
         WsListener {
             listener,
             local_endpoint,
-            acceptor,
+            tls,
         }
     }
 }
@@ -49,10 +49,10 @@ impl Listener for WsListener {
     fn accept(&mut self) -> Result<Box<dyn Connection>, AcceptError> {
         let (stream, _) = self.listener.accept()?;
 
-        if let Some(acceptor) = &self.acceptor {
+        if let Some(tls) = &self.tls {
             let remote_endpoint = format!("{}{}", WSS_PROTOCOL_PREFIX, stream.peer_addr()?);
 
-            let websocket = accept(acceptor.accept(stream)?).map_or_else(
+            let websocket = accept(tls.acceptor().accept(stream)?).map_or_else(
                 {
                     |mut handshake_err| loop {
                         match handshake_err {
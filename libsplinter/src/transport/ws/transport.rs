@@ -17,11 +17,12 @@ use std::thread;
 use std::time::Duration;
 
 use openssl::error::ErrorStack;
-use openssl::ssl::{SslAcceptor, SslConnector};
 use tungstenite::{client, handshake::HandshakeError};
 use url::{ParseError, Url};
 
-use crate::transport::tls::{build_acceptor, build_connector, TlsConfig};
+use crate::transport::tls::{
+    build_acceptor, build_connector, TlsConfig, TlsContexts, TlsReloadHandle,
+};
 use crate::transport::{ConnectError, Connection, ListenError, Listener, Transport};
 
 use super::connection::WsConnection;
@@ -30,11 +31,6 @@ use super::listener::WsListener;
 pub(super) const WS_PROTOCOL_PREFIX: &str = "ws://";
 pub(super) const WSS_PROTOCOL_PREFIX: &str = "wss://";
 
-struct TlsInner {
-    acceptor: SslAcceptor,
-    connector: SslConnector,
-}
-
 /// A WebSocket-based `Transport`.
 ///
 /// Supports endpoints of the format `ws://ip_or_host:port`.
@@ -95,22 +91,33 @@ struct TlsInner {
 /// ```
 #[derive(Default)]
 pub struct WsTransport {
-    tls_inner: Option<TlsInner>,
+    tls_inner: Option<TlsReloadHandle>,
 }
 
 impl WsTransport {
     pub fn new(config: Option<&TlsConfig>) -> Result<Self, WsInitError> {
         if let Some(conf) = config {
-            Ok(WsTransport {
-                tls_inner: Some(TlsInner {
+            let conf = conf.clone();
+            let tls_inner = TlsReloadHandle::new(conf.files(), move || {
+                Ok(TlsContexts {
                     acceptor: build_acceptor(&conf)?,
                     connector: build_connector(&conf)?,
-                }),
+                })
+            })?;
+
+            Ok(WsTransport {
+                tls_inner: Some(tls_inner),
             })
         } else {
             Ok(WsTransport { tls_inner: None })
         }
     }
+
+    /// Returns a handle that can be used to reload this transport's certificates and keys, if
+    /// the transport was configured with TLS.
+    pub fn reload_handle(&self) -> Option<TlsReloadHandle> {
+        self.tls_inner.clone()
+    }
 }
 
 fn endpoint_to_dns_name(endpoint: &str) -> Result<String, ParseError> {
@@ -181,7 +188,7 @@ impl Transport for WsTransport {
                         WSS_PROTOCOL_PREFIX
                     ))
                 })?
-                .connector
+                .connector()
                 .connect(&dns_name, stream)?;
 
             let (websocket, _) = client(endpoint, tls_stream).map_or_else(
@@ -256,7 +263,7 @@ impl Transport for WsTransport {
             Ok(Box::new(WsListener::new(
                 tcp_listener,
                 local_endpoint,
-                Some(inner.acceptor.clone()),
+                Some(inner.clone()),
             )))
         } else {
             Err(ListenError::ProtocolError(format!(
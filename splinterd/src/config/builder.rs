@@ -209,8 +209,7 @@ impl ConfigBuilder {
                 .find_map(|p| match p.database() {
                     Some(v) => Some((v, p.source())),
                     None => None,
-                })
-                .ok_or_else(|| ConfigError::MissingValue("database".to_string()))?,
+                }),
             registries: self
                 .partial_configs
                 .iter()
@@ -291,6 +290,15 @@ impl ConfigBuilder {
                     None => None,
                 })
                 .ok_or_else(|| ConfigError::MissingValue("enable_biome".to_string()))?,
+            #[cfg(feature = "biome-api-keys")]
+            biome_api_key_guards: self
+                .partial_configs
+                .iter()
+                .find_map(|p| match p.biome_api_key_guards() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                })
+                .ok_or_else(|| ConfigError::MissingValue("biome_api_key_guards".to_string()))?,
             #[cfg(feature = "rest-api-cors")]
             whitelist: self
                 .partial_configs
@@ -307,6 +315,99 @@ impl ConfigBuilder {
                     None => None,
                 })
                 .ok_or_else(|| ConfigError::MissingValue("strict_ref_counts".to_string()))?,
+            #[cfg(feature = "service-auth")]
+            service_auth_keys: self.partial_configs.iter().find_map(|p| {
+                match p.service_auth_keys() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }
+            }),
+            #[cfg(feature = "service-auth")]
+            service_auth_identities: self.partial_configs.iter().find_map(|p| {
+                match p.service_auth_identities() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }
+            }),
+            #[cfg(feature = "service-supervisor")]
+            supervise_services: self
+                .partial_configs
+                .iter()
+                .find_map(|p| match p.supervise_services() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                })
+                .ok_or_else(|| ConfigError::MissingValue("supervise_services".to_string()))?,
+            #[cfg(feature = "routing-table-database")]
+            routing_table: self
+                .partial_configs
+                .iter()
+                .find_map(|p| match p.routing_table() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                })
+                .ok_or_else(|| ConfigError::MissingValue("routing_table".to_string()))?,
+            #[cfg(feature = "tls-reload")]
+            tls_reload_interval: self
+                .partial_configs
+                .iter()
+                .find_map(|p| match p.tls_reload_interval() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                })
+                .ok_or_else(|| ConfigError::MissingValue("tls_reload_interval".to_string()))?,
+            #[cfg(feature = "config-reload")]
+            log_level: self
+                .partial_configs
+                .iter()
+                .find_map(|p| match p.log_level() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                })
+                .ok_or_else(|| ConfigError::MissingValue("log_level".to_string()))?,
+            #[cfg(feature = "tls-revocation")]
+            tls_crl_file: self
+                .partial_configs
+                .iter()
+                .find_map(|p| match p.tls_crl_file() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }),
+            #[cfg(feature = "tls-revocation")]
+            tls_denied_fingerprints: self.partial_configs.iter().find_map(|p| {
+                match p.tls_denied_fingerprints() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }
+            }),
+            #[cfg(feature = "biome-oidc")]
+            biome_oidc_issuer_url: self.partial_configs.iter().find_map(|p| {
+                match p.biome_oidc_issuer_url() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }
+            }),
+            #[cfg(feature = "biome-oidc")]
+            biome_oidc_client_id: self.partial_configs.iter().find_map(|p| {
+                match p.biome_oidc_client_id() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }
+            }),
+            #[cfg(feature = "biome-oidc")]
+            biome_oidc_client_secret: self.partial_configs.iter().find_map(|p| {
+                match p.biome_oidc_client_secret() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }
+            }),
+            #[cfg(feature = "biome-oidc")]
+            biome_oidc_redirect_url: self.partial_configs.iter().find_map(|p| {
+                match p.biome_oidc_redirect_url() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }
+            }),
         })
     }
 }
@@ -108,12 +108,105 @@ impl<'a> PartialConfigBuilder for ClapPartialConfigBuilder<'_> {
                 });
         }
 
+        #[cfg(feature = "biome-api-keys")]
+        {
+            partial_config = partial_config.with_biome_api_key_guards(
+                if self.matches.is_present("biome_api_key_guards") {
+                    Some(true)
+                } else {
+                    None
+                },
+            );
+        }
+
+        #[cfg(feature = "service-supervisor")]
+        {
+            partial_config = partial_config.with_supervise_services(
+                if self.matches.is_present("supervise_services") {
+                    Some(true)
+                } else {
+                    None
+                },
+            );
+        }
+
         #[cfg(feature = "database")]
         {
             partial_config =
                 partial_config.with_database(self.matches.value_of("database").map(String::from))
         }
 
+        #[cfg(feature = "service-auth")]
+        {
+            partial_config = partial_config
+                .with_service_auth_keys(
+                    self.matches
+                        .values_of("service_auth_keys")
+                        .map(|values| values.map(String::from).collect::<Vec<String>>()),
+                )
+                .with_service_auth_identities(
+                    self.matches
+                        .values_of("service_auth_identities")
+                        .map(|values| values.map(String::from).collect::<Vec<String>>()),
+                );
+        }
+
+        // The OpenID Connect client secret may only be set in the config file, so that it is not
+        // exposed in the process list
+        #[cfg(feature = "biome-oidc")]
+        {
+            partial_config = partial_config
+                .with_biome_oidc_issuer_url(
+                    self.matches
+                        .value_of("biome_oidc_issuer_url")
+                        .map(String::from),
+                )
+                .with_biome_oidc_client_id(
+                    self.matches
+                        .value_of("biome_oidc_client_id")
+                        .map(String::from),
+                )
+                .with_biome_oidc_redirect_url(
+                    self.matches
+                        .value_of("biome_oidc_redirect_url")
+                        .map(String::from),
+                );
+        }
+
+        #[cfg(feature = "routing-table-database")]
+        {
+            partial_config = partial_config
+                .with_routing_table(self.matches.value_of("routing_table").map(String::from))
+        }
+
+        #[cfg(feature = "tls-reload")]
+        {
+            partial_config = partial_config
+                .with_tls_reload_interval(parse_value(&self.matches, "tls_reload_interval")?)
+        }
+
+        #[cfg(feature = "config-reload")]
+        {
+            partial_config =
+                partial_config.with_log_level(match self.matches.occurrences_of("verbose") {
+                    0 => None,
+                    1 => Some("info".to_string()),
+                    2 => Some("debug".to_string()),
+                    _ => Some("trace".to_string()),
+                });
+        }
+
+        #[cfg(feature = "tls-revocation")]
+        {
+            partial_config = partial_config
+                .with_tls_crl_file(self.matches.value_of("tls_crl_file").map(String::from))
+                .with_tls_denied_fingerprints(
+                    self.matches
+                        .values_of("tls_denied_fingerprints")
+                        .map(|values| values.map(String::from).collect::<Vec<String>>()),
+                );
+        }
+
         #[cfg(feature = "rest-api-cors")]
         {
             partial_config = partial_config.with_whitelist(
@@ -32,8 +32,12 @@ const REST_API_ENDPOINT: &str = "127.0.0.1:8080";
 #[cfg(feature = "service-endpoint")]
 const SERVICE_ENDPOINT: &str = "tcp://127.0.0.1:8043";
 const NETWORK_ENDPOINT: &str = "tcps://127.0.0.1:8044";
-#[cfg(feature = "database")]
-const DATABASE: &str = "127.0.0.1:5432";
+#[cfg(feature = "routing-table-database")]
+const ROUTING_TABLE: &str = "memory";
+#[cfg(feature = "tls-reload")]
+const TLS_RELOAD_INTERVAL: u64 = 60; // 60 seconds
+#[cfg(feature = "config-reload")]
+const LOG_LEVEL: &str = "warn";
 
 const REGISTRY_AUTO_REFRESH: u64 = 600; // 600 seconds = 10 minutes
 const REGISTRY_FORCED_REFRESH: u64 = 10; // 10 seconds
@@ -84,10 +88,29 @@ impl PartialConfigBuilder for DefaultPartialConfigBuilder {
         {
             partial_config = partial_config.with_enable_biome(Some(false));
         }
+        #[cfg(feature = "biome-api-keys")]
+        {
+            partial_config = partial_config.with_biome_api_key_guards(Some(false));
+        }
 
-        #[cfg(feature = "database")]
+        #[cfg(feature = "service-supervisor")]
+        {
+            partial_config = partial_config.with_supervise_services(Some(false));
+        }
+
+        #[cfg(feature = "routing-table-database")]
+        {
+            partial_config = partial_config.with_routing_table(Some(String::from(ROUTING_TABLE)));
+        }
+
+        #[cfg(feature = "tls-reload")]
+        {
+            partial_config = partial_config.with_tls_reload_interval(Some(TLS_RELOAD_INTERVAL));
+        }
+
+        #[cfg(feature = "config-reload")]
         {
-            partial_config = partial_config.with_database(Some(String::from(DATABASE)));
+            partial_config = partial_config.with_log_level(Some(String::from(LOG_LEVEL)));
         }
 
         Ok(partial_config)
@@ -132,7 +155,11 @@ mod tests {
             Some(String::from(REST_API_ENDPOINT))
         );
         #[cfg(feature = "database")]
-        assert_eq!(config.database(), Some(String::from(DATABASE)));
+        assert_eq!(config.database(), None);
+        #[cfg(feature = "routing-table-database")]
+        assert_eq!(config.routing_table(), Some(String::from(ROUTING_TABLE)));
+        #[cfg(feature = "tls-reload")]
+        assert_eq!(config.tls_reload_interval(), Some(TLS_RELOAD_INTERVAL));
         assert_eq!(config.registries(), Some(vec![]));
         assert_eq!(config.registry_auto_refresh(), Some(REGISTRY_AUTO_REFRESH));
         assert_eq!(
@@ -149,6 +176,8 @@ mod tests {
         assert_eq!(config.no_tls(), Some(false));
         #[cfg(feature = "biome")]
         assert_eq!(config.enable_biome(), Some(false));
+        #[cfg(feature = "biome-api-keys")]
+        assert_eq!(config.biome_api_key_guards(), Some(false));
         // Assert the source is correctly identified for this `PartialConfig` object.
         assert_eq!(config.source(), ConfigSource::Default);
     }
@@ -23,6 +23,8 @@ mod clap;
 mod default;
 mod env;
 mod error;
+#[cfg(feature = "config-command")]
+mod inspect;
 mod partial;
 mod toml;
 
@@ -34,11 +36,13 @@ pub use crate::config::env::EnvPartialConfigBuilder;
 pub use crate::config::toml::TomlPartialConfigBuilder;
 pub use builder::{ConfigBuilder, PartialConfigBuilder};
 pub use error::ConfigError;
+#[cfg(feature = "config-command")]
+pub use inspect::{check_config, ShownConfig};
 pub use partial::{ConfigSource, PartialConfig};
 
 /// `Config` is the final representation of configuration values. This final config object assembles
 /// values from `PartialConfig` builder objects generated from various sources.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Config {
     config_dir: (String, ConfigSource),
     storage: (String, ConfigSource),
@@ -57,7 +61,7 @@ pub struct Config {
     display_name: Option<(String, ConfigSource)>,
     rest_api_endpoint: (String, ConfigSource),
     #[cfg(feature = "database")]
-    database: (String, ConfigSource),
+    database: Option<(String, ConfigSource)>,
     registries: (Vec<String>, ConfigSource),
     registry_auto_refresh: (u64, ConfigSource),
     registry_forced_refresh: (u64, ConfigSource),
@@ -68,9 +72,35 @@ pub struct Config {
     no_tls: (bool, ConfigSource),
     #[cfg(feature = "biome")]
     enable_biome: (bool, ConfigSource),
+    #[cfg(feature = "biome-api-keys")]
+    biome_api_key_guards: (bool, ConfigSource),
     #[cfg(feature = "rest-api-cors")]
     whitelist: Option<(Vec<String>, ConfigSource)>,
     strict_ref_counts: (bool, ConfigSource),
+    #[cfg(feature = "service-supervisor")]
+    supervise_services: (bool, ConfigSource),
+    #[cfg(feature = "service-auth")]
+    service_auth_keys: Option<(Vec<String>, ConfigSource)>,
+    #[cfg(feature = "service-auth")]
+    service_auth_identities: Option<(Vec<String>, ConfigSource)>,
+    #[cfg(feature = "routing-table-database")]
+    routing_table: (String, ConfigSource),
+    #[cfg(feature = "tls-reload")]
+    tls_reload_interval: (u64, ConfigSource),
+    #[cfg(feature = "config-reload")]
+    log_level: (String, ConfigSource),
+    #[cfg(feature = "tls-revocation")]
+    tls_crl_file: Option<(String, ConfigSource)>,
+    #[cfg(feature = "tls-revocation")]
+    tls_denied_fingerprints: Option<(Vec<String>, ConfigSource)>,
+    #[cfg(feature = "biome-oidc")]
+    biome_oidc_issuer_url: Option<(String, ConfigSource)>,
+    #[cfg(feature = "biome-oidc")]
+    biome_oidc_client_id: Option<(String, ConfigSource)>,
+    #[cfg(feature = "biome-oidc")]
+    biome_oidc_client_secret: Option<(String, ConfigSource)>,
+    #[cfg(feature = "biome-oidc")]
+    biome_oidc_redirect_url: Option<(String, ConfigSource)>,
 }
 
 impl Config {
@@ -144,8 +174,12 @@ impl Config {
     }
 
     #[cfg(feature = "database")]
-    pub fn database(&self) -> &str {
-        &self.database.0
+    pub fn database(&self) -> Option<&str> {
+        if let Some((database, _)) = &self.database {
+            Some(database)
+        } else {
+            None
+        }
     }
 
     pub fn registries(&self) -> &[String] {
@@ -185,6 +219,11 @@ impl Config {
         self.enable_biome.0
     }
 
+    #[cfg(feature = "biome-api-keys")]
+    pub fn biome_api_key_guards(&self) -> bool {
+        self.biome_api_key_guards.0
+    }
+
     #[cfg(feature = "rest-api-cors")]
     pub fn whitelist(&self) -> Option<&[String]> {
         if let Some((list, _)) = &self.whitelist {
@@ -198,6 +237,80 @@ impl Config {
         self.strict_ref_counts.0
     }
 
+    #[cfg(feature = "service-auth")]
+    pub fn service_auth_keys(&self) -> Option<&[String]> {
+        self.service_auth_keys
+            .as_ref()
+            .map(|(keys, _)| keys.as_slice())
+    }
+
+    #[cfg(feature = "service-auth")]
+    pub fn service_auth_identities(&self) -> Option<&[String]> {
+        self.service_auth_identities
+            .as_ref()
+            .map(|(identities, _)| identities.as_slice())
+    }
+
+    #[cfg(feature = "service-supervisor")]
+    pub fn supervise_services(&self) -> bool {
+        self.supervise_services.0
+    }
+
+    #[cfg(feature = "routing-table-database")]
+    pub fn routing_table(&self) -> &str {
+        &self.routing_table.0
+    }
+
+    #[cfg(feature = "tls-reload")]
+    pub fn tls_reload_interval(&self) -> u64 {
+        self.tls_reload_interval.0
+    }
+
+    #[cfg(feature = "config-reload")]
+    pub fn log_level(&self) -> &str {
+        &self.log_level.0
+    }
+
+    #[cfg(feature = "tls-revocation")]
+    pub fn tls_crl_file(&self) -> Option<&str> {
+        self.tls_crl_file.as_ref().map(|(file, _)| file.as_str())
+    }
+
+    #[cfg(feature = "tls-revocation")]
+    pub fn tls_denied_fingerprints(&self) -> Option<&[String]> {
+        self.tls_denied_fingerprints
+            .as_ref()
+            .map(|(fingerprints, _)| fingerprints.as_slice())
+    }
+
+    #[cfg(feature = "biome-oidc")]
+    pub fn biome_oidc_issuer_url(&self) -> Option<&str> {
+        self.biome_oidc_issuer_url
+            .as_ref()
+            .map(|(value, _)| value.as_str())
+    }
+
+    #[cfg(feature = "biome-oidc")]
+    pub fn biome_oidc_client_id(&self) -> Option<&str> {
+        self.biome_oidc_client_id
+            .as_ref()
+            .map(|(value, _)| value.as_str())
+    }
+
+    #[cfg(feature = "biome-oidc")]
+    pub fn biome_oidc_client_secret(&self) -> Option<&str> {
+        self.biome_oidc_client_secret
+            .as_ref()
+            .map(|(value, _)| value.as_str())
+    }
+
+    #[cfg(feature = "biome-oidc")]
+    pub fn biome_oidc_redirect_url(&self) -> Option<&str> {
+        self.biome_oidc_redirect_url
+            .as_ref()
+            .map(|(value, _)| value.as_str())
+    }
+
     pub fn config_dir_source(&self) -> &ConfigSource {
         &self.config_dir.1
     }
@@ -268,8 +381,12 @@ impl Config {
     }
 
     #[cfg(feature = "database")]
-    fn database_source(&self) -> &ConfigSource {
-        &self.database.1
+    fn database_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.database {
+            Some(source)
+        } else {
+            None
+        }
     }
 
     fn registries_source(&self) -> &ConfigSource {
@@ -309,6 +426,31 @@ impl Config {
         &self.enable_biome.1
     }
 
+    #[cfg(feature = "biome-api-keys")]
+    fn biome_api_key_guards_source(&self) -> &ConfigSource {
+        &self.biome_api_key_guards.1
+    }
+
+    #[cfg(feature = "service-supervisor")]
+    fn supervise_services_source(&self) -> &ConfigSource {
+        &self.supervise_services.1
+    }
+
+    #[cfg(feature = "routing-table-database")]
+    fn routing_table_source(&self) -> &ConfigSource {
+        &self.routing_table.1
+    }
+
+    #[cfg(feature = "tls-reload")]
+    fn tls_reload_interval_source(&self) -> &ConfigSource {
+        &self.tls_reload_interval.1
+    }
+
+    #[cfg(feature = "config-reload")]
+    fn log_level_source(&self) -> &ConfigSource {
+        &self.log_level.1
+    }
+
     #[cfg(feature = "rest-api-cors")]
     pub fn whitelist_source(&self) -> Option<&ConfigSource> {
         if let Some((_, source)) = &self.whitelist {
@@ -322,6 +464,63 @@ impl Config {
         &self.strict_ref_counts.1
     }
 
+    #[cfg(feature = "service-auth")]
+    fn log_service_auth(&self) {
+        if let Some((keys, source)) = &self.service_auth_keys {
+            debug!(
+                "Config: service_auth_keys: {:?} (source: {:?})",
+                keys, source
+            );
+        }
+        if let Some((identities, source)) = &self.service_auth_identities {
+            debug!(
+                "Config: service_auth_identities: {:?} (source: {:?})",
+                identities, source
+            );
+        }
+    }
+
+    #[cfg(feature = "tls-revocation")]
+    fn log_tls_revocation(&self) {
+        if let Some((file, source)) = &self.tls_crl_file {
+            debug!("Config: tls_crl_file: {} (source: {:?})", file, source);
+        }
+        if let Some((fingerprints, source)) = &self.tls_denied_fingerprints {
+            debug!(
+                "Config: tls_denied_fingerprints: {:?} (source: {:?})",
+                fingerprints, source
+            );
+        }
+    }
+
+    #[cfg(feature = "biome-oidc")]
+    fn log_biome_oidc(&self) {
+        if let Some((value, source)) = &self.biome_oidc_issuer_url {
+            debug!(
+                "Config: biome_oidc_issuer_url: {} (source: {:?})",
+                value, source
+            );
+        }
+        if let Some((value, source)) = &self.biome_oidc_client_id {
+            debug!(
+                "Config: biome_oidc_client_id: {} (source: {:?})",
+                value, source
+            );
+        }
+        if let Some((_, source)) = &self.biome_oidc_client_secret {
+            debug!(
+                "Config: biome_oidc_client_secret: <redacted> (source: {:?})",
+                source
+            );
+        }
+        if let Some((value, source)) = &self.biome_oidc_redirect_url {
+            debug!(
+                "Config: biome_oidc_redirect_url: {} (source: {:?})",
+                value, source
+            );
+        }
+    }
+
     #[allow(clippy::cognitive_complexity)]
     /// Displays the configuration value along with where the value was sourced from.
     pub fn log_as_debug(&self) {
@@ -428,11 +627,11 @@ impl Config {
             self.admin_timeout_source()
         );
         #[cfg(feature = "database")]
-        debug!(
-            "database: {} (source: {:?})",
-            self.database(),
-            self.database_source(),
-        );
+        {
+            if let (Some(database), Some(source)) = (self.database(), self.database_source()) {
+                debug!("Config: database: {} (source: {:?})", database, source);
+            }
+        }
         debug!(
             "Config: tls_insecure: {:?} (source: {:?})",
             self.tls_insecure(),
@@ -449,6 +648,12 @@ impl Config {
             self.enable_biome(),
             self.enable_biome_source()
         );
+        #[cfg(feature = "biome-api-keys")]
+        debug!(
+            "Config: biome_api_key_guards: {:?} (source: {:?})",
+            self.biome_api_key_guards(),
+            self.biome_api_key_guards_source()
+        );
         #[cfg(feature = "rest-api-cors")]
         self.log_whitelist();
         debug!(
@@ -456,6 +661,36 @@ impl Config {
             self.strict_ref_counts(),
             self.strict_ref_counts_source()
         );
+        #[cfg(feature = "service-supervisor")]
+        debug!(
+            "Config: supervise_services: {:?} (source: {:?})",
+            self.supervise_services(),
+            self.supervise_services_source()
+        );
+        #[cfg(feature = "routing-table-database")]
+        debug!(
+            "Config: routing_table: {} (source: {:?})",
+            self.routing_table(),
+            self.routing_table_source()
+        );
+        #[cfg(feature = "tls-reload")]
+        debug!(
+            "Config: tls_reload_interval: {} (source: {:?})",
+            self.tls_reload_interval(),
+            self.tls_reload_interval_source()
+        );
+        #[cfg(feature = "config-reload")]
+        debug!(
+            "Config: log_level: {} (source: {:?})",
+            self.log_level(),
+            self.log_level_source()
+        );
+        #[cfg(feature = "service-auth")]
+        self.log_service_auth();
+        #[cfg(feature = "tls-revocation")]
+        self.log_tls_revocation();
+        #[cfg(feature = "biome-oidc")]
+        self.log_biome_oidc();
     }
 
     #[cfg(feature = "rest-api-cors")]
@@ -464,6 +699,149 @@ impl Config {
             debug!("Config: whitelist: {:?} (source: {:?})", list, source,);
         }
     }
+
+    /// Returns the names of the settings whose values differ between this config and `other`.
+    ///
+    /// Only the values are compared; a setting whose value is unchanged but is now sourced from
+    /// somewhere else is not reported.
+    #[cfg(feature = "config-reload")]
+    pub fn changed_settings(&self, other: &Config) -> Vec<&'static str> {
+        #[allow(unused_mut)]
+        let mut settings = vec![
+            ("config_dir", self.config_dir() != other.config_dir()),
+            ("storage", self.storage() != other.storage()),
+            ("tls_cert_dir", self.tls_cert_dir() != other.tls_cert_dir()),
+            ("tls_ca_file", self.tls_ca_file() != other.tls_ca_file()),
+            (
+                "tls_client_cert",
+                self.tls_client_cert() != other.tls_client_cert(),
+            ),
+            (
+                "tls_client_key",
+                self.tls_client_key() != other.tls_client_key(),
+            ),
+            (
+                "tls_server_cert",
+                self.tls_server_cert() != other.tls_server_cert(),
+            ),
+            (
+                "tls_server_key",
+                self.tls_server_key() != other.tls_server_key(),
+            ),
+            (
+                "network_endpoints",
+                self.network_endpoints() != other.network_endpoints(),
+            ),
+            (
+                "advertised_endpoints",
+                self.advertised_endpoints() != other.advertised_endpoints(),
+            ),
+            ("peers", self.peers() != other.peers()),
+            ("node_id", self.node_id() != other.node_id()),
+            ("display_name", self.display_name() != other.display_name()),
+            (
+                "rest_api_endpoint",
+                self.rest_api_endpoint() != other.rest_api_endpoint(),
+            ),
+            ("registries", self.registries() != other.registries()),
+            (
+                "registry_auto_refresh",
+                self.registry_auto_refresh() != other.registry_auto_refresh(),
+            ),
+            (
+                "registry_forced_refresh",
+                self.registry_forced_refresh() != other.registry_forced_refresh(),
+            ),
+            ("heartbeat", self.heartbeat() != other.heartbeat()),
+            (
+                "admin_timeout",
+                self.admin_timeout() != other.admin_timeout(),
+            ),
+            ("state_dir", self.state_dir() != other.state_dir()),
+            ("tls_insecure", self.tls_insecure() != other.tls_insecure()),
+            ("no_tls", self.no_tls() != other.no_tls()),
+            (
+                "strict_ref_counts",
+                self.strict_ref_counts() != other.strict_ref_counts(),
+            ),
+            ("log_level", self.log_level() != other.log_level()),
+        ];
+        #[cfg(feature = "service-endpoint")]
+        settings.push((
+            "service_endpoint",
+            self.service_endpoint() != other.service_endpoint(),
+        ));
+        #[cfg(feature = "database")]
+        settings.push(("database", self.database() != other.database()));
+        #[cfg(feature = "biome")]
+        settings.push(("enable_biome", self.enable_biome() != other.enable_biome()));
+        #[cfg(feature = "biome-api-keys")]
+        settings.push((
+            "biome_api_key_guards",
+            self.biome_api_key_guards() != other.biome_api_key_guards(),
+        ));
+        #[cfg(feature = "rest-api-cors")]
+        settings.push(("whitelist", self.whitelist() != other.whitelist()));
+        #[cfg(feature = "service-supervisor")]
+        settings.push((
+            "supervise_services",
+            self.supervise_services() != other.supervise_services(),
+        ));
+        #[cfg(feature = "service-auth")]
+        {
+            settings.push((
+                "service_auth_keys",
+                self.service_auth_keys() != other.service_auth_keys(),
+            ));
+            settings.push((
+                "service_auth_identities",
+                self.service_auth_identities() != other.service_auth_identities(),
+            ));
+        }
+        #[cfg(feature = "routing-table-database")]
+        settings.push((
+            "routing_table",
+            self.routing_table() != other.routing_table(),
+        ));
+        #[cfg(feature = "tls-reload")]
+        settings.push((
+            "tls_reload_interval",
+            self.tls_reload_interval() != other.tls_reload_interval(),
+        ));
+        #[cfg(feature = "tls-revocation")]
+        {
+            settings.push(("tls_crl_file", self.tls_crl_file() != other.tls_crl_file()));
+            settings.push((
+                "tls_denied_fingerprints",
+                self.tls_denied_fingerprints() != other.tls_denied_fingerprints(),
+            ));
+        }
+        #[cfg(feature = "biome-oidc")]
+        {
+            settings.push((
+                "biome_oidc_issuer_url",
+                self.biome_oidc_issuer_url() != other.biome_oidc_issuer_url(),
+            ));
+            settings.push((
+                "biome_oidc_client_id",
+                self.biome_oidc_client_id() != other.biome_oidc_client_id(),
+            ));
+            settings.push((
+                "biome_oidc_client_secret",
+                self.biome_oidc_client_secret() != other.biome_oidc_client_secret(),
+            ));
+            settings.push((
+                "biome_oidc_redirect_url",
+                self.biome_oidc_redirect_url() != other.biome_oidc_redirect_url(),
+            ));
+        }
+
+        settings
+            .into_iter()
+            .filter(|(_, changed)| *changed)
+            .map(|(setting, _)| setting)
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -940,11 +1318,10 @@ mod tests {
             ("127.0.0.1:8080", &ConfigSource::Default)
         );
         #[cfg(feature = "database")]
-        // The `DefaultPartialConfigBuilder` is the only config with a value for `database` (source
-        // should be `Default`).
+        // No config had a value for `database`, so no database-backed stores are configured.
         assert_eq!(
             (final_config.database(), final_config.database_source()),
-            ("127.0.0.1:5432", &ConfigSource::Default)
+            (None, None)
         );
         // The `DefaultPartialConfigBuilder` is the only config with a value for
         // `registry_auto_refresh` (source should be `Default`).
@@ -1111,4 +1488,51 @@ mod tests {
             )
         );
     }
+
+    #[cfg(feature = "config-reload")]
+    #[test]
+    /// This test verifies that `Config::changed_settings` reports only the settings whose values
+    /// differ, in the following steps:
+    ///
+    /// 1. A `Config` object is built from just a `DefaultPartialConfigBuilder`.
+    /// 2. A second `Config` object is built from a `PartialConfig` that changes the `storage`,
+    ///    `heartbeat` and `log_level` values and sets `peers` to its default value, followed by a
+    ///    `DefaultPartialConfigBuilder`.
+    ///
+    /// This test then verifies that the changed settings are reported, and that `peers` is not
+    /// reported even though its source changed.
+    fn test_changed_settings() {
+        let default_config = ConfigBuilder::new()
+            .with_partial_config(
+                DefaultPartialConfigBuilder::new()
+                    .build()
+                    .expect("Unable to build DefaultPartialConfigBuilder"),
+            )
+            .build()
+            .expect("Unable to build default Config");
+
+        let changed_config = ConfigBuilder::new()
+            .with_partial_config(
+                PartialConfig::new(ConfigSource::Toml {
+                    file: TEST_TOML.to_string(),
+                })
+                .with_storage(Some("memory".to_string()))
+                .with_heartbeat(Some(10))
+                .with_log_level(Some("debug".to_string()))
+                .with_peers(Some(vec![])),
+            )
+            .with_partial_config(
+                DefaultPartialConfigBuilder::new()
+                    .build()
+                    .expect("Unable to build DefaultPartialConfigBuilder"),
+            )
+            .build()
+            .expect("Unable to build changed Config");
+
+        assert_eq!(
+            default_config.changed_settings(&changed_config),
+            vec!["storage", "heartbeat", "log_level"]
+        );
+        assert!(changed_config.changed_settings(&changed_config).is_empty());
+    }
 }
@@ -27,6 +27,17 @@ pub enum ConfigSource {
     CommandLine,
 }
 
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ConfigSource::Toml { file } => write!(f, "toml file {}", file),
+            ConfigSource::Default => f.write_str("default"),
+            ConfigSource::Environment => f.write_str("environment"),
+            ConfigSource::CommandLine => f.write_str("command line"),
+        }
+    }
+}
+
 /// `PartialConfig` is an intermediate representation of configuration values, used when combining
 /// several sources. As such, all values of the `PartialConfig` are options as it is not necessary
 /// to provide all values from a single source.
@@ -61,9 +72,35 @@ pub struct PartialConfig {
     no_tls: Option<bool>,
     #[cfg(feature = "biome")]
     enable_biome: Option<bool>,
+    #[cfg(feature = "biome-api-keys")]
+    biome_api_key_guards: Option<bool>,
     #[cfg(feature = "rest-api-cors")]
     whitelist: Option<Vec<String>>,
     strict_ref_counts: Option<bool>,
+    #[cfg(feature = "service-supervisor")]
+    supervise_services: Option<bool>,
+    #[cfg(feature = "service-auth")]
+    service_auth_keys: Option<Vec<String>>,
+    #[cfg(feature = "service-auth")]
+    service_auth_identities: Option<Vec<String>>,
+    #[cfg(feature = "routing-table-database")]
+    routing_table: Option<String>,
+    #[cfg(feature = "tls-reload")]
+    tls_reload_interval: Option<u64>,
+    #[cfg(feature = "config-reload")]
+    log_level: Option<String>,
+    #[cfg(feature = "tls-revocation")]
+    tls_crl_file: Option<String>,
+    #[cfg(feature = "tls-revocation")]
+    tls_denied_fingerprints: Option<Vec<String>>,
+    #[cfg(feature = "biome-oidc")]
+    biome_oidc_issuer_url: Option<String>,
+    #[cfg(feature = "biome-oidc")]
+    biome_oidc_client_id: Option<String>,
+    #[cfg(feature = "biome-oidc")]
+    biome_oidc_client_secret: Option<String>,
+    #[cfg(feature = "biome-oidc")]
+    biome_oidc_redirect_url: Option<String>,
 }
 
 impl PartialConfig {
@@ -99,9 +136,35 @@ impl PartialConfig {
             no_tls: None,
             #[cfg(feature = "biome")]
             enable_biome: None,
+            #[cfg(feature = "biome-api-keys")]
+            biome_api_key_guards: None,
             #[cfg(feature = "rest-api-cors")]
             whitelist: None,
             strict_ref_counts: None,
+            #[cfg(feature = "service-supervisor")]
+            supervise_services: None,
+            #[cfg(feature = "service-auth")]
+            service_auth_keys: None,
+            #[cfg(feature = "service-auth")]
+            service_auth_identities: None,
+            #[cfg(feature = "routing-table-database")]
+            routing_table: None,
+            #[cfg(feature = "tls-reload")]
+            tls_reload_interval: None,
+            #[cfg(feature = "config-reload")]
+            log_level: None,
+            #[cfg(feature = "tls-revocation")]
+            tls_crl_file: None,
+            #[cfg(feature = "tls-revocation")]
+            tls_denied_fingerprints: None,
+            #[cfg(feature = "biome-oidc")]
+            biome_oidc_issuer_url: None,
+            #[cfg(feature = "biome-oidc")]
+            biome_oidc_client_id: None,
+            #[cfg(feature = "biome-oidc")]
+            biome_oidc_client_secret: None,
+            #[cfg(feature = "biome-oidc")]
+            biome_oidc_redirect_url: None,
         }
     }
 
@@ -212,11 +275,76 @@ impl PartialConfig {
         self.enable_biome
     }
 
+    #[cfg(feature = "biome-api-keys")]
+    pub fn biome_api_key_guards(&self) -> Option<bool> {
+        self.biome_api_key_guards
+    }
+
     #[cfg(feature = "rest-api-cors")]
     pub fn whitelist(&self) -> Option<Vec<String>> {
         self.whitelist.clone()
     }
 
+    #[cfg(feature = "service-supervisor")]
+    pub fn supervise_services(&self) -> Option<bool> {
+        self.supervise_services
+    }
+
+    #[cfg(feature = "service-auth")]
+    pub fn service_auth_keys(&self) -> Option<Vec<String>> {
+        self.service_auth_keys.clone()
+    }
+
+    #[cfg(feature = "service-auth")]
+    pub fn service_auth_identities(&self) -> Option<Vec<String>> {
+        self.service_auth_identities.clone()
+    }
+
+    #[cfg(feature = "routing-table-database")]
+    pub fn routing_table(&self) -> Option<String> {
+        self.routing_table.clone()
+    }
+
+    #[cfg(feature = "tls-reload")]
+    pub fn tls_reload_interval(&self) -> Option<u64> {
+        self.tls_reload_interval
+    }
+
+    #[cfg(feature = "config-reload")]
+    pub fn log_level(&self) -> Option<String> {
+        self.log_level.clone()
+    }
+
+    #[cfg(feature = "tls-revocation")]
+    pub fn tls_crl_file(&self) -> Option<String> {
+        self.tls_crl_file.clone()
+    }
+
+    #[cfg(feature = "tls-revocation")]
+    pub fn tls_denied_fingerprints(&self) -> Option<Vec<String>> {
+        self.tls_denied_fingerprints.clone()
+    }
+
+    #[cfg(feature = "biome-oidc")]
+    pub fn biome_oidc_issuer_url(&self) -> Option<String> {
+        self.biome_oidc_issuer_url.clone()
+    }
+
+    #[cfg(feature = "biome-oidc")]
+    pub fn biome_oidc_client_id(&self) -> Option<String> {
+        self.biome_oidc_client_id.clone()
+    }
+
+    #[cfg(feature = "biome-oidc")]
+    pub fn biome_oidc_client_secret(&self) -> Option<String> {
+        self.biome_oidc_client_secret.clone()
+    }
+
+    #[cfg(feature = "biome-oidc")]
+    pub fn biome_oidc_redirect_url(&self) -> Option<String> {
+        self.biome_oidc_redirect_url.clone()
+    }
+
     pub fn strict_ref_counts(&self) -> Option<bool> {
         self.strict_ref_counts
     }
@@ -508,6 +636,178 @@ impl PartialConfig {
         self
     }
 
+    #[cfg(feature = "biome-api-keys")]
+    /// Adds a `biome_api_key_guards` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `biome_api_key_guards` - Require a Biome access token or an API key for the admin,
+    ///   registry and scabbard REST API routes
+    ///
+    pub fn with_biome_api_key_guards(mut self, biome_api_key_guards: Option<bool>) -> Self {
+        self.biome_api_key_guards = biome_api_key_guards;
+        self
+    }
+
+    #[cfg(feature = "service-supervisor")]
+    /// Adds a `supervise_services` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `supervise_services` - Run circuit services in supervised child processes
+    ///
+    pub fn with_supervise_services(mut self, supervise_services: Option<bool>) -> Self {
+        self.supervise_services = supervise_services;
+        self
+    }
+
+    #[cfg(feature = "service-auth")]
+    /// Adds a `service_auth_keys` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `service_auth_keys` - Hex-encoded public keys trusted to sign service registration
+    ///   tokens
+    ///
+    pub fn with_service_auth_keys(mut self, service_auth_keys: Option<Vec<String>>) -> Self {
+        self.service_auth_keys = service_auth_keys;
+        self
+    }
+
+    #[cfg(feature = "service-auth")]
+    /// Adds a `service_auth_identities` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `service_auth_identities` - TLS identities trusted to connect services, either as
+    ///   `<identity>` or `<identity>@<circuit>::<service_id>`
+    ///
+    pub fn with_service_auth_identities(
+        mut self,
+        service_auth_identities: Option<Vec<String>>,
+    ) -> Self {
+        self.service_auth_identities = service_auth_identities;
+        self
+    }
+
+    #[cfg(feature = "routing-table-database")]
+    /// Adds a `routing_table` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `routing_table` - Storage for the circuit routing table, either `memory` or `database`
+    ///
+    pub fn with_routing_table(mut self, routing_table: Option<String>) -> Self {
+        self.routing_table = routing_table;
+        self
+    }
+
+    #[cfg(feature = "tls-reload")]
+    /// Adds a `tls_reload_interval` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `tls_reload_interval` - How often the TLS certificate and key files are checked for
+    ///   changes, in seconds
+    ///
+    pub fn with_tls_reload_interval(mut self, tls_reload_interval: Option<u64>) -> Self {
+        self.tls_reload_interval = tls_reload_interval;
+        self
+    }
+
+    #[cfg(feature = "config-reload")]
+    /// Adds a `log_level` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `log_level` - The default log level, such as `warn` or `debug`
+    ///
+    pub fn with_log_level(mut self, log_level: Option<String>) -> Self {
+        self.log_level = log_level;
+        self
+    }
+
+    #[cfg(feature = "tls-revocation")]
+    /// Adds a `tls_crl_file` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `tls_crl_file` - Path to a PEM-encoded certificate revocation list issued by the CA
+    ///
+    pub fn with_tls_crl_file(mut self, tls_crl_file: Option<String>) -> Self {
+        self.tls_crl_file = tls_crl_file;
+        self
+    }
+
+    #[cfg(feature = "tls-revocation")]
+    /// Adds a `tls_denied_fingerprints` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `tls_denied_fingerprints` - Hex-encoded SHA-256 fingerprints of peer certificates that
+    ///   are rejected during the TLS handshake
+    ///
+    pub fn with_tls_denied_fingerprints(
+        mut self,
+        tls_denied_fingerprints: Option<Vec<String>>,
+    ) -> Self {
+        self.tls_denied_fingerprints = tls_denied_fingerprints;
+        self
+    }
+
+    #[cfg(feature = "biome-oidc")]
+    /// Adds a `biome_oidc_issuer_url` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `biome_oidc_issuer_url` - URL of the OpenID Connect provider that Biome users may log in through
+    ///
+    pub fn with_biome_oidc_issuer_url(mut self, biome_oidc_issuer_url: Option<String>) -> Self {
+        self.biome_oidc_issuer_url = biome_oidc_issuer_url;
+        self
+    }
+
+    #[cfg(feature = "biome-oidc")]
+    /// Adds a `biome_oidc_client_id` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `biome_oidc_client_id` - Client ID that the node is registered with at the OpenID Connect provider
+    ///
+    pub fn with_biome_oidc_client_id(mut self, biome_oidc_client_id: Option<String>) -> Self {
+        self.biome_oidc_client_id = biome_oidc_client_id;
+        self
+    }
+
+    #[cfg(feature = "biome-oidc")]
+    /// Adds a `biome_oidc_client_secret` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `biome_oidc_client_secret` - Client secret that the node is registered with at the OpenID Connect
+    ///   provider
+    ///
+    pub fn with_biome_oidc_client_secret(
+        mut self,
+        biome_oidc_client_secret: Option<String>,
+    ) -> Self {
+        self.biome_oidc_client_secret = biome_oidc_client_secret;
+        self
+    }
+
+    #[cfg(feature = "biome-oidc")]
+    /// Adds a `biome_oidc_redirect_url` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `biome_oidc_redirect_url` - URL the OpenID Connect provider redirects to after a login, which must
+    ///   route to `/biome/oidc/callback`
+    ///
+    pub fn with_biome_oidc_redirect_url(mut self, biome_oidc_redirect_url: Option<String>) -> Self {
+        self.biome_oidc_redirect_url = biome_oidc_redirect_url;
+        self
+    }
+
     #[cfg(feature = "rest-api-cors")]
     /// Adds a `whitelist` value to the `PartialConfig` object.
     ///
@@ -53,6 +53,30 @@ struct TomlConfig {
     version: Option<String>,
     #[cfg(feature = "rest-api-cors")]
     whitelist: Option<Vec<String>>,
+    #[cfg(feature = "service-auth")]
+    service_auth_keys: Option<Vec<String>>,
+    #[cfg(feature = "service-auth")]
+    service_auth_identities: Option<Vec<String>>,
+    #[cfg(feature = "routing-table-database")]
+    routing_table: Option<String>,
+    #[cfg(feature = "tls-reload")]
+    tls_reload_interval: Option<u64>,
+    #[cfg(feature = "config-reload")]
+    log_level: Option<String>,
+    #[cfg(feature = "tls-revocation")]
+    tls_crl_file: Option<String>,
+    #[cfg(feature = "tls-revocation")]
+    tls_denied_fingerprints: Option<Vec<String>>,
+    #[cfg(feature = "biome-oidc")]
+    biome_oidc_issuer_url: Option<String>,
+    #[cfg(feature = "biome-oidc")]
+    biome_oidc_client_id: Option<String>,
+    #[cfg(feature = "biome-oidc")]
+    biome_oidc_client_secret: Option<String>,
+    #[cfg(feature = "biome-oidc")]
+    biome_oidc_redirect_url: Option<String>,
+    #[cfg(feature = "biome-api-keys")]
+    biome_api_key_guards: Option<bool>,
 
     // Deprecated values
     cert_dir: Option<String>,
@@ -149,6 +173,51 @@ impl PartialConfigBuilder for TomlPartialConfigBuilder {
             partial_config = partial_config.with_whitelist(self.toml_config.whitelist);
         }
 
+        #[cfg(feature = "service-auth")]
+        {
+            partial_config = partial_config
+                .with_service_auth_keys(self.toml_config.service_auth_keys)
+                .with_service_auth_identities(self.toml_config.service_auth_identities);
+        }
+
+        #[cfg(feature = "routing-table-database")]
+        {
+            partial_config = partial_config.with_routing_table(self.toml_config.routing_table);
+        }
+
+        #[cfg(feature = "tls-reload")]
+        {
+            partial_config =
+                partial_config.with_tls_reload_interval(self.toml_config.tls_reload_interval);
+        }
+
+        #[cfg(feature = "config-reload")]
+        {
+            partial_config = partial_config.with_log_level(self.toml_config.log_level);
+        }
+
+        #[cfg(feature = "tls-revocation")]
+        {
+            partial_config = partial_config
+                .with_tls_crl_file(self.toml_config.tls_crl_file)
+                .with_tls_denied_fingerprints(self.toml_config.tls_denied_fingerprints);
+        }
+
+        #[cfg(feature = "biome-oidc")]
+        {
+            partial_config = partial_config
+                .with_biome_oidc_issuer_url(self.toml_config.biome_oidc_issuer_url)
+                .with_biome_oidc_client_id(self.toml_config.biome_oidc_client_id)
+                .with_biome_oidc_client_secret(self.toml_config.biome_oidc_client_secret)
+                .with_biome_oidc_redirect_url(self.toml_config.biome_oidc_redirect_url);
+        }
+
+        #[cfg(feature = "biome-api-keys")]
+        {
+            partial_config =
+                partial_config.with_biome_api_key_guards(self.toml_config.biome_api_key_guards);
+        }
+
         // deprecated values, only set if the current value was not set
         if partial_config.tls_cert_dir().is_none() {
             partial_config = partial_config.with_tls_cert_dir(self.toml_config.cert_dir)
@@ -0,0 +1,68 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implements the `splinterd config` subcommands, which inspect the configuration that the daemon
+//! would start with, without starting it.
+
+use clap::ArgMatches;
+
+use crate::config::{check_config, ShownConfig};
+use crate::error::UserError;
+
+/// Runs the `config` subcommand given by `config_matches`, using the configuration built from the
+/// top-level `matches`.
+pub fn run_config_command(
+    matches: &ArgMatches<'static>,
+    config_matches: &ArgMatches,
+) -> Result<(), UserError> {
+    let config = crate::create_config(crate::find_config_file(matches), matches.clone())?;
+
+    match config_matches.subcommand() {
+        ("check", Some(_)) => {
+            let problems = check_config(&config);
+            if problems.is_empty() {
+                println!("Configuration is valid");
+                Ok(())
+            } else {
+                for problem in &problems {
+                    println!("{}", problem);
+                }
+                Err(UserError::InvalidArgument(format!(
+                    "configuration has {} problem(s)",
+                    problems.len()
+                )))
+            }
+        }
+        ("show", Some(show_matches)) => {
+            let shown = ShownConfig::new(&config);
+            let output = match show_matches.value_of("format").unwrap_or("toml") {
+                "json" => serde_json::to_string_pretty(&shown).map_err(|err| {
+                    UserError::daemon_err_with_source(
+                        "unable to serialize configuration",
+                        Box::new(err),
+                    )
+                })?,
+                _ => toml::to_string(&shown).map_err(|err| {
+                    UserError::daemon_err_with_source(
+                        "unable to serialize configuration",
+                        Box::new(err),
+                    )
+                })?,
+            };
+            println!("{}", output);
+            Ok(())
+        }
+        _ => Err(UserError::MissingArgument("config subcommand".into())),
+    }
+}
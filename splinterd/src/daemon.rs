@@ -12,13 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-#[cfg(feature = "service-arg-validation")]
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
 use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
@@ -29,13 +28,38 @@ use scabbard::service::ScabbardArgValidator;
 use scabbard::service::ScabbardFactory;
 use splinter::admin::rest_api::CircuitResourceProvider;
 use splinter::admin::service::{admin_service_id, AdminService};
+#[cfg(feature = "biome-notifications")]
+use splinter::admin::service::{AdminCommands, ALL_EVENT_TYPES};
+#[cfg(all(
+    feature = "biome-authorization",
+    any(feature = "tls-reload", feature = "config-reload")
+))]
+use splinter::biome::authorization::Permission;
+#[cfg(feature = "biome-notifications")]
+use splinter::biome::notifications::admin::AdminNotificationSubscriber;
+#[cfg(feature = "biome-oidc")]
+use splinter::biome::oidc::OidcClient;
+#[cfg(all(
+    feature = "biome-authorization",
+    any(feature = "tls-reload", feature = "config-reload")
+))]
+use splinter::biome::rest_api::PermissionGuard;
 #[cfg(feature = "biome")]
 use splinter::biome::rest_api::{BiomeRestResourceManager, BiomeRestResourceManagerBuilder};
 use splinter::circuit::directory::CircuitDirectory;
+#[cfg(feature = "service-auth")]
+use splinter::circuit::handlers::ServiceAuthorizationPolicy;
 use splinter::circuit::handlers::{
     AdminDirectMessageHandler, CircuitDirectMessageHandler, CircuitErrorHandler,
     CircuitMessageHandler, ServiceConnectRequestHandler, ServiceDisconnectRequestHandler,
 };
+#[cfg(feature = "routing-table-database")]
+use splinter::circuit::routing::{self, RoutingTableReader, RoutingTableWriter};
+#[cfg(feature = "circuit-template")]
+use splinter::circuit::template::{
+    catalog::{LocalTemplateCatalog, TemplateCatalog},
+    DEFAULT_TEMPLATE_DIR,
+};
 use splinter::circuit::{SplinterState, SplinterStateError};
 use splinter::keys::insecure::AllowAllKeyPermissionManager;
 use splinter::mesh::Mesh;
@@ -47,6 +71,9 @@ use splinter::network::dispatch::{
     dispatch_channel, DispatchLoopBuilder, DispatchMessageSender, Dispatcher,
 };
 use splinter::network::handlers::{NetworkEchoHandler, NetworkHeartbeatHandler};
+use splinter::network::priority::network_message_priority;
+#[cfg(feature = "service-supervisor")]
+use splinter::orchestrator::ServiceProcessConfig;
 use splinter::orchestrator::{NewOrchestratorError, ServiceOrchestrator};
 use splinter::peer::interconnect::NetworkMessageSender;
 use splinter::peer::interconnect::PeerInterconnectBuilder;
@@ -57,6 +84,10 @@ use splinter::registry::{
     LocalYamlRegistry, RegistryReader, RemoteYamlRegistry, RemoteYamlShutdownHandle, RwRegistry,
     UnifiedRegistry,
 };
+#[cfg(feature = "rest-api-cors")]
+use splinter::rest_api::cors::Cors;
+#[cfg(any(feature = "tls-reload", feature = "config-reload"))]
+use splinter::rest_api::{LocalRequestGuard, RequestGuard};
 use splinter::rest_api::{
     Method, Resource, RestApiBuilder, RestApiServerError, RestResourceProvider,
 };
@@ -64,12 +95,27 @@ use splinter::rest_api::{
 use splinter::service::validation::ServiceArgValidator;
 use splinter::service::{self, ServiceProcessor, ShutdownHandle};
 use splinter::signing::sawtooth::SawtoothSecp256k1SignatureVerifier;
+#[cfg(all(feature = "service-auth", feature = "service-supervisor"))]
+use splinter::signing::sawtooth::SawtoothSecp256k1Signer;
+#[cfg(all(feature = "service-auth", feature = "service-supervisor"))]
+use splinter::signing::Signer;
 use splinter::storage::get_storage;
+#[cfg(any(
+    feature = "admin-service-store",
+    feature = "biome",
+    feature = "registry-database",
+    feature = "routing-table-database"
+))]
+use splinter::store::StoreFactory;
+#[cfg(feature = "tls-reload")]
+use splinter::transport::tls::{TlsFileWatcher, TlsReloadHandle};
 use splinter::transport::{
     inproc::InprocTransport, multi::MultiTransport, AcceptError, ConnectError, Connection,
     Incoming, ListenError, Listener, Transport,
 };
 
+#[cfg(feature = "config-reload")]
+use crate::reload::{self, ConfigReloader, ReloadTargets};
 use crate::routes;
 
 const ORCHESTRATOR_INCOMING_CAPACITY: usize = 8;
@@ -104,6 +150,10 @@ pub struct SplinterDaemon {
     db_url: Option<String>,
     #[cfg(feature = "biome")]
     enable_biome: bool,
+    #[cfg(feature = "biome-api-keys")]
+    biome_api_key_guards: bool,
+    #[cfg(feature = "biome-oidc")]
+    biome_oidc_client: Option<OidcClient>,
     registries: Vec<String>,
     registry_auto_refresh: u64,
     registry_forced_refresh: u64,
@@ -113,6 +163,22 @@ pub struct SplinterDaemon {
     whitelist: Option<Vec<String>>,
     heartbeat: u64,
     strict_ref_counts: bool,
+    #[cfg(feature = "service-supervisor")]
+    supervise_services: bool,
+    #[cfg(feature = "service-supervisor")]
+    service_process_args: Vec<String>,
+    #[cfg(feature = "service-auth")]
+    service_auth_keys: Vec<String>,
+    #[cfg(feature = "service-auth")]
+    service_auth_identities: Vec<String>,
+    #[cfg(feature = "routing-table-database")]
+    routing_table: String,
+    #[cfg(feature = "tls-reload")]
+    tls_reload_handles: Vec<TlsReloadHandle>,
+    #[cfg(feature = "tls-reload")]
+    tls_reload_interval: u64,
+    #[cfg(feature = "config-reload")]
+    config_reloader: Option<ConfigReloader>,
 }
 
 impl SplinterDaemon {
@@ -144,7 +210,33 @@ impl SplinterDaemon {
         let storage = get_storage(&storage_location, CircuitDirectory::new)
             .map_err(StartError::StorageError)?;
         let circuit_directory = storage.read().clone();
+
+        // All of the node state kept in the database is accessed through a single store factory,
+        // which is only created if a database is configured; without one, that state is kept in
+        // memory or in the state directory
+        #[cfg(any(
+            feature = "admin-service-store",
+            feature = "biome",
+            feature = "registry-database",
+            feature = "routing-table-database"
+        ))]
+        let store_factory = match &self.db_url {
+            Some(db_url) => Some(create_store_factory(db_url)?),
+            None => None,
+        };
+
+        #[cfg(feature = "routing-table-database")]
+        let (routing_reader, routing_writer) = load_routing_table(
+            &self.routing_table,
+            store_factory.as_deref(),
+            &circuit_directory,
+        )?;
+
         let state = SplinterState::new(storage_location, circuit_directory);
+        // Circuits and nodes added or removed by the admin service are written through to the
+        // routing table, which the circuit handlers consult when routing messages.
+        #[cfg(feature = "routing-table-database")]
+        let state = state.with_routing_table(routing_reader, routing_writer);
 
         // set up the listeners on the transport. This will set up listeners for different
         // transports based on the protocol prefix of the endpoint.
@@ -203,6 +295,42 @@ impl SplinterDaemon {
             format!("health::{}", &self.node_id),
         ));
 
+        // The internal services' identities may only be held by their in-process connections,
+        // which are authorized by the inproc authorizer below.
+        let authorization_manager = authorization_manager
+            .with_reserved_identities(inproc_ids.iter().map(|(_, identity)| identity.clone()));
+
+        #[cfg(feature = "service-auth")]
+        let (authorization_manager, service_authorization_policy) = {
+            let (policy, verified_identities) = self.service_authorization_policy(
+                inproc_ids.iter().map(|(_, identity)| identity.as_str()),
+            )?;
+            (
+                authorization_manager.with_verified_identities(verified_identities),
+                policy,
+            )
+        };
+
+        // Supervised service processes present registration tokens signed by a key that is
+        // generated for this run of the daemon
+        #[cfg(all(feature = "service-auth", feature = "service-supervisor"))]
+        let (service_authorization_policy, service_registration_signer) =
+            match service_authorization_policy {
+                Some(policy) if self.supervise_services => {
+                    let signer = SawtoothSecp256k1Signer::new_random().map_err(|err| {
+                        StartError::OrchestratorError(format!(
+                            "unable to create the service registration signer: {}",
+                            err
+                        ))
+                    })?;
+                    (
+                        Some(policy.with_trusted_key(signer.public_key().to_vec())),
+                        Some(Arc::new(signer)),
+                    )
+                }
+                policy => (policy, None),
+            };
+
         let inproc_authorizer = InprocAuthorizer::new(inproc_ids);
 
         let mut authorizers = Authorizers::new();
@@ -221,6 +349,8 @@ impl SplinterDaemon {
             })?;
         let connection_connector = connection_manager.connector();
         let connection_manager_shutdown = connection_manager.shutdown_signaler();
+        #[cfg(feature = "config-reload")]
+        let heartbeat_interval_handle = connection_manager.heartbeat_interval_handle();
 
         let peer_manager = PeerManager::builder()
             .with_connector(connection_connector.clone())
@@ -291,6 +421,8 @@ impl SplinterDaemon {
             &self.node_id,
             &self.network_endpoints,
             state.clone(),
+            #[cfg(feature = "service-auth")]
+            service_authorization_policy,
         );
         let circuit_dispatch_loop = DispatchLoopBuilder::new()
             .with_dispatcher(circuit_dispatcher)
@@ -304,8 +436,12 @@ impl SplinterDaemon {
         let circuit_dispatcher_shutdown = circuit_dispatch_loop.shutdown_signaler();
 
         // Set up the Network dispatcher
-        let network_dispatcher =
-            set_up_network_dispatcher(network_sender, &self.node_id, circuit_dispatch_sender);
+        let network_dispatcher = set_up_network_dispatcher(
+            network_sender,
+            &self.node_id,
+            circuit_dispatch_sender,
+            connection_connector.clone(),
+        );
 
         let network_dispatch_loop = DispatchLoopBuilder::new()
             .with_dispatcher(network_dispatcher)
@@ -356,10 +492,12 @@ impl SplinterDaemon {
             .collect::<Vec<_>>();
 
         // hold on to peer refs for the peers provided to ensure the connections are kept around
-        let mut peer_refs = vec![];
+        let mut peer_refs = HashMap::new();
         for endpoint in self.initial_peers.iter() {
             match peer_connector.add_unidentified_peer(endpoint.into()) {
-                Ok(peer_ref) => peer_refs.push(peer_ref),
+                Ok(peer_ref) => {
+                    peer_refs.insert(endpoint.to_string(), peer_ref);
+                }
                 Err(err) => error!("Connect Error: {}", err),
             }
         }
@@ -377,16 +515,67 @@ impl SplinterDaemon {
             ORCHESTRATOR_OUTGOING_CAPACITY,
             ORCHESTRATOR_CHANNEL_CAPACITY,
         )?;
-        let orchestrator_resources = orchestrator.resources();
+        #[cfg(feature = "service-supervisor")]
+        let orchestrator = if self.supervise_services {
+            // Services are run by this executable's hidden `run-service` subcommand
+            let program = std::env::current_exe().map_err(|err| {
+                StartError::OrchestratorError(format!(
+                    "unable to determine the splinterd executable: {}",
+                    err
+                ))
+            })?;
+            let args = if self.service_process_args.is_empty() {
+                vec![
+                    "run-service".into(),
+                    "--service-endpoint".into(),
+                    self.service_endpoint.clone(),
+                ]
+            } else {
+                self.service_process_args.clone()
+            };
+            let config = ServiceProcessConfig::new(&program.to_string_lossy()).with_args(args);
+            #[cfg(feature = "service-auth")]
+            let config = match &service_registration_signer {
+                Some(signer) => config.with_registration_signer(signer.clone()),
+                None => config,
+            };
+            orchestrator.with_process_supervision(config)
+        } else {
+            orchestrator
+        };
+        let mut orchestrator_resources = orchestrator.resources();
 
         let signature_verifier = SawtoothSecp256k1SignatureVerifier::new();
 
-        let (registry, registry_shutdown) = create_registry(
+        #[cfg(feature = "registry-database")]
+        let local_registry = match &store_factory {
+            Some(store_factory) => {
+                let local_registry = store_factory.get_registry_store();
+                import_local_yaml_registry(&self.state_dir, &*local_registry)?;
+                local_registry
+            }
+            None => create_local_yaml_registry(&self.state_dir)?,
+        };
+        #[cfg(not(feature = "registry-database"))]
+        let local_registry = create_local_yaml_registry(&self.state_dir)?;
+
+        let (unified_registry, registry_shutdown) = create_registry(
+            local_registry,
             &self.state_dir,
             &self.registries,
             self.registry_auto_refresh,
             self.registry_forced_refresh,
         )?;
+        // Allowing possibly redundant clone of `unified_registry` since it will be needed again if
+        // the `config-reload` feature is enabled
+        #[allow(clippy::redundant_clone)]
+        let registry: Box<dyn RwRegistry> = Box::new(unified_registry.clone());
+        // The remote registries may be replaced if the configuration is reloaded, so the handle
+        // used to shut them down is shared
+        let registry_shutdown = Arc::new(Mutex::new(registry_shutdown));
+
+        #[cfg(feature = "config-reload")]
+        let reload_peer_connector = peer_connector.clone();
 
         let (admin_service, admin_notification_join) = AdminService::new(
             &self.node_id,
@@ -411,6 +600,20 @@ impl SplinterDaemon {
             StartError::AdminServiceError(format!("unable to create admin service: {}", err))
         })?;
 
+        #[cfg(feature = "admin-service-store")]
+        {
+            if let Some(store_factory) = &store_factory {
+                admin_service
+                    .set_event_store(store_factory.get_admin_service_event_set())
+                    .map_err(|err| {
+                        StartError::AdminServiceError(format!(
+                            "unable to set admin service event store: {}",
+                            err
+                        ))
+                    })?;
+            }
+        }
+
         let node_id = self.node_id.clone();
         let display_name = self.display_name.clone();
         #[cfg(feature = "service-endpoint")]
@@ -439,33 +642,113 @@ impl SplinterDaemon {
                         advertised_endpoints.clone(),
                     )
                 }),
-            )
-            .add_resources(registry.resources())
-            .add_resources(admin_service.resources())
-            .add_resources(orchestrator_resources)
-            .add_resources(circuit_resource_provider.resources());
+            );
 
-        #[cfg(feature = "rest-api-cors")]
+        // The admin, registry and service endpoints, which are protected by API key guards if
+        // Biome is enabled
+        let mut api_resources = registry.resources();
+        api_resources.append(&mut admin_service.resources());
+        api_resources.append(&mut orchestrator_resources);
+        api_resources.append(&mut circuit_resource_provider.resources());
+
+        #[cfg(feature = "circuit-template")]
         {
-            if let Some(list) = &self.whitelist {
+            match LocalTemplateCatalog::new(&[DEFAULT_TEMPLATE_DIR]) {
+                Ok(catalog) => {
+                    let catalog: Box<dyn TemplateCatalog> = Box::new(catalog);
+                    api_resources.append(&mut catalog.resources());
+                }
+                Err(err) => warn!("Circuit templates will not be served: {}", err),
+            }
+        }
+
+        #[cfg(feature = "rest-api-cors")]
+        let cors = match &self.whitelist {
+            Some(list) => {
                 debug!("Whitelisted domains added to CORS");
-                rest_api_builder = rest_api_builder.with_whitelist(list.to_vec());
+                Cors::new(list.to_vec())
             }
+            None => Cors::new_allow_any(),
+        };
+        // Allowing possibly redundant clone of `cors` since it will be needed again if the
+        // `config-reload` feature is enabled
+        #[cfg(feature = "rest-api-cors")]
+        #[allow(clippy::redundant_clone)]
+        {
+            rest_api_builder = rest_api_builder.with_cors(cors.clone());
         }
 
+        // The guard for endpoints that manage the node, which is only available if Biome is enabled
+        #[cfg(all(
+            feature = "biome-authorization",
+            any(feature = "tls-reload", feature = "config-reload")
+        ))]
+        let mut node_manage_guard = None;
+
         #[cfg(feature = "biome")]
         {
             if self.enable_biome {
-                let db_url = self.db_url.clone().ok_or_else(|| {
+                let store_factory = store_factory.as_ref().ok_or_else(|| {
                     StartError::StorageError(
-                        "biome was enabled but the builder failed to require the db URL".into(),
+                        "biome was enabled but the store factory was not created".into(),
                     )
                 })?;
-                let biome_resources = build_biome_routes(db_url)?;
+                let biome_resources = build_biome_routes(
+                    &**store_factory,
+                    #[cfg(feature = "biome-oidc")]
+                    self.biome_oidc_client.take(),
+                )?;
                 rest_api_builder = rest_api_builder.add_resources(biome_resources.resources());
+
+                // Access tokens issued for a revoked session are rejected by every endpoint
+                #[cfg(feature = "biome-sessions")]
+                {
+                    rest_api_builder = rest_api_builder
+                        .add_request_guard(biome_resources.session_revocation_guard());
+                }
+
+                // The CLI does not present credentials, so the guards are only added on request
+                #[cfg(feature = "biome-api-keys")]
+                {
+                    if self.biome_api_key_guards {
+                        api_resources = biome_resources.add_api_key_guards(api_resources);
+                    }
+                }
+
+                #[cfg(all(
+                    feature = "biome-authorization",
+                    any(feature = "tls-reload", feature = "config-reload")
+                ))]
+                {
+                    node_manage_guard =
+                        Some(biome_resources.permission_guard(Permission::ManageNode));
+                }
+
+                #[cfg(feature = "biome-notifications")]
+                {
+                    admin_service
+                        .commands()
+                        .add_event_subscriber(
+                            ALL_EVENT_TYPES,
+                            Box::new(AdminNotificationSubscriber::new(
+                                &self.node_id,
+                                registry.clone_box_as_reader(),
+                                store_factory.get_biome_key_store(),
+                                store_factory.get_biome_notification_store(),
+                            )),
+                        )
+                        .map_err(|err| {
+                            StartError::AdminServiceError(format!(
+                                "unable to add Biome notification subscriber: {}",
+                                err
+                            ))
+                        })?;
+                }
             }
         }
 
+        rest_api_builder = rest_api_builder.add_resources(api_resources);
+
         let mut health_service_processor_join_handle: Option<_> = None;
         #[cfg(feature = "health")]
         {
@@ -484,8 +767,77 @@ impl SplinterDaemon {
             health_service_processor_join_handle.replace(());
         }
 
+        #[cfg(feature = "tls-reload")]
+        {
+            let tls_reload_handles = self.tls_reload_handles.clone();
+            rest_api_builder = rest_api_builder.add_resource(
+                Resource::build("/admin/tls/reload")
+                    .add_request_guard(node_management_guard(
+                        #[cfg(feature = "biome-authorization")]
+                        node_manage_guard.as_ref(),
+                    ))
+                    .add_method(Method::Post, move |_, _| {
+                        routes::reload_tls(&tls_reload_handles)
+                    }),
+            );
+        }
+
+        // Reload the configuration on SIGHUP or when requested through the REST API, applying the
+        // settings that can be changed while the daemon is running
+        #[cfg(feature = "config-reload")]
+        {
+            if let Some(config_reloader) = self.config_reloader.take() {
+                let config_reloader =
+                    Arc::new(Mutex::new(config_reloader.with_targets(ReloadTargets {
+                        peer_connector: reload_peer_connector,
+                        peer_refs,
+                        registry: unified_registry,
+                        registry_shutdown: registry_shutdown.clone(),
+                        heartbeat_interval: heartbeat_interval_handle,
+                        coordinator_timeout: admin_service.coordinator_timeout_handle(),
+                        #[cfg(feature = "rest-api-cors")]
+                        cors,
+                    })));
+
+                reload::listen_for_sighup(config_reloader.clone()).map_err(|err| {
+                    StartError::ConfigReloadError(format!("unable to listen for SIGHUP: {}", err))
+                })?;
+
+                rest_api_builder = rest_api_builder.add_resource(
+                    Resource::build("/admin/config/reload")
+                        .add_request_guard(node_management_guard(
+                            #[cfg(feature = "biome-authorization")]
+                            node_manage_guard.as_ref(),
+                        ))
+                        .add_method(Method::Post, move |_, _| {
+                            routes::reload_config(&config_reloader)
+                        }),
+                );
+            }
+        }
+
         let (rest_api_shutdown_handle, rest_api_join_handle) = rest_api_builder.build()?.run()?;
 
+        // Watch the TLS certificate and key files, if enabled, so that rotated certificates are
+        // used for new connections without restarting the daemon
+        #[cfg(feature = "tls-reload")]
+        let tls_file_watcher =
+            if self.tls_reload_interval > 0 && !self.tls_reload_handles.is_empty() {
+                Some(
+                    TlsFileWatcher::start(
+                        self.tls_reload_handles.clone(),
+                        Duration::from_secs(self.tls_reload_interval),
+                    )
+                    .map_err(|err| StartError::TransportError(err.to_string()))?,
+                )
+            } else {
+                None
+            };
+        #[cfg(feature = "tls-reload")]
+        let tls_file_watcher_shutdown = tls_file_watcher
+            .as_ref()
+            .map(|watcher| watcher.shutdown_signaler());
+
         let (admin_shutdown_handle, service_processor_join_handle) =
             Self::start_admin_service(admin_connection, admin_service, Arc::clone(&running))?;
 
@@ -506,8 +858,17 @@ impl SplinterDaemon {
             }
             circuit_dispatcher_shutdown.shutdown();
             network_dispatcher_shutdown.shutdown();
-            registry_shutdown.shutdown();
+            match registry_shutdown.lock() {
+                Ok(registry_shutdown) => registry_shutdown.shutdown(),
+                Err(_) => error!("Unable to shut down registries: lock poisoned"),
+            }
             interconnect_shutdown.shutdown();
+            #[cfg(feature = "tls-reload")]
+            {
+                if let Some(tls_file_watcher_shutdown) = &tls_file_watcher_shutdown {
+                    tls_file_watcher_shutdown.shutdown();
+                }
+            }
         })
         .expect("Error setting Ctrl-C handler");
 
@@ -536,10 +897,76 @@ impl SplinterDaemon {
         debug!("Shutting down admin service's peer manager notification receiver (complete)");
         connection_manager_shutdown.shutdown();
         connection_manager.await_shutdown();
+        #[cfg(feature = "tls-reload")]
+        {
+            if let Some(tls_file_watcher) = tls_file_watcher {
+                tls_file_watcher.await_shutdown();
+            }
+        }
         self.mesh.shutdown_signaler().shutdown();
         Ok(())
     }
 
+    /// Builds the policy that services connecting to this node must satisfy, if any service auth
+    /// keys or identities are configured.  Returns the policy along with the identities that
+    /// connections must have verified through TLS.
+    ///
+    /// The node's internal services are always authorized.  This is only safe because their
+    /// identities are reserved for the in-process connections; the configured identities may not
+    /// name an internal service.
+    #[cfg(feature = "service-auth")]
+    fn service_authorization_policy<'a>(
+        &self,
+        internal_identities: impl Iterator<Item = &'a str>,
+    ) -> Result<(Option<ServiceAuthorizationPolicy>, Vec<String>), StartError> {
+        if self.service_auth_keys.is_empty() && self.service_auth_identities.is_empty() {
+            return Ok((None, vec![]));
+        }
+
+        let mut policy =
+            ServiceAuthorizationPolicy::new(Box::new(SawtoothSecp256k1SignatureVerifier::new()));
+
+        let internal_identities = internal_identities.collect::<Vec<_>>();
+        for identity in &internal_identities {
+            policy = policy.with_trusted_identity(identity, "*", "*");
+        }
+
+        for key in &self.service_auth_keys {
+            policy = policy.with_trusted_hex_key(key).map_err(|err| {
+                StartError::NetworkError(format!("Invalid service auth key {}: {}", key, err))
+            })?;
+        }
+
+        let mut verified_identities = vec![];
+        for entry in &self.service_auth_identities {
+            let (identity, circuit, service_id) = match entry.splitn(2, '@').collect::<Vec<_>>()[..]
+            {
+                [identity] => (identity, "*", "*"),
+                [identity, service] => match service.splitn(2, "::").collect::<Vec<_>>()[..] {
+                    [circuit, service_id] => (identity, circuit, service_id),
+                    _ => {
+                        return Err(StartError::NetworkError(format!(
+                            "Invalid service auth identity {}: expected \
+                             <identity>@<circuit>::<service_id>",
+                            entry
+                        )))
+                    }
+                },
+                _ => unreachable!(),
+            };
+            if internal_identities.contains(&identity) {
+                return Err(StartError::NetworkError(format!(
+                    "Invalid service auth identity {}: {} is reserved for an internal service",
+                    entry, identity
+                )));
+            }
+            policy = policy.with_trusted_identity(identity, circuit, service_id);
+            verified_identities.push(identity.to_string());
+        }
+
+        Ok((Some(policy), verified_identities))
+    }
+
     fn listen_for_services(
         connection_connector: Connector,
         internal_service_listeners: Vec<Box<dyn Listener>>,
@@ -568,14 +995,25 @@ impl SplinterDaemon {
 
             #[cfg(feature = "service-endpoint")]
             {
+                let endpoint = external_service_listener.endpoint();
                 for connection_result in external_service_listener.incoming() {
+                    // A connection that fails to be accepted, such as one from a local peer whose
+                    // credentials are refused, does not stop the listener
                     let connection = match connection_result {
                         Ok(connection) => connection,
-                        Err(err) => {
-                            return Err(StartError::TransportError(format!(
-                                "Accept Error: {:?}",
-                                err
-                            )));
+                        Err(AcceptError::ProtocolError(msg)) => {
+                            warn!(
+                                "Failed to accept service connection on {}: {}",
+                                endpoint, msg
+                            );
+                            continue;
+                        }
+                        Err(AcceptError::IoError(err)) => {
+                            warn!(
+                                "Failed to accept service connection on {}: {}",
+                                endpoint, err
+                            );
+                            continue;
                         }
                     };
                     debug!(
@@ -688,15 +1126,99 @@ fn start_health_service(
     })?
 }
 
-#[cfg(feature = "biome")]
-fn build_biome_routes(db_url: String) -> Result<BiomeRestResourceManager, StartError> {
-    info!("Adding biome routes");
+#[cfg(any(
+    feature = "admin-service-store",
+    feature = "biome",
+    feature = "registry-database",
+    feature = "routing-table-database"
+))]
+fn create_store_factory(db_url: &str) -> Result<Box<dyn StoreFactory>, StartError> {
     let connection_uri = db_url.parse().map_err(|err| {
         StartError::StorageError(format!("Invalid database URL provided: {}", err))
     })?;
     let store_factory = splinter::store::create_store_factory(connection_uri).map_err(|err| {
         StartError::StorageError(format!("Failed to initialize store factory: {}", err))
     })?;
+    // Bring the database up to date before any store is used, so that the daemon does not depend
+    // on `splinter database migrate` having been run
+    store_factory
+        .run_migrations()
+        .map_err(|err| StartError::StorageError(err.to_string()))?;
+    Ok(store_factory)
+}
+
+/// Imports the nodes of the local YAML registry file, if there is one, into the database-backed
+/// local registry.
+///
+/// Nodes that are already in the database are kept as they are. Once the nodes are imported, the
+/// file is renamed to `local_registry.yaml.imported` so that it is not imported again. Any failure
+/// stops the daemon, rather than letting it start without the nodes it had before.
+#[cfg(feature = "registry-database")]
+fn import_local_yaml_registry(
+    state_dir: &str,
+    registry: &dyn RwRegistry,
+) -> Result<(), StartError> {
+    let yaml_path = Path::new(state_dir).join("local_registry.yaml");
+    if !yaml_path.exists() {
+        return Ok(());
+    }
+
+    let yaml_registry = LocalYamlRegistry::new(
+        yaml_path
+            .to_str()
+            .expect("path built from &str cannot be invalid"),
+    )
+    .map_err(|err| {
+        StartError::RegistryError(format!(
+            "Failed to read local registry file {}: {}",
+            yaml_path.display(),
+            err
+        ))
+    })?;
+    let nodes = yaml_registry
+        .list_nodes(&[])
+        .map_err(|err| {
+            StartError::RegistryError(format!("Failed to list local registry nodes: {}", err))
+        })?
+        .collect::<Vec<_>>();
+
+    let mut imported = 0;
+    for node in nodes {
+        let exists = registry.has_node(&node.identity).map_err(|err| {
+            StartError::RegistryError(format!("Failed to check local registry node: {}", err))
+        })?;
+        if !exists {
+            registry.insert_node(node).map_err(|err| {
+                StartError::RegistryError(format!("Failed to import local registry node: {}", err))
+            })?;
+            imported += 1;
+        }
+    }
+
+    let imported_path = Path::new(state_dir).join("local_registry.yaml.imported");
+    std::fs::rename(&yaml_path, &imported_path).map_err(|err| {
+        StartError::RegistryError(format!(
+            "Imported the local registry file {}, but failed to rename it: {}",
+            yaml_path.display(),
+            err
+        ))
+    })?;
+    info!(
+        "Imported {} nodes from {} into the database registry; the file was renamed to {}",
+        imported,
+        yaml_path.display(),
+        imported_path.display()
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "biome")]
+fn build_biome_routes(
+    store_factory: &dyn StoreFactory,
+    #[cfg(feature = "biome-oidc")] oidc_client: Option<OidcClient>,
+) -> Result<BiomeRestResourceManager, StartError> {
+    info!("Adding biome routes");
     let mut biome_rest_provider_builder: BiomeRestResourceManagerBuilder = Default::default();
     biome_rest_provider_builder =
         biome_rest_provider_builder.with_user_store(store_factory.get_biome_user_store());
@@ -712,6 +1234,27 @@ fn build_biome_routes(db_url: String) -> Result<BiomeRestResourceManager, StartE
         biome_rest_provider_builder =
             biome_rest_provider_builder.with_key_store(store_factory.get_biome_key_store())
     }
+    #[cfg(feature = "biome-notifications")]
+    {
+        biome_rest_provider_builder = biome_rest_provider_builder
+            .with_notification_store(store_factory.get_biome_notification_store())
+    }
+    #[cfg(feature = "biome-authorization")]
+    {
+        biome_rest_provider_builder = biome_rest_provider_builder
+            .with_role_store(store_factory.get_biome_role_store())
+            .with_group_store(store_factory.get_biome_group_store())
+    }
+    #[cfg(feature = "biome-oidc")]
+    {
+        if let Some(oidc_client) = oidc_client {
+            info!(
+                "Adding OpenID Connect login through {}",
+                oidc_client.issuer_url()
+            );
+            biome_rest_provider_builder = biome_rest_provider_builder.with_oidc_client(oidc_client)
+        }
+    }
     let biome_rest_provider = biome_rest_provider_builder.build().map_err(|err| {
         StartError::RestApiError(format!("Unable to build Biome REST routes: {}", err))
     })?;
@@ -734,6 +1277,10 @@ pub struct SplinterDaemonBuilder {
     db_url: Option<String>,
     #[cfg(feature = "biome")]
     enable_biome: bool,
+    #[cfg(feature = "biome-api-keys")]
+    biome_api_key_guards: bool,
+    #[cfg(feature = "biome-oidc")]
+    biome_oidc_client: Option<OidcClient>,
     registries: Vec<String>,
     registry_auto_refresh: Option<u64>,
     registry_forced_refresh: Option<u64>,
@@ -743,6 +1290,22 @@ pub struct SplinterDaemonBuilder {
     #[cfg(feature = "rest-api-cors")]
     whitelist: Option<Vec<String>>,
     strict_ref_counts: Option<bool>,
+    #[cfg(feature = "service-supervisor")]
+    supervise_services: bool,
+    #[cfg(feature = "service-supervisor")]
+    service_process_args: Vec<String>,
+    #[cfg(feature = "service-auth")]
+    service_auth_keys: Vec<String>,
+    #[cfg(feature = "service-auth")]
+    service_auth_identities: Vec<String>,
+    #[cfg(feature = "routing-table-database")]
+    routing_table: Option<String>,
+    #[cfg(feature = "tls-reload")]
+    tls_reload_handles: Vec<TlsReloadHandle>,
+    #[cfg(feature = "tls-reload")]
+    tls_reload_interval: Option<u64>,
+    #[cfg(feature = "config-reload")]
+    config_reloader: Option<ConfigReloader>,
 }
 
 impl SplinterDaemonBuilder {
@@ -803,6 +1366,69 @@ impl SplinterDaemonBuilder {
         self
     }
 
+    /// Requires a Biome access token, or an API key with the matching scope, for the admin,
+    /// registry and scabbard endpoints
+    #[cfg(feature = "biome-api-keys")]
+    pub fn enable_biome_api_key_guards(mut self, enabled: bool) -> Self {
+        self.biome_api_key_guards = enabled;
+        self
+    }
+
+    #[cfg(feature = "biome-oidc")]
+    pub fn with_biome_oidc_client(mut self, client: Option<OidcClient>) -> Self {
+        self.biome_oidc_client = client;
+        self
+    }
+
+    #[cfg(feature = "service-supervisor")]
+    pub fn with_supervise_services(mut self, supervise_services: bool) -> Self {
+        self.supervise_services = supervise_services;
+        self
+    }
+
+    /// Sets the arguments that the splinterd executable is run with to run a supervised service.
+    #[cfg(feature = "service-supervisor")]
+    pub fn with_service_process_args(mut self, service_process_args: Vec<String>) -> Self {
+        self.service_process_args = service_process_args;
+        self
+    }
+
+    #[cfg(feature = "service-auth")]
+    pub fn with_service_auth_keys(mut self, service_auth_keys: Vec<String>) -> Self {
+        self.service_auth_keys = service_auth_keys;
+        self
+    }
+
+    #[cfg(feature = "service-auth")]
+    pub fn with_service_auth_identities(mut self, service_auth_identities: Vec<String>) -> Self {
+        self.service_auth_identities = service_auth_identities;
+        self
+    }
+
+    #[cfg(feature = "routing-table-database")]
+    pub fn with_routing_table(mut self, routing_table: String) -> Self {
+        self.routing_table = Some(routing_table);
+        self
+    }
+
+    #[cfg(feature = "tls-reload")]
+    pub fn with_tls_reload_handles(mut self, tls_reload_handles: Vec<TlsReloadHandle>) -> Self {
+        self.tls_reload_handles = tls_reload_handles;
+        self
+    }
+
+    #[cfg(feature = "tls-reload")]
+    pub fn with_tls_reload_interval(mut self, value: u64) -> Self {
+        self.tls_reload_interval = Some(value);
+        self
+    }
+
+    #[cfg(feature = "config-reload")]
+    pub fn with_config_reloader(mut self, config_reloader: ConfigReloader) -> Self {
+        self.config_reloader = Some(config_reloader);
+        self
+    }
+
     pub fn with_registries(mut self, registries: Vec<String>) -> Self {
         self.registries = registries;
         self
@@ -853,7 +1479,7 @@ impl SplinterDaemonBuilder {
             CreateError::MissingRequiredField("Missing field: node_id".to_string())
         })?;
 
-        let mesh = Mesh::new(512, 128);
+        let mesh = Mesh::new_with_classifier(512, 128, network_message_priority);
 
         let state_dir = self.state_dir.ok_or_else(|| {
             CreateError::MissingRequiredField("Missing field: state_dir".to_string())
@@ -896,6 +1522,27 @@ impl SplinterDaemonBuilder {
             }
         }
 
+        #[cfg(feature = "routing-table-database")]
+        let routing_table = self.routing_table.unwrap_or_else(|| String::from("memory"));
+        #[cfg(feature = "routing-table-database")]
+        {
+            match routing_table.as_str() {
+                "memory" => (),
+                "database" if db_url.is_some() => (),
+                "database" => {
+                    return Err(CreateError::MissingRequiredField(
+                        "db_url is required to keep the routing table in the database.".to_string(),
+                    ))
+                }
+                _ => {
+                    return Err(CreateError::InvalidArgument(format!(
+                        "routing_table must be \"memory\" or \"database\", not {}",
+                        routing_table
+                    )))
+                }
+            }
+        }
+
         let registry_auto_refresh = self.registry_auto_refresh.ok_or_else(|| {
             CreateError::MissingRequiredField("Missing field: registry_auto_refresh".to_string())
         })?;
@@ -927,6 +1574,10 @@ impl SplinterDaemonBuilder {
             db_url,
             #[cfg(feature = "biome")]
             enable_biome: self.enable_biome,
+            #[cfg(feature = "biome-api-keys")]
+            biome_api_key_guards: self.biome_api_key_guards,
+            #[cfg(feature = "biome-oidc")]
+            biome_oidc_client: self.biome_oidc_client,
             registries: self.registries,
             registry_auto_refresh,
             registry_forced_refresh,
@@ -936,21 +1587,136 @@ impl SplinterDaemonBuilder {
             whitelist: self.whitelist,
             heartbeat,
             strict_ref_counts,
+            #[cfg(feature = "service-supervisor")]
+            supervise_services: self.supervise_services,
+            #[cfg(feature = "service-supervisor")]
+            service_process_args: self.service_process_args,
+            #[cfg(feature = "service-auth")]
+            service_auth_keys: self.service_auth_keys,
+            #[cfg(feature = "service-auth")]
+            service_auth_identities: self.service_auth_identities,
+            #[cfg(feature = "routing-table-database")]
+            routing_table,
+            #[cfg(feature = "tls-reload")]
+            tls_reload_handles: self.tls_reload_handles,
+            #[cfg(feature = "tls-reload")]
+            tls_reload_interval: self.tls_reload_interval.unwrap_or(0),
+            #[cfg(feature = "config-reload")]
+            config_reloader: self.config_reloader,
         })
     }
 }
 
+/// Returns the guard for an endpoint that manages the node.
+///
+/// If Biome authorization is enabled, the endpoint is served to users who have been granted the
+/// node:manage permission. Otherwise it is only served to clients on the local host.
+#[cfg(any(feature = "tls-reload", feature = "config-reload"))]
+fn node_management_guard(
+    #[cfg(feature = "biome-authorization")] permission_guard: Option<&PermissionGuard>,
+) -> Box<dyn RequestGuard> {
+    #[cfg(feature = "biome-authorization")]
+    {
+        if let Some(permission_guard) = permission_guard {
+            return Box::new(permission_guard.clone());
+        }
+    }
+    Box::new(LocalRequestGuard)
+}
+
+/// Creates the routing table for the given storage, which is either "memory" or "database".
+///
+/// An in-memory routing table is loaded from the circuit directory on every start. A database
+/// routing table is the source of truth for routing, so the circuit directory is only migrated
+/// into it the first time it is used.
+#[cfg(feature = "routing-table-database")]
+fn load_routing_table(
+    routing_table: &str,
+    store_factory: Option<&dyn StoreFactory>,
+    circuit_directory: &CircuitDirectory,
+) -> Result<(Box<dyn RoutingTableReader>, Box<dyn RoutingTableWriter>), StartError> {
+    let (reader, mut writer): (Box<dyn RoutingTableReader>, Box<dyn RoutingTableWriter>) =
+        match (routing_table, store_factory) {
+            ("database", Some(store_factory)) => (
+                store_factory.get_routing_table_reader(),
+                store_factory.get_routing_table_writer(),
+            ),
+            ("database", None) => {
+                return Err(StartError::StorageError(
+                    "the routing table is kept in the database, but no database is configured"
+                        .into(),
+                ))
+            }
+            _ => {
+                let table = routing::memory::RoutingTable::default();
+                (Box::new(table.clone()), Box::new(table))
+            }
+        };
+
+    if routing_table == "database" {
+        // Circuits are written through to both the circuit directory and the routing table, so
+        // the directory has already been migrated if any of its circuits is in the table
+        let migrated = match circuit_directory.circuits().keys().next() {
+            Some(circuit_id) => reader
+                .fetch_circuit(circuit_id)
+                .map_err(|err| {
+                    StartError::StorageError(format!("Unable to read routing table: {}", err))
+                })?
+                .is_some(),
+            None => true,
+        };
+        if migrated {
+            info!("Using the database routing table");
+            return Ok((reader, writer));
+        }
+    }
+
+    let nodes = circuit_directory
+        .nodes()
+        .values()
+        .map(routing::CircuitNode::from)
+        .collect();
+    writer.add_nodes(nodes).map_err(|err| {
+        StartError::StorageError(format!("Unable to add nodes to routing table: {}", err))
+    })?;
+
+    let circuits = circuit_directory
+        .circuits()
+        .values()
+        .map(routing::Circuit::from)
+        .collect::<Vec<_>>();
+    let circuit_count = circuits.len();
+    writer.add_circuits(circuits).map_err(|err| {
+        StartError::StorageError(format!("Unable to add circuits to routing table: {}", err))
+    })?;
+
+    if routing_table == "database" {
+        info!(
+            "Migrated {} circuits from the circuit directory into the database routing table",
+            circuit_count
+        );
+    } else {
+        info!(
+            "Loaded {} circuits into the in-memory routing table",
+            circuit_count
+        );
+    }
+
+    Ok((reader, writer))
+}
+
 fn set_up_network_dispatcher(
     network_sender: NetworkMessageSender,
     node_id: &str,
     circuit_sender: DispatchMessageSender<CircuitMessageType>,
+    connector: Connector,
 ) -> Dispatcher<NetworkMessageType> {
     let mut dispatcher = Dispatcher::<NetworkMessageType>::new(Box::new(network_sender));
 
     let network_echo_handler = NetworkEchoHandler::new(node_id.to_string());
     dispatcher.set_handler(Box::new(network_echo_handler));
 
-    let network_heartbeat_handler = NetworkHeartbeatHandler::new();
+    let network_heartbeat_handler = NetworkHeartbeatHandler::new().with_connector(connector);
     // do not add auth guard
     dispatcher.set_handler(Box::new(network_heartbeat_handler));
 
@@ -965,11 +1731,19 @@ fn set_up_circuit_dispatcher(
     node_id: &str,
     endpoints: &[String],
     state: SplinterState,
+    #[cfg(feature = "service-auth")] service_authorization_policy: Option<
+        ServiceAuthorizationPolicy,
+    >,
 ) -> Dispatcher<CircuitMessageType> {
     let mut dispatcher = Dispatcher::<CircuitMessageType>::new(Box::new(network_sender));
 
     let service_connect_request_handler =
         ServiceConnectRequestHandler::new(node_id.to_string(), endpoints.to_vec(), state.clone());
+    #[cfg(feature = "service-auth")]
+    let service_connect_request_handler = match service_authorization_policy {
+        Some(policy) => service_connect_request_handler.with_authorization_policy(policy),
+        None => service_connect_request_handler,
+    };
     dispatcher.set_handler(Box::new(service_connect_request_handler));
 
     let service_disconnect_request_handler = ServiceDisconnectRequestHandler::new(state.clone());
@@ -990,13 +1764,26 @@ fn set_up_circuit_dispatcher(
 }
 
 fn create_registry(
+    local_registry: Box<dyn RwRegistry>,
     state_dir: &str,
     registries: &[String],
     auto_refresh_interval: u64,
     forced_refresh_interval: u64,
-) -> Result<(Box<dyn RwRegistry>, RegistryShutdownHandle), StartError> {
-    let mut registry_shutdown_handle = RegistryShutdownHandle::new();
+) -> Result<(UnifiedRegistry, RegistryShutdownHandle), StartError> {
+    let (read_only_registries, registry_shutdown_handle) = create_read_only_registries(
+        state_dir,
+        registries,
+        auto_refresh_interval,
+        forced_refresh_interval,
+    );
 
+    let unified_registry = UnifiedRegistry::new(local_registry, read_only_registries);
+
+    Ok((unified_registry, registry_shutdown_handle))
+}
+
+/// Creates the local registry backed by a YAML file in the state directory.
+fn create_local_yaml_registry(state_dir: &str) -> Result<Box<dyn RwRegistry>, StartError> {
     let local_registry_path = Path::new(state_dir)
         .join("local_registry.yaml")
         .to_str()
@@ -1006,12 +1793,25 @@ fn create_registry(
         "Creating local registry with registry file: {:?}",
         local_registry_path
     );
-    let local_registry = Box::new(LocalYamlRegistry::new(&local_registry_path).map_err(|err| {
+    let local_registry = LocalYamlRegistry::new(&local_registry_path).map_err(|err| {
         StartError::RegistryError(format!(
             "Failed to initialize local LocalYamlRegistry: {}",
             err
         ))
-    })?);
+    })?;
+
+    Ok(Box::new(local_registry))
+}
+
+/// Creates the read-only registries from the registry arguments, along with the handle used to
+/// shut down any remote registries. Registries that fail to be created are logged and skipped.
+pub fn create_read_only_registries(
+    state_dir: &str,
+    registries: &[String],
+    auto_refresh_interval: u64,
+    forced_refresh_interval: u64,
+) -> (Vec<Box<dyn RegistryReader>>, RegistryShutdownHandle) {
+    let mut registry_shutdown_handle = RegistryShutdownHandle::new();
 
     let read_only_registries = registries
         .iter()
@@ -1079,9 +1879,7 @@ fn create_registry(
         })
         .collect();
 
-    let unified_registry = Box::new(UnifiedRegistry::new(local_registry, read_only_registries));
-
-    Ok((unified_registry, registry_shutdown_handle))
+    (read_only_registries, registry_shutdown_handle)
 }
 
 fn parse_registry_arg(registry: &str) -> Result<(&str, &str), &str> {
@@ -1094,7 +1892,7 @@ fn parse_registry_arg(registry: &str) -> Result<(&str, &str), &str> {
 }
 
 #[derive(Default)]
-struct RegistryShutdownHandle {
+pub struct RegistryShutdownHandle {
     remote_yaml_shutdown_handles: Vec<RemoteYamlShutdownHandle>,
 }
 
@@ -1107,7 +1905,7 @@ impl RegistryShutdownHandle {
         self.remote_yaml_shutdown_handles.push(handle);
     }
 
-    fn shutdown(&self) {
+    pub fn shutdown(&self) {
         self.remote_yaml_shutdown_handles
             .iter()
             .for_each(|handle| handle.shutdown());
@@ -1117,6 +1915,7 @@ impl RegistryShutdownHandle {
 #[derive(Debug)]
 pub enum CreateError {
     MissingRequiredField(String),
+    InvalidArgument(String),
 }
 
 impl Error for CreateError {}
@@ -1125,6 +1924,7 @@ impl fmt::Display for CreateError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             CreateError::MissingRequiredField(msg) => write!(f, "missing required field: {}", msg),
+            CreateError::InvalidArgument(msg) => write!(f, "invalid argument: {}", msg),
         }
     }
 }
@@ -1142,6 +1942,8 @@ pub enum StartError {
     HealthServiceError(String),
     OrchestratorError(String),
     StateError(String),
+    #[cfg(feature = "config-reload")]
+    ConfigReloadError(String),
 }
 
 impl Error for StartError {}
@@ -1166,6 +1968,10 @@ impl fmt::Display for StartError {
                 write!(f, "the orchestrator encountered an error: {}", msg)
             }
             StartError::StateError(msg) => write!(f, "{}", msg),
+            #[cfg(feature = "config-reload")]
+            StartError::ConfigReloadError(msg) => {
+                write!(f, "unable to set up configuration reloading: {}", msg)
+            }
         }
     }
 }
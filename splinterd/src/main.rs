@@ -20,12 +20,20 @@ extern crate serde_derive;
 extern crate clap;
 
 mod config;
+#[cfg(feature = "config-command")]
+mod config_command;
 mod daemon;
 mod error;
+#[cfg(feature = "config-reload")]
+mod reload;
 mod routes;
+#[cfg(feature = "service-supervisor")]
+mod service_process;
 mod transport;
 
-use flexi_logger::{style, DeferredNow, LogSpecBuilder, Logger};
+#[cfg(feature = "config-reload")]
+use flexi_logger::ReconfigurationHandle;
+use flexi_logger::{style, DeferredNow, LogSpecBuilder, LogSpecification, Logger};
 use log::Record;
 use rand::{thread_rng, Rng};
 
@@ -34,6 +42,8 @@ use crate::config::{
     EnvPartialConfigBuilder, PartialConfigBuilder, TomlPartialConfigBuilder,
 };
 use crate::daemon::SplinterDaemonBuilder;
+#[cfg(feature = "config-reload")]
+use crate::reload::ConfigReloader;
 use clap::{clap_app, crate_version};
 use clap::{Arg, ArgMatches};
 
@@ -75,6 +85,20 @@ fn create_config(_toml_path: Option<&str>, _matches: ArgMatches) -> Result<Confi
         .map_err(|e| UserError::MissingArgument(e.to_string()))
 }
 
+// Returns the provided config file, or the default location if no file was provided, as long as
+// the file exists.
+fn find_config_file<'a>(matches: &'a ArgMatches) -> Option<&'a str> {
+    let config_file = matches
+        .value_of("config")
+        .unwrap_or("/etc/splinter/splinterd.toml");
+
+    if Path::new(&config_file).is_file() {
+        Some(config_file)
+    } else {
+        None
+    }
+}
+
 // Checks whether there is a saved node_id file. If there is, the config node_id must match
 // the node_id in the file, otherwise we will return an error.
 fn find_node_id(config: &Config) -> Result<String, UserError> {
@@ -143,6 +167,46 @@ fn find_node_id(config: &Config) -> Result<String, UserError> {
 }
 
 // format for logs
+/// Builds the log specification for the given default log level.
+/// Builds the client for the OpenID Connect provider that Biome users may log in through, if one
+/// is configured.
+#[cfg(feature = "biome-oidc")]
+fn build_biome_oidc_client(
+    config: &Config,
+) -> Result<Option<splinter::biome::oidc::OidcClient>, UserError> {
+    let issuer_url = match config.biome_oidc_issuer_url() {
+        Some(issuer_url) => issuer_url,
+        None => return Ok(None),
+    };
+
+    let client_id = config.biome_oidc_client_id().ok_or_else(|| {
+        UserError::MissingArgument("biome_oidc_client_id is required for OpenID Connect".into())
+    })?;
+    let client_secret = config.biome_oidc_client_secret().ok_or_else(|| {
+        UserError::MissingArgument("biome_oidc_client_secret is required for OpenID Connect".into())
+    })?;
+    let redirect_url = config.biome_oidc_redirect_url().ok_or_else(|| {
+        UserError::MissingArgument("biome_oidc_redirect_url is required for OpenID Connect".into())
+    })?;
+
+    splinter::biome::oidc::OidcClientBuilder::new()
+        .with_issuer_url(issuer_url)
+        .with_client_id(client_id)
+        .with_client_secret(client_secret)
+        .with_redirect_url(redirect_url)
+        .build()
+        .map(Some)
+        .map_err(|err| UserError::InvalidArgument(format!("OpenID Connect: {}", err)))
+}
+
+fn log_spec(log_level: log::LevelFilter) -> LogSpecification {
+    let mut log_spec_builder = LogSpecBuilder::new();
+    log_spec_builder.default(log_level);
+    log_spec_builder.module("hyper", log::LevelFilter::Warn);
+    log_spec_builder.module("tokio", log::LevelFilter::Warn);
+    log_spec_builder.build()
+}
+
 pub fn log_format(
     w: &mut dyn std::io::Write,
     now: &mut DeferredNow,
@@ -308,7 +372,7 @@ fn main() {
     let app = app.arg(
         Arg::with_name("database")
             .long("database")
-            .long_help("DB connection URL")
+            .long_help("DB connection URL, either postgres://... or sqlite://<path>")
             .takes_value(true),
     );
 
@@ -319,6 +383,113 @@ fn main() {
             .long_help("Enable the biome subsystem"),
     );
 
+    #[cfg(feature = "biome-api-keys")]
+    let app = app.arg(
+        Arg::with_name("biome_api_key_guards")
+            .long("biome-api-key-guards")
+            .long_help(
+                "Require a Biome access token, or an API key with the matching scope, for the \
+                 admin, registry and scabbard endpoints; clients such as the splinter CLI must \
+                 then be able to present one",
+            ),
+    );
+
+    #[cfg(feature = "biome-oidc")]
+    let app = app
+        .arg(
+            Arg::with_name("biome_oidc_issuer_url")
+                .long("biome-oidc-issuer-url")
+                .takes_value(true)
+                .long_help(
+                    "URL of an OpenID Connect provider that Biome users may log in through; the \
+                     client secret must be set in the config file",
+                ),
+        )
+        .arg(
+            Arg::with_name("biome_oidc_client_id")
+                .long("biome-oidc-client-id")
+                .takes_value(true)
+                .help("Client ID the node is registered with at the OpenID Connect provider"),
+        )
+        .arg(
+            Arg::with_name("biome_oidc_redirect_url")
+                .long("biome-oidc-redirect-url")
+                .takes_value(true)
+                .long_help(
+                    "URL the OpenID Connect provider redirects to after a login; must route to \
+                     /biome/oidc/callback on this node's REST API",
+                ),
+        );
+
+    #[cfg(feature = "service-supervisor")]
+    let app = app
+        .arg(
+            Arg::with_name("supervise_services")
+                .long("supervise-services")
+                .long_help(
+                    "Run each circuit service in a child process that is restarted if it exits",
+                ),
+        )
+        .subcommand(service_process::run_service_subcommand());
+
+    #[cfg(feature = "service-auth")]
+    let app = app
+        .arg(
+            Arg::with_name("service_auth_keys")
+                .long("service-auth-key")
+                .multiple(true)
+                .takes_value(true)
+                .help("Public key trusted to sign service registration tokens"),
+        )
+        .arg(
+            Arg::with_name("service_auth_identities")
+                .long("service-auth-identity")
+                .multiple(true)
+                .takes_value(true)
+                .long_help(
+                    "TLS identity trusted to connect services, as <identity> for any service \
+                     or <identity>@<circuit>::<service_id> for a single service",
+                ),
+        );
+
+    #[cfg(feature = "routing-table-database")]
+    let app = app.arg(
+        Arg::with_name("routing_table")
+            .long("routing-table")
+            .takes_value(true)
+            .long_help(
+                "Storage for the circuit routing table: \"memory\" or \"database\", which keeps \
+                 it in the database given by --database",
+            ),
+    );
+
+    #[cfg(feature = "tls-reload")]
+    let app = app.arg(
+        Arg::with_name("tls_reload_interval")
+            .long("tls-reload-interval")
+            .takes_value(true)
+            .long_help(
+                "How often the TLS certificate and key files are checked for changes, in \
+                 seconds; defaults to 60 seconds, 0 means off",
+            ),
+    );
+
+    #[cfg(feature = "tls-revocation")]
+    let app = app
+        .arg(
+            Arg::with_name("tls_crl_file")
+                .long("tls-crl-file")
+                .takes_value(true)
+                .help("File containing a PEM-encoded certificate revocation list from the CA"),
+        )
+        .arg(
+            Arg::with_name("tls_denied_fingerprints")
+                .long("tls-deny-fingerprint")
+                .multiple(true)
+                .takes_value(true)
+                .help("SHA-256 fingerprint of a peer certificate to reject"),
+        );
+
     #[cfg(feature = "rest-api-cors")]
     let app = app.arg(
         Arg::with_name("whitelist")
@@ -329,6 +500,30 @@ fn main() {
             .help("Whitelisted domains"),
     );
 
+    #[cfg(feature = "config-command")]
+    let app = app.subcommand(
+        clap::SubCommand::with_name("config")
+            .about("Inspect the configuration without starting the daemon")
+            .setting(clap::AppSettings::SubcommandRequiredElseHelp)
+            .subcommand(
+                clap::SubCommand::with_name("check")
+                    .about("Validate the configuration and report any problems found"),
+            )
+            .subcommand(
+                clap::SubCommand::with_name("show")
+                    .about("Print the effective configuration and the source of each value")
+                    .arg(
+                        Arg::with_name("format")
+                            .long("format")
+                            .short("f")
+                            .takes_value(true)
+                            .possible_values(&["toml", "json"])
+                            .default_value("toml")
+                            .help("Output format"),
+                    ),
+            ),
+    );
+
     let matches = app.get_matches();
 
     let log_level = match matches.occurrences_of("verbose") {
@@ -338,37 +533,56 @@ fn main() {
         _ => log::LevelFilter::Trace,
     };
 
-    let mut log_spec_builder = LogSpecBuilder::new();
-    log_spec_builder.default(log_level);
-    log_spec_builder.module("hyper", log::LevelFilter::Warn);
-    log_spec_builder.module("tokio", log::LevelFilter::Warn);
-
-    Logger::with(log_spec_builder.build())
+    #[cfg_attr(not(feature = "config-reload"), allow(unused_variables))]
+    let log_handle = Logger::with(log_spec(log_level))
         .format(log_format)
         .log_target(flexi_logger::LogTarget::StdOut)
         .start()
         .expect("Failed to create logger");
 
-    if let Err(err) = start_daemon(matches) {
+    #[cfg(feature = "service-supervisor")]
+    {
+        if let Some(run_service_matches) = matches.subcommand_matches("run-service") {
+            if let Err(err) = service_process::run_service(run_service_matches) {
+                error!("Service process failed, {}", err);
+                std::process::exit(1);
+            }
+            return;
+        }
+    }
+
+    #[cfg(feature = "config-command")]
+    {
+        if let Some(config_matches) = matches.subcommand_matches("config") {
+            if let Err(err) = config_command::run_config_command(&matches, config_matches) {
+                error!("Failed to inspect configuration, {}", err);
+                std::process::exit(1);
+            }
+            return;
+        }
+    }
+
+    if let Err(err) = start_daemon(
+        matches,
+        #[cfg(feature = "config-reload")]
+        log_handle,
+    ) {
         error!("Failed to start daemon, {}", err);
         std::process::exit(1);
     }
 }
 
-fn start_daemon(matches: ArgMatches) -> Result<(), UserError> {
-    // get provided config file or search default location
-    let config_file = matches
-        .value_of("config")
-        .unwrap_or("/etc/splinter/splinterd.toml");
-
-    let config_file_path = if Path::new(&config_file).is_file() {
-        Some(config_file)
-    } else {
-        None
-    };
+fn start_daemon(
+    matches: ArgMatches<'static>,
+    #[cfg(feature = "config-reload")] mut log_handle: ReconfigurationHandle,
+) -> Result<(), UserError> {
+    let config_file_path = find_config_file(&matches);
 
     let config = create_config(config_file_path, matches.clone())?;
 
+    #[cfg(feature = "config-reload")]
+    log_handle.set_new_spec(log_spec(reload::parse_log_level(config.log_level())?));
+
     if config.no_tls() {
         for network_endpoint in config.network_endpoints() {
             if network_endpoint.starts_with("tcps://") {
@@ -380,7 +594,8 @@ fn start_daemon(matches: ArgMatches) -> Result<(), UserError> {
         }
     }
 
-    let transport = build_transport(&config)?;
+    #[cfg_attr(not(feature = "tls-reload"), allow(unused_variables))]
+    let (transport, tls_reload_handles) = build_transport(&config)?;
 
     let rest_api_endpoint = config.rest_api_endpoint();
 
@@ -432,7 +647,7 @@ fn start_daemon(matches: ArgMatches) -> Result<(), UserError> {
 
     #[cfg(feature = "database")]
     {
-        daemon_builder = daemon_builder.with_db_url(Some(String::from(db_url)));
+        daemon_builder = daemon_builder.with_db_url(db_url.map(String::from));
     }
 
     #[cfg(feature = "biome")]
@@ -440,11 +655,78 @@ fn start_daemon(matches: ArgMatches) -> Result<(), UserError> {
         daemon_builder = daemon_builder.enable_biome(config.enable_biome());
     }
 
+    #[cfg(feature = "biome-api-keys")]
+    {
+        daemon_builder = daemon_builder.enable_biome_api_key_guards(config.biome_api_key_guards());
+    }
+
+    #[cfg(feature = "biome-oidc")]
+    {
+        daemon_builder = daemon_builder.with_biome_oidc_client(build_biome_oidc_client(&config)?);
+    }
+
+    #[cfg(feature = "service-supervisor")]
+    {
+        daemon_builder = daemon_builder
+            .with_supervise_services(config.supervise_services())
+            .with_service_process_args(service_process::run_service_args(
+                &config,
+                config.service_endpoint(),
+            ));
+    }
+
+    #[cfg(feature = "service-auth")]
+    {
+        let service_auth_identities = config
+            .service_auth_identities()
+            .map(ToOwned::to_owned)
+            .unwrap_or_default();
+        if !service_auth_identities.is_empty() && config.tls_insecure() {
+            warn!(
+                "Service auth identities require verified TLS client certificates; services will \
+                 not be authorized by identity while TLS is insecure"
+            );
+        }
+        daemon_builder = daemon_builder
+            .with_service_auth_keys(
+                config
+                    .service_auth_keys()
+                    .map(ToOwned::to_owned)
+                    .unwrap_or_default(),
+            )
+            .with_service_auth_identities(service_auth_identities);
+    }
+
+    #[cfg(feature = "routing-table-database")]
+    {
+        daemon_builder = daemon_builder.with_routing_table(String::from(config.routing_table()));
+    }
+
+    #[cfg(feature = "tls-reload")]
+    {
+        daemon_builder = daemon_builder
+            .with_tls_reload_handles(tls_reload_handles)
+            .with_tls_reload_interval(config.tls_reload_interval());
+    }
+
     #[cfg(feature = "rest-api-cors")]
     {
         daemon_builder = daemon_builder.with_whitelist(config.whitelist().map(ToOwned::to_owned));
     }
 
+    #[cfg(feature = "config-reload")]
+    {
+        let reload_config_file_path = config_file_path.map(String::from);
+        let reload_matches = matches.clone();
+        daemon_builder = daemon_builder.with_config_reloader(ConfigReloader::new(
+            Box::new(move || {
+                create_config(reload_config_file_path.as_deref(), reload_matches.clone())
+            }),
+            log_handle,
+            config.clone(),
+        ));
+    }
+
     let mut node = daemon_builder.build().map_err(|err| {
         UserError::daemon_err_with_source("unable to build the Splinter daemon", Box::new(err))
     })?;
@@ -0,0 +1,300 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Live reloading of the daemon's configuration.
+//!
+//! The configuration is re-read when the daemon receives `SIGHUP`, or when a client calls the
+//! `POST /admin/config/reload` endpoint. Settings that can be changed safely while the daemon is
+//! running are applied in place; changes to any other setting are reported back as requiring a
+//! restart.
+
+use std::collections::HashMap;
+use std::io;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use flexi_logger::ReconfigurationHandle;
+use signal_hook::{iterator::Signals, SIGHUP};
+use splinter::consensus::two_phase::CoordinatorTimeoutHandle;
+use splinter::network::connection_manager::IntervalHandle;
+use splinter::peer::{EndpointPeerRef, PeerManagerConnector};
+use splinter::registry::UnifiedRegistry;
+#[cfg(feature = "rest-api-cors")]
+use splinter::rest_api::cors::Cors;
+
+use crate::config::Config;
+use crate::daemon::{create_read_only_registries, RegistryShutdownHandle};
+use crate::error::UserError;
+
+/// The settings that are applied in place when the configuration is reloaded.
+const RELOADABLE_SETTINGS: &[&str] = &[
+    "peers",
+    "registries",
+    "registry_auto_refresh",
+    "registry_forced_refresh",
+    "heartbeat",
+    "admin_timeout",
+    "whitelist",
+    "log_level",
+];
+
+/// Loads the daemon's configuration from its sources.
+pub type ConfigLoader = Box<dyn Fn() -> Result<Config, UserError> + Send>;
+
+/// The result of reloading the configuration.
+#[derive(Debug, Serialize)]
+pub struct ReloadReport {
+    /// The settings whose new values were applied
+    applied: Vec<String>,
+    /// The settings that differ from the values the daemon was started with, which only take
+    /// effect when the daemon is restarted
+    restart_required: Vec<String>,
+    /// The changed settings that are not supported by this build of the daemon, and so have no
+    /// effect
+    ignored: Vec<String>,
+}
+
+/// The running components that reloadable settings are applied to.
+pub struct ReloadTargets {
+    pub peer_connector: PeerManagerConnector,
+    /// The peer refs for the configured peers, by endpoint
+    pub peer_refs: HashMap<String, EndpointPeerRef>,
+    pub registry: UnifiedRegistry,
+    pub registry_shutdown: Arc<Mutex<RegistryShutdownHandle>>,
+    pub heartbeat_interval: IntervalHandle,
+    pub coordinator_timeout: CoordinatorTimeoutHandle,
+    #[cfg(feature = "rest-api-cors")]
+    pub cors: Cors,
+}
+
+/// Reloads the daemon's configuration and applies the settings that can change while it runs.
+pub struct ConfigReloader {
+    load_config: ConfigLoader,
+    log_handle: ReconfigurationHandle,
+    /// The configuration the daemon was started with
+    startup_config: Config,
+    /// The configuration whose reloadable settings are currently applied
+    current_config: Config,
+    targets: Option<ReloadTargets>,
+}
+
+impl ConfigReloader {
+    pub fn new(
+        load_config: ConfigLoader,
+        log_handle: ReconfigurationHandle,
+        startup_config: Config,
+    ) -> Self {
+        Self {
+            load_config,
+            log_handle,
+            current_config: startup_config.clone(),
+            startup_config,
+            targets: None,
+        }
+    }
+
+    /// Sets the running components that reloaded settings are applied to.
+    pub fn with_targets(mut self, targets: ReloadTargets) -> Self {
+        self.targets = Some(targets);
+        self
+    }
+
+    /// Loads the configuration again and applies the reloadable settings that have changed.
+    ///
+    /// Nothing is applied if the new configuration can't be loaded or is invalid, or if the
+    /// registries can't be replaced. The registries are replaced before any other setting is
+    /// applied, as that is the only step that can fail.
+    pub fn reload(&mut self) -> Result<ReloadReport, UserError> {
+        let config = (self.load_config)()?;
+        let log_level = parse_log_level(config.log_level())?;
+        let targets = self
+            .targets
+            .as_mut()
+            .ok_or_else(|| UserError::DaemonError {
+                context: "the daemon has not started".into(),
+                source: None,
+            })?;
+
+        let mut report = ReloadReport {
+            applied: vec![],
+            restart_required: self
+                .startup_config
+                .changed_settings(&config)
+                .into_iter()
+                .filter(|setting| !RELOADABLE_SETTINGS.contains(setting))
+                .map(String::from)
+                .collect(),
+            ignored: vec![],
+        };
+
+        let changed_settings = self.current_config.changed_settings(&config);
+
+        if changed_settings.iter().any(|setting| {
+            matches!(
+                *setting,
+                "registries" | "registry_auto_refresh" | "registry_forced_refresh"
+            )
+        }) {
+            update_registries(targets, self.startup_config.state_dir(), &config)?;
+        }
+
+        for setting in changed_settings {
+            match setting {
+                "peers" => update_peers(targets, config.peers()),
+                "registries" | "registry_auto_refresh" | "registry_forced_refresh" => (),
+                "heartbeat" => targets.heartbeat_interval.set_interval(config.heartbeat()),
+                "admin_timeout" => targets
+                    .coordinator_timeout
+                    .set_timeout(config.admin_timeout()),
+                #[cfg(feature = "rest-api-cors")]
+                "whitelist" => targets.cors.set_whitelist(
+                    config
+                        .whitelist()
+                        .map(ToOwned::to_owned)
+                        .unwrap_or_else(|| vec!["*".into()]),
+                ),
+                #[cfg(not(feature = "rest-api-cors"))]
+                "whitelist" => {
+                    report.ignored.push(setting.to_string());
+                    continue;
+                }
+                "log_level" => self.log_handle.set_new_spec(crate::log_spec(log_level)),
+                _ => continue,
+            }
+            report.applied.push(setting.to_string());
+        }
+
+        self.current_config = config;
+
+        if !report.applied.is_empty() {
+            info!("Applied reloaded settings: {}", report.applied.join(", "));
+        }
+        if !report.restart_required.is_empty() {
+            warn!(
+                "Changed settings require a restart to take effect: {}",
+                report.restart_required.join(", ")
+            );
+        }
+        if !report.ignored.is_empty() {
+            warn!(
+                "Changed settings are not supported by this build and were ignored: {}",
+                report.ignored.join(", ")
+            );
+        }
+
+        Ok(report)
+    }
+}
+
+/// Reloads the configuration with the shared reloader.
+pub fn reload(config_reloader: &Mutex<ConfigReloader>) -> Result<ReloadReport, UserError> {
+    config_reloader
+        .lock()
+        .map_err(|_| UserError::DaemonError {
+            context: "config reloader lock poisoned".into(),
+            source: None,
+        })?
+        .reload()
+}
+
+/// Starts a thread that reloads the configuration whenever the daemon receives `SIGHUP`.
+pub fn listen_for_sighup(config_reloader: Arc<Mutex<ConfigReloader>>) -> Result<(), io::Error> {
+    let signals = Signals::new(&[SIGHUP])?;
+
+    // this thread will just be dropped on shutdown
+    thread::Builder::new()
+        .name("ConfigReloader".into())
+        .spawn(move || {
+            for _ in signals.forever() {
+                info!("Received SIGHUP, reloading configuration");
+                if let Err(err) = reload(&config_reloader) {
+                    error!("Unable to reload configuration: {}", err);
+                }
+            }
+        })?;
+
+    Ok(())
+}
+
+pub fn parse_log_level(log_level: &str) -> Result<log::LevelFilter, UserError> {
+    log::LevelFilter::from_str(log_level)
+        .map_err(|_| UserError::InvalidArgument(format!("invalid log level: {}", log_level)))
+}
+
+/// Adds the peers that are newly configured and removes the peers that are no longer configured.
+///
+/// Removing a configured peer only drops this daemon's reference to it; the connection remains if
+/// the peer is still required by a circuit.
+fn update_peers(targets: &mut ReloadTargets, peers: &[String]) {
+    targets
+        .peer_refs
+        .retain(|endpoint, _| peers.contains(endpoint));
+
+    for endpoint in peers {
+        if targets.peer_refs.contains_key(endpoint) {
+            continue;
+        }
+        match targets
+            .peer_connector
+            .add_unidentified_peer(endpoint.to_string())
+        {
+            Ok(peer_ref) => {
+                targets.peer_refs.insert(endpoint.to_string(), peer_ref);
+            }
+            Err(err) => error!("Unable to add peer {}: {}", endpoint, err),
+        }
+    }
+}
+
+/// Replaces the read-only registries, then shuts down the remote registries that were replaced.
+///
+/// If the registries can't be replaced, the new registries are shut down and the existing ones
+/// remain in use.
+fn update_registries(
+    targets: &mut ReloadTargets,
+    state_dir: &str,
+    config: &Config,
+) -> Result<(), UserError> {
+    let mut current_registry_shutdown =
+        targets
+            .registry_shutdown
+            .lock()
+            .map_err(|_| UserError::DaemonError {
+                context: "registry shutdown handle lock poisoned".into(),
+                source: None,
+            })?;
+
+    let (read_only_registries, registry_shutdown) = create_read_only_registries(
+        state_dir,
+        config.registries(),
+        config.registry_auto_refresh(),
+        config.registry_forced_refresh(),
+    );
+
+    if let Err(err) = targets
+        .registry
+        .set_read_only_registries(read_only_registries)
+    {
+        registry_shutdown.shutdown();
+        return Err(UserError::daemon_err_with_source(
+            "unable to replace registries",
+            Box::new(err),
+        ));
+    }
+
+    std::mem::replace(&mut *current_registry_shutdown, registry_shutdown).shutdown();
+
+    Ok(())
+}
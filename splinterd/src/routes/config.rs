@@ -0,0 +1,50 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Mutex;
+
+use splinter::actix_web::{Error, HttpResponse};
+use splinter::futures::{Future, IntoFuture};
+use splinter::rest_api::ErrorResponse;
+
+use crate::error::UserError;
+use crate::reload::{self, ConfigReloader};
+
+/// Reloads the daemon's configuration, applying the settings that can change while it runs.
+///
+/// The response lists the settings that were applied and the changed settings that require a
+/// restart.
+pub fn reload_config(
+    config_reloader: &Mutex<ConfigReloader>,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    match reload::reload(config_reloader) {
+        Ok(report) => Box::new(HttpResponse::Ok().json(report).into_future()),
+        Err(err @ UserError::DaemonError { .. }) => {
+            error!("Unable to reload configuration: {}", err);
+            Box::new(
+                HttpResponse::InternalServerError()
+                    .json(ErrorResponse::internal_error())
+                    .into_future(),
+            )
+        }
+        Err(err) => {
+            error!("Unable to reload configuration: {}", err);
+            Box::new(
+                HttpResponse::BadRequest()
+                    .json(ErrorResponse::bad_request(&err.to_string()))
+                    .into_future(),
+            )
+        }
+    }
+}
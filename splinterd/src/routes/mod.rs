@@ -13,6 +13,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "config-reload")]
+mod config;
 mod status;
+#[cfg(feature = "tls-reload")]
+mod tls;
 
+#[cfg(feature = "config-reload")]
+pub use config::*;
 pub use status::*;
+#[cfg(feature = "tls-reload")]
+pub use tls::*;
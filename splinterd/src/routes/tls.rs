@@ -0,0 +1,63 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use splinter::actix_web::{Error, HttpResponse};
+use splinter::futures::{Future, IntoFuture};
+use splinter::rest_api::ErrorResponse;
+use splinter::transport::tls::TlsReloadHandle;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TlsReloadResponse {
+    reloaded_files: Vec<String>,
+}
+
+/// Reloads the certificates and keys of every TLS-enabled transport.
+///
+/// Existing connections are not affected; new handshakes use the reloaded material. If any
+/// transport fails to reload, the transports that failed continue to use their previous
+/// material.
+pub fn reload_tls(
+    reload_handles: &[TlsReloadHandle],
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    if reload_handles.is_empty() {
+        return Box::new(
+            HttpResponse::BadRequest()
+                .json(ErrorResponse::bad_request("TLS is not enabled"))
+                .into_future(),
+        );
+    }
+
+    let mut reloaded_files = vec![];
+    for handle in reload_handles {
+        if let Err(err) = handle.reload() {
+            error!("Unable to reload TLS files: {}", err);
+            return Box::new(
+                HttpResponse::BadRequest()
+                    .json(ErrorResponse::bad_request(&err.to_string()))
+                    .into_future(),
+            );
+        }
+        reloaded_files.extend(handle.files().iter().cloned());
+    }
+    reloaded_files.sort();
+    reloaded_files.dedup();
+
+    info!("Reloaded TLS files {}", reloaded_files.join(", "));
+
+    Box::new(
+        HttpResponse::Ok()
+            .json(TlsReloadResponse { reloaded_files })
+            .into_future(),
+    )
+}
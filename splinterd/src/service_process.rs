@@ -0,0 +1,183 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runs a single circuit service in a child process of a splinterd that supervises its services.
+
+use std::fs;
+
+use clap::{App, Arg, ArgMatches, SubCommand};
+use scabbard::service::ScabbardFactory;
+use splinter::orchestrator::{run_service_process, ServiceProcessRequest};
+use splinter::service::ServiceFactory;
+use splinter::signing::sawtooth::SawtoothSecp256k1SignatureVerifier;
+use splinter::transport::Transport;
+
+use crate::config::Config;
+use crate::error::UserError;
+use crate::transport::build_transport;
+
+/// Returns the hidden `run-service` subcommand.
+///
+/// Besides the service endpoint, the subcommand takes the transport settings of the supervising
+/// splinterd, under the same names as the daemon's arguments.
+pub fn run_service_subcommand<'a, 'b>() -> App<'a, 'b> {
+    let subcommand = SubCommand::with_name("run-service")
+        .about("Run a single circuit service for a supervising splinterd")
+        .setting(clap::AppSettings::Hidden)
+        .arg(
+            Arg::with_name("service_endpoint")
+                .long("service-endpoint")
+                .help("Endpoint of the splinterd service transport, tcp://ip:port")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(Arg::with_name("no_tls").long("no-tls"))
+        .arg(Arg::with_name("tls_insecure").long("tls-insecure"))
+        .arg(
+            Arg::with_name("tls_ca_file")
+                .long("tls-ca-file")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("tls_client_cert")
+                .long("tls-client-cert")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("tls_client_key")
+                .long("tls-client-key")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("tls_server_cert")
+                .long("tls-server-cert")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("tls_server_key")
+                .long("tls-server-key")
+                .takes_value(true),
+        );
+
+    #[cfg(feature = "tls-revocation")]
+    let subcommand = subcommand
+        .arg(
+            Arg::with_name("tls_crl_file")
+                .long("tls-crl-file")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("tls_denied_fingerprints")
+                .long("tls-deny-fingerprint")
+                .multiple(true)
+                .number_of_values(1)
+                .takes_value(true),
+        );
+
+    subcommand
+}
+
+/// Returns the arguments that run a service with the `run-service` subcommand, connected to the
+/// given service endpoint with the daemon's transport settings.
+pub fn run_service_args(config: &Config, service_endpoint: &str) -> Vec<String> {
+    let mut args = vec![
+        "run-service".to_string(),
+        "--service-endpoint".to_string(),
+        service_endpoint.to_string(),
+    ];
+
+    if config.no_tls() {
+        args.push("--no-tls".into());
+        return args;
+    }
+
+    // Files are passed as absolute paths, as a relative path would be resolved against the
+    // service process's default certificate directory
+    let files = vec![
+        ("--tls-ca-file", config.tls_ca_file()),
+        ("--tls-client-cert", config.tls_client_cert()),
+        ("--tls-client-key", config.tls_client_key()),
+        ("--tls-server-cert", config.tls_server_cert()),
+        ("--tls-server-key", config.tls_server_key()),
+    ];
+    for (arg, file) in files {
+        args.push(arg.into());
+        args.push(absolute_path(file));
+    }
+    if config.tls_insecure() {
+        args.push("--tls-insecure".into());
+    }
+
+    #[cfg(feature = "tls-revocation")]
+    {
+        if let Some(crl_file) = config.tls_crl_file() {
+            args.push("--tls-crl-file".into());
+            args.push(absolute_path(crl_file));
+        }
+        for fingerprint in config.tls_denied_fingerprints().unwrap_or(&[]) {
+            args.push("--tls-deny-fingerprint".into());
+            args.push(fingerprint.clone());
+        }
+    }
+
+    args
+}
+
+fn absolute_path(file: &str) -> String {
+    fs::canonicalize(file)
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| file.to_string())
+}
+
+/// Reads the service request from standard input and runs the service, connected to the
+/// supervising splinterd's service endpoint, until the connection is closed.
+///
+/// The connection is made with the same transports as the daemon's, built from the transport
+/// settings passed to the subcommand, so that TLS and Unix domain socket service endpoints are
+/// supported. If the request includes a registration token, it is presented when the service
+/// connects.
+pub fn run_service(matches: &ArgMatches<'static>) -> Result<(), UserError> {
+    let service_endpoint = matches
+        .value_of("service_endpoint")
+        .ok_or_else(|| UserError::MissingArgument("service endpoint".into()))?;
+
+    let config = crate::create_config(None, matches.clone())?;
+    let (mut transport, _) = build_transport(&config)?;
+
+    let request = ServiceProcessRequest::from_reader(std::io::stdin()).map_err(|err| {
+        UserError::daemon_err_with_source("unable to read service request", Box::new(err))
+    })?;
+    debug!(
+        "Starting service process for {:?}",
+        request.service_definition
+    );
+
+    let connection = transport.connect(service_endpoint).map_err(|err| {
+        UserError::daemon_err_with_source(
+            &format!("unable to connect to service endpoint {}", service_endpoint),
+            Box::new(err),
+        )
+    })?;
+
+    let service_factories: Vec<Box<dyn ServiceFactory>> = vec![Box::new(ScabbardFactory::new(
+        None,
+        None,
+        None,
+        None,
+        Box::new(SawtoothSecp256k1SignatureVerifier::new()),
+    ))];
+
+    run_service_process(&service_factories, request, connection)
+        .map_err(|err| UserError::daemon_err_with_source("service failed", Box::new(err)))
+}
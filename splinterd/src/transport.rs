@@ -18,7 +18,9 @@ use std::path::Path;
 use splinter::transport::multi::MultiTransport;
 use splinter::transport::socket::TcpTransport;
 use splinter::transport::socket::TlsTransport;
-use splinter::transport::tls::{TlsConfig, TlsConfigBuilder};
+#[cfg(feature = "uds-transport")]
+use splinter::transport::socket::UdsTransport;
+use splinter::transport::tls::{TlsConfig, TlsConfigBuilder, TlsReloadHandle};
 #[cfg(feature = "ws-transport")]
 use splinter::transport::ws::WsTransport;
 use splinter::transport::Transport;
@@ -28,13 +30,24 @@ use crate::error::GetTransportError;
 
 type SendableTransport = Box<dyn Transport + Send>;
 
-pub fn build_transport(config: &Config) -> Result<MultiTransport, GetTransportError> {
+/// Builds the transports used by the daemon.
+///
+/// Returns the combined transport, along with the handles for reloading the certificates and keys
+/// of each TLS-enabled transport.
+pub fn build_transport(
+    config: &Config,
+) -> Result<(MultiTransport, Vec<TlsReloadHandle>), GetTransportError> {
     let mut transports: Vec<SendableTransport> = vec![];
+    let mut reload_handles = vec![];
 
     // add tcp transport
     // this will be default for endpoints without a prefix
     transports.push(Box::new(TcpTransport::default()));
 
+    // add unix domain socket transport, for local connections
+    #[cfg(feature = "uds-transport")]
+    transports.push(Box::new(UdsTransport::default()));
+
     // add web socket transport
 
     // add tls transport
@@ -43,26 +56,24 @@ pub fn build_transport(config: &Config) -> Result<MultiTransport, GetTransportEr
         validate_tls_config(&tls_config)?;
         print_tls_config(&tls_config)?;
 
-        transports.push(Box::new(TlsTransport::new(
-            tls_config.ca_certs_file().to_owned(),
-            tls_config.client_private_key_file().to_string(),
-            tls_config.client_cert_file().to_string(),
-            tls_config.server_private_key_file().to_string(),
-            tls_config.server_cert_file().to_string(),
-        )?));
+        let tls_transport = TlsTransport::from_config(&tls_config)?;
+        reload_handles.push(tls_transport.reload_handle());
+        transports.push(Box::new(tls_transport));
 
         #[cfg(feature = "ws-transport")]
-        transports.push(Box::new(WsTransport::new(Some(&tls_config)).map_err(
-            |e| {
+        {
+            let ws_transport = WsTransport::new(Some(&tls_config)).map_err(|e| {
                 GetTransportError::CertError(format!("Failed to create WebSocket transport: {}", e))
-            },
-        )?));
+            })?;
+            reload_handles.extend(ws_transport.reload_handle());
+            transports.push(Box::new(ws_transport));
+        }
     } else {
         #[cfg(feature = "ws-transport")]
         transports.push(Box::new(WsTransport::default()));
     }
 
-    Ok(MultiTransport::new(transports))
+    Ok((MultiTransport::new(transports), reload_handles))
 }
 
 fn build_tls_config(config: &Config) -> Result<TlsConfig, GetTransportError> {
@@ -78,6 +89,16 @@ fn build_tls_config(config: &Config) -> Result<TlsConfig, GetTransportError> {
         builder = builder.with_ca_certs_file(config.tls_ca_file().to_string());
     }
 
+    #[cfg(feature = "tls-revocation")]
+    {
+        if let Some(crl_file) = config.tls_crl_file() {
+            builder = builder.with_crl_file(crl_file.to_string());
+        }
+        if let Some(fingerprints) = config.tls_denied_fingerprints() {
+            builder = builder.with_denied_fingerprints(fingerprints.to_vec());
+        }
+    }
+
     builder
         .build()
         .map_err(|e| GetTransportError::CertError(format!("TLS config error: {}", e)))
@@ -125,6 +146,15 @@ fn validate_tls_config(tls_config: &TlsConfig) -> Result<(), GetTransportError>
         }
     }
 
+    if let Some(crl_file) = tls_config.crl_file() {
+        if !Path::new(&crl_file).is_file() {
+            return Err(GetTransportError::CertError(format!(
+                "Must provide a valid certificate revocation list file: {}",
+                crl_file
+            )));
+        }
+    }
+
     Ok(())
 }
 
@@ -148,6 +178,15 @@ fn print_tls_config(tls_config: &TlsConfig) -> Result<(), GetTransportError> {
     if let Some(ca_path) = tls_config.ca_certs_file() {
         debug!("Using ca certs file: {:?}", ca_path);
     }
+    if let Some(crl_path) = tls_config.crl_file() {
+        debug!("Using certificate revocation list file: {:?}", crl_path);
+    }
+    if !tls_config.denied_fingerprints().is_empty() {
+        debug!(
+            "Denying {} certificate fingerprint(s)",
+            tls_config.denied_fingerprints().len()
+        );
+    }
 
     Ok(())
 }